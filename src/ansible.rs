@@ -0,0 +1,93 @@
+use std::path::Path;
+use serde_json::Value as JsonValue;
+
+use crate::error::AppError;
+use crate::presets::{PresetRule, SchemaSource};
+use crate::validation::reference_span;
+
+// Forward-slash path with a leading slash so `/playbooks/` also matches relative paths
+fn normalized(path: &Path) -> String {
+    format!("/{}", path.to_string_lossy().replace('\\', "/").trim_start_matches("./"))
+}
+
+fn has_yaml_extension(path: &Path) -> bool {
+    matches!(path.extension().and_then(|e| e.to_str()), Some("yml") | Some("yaml"))
+}
+
+fn is_role_argument_spec(path: &Path) -> bool {
+    let p = normalized(path);
+    p.ends_with("/meta/argument_specs.yml") || p.ends_with("/meta/argument_specs.yaml")
+}
+
+fn is_inventory(path: &Path) -> bool {
+    let p = normalized(path);
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+    has_yaml_extension(path)
+        && (p.contains("/inventory/") || p.contains("/inventories/") || stem == "inventory" || stem == "hosts")
+        // group_vars/host_vars live next to inventories but are plain variable files
+        && !p.contains("/group_vars/")
+        && !p.contains("/host_vars/")
+}
+
+fn is_playbook(path: &Path) -> bool {
+    let p = normalized(path);
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+    has_yaml_extension(path)
+        && !is_role_argument_spec(path)
+        && !is_inventory(path)
+        && (p.contains("/playbooks/") || stem == "site" || stem.contains("playbook"))
+}
+
+const PLAY_SOURCES: &[&str] = &["hosts", "import_playbook", "ansible.builtin.import_playbook"];
+
+/// Every play must target hosts or import another playbook; a play with neither is
+/// usually a task list saved in the wrong place.
+pub fn check_plays_have_hosts(path: &Path, content: &str, document: &JsonValue) -> Vec<AppError> {
+    let Some(plays) = document.as_array() else { return Vec::new() };
+    plays
+        .iter()
+        .enumerate()
+        .filter(|(_, play)| play.is_object() && !PLAY_SOURCES.iter().any(|k| play.get(k).is_some()))
+        .map(|(index, play)| {
+            let name = play.get("name").and_then(|n| n.as_str()).unwrap_or_default();
+            AppError::SemanticError {
+                path: path.to_path_buf(),
+                check: "play-hosts".to_string(),
+                message: format!("Play {} has neither `hosts` nor `import_playbook`", index + 1),
+                span: reference_span(content, "name", name),
+                source_code: content.to_string(),
+            }
+        })
+        .collect()
+}
+
+// Schemas maintained by ansible-lint, which is what most editors use for Ansible files
+pub const ANSIBLE_RULES: &[PresetRule] = &[
+    PresetRule {
+        name: "ansible-role-argument-spec",
+        matches: is_role_argument_spec,
+        schema: SchemaSource::Catalog(concat!(
+            "https://raw.githubusercontent.com/ansible/ansible-lint/main/src/ansiblelint/schemas",
+            "/role-arg-spec.json"
+        )),
+        check: None,
+    },
+    PresetRule {
+        name: "ansible-inventory",
+        matches: is_inventory,
+        schema: SchemaSource::Catalog(concat!(
+            "https://raw.githubusercontent.com/ansible/ansible-lint/main/src/ansiblelint/schemas",
+            "/inventory.json"
+        )),
+        check: None,
+    },
+    PresetRule {
+        name: "ansible-playbook",
+        matches: is_playbook,
+        schema: SchemaSource::Catalog(concat!(
+            "https://raw.githubusercontent.com/ansible/ansible-lint/main/src/ansiblelint/schemas",
+            "/playbook.json"
+        )),
+        check: Some(check_plays_have_hosts),
+    },
+];
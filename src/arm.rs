@@ -0,0 +1,106 @@
+use std::fs;
+use std::path::Path;
+use serde_json::{json, Map, Value as JsonValue};
+use jsonschema::Validator;
+
+use crate::error::AppError;
+
+fn template_error(template_path: &Path, message: String) -> AppError {
+    AppError::ArmTemplateError {
+        template: template_path.to_string_lossy().into_owned(),
+        message,
+    }
+}
+
+// JSON Schema for the `value` of one template parameter declaration
+fn parameter_value_schema(declaration: &JsonValue) -> Result<JsonValue, String> {
+    let declared_type = declaration
+        .get("type")
+        .and_then(|t| t.as_str())
+        .ok_or("parameter has no type")?;
+    // ARM type names are case-insensitive
+    let mut schema = match declared_type.to_ascii_lowercase().as_str() {
+        "string" | "securestring" => json!({ "type": "string" }),
+        "int" => json!({ "type": "integer" }),
+        "bool" => json!({ "type": "boolean" }),
+        "object" | "secureobject" => json!({ "type": "object" }),
+        "array" => json!({ "type": "array" }),
+        other => return Err(format!("unsupported parameter type {:?}", other)),
+    };
+    if let Some(allowed) = declaration.get("allowedValues") {
+        schema["enum"] = allowed.clone();
+    }
+    for (arm_key, schema_key) in [
+        ("minValue", "minimum"),
+        ("maxValue", "maximum"),
+        ("minLength", if schema["type"] == "array" { "minItems" } else { "minLength" }),
+        ("maxLength", if schema["type"] == "array" { "maxItems" } else { "maxLength" }),
+    ] {
+        if let Some(limit) = declaration.get(arm_key) {
+            schema[schema_key] = limit.clone();
+        }
+    }
+    Ok(schema)
+}
+
+/// Builds a schema for ARM deployment parameter files from the `parameters` section of
+/// an ARM template. Parameters without a `defaultValue` are required; each parameter
+/// must supply either a `value` of the declared type or a Key Vault `reference`.
+pub fn parameters_file_schema(template: &JsonValue) -> Result<JsonValue, String> {
+    let declarations = match template.get("parameters") {
+        Some(JsonValue::Object(p)) => p.clone(),
+        Some(_) => return Err("`parameters` is not an object".to_string()),
+        None => Map::new(),
+    };
+
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+    for (name, declaration) in &declarations {
+        let value_schema = parameter_value_schema(declaration).map_err(|m| format!("parameter {:?}: {}", name, m))?;
+        properties.insert(
+            name.clone(),
+            json!({
+                "type": "object",
+                "oneOf": [
+                    { "required": ["value"], "properties": { "value": value_schema } },
+                    { "required": ["reference"], "properties": { "reference": { "type": "object", "required": ["keyVault", "secretName"] } } }
+                ]
+            }),
+        );
+        if declaration.get("defaultValue").is_none() {
+            required.push(json!(name));
+        }
+    }
+
+    Ok(json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "type": "object",
+        "required": ["parameters"],
+        "properties": {
+            "$schema": { "type": "string" },
+            "contentVersion": { "type": "string" },
+            "parameters": {
+                "type": "object",
+                "properties": properties,
+                "required": required,
+                "additionalProperties": false
+            }
+        }
+    }))
+}
+
+/// Compiles a validator for parameter files of the ARM template at `template_path`.
+pub fn compile_parameters_validator(template_path: &Path) -> Result<Validator, AppError> {
+    let content = fs::read_to_string(template_path).map_err(|e| AppError::FileReadError {
+        path: template_path.to_path_buf(),
+        source: e,
+        span: None,
+    })?;
+    let template: JsonValue = serde_json::from_str(&content)
+        .map_err(|e| template_error(template_path, format!("Template is not valid JSON: {}", e)))?;
+    let schema = parameters_file_schema(&template).map_err(|m| template_error(template_path, m))?;
+    Validator::new(&schema).map_err(|e| AppError::SchemaCompileError {
+        source_display: template_path.to_string_lossy().into_owned(),
+        source: Box::new(e),
+    })
+}
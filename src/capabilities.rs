@@ -0,0 +1,177 @@
+use std::path::Path;
+use serde::Serialize;
+
+use crate::containerfile;
+use crate::hcl_json;
+use crate::presets;
+use crate::report_schema;
+
+/// An input format and the file names that select it.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InputFormat {
+    pub name: &'static str,
+    pub extensions: &'static [&'static str],
+    /// Flag that must be set for the format to be read, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub requires: Option<&'static str>,
+}
+
+/// A diagnostic code and how it is treated unless a rules pack remaps it.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticCode {
+    pub code: &'static str,
+    pub default_severity: &'static str,
+    pub description: &'static str,
+}
+
+/// Everything the installed binary supports.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Capabilities {
+    pub version: &'static str,
+    pub input_formats: &'static [InputFormat],
+    pub output_formats: &'static [&'static str],
+    /// `reportVersion` of `--format json` reports
+    pub report_version: u32,
+    pub schema_drafts: &'static [&'static str],
+    pub presets: Vec<&'static str>,
+    pub bundled_schemas: Vec<&'static str>,
+    pub semantic_checks: Vec<&'static str>,
+    pub diagnostics: &'static [DiagnosticCode],
+}
+
+const INPUT_FORMATS: &[InputFormat] = &[
+    InputFormat { name: "json", extensions: &["json"], requires: None },
+    InputFormat { name: "yaml", extensions: &["yaml", "yml"], requires: None },
+    InputFormat { name: "toml", extensions: &["toml"], requires: None },
+    InputFormat { name: "hcl", extensions: &["hcl"], requires: None },
+    InputFormat { name: "hcl-json", extensions: &["tf.json", "hcl.json"], requires: None },
+    InputFormat { name: "ini", extensions: &["ini", "cfg"], requires: None },
+    InputFormat { name: "properties", extensions: &["properties"], requires: None },
+    InputFormat { name: "kdl", extensions: &["kdl"], requires: None },
+    InputFormat { name: "hjson", extensions: &["hjson"], requires: None },
+    InputFormat { name: "ndjson", extensions: &["ndjson", "jsonl"], requires: None },
+    InputFormat { name: "cue", extensions: &["cue"], requires: None },
+    InputFormat { name: "pkl", extensions: &["pkl"], requires: None },
+    InputFormat { name: "nickel", extensions: &["ncl"], requires: None },
+    InputFormat { name: "containerfile", extensions: &["Dockerfile", "Containerfile"], requires: Some("--containerfile") },
+];
+
+const OUTPUT_FORMATS: &[&str] = &["human", "json", "ndjson", "sarif", "csv", "compact", "codeclimate", "azure", "template"];
+
+// `$schema` dialects the validator understands
+const SCHEMA_DRAFTS: &[&str] = &[
+    "http://json-schema.org/draft-04/schema#",
+    "http://json-schema.org/draft-06/schema#",
+    "http://json-schema.org/draft-07/schema#",
+    "https://json-schema.org/draft/2019-09/schema",
+    "https://json-schema.org/draft/2020-12/schema",
+];
+
+// Keep in sync with the `#[diagnostic(code(...))]` attributes on `AppError`
+const DIAGNOSTICS: &[DiagnosticCode] = &[
+    DiagnosticCode { code: "app::io::read_file", default_severity: "error", description: "An input or schema file could not be read" },
+    DiagnosticCode { code: "app::network::fetch_schema", default_severity: "error", description: "A remote schema could not be downloaded" },
+    DiagnosticCode { code: "app::schema::parse_error", default_severity: "error", description: "A schema is not valid JSON" },
+    DiagnosticCode { code: "app::schema::compile_error", default_severity: "error", description: "A schema is not a valid JSON Schema" },
+    DiagnosticCode { code: "app::schema::invalid_shape", default_severity: "error", description: "A schema document is an array, string, number or null instead of an object or boolean" },
+    DiagnosticCode { code: "app::schema::invalid_path", default_severity: "error", description: "No usable schema location was given" },
+    DiagnosticCode { code: "app::schema::assumed_dialect", default_severity: "warning", description: "A schema has no $schema, so its dialect was assumed" },
+    DiagnosticCode { code: "app::schema::unknown_version", default_severity: "error", description: "A config declares a version [schema_versions] does not map to a schema" },
+    DiagnosticCode { code: "app::schema::validation_error", default_severity: "error", description: "A config does not satisfy its schema" },
+    DiagnosticCode { code: "app::schema::coercible_type", default_severity: "error", description: "A value has the wrong type but quoting or unquoting it fixes it (\"8080\" for 8080)" },
+    DiagnosticCode { code: "app::schema::renamed_property", default_severity: "warning", description: "A config uses a property name marked x-renamed-from" },
+    DiagnosticCode { code: "app::schema::renamed_enum_value", default_severity: "warning", description: "A config uses an enum value renamed with --map-enum" },
+    DiagnosticCode { code: "app::schema::unused_definition", default_severity: "warning", description: "A definitions/$defs entry that no $ref reaches from the schema root (schema unused)" },
+    DiagnosticCode { code: "app::schema::invalid_example", default_severity: "error", description: "An examples entry in a schema does not match the subschema it belongs to (schema lint)" },
+    DiagnosticCode { code: "app::schema::unsafe_pattern", default_severity: "warning", description: "A pattern or patternProperties regex that can backtrack exponentially (schema lint)" },
+    DiagnosticCode { code: "app::lint::limit_exceeded", default_severity: "warning", description: "A file exceeds a --max-depth/keys/lines/array-len limit" },
+    DiagnosticCode { code: "app::json::parse_error", default_severity: "error", description: "A JSON input is malformed" },
+    DiagnosticCode { code: "app::yaml::parse_error", default_severity: "error", description: "A YAML input is malformed or uses a rejected tag" },
+    DiagnosticCode { code: "app::yaml::unrepresentable_value", default_severity: "error", description: "A YAML construct (complex key, set, binary, NaN) has no JSON equivalent" },
+    DiagnosticCode { code: "app::toml::parse_error", default_severity: "error", description: "A TOML input is malformed" },
+    DiagnosticCode { code: "app::hcl::parse_error", default_severity: "error", description: "An HCL input is malformed" },
+    DiagnosticCode { code: "app::ini::parse_error", default_severity: "error", description: "An INI input is malformed" },
+    DiagnosticCode { code: "app::properties::parse_error", default_severity: "error", description: "A Java .properties input is malformed" },
+    DiagnosticCode { code: "app::kdl::parse_error", default_severity: "error", description: "A KDL input is malformed" },
+    DiagnosticCode { code: "app::hjson::parse_error", default_severity: "error", description: "An Hjson input is malformed" },
+    DiagnosticCode { code: "app::evaluator::error", default_severity: "error", description: "cue, pkl or nickel failed to evaluate an input" },
+    DiagnosticCode { code: "app::grouped", default_severity: "error", description: "Related schema or semantic errors in one file, shown as one human-readable report" },
+    DiagnosticCode { code: "app::preset::semantic_error", default_severity: "error", description: "A semantic check (references, required keys) failed" },
+    DiagnosticCode { code: "app::preset::unknown", default_severity: "error", description: "--preset names no known preset" },
+    DiagnosticCode { code: "app::openapi::resolve_error", default_severity: "error", description: "An OpenAPI operation or payload could not be resolved" },
+    DiagnosticCode { code: "app::proto::descriptor_error", default_severity: "error", description: "A protobuf descriptor set or message could not be used" },
+    DiagnosticCode { code: "app::arm::template_error", default_severity: "error", description: "An ARM template could not be turned into a parameters schema" },
+    DiagnosticCode { code: "app::report::signing_error", default_severity: "error", description: "A report could not be written, signed or verified" },
+    DiagnosticCode { code: "app::run_record::error", default_severity: "error", description: "A run record could not be written or read" },
+    DiagnosticCode { code: "app::debug_bundle::error", default_severity: "error", description: "A debug bundle could not be written or replayed" },
+    DiagnosticCode { code: "app::rules_pack::error", default_severity: "error", description: "A rules pack could not be resolved or loaded" },
+    DiagnosticCode { code: "app::diff_base::error", default_severity: "error", description: "The --diff-base version of the inputs could not be read" },
+    DiagnosticCode { code: "app::history::error", default_severity: "error", description: "The inputs could not be read at the --at or --since revision" },
+    DiagnosticCode { code: "app::rules_pack::downgraded", default_severity: "warning", description: "A diagnostic a rules pack or --severity maps to warning" },
+    DiagnosticCode { code: "app::severity::info", default_severity: "note", description: "A diagnostic a rules pack or --severity maps to info" },
+    DiagnosticCode { code: "app::severity::escalated", default_severity: "error", description: "A warning a rules pack or --severity maps to error" },
+    DiagnosticCode { code: "app::source_map::mapped", default_severity: "error", description: "A diagnostic in a generated file, moved to its template by a .map sidecar" },
+    DiagnosticCode { code: "app::stamp::stale", default_severity: "warning", description: "A --stamp comment predates the file's last change or names another schema digest" },
+    DiagnosticCode { code: "app::expect::unexpectedly_valid", default_severity: "error", description: "A file expected to be invalid (--expect-invalid, expect_invalid globs) validates" },
+    DiagnosticCode { code: "app::require::missing_file", default_severity: "error", description: "A directory a project config [[require]] entry matches lacks its required file" },
+    DiagnosticCode { code: "app::consistency::mismatch", default_severity: "error", description: "A value a project config [[consistent]] entry pairs with another does not match it" },
+    DiagnosticCode { code: "app::merge::conflict", default_severity: "error", description: "merge --strategy error-on-conflict found layers setting one path to different values" },
+    DiagnosticCode { code: "app::style::line_endings", default_severity: "warning", description: "A file has lines that do not end with the configured (or its usual) line ending" },
+    DiagnosticCode { code: "app::style::final_newline", default_severity: "warning", description: "A file does not end with a line break (--final-newline)" },
+    DiagnosticCode { code: "app::suppression::expired", default_severity: "warning", description: "An ignore comment or suppressions entry is past its expires date" },
+];
+
+/// Name of the input format `path` is read as, if any.
+pub fn input_format(path: &Path) -> Option<&'static str> {
+    if containerfile::is_containerfile(path) {
+        return Some("containerfile");
+    }
+    if hcl_json::is_hcl_json(path) {
+        return Some("hcl-json");
+    }
+    let extension = path.extension()?.to_str()?;
+    INPUT_FORMATS.iter().find(|f| f.extensions.contains(&extension)).map(|f| f.name)
+}
+
+/// The documented entry for a diagnostic code, if it is one of ours.
+pub fn diagnostic_code(code: &str) -> Option<&'static DiagnosticCode> {
+    DIAGNOSTICS.iter().find(|d| d.code == code)
+}
+
+/// Describes what this build can read, write and report.
+pub fn capabilities() -> Capabilities {
+    Capabilities {
+        version: env!("CARGO_PKG_VERSION"),
+        input_formats: INPUT_FORMATS,
+        output_formats: OUTPUT_FORMATS,
+        report_version: report_schema::REPORT_VERSION,
+        schema_drafts: SCHEMA_DRAFTS,
+        presets: presets::preset_names().collect(),
+        bundled_schemas: presets::bundled_schema_names().collect(),
+        semantic_checks: presets::semantic_check_names().collect(),
+        diagnostics: DIAGNOSTICS,
+    }
+}
+
+/// Plain-text rendering for `capabilities --format human`.
+pub fn render_human(capabilities: &Capabilities) -> String {
+    let mut out = format!("toml-and-jerry {}\n\nInput formats:\n", capabilities.version);
+    for format in capabilities.input_formats {
+        let requires = format.requires.map(|flag| format!(" (with {})", flag)).unwrap_or_default();
+        out.push_str(&format!("  {:<14} {}{}\n", format.name, format.extensions.join(", "), requires));
+    }
+    out.push_str(&format!("\nOutput formats: {}\n", capabilities.output_formats.join(", ")));
+    out.push_str(&format!("JSON report version: {}\n", capabilities.report_version));
+    out.push_str(&format!("\nSchema drafts:\n  {}\n", capabilities.schema_drafts.join("\n  ")));
+    out.push_str(&format!("\nPresets: {}\n", capabilities.presets.join(", ")));
+    out.push_str(&format!("Bundled schemas: {}\n", capabilities.bundled_schemas.join(", ")));
+    out.push_str(&format!("Semantic checks: {}\n", capabilities.semantic_checks.join(", ")));
+    out.push_str("\nDiagnostics:\n");
+    for diagnostic in capabilities.diagnostics {
+        out.push_str(&format!("  {:<34} {:<8} {}\n", diagnostic.code, diagnostic.default_severity, diagnostic.description));
+    }
+    out
+}
@@ -0,0 +1,192 @@
+use std::collections::HashSet;
+use std::path::Path;
+use serde_json::Value as JsonValue;
+
+use crate::error::AppError;
+use crate::presets::{PresetRule, SchemaSource};
+use crate::validation::reference_span;
+
+// Forward-slash rendering of a path so matchers behave the same on Windows
+fn normalized(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+fn has_yaml_extension(path: &Path) -> bool {
+    matches!(path.extension().and_then(|e| e.to_str()), Some("yml") | Some("yaml"))
+}
+
+fn is_github_workflow(path: &Path) -> bool {
+    has_yaml_extension(path) && normalized(path).contains(".github/workflows/")
+}
+
+fn is_gitlab_ci(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|n| n == ".gitlab-ci.yml" || n.ends_with(".gitlab-ci.yml"))
+}
+
+fn is_azure_pipeline(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|n| n == "azure-pipelines.yml" || n == "azure-pipelines.yaml")
+}
+
+fn semantic_error(path: &Path, content: &str, check: &str, key: &str, missing: &str, message: String) -> AppError {
+    AppError::SemanticError {
+        path: path.to_path_buf(),
+        check: check.to_string(),
+        message,
+        span: reference_span(content, key, missing),
+        source_code: content.to_string(),
+    }
+}
+
+// `needs:` may be a single job id or a list of ids
+fn string_or_list(value: Option<&JsonValue>) -> Vec<String> {
+    match value {
+        Some(JsonValue::String(s)) => vec![s.clone()],
+        Some(JsonValue::Array(items)) => items.iter().filter_map(|i| i.as_str().map(str::to_string)).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// GitHub Actions: every `jobs.<id>.needs` entry must name another job in the workflow.
+pub fn check_github_needs(path: &Path, content: &str, document: &JsonValue) -> Vec<AppError> {
+    let Some(jobs) = document.get("jobs").and_then(|j| j.as_object()) else { return Vec::new() };
+    let mut errors = Vec::new();
+    for (job_id, job) in jobs {
+        for needed in string_or_list(job.get("needs")) {
+            if !jobs.contains_key(&needed) {
+                errors.push(semantic_error(
+                    path,
+                    content,
+                    "needs-reference",
+                    "needs",
+                    &needed,
+                    format!("Job `{}` needs `{}`, which is not defined in this workflow", job_id, needed),
+                ));
+            }
+        }
+    }
+    errors
+}
+
+// Top-level GitLab keys that configure the pipeline rather than define jobs
+const GITLAB_RESERVED_KEYS: &[&str] = &[
+    "default", "include", "stages", "variables", "workflow", "image", "services", "cache",
+    "before_script", "after_script", "spec",
+];
+
+/// GitLab CI: `needs` and `dependencies` entries must name jobs defined in the file.
+/// Files with `include:` may pull jobs from elsewhere, so only local-looking
+/// references are checked there.
+pub fn check_gitlab_needs(path: &Path, content: &str, document: &JsonValue) -> Vec<AppError> {
+    let Some(root) = document.as_object() else { return Vec::new() };
+    if root.contains_key("include") {
+        return Vec::new();
+    }
+    let jobs: HashSet<&str> = root
+        .keys()
+        .filter(|k| !GITLAB_RESERVED_KEYS.contains(&k.as_str()))
+        .map(String::as_str)
+        .collect();
+
+    let mut errors = Vec::new();
+    for (job_id, job) in root.iter().filter(|(k, _)| jobs.contains(k.as_str())) {
+        let mut references = Vec::new();
+        if let Some(JsonValue::Array(needs)) = job.get("needs") {
+            for need in needs {
+                match need {
+                    JsonValue::String(s) => references.push(("needs", s.clone())),
+                    // Cross-project/pipeline needs are not local references
+                    JsonValue::Object(obj) if !obj.contains_key("project") && !obj.contains_key("pipeline") => {
+                        if let Some(name) = obj.get("job").and_then(|j| j.as_str()) {
+                            references.push(("needs", name.to_string()));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        for dependency in string_or_list(job.get("dependencies")) {
+            references.push(("dependencies", dependency));
+        }
+        for (key, reference) in references {
+            if !jobs.contains(reference.as_str()) {
+                errors.push(semantic_error(
+                    path,
+                    content,
+                    "needs-reference",
+                    key,
+                    &reference,
+                    format!("Job `{}` {} `{}`, which is not defined in this file", job_id, key, reference),
+                ));
+            }
+        }
+    }
+    errors
+}
+
+// Checks `dependsOn` within one list of Azure stages or jobs, identified by `id_key`
+fn check_azure_depends_on(
+    path: &Path,
+    content: &str,
+    items: &[JsonValue],
+    id_key: &str,
+    errors: &mut Vec<AppError>,
+) {
+    let ids: HashSet<&str> = items.iter().filter_map(|i| i.get(id_key).and_then(|v| v.as_str())).collect();
+    for item in items {
+        let owner = item.get(id_key).and_then(|v| v.as_str()).unwrap_or("<unnamed>");
+        for dependency in string_or_list(item.get("dependsOn")) {
+            if !ids.contains(dependency.as_str()) {
+                errors.push(semantic_error(
+                    path,
+                    content,
+                    "needs-reference",
+                    "dependsOn",
+                    &dependency,
+                    format!("{} `{}` depends on `{}`, which is not defined alongside it", id_key, owner, dependency),
+                ));
+            }
+        }
+    }
+}
+
+/// Azure Pipelines: `dependsOn` must reference sibling stages/jobs.
+pub fn check_azure_depends_on_references(path: &Path, content: &str, document: &JsonValue) -> Vec<AppError> {
+    let mut errors = Vec::new();
+    if let Some(JsonValue::Array(stages)) = document.get("stages") {
+        check_azure_depends_on(path, content, stages, "stage", &mut errors);
+        for stage in stages {
+            if let Some(JsonValue::Array(jobs)) = stage.get("jobs") {
+                check_azure_depends_on(path, content, jobs, "job", &mut errors);
+            }
+        }
+    }
+    if let Some(JsonValue::Array(jobs)) = document.get("jobs") {
+        check_azure_depends_on(path, content, jobs, "job", &mut errors);
+    }
+    errors
+}
+
+pub const CI_RULES: &[PresetRule] = &[
+    PresetRule {
+        name: "github-workflow",
+        matches: is_github_workflow,
+        schema: SchemaSource::Catalog("https://json.schemastore.org/github-workflow.json"),
+        check: Some(check_github_needs),
+    },
+    PresetRule {
+        name: "gitlab-ci",
+        matches: is_gitlab_ci,
+        schema: SchemaSource::Catalog("https://gitlab.com/gitlab-org/gitlab/-/raw/master/app/assets/javascripts/editor/schema/ci.json"),
+        check: Some(check_gitlab_needs),
+    },
+    PresetRule {
+        name: "azure-pipelines",
+        matches: is_azure_pipeline,
+        schema: SchemaSource::Catalog("https://raw.githubusercontent.com/microsoft/azure-pipelines-vscode/main/service-schema.json"),
+        check: Some(check_azure_depends_on_references),
+    },
+];
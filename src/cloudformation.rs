@@ -0,0 +1,103 @@
+use std::collections::HashSet;
+use std::path::Path;
+use serde_json::Value as JsonValue;
+
+use crate::error::AppError;
+use crate::presets::{PresetRule, SchemaSource};
+use crate::validation::reference_span;
+
+fn is_template(path: &Path) -> bool {
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default().to_ascii_lowercase();
+    let normalized = format!("/{}", path.to_string_lossy().replace('\\', "/"));
+    let data_file = [".yaml", ".yml", ".json"].iter().any(|ext| name.ends_with(ext));
+    data_file
+        && (name.starts_with("template.")
+            || name.contains(".template.")
+            || name.contains(".cfn.")
+            || normalized.contains("/cloudformation/")
+            || normalized.contains("/cfn/"))
+}
+
+// Collects every `Ref` and `Fn::GetAtt` target in the template with the key it was found under
+fn collect_references(value: &JsonValue, out: &mut Vec<(&'static str, String)>) {
+    match value {
+        JsonValue::Object(map) => {
+            if let Some(JsonValue::String(target)) = map.get("Ref") {
+                out.push(("Ref", target.clone()));
+            }
+            match map.get("Fn::GetAtt") {
+                Some(JsonValue::Array(parts)) => {
+                    if let Some(JsonValue::String(resource)) = parts.first() {
+                        out.push(("GetAtt", resource.clone()));
+                    }
+                }
+                Some(JsonValue::String(dotted)) => {
+                    out.push(("GetAtt", dotted.split('.').next().unwrap_or_default().to_string()));
+                }
+                _ => {}
+            }
+            for child in map.values() {
+                collect_references(child, out);
+            }
+        }
+        JsonValue::Array(items) => items.iter().for_each(|i| collect_references(i, out)),
+        _ => {}
+    }
+}
+
+/// `Ref`, `Fn::GetAtt` and `DependsOn` must point at a declared parameter or resource
+/// (or an `AWS::` pseudo parameter for `Ref`).
+pub fn check_references(path: &Path, content: &str, document: &JsonValue) -> Vec<AppError> {
+    let keys = |section: &str| -> HashSet<String> {
+        document
+            .get(section)
+            .and_then(|s| s.as_object())
+            .map(|s| s.keys().cloned().collect())
+            .unwrap_or_default()
+    };
+    let resources = keys("Resources");
+    let parameters = keys("Parameters");
+    if resources.is_empty() {
+        return Vec::new();
+    }
+
+    let mut references = Vec::new();
+    collect_references(document, &mut references);
+    if let Some(JsonValue::Object(declared)) = document.get("Resources") {
+        for resource in declared.values() {
+            match resource.get("DependsOn") {
+                Some(JsonValue::String(d)) => references.push(("DependsOn", d.clone())),
+                Some(JsonValue::Array(ds)) => {
+                    references.extend(ds.iter().filter_map(|d| d.as_str()).map(|d| ("DependsOn", d.to_string())))
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let mut errors = Vec::new();
+    for (kind, target) in references {
+        let known = match kind {
+            "Ref" => resources.contains(&target) || parameters.contains(&target) || target.starts_with("AWS::"),
+            _ => resources.contains(&target),
+        };
+        if !known {
+            errors.push(AppError::SemanticError {
+                path: path.to_path_buf(),
+                check: "cfn-reference".to_string(),
+                message: format!("{} target `{}` is not declared in this template", kind, target),
+                span: reference_span(content, kind, &target),
+                source_code: content.to_string(),
+            });
+        }
+    }
+    errors
+}
+
+pub const CLOUDFORMATION_RULES: &[PresetRule] = &[PresetRule {
+    name: "cloudformation-template",
+    matches: is_template,
+    // Generated from the CloudFormation resource specification
+    schema: SchemaSource::Catalog("https://raw.githubusercontent.com/awslabs/goformation/master/schema/cloudformation.schema.json"),
+    check: Some(check_references),
+}];
@@ -0,0 +1,172 @@
+use std::ops::Range;
+use miette::SourceSpan;
+use serde_json::Value as JsonValue;
+
+use crate::config_merge;
+use crate::error::{AppError, CoercibleType, SchemaValidationError};
+use crate::ini::IniCoercion;
+
+/// A type mismatch that a lexical change resolves: `"8080"` where the schema wants a number,
+/// `8080` where it wants a string, `8080.0` where it wants an integer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Coercion {
+    /// The schema type the value satisfies once rewritten
+    pub expected: &'static str,
+    /// The value's type as written
+    pub found: &'static str,
+    /// The value as the expected type
+    pub value: JsonValue,
+}
+
+/// The JSON Schema type of `value`; numbers without a fraction are `integer`.
+pub fn type_name(value: &JsonValue) -> &'static str {
+    match value {
+        JsonValue::Null => "null",
+        JsonValue::Bool(_) => "boolean",
+        JsonValue::Number(n) if n.is_i64() || n.is_u64() => "integer",
+        JsonValue::Number(_) => "number",
+        JsonValue::String(_) => "string",
+        JsonValue::Array(_) => "array",
+        JsonValue::Object(_) => "object",
+    }
+}
+
+// A decimal number the way configs write one; `f64` parsing would also take `inf` and `NaN`
+fn is_numeric(text: &str) -> bool {
+    text.bytes().any(|b| b.is_ascii_digit()) && text.bytes().all(|b| b.is_ascii_digit() || b"+-.eE".contains(&b))
+}
+
+// `value` read as `expected`, if that takes no more than quoting or unquoting it
+fn coerce_to(value: &JsonValue, expected: &str) -> Option<(&'static str, JsonValue)> {
+    match (expected, value) {
+        ("integer", JsonValue::String(text)) => text.parse::<i64>().ok().map(|n| ("integer", n.into())),
+        ("integer", JsonValue::Number(n)) => {
+            let float = n.as_f64()?;
+            (float.fract() == 0.0 && float.abs() < i64::MAX as f64).then(|| ("integer", (float as i64).into()))
+        }
+        ("number", JsonValue::String(text)) if is_numeric(text) => {
+            let number = serde_json::Number::from_f64(text.parse().ok()?)?;
+            // `"8080"` reads back as `8080`, not `8080.0`
+            let value = text.parse::<i64>().map_or(JsonValue::Number(number), JsonValue::from);
+            Some(("number", value))
+        }
+        ("boolean", JsonValue::String(text)) => match text.as_str() {
+            "true" => Some(("boolean", true.into())),
+            "false" => Some(("boolean", false.into())),
+            _ => None,
+        },
+        ("string", JsonValue::Number(_) | JsonValue::Bool(_)) => Some(("string", value.to_string().into())),
+        _ => None,
+    }
+}
+
+/// How `actual` satisfies a `type` keyword that rejected it (`expected` is a type name or a
+/// list of them, as in `Violation::expected`), trying the types in order. `None` when no
+/// lexical change does.
+pub fn coerce(actual: &JsonValue, expected: &JsonValue) -> Option<Coercion> {
+    let types: Vec<&str> = match expected {
+        JsonValue::String(single) => vec![single.as_str()],
+        JsonValue::Array(many) => many.iter().filter_map(JsonValue::as_str).collect(),
+        _ => return None,
+    };
+    types.into_iter().find_map(|expected| {
+        let (expected, value) = coerce_to(actual, expected)?;
+        Some(Coercion { expected, found: type_name(actual), value })
+    })
+}
+
+// Byte range of the scalar written after the key ending at `key_end`, quotes included
+fn value_after_key(content: &str, key_end: usize) -> Option<Range<usize>> {
+    let rest = content[key_end..].strip_prefix(['"', '\'']).unwrap_or(&content[key_end..]);
+    let rest = rest.trim_start_matches([' ', '\t']).strip_prefix([':', '='])?;
+    let value = rest.trim_start_matches([' ', '\t']);
+    let start = content.len() - value.len();
+    let end = match value.chars().next()? {
+        quote @ ('"' | '\'') => start + 1 + value[1..].find(quote)? + 1,
+        _ => {
+            let cut = value.find(['\n', '\r', ',', '}', ']', '#']).unwrap_or(value.len());
+            start + value[..cut].trim_end().len()
+        }
+    };
+    (end > start).then_some(start..end)
+}
+
+// Where `actual` is written at `pointer`, and what to write there instead. `None` unless the
+// text found there is the value, so a fix never rewrites the wrong occurrence; values inside
+// arrays are not located, since keys alone do not tell their elements apart.
+fn rewrite(content: &str, pointer: &str, actual: &JsonValue, coercion: &Coercion) -> Option<(Range<usize>, String)> {
+    if pointer.split('/').skip(1).any(|segment| segment.parse::<usize>().is_ok()) {
+        return None;
+    }
+    let key = config_merge::pointer_span(content, pointer)?;
+    let range = value_after_key(content, key.end)?;
+    let written = &content[range.clone()];
+    let quoted = written.len() >= 2 && written.starts_with(['"', '\'']);
+    let replacement = match (actual, &coercion.value) {
+        // Unquoted as written (`1e3` stays `1e3`) unless that is not a JSON literal, like `+5`
+        (JsonValue::String(text), value) if quoted && &written[1..written.len() - 1] == text => match serde_json::from_str::<JsonValue>(text) {
+            Ok(_) => text.clone(),
+            Err(_) => value.to_string(),
+        },
+        (JsonValue::Number(n), JsonValue::Number(_)) if !quoted && written.parse::<f64>().ok() == n.as_f64() => coercion.value.to_string(),
+        (JsonValue::Number(n), JsonValue::String(_)) if !quoted && written.parse::<f64>().ok() == n.as_f64() => format!("\"{}\"", written),
+        (JsonValue::Bool(b), JsonValue::String(_)) if written == b.to_string() => format!("\"{}\"", written),
+        _ => return None,
+    };
+    Some((range, replacement))
+}
+
+/// `error` as a `CoercibleType` diagnostic when it is a `type` failure that quoting or
+/// unquoting the value resolves, with the rewrite attached where the value can be located;
+/// any other error is returned as it is.
+pub fn explain(error: AppError) -> AppError {
+    let AppError::SchemaValidationError(schema_error) = &error else {
+        return error;
+    };
+    let SchemaValidationError { path, source_code, error_span, instance_path, schema_path, violation, .. } = &**schema_error;
+    if violation.keyword != "type" {
+        return error;
+    }
+    let (Some(actual), Some(expected)) = (&violation.actual, &violation.expected) else { return error };
+    let Some(coercion) = coerce(actual, expected) else { return error };
+    let written = actual.to_string();
+    let (span, replacement) = match rewrite(source_code, instance_path, actual, &coercion) {
+        Some((range, replacement)) => (SourceSpan::new(range.start.into(), range.len()), Some(replacement)),
+        None => (*error_span, None),
+    };
+    let hint = match &replacement {
+        Some(replacement) => format!("write it as `{}`, or run with --fix to rewrite it", replacement),
+        None => format!("write it as `{}`", coercion.value),
+    };
+    AppError::CoercibleType(Box::new(CoercibleType {
+        path: path.clone(),
+        instance_path: instance_path.clone(),
+        schema_path: schema_path.clone(),
+        expected: coercion.expected.to_string(),
+        found: coercion.found.to_string(),
+        written,
+        replacement,
+        hint,
+        span,
+        source_code: source_code.clone(),
+        violation: violation.clone(),
+    }))
+}
+
+/// `explain` for INI and `.properties` files, whose values are strings unless `flag`
+/// (`--ini-coerce`, `--properties-coerce`) reads them as booleans or numbers. While it does
+/// not, no rewrite makes such a value a boolean or number, so the hint names the flag.
+pub fn explain_untyped(error: AppError, coercion: IniCoercion, flag: &str) -> AppError {
+    let mut error = explain(error);
+    if let AppError::CoercibleType(coercible) = &mut error {
+        let (kind, read) = match coercible.expected.as_str() {
+            "boolean" => ("booleans", coercion.booleans),
+            _ => ("numbers", coercion.numbers),
+        };
+        if coercible.found == "string" && !read {
+            coercible.replacement = None;
+            coercible.hint = format!("pass `{} {}` to read values like this one as {}", flag, kind, kind);
+        }
+    }
+    error
+}
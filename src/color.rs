@@ -0,0 +1,54 @@
+use miette::MietteHandlerOpts;
+
+/// Whether diagnostics are rendered with ANSI colors, from `--color`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// Color when stderr is a terminal that supports it, unless the environment says otherwise
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "auto" => Some(ColorChoice::Auto),
+            "always" => Some(ColorChoice::Always),
+            "never" => Some(ColorChoice::Never),
+            _ => None,
+        }
+    }
+
+    /// Settles `auto` with the `NO_COLOR` and `CLICOLOR_FORCE` conventions: a non-empty
+    /// `NO_COLOR` turns color off, a `CLICOLOR_FORCE` other than `0` turns it on, and
+    /// `NO_COLOR` wins when both are set. An explicit `always`/`never` beats both.
+    pub fn resolve(self, no_color: Option<&str>, clicolor_force: Option<&str>) -> Self {
+        match self {
+            ColorChoice::Auto if no_color.is_some_and(|v| !v.is_empty()) => ColorChoice::Never,
+            ColorChoice::Auto if clicolor_force.is_some_and(|v| !v.is_empty() && v != "0") => ColorChoice::Always,
+            choice => choice,
+        }
+    }
+
+    /// `resolve` with this process's environment.
+    pub fn with_env(self) -> Self {
+        self.resolve(std::env::var("NO_COLOR").ok().as_deref(), std::env::var("CLICOLOR_FORCE").ok().as_deref())
+    }
+}
+
+/// Handler options for the choice: `always` renders colored graphical reports even when
+/// stderr is not a terminal, `never` drops colors and terminal hyperlinks.
+pub fn handler_opts(choice: ColorChoice) -> MietteHandlerOpts {
+    let opts = MietteHandlerOpts::new();
+    match choice {
+        ColorChoice::Auto => opts,
+        ColorChoice::Always => opts.color(true).force_graphical(true),
+        ColorChoice::Never => opts.color(false).terminal_links(false),
+    }
+}
+
+/// Installs the report handler used for every `miette::Report` printed afterwards.
+pub fn install_report_handler(choice: ColorChoice) {
+    // Only fails if a handler is already installed, which keeps that one
+    let _ = miette::set_hook(Box::new(move |_| Box::new(handler_opts(choice).build())));
+}
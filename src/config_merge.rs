@@ -0,0 +1,197 @@
+use std::collections::HashMap;
+use std::fs;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use miette::SourceSpan;
+use serde_json::Value as JsonValue;
+
+use crate::error::{AppError, MergeConflict};
+use crate::patch::load_instance;
+use crate::renames;
+use crate::yaml;
+
+/// How `merge` resolves a path that several layers set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MergeStrategy {
+    /// The last layer's value wins, as in most config loaders
+    #[default]
+    LastWins,
+    /// A value that differs from an earlier layer's is a diagnostic
+    ErrorOnConflict,
+}
+
+impl MergeStrategy {
+    /// Parses `--strategy`: `last-wins` or `error-on-conflict`.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "last-wins" => Some(MergeStrategy::LastWins),
+            "error-on-conflict" => Some(MergeStrategy::ErrorOnConflict),
+            _ => None,
+        }
+    }
+}
+
+/// One config file of a stack, base first.
+#[derive(Debug, Clone)]
+pub struct Layer {
+    pub path: PathBuf,
+    pub content: String,
+    pub value: JsonValue,
+}
+
+/// Reads a layer in any format `check` can validate.
+pub fn load_layer(path: &Path) -> Result<Layer, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("Cannot read layer {}: {}", path.display(), e))?;
+    let value = load_instance(path, &yaml::TagPolicy::default())
+        .ok_or_else(|| format!("Cannot parse layer {} (json, yaml, toml or hcl, one document)", path.display()))?;
+    Ok(Layer { path: path.to_path_buf(), content, value })
+}
+
+fn escape_pointer_segment(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+// Span of the key the pointer ends in, found by walking its keys through the file in order.
+// Keys that are not written as `key:`/`key =` (TOML table headers, array indices) are skipped
+pub(crate) fn pointer_span(content: &str, pointer: &str) -> Option<Range<usize>> {
+    let mut from = 0;
+    let mut found = None;
+    for segment in pointer.split('/').skip(1) {
+        let key = segment.replace("~1", "/").replace("~0", "~");
+        found = renames::find_key_spans(content, &key).into_iter().find(|span| span.start >= from);
+        if let Some(span) = &found {
+            from = span.end;
+        }
+    }
+    found
+}
+
+// Span of the value written after the key the pointer ends in, when it is a scalar on the same
+// line (`"port": 80`, `port: 80`, `port = 80`); the key's span for objects, arrays and blocks
+pub(crate) fn value_span(content: &str, pointer: &str) -> Option<Range<usize>> {
+    let key = pointer_span(content, pointer)?;
+    let line_end = content[key.end..].find('\n').map_or(content.len(), |i| key.end + i);
+    let after_key = content[key.end..line_end].trim_start_matches(['"', '\'']).trim_start_matches([' ', '\t']);
+    let Some(value) = after_key.strip_prefix([':', '=']).map(|rest| rest.trim_start_matches([' ', '\t'])) else {
+        return Some(key);
+    };
+    let start = line_end - value.len();
+    let length = match value.chars().next() {
+        Some(quote @ ('"' | '\'')) => {
+            let mut escaped = false;
+            let close = value[1..].find(|c: char| {
+                let closes = c == quote && !escaped;
+                escaped = quote == '"' && c == '\\' && !escaped;
+                closes
+            });
+            close.map_or(value.trim_end().len(), |i| i + 2)
+        }
+        None | Some('{' | '[' | '|' | '>' | '&' | '*' | '!' | '#') => return Some(key),
+        Some(_) => {
+            let end = value.find([',', '}', ']']).unwrap_or(value.len());
+            let end = value[..end].find(" #").unwrap_or(end);
+            value[..end].trim_end().len()
+        }
+    };
+    Some(start..start + length)
+}
+
+fn conflict(layer: &Layer, pointer: &str, earlier: Vec<AppError>) -> AppError {
+    let span = pointer_span(&layer.content, pointer).unwrap_or(0..layer.content.len());
+    let value = layer.value.pointer(pointer).map(|v| v.to_string()).unwrap_or_default();
+    AppError::MergeConflict(Box::new(MergeConflict {
+        path: layer.path.clone(),
+        pointer: pointer.to_string(),
+        value,
+        span: SourceSpan::new(span.start.into(), span.len()),
+        source_code: layer.content.clone(),
+        earlier,
+    }))
+}
+
+// Remembers layer `index` as the origin of every value under `pointer`
+fn record(value: &JsonValue, pointer: &str, index: usize, origins: &mut HashMap<String, usize>) {
+    match value {
+        JsonValue::Object(object) if !object.is_empty() => {
+            for (key, child) in object {
+                record(child, &format!("{}/{}", pointer, escape_pointer_segment(key)), index, origins);
+            }
+        }
+        _ => {
+            origins.insert(pointer.to_string(), index);
+        }
+    }
+}
+
+// Merges `overlay` (from layer `index`) into `base` at `pointer`. `origins` remembers which
+// layer set each value, so a conflict can point at both files
+fn merge_into(
+    base: &mut JsonValue,
+    overlay: &JsonValue,
+    pointer: &str,
+    index: usize,
+    origins: &mut HashMap<String, usize>,
+    conflicts: &mut Vec<(String, usize, usize)>,
+) {
+    match (base, overlay) {
+        (JsonValue::Object(base), JsonValue::Object(overlay)) => {
+            for (key, value) in overlay {
+                let child = format!("{}/{}", pointer, escape_pointer_segment(key));
+                match base.get_mut(key) {
+                    Some(existing) => merge_into(existing, value, &child, index, origins, conflicts),
+                    None => {
+                        base.insert(key.clone(), value.clone());
+                        record(value, &child, index, origins);
+                    }
+                }
+            }
+        }
+        (base, overlay) => {
+            if base != overlay {
+                // The value replaced may be a whole object; its first origin stands for it
+                let below = |p: &String| p == pointer || p.strip_prefix(pointer).is_some_and(|rest| rest.starts_with('/'));
+                let replaced: Vec<String> = origins.keys().filter(|p| below(p)).cloned().collect();
+                if let Some(earlier) = replaced.iter().filter_map(|p| origins.get(p)).min() {
+                    conflicts.push((pointer.to_string(), *earlier, index));
+                }
+                for p in replaced {
+                    origins.remove(&p);
+                }
+                *base = overlay.clone();
+            }
+            record(overlay, pointer, index, origins);
+        }
+    }
+}
+
+/// Deep-merges the layers in order: objects merge key by key, and any other value replaces
+/// the one below it. With `ErrorOnConflict`, every path that a later layer sets to a
+/// different value is reported, labelled in the overriding file with the earlier file's
+/// value attached.
+pub fn merge_layers(layers: &[Layer], strategy: MergeStrategy) -> Result<JsonValue, Vec<AppError>> {
+    let mut merged = match layers.first() {
+        Some(first) if first.value.is_object() => JsonValue::Object(Default::default()),
+        _ => JsonValue::Null,
+    };
+    let mut origins = HashMap::new();
+    let mut conflicts = Vec::new();
+    for (index, layer) in layers.iter().enumerate() {
+        merge_into(&mut merged, &layer.value, "", index, &mut origins, &mut conflicts);
+    }
+    if strategy == MergeStrategy::LastWins || conflicts.is_empty() {
+        return Ok(merged);
+    }
+    Err(conflicts
+        .into_iter()
+        .map(|(pointer, earlier, later)| conflict(&layers[later], &pointer, vec![conflict(&layers[earlier], &pointer, Vec::new())]))
+        .collect())
+}
+
+/// The merged config in the format `out`'s extension names (yaml, yml or toml); JSON otherwise.
+pub fn render_merged(merged: &JsonValue, out: Option<&Path>) -> Result<String, String> {
+    match out.and_then(|p| p.extension()).and_then(|e| e.to_str()) {
+        Some("yaml" | "yml") => serde_yaml::to_string(merged).map_err(|e| e.to_string()),
+        Some("toml") => toml::to_string_pretty(merged).map_err(|e| e.to_string()),
+        _ => serde_json::to_string_pretty(merged).map(|json| json + "\n").map_err(|e| e.to_string()),
+    }
+}
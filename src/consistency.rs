@@ -0,0 +1,129 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use miette::SourceSpan;
+use serde::Deserialize;
+use serde_json::Value as JsonValue;
+
+use crate::config_merge::pointer_span;
+use crate::error::{AppError, InconsistentValue};
+use crate::patch::load_instance;
+use crate::yaml;
+
+/// A value in a file: the file and a JSON pointer into it, e.g.
+/// `{ file = "Cargo.toml", path = "/package/version" }`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ValueSelector {
+    /// Relative to the current directory; any format `check` reads
+    pub file: PathBuf,
+    pub path: String,
+}
+
+/// How the two values of a `[[consistent]]` entry must relate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Comparison {
+    #[default]
+    Equal,
+    NotEqual,
+    /// `right` contains `left`, e.g. an image reference that carries the version
+    Contains,
+}
+
+impl Comparison {
+    fn holds(self, left: &str, right: &str) -> bool {
+        match self {
+            Comparison::Equal => left == right,
+            Comparison::NotEqual => left != right,
+            Comparison::Contains => right.contains(left),
+        }
+    }
+
+    fn requirement(self) -> &'static str {
+        match self {
+            Comparison::Equal => "must equal",
+            Comparison::NotEqual => "must differ from",
+            Comparison::Contains => "must contain",
+        }
+    }
+}
+
+/// Values that must agree across two files, e.g.
+/// `[[consistent]] left = { file = "Cargo.toml", path = "/package/version" }  right = { file = "app.yaml", path = "/version" }`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConsistencyRule {
+    /// The reference value
+    pub left: ValueSelector,
+    /// The value that must match it; mismatches are labelled here
+    pub right: ValueSelector,
+    #[serde(default)]
+    pub compare: Comparison,
+}
+
+struct Side<'a> {
+    selector: &'a ValueSelector,
+    content: String,
+    value: Option<JsonValue>,
+}
+
+impl Side<'_> {
+    fn load(selector: &ValueSelector) -> Result<Side<'_>, String> {
+        let file = &selector.file;
+        let content = fs::read_to_string(file).map_err(|e| format!("Cannot read [[consistent]] file {}: {}", file.display(), e))?;
+        let document = load_instance(file, &yaml::TagPolicy::default())
+            .ok_or_else(|| format!("Cannot parse [[consistent]] file {} (json, yaml, toml or hcl, one document)", file.display()))?;
+        Ok(Side { selector, value: document.pointer(&selector.path).cloned(), content })
+    }
+
+    // Strings compare by their contents, so `"8080"` in one format matches `8080` in another
+    fn text(&self) -> Option<String> {
+        self.value.as_ref().map(|value| match value {
+            JsonValue::String(text) => text.clone(),
+            other => other.to_string(),
+        })
+    }
+
+    fn shown(&self) -> String {
+        self.value.as_ref().map_or_else(|| "unset".to_string(), JsonValue::to_string)
+    }
+
+    fn diagnostic(&self, requirement: String, other: Vec<AppError>) -> AppError {
+        let span = pointer_span(&self.content, &self.selector.path).unwrap_or(0..0);
+        AppError::InconsistentValue(Box::new(InconsistentValue {
+            path: self.selector.file.clone(),
+            pointer: self.selector.path.clone(),
+            value: self.shown(),
+            requirement,
+            span: SourceSpan::new(span.start.into(), span.len()),
+            source_code: self.content.clone(),
+            other,
+        }))
+    }
+}
+
+fn describe(path: &Path, pointer: &str) -> String {
+    format!("`{}` in {:?}", pointer, path)
+}
+
+/// Checks every `[[consistent]]` entry: a diagnostic labelled at `right`, with `left`
+/// attached, for each pair of values that do not compare as required or are not set.
+/// A file that cannot be read or parsed is an error of the configuration.
+pub fn check_consistency(rules: &[ConsistencyRule]) -> Result<Vec<AppError>, String> {
+    let mut errors = Vec::new();
+    for rule in rules {
+        let left = Side::load(&rule.left)?;
+        let right = Side::load(&rule.right)?;
+        let holds = match (left.text(), right.text()) {
+            (Some(l), Some(r)) => rule.compare.holds(&l, &r),
+            _ => false,
+        };
+        if holds {
+            continue;
+        }
+        let reference = left.diagnostic(format!("it is compared with {}", describe(&rule.right.file, &rule.right.path)), Vec::new());
+        let requirement = format!("it {} {} ({})", rule.compare.requirement(), describe(&rule.left.file, &rule.left.path), left.shown());
+        errors.push(right.diagnostic(requirement, vec![reference]));
+    }
+    Ok(errors)
+}
@@ -0,0 +1,216 @@
+use std::collections::BTreeMap;
+use std::ops::Range;
+use std::path::Path;
+use serde_json::{json, Map, Value as JsonValue};
+
+/// Metadata extracted from a Dockerfile/Containerfile, shaped for schema validation.
+///
+/// `spans` maps JSON pointers (`/labels/maintainer`) to the byte range of the
+/// instruction that produced them so diagnostics can point at the right line.
+/// Collections (`/labels`) map to their first instruction.
+pub struct ContainerfileMetadata {
+    pub value: JsonValue,
+    pub spans: BTreeMap<String, Range<usize>>,
+}
+
+/// Whether a path looks like a Dockerfile/Containerfile (`Dockerfile`, `Containerfile.dev`,
+/// `api.dockerfile`, ...).
+pub fn is_containerfile(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|n| n.to_str()) else { return false };
+    let lower = name.to_ascii_lowercase();
+    lower == "dockerfile"
+        || lower == "containerfile"
+        || lower.starts_with("dockerfile.")
+        || lower.starts_with("containerfile.")
+        || lower.ends_with(".dockerfile")
+        || lower.ends_with(".containerfile")
+}
+
+// Joins `\`-continued lines into logical instructions, keeping the byte range each covers.
+// Comment lines and blank lines are dropped.
+fn logical_instructions(content: &str) -> Vec<(String, Range<usize>)> {
+    let mut instructions = Vec::new();
+    let mut current = String::new();
+    let mut start = None;
+    let mut offset = 0;
+    for raw_line in content.split_inclusive('\n') {
+        let line_start = offset;
+        offset += raw_line.len();
+        let line = raw_line.trim_end_matches(['\n', '\r']);
+        let trimmed = line.trim_start();
+        if trimmed.starts_with('#') || (trimmed.is_empty() && start.is_none()) {
+            continue;
+        }
+        start.get_or_insert(line_start);
+        if let Some(continued) = line.trim_end().strip_suffix('\\') {
+            current.push_str(continued);
+            current.push(' ');
+            continue;
+        }
+        current.push_str(line);
+        let begin = start.take().unwrap_or(line_start);
+        instructions.push((std::mem::take(&mut current), begin..line_start + line.len()));
+    }
+    if let Some(begin) = start {
+        instructions.push((current, begin..content.len()));
+    }
+    instructions
+}
+
+// Splits shell-like words, honouring double/single quotes and backslash escapes
+fn split_words(input: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+    let mut in_word = false;
+    let mut chars = input.chars();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('"'), '\\') => {
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+            }
+            (Some(_), c) => current.push(c),
+            (None, '"') | (None, '\'') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, '\\') => {
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                    in_word = true;
+                }
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                current.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if in_word {
+        words.push(current);
+    }
+    words
+}
+
+// Parses `key=value key2="v 2"` pairs, or the legacy single `key value` form
+fn key_value_pairs(args: &str) -> Vec<(String, String)> {
+    let words = split_words(args);
+    if !words.is_empty() && !words[0].contains('=') {
+        let mut legacy = args.trim().splitn(2, char::is_whitespace);
+        let key = legacy.next().unwrap_or_default().to_string();
+        let value = split_words(legacy.next().unwrap_or_default()).join(" ");
+        return vec![(key, value)];
+    }
+    words
+        .into_iter()
+        .filter_map(|w| w.split_once('=').map(|(k, v)| (k.to_string(), v.to_string())))
+        .collect()
+}
+
+fn escape_pointer_segment(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+/// Parses the metadata instructions of a Containerfile into a JSON object with
+/// `from`, `labels`, `env`, `args`, `expose`, `user`, `workdir` and `healthcheck`.
+pub fn parse_containerfile(content: &str) -> ContainerfileMetadata {
+    let mut from = Vec::new();
+    let mut labels = Map::new();
+    let mut env = Map::new();
+    let mut args = Map::new();
+    let mut expose = Vec::new();
+    let mut root = Map::new();
+    let mut spans = BTreeMap::new();
+
+    for (instruction, range) in logical_instructions(content) {
+        let trimmed = instruction.trim();
+        let (keyword, rest) = trimmed.split_once(char::is_whitespace).unwrap_or((trimmed, ""));
+        let rest = rest.trim();
+        match keyword.to_ascii_uppercase().as_str() {
+            "FROM" => {
+                let words = split_words(rest);
+                let mut stage = Map::new();
+                if let Some(platform) = words.iter().find_map(|w| w.strip_prefix("--platform=")) {
+                    stage.insert("platform".to_string(), json!(platform));
+                }
+                let mut positional = words.iter().filter(|w| !w.starts_with("--"));
+                if let Some(image) = positional.next() {
+                    stage.insert("image".to_string(), json!(image));
+                }
+                if positional.next().is_some_and(|w| w.eq_ignore_ascii_case("as")) {
+                    if let Some(alias) = positional.next() {
+                        stage.insert("alias".to_string(), json!(alias));
+                    }
+                }
+                spans.insert(format!("/from/{}", from.len()), range.clone());
+                from.push(JsonValue::Object(stage));
+            }
+            "LABEL" => {
+                spans.entry("/labels".to_string()).or_insert_with(|| range.clone());
+                for (key, value) in key_value_pairs(rest) {
+                    spans.insert(format!("/labels/{}", escape_pointer_segment(&key)), range.clone());
+                    labels.insert(key, json!(value));
+                }
+            }
+            "ENV" => {
+                spans.entry("/env".to_string()).or_insert_with(|| range.clone());
+                for (key, value) in key_value_pairs(rest) {
+                    spans.insert(format!("/env/{}", escape_pointer_segment(&key)), range.clone());
+                    env.insert(key, json!(value));
+                }
+            }
+            "ARG" => {
+                spans.entry("/args".to_string()).or_insert_with(|| range.clone());
+                let (key, value) = match rest.split_once('=') {
+                    Some((k, v)) => (k.to_string(), json!(split_words(v).join(" "))),
+                    None => (rest.to_string(), JsonValue::Null),
+                };
+                spans.insert(format!("/args/{}", escape_pointer_segment(&key)), range.clone());
+                args.insert(key, value);
+            }
+            "EXPOSE" => {
+                spans.entry("/expose".to_string()).or_insert_with(|| range.clone());
+                for port in split_words(rest) {
+                    spans.insert(format!("/expose/{}", expose.len()), range.clone());
+                    expose.push(json!(port));
+                }
+            }
+            "USER" | "WORKDIR" | "HEALTHCHECK" => {
+                let key = keyword.to_ascii_lowercase();
+                spans.insert(format!("/{}", key), range.clone());
+                root.insert(key, json!(rest));
+            }
+            _ => {}
+        }
+    }
+
+    root.insert("from".to_string(), JsonValue::Array(from));
+    root.insert("labels".to_string(), JsonValue::Object(labels));
+    root.insert("env".to_string(), JsonValue::Object(env));
+    root.insert("args".to_string(), JsonValue::Object(args));
+    root.insert("expose".to_string(), JsonValue::Array(expose));
+    ContainerfileMetadata { value: JsonValue::Object(root), spans }
+}
+
+impl ContainerfileMetadata {
+    /// Byte range of the instruction responsible for `pointer`, walking up to the
+    /// closest recorded ancestor (`/labels/a/b` falls back to `/labels/a`).
+    pub fn span_for_pointer(&self, pointer: &str) -> Option<Range<usize>> {
+        let mut candidate = pointer;
+        loop {
+            if let Some(range) = self.spans.get(candidate) {
+                return Some(range.clone());
+            }
+            candidate = &candidate[..candidate.rfind('/')?];
+        }
+    }
+}
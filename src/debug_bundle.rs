@@ -0,0 +1,425 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use tempfile::TempDir;
+
+use crate::error::AppError;
+use crate::project_config::PROJECT_CONFIG_FILE;
+
+/// Manifest at the root of every bundle.
+pub const MANIFEST_FILE: &str = "bundle.json";
+/// The JSON report of the failing run.
+pub const REPORT_FILE: &str = "report.json";
+
+/// What stands in for a redacted value.
+pub const REDACTED: &str = "REDACTED";
+
+// Key names (lower-cased, without `_`, `-` and `.`) whose values --debug-bundle-redact hides
+const SECRET_KEY_WORDS: &[&str] = &["password", "passwd", "secret", "token", "apikey", "privatekey", "credential"];
+
+// Flags a replay must not repeat: they write next to the original run or into its inputs,
+// post to a webhook, run other programs or narrow its inputs. The ones that only go with a
+// dropped flag are dropped along with it
+const DROPPED_FLAGS: &[&str] = &[
+    "--debug-bundle",
+    "--config",
+    "--record-run",
+    "--shard",
+    "--result-log",
+    "--notify-webhook",
+    "--notify-on",
+    "--notify-top",
+    "--output",
+    "--output-file",
+    "--sign-report",
+    "--status-file",
+    "--ratchet",
+    "--emit-patch",
+    "--fix-out",
+    "--fix-summary",
+    "--fix-changelog",
+    "--diff-base",
+    "--at",
+    "--since",
+    "--cue-binary",
+    "--pkl-binary",
+    "--nickel-binary",
+];
+const DROPPED_SWITCHES: &[&str] = &[
+    "--debug-bundle-redact",
+    "--resume",
+    "--stamp",
+    "--fix",
+    "--allow-dirty",
+    "--diff",
+    "--dry-run",
+    "--check",
+];
+// Flags whose value is a schema the bundle carries a copy of
+const SCHEMA_FLAGS: &[&str] = &["--schema", "-s", "--schema-fallback"];
+// Flags whose value is another file the run read, which the bundle carries as it is
+const FILE_FLAGS: &[&str] = &["--suppressions", "--enum-map", "--openapi", "--proto-descriptor", "--arm-template"];
+// `--embedded POINTER=FORMAT:SCHEMA` names a schema file at the end of its value
+const EMBEDDED_FLAG: &str = "--embedded";
+
+/// One file packed into a bundle.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundledFile {
+    /// The path (or URL, for schemas) the failing run read it from
+    pub original: String,
+    /// Its path inside the bundle
+    pub bundled: String,
+    /// `input`, `schema`, `file` or `config`
+    pub kind: String,
+    /// Whether secret-looking values were replaced with `REDACTED`
+    pub redacted: bool,
+}
+
+/// `bundle.json`: how the failing run was invoked and what the bundle holds.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleManifest {
+    pub tool_version: String,
+    /// Arguments of the failing run after the program name
+    pub args: Vec<String>,
+    /// Arguments `--replay` runs from the unpacked bundle, pointing at the bundled copies
+    pub replay_args: Vec<String>,
+    pub cwd: PathBuf,
+    /// Recorded for reference; a replay does not restore it
+    pub env: BTreeMap<String, String>,
+    pub files: Vec<BundledFile>,
+}
+
+/// What a failing `check` run packs into its bundle.
+pub struct BundleSources<'a> {
+    /// Arguments after the program name
+    pub args: &'a [String],
+    /// The inputs as given on the command line
+    pub given_inputs: &'a [PathBuf],
+    /// Files with at least one diagnostic
+    pub offending: &'a [PathBuf],
+    /// Each schema as given on the command line, with the document it resolved to
+    pub schemas: &'a [(String, JsonValue)],
+    /// The project config the run loaded, if any
+    pub config: Option<&'a Path>,
+    /// The full JSON report
+    pub report: &'a str,
+    pub redact: bool,
+}
+
+fn bundle_error(path: &Path, message: impl Into<String>) -> AppError {
+    AppError::DebugBundleError {
+        path: path.to_string_lossy().into_owned(),
+        message: message.into(),
+    }
+}
+
+/// Path inside the bundle for `original` under `dir`: a relative path keeps its shape, an
+/// absolute one or a URL loses its root or scheme, and `..` becomes `_parent` so nothing
+/// unpacks outside the bundle.
+pub fn bundled_path(dir: &str, original: &str) -> String {
+    let without_scheme = original.split_once("://").map_or(original, |(_, rest)| rest);
+    let mut parts = vec![dir.to_string()];
+    for component in Path::new(without_scheme).components() {
+        match component {
+            Component::Normal(part) => parts.push(part.to_string_lossy().into_owned()),
+            Component::ParentDir => parts.push("_parent".to_string()),
+            Component::CurDir | Component::RootDir | Component::Prefix(_) => {}
+        }
+    }
+    parts.join("/")
+}
+
+fn is_secret_key(key: &str) -> bool {
+    let last = key.rsplit('.').next().unwrap_or(key);
+    let normalized: String = last.chars().filter(|c| !matches!(c, '_' | '-')).flat_map(char::to_lowercase).collect();
+    SECRET_KEY_WORDS.iter().any(|word| normalized.contains(word))
+}
+
+// `line` with the value of a secret-looking `key: value` / `key = value` replaced, if it has one
+fn redact_line(line: &str) -> Option<String> {
+    let body = line.trim_start();
+    let body = body.strip_prefix("- ").unwrap_or(body);
+    let (key, rest) = match body.strip_prefix('"') {
+        Some(quoted) => {
+            let end = quoted.find('"')?;
+            (&quoted[..end], &quoted[end + 1..])
+        }
+        None => {
+            let end = body.find([':', '='])?;
+            (body[..end].trim_end(), &body[end..])
+        }
+    };
+    let rest = rest.trim_start();
+    if !rest.starts_with([':', '=']) || !is_secret_key(key) {
+        return None;
+    }
+    let value = rest[1..].trim();
+    let comma = value.ends_with(',');
+    let value = value.trim_end_matches(',');
+    // Nested objects, lists and YAML block scalars are left alone
+    if value.is_empty() || value.starts_with(['{', '[', '|', '>']) {
+        return None;
+    }
+    let value_start = line.len() - rest[1..].trim_start().len();
+    Some(format!("{}\"{}\"{}", &line[..value_start], REDACTED, if comma { "," } else { "" }))
+}
+
+/// `content` with the values of secret-looking keys (passwords, tokens, API keys, ...) on
+/// `key: value` and `key = value` lines replaced by `"REDACTED"`, and how many were. Works line
+/// by line, so it covers YAML, JSON, TOML, INI and properties files alike.
+pub fn redact_secrets(content: &str) -> (String, usize) {
+    let mut redacted = 0;
+    let lines: Vec<String> = content
+        .split('\n')
+        .map(|line| match redact_line(line) {
+            Some(replaced) => {
+                redacted += 1;
+                replaced
+            }
+            None => line.to_string(),
+        })
+        .collect();
+    (lines.join("\n"), redacted)
+}
+
+/// The JSON report with `redact_secrets` applied to each diagnostic's snippet, and the
+/// offending value of a diagnostic about a secret-looking key replaced by `"REDACTED"`.
+/// A report that is not JSON is returned as it is.
+pub fn redact_report(report: &str) -> String {
+    let Ok(mut document) = serde_json::from_str::<JsonValue>(report) else {
+        return report.to_string();
+    };
+    let diagnostics = document.get_mut("errors").and_then(JsonValue::as_array_mut).into_iter().flatten();
+    for diagnostic in diagnostics {
+        if let Some(JsonValue::String(text)) = diagnostic.pointer_mut("/snippet/text") {
+            *text = redact_secrets(text).0;
+        }
+        let secret = diagnostic
+            .get("jsonPath")
+            .and_then(JsonValue::as_str)
+            .and_then(|path| path.rsplit('/').next())
+            .is_some_and(is_secret_key);
+        if let Some(actual) = diagnostic.pointer_mut("/violation/actual").filter(|_| secret) {
+            *actual = JsonValue::String(REDACTED.to_string());
+        }
+    }
+    let rendered = serde_json::to_string_pretty(&document).unwrap_or_else(|_| report.to_string());
+    if report.ends_with('\n') {
+        rendered + "\n"
+    } else {
+        rendered
+    }
+}
+
+// `--flag=value` split into the flag and its inline value
+fn split_flag(arg: &str) -> (&str, Option<&str>) {
+    match arg.split_once('=') {
+        Some((flag, value)) if flag.starts_with('-') => (flag, Some(value)),
+        _ => (arg, None),
+    }
+}
+
+// The schema file of an `--embedded` rule
+fn embedded_schema(rule: &str) -> Option<&str> {
+    let (_, rest) = rule.split_once('=')?;
+    rest.split_once(':').map(|(_, schema)| schema).filter(|schema| !schema.is_empty())
+}
+
+// `rule` with its schema file replaced by `schema`
+fn with_embedded_schema(rule: &str, schema: &str) -> String {
+    match embedded_schema(rule) {
+        Some(old) => format!("{}{}", &rule[..rule.len() - old.len()], schema),
+        None => rule.to_string(),
+    }
+}
+
+// The files named by FILE_FLAGS and `--embedded` rules in `args`
+fn flag_files(args: &[String]) -> Vec<String> {
+    let mut files = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let (flag, inline) = split_flag(arg);
+        if !FILE_FLAGS.contains(&flag) && flag != EMBEDDED_FLAG {
+            continue;
+        }
+        let Some(value) = inline.map(str::to_string).or_else(|| iter.next().cloned()) else {
+            continue;
+        };
+        let file = if flag == EMBEDDED_FLAG { embedded_schema(&value).map(str::to_string) } else { Some(value) };
+        files.extend(file.filter(|file| !files.contains(file)));
+    }
+    files
+}
+
+/// `args` without the flags a replay must not repeat: the ones that write next to the
+/// original run or into its inputs, post to a webhook, run other programs (such as
+/// `--cue-binary`) or narrow its inputs. `--replay` applies this again to the arguments it
+/// reads from a bundle, so a hand-made bundle cannot bring them back.
+pub fn without_dropped_flags(args: &[String]) -> Vec<String> {
+    let mut kept = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let (flag, inline) = split_flag(arg);
+        if DROPPED_SWITCHES.contains(&flag) {
+            continue;
+        }
+        if DROPPED_FLAGS.contains(&flag) {
+            if inline.is_none() {
+                iter.next();
+            }
+            continue;
+        }
+        kept.push(arg.clone());
+    }
+    kept
+}
+
+/// `args` rewritten to run from an unpacked bundle: schemas and the files of `FILE_FLAGS`
+/// and `--embedded` rules point at their bundled copies (`copies`, keyed by the path as
+/// given), the given inputs are replaced by `bundled_inputs` (when there are any), and the
+/// flags `without_dropped_flags` drops are dropped.
+pub fn replay_args(args: &[String], given_inputs: &[PathBuf], copies: &BTreeMap<String, String>, bundled_inputs: &[String]) -> Vec<String> {
+    let mut kept = Vec::new();
+    let args = without_dropped_flags(args);
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let (flag, inline) = split_flag(arg);
+        if SCHEMA_FLAGS.contains(&flag) || FILE_FLAGS.contains(&flag) || flag == EMBEDDED_FLAG {
+            let value = match inline {
+                Some(value) => Some(value.to_string()),
+                None => iter.next().cloned(),
+            };
+            kept.push(flag.to_string());
+            if let Some(value) = value {
+                kept.push(match embedded_schema(&value).filter(|_| flag == EMBEDDED_FLAG) {
+                    Some(schema) => copies.get(schema).map_or(value.clone(), |copy| with_embedded_schema(&value, copy)),
+                    None => copies.get(&value).cloned().unwrap_or(value),
+                });
+            }
+            continue;
+        }
+        if !bundled_inputs.is_empty() && !arg.starts_with('-') && given_inputs.contains(&PathBuf::from(arg)) {
+            continue;
+        }
+        kept.push(arg.clone());
+    }
+    kept.extend(bundled_inputs.iter().cloned());
+    kept
+}
+
+fn append(builder: &mut tar::Builder<impl Write>, name: &str, data: &[u8]) -> std::io::Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder.append_data(&mut header, name, data)
+}
+
+/// Packs `sources` into a gzipped tarball at `path` and returns its manifest.
+pub fn write_bundle(path: &Path, sources: &BundleSources) -> Result<BundleManifest, AppError> {
+    let mut files = Vec::new();
+    let mut entries: Vec<(String, Vec<u8>)> = Vec::new();
+    let mut bundled_inputs = Vec::new();
+    for input in sources.offending {
+        let content = fs::read(input).map_err(|e| bundle_error(path, format!("cannot read {}: {}", input.display(), e)))?;
+        let (content, redacted) = match (sources.redact, String::from_utf8(content)) {
+            (true, Ok(text)) => {
+                let (text, count) = redact_secrets(&text);
+                (text.into_bytes(), count > 0)
+            }
+            (_, Ok(text)) => (text.into_bytes(), false),
+            (_, Err(e)) => (e.into_bytes(), false),
+        };
+        let original = input.to_string_lossy().into_owned();
+        let bundled = bundled_path("inputs", &original);
+        bundled_inputs.push(bundled.clone());
+        files.push(BundledFile { original, bundled: bundled.clone(), kind: "input".to_string(), redacted });
+        entries.push((bundled, content));
+    }
+    let mut copies = BTreeMap::new();
+    for (original, document) in sources.schemas {
+        let bundled = bundled_path("schemas", original);
+        let rendered = serde_json::to_string_pretty(document).map_err(|e| bundle_error(path, e.to_string()))?;
+        copies.insert(original.clone(), bundled.clone());
+        files.push(BundledFile { original: original.clone(), bundled: bundled.clone(), kind: "schema".to_string(), redacted: false });
+        entries.push((bundled, (rendered + "\n").into_bytes()));
+    }
+    for original in flag_files(sources.args) {
+        let content = fs::read(&original).map_err(|e| bundle_error(path, format!("cannot read {}: {}", original, e)))?;
+        let bundled = bundled_path("files", &original);
+        copies.insert(original.clone(), bundled.clone());
+        files.push(BundledFile { original, bundled: bundled.clone(), kind: "file".to_string(), redacted: false });
+        entries.push((bundled, content));
+    }
+    if let Some(config) = sources.config {
+        let content = fs::read(config).map_err(|e| bundle_error(path, format!("cannot read {}: {}", config.display(), e)))?;
+        // A replay runs from the bundle root, where the config is picked up by its default name
+        files.push(BundledFile {
+            original: config.to_string_lossy().into_owned(),
+            bundled: PROJECT_CONFIG_FILE.to_string(),
+            kind: "config".to_string(),
+            redacted: false,
+        });
+        entries.push((PROJECT_CONFIG_FILE.to_string(), content));
+    }
+    let report = if sources.redact { redact_report(sources.report) } else { sources.report.to_string() };
+    entries.push((REPORT_FILE.to_string(), report.into_bytes()));
+
+    let mut env = crate::run_record::recorded_env();
+    if sources.redact {
+        env.values_mut().for_each(|value| *value = REDACTED.to_string());
+    }
+    let manifest = BundleManifest {
+        tool_version: env!("CARGO_PKG_VERSION").to_string(),
+        args: sources.args.to_vec(),
+        replay_args: replay_args(sources.args, sources.given_inputs, &copies, &bundled_inputs),
+        cwd: std::env::current_dir().unwrap_or_default(),
+        env,
+        files,
+    };
+    let rendered = serde_json::to_string_pretty(&manifest).map_err(|e| bundle_error(path, e.to_string()))?;
+    entries.insert(0, (MANIFEST_FILE.to_string(), (rendered + "\n").into_bytes()));
+
+    let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default()));
+    let archive = entries
+        .iter()
+        .try_for_each(|(name, data)| append(&mut builder, name, data))
+        .and_then(|()| builder.into_inner())
+        .and_then(|encoder| encoder.finish())
+        .map_err(|e| bundle_error(path, format!("could not pack bundle: {}", e)))?;
+    crate::paths::check_writable(path)
+        .and_then(|()| crate::paths::write_with_parents(path, &archive))
+        .map_err(|e| bundle_error(path, format!("could not write bundle: {}", e)))?;
+    Ok(manifest)
+}
+
+/// Whether `path` is a gzipped bundle rather than a `--record-run` JSON record.
+pub fn is_bundle(path: &Path) -> bool {
+    let mut magic = [0u8; 2];
+    fs::File::open(path).and_then(|mut file| std::io::Read::read_exact(&mut file, &mut magic)).is_ok() && magic == [0x1f, 0x8b]
+}
+
+/// Unpacks the bundle at `path` into a fresh temporary directory with a random name and
+/// reads its manifest. The directory is removed when the returned `TempDir` is dropped.
+pub fn unpack_bundle(path: &Path) -> Result<(TempDir, BundleManifest), AppError> {
+    let bytes = fs::read(path).map_err(|e| AppError::FileReadError {
+        path: path.to_path_buf(),
+        source: e,
+        span: None,
+    })?;
+    let root = crate::paths::check_writable(&std::env::temp_dir())
+        .and_then(|()| tempfile::Builder::new().prefix("toml-and-jerry-replay-").tempdir())
+        .map_err(|e| bundle_error(path, format!("could not unpack bundle: {}", e)))?;
+    // `unpack` refuses entries that would escape the destination
+    tar::Archive::new(flate2::read::GzDecoder::new(bytes.as_slice()))
+        .unpack(root.path())
+        .map_err(|e| bundle_error(path, format!("could not unpack bundle: {}", e)))?;
+    let manifest = fs::read_to_string(root.path().join(MANIFEST_FILE)).map_err(|e| bundle_error(path, format!("no {}: {}", MANIFEST_FILE, e)))?;
+    let manifest = serde_json::from_str(&manifest).map_err(|e| bundle_error(path, format!("not a debug bundle: {}", e)))?;
+    Ok((root, manifest))
+}
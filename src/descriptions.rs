@@ -0,0 +1,371 @@
+use std::ops::Range;
+use std::path::Path;
+use serde_json::Value as JsonValue;
+
+use crate::renames::MAX_REF_DEPTH;
+use crate::schema_comment::SCHEMA_COMMENT;
+use crate::stamp::STAMP_MARKER;
+
+/// How an example config lays out its keys; both use `#` comments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    Yaml,
+    Toml,
+}
+
+impl Layout {
+    /// The layout of `path` by extension; `None` for formats without comments.
+    pub fn for_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "yaml" | "yml" => Some(Layout::Yaml),
+            "toml" => Some(Layout::Toml),
+            _ => None,
+        }
+    }
+}
+
+/// A key written in an example config, with the comment that documents it: the block of
+/// comment lines directly above it, or else a comment at the end of its line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyComment {
+    /// Key path; `[]` stands for any array item, as in `schema keys`
+    pub path: Vec<String>,
+    /// Index of the key's line
+    pub line: usize,
+    pub indent: String,
+    /// Lines of the comment block above the key
+    pub block: Range<usize>,
+    /// Byte range of the trailing comment within the key's line, `#` included
+    pub trailing: Option<Range<usize>>,
+    pub comment: Option<String>,
+}
+
+/// Dotted form of a key path, e.g. `workers[].threads`.
+pub fn display_path(path: &[String]) -> String {
+    let mut out = String::new();
+    for segment in path {
+        if segment != "[]" && !out.is_empty() {
+            out.push('.');
+        }
+        out.push_str(segment);
+    }
+    out
+}
+
+fn unquote(key: &str) -> String {
+    let key = key.trim();
+    match key.as_bytes().first() {
+        Some(b'"' | b'\'') if key.len() >= 2 => key[1..key.len() - 1].to_string(),
+        _ => key.to_string(),
+    }
+}
+
+// Byte offset of a `#` that starts a comment (not inside quotes, after whitespace)
+fn comment_start(line: &str) -> Option<usize> {
+    let mut quote = None;
+    let mut previous = ' ';
+    for (i, c) in line.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '#' && previous.is_whitespace() => return Some(i),
+            None => {}
+        }
+        previous = c;
+    }
+    None
+}
+
+fn comment_text(comment: &str) -> &str {
+    comment.trim_start().trim_start_matches('#').trim()
+}
+
+// Comment lines that are tool markers rather than documentation
+fn is_marker(line: &str) -> bool {
+    line.contains(SCHEMA_COMMENT) || line.contains(STAMP_MARKER)
+}
+
+// `key` of a `key: value` line (YAML) or `key = value` line (TOML), and where its value starts
+fn split_key(text: &str, separator: char) -> Option<(&str, &str)> {
+    let end = match text.chars().next()? {
+        q @ ('"' | '\'') => text[1..].find(q)? + 2,
+        _ => text.find(separator)?,
+    };
+    let (key, rest) = text.split_at(end);
+    let rest = rest.trim_start().strip_prefix(separator)?;
+    if separator == ':' && !(rest.is_empty() || rest.starts_with([' ', '\t'])) {
+        return None;
+    }
+    (!key.trim().is_empty()).then_some((key, rest))
+}
+
+fn yaml_keys(lines: &[&str]) -> Vec<(usize, String, Vec<String>)> {
+    let mut keys = Vec::new();
+    let mut parents: Vec<(usize, Vec<String>)> = Vec::new();
+    let mut block_scalar: Option<usize> = None;
+    for (index, line) in lines.iter().enumerate() {
+        let text = line.trim_start();
+        let column = line.len() - text.len();
+        if text.is_empty() || text.starts_with('#') || *line == "---" {
+            continue;
+        }
+        if let Some(owner) = block_scalar {
+            if column > owner {
+                continue;
+            }
+            block_scalar = None;
+        }
+        if text == "-" {
+            // A sequence item whose content starts on the next line
+            parents.retain(|(c, _)| *c < column);
+            let mut path = parents.last().map(|(_, p)| p.clone()).unwrap_or_default();
+            path.push("[]".to_string());
+            parents.push((column, path));
+            continue;
+        }
+        let (column, text) = match text.strip_prefix("- ") {
+            Some(item) => {
+                parents.retain(|(c, _)| *c < column);
+                let mut path = parents.last().map(|(_, p)| p.clone()).unwrap_or_default();
+                path.push("[]".to_string());
+                parents.push((column, path));
+                let item_text = item.trim_start();
+                (line.len() - item_text.len(), item_text)
+            }
+            None => (column, text),
+        };
+        let Some((key, value)) = split_key(text, ':') else { continue };
+        parents.retain(|(c, _)| *c < column);
+        let mut path = parents.last().map(|(_, p)| p.clone()).unwrap_or_default();
+        path.push(unquote(key));
+        let value = value.trim_start();
+        if value.starts_with('|') || value.starts_with('>') {
+            block_scalar = Some(column);
+        }
+        parents.push((column, path.clone()));
+        keys.push((index, line[..line.len() - line.trim_start().len()].to_string(), path));
+    }
+    keys
+}
+
+fn toml_path(dotted: &str) -> Vec<String> {
+    let mut path = Vec::new();
+    let mut rest = dotted.trim();
+    while !rest.is_empty() {
+        let end = match rest.chars().next() {
+            Some(q @ ('"' | '\'')) => rest[1..].find(q).map_or(rest.len(), |i| i + 2),
+            _ => rest.find('.').unwrap_or(rest.len()),
+        };
+        path.push(unquote(&rest[..end]));
+        rest = rest[end..].trim_start().trim_start_matches('.').trim_start();
+    }
+    path
+}
+
+fn toml_keys(lines: &[&str]) -> Vec<(usize, String, Vec<String>)> {
+    let mut keys = Vec::new();
+    let mut table = Vec::new();
+    let mut in_multiline = false;
+    for (index, line) in lines.iter().enumerate() {
+        let text = line.trim_start();
+        let indent = line[..line.len() - text.len()].to_string();
+        if in_multiline {
+            in_multiline = !(text.contains("\"\"\"") || text.contains("'''"));
+            continue;
+        }
+        if text.is_empty() || text.starts_with('#') {
+            continue;
+        }
+        if let Some(header) = text.strip_prefix("[[") {
+            table = toml_path(header.split("]]").next().unwrap_or_default());
+            table.push("[]".to_string());
+            keys.push((index, indent, table.clone()));
+            continue;
+        }
+        if let Some(header) = text.strip_prefix('[') {
+            table = toml_path(header.split(']').next().unwrap_or_default());
+            keys.push((index, indent, table.clone()));
+            continue;
+        }
+        let Some((key, value)) = split_key(text, '=') else { continue };
+        let mut path = table.clone();
+        path.extend(toml_path(key));
+        let value = value.trim_start();
+        in_multiline = (value.starts_with("\"\"\"") && value.matches("\"\"\"").count() == 1)
+            || (value.starts_with("'''") && value.matches("'''").count() == 1);
+        keys.push((index, indent, path));
+    }
+    keys
+}
+
+/// Every key of an example config with the comment documenting it, in file order.
+pub fn key_comments(content: &str, layout: Layout) -> Vec<KeyComment> {
+    let lines: Vec<&str> = content.lines().collect();
+    let keys = match layout {
+        Layout::Yaml => yaml_keys(&lines),
+        Layout::Toml => toml_keys(&lines),
+    };
+    keys.into_iter()
+        .map(|(line, indent, path)| {
+            let mut start = line;
+            while start > 0 && lines[start - 1].trim_start().starts_with('#') && !is_marker(lines[start - 1]) {
+                start -= 1;
+            }
+            let above: Vec<&str> = lines[start..line].iter().map(|l| comment_text(l)).collect();
+            let trailing = comment_start(lines[line]).map(|at| at..lines[line].len());
+            let comment = if above.is_empty() {
+                trailing.clone().map(|range| comment_text(&lines[line][range]).to_string()).filter(|c| !c.is_empty())
+            } else {
+                Some(above.join(" ").trim().to_string()).filter(|c| !c.is_empty())
+            };
+            KeyComment { path, line, indent, block: start..line, trailing, comment }
+        })
+        .collect()
+}
+
+fn escape_pointer_segment(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+// Pointer of the schema a local `$ref` chain starting at `pointer` ends in
+fn resolve(root: &JsonValue, pointer: String) -> String {
+    let mut current = pointer;
+    for _ in 0..MAX_REF_DEPTH {
+        let target = root.pointer(&current).and_then(|s| s.get("$ref")).and_then(|r| r.as_str()).and_then(|r| r.strip_prefix('#'));
+        match target {
+            Some(target) if root.pointer(target).is_some() => current = target.to_string(),
+            _ => break,
+        }
+    }
+    current
+}
+
+// Pointer of the subschema for `segment` below the schema at `pointer`: its property (also
+// looked for in `allOf`/`anyOf`/`oneOf` branches), `additionalProperties`, or `items` for `[]`
+fn child(root: &JsonValue, pointer: &str, segment: &str, depth: usize) -> Option<String> {
+    if depth > MAX_REF_DEPTH {
+        return None;
+    }
+    let pointer = resolve(root, pointer.to_string());
+    let schema = root.pointer(&pointer)?;
+    if segment == "[]" {
+        return schema.get("items").filter(|i| i.is_object()).map(|_| format!("{}/items", pointer));
+    }
+    if schema.get("properties").and_then(|p| p.get(segment)).is_some() {
+        return Some(format!("{}/properties/{}", pointer, escape_pointer_segment(segment)));
+    }
+    for combinator in ["allOf", "anyOf", "oneOf"] {
+        let count = schema.get(combinator).and_then(JsonValue::as_array).map_or(0, Vec::len);
+        for i in 0..count {
+            if let Some(found) = child(root, &format!("{}/{}/{}", pointer, combinator, i), segment, depth + 1) {
+                return Some(found);
+            }
+        }
+    }
+    schema
+        .get("additionalProperties")
+        .filter(|a| a.is_object())
+        .map(|_| format!("{}/additionalProperties", pointer))
+}
+
+/// Pointer of the subschema that describes the value at `path`.
+pub fn schema_pointer(schema: &JsonValue, path: &[String]) -> Option<String> {
+    path.iter().try_fold(String::new(), |pointer, segment| child(schema, &pointer, segment, 0))
+}
+
+/// The `description` the schema gives the value at `path`, following `$ref`s.
+pub fn description_at(schema: &JsonValue, path: &[String]) -> Option<String> {
+    let pointer = schema_pointer(schema, path)?;
+    let direct = schema.pointer(&pointer)?.get("description");
+    direct
+        .or_else(|| schema.pointer(&resolve(schema, pointer))?.get("description"))
+        .and_then(JsonValue::as_str)
+        .map(str::to_string)
+}
+
+/// What `sync_descriptions` did, by dotted key path.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SyncOutcome {
+    pub updated: Vec<String>,
+    pub unchanged: Vec<String>,
+    /// Commented keys the schema has no subschema for
+    pub unknown: Vec<String>,
+}
+
+/// Sets the `description` of each commented key's subschema to its comment.
+pub fn sync_descriptions(schema: &mut JsonValue, comments: &[KeyComment]) -> SyncOutcome {
+    let mut outcome = SyncOutcome::default();
+    for key in comments {
+        let Some(comment) = &key.comment else { continue };
+        let path = display_path(&key.path);
+        let Some(pointer) = schema_pointer(schema, &key.path) else {
+            outcome.unknown.push(path);
+            continue;
+        };
+        match schema.pointer_mut(&pointer).and_then(JsonValue::as_object_mut) {
+            Some(subschema) if subschema.get("description").and_then(JsonValue::as_str) == Some(comment.as_str()) => {
+                outcome.unchanged.push(path)
+            }
+            Some(subschema) => {
+                subschema.insert("description".to_string(), JsonValue::String(comment.clone()));
+                outcome.updated.push(path);
+            }
+            None => outcome.unknown.push(path),
+        }
+    }
+    outcome
+}
+
+/// The example with each key documented by its schema `description`, written as the comment
+/// block above the key (or in place of its trailing comment, when that is how it is
+/// documented). Keys without a description keep their comments. With `commented` false every
+/// key comment is removed instead, leaving the bare example.
+pub fn render(content: &str, layout: Layout, schema: &JsonValue, commented: bool) -> String {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut replaced: Vec<Option<Vec<String>>> = vec![None; lines.len()];
+    let mut dropped = vec![false; lines.len()];
+    for key in key_comments(content, layout) {
+        let line = lines[key.line];
+        let description = if commented { description_at(schema, &key.path) } else { None };
+        match description {
+            Some(description) if key.block.is_empty() && key.trailing.is_some() => {
+                let at = key.trailing.clone().map_or(line.len(), |t| t.start);
+                let text = description.split_whitespace().collect::<Vec<_>>().join(" ");
+                replaced[key.line] = Some(vec![format!("{}# {}", &line[..at], text)]);
+            }
+            Some(description) => {
+                dropped[key.block.clone()].fill(true);
+                let mut block: Vec<String> = description.lines().map(|l| format!("{}# {}", key.indent, l).trim_end().to_string()).collect();
+                block.push(line.to_string());
+                replaced[key.line] = Some(block);
+            }
+            None if !commented => {
+                dropped[key.block.clone()].fill(true);
+                let bare = key.trailing.clone().map_or(line, |t| line[..t.start].trim_end());
+                replaced[key.line] = Some(vec![bare.to_string()]);
+            }
+            None => {}
+        }
+    }
+    let mut out = String::new();
+    for (index, line) in lines.iter().enumerate() {
+        if dropped[index] {
+            continue;
+        }
+        match &replaced[index] {
+            Some(block) => block.iter().for_each(|l| {
+                out.push_str(l);
+                out.push('\n');
+            }),
+            None => {
+                out.push_str(line);
+                out.push('\n');
+            }
+        }
+    }
+    if !content.ends_with('\n') {
+        out.pop();
+    }
+    out
+}
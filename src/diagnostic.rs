@@ -0,0 +1,137 @@
+use miette::{Diagnostic as _, SourceSpan};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{AppError, Severity};
+use crate::fix;
+use crate::validation::PrintableError;
+
+/// A diagnostic for library consumers: what `check` reports about a file, as plain data that
+/// serializes with serde. Unlike `AppError` its fields do not change with the variants, and
+/// reading it needs nothing from miette. Convert with `Diagnostic::from(&error)`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct Diagnostic {
+    /// The rule, e.g. `app::schema::validation_error`
+    pub code: String,
+    pub severity: Severity,
+    /// The file (or schema, or URL) the diagnostic is about
+    pub file: String,
+    /// Where in `file` it points; absent for diagnostics about a file as a whole that carry
+    /// no source
+    pub range: Option<Range>,
+    /// JSON pointer to the offending value, for diagnostics about a value
+    pub instance_path: Option<String>,
+    /// JSON pointer to the failing schema keyword, for schema validation errors
+    pub schema_path: Option<String>,
+    pub message: String,
+    /// Ways to resolve it, most specific first
+    pub suggestions: Vec<Suggestion>,
+}
+
+/// A range of a file, both as byte offsets and as 1-based line/column positions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct Range {
+    pub offset: usize,
+    pub length: usize,
+    pub start: Position,
+    /// Just past the last byte of the range
+    pub end: Position,
+}
+
+/// A 1-based line and column; columns count bytes, like the offsets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// A way to resolve a diagnostic: advice, or an automatic fix with the edits it makes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Suggestion {
+    pub message: String,
+    /// Replacements in the diagnostic's `file` that apply it; empty for advice
+    pub edits: Vec<Edit>,
+}
+
+/// Replace the `length` bytes at `offset` with `replacement`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Edit {
+    pub offset: usize,
+    pub length: usize,
+    pub replacement: String,
+}
+
+// The first label of `error`, placed in its source
+fn range(error: &AppError) -> Option<Range> {
+    let label = error.labels()?.next()?;
+    let source = error.source_code()?;
+    let start = source.read_span(label.inner(), 0, 0).ok()?;
+    let end = source.read_span(&SourceSpan::new((label.offset() + label.len()).into(), 0), 0, 0).ok()?;
+    Some(Range {
+        offset: label.offset(),
+        length: label.len(),
+        start: Position { line: start.line() + 1, column: start.column() + 1 },
+        end: Position { line: end.line() + 1, column: end.column() + 1 },
+    })
+}
+
+fn schema_path(error: &AppError) -> Option<String> {
+    match error.unwrapped() {
+        AppError::SchemaValidationError(error) => Some(error.schema_path.clone()),
+        AppError::CoercibleType(error) => Some(error.schema_path.clone()),
+        AppError::MappedToTemplate(mapped) => mapped.generated.first().and_then(schema_path),
+        _ => None,
+    }
+}
+
+fn suggestions(error: &AppError) -> Vec<Suggestion> {
+    let error = error.unwrapped();
+    let mut suggestions = Vec::new();
+    let edits: Vec<Edit> = fix::fixes_for(error)
+        .into_iter()
+        .map(|fix| Edit { offset: fix.range.start, length: fix.range.len(), replacement: fix.replacement })
+        .collect();
+    if !edits.is_empty() {
+        let message = fix::describe_change(error).unwrap_or_else(|| "apply the automatic fix".to_string());
+        suggestions.push(Suggestion { message, edits });
+    }
+    if let Some(help) = error.help() {
+        suggestions.push(Suggestion { message: help.to_string(), edits: Vec::new() });
+    }
+    suggestions
+}
+
+impl From<&AppError> for Diagnostic {
+    fn from(error: &AppError) -> Self {
+        // Reports and library consumers agree on the file, code, message and instance path
+        let printable = PrintableError::from(error);
+        Diagnostic {
+            code: printable.rule_id,
+            severity: error.level(),
+            file: printable.file_path,
+            // Severity mappings wrap the diagnostic without its labels
+            range: range(error.unwrapped()),
+            instance_path: printable.json_path,
+            schema_path: schema_path(error),
+            message: printable.message,
+            suggestions: suggestions(error),
+        }
+    }
+}
+
+impl From<AppError> for Diagnostic {
+    fn from(error: AppError) -> Self {
+        Diagnostic::from(&error)
+    }
+}
+
+/// `errors` as `Diagnostic`s, in the same order.
+pub fn diagnostics(errors: &[AppError]) -> Vec<Diagnostic> {
+    errors.iter().map(Diagnostic::from).collect()
+}
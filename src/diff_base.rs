@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+
+use crate::error::AppError;
+use crate::validation::PrintableError;
+
+/// Where the base version of the inputs comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffBase {
+    /// A checkout of the base version; inputs are looked up at the same relative paths
+    Dir(PathBuf),
+    /// A git revision, read with `git show <ref>:<path>`
+    GitRef(String),
+}
+
+impl DiffBase {
+    /// An existing directory is a base checkout; anything else is taken as a git revision.
+    pub fn parse(spec: &str) -> Self {
+        if Path::new(spec).is_dir() {
+            DiffBase::Dir(PathBuf::from(spec))
+        } else {
+            DiffBase::GitRef(spec.to_string())
+        }
+    }
+
+    /// Base version of `relative`, or `None` if the file did not exist there.
+    pub fn read(&self, relative: &Path) -> Result<Option<String>, String> {
+        match self {
+            DiffBase::Dir(dir) => match fs::read_to_string(dir.join(relative)) {
+                Ok(content) => Ok(Some(content)),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+                Err(e) => Err(format!("Cannot read {}: {}", dir.join(relative).display(), e)),
+            },
+            DiffBase::GitRef(rev) => {
+                let output = Command::new("git")
+                    .arg("show")
+                    .arg(format!("{}:./{}", rev, relative.to_string_lossy().replace('\\', "/")))
+                    .output()
+                    .map_err(|e| format!("Cannot run git: {}", e))?;
+                if output.status.success() {
+                    return Ok(Some(String::from_utf8_lossy(&output.stdout).into_owned()));
+                }
+                // Distinguish "file is new" from "revision does not exist"
+                let rev_exists = Command::new("git")
+                    .args(["rev-parse", "--verify", "--quiet"])
+                    .arg(format!("{}^{{commit}}", rev))
+                    .output()
+                    .is_ok_and(|o| o.status.success());
+                if rev_exists {
+                    Ok(None)
+                } else {
+                    Err(format!("{:?} is neither a directory nor a git revision", rev))
+                }
+            }
+        }
+    }
+
+    fn display(&self) -> String {
+        match self {
+            DiffBase::Dir(dir) => dir.display().to_string(),
+            DiffBase::GitRef(rev) => rev.clone(),
+        }
+    }
+}
+
+// Input path relative to the working directory, without `./` or `..` noise
+fn relative_to_cwd(path: &Path) -> PathBuf {
+    let cwd = std::env::current_dir().unwrap_or_default();
+    let path = path.strip_prefix(&cwd).unwrap_or(path);
+    path.components().filter(|c| !matches!(c, Component::CurDir)).collect()
+}
+
+/// Base versions of the changed inputs, copied into a scratch tree that mirrors the input
+/// layout so presets and rules packs see the same relative paths. Removed on drop.
+pub struct BaseMirror {
+    pub root: PathBuf,
+    /// Mirrored counterparts of the inputs, for validating the base version
+    pub inputs: Vec<PathBuf>,
+    /// Inputs whose content is the same in the base; their diagnostics are never new
+    pub unchanged: Vec<PathBuf>,
+}
+
+impl BaseMirror {
+    pub fn create(base: &DiffBase, inputs: &[PathBuf]) -> Result<Self, AppError> {
+        let error = |message: String| AppError::DiffBaseError { base: base.display(), message };
+        let root = std::env::temp_dir().join(format!("toml-and-jerry-diff-base-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let mut mirror = BaseMirror { root, inputs: Vec::new(), unchanged: Vec::new() };
+
+        for input in inputs {
+            let files: Vec<PathBuf> = if input.is_dir() {
+                walkdir::WalkDir::new(input)
+                    .sort_by_file_name()
+                    .into_iter()
+                    .flatten()
+                    .filter(|e| e.file_type().is_file())
+                    .map(|e| e.into_path())
+                    .collect()
+            } else {
+                vec![input.clone()]
+            };
+            let mut mirrored_any = false;
+            for file in files {
+                let relative = relative_to_cwd(&file);
+                let Some(base_content) = base.read(&relative).map_err(error)? else { continue };
+                if fs::read_to_string(&file).is_ok_and(|current| current == base_content) {
+                    mirror.unchanged.push(relative);
+                    continue;
+                }
+                let copy = mirror.root.join(&relative);
+                crate::paths::write_with_parents(&copy, base_content.as_bytes())
+                    .map_err(|e| error(format!("Cannot write {}: {}", copy.display(), e)))?;
+                mirrored_any = true;
+            }
+            if mirrored_any {
+                mirror.inputs.push(mirror.root.join(relative_to_cwd(input)));
+            }
+        }
+        Ok(mirror)
+    }
+}
+
+impl Drop for BaseMirror {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
+
+// Rule + file + instance path; what makes two diagnostics "the same" across versions
+fn diagnostic_key(error: &AppError, root: Option<&Path>) -> (String, PathBuf, String) {
+    let printable = PrintableError::from(error);
+    let file = PathBuf::from(&printable.file_path);
+    let file = root.and_then(|r| file.strip_prefix(r).ok()).map(Path::to_path_buf).unwrap_or(file);
+    (printable.rule_id, relative_to_cwd(&file), printable.json_path.unwrap_or_default())
+}
+
+/// Keeps the `current` diagnostics that the base version does not have: diagnostics in
+/// unchanged files are dropped, and each base diagnostic cancels one current diagnostic
+/// with the same rule, file and instance path.
+pub fn new_diagnostics(current: Vec<AppError>, base: &[AppError], mirror: &BaseMirror) -> Vec<AppError> {
+    let mut remaining: HashMap<_, usize> = HashMap::new();
+    for error in base {
+        *remaining.entry(diagnostic_key(error, Some(&mirror.root))).or_insert(0) += 1;
+    }
+    current
+        .into_iter()
+        .filter(|error| {
+            let key = diagnostic_key(error, None);
+            if mirror.unchanged.contains(&key.1) {
+                return false;
+            }
+            match remaining.get_mut(&key) {
+                Some(count) if *count > 0 => {
+                    *count -= 1;
+                    false
+                }
+                _ => true,
+            }
+        })
+        .collect()
+}
+
+/// Runs `validate` on the inputs and, with a base, on the base versions of the changed
+/// inputs, keeping only the diagnostics the change introduced.
+pub fn validate_against_base(
+    inputs: &[PathBuf],
+    base: Option<&DiffBase>,
+    validate: impl Fn(Vec<PathBuf>) -> Result<Vec<AppError>, AppError>,
+) -> Result<Vec<AppError>, AppError> {
+    let current = validate(inputs.to_vec())?;
+    let Some(base) = base else { return Ok(current) };
+    let mirror = BaseMirror::create(base, inputs)?;
+    let base_diagnostics = if mirror.inputs.is_empty() { Vec::new() } else { validate(mirror.inputs.clone())? };
+    Ok(new_diagnostics(current, &base_diagnostics, &mirror))
+}
@@ -0,0 +1,209 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+
+use crate::patch::load_instance;
+use crate::yaml;
+
+/// Knobs for `find_duplicates`.
+#[derive(Debug, Clone)]
+pub struct DuplicateOptions {
+    /// Minimum Jaccard similarity of two blocks' (path, value) leaves, 0.0..=1.0
+    pub similarity: f64,
+    /// Blocks with fewer leaf values are ignored
+    pub min_size: usize,
+    /// A group is only reported when it spans this many files
+    pub min_files: usize,
+}
+
+impl Default for DuplicateOptions {
+    fn default() -> Self {
+        DuplicateOptions { similarity: 0.8, min_size: 5, min_files: 2 }
+    }
+}
+
+/// Where one copy of a duplicated block lives.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Occurrence {
+    pub file: String,
+    /// JSON pointer of the block; empty for the whole file
+    pub pointer: String,
+}
+
+/// Blocks that are identical or near-identical across files.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateGroup {
+    /// Leaf values in the largest copy
+    pub size: usize,
+    /// Lowest similarity between a copy and the group's first copy (1.0 = identical)
+    pub similarity: f64,
+    pub occurrences: Vec<Occurrence>,
+}
+
+struct Block {
+    file: usize,
+    pointer: String,
+    leaves: BTreeSet<(String, String)>,
+}
+
+fn escape_pointer_segment(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+// (path relative to the block, scalar as JSON) for every leaf under `value`
+fn leaves(value: &JsonValue, relative: &str, out: &mut BTreeSet<(String, String)>) {
+    match value {
+        JsonValue::Object(map) => {
+            for (key, child) in map {
+                leaves(child, &format!("{}/{}", relative, escape_pointer_segment(key)), out);
+            }
+        }
+        JsonValue::Array(items) => {
+            for (index, child) in items.iter().enumerate() {
+                leaves(child, &format!("{}/{}", relative, index), out);
+            }
+        }
+        scalar => {
+            out.insert((relative.to_string(), scalar.to_string()));
+        }
+    }
+}
+
+fn collect_blocks(file: usize, value: &JsonValue, pointer: &str, min_size: usize, blocks: &mut Vec<Block>) {
+    let children: Vec<(String, &JsonValue)> = match value {
+        JsonValue::Object(map) => map.iter().map(|(k, v)| (escape_pointer_segment(k), v)).collect(),
+        JsonValue::Array(items) => items.iter().enumerate().map(|(i, v)| (i.to_string(), v)).collect(),
+        _ => return,
+    };
+    let mut block_leaves = BTreeSet::new();
+    leaves(value, "", &mut block_leaves);
+    if block_leaves.len() < min_size {
+        return;
+    }
+    blocks.push(Block { file, pointer: pointer.to_string(), leaves: block_leaves });
+    for (segment, child) in children {
+        collect_blocks(file, child, &format!("{}/{}", pointer, segment), min_size, blocks);
+    }
+}
+
+fn jaccard(a: &BTreeSet<(String, String)>, b: &BTreeSet<(String, String)>) -> f64 {
+    let shared = a.intersection(b).count();
+    shared as f64 / (a.len() + b.len() - shared) as f64
+}
+
+fn find_root(parents: &mut [usize], mut i: usize) -> usize {
+    while parents[i] != i {
+        parents[i] = parents[parents[i]];
+        i = parents[i];
+    }
+    i
+}
+
+/// Expands directories into the config files under them, in a stable order.
+pub fn input_files(inputs: &[PathBuf]) -> Vec<PathBuf> {
+    let is_config = |p: &Path| matches!(p.extension().and_then(|e| e.to_str()), Some("json" | "yaml" | "yml" | "toml" | "hcl"));
+    let mut files = Vec::new();
+    for input in inputs {
+        if input.is_dir() {
+            let walker = walkdir::WalkDir::new(input).sort_by_file_name();
+            files.extend(walker.into_iter().flatten().filter(|e| e.file_type().is_file() && is_config(e.path())).map(|e| e.into_path()));
+        } else {
+            files.push(input.clone());
+        }
+    }
+    files
+}
+
+/// Groups blocks (objects and arrays with at least `min_size` leaves) that are at least
+/// `similarity` alike across at least `min_files` files. Blocks nested inside a reported
+/// copy are not reported again. Largest groups first.
+pub fn find_duplicates(files: &[PathBuf], options: &DuplicateOptions, tags: &yaml::TagPolicy) -> Vec<DuplicateGroup> {
+    let mut blocks = Vec::new();
+    for (index, file) in files.iter().enumerate() {
+        if let Some(instance) = load_instance(file, tags) {
+            collect_blocks(index, &instance, "", options.min_size.max(1), &mut blocks);
+        }
+    }
+
+    let mut parents: Vec<usize> = (0..blocks.len()).collect();
+    for i in 0..blocks.len() {
+        for j in i + 1..blocks.len() {
+            if blocks[i].file == blocks[j].file {
+                continue;
+            }
+            let (small, large) = (blocks[i].leaves.len().min(blocks[j].leaves.len()), blocks[i].leaves.len().max(blocks[j].leaves.len()));
+            // Jaccard can never reach the threshold when the sizes are too far apart
+            if (small as f64) < options.similarity * large as f64 {
+                continue;
+            }
+            if jaccard(&blocks[i].leaves, &blocks[j].leaves) >= options.similarity {
+                let (a, b) = (find_root(&mut parents, i), find_root(&mut parents, j));
+                parents[a] = b;
+            }
+        }
+    }
+
+    let mut clusters: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for i in 0..blocks.len() {
+        let root = find_root(&mut parents, i);
+        clusters.entry(root).or_default().push(i);
+    }
+    let mut clusters: Vec<Vec<usize>> = clusters
+        .into_values()
+        .filter(|members| members.iter().map(|&m| blocks[m].file).collect::<BTreeSet<_>>().len() >= options.min_files.max(2))
+        .collect();
+    clusters.sort_by_key(|members| std::cmp::Reverse(members.iter().map(|&m| blocks[m].leaves.len()).max().unwrap_or(0)));
+
+    let mut covered: Vec<(usize, String)> = Vec::new();
+    let mut groups = Vec::new();
+    for members in clusters {
+        let inside_reported = |m: &usize| {
+            covered
+                .iter()
+                .any(|(file, pointer)| *file == blocks[*m].file && blocks[*m].pointer.starts_with(&format!("{}/", pointer)))
+        };
+        if members.iter().all(inside_reported) {
+            continue;
+        }
+        let first = &blocks[members[0]];
+        let similarity = members.iter().map(|&m| jaccard(&first.leaves, &blocks[m].leaves)).fold(1.0, f64::min);
+        let mut occurrences: Vec<Occurrence> = members
+            .iter()
+            .map(|&m| Occurrence { file: files[blocks[m].file].to_string_lossy().into_owned(), pointer: blocks[m].pointer.clone() })
+            .collect();
+        occurrences.sort();
+        covered.extend(members.iter().map(|&m| (blocks[m].file, blocks[m].pointer.clone())));
+        groups.push(DuplicateGroup {
+            size: members.iter().map(|&m| blocks[m].leaves.len()).max().unwrap_or(0),
+            similarity,
+            occurrences,
+        });
+    }
+    groups
+}
+
+/// Plain-text rendering for `duplicates --format human`.
+pub fn render_human(groups: &[DuplicateGroup]) -> String {
+    if groups.is_empty() {
+        return "No duplicated blocks found.\n".to_string();
+    }
+    let mut out = String::new();
+    for group in groups {
+        let files: BTreeSet<&str> = group.occurrences.iter().map(|o| o.file.as_str()).collect();
+        out.push_str(&format!(
+            "{} files share a block of {} values ({:.0}% similar):\n",
+            files.len(),
+            group.size,
+            group.similarity * 100.0
+        ));
+        for occurrence in &group.occurrences {
+            let pointer = if occurrence.pointer.is_empty() { "(whole file)" } else { occurrence.pointer.as_str() };
+            out.push_str(&format!("  {} {}\n", occurrence.file, pointer));
+        }
+        out.push_str("  consider extracting it into a shared include\n\n");
+    }
+    out
+}
@@ -0,0 +1,123 @@
+use std::path::PathBuf;
+use miette::SourceSpan;
+use serde_json::Value as JsonValue;
+
+/// Format of a document embedded in a string value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmbeddedFormat {
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl EmbeddedFormat {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "json" => Some(EmbeddedFormat::Json),
+            "yaml" | "yml" => Some(EmbeddedFormat::Yaml),
+            "toml" => Some(EmbeddedFormat::Toml),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            EmbeddedFormat::Json => "JSON",
+            EmbeddedFormat::Yaml => "YAML",
+            EmbeddedFormat::Toml => "TOML",
+        }
+    }
+}
+
+/// "The string at `pointer` is a `format` document that must satisfy `schema`".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmbeddedRule {
+    /// JSON pointer; a `*` segment matches any key or index, e.g. `/data/*`
+    pub pointer: String,
+    pub format: EmbeddedFormat,
+    pub schema: PathBuf,
+}
+
+impl EmbeddedRule {
+    /// Parses `POINTER=FORMAT:SCHEMA`, e.g. `/data/config.yaml=yaml:schemas/app.json`.
+    pub fn parse(spec: &str) -> Option<Self> {
+        let (pointer, rest) = spec.split_once('=')?;
+        let (format, schema) = rest.split_once(':')?;
+        if !(pointer.is_empty() || pointer.starts_with('/')) || schema.is_empty() {
+            return None;
+        }
+        Some(EmbeddedRule {
+            pointer: pointer.to_string(),
+            format: EmbeddedFormat::parse(format)?,
+            schema: PathBuf::from(schema),
+        })
+    }
+}
+
+fn unescape_segment(segment: &str) -> String {
+    segment.replace("~1", "/").replace("~0", "~")
+}
+
+fn escape_segment(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+fn collect_strings<'a>(value: &'a JsonValue, segments: &[&str], pointer: String, found: &mut Vec<(String, &'a str)>) {
+    let Some((first, rest)) = segments.split_first() else {
+        if let JsonValue::String(text) = value {
+            found.push((pointer, text));
+        }
+        return;
+    };
+    match value {
+        JsonValue::Object(map) => {
+            for (key, child) in map {
+                if *first == "*" || unescape_segment(first) == *key {
+                    collect_strings(child, rest, format!("{}/{}", pointer, escape_segment(key)), found);
+                }
+            }
+        }
+        JsonValue::Array(items) => {
+            for (index, child) in items.iter().enumerate() {
+                if *first == "*" || first.parse() == Ok(index) {
+                    collect_strings(child, rest, format!("{}/{}", pointer, index), found);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// String values matched by `pointer`, with their concrete pointers.
+pub fn matching_strings<'a>(instance: &'a JsonValue, pointer: &str) -> Vec<(String, &'a str)> {
+    let segments: Vec<&str> = match pointer.strip_prefix('/') {
+        Some(rest) => rest.split('/').collect(),
+        None => Vec::new(),
+    };
+    let mut found = Vec::new();
+    collect_strings(instance, &segments, String::new(), &mut found);
+    found
+}
+
+/// Maps a span inside an embedded document to the outer file. Works when the embedded
+/// lines appear verbatim in the outer file, as in YAML block scalars, TOML multi-line
+/// strings and unescaped one-line strings; `None` otherwise (e.g. JSON-escaped strings).
+pub fn map_span(outer: &str, inner: &str, inner_span: SourceSpan) -> Option<SourceSpan> {
+    let mut line_starts = Vec::new();
+    let mut search_from = outer.find(inner.lines().find(|l| !l.trim().is_empty())?)?;
+    for line in inner.lines() {
+        if line.is_empty() {
+            line_starts.push(search_from);
+            continue;
+        }
+        let found = search_from + outer[search_from..].find(line)?;
+        line_starts.push(found);
+        search_from = found + line.len();
+    }
+    let start = inner_span.offset().min(inner.len());
+    let line_index = inner[..start].matches('\n').count();
+    let column = start - inner[..start].rfind('\n').map_or(0, |i| i + 1);
+    let line_len = inner[start..].find('\n').unwrap_or(inner.len() - start);
+    let outer_start = line_starts.get(line_index)? + column;
+    Some(SourceSpan::new(outer_start.into(), inner_span.len().clamp(1, line_len.max(1))))
+}
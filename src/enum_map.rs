@@ -0,0 +1,127 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+use serde_json::Value as JsonValue;
+
+use crate::embedded::matching_strings;
+use crate::renames::find_key_spans;
+
+/// An enum value renamed in the schema: strings at `pointer` equal to `from` become `to`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnumMapping {
+    /// JSON pointer; a `*` segment matches any key or index, e.g. `/services/*/log_level`
+    pub pointer: String,
+    pub from: String,
+    pub to: String,
+}
+
+/// A config value that still uses an old enum value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnumHit {
+    /// JSON pointer of the value
+    pub pointer: String,
+    pub from: String,
+    pub to: String,
+}
+
+/// Parses `POINTER: OLD=NEW[, OLD=NEW...]`, e.g. `/log/level: warn=warning, err=error`.
+pub fn parse_mapping(spec: &str) -> Option<Vec<EnumMapping>> {
+    let (pointer, pairs) = spec.split_once(':')?;
+    let pointer = pointer.trim();
+    if !pointer.starts_with('/') {
+        return None;
+    }
+    pairs
+        .split(',')
+        .map(|pair| {
+            let (from, to) = pair.split_once('=')?;
+            let (from, to) = (from.trim(), to.trim());
+            (!from.is_empty() && !to.is_empty()).then(|| EnumMapping {
+                pointer: pointer.to_string(),
+                from: from.to_string(),
+                to: to.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Loads a rules file of `[enum."POINTER"]` tables mapping old values to new ones:
+///
+/// ```toml
+/// [enum."/log/level"]
+/// warn = "warning"
+/// ```
+pub fn load_mappings(path: &Path) -> Result<Vec<EnumMapping>, String> {
+    #[derive(serde::Deserialize)]
+    struct RulesFile {
+        #[serde(rename = "enum", default)]
+        enums: BTreeMap<String, BTreeMap<String, String>>,
+    }
+    let content = fs::read_to_string(path).map_err(|e| format!("Cannot read enum map {}: {}", path.display(), e))?;
+    let file: RulesFile = toml::from_str(&content).map_err(|e| format!("Invalid enum map {}: {}", path.display(), e))?;
+    Ok(file
+        .enums
+        .into_iter()
+        .flat_map(|(pointer, values)| {
+            values.into_iter().map(move |(from, to)| EnumMapping { pointer: pointer.clone(), from, to })
+        })
+        .collect())
+}
+
+/// String values in `instance` that a mapping renames.
+pub fn find_old_enum_values(instance: &JsonValue, mappings: &[EnumMapping]) -> Vec<EnumHit> {
+    let mut hits = Vec::new();
+    for mapping in mappings {
+        for (pointer, value) in matching_strings(instance, &mapping.pointer) {
+            if value == mapping.from {
+                hits.push(EnumHit { pointer, from: mapping.from.clone(), to: mapping.to.clone() });
+            }
+        }
+    }
+    hits
+}
+
+// The value written right after the key at `key_span` (`key: warn`, `"key": "warn"`,
+// `key = 'warn'`), if it is exactly `value`; the range excludes quotes
+fn value_after_key(content: &str, key_span: &Range<usize>, value: &str) -> Option<Range<usize>> {
+    let rest = content[key_span.end..].trim_start_matches(['"', '\'']);
+    let rest = rest.trim_start_matches([' ', '\t']).strip_prefix([':', '='])?;
+    let trimmed = rest.trim_start_matches([' ', '\t']);
+    let quote = trimmed.chars().next().filter(|c| *c == '"' || *c == '\'');
+    let start = content.len() - trimmed.len() + quote.map_or(0, |q| q.len_utf8());
+    let end = start + value.len();
+    if !content[start..].starts_with(value) {
+        return None;
+    }
+    let after = content[end..].chars().next();
+    let closed = match quote {
+        Some(q) => after == Some(q),
+        None => !after.is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '-' || c == '.'),
+    };
+    closed.then_some(start..end)
+}
+
+/// Where each hit's old value is written, in the order of `hits`. A value is only located
+/// when the file holds exactly as many `key: old` pairs as there are hits for that key and
+/// value; otherwise (e.g. an unrelated key with the same name and value, or a bare list
+/// item) it is `None`, so no rewrite can touch the wrong text.
+pub fn locate_hits(content: &str, hits: &[EnumHit]) -> Vec<Option<Range<usize>>> {
+    let mut occurrences: BTreeMap<(String, String), std::vec::IntoIter<Range<usize>>> = BTreeMap::new();
+    hits.iter()
+        .map(|hit| {
+            let key = hit.pointer.rsplit('/').next().unwrap_or_default().replace("~1", "/").replace("~0", "~");
+            let expected = hits.iter().filter(|h| h.from == hit.from && h.pointer.rsplit('/').next() == hit.pointer.rsplit('/').next()).count();
+            occurrences
+                .entry((key.clone(), hit.from.clone()))
+                .or_insert_with(|| {
+                    let found: Vec<Range<usize>> = find_key_spans(content, &key)
+                        .iter()
+                        .filter_map(|span| value_after_key(content, span, &hit.from))
+                        .collect();
+                    (if found.len() == expected { found } else { Vec::new() }).into_iter()
+                })
+                .next()
+        })
+        .collect()
+}
@@ -0,0 +1,104 @@
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+
+use crate::schema_keys::SchemaKeys;
+
+/// The environment variable that overrides one config key.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvVar {
+    /// e.g. `APP_DATABASE_PORT`
+    pub name: String,
+    /// The config key as `schema keys` writes it, e.g. `database.port`
+    pub path: String,
+    pub types: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<JsonValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub required: bool,
+}
+
+/// Environment variables for every config key a schema allows.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvDoc {
+    pub schema: String,
+    pub prefix: String,
+    pub variables: Vec<EnvVar>,
+}
+
+// One key segment in SCREAMING_SNAKE_CASE: `maxConnections` and `max-connections` are both
+// `MAX_CONNECTIONS`
+fn screaming(segment: &str) -> String {
+    let mut out = String::new();
+    let mut previous: Option<char> = None;
+    for c in segment.chars() {
+        if c.is_ascii_alphanumeric() {
+            if c.is_ascii_uppercase() && previous.is_some_and(|p| p.is_ascii_lowercase() || p.is_ascii_digit()) {
+                out.push('_');
+            }
+            out.push(c.to_ascii_uppercase());
+        } else if !out.is_empty() && !out.ends_with('_') {
+            out.push('_');
+        }
+        previous = Some(c);
+    }
+    out.trim_end_matches('_').to_string()
+}
+
+/// The variable for config key `path` (dotted, as `schema keys` writes it): `prefix`, then
+/// each key segment upper-cased, joined by `separator`.
+pub fn env_name(prefix: &str, separator: &str, path: &str) -> String {
+    let segments: Vec<String> = path.split('.').map(screaming).collect();
+    format!("{}{}", prefix, segments.join(separator))
+}
+
+/// Maps the keys of `keys` to environment variables. Only keys an environment variable can
+/// address are listed: those under array items (`[]`) or map entries (`*`) are not, and an
+/// object is listed only when none of its properties are, so its value is set as a whole.
+pub fn env_doc(keys: &SchemaKeys, prefix: &str, separator: &str) -> EnvDoc {
+    let addressable: Vec<_> = keys.keys.iter().filter(|key| !key.path.contains("[]") && !key.path.contains('*')).collect();
+    let variables = addressable
+        .iter()
+        .filter(|key| {
+            let nested = format!("{}.", key.path);
+            !addressable.iter().any(|other| other.path.starts_with(&nested))
+        })
+        .map(|key| EnvVar {
+            name: env_name(prefix, separator, &key.path),
+            path: key.path.clone(),
+            types: key.types.clone(),
+            default: key.default.clone(),
+            description: key.description.clone(),
+            required: key.required,
+        })
+        .collect();
+    EnvDoc { schema: keys.schema.clone(), prefix: prefix.to_string(), variables }
+}
+
+// Table cells cannot hold line breaks or bare pipes
+fn cell(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ").replace('|', "\\|")
+}
+
+/// Markdown table for `schema envdoc --format markdown`.
+pub fn render_markdown(doc: &EnvDoc) -> String {
+    let mut out = String::from("| Variable | Config key | Type | Default | Description |\n|---|---|---|---|---|\n");
+    for var in &doc.variables {
+        let default = var.default.as_ref().map(|d| format!("`{}`", cell(&d.to_string()))).unwrap_or_default();
+        let mut description = var.description.as_deref().map(cell).unwrap_or_default();
+        if var.required {
+            description = format!("**Required.** {}", description).trim_end().to_string();
+        }
+        out.push_str(&format!(
+            "| `{}` | `{}` | {} | {} | {} |\n",
+            var.name,
+            var.path,
+            var.types.join(" \\| "),
+            default,
+            description
+        ));
+    }
+    out
+}
@@ -1,5 +1,6 @@
-use miette::{Diagnostic, SourceSpan};
-use std::path::PathBuf;
+use miette::{Diagnostic, LabeledSpan, SourceSpan};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 // No need to import SpannedJsonValue or JsonSpan here if they are only used in main.rs for now
 // unless AppError variants themselves need to hold them directly, which they don't currently.
@@ -28,11 +29,26 @@ pub enum AppError { // Made AppError public
         #[source] source: serde_json::Error,
     },
 
+    #[error("Schema {source_display:?} is {kind}, not a JSON Schema object or boolean")]
+    #[diagnostic(
+        code(app::schema::invalid_shape),
+        help("a schema document must be an object like {{\"type\": \"object\"}} or `true`/`false`; check that the right file was exported, and pass an OpenAPI document with --openapi and --operation rather than --schema")
+    )]
+    InvalidSchemaShape {
+        source_display: String,
+        /// e.g. `an array`, `a string`
+        kind: &'static str,
+        #[label("this is {kind}")]
+        span: SourceSpan,
+        #[source_code]
+        source_code: String,
+    },
+
     #[error("Failed to compile schema (from {source_display:?}): {source}")]
     #[diagnostic(code(app::schema::compile_error))]
     SchemaCompileError {
         source_display: String, 
-        #[source] source: jsonschema::ValidationError<'static>,
+        #[source] source: Box<jsonschema::ValidationError<'static>>,
     },
 
     #[error("YAML parsing error in file {path:?}: {message}")]
@@ -46,19 +62,9 @@ pub enum AppError { // Made AppError public
         source_code: String,
     },
 
-    #[error("Schema validation error in file {path:?}: {message}")]
-    #[diagnostic(code(app::schema::validation_error))]
-    SchemaValidationError {
-        path: PathBuf,
-        message: String, 
-        #[source_code]
-        source_code: String, 
-        #[label("{label_message}")]
-        error_span: SourceSpan,
-        label_message: String, 
-        instance_path: String,
-        kind: String, 
-    },
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    SchemaValidationError(Box<SchemaValidationError>),
 
     #[error("JSON parsing error in file {path:?}: {message}")]
     #[diagnostic(code(app::json::parse_error))]
@@ -94,6 +100,337 @@ pub enum AppError { // Made AppError public
         source_code: String,
     },
 
+    #[error("INI parsing error in file {path:?}: {message}")]
+    #[diagnostic(code(app::ini::parse_error))]
+    IniParseError {
+        path: PathBuf,
+        message: String,
+        #[label = "{message}"]
+        span: SourceSpan,
+        #[source_code]
+        source_code: String,
+    },
+
+    #[error("Properties parsing error in file {path:?}: {message}")]
+    #[diagnostic(code(app::properties::parse_error))]
+    PropertiesParseError {
+        path: PathBuf,
+        message: String,
+        #[label = "{message}"]
+        span: SourceSpan,
+        #[source_code]
+        source_code: String,
+    },
+
+    #[error("KDL parsing error in file {path:?}: {message}")]
+    #[diagnostic(code(app::kdl::parse_error))]
+    KdlParseError {
+        path: PathBuf,
+        message: String,
+        #[label = "{message}"]
+        span: SourceSpan,
+        #[source_code]
+        source_code: String,
+    },
+
+    #[error("Hjson parsing error in file {path:?}: {message}")]
+    #[diagnostic(code(app::hjson::parse_error))]
+    HjsonParseError {
+        path: PathBuf,
+        message: String,
+        #[label = "{message}"]
+        span: SourceSpan,
+        #[source_code]
+        source_code: String,
+    },
+
+    #[error("{tool} evaluation error in file {path:?}: {message}")]
+    #[diagnostic(code(app::evaluator::error))]
+    EvaluationError {
+        path: PathBuf,
+        tool: String,
+        message: String,
+        #[label("reported here")]
+        span: SourceSpan,
+        #[source_code]
+        source_code: String,
+    },
+
+    #[error("{check} check failed in file {path:?}: {message}")]
+    #[diagnostic(code(app::preset::semantic_error))]
+    SemanticError {
+        path: PathBuf,
+        check: String,
+        message: String,
+        #[label("{message}")]
+        span: SourceSpan,
+        #[source_code]
+        source_code: String,
+    },
+
+    #[error("Unknown preset {name:?} (available: {available})")]
+    #[diagnostic(code(app::preset::unknown))]
+    UnknownPreset {
+        name: String,
+        available: String,
+    },
+
+    #[error("OpenAPI error in {spec}: {message}")]
+    #[diagnostic(code(app::openapi::resolve_error))]
+    OpenApiError {
+        spec: String,
+        message: String,
+    },
+
+    #[error("Protobuf descriptor error in {descriptor}: {message}")]
+    #[diagnostic(code(app::proto::descriptor_error))]
+    ProtoDescriptorError {
+        descriptor: String,
+        message: String,
+    },
+
+    #[error("ARM template error in {template}: {message}")]
+    #[diagnostic(code(app::arm::template_error))]
+    ArmTemplateError {
+        template: String,
+        message: String,
+    },
+
+    #[error("Report signing error for {path}: {message}")]
+    #[diagnostic(code(app::report::signing_error))]
+    ReportSigningError {
+        path: String,
+        message: String,
+    },
+
+    #[error("Run record error in {path}: {message}")]
+    #[diagnostic(code(app::run_record::error))]
+    RunRecordError {
+        path: String,
+        message: String,
+    },
+
+    #[error("Debug bundle error in {path}: {message}")]
+    #[diagnostic(code(app::debug_bundle::error))]
+    DebugBundleError {
+        path: String,
+        message: String,
+    },
+
+    #[error("Rules pack error in {pack}: {message}")]
+    #[diagnostic(code(app::rules_pack::error))]
+    RulesPackError {
+        pack: String,
+        message: String,
+    },
+
+    #[error("Diff base error for {base}: {message}")]
+    #[diagnostic(code(app::diff_base::error))]
+    DiffBaseError {
+        base: String,
+        message: String,
+    },
+
+    #[error("Cannot read the inputs at revision {revision}: {message}")]
+    #[diagnostic(code(app::history::error))]
+    HistoryError {
+        revision: String,
+        message: String,
+    },
+
+    /// A diagnostic a rules pack or `--severity` maps to `warning`: still reported, but does
+    /// not fail the run.
+    #[error("{inner}")]
+    #[diagnostic(forward(inner), code(app::rules_pack::downgraded), severity(Warning))]
+    Downgraded {
+        inner: Box<AppError>,
+    },
+
+    /// A diagnostic mapped to `info`: reported as a note.
+    #[error("{inner}")]
+    #[diagnostic(forward(inner), code(app::severity::info), severity(Advice))]
+    Informational {
+        inner: Box<AppError>,
+    },
+
+    /// A warning mapped to `error`: fails the run.
+    #[error("{inner}")]
+    #[diagnostic(forward(inner), code(app::severity::escalated), severity(Error))]
+    Escalated {
+        inner: Box<AppError>,
+    },
+
+    #[error("Suppression at {location} expired on {expires} and no longer applies ({reason})")]
+    #[diagnostic(
+        code(app::suppression::expired),
+        severity(Warning),
+        help("fix the underlying diagnostic, or extend `expires` with a reason for the delay")
+    )]
+    ExpiredSuppression {
+        path: PathBuf,
+        location: String,
+        expires: String,
+        reason: String,
+    },
+
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    LimitExceeded(Box<LimitExceeded>),
+
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    UnrepresentableValue(Box<UnrepresentableValue>),
+
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    RenamedProperty(Box<RenamedProperty>),
+
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    RenamedEnumValue(Box<RenamedEnumValue>),
+
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    CoercibleType(Box<CoercibleType>),
+
+    /// Errors in one file that share a cause, reported once with a label per occurrence.
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    RelatedErrors(Box<RelatedErrors>),
+
+    #[error("Schema {schema} has no `$schema`; validating it as {dialect}")]
+    #[diagnostic(
+        code(app::schema::assumed_dialect),
+        severity(Warning),
+        help("declare the dialect with `$schema`, or pick the default with --default-draft or `default_draft` in .toml-and-jerry.toml")
+    )]
+    AssumedDialect {
+        schema: String,
+        dialect: String,
+    },
+
+    /// A diagnostic in a generated file, reported at the template line its source map points to.
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    MappedToTemplate(Box<MappedToTemplate>),
+
+    #[error("Config version `{version}` in file {path:?} has no schema")]
+    #[diagnostic(
+        code(app::schema::unknown_version),
+        help("known versions: {known}; add it to [schema_versions] in .toml-and-jerry.toml")
+    )]
+    UnknownConfigVersion {
+        path: PathBuf,
+        version: String,
+        known: String,
+        #[label("unknown version")]
+        span: SourceSpan,
+        #[source_code]
+        source_code: String,
+    },
+
+    #[error("Validation stamp in file {path:?} is stale: {reason}")]
+    #[diagnostic(
+        code(app::stamp::stale),
+        severity(Warning),
+        help("run check --stamp again to refresh it")
+    )]
+    StaleStamp {
+        path: PathBuf,
+        reason: String,
+        #[label("stale stamp")]
+        span: SourceSpan,
+        #[source_code]
+        source_code: String,
+    },
+
+    #[error("File {path:?} is expected to be invalid, but it validates")]
+    #[diagnostic(
+        code(app::expect::unexpectedly_valid),
+        help("the schema no longer rejects this bad example; fix the example or stop expecting it to fail")
+    )]
+    UnexpectedlyValid {
+        path: PathBuf,
+    },
+
+    #[error("Required file {path:?} is missing; every directory matching `{dirs}` must have one")]
+    #[diagnostic(
+        code(app::require::missing_file),
+        help("add the file, or change the [[require]] entry of the project config")
+    )]
+    MissingRequiredFile {
+        path: PathBuf,
+        dirs: String,
+    },
+
+    /// A value a project config `[[consistent]]` entry pairs with another does not compare as required.
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    InconsistentValue(Box<InconsistentValue>),
+
+    /// A later config layer sets a path to a different value than an earlier one.
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    MergeConflict(Box<MergeConflict>),
+
+    #[error("File {path:?} has {count} line(s) not ending with {expected}")]
+    #[diagnostic(
+        code(app::style::line_endings),
+        severity(Warning),
+        help("convert the file to {expected} line endings, or run with --fix to rewrite them")
+    )]
+    InconsistentLineEndings {
+        path: PathBuf,
+        /// `LF` or `CRLF`
+        expected: String,
+        count: usize,
+        /// The first few offending lines
+        #[label(collection)]
+        labels: Vec<LabeledSpan>,
+        #[source_code]
+        source_code: String,
+    },
+
+    #[error("File {path:?} does not end with a newline")]
+    #[diagnostic(
+        code(app::style::final_newline),
+        severity(Warning),
+        help("end the last line with {newline}, or run with --fix to add it")
+    )]
+    MissingFinalNewline {
+        path: PathBuf,
+        /// `LF` or `CRLF`, whichever the file uses
+        newline: String,
+        #[label("no line break after this line")]
+        span: SourceSpan,
+        #[source_code]
+        source_code: String,
+    },
+
+    #[error("Definition `{pointer}` in schema {schema} is never referenced")]
+    #[diagnostic(
+        code(app::schema::unused_definition),
+        severity(Warning),
+        help("no $ref reaches it from the schema root; remove it, or reference it where it was meant to be used")
+    )]
+    UnusedDefinition {
+        schema: String,
+        /// JSON pointer of the entry, e.g. `/$defs/Database`
+        pointer: String,
+        #[label("unused definition")]
+        span: SourceSpan,
+        #[source_code]
+        source_code: String,
+    },
+
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    InvalidSchemaExample(Box<InvalidSchemaExample>),
+
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    UnsafePattern(Box<UnsafePattern>),
+
     #[error("Invalid schema path: {path_display}")]
     #[diagnostic(code(app::schema::invalid_path))]
     InvalidSchemaPath {
@@ -101,5 +438,434 @@ pub enum AppError { // Made AppError public
     },
 }
 
+// The larger diagnostics keep their fields behind a box, so that every `Result<_, AppError>`
+// stays small
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("Schema validation error in file {path:?}: {message}")]
+#[diagnostic(code(app::schema::validation_error))]
+pub struct SchemaValidationError {
+    pub path: PathBuf,
+    pub message: String,
+    #[source_code]
+    pub source_code: String,
+    #[label("{label_message}")]
+    pub error_span: SourceSpan,
+    pub label_message: String,
+    pub instance_path: String,
+    /// Keyword location in the schema, e.g. `/properties/port/minimum`
+    pub schema_path: String,
+    pub kind: String,
+    /// The failing keyword with what it expected and what it got
+    pub violation: crate::violation::Violation,
+}
 
- 
\ No newline at end of file
+#[derive(Debug, Error, Diagnostic)]
+#[error("{rule} exceeded in file {path:?}: {message}")]
+#[diagnostic(
+    code(app::lint::limit_exceeded),
+    severity(Warning),
+    help("split the file or extract nested blocks to keep it reviewable")
+)]
+pub struct LimitExceeded {
+    pub path: PathBuf,
+    pub rule: String,
+    pub message: String,
+    pub instance_path: String,
+    #[label("{message}")]
+    pub span: SourceSpan,
+    #[source_code]
+    pub source_code: String,
+}
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("YAML {construct} in file {path:?} cannot be represented in JSON: {message}")]
+#[diagnostic(
+    code(app::yaml::unrepresentable_value),
+    help("use string keys and plain sequences/mappings so the document validates as written")
+)]
+pub struct UnrepresentableValue {
+    pub path: PathBuf,
+    pub construct: String,
+    pub message: String,
+    pub instance_path: String,
+    #[label("{construct}")]
+    pub span: SourceSpan,
+    #[source_code]
+    pub source_code: String,
+}
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("Property `{from}` in file {path:?} was renamed to `{to}`")]
+#[diagnostic(
+    code(app::schema::renamed_property),
+    severity(Warning),
+    help("rename `{from}` to `{to}`, or run with --fix to apply the rename")
+)]
+pub struct RenamedProperty {
+    pub path: PathBuf,
+    pub from: String,
+    pub to: String,
+    pub instance_path: String,
+    #[label("renamed to `{to}`")]
+    pub span: SourceSpan,
+    #[source_code]
+    pub source_code: String,
+}
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("Value `{from}` at {instance_path} in file {path:?} was renamed to `{to}`")]
+#[diagnostic(
+    code(app::schema::renamed_enum_value),
+    severity(Warning),
+    help("replace `{from}` with `{to}`, or run with --fix to rewrite it")
+)]
+pub struct RenamedEnumValue {
+    pub path: PathBuf,
+    pub from: String,
+    pub to: String,
+    pub instance_path: String,
+    #[label("renamed to `{to}`")]
+    pub span: SourceSpan,
+    #[source_code]
+    pub source_code: String,
+}
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("Value {written} at {instance_path} in file {path:?} is written as {found}, but the schema expects {expected}")]
+#[diagnostic(code(app::schema::coercible_type), help("{hint}"))]
+pub struct CoercibleType {
+    pub path: PathBuf,
+    pub instance_path: String,
+    /// Keyword location in the schema, e.g. `/properties/port/type`
+    pub schema_path: String,
+    pub expected: String,
+    pub found: String,
+    /// The value as JSON, e.g. `"8080"`
+    pub written: String,
+    /// Text that replaces the span to give the value the expected type, when it was found
+    /// written at its key
+    pub replacement: Option<String>,
+    pub hint: String,
+    #[label("written as {found}")]
+    pub span: SourceSpan,
+    #[source_code]
+    pub source_code: String,
+    pub violation: crate::violation::Violation,
+}
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("{count} related errors in file {path:?}: {message}")]
+#[diagnostic(code(app::grouped))]
+pub struct RelatedErrors {
+    pub path: PathBuf,
+    pub message: String,
+    pub count: usize,
+    #[label(collection)]
+    pub labels: Vec<LabeledSpan>,
+    #[source_code]
+    pub source_code: String,
+    /// The grouped diagnostics, in the order they were reported
+    pub members: Vec<AppError>,
+}
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("{message}")]
+#[diagnostic(
+    code(app::source_map::mapped),
+    help("fix the template {template:?} and regenerate; the generated location is reported below")
+)]
+pub struct MappedToTemplate {
+    pub template: PathBuf,
+    pub message: String,
+    /// `file:line:column` in the generated file
+    pub generated_at: String,
+    #[label("produces {generated_at}")]
+    pub span: SourceSpan,
+    #[source_code]
+    pub source_code: String,
+    /// The original diagnostic, at its generated location
+    #[related]
+    pub generated: Vec<AppError>,
+}
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("Value of `{pointer}` in file {path:?} is {value}; {requirement}")]
+#[diagnostic(
+    code(app::consistency::mismatch),
+    help("make the values agree, or change the [[consistent]] entry of the project config")
+)]
+pub struct InconsistentValue {
+    pub path: PathBuf,
+    pub pointer: String,
+    pub value: String,
+    pub requirement: String,
+    #[label("{value}")]
+    pub span: SourceSpan,
+    #[source_code]
+    pub source_code: String,
+    /// The value it is compared with
+    #[related]
+    pub other: Vec<AppError>,
+}
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("Conflicting value for `{pointer}` in file {path:?}: {value}")]
+#[diagnostic(
+    code(app::merge::conflict),
+    help("make the layers agree, or merge with --strategy last-wins to let the last layer win")
+)]
+pub struct MergeConflict {
+    pub path: PathBuf,
+    pub pointer: String,
+    pub value: String,
+    #[label("set to {value} here")]
+    pub span: SourceSpan,
+    #[source_code]
+    pub source_code: String,
+    /// Where the earlier layer sets it
+    #[related]
+    pub earlier: Vec<AppError>,
+}
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("Example {index} at `{pointer}` in schema {schema} does not match its schema: {message}")]
+#[diagnostic(
+    code(app::schema::invalid_example),
+    help("update the example to what the schema accepts now, or fix the schema if the example is right")
+)]
+pub struct InvalidSchemaExample {
+    pub schema: String,
+    /// JSON pointer of the `examples` keyword, e.g. `/properties/port/examples`
+    pub pointer: String,
+    pub index: usize,
+    pub message: String,
+    #[label("example {index} is invalid")]
+    pub span: SourceSpan,
+    #[source_code]
+    pub source_code: String,
+}
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("Pattern `{pattern}` at `{pointer}` in schema {schema} can backtrack exponentially: {reason}")]
+#[diagnostic(
+    code(app::schema::unsafe_pattern),
+    severity(Warning),
+    help("rewrite it so each part of a value can match only one way, e.g. `(a+)+` as `a+`; checks cut such patterns off after --regex-backtrack-limit steps")
+)]
+pub struct UnsafePattern {
+    pub schema: String,
+    /// JSON pointer of the `pattern` keyword or `patternProperties` entry
+    pub pointer: String,
+    pub pattern: String,
+    pub reason: String,
+    #[label("{reason}")]
+    pub span: SourceSpan,
+    #[source_code]
+    pub source_code: String,
+}
+
+/// What kind of problem a diagnostic reports, for filtering and grouping reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Category {
+    /// A file, URL or report could not be read or written
+    Io,
+    /// An input is malformed or has no JSON equivalent
+    Parse,
+    /// An input breaks its schema, or the schema itself is unusable
+    Schema,
+    /// A project rule: semantic checks, limits, expectations, suppressions, stamps, layer conflicts
+    Policy,
+    /// Renamed keys and values, line endings, and other things that still validate
+    Style,
+}
+
+impl Category {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Category::Io => "io",
+            Category::Parse => "parse",
+            Category::Schema => "schema",
+            Category::Policy => "policy",
+            Category::Style => "style",
+        }
+    }
+}
+
+/// Whether a diagnostic fails the run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    /// Reported, but does not fail the run
+    Warning,
+    /// Reported as a note; does not fail the run
+    #[serde(alias = "note")]
+    Info,
+}
+
+impl Severity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Info => "info",
+        }
+    }
+
+    /// The SARIF `level`: `error`, `warning` or `note`.
+    pub fn sarif_level(self) -> &'static str {
+        match self {
+            Severity::Info => "note",
+            other => other.as_str(),
+        }
+    }
+}
+
+impl AppError {
+    pub fn category(&self) -> Category {
+        match self {
+            AppError::FileReadError { .. }
+            | AppError::SchemaFetchError { .. }
+            | AppError::InvalidSchemaPath { .. }
+            | AppError::ReportSigningError { .. }
+            | AppError::RunRecordError { .. }
+            | AppError::DebugBundleError { .. }
+            | AppError::RulesPackError { .. }
+            | AppError::DiffBaseError { .. }
+            | AppError::HistoryError { .. } => Category::Io,
+            AppError::YamlParseError { .. }
+            | AppError::JsonParseError { .. }
+            | AppError::TomlParseError { .. }
+            | AppError::HclParseError { .. }
+            | AppError::IniParseError { .. }
+            | AppError::PropertiesParseError { .. }
+            | AppError::KdlParseError { .. }
+            | AppError::HjsonParseError { .. }
+            | AppError::EvaluationError { .. }
+            | AppError::UnrepresentableValue { .. } => Category::Parse,
+            AppError::SchemaParseError { .. }
+            | AppError::InvalidSchemaShape { .. }
+            | AppError::SchemaCompileError { .. }
+            | AppError::SchemaValidationError { .. }
+            | AppError::CoercibleType { .. }
+            | AppError::OpenApiError { .. }
+            | AppError::ProtoDescriptorError { .. }
+            | AppError::ArmTemplateError { .. }
+            | AppError::AssumedDialect { .. }
+            | AppError::UnknownConfigVersion { .. }
+            | AppError::UnusedDefinition { .. }
+            | AppError::InvalidSchemaExample { .. }
+            | AppError::UnsafePattern { .. } => Category::Schema,
+            AppError::SemanticError { .. }
+            | AppError::UnknownPreset { .. }
+            | AppError::LimitExceeded { .. }
+            | AppError::ExpiredSuppression { .. }
+            | AppError::StaleStamp { .. }
+            | AppError::UnexpectedlyValid { .. }
+            | AppError::MissingRequiredFile { .. }
+            | AppError::InconsistentValue { .. }
+            | AppError::MergeConflict { .. } => Category::Policy,
+            AppError::RenamedProperty { .. }
+            | AppError::RenamedEnumValue { .. }
+            | AppError::InconsistentLineEndings { .. }
+            | AppError::MissingFinalNewline { .. } => Category::Style,
+            // Wrappers report what they wrap
+            AppError::Downgraded { inner } | AppError::Informational { inner } | AppError::Escalated { inner } => inner.category(),
+            AppError::RelatedErrors(group) => group.members.first().map_or(Category::Schema, AppError::category),
+            AppError::MappedToTemplate(mapped) => mapped.generated.first().map_or(Category::Schema, AppError::category),
+        }
+    }
+
+    /// The diagnostic's severity: its `severity(Warning)` or `severity(Advice)` attribute, or
+    /// that of a mapped diagnostic. Errors fail the run.
+    pub fn level(&self) -> Severity {
+        if let AppError::MappedToTemplate(mapped) = self {
+            return if mapped.generated.iter().all(AppError::is_warning) { Severity::Warning } else { Severity::Error };
+        }
+        match self.severity() {
+            Some(miette::Severity::Warning) => Severity::Warning,
+            Some(miette::Severity::Advice) => Severity::Info,
+            _ => Severity::Error,
+        }
+    }
+
+    /// Warnings (and info diagnostics) are reported but do not fail the run.
+    pub fn is_warning(&self) -> bool {
+        self.level() != Severity::Error
+    }
+
+    /// The diagnostic a severity mapping wrapped, or the diagnostic itself.
+    pub fn unwrapped(&self) -> &AppError {
+        match self {
+            AppError::Downgraded { inner } | AppError::Informational { inner } | AppError::Escalated { inner } => inner.unwrapped(),
+            other => other,
+        }
+    }
+
+    /// Replaces every input path the diagnostic names, its own and those of the diagnostics it
+    /// wraps or relates, with `relocate(path)`. Schema, URL and pack paths are left alone.
+    pub fn map_paths(&mut self, relocate: &impl Fn(&Path) -> PathBuf) {
+        match self {
+            AppError::FileReadError { path, .. }
+            | AppError::YamlParseError { path, .. }
+            | AppError::JsonParseError { path, .. }
+            | AppError::TomlParseError { path, .. }
+            | AppError::HclParseError { path, .. }
+            | AppError::IniParseError { path, .. }
+            | AppError::PropertiesParseError { path, .. }
+            | AppError::KdlParseError { path, .. }
+            | AppError::HjsonParseError { path, .. }
+            | AppError::EvaluationError { path, .. }
+            | AppError::SemanticError { path, .. }
+            | AppError::ExpiredSuppression { path, .. }
+            | AppError::UnknownConfigVersion { path, .. }
+            | AppError::StaleStamp { path, .. }
+            | AppError::UnexpectedlyValid { path }
+            | AppError::MissingRequiredFile { path, .. }
+            | AppError::InconsistentLineEndings { path, .. }
+            | AppError::MissingFinalNewline { path, .. } => *path = relocate(path),
+            AppError::SchemaValidationError(error) => error.path = relocate(&error.path),
+            AppError::CoercibleType(error) => error.path = relocate(&error.path),
+            AppError::LimitExceeded(error) => error.path = relocate(&error.path),
+            AppError::UnrepresentableValue(error) => error.path = relocate(&error.path),
+            AppError::RenamedProperty(error) => error.path = relocate(&error.path),
+            AppError::RenamedEnumValue(error) => error.path = relocate(&error.path),
+            AppError::RelatedErrors(error) => {
+                error.path = relocate(&error.path);
+                error.members.iter_mut().for_each(|error| error.map_paths(relocate));
+            }
+            AppError::InconsistentValue(error) => {
+                error.path = relocate(&error.path);
+                error.other.iter_mut().for_each(|error| error.map_paths(relocate));
+            }
+            AppError::MergeConflict(error) => {
+                error.path = relocate(&error.path);
+                error.earlier.iter_mut().for_each(|error| error.map_paths(relocate));
+            }
+            AppError::MappedToTemplate(error) => {
+                error.template = relocate(&error.template);
+                error.generated.iter_mut().for_each(|error| error.map_paths(relocate));
+            }
+            AppError::Downgraded { inner } | AppError::Informational { inner } | AppError::Escalated { inner } => inner.map_paths(relocate),
+            _ => {}
+        }
+    }
+
+    /// The diagnostic reported at `level`, wrapped only when that differs from its own.
+    pub fn into_level(self, level: Severity) -> AppError {
+        let error = match self {
+            AppError::Downgraded { inner } | AppError::Informational { inner } | AppError::Escalated { inner } => {
+                return (*inner).into_level(level)
+            }
+            other => other,
+        };
+        match level {
+            _ if error.level() == level => error,
+            Severity::Error => AppError::Escalated { inner: Box::new(error) },
+            Severity::Warning => AppError::Downgraded { inner: Box::new(error) },
+            Severity::Info => AppError::Informational { inner: Box::new(error) },
+        }
+    }
+}
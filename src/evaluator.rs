@@ -0,0 +1,129 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use serde_json::Value as JsonValue;
+
+/// An external configuration language evaluator that can render a file to JSON.
+///
+/// Languages such as CUE are programs rather than data, so instead of embedding an
+/// interpreter we shell out to the language's own CLI and validate what it exports.
+pub struct Evaluator {
+    /// Display name used in diagnostics, e.g. "CUE"
+    pub tool: &'static str,
+    /// Executable looked up on PATH when no explicit binary is configured
+    pub default_binary: &'static str,
+    /// The CLI flag users pass to point at a specific binary
+    pub binary_flag: &'static str,
+    args: fn(&Path) -> Vec<String>,
+    position: fn(&str, &Path) -> Option<(usize, usize)>,
+}
+
+/// Why evaluating a file failed, with the 1-based line/column of the problem when the
+/// evaluator reported one for the input file.
+#[derive(Debug)]
+pub struct EvaluationFailure {
+    pub message: String,
+    pub position: Option<(usize, usize)>,
+}
+
+pub const CUE: Evaluator = Evaluator {
+    tool: "CUE",
+    default_binary: "cue",
+    binary_flag: "--cue-binary",
+    args: cue_args,
+    position: file_line_column_position,
+};
+
+fn cue_args(file: &Path) -> Vec<String> {
+    vec!["export".into(), "--out".into(), "json".into(), file.to_string_lossy().into_owned()]
+}
+
+pub const PKL: Evaluator = Evaluator {
+    tool: "Pkl",
+    default_binary: "pkl",
+    binary_flag: "--pkl-binary",
+    args: pkl_args,
+    position: pkl_position,
+};
+
+fn pkl_args(file: &Path) -> Vec<String> {
+    vec!["eval".into(), "--format".into(), "json".into(), file.to_string_lossy().into_owned()]
+}
+
+// Pkl reports `at module#prop (file:///.../config.pkl, line 3)` and underlines the
+// offending expression beneath an excerpt like `3 | port = "abc"`.
+fn pkl_position(output: &str, file: &Path) -> Option<(usize, usize)> {
+    let file_name = file.file_name()?.to_str()?;
+    let line = output.match_indices(file_name).find_map(|(idx, _)| {
+        let rest = output[idx + file_name.len()..].strip_prefix(", line ")?;
+        let digits: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+        digits.parse::<usize>().ok()
+    })?;
+
+    let excerpt_prefix = format!("{} | ", line);
+    let mut lines = output.lines();
+    let column = loop {
+        let Some(current) = lines.next() else { break 1 };
+        if current.starts_with(&excerpt_prefix) {
+            break lines
+                .next()
+                .and_then(|carets| carets.find('^'))
+                .map(|caret| caret.saturating_sub(excerpt_prefix.len()) + 1)
+                .unwrap_or(1);
+        }
+    };
+    Some((line, column))
+}
+
+pub const NICKEL: Evaluator = Evaluator {
+    tool: "Nickel",
+    default_binary: "nickel",
+    binary_flag: "--nickel-binary",
+    args: nickel_args,
+    // Nickel's codespan diagnostics point at `┌─ config.ncl:3:10`
+    position: file_line_column_position,
+};
+
+fn nickel_args(file: &Path) -> Vec<String> {
+    vec!["export".into(), "--format".into(), "json".into(), file.to_string_lossy().into_owned()]
+}
+
+// Finds the first `<file name>:<line>:<col>` reference to the input in evaluator output.
+// CUE prints positions as `./config.cue:3:8`.
+fn file_line_column_position(output: &str, file: &Path) -> Option<(usize, usize)> {
+    let file_name = file.file_name()?.to_str()?;
+    output.match_indices(file_name).find_map(|(idx, _)| {
+        let rest = &output[idx + file_name.len()..];
+        let mut parts = rest.strip_prefix(':')?.splitn(3, ':');
+        let line = parts.next()?.parse::<usize>().ok()?;
+        let column_digits: String = parts.next()?.chars().take_while(|c| c.is_ascii_digit()).collect();
+        let column = column_digits.parse::<usize>().ok()?;
+        Some((line, column))
+    })
+}
+
+impl Evaluator {
+    /// Runs the evaluator on `file` and parses its JSON output.
+    pub fn evaluate(&self, binary: Option<&Path>, file: &Path) -> Result<JsonValue, EvaluationFailure> {
+        let binary: PathBuf = binary.map(Path::to_path_buf).unwrap_or_else(|| PathBuf::from(self.default_binary));
+        let output = Command::new(&binary).args((self.args)(file)).output().map_err(|e| EvaluationFailure {
+            message: format!(
+                "Could not run {} evaluator {:?} ({}); install it or pass {}",
+                self.tool, binary, e, self.binary_flag
+            ),
+            position: None,
+        })?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            return Err(EvaluationFailure {
+                position: (self.position)(&stderr, file),
+                message: stderr,
+            });
+        }
+
+        serde_json::from_slice(&output.stdout).map_err(|e| EvaluationFailure {
+            message: format!("{} produced output that is not JSON: {}", self.tool, e),
+            position: None,
+        })
+    }
+}
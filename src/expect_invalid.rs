@@ -0,0 +1,57 @@
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+
+use crate::error::AppError;
+use crate::validation::PrintableError;
+
+/// Files that are meant to fail validation, such as "bad example" fixtures: every input with
+/// `--expect-invalid`, plus those matching the project config's `expect_invalid` globs.
+#[derive(Debug, Clone)]
+pub struct ExpectInvalid {
+    all: bool,
+    globs: GlobSet,
+}
+
+impl ExpectInvalid {
+    pub fn new(all: bool, patterns: &[String]) -> Result<Self, String> {
+        let mut builder = GlobSetBuilder::new();
+        for pattern in patterns {
+            builder.add(Glob::new(pattern).map_err(|e| format!("Invalid expect_invalid pattern {:?}: {}", pattern, e))?);
+        }
+        let globs = builder.build().map_err(|e| format!("Invalid expect_invalid patterns: {}", e))?;
+        Ok(ExpectInvalid { all, globs })
+    }
+
+    /// Expects nothing to fail.
+    pub fn is_empty(&self) -> bool {
+        !self.all && self.globs.is_empty()
+    }
+
+    pub fn expects(&self, file: &str) -> bool {
+        self.all || self.globs.is_match(file)
+    }
+}
+
+/// Turns the failing diagnostics of `files` (those `expected` is true for) into the expected
+/// outcome: they are dropped, and a file that has none gets an `UnexpectedlyValid` error
+/// instead. Warnings and other files' diagnostics are kept.
+pub fn apply(files: &[PathBuf], errors: Vec<AppError>, expected: impl Fn(&str) -> bool) -> Vec<AppError> {
+    let mut failed = BTreeSet::new();
+    let mut kept = Vec::new();
+    for error in errors {
+        let file = PrintableError::from(&error).file_path;
+        if !error.is_warning() && expected(&file) {
+            failed.insert(file);
+        } else {
+            kept.push(error);
+        }
+    }
+    for file in files {
+        let display = file.to_string_lossy();
+        if expected(&display) && !failed.contains(display.as_ref()) {
+            kept.push(AppError::UnexpectedlyValid { path: file.clone() });
+        }
+    }
+    kept
+}
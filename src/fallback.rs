@@ -0,0 +1,69 @@
+use std::path::{Path, PathBuf};
+use jsonschema::Validator;
+
+use crate::error::{AppError, Severity};
+use crate::patch::load_instance;
+use crate::progress;
+use crate::validation::{validate_inputs_with_options, ValidationOptions};
+
+/// One schema of a `--schema` + `--schema-fallback` list.
+pub struct SchemaCandidate<'a> {
+    pub display: String,
+    pub validator: &'a Validator,
+    /// Options with this schema's `x-renamed-from` rules
+    pub options: ValidationOptions,
+}
+
+/// Which schema a file was judged against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FallbackOutcome {
+    /// The file is valid against this candidate
+    Matched(usize),
+    /// No candidate matched; this one had the fewest violations
+    Closest(usize),
+}
+
+// Violations of `validator` across the whole document (validation itself stops at the first)
+fn violation_count(input: &Path, candidate: &SchemaCandidate, failing: usize) -> usize {
+    match load_instance(input, &candidate.options.yaml_tags) {
+        Some(instance) => candidate.validator.iter_errors(&instance).count().max(failing),
+        None => failing,
+    }
+}
+
+/// Validates one file against the candidates in order, stopping at the first it satisfies.
+/// Without a match, the diagnostics of the closest candidate are returned.
+pub fn validate_file(input: &Path, candidates: &[SchemaCandidate]) -> Result<(FallbackOutcome, Vec<AppError>), AppError> {
+    let mut closest: Option<(usize, usize, Vec<AppError>)> = None;
+    for (index, candidate) in candidates.iter().enumerate() {
+        let errors = validate_inputs_with_options(vec![input.to_path_buf()], candidate.validator, &candidate.options)?;
+        let failing = errors.iter().filter(|e| e.level() == Severity::Error).count();
+        if failing == 0 {
+            return Ok((FallbackOutcome::Matched(index), errors));
+        }
+        let score = violation_count(input, candidate, failing);
+        if closest.as_ref().is_none_or(|(_, best, _)| score < *best) {
+            closest = Some((index, score, errors));
+        }
+    }
+    let (index, _, errors) = closest.unwrap_or_default();
+    Ok((FallbackOutcome::Closest(index), errors))
+}
+
+/// `validate_file` over all inputs, printing which schema each file was judged against.
+pub fn validate_with_fallback(inputs: &[PathBuf], candidates: &[SchemaCandidate]) -> Result<Vec<AppError>, AppError> {
+    let mut collected_errors = Vec::new();
+    for input in inputs {
+        let (outcome, errors) = validate_file(input, candidates)?;
+        match outcome {
+            FallbackOutcome::Matched(index) => progress::line(format_args!("{}: matches {}", input.display(), candidates[index].display)),
+            FallbackOutcome::Closest(index) => progress::line(format_args!(
+                "{}: matches no schema; reporting errors against the closest, {}",
+                input.display(),
+                candidates[index].display
+            )),
+        }
+        collected_errors.extend(errors);
+    }
+    Ok(collected_errors)
+}
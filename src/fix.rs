@@ -0,0 +1,264 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::ops::Range;
+use std::path::{Component, Path, PathBuf};
+use miette::{Diagnostic, SourceSpan};
+use serde::Serialize;
+
+use crate::error::AppError;
+use crate::newlines;
+
+/// A single textual edit that resolves a diagnostic.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fix {
+    pub path: PathBuf,
+    pub range: Range<usize>,
+    pub replacement: String,
+}
+
+/// The automatic fix for a diagnostic, if it has one (its first edit, for diagnostics that
+/// take several).
+pub fn fix_for(error: &AppError) -> Option<Fix> {
+    fixes_for(error).into_iter().next()
+}
+
+/// Every edit that resolves a diagnostic: one for a renamed key or value or a coercible
+/// type, one per line break for line endings.
+pub fn fixes_for(error: &AppError) -> Vec<Fix> {
+    match error {
+        AppError::RenamedProperty(renamed) => rename_fix(&renamed.path, &renamed.to, renamed.span, &renamed.source_code),
+        AppError::RenamedEnumValue(renamed) => rename_fix(&renamed.path, &renamed.to, renamed.span, &renamed.source_code),
+        AppError::CoercibleType(coercible) => match &coercible.replacement {
+            Some(replacement) => {
+                let span = coercible.span;
+                vec![Fix { path: coercible.path.clone(), range: span.offset()..span.offset() + span.len(), replacement: replacement.clone() }]
+            }
+            None => Vec::new(),
+        },
+        AppError::InconsistentLineEndings { path, .. } | AppError::MissingFinalNewline { path, .. } => newlines::fix_edits(error)
+            .into_iter()
+            .map(|(range, replacement)| Fix { path: path.clone(), range, replacement })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn rename_fix(path: &Path, to: &str, span: SourceSpan, source_code: &str) -> Vec<Fix> {
+    let range = span.offset()..span.offset() + span.len();
+    // Only whole-key/value spans are safe to rewrite; fallback spans cover the whole file
+    if range.len() == source_code.len() {
+        return Vec::new();
+    }
+    vec![Fix { path: path.to_path_buf(), range, replacement: to.to_string() }]
+}
+
+/// One line describing what the fix for a diagnostic changes, for `--fix` output.
+pub fn describe_change(error: &AppError) -> Option<String> {
+    match error {
+        AppError::RenamedProperty(renamed) => {
+            let at = if renamed.instance_path.is_empty() { "/" } else { &renamed.instance_path };
+            Some(format!("{}: property `{}` -> `{}`", at, renamed.from, renamed.to))
+        }
+        AppError::RenamedEnumValue(renamed) => Some(format!("{}: `{}` -> `{}`", renamed.instance_path, renamed.from, renamed.to)),
+        AppError::CoercibleType(coercible) => coercible.replacement.as_ref().map(|replacement| {
+            format!("{}: {} -> {} ({})", coercible.instance_path, coercible.written, replacement, coercible.expected)
+        }),
+        AppError::InconsistentLineEndings { expected, count, .. } => Some(format!("line endings: {} line(s) -> {}", count, expected)),
+        AppError::MissingFinalNewline { newline, .. } => Some(format!("final newline: added {}", newline)),
+        _ => None,
+    }
+}
+
+/// Applies `edits` to `content`. Overlapping edits after the first are skipped.
+pub fn apply_edits(content: &str, edits: &[Fix]) -> String {
+    let mut sorted: Vec<&Fix> = edits.iter().collect();
+    sorted.sort_by_key(|f| f.range.start);
+    let mut result = String::with_capacity(content.len());
+    let mut cursor = 0;
+    for edit in sorted {
+        if edit.range.start < cursor || edit.range.end > content.len() {
+            continue;
+        }
+        result.push_str(&content[cursor..edit.range.start]);
+        result.push_str(&edit.replacement);
+        cursor = edit.range.end;
+    }
+    result.push_str(&content[cursor..]);
+    result
+}
+
+/// Groups the fixable diagnostics by file.
+pub fn collect_fixes(errors: &[AppError]) -> BTreeMap<PathBuf, Vec<Fix>> {
+    let mut by_file: BTreeMap<PathBuf, Vec<Fix>> = BTreeMap::new();
+    for fix in errors.iter().flat_map(fixes_for) {
+        by_file.entry(fix.path.clone()).or_default().push(fix);
+    }
+    by_file
+}
+
+/// A file's content before and after its fixes, without writing anything.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedFix {
+    pub path: PathBuf,
+    pub before: String,
+    pub after: String,
+    pub count: usize,
+}
+
+/// Works out what every file with fixable diagnostics would become.
+pub fn plan_fixes(errors: &[AppError]) -> io::Result<Vec<PlannedFix>> {
+    let mut planned = Vec::new();
+    for (path, fixes) in collect_fixes(errors) {
+        let before = fs::read_to_string(&path)?;
+        let after = apply_edits(&before, &fixes);
+        planned.push(PlannedFix { path, before, after, count: fixes.len() });
+    }
+    Ok(planned)
+}
+
+/// Unified diff of a planned fix, with `a/` and `b/` headers as `git diff` writes them.
+pub fn unified_diff(planned: &PlannedFix) -> String {
+    let path = planned.path.to_string_lossy();
+    similar::TextDiff::from_lines(&planned.before, &planned.after)
+        .unified_diff()
+        .context_radius(3)
+        .header(&format!("a/{}", path), &format!("b/{}", path))
+        .to_string()
+}
+
+/// Where `--fix-out` writes the patch for `path`: under `dir`, mirroring the path, with
+/// `.patch` appended (`dir/configs/app.yaml.patch`). Root and `..` components are dropped.
+pub fn patch_path(dir: &Path, path: &Path) -> PathBuf {
+    let relative: PathBuf = path.components().filter(|c| matches!(c, Component::Normal(_))).collect();
+    let mut name = relative.into_os_string();
+    name.push(".patch");
+    dir.join(name)
+}
+
+/// Writes one patch per file that has fixable diagnostics instead of rewriting the file:
+/// the unified diff `--diff` prints, which `git apply` takes. Returns the patches written.
+pub fn write_patches(errors: &[AppError], dir: &Path) -> io::Result<Vec<(PathBuf, usize)>> {
+    let mut written = Vec::new();
+    for planned in plan_fixes(errors)? {
+        let patch = patch_path(dir, &planned.path);
+        crate::paths::check_writable(&patch)?;
+        if let Some(parent) = patch.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&patch, unified_diff(&planned))?;
+        written.push((patch, planned.count));
+    }
+    Ok(written)
+}
+
+/// Rewrites every file that has fixable diagnostics. Returns how many fixes were applied per file.
+pub fn apply_fixes(errors: &[AppError]) -> io::Result<Vec<(PathBuf, usize)>> {
+    let mut applied = Vec::new();
+    for planned in plan_fixes(errors)? {
+        crate::paths::check_writable(&planned.path)?;
+        fs::write(&planned.path, &planned.after)?;
+        applied.push((planned.path, planned.count));
+    }
+    Ok(applied)
+}
+
+/// What a `--fix` run changed, for bots that open fix PRs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FixSummary {
+    pub files: Vec<FileChanges>,
+    /// Diagnostic codes the applied fixes resolved, sorted
+    pub rules_satisfied: Vec<String>,
+    pub total_changes: usize,
+}
+
+/// The changes made to one file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileChanges {
+    pub path: String,
+    pub changes: Vec<KeyChange>,
+}
+
+/// One rewritten key or value, or a file's line breaks.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyChange {
+    /// JSON pointer of the object holding a renamed property, or of a rewritten value; `/`
+    /// for line breaks
+    pub pointer: String,
+    /// `property`, `value`, `type`, `line-endings` or `final-newline`
+    pub kind: &'static str,
+    pub from: String,
+    pub to: String,
+    pub rule: String,
+}
+
+fn key_change(error: &AppError) -> Option<KeyChange> {
+    let (kind, from, to, instance_path) = match error {
+        AppError::RenamedProperty(renamed) => ("property", renamed.from.clone(), renamed.to.clone(), renamed.instance_path.as_str()),
+        AppError::RenamedEnumValue(renamed) => ("value", renamed.from.clone(), renamed.to.clone(), renamed.instance_path.as_str()),
+        AppError::CoercibleType(coercible) => {
+            let replacement = coercible.replacement.clone()?;
+            ("type", coercible.written.clone(), replacement, coercible.instance_path.as_str())
+        }
+        AppError::InconsistentLineEndings { expected, .. } => {
+            let from = if expected == "LF" { "CRLF" } else { "LF" };
+            ("line-endings", from.to_string(), expected.clone(), "")
+        }
+        AppError::MissingFinalNewline { newline, .. } => ("final-newline", String::new(), newline.clone(), ""),
+        _ => return None,
+    };
+    Some(KeyChange {
+        pointer: if instance_path.is_empty() { "/".to_string() } else { instance_path.to_string() },
+        kind,
+        from,
+        to,
+        rule: error.code().map(|c| c.to_string()).unwrap_or_default(),
+    })
+}
+
+/// Summarizes the fixes `apply_fixes` applied (`fixed`) for the diagnostics in `errors`.
+pub fn summarize(errors: &[AppError], fixed: &[(PathBuf, usize)]) -> FixSummary {
+    let files: Vec<FileChanges> = fixed
+        .iter()
+        .map(|(path, _)| FileChanges {
+            path: path.to_string_lossy().into_owned(),
+            changes: errors
+                .iter()
+                .filter(|e| fix_for(e).is_some_and(|f| &f.path == path))
+                .filter_map(key_change)
+                .collect(),
+        })
+        .collect();
+    let mut rules_satisfied: Vec<String> = files.iter().flat_map(|f| f.changes.iter().map(|c| c.rule.clone())).collect();
+    rules_satisfied.sort();
+    rules_satisfied.dedup();
+    FixSummary { total_changes: files.iter().map(|f| f.changes.len()).sum(), files, rules_satisfied }
+}
+
+/// The summary as a commit message: a subject line, then the rules satisfied and the changes per file.
+pub fn render_changelog(summary: &FixSummary) -> String {
+    let plural = |n: usize, one: &str, many: &str| format!("{} {}", n, if n == 1 { one } else { many });
+    let mut out = format!(
+        "Apply {} in {}\n",
+        plural(summary.total_changes, "automatic fix", "automatic fixes"),
+        plural(summary.files.len(), "file", "files")
+    );
+    if !summary.rules_satisfied.is_empty() {
+        out.push_str(&format!("\nRules satisfied: {}\n", summary.rules_satisfied.join(", ")));
+    }
+    for file in &summary.files {
+        out.push_str(&format!("\n{}:\n", file.path));
+        for change in &file.changes {
+            match change.kind {
+                "property" => out.push_str(&format!("- {}: rename property `{}` to `{}`\n", change.pointer, change.from, change.to)),
+                "line-endings" => out.push_str(&format!("- convert line endings from {} to {}\n", change.from, change.to)),
+                "final-newline" => out.push_str(&format!("- add a final {} newline\n", change.to)),
+                _ => out.push_str(&format!("- {}: change `{}` to `{}`\n", change.pointer, change.from, change.to)),
+            }
+        }
+    }
+    out
+}
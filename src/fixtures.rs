@@ -0,0 +1,361 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use serde_json::{Map, Value as JsonValue};
+
+use crate::error::AppError;
+use crate::schema::compile_schema;
+
+// Past this many nested subschemas, a recursive schema gets no deeper values
+const MAX_DEPTH: usize = 32;
+
+/// One generated instance and the name of its file, without the extension: `minimal`, `full`,
+/// or the instance location and branch it covers, e.g. `server-mode-fast`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fixture {
+    pub name: String,
+    pub value: JsonValue,
+}
+
+/// A fixture the schema rejects, usually because of a keyword generation does not satisfy
+/// (`pattern`, `if`/`then`, `uniqueItems`); give the subschema an `examples` entry instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rejected {
+    pub name: String,
+    pub reason: String,
+}
+
+// A place where the schema offers alternatives: an `enum`, or the arms of a `oneOf`/`anyOf`
+struct Choice {
+    schema_pointer: String,
+    instance_path: String,
+    labels: Vec<String>,
+}
+
+struct Generator<'a> {
+    root: &'a JsonValue,
+    // The option taken at each choice, by schema pointer; the first when absent
+    picks: &'a BTreeMap<String, usize>,
+    // Fill in optional properties and one item of arrays that may be empty
+    full: bool,
+    choices: Vec<Choice>,
+}
+
+fn slug(text: &str) -> String {
+    let mut slug = String::new();
+    for c in text.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_matches('-').to_string()
+}
+
+// How a fixture names an arm: its title, the definition it refers to, the `const` that tells it
+// apart from its siblings, or its position
+fn arm_label(arm: &JsonValue, index: usize) -> String {
+    if let Some(title) = arm.get("title").and_then(JsonValue::as_str) {
+        return title.to_string();
+    }
+    if let Some(reference) = arm.get("$ref").and_then(JsonValue::as_str) {
+        return reference.rsplit('/').next().unwrap_or(reference).to_string();
+    }
+    let discriminator = arm
+        .get("properties")
+        .and_then(JsonValue::as_object)
+        .and_then(|properties| properties.values().find_map(|property| property.get("const")?.as_str()));
+    discriminator.map_or_else(|| (index + 1).to_string(), str::to_string)
+}
+
+fn enum_label(value: &JsonValue) -> String {
+    match value {
+        JsonValue::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+// Objects merge key by key; anything else is replaced by the later value
+fn merge(into: &mut JsonValue, value: JsonValue) {
+    match (into, value) {
+        (JsonValue::Object(target), JsonValue::Object(source)) => {
+            for (key, value) in source {
+                match target.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        target.insert(key, value);
+                    }
+                }
+            }
+        }
+        (into, value) => *into = value,
+    }
+}
+
+fn string_for_format(format: &str) -> Option<&'static str> {
+    Some(match format {
+        "date-time" => "2024-01-01T00:00:00Z",
+        "date" => "2024-01-01",
+        "time" => "00:00:00Z",
+        "duration" => "P1D",
+        "email" | "idn-email" => "user@example.com",
+        "hostname" | "idn-hostname" => "example.com",
+        "ipv4" => "192.0.2.1",
+        "ipv6" => "2001:db8::1",
+        "uri" | "iri" | "uri-reference" | "iri-reference" | "uri-template" => "https://example.com/",
+        "uuid" => "00000000-0000-4000-8000-000000000000",
+        "json-pointer" => "/example",
+        "regex" => "^example$",
+        _ => return None,
+    })
+}
+
+// The number closest to zero within the schema's bounds, rounded up to its `multipleOf`
+fn number_in_bounds(schema: &Map<String, JsonValue>, integer: bool) -> JsonValue {
+    let bound = |key: &str| schema.get(key).and_then(JsonValue::as_f64);
+    let step = if integer { 1.0 } else { 0.5 };
+    let low = bound("minimum").or(bound("exclusiveMinimum").map(|n| n + step));
+    let high = bound("maximum").or(bound("exclusiveMaximum").map(|n| n - step));
+    let mut value = 0.0_f64;
+    if let Some(low) = low {
+        value = value.max(low);
+    }
+    if let Some(high) = high {
+        value = value.min(high);
+    }
+    if let Some(multiple) = bound("multipleOf").filter(|m| *m > 0.0) {
+        value = (value / multiple).ceil() * multiple;
+    }
+    if integer {
+        JsonValue::from(value.ceil() as i64)
+    } else {
+        serde_json::Number::from_f64(value).map_or(JsonValue::from(0), JsonValue::Number)
+    }
+}
+
+impl Generator<'_> {
+    fn pick(&mut self, schema_pointer: &str, instance_path: &str, labels: Vec<String>) -> usize {
+        let picked = self.picks.get(schema_pointer).copied().filter(|&index| index < labels.len()).unwrap_or(0);
+        self.choices.push(Choice { schema_pointer: schema_pointer.to_string(), instance_path: instance_path.to_string(), labels });
+        picked
+    }
+
+    fn generate(&mut self, schema: &JsonValue, pointer: &str, instance_path: &str, depth: usize) -> JsonValue {
+        let Some(object) = schema.as_object() else {
+            return if schema == &JsonValue::Bool(false) { JsonValue::Null } else { JsonValue::from("example") };
+        };
+        if depth > MAX_DEPTH {
+            return JsonValue::Null;
+        }
+        if let Some(reference) = object.get("$ref").and_then(JsonValue::as_str) {
+            let target = reference.strip_prefix('#').and_then(|fragment| Some((fragment, self.root.pointer(fragment)?)));
+            if let Some((fragment, target)) = target {
+                return self.generate(target, fragment, instance_path, depth + 1);
+            }
+        }
+        if let Some(value) = object.get("const") {
+            return value.clone();
+        }
+        if let Some(JsonValue::Array(values)) = object.get("enum").filter(|values| values.as_array().is_some_and(|v| !v.is_empty())) {
+            let index = self.pick(&format!("{}/enum", pointer), instance_path, values.iter().map(enum_label).collect());
+            return values[index].clone();
+        }
+
+        let mut value = self.own_value(object, pointer, instance_path, depth);
+        for keyword in ["oneOf", "anyOf"] {
+            let Some(JsonValue::Array(arms)) = object.get(keyword).filter(|arms| arms.as_array().is_some_and(|a| !a.is_empty())) else {
+                continue;
+            };
+            let arms_pointer = format!("{}/{}", pointer, keyword);
+            let labels = arms.iter().enumerate().map(|(index, arm)| arm_label(arm, index)).collect();
+            let index = self.pick(&arms_pointer, instance_path, labels);
+            let arm = self.generate(&arms[index], &format!("{}/{}", arms_pointer, index), instance_path, depth + 1);
+            match &mut value {
+                Some(own) => merge(own, arm),
+                None => value = Some(arm),
+            }
+        }
+        if let Some(JsonValue::Array(arms)) = object.get("allOf") {
+            for (index, arm) in arms.iter().enumerate() {
+                let arm = self.generate(arm, &format!("{}/allOf/{}", pointer, index), instance_path, depth + 1);
+                match &mut value {
+                    Some(own) => merge(own, arm),
+                    None => value = Some(arm),
+                }
+            }
+        }
+        value.unwrap_or_else(|| JsonValue::from("example"))
+    }
+
+    // The value the schema's own keywords describe, before its combinators are merged in;
+    // `None` when they say nothing about it
+    fn own_value(&mut self, object: &Map<String, JsonValue>, pointer: &str, instance_path: &str, depth: usize) -> Option<JsonValue> {
+        let structured = ["properties", "items", "prefixItems", "oneOf", "anyOf", "allOf"].iter().any(|key| object.contains_key(*key));
+        if !structured {
+            let example = object.get("examples").and_then(|examples| examples.as_array()?.first());
+            if let Some(example) = example.or(object.get("default")) {
+                return Some(example.clone());
+            }
+        }
+        let declared = match object.get("type") {
+            Some(JsonValue::String(name)) => Some(name.as_str()),
+            Some(JsonValue::Array(names)) => {
+                let names: Vec<&str> = names.iter().filter_map(JsonValue::as_str).collect();
+                names.iter().find(|name| **name != "null").or(names.first()).copied()
+            }
+            _ => None,
+        };
+        let has = |keys: &[&str]| keys.iter().any(|key| object.contains_key(*key));
+        let kind = declared.or_else(|| {
+            if has(&["properties", "required", "additionalProperties", "patternProperties"]) {
+                Some("object")
+            } else if has(&["items", "prefixItems", "minItems"]) {
+                Some("array")
+            } else if has(&["minLength", "maxLength", "pattern", "format"]) {
+                Some("string")
+            } else if has(&["minimum", "maximum", "exclusiveMinimum", "exclusiveMaximum", "multipleOf"]) {
+                Some("number")
+            } else {
+                None
+            }
+        })?;
+        Some(match kind {
+            "object" => self.object_value(object, pointer, instance_path, depth),
+            "array" => self.array_value(object, pointer, instance_path, depth),
+            "string" => {
+                let format = object.get("format").and_then(JsonValue::as_str).and_then(string_for_format);
+                let mut text = format.unwrap_or("example").to_string();
+                let length = |key: &str| object.get(key).and_then(JsonValue::as_u64).map(|n| n as usize);
+                if let Some(min) = length("minLength") {
+                    while text.chars().count() < min {
+                        text.push('x');
+                    }
+                }
+                if let Some(max) = length("maxLength") {
+                    text = text.chars().take(max).collect();
+                }
+                JsonValue::String(text)
+            }
+            "integer" => number_in_bounds(object, true),
+            "number" => number_in_bounds(object, false),
+            "boolean" => JsonValue::Bool(false),
+            _ => JsonValue::Null,
+        })
+    }
+
+    fn object_value(&mut self, object: &Map<String, JsonValue>, pointer: &str, instance_path: &str, depth: usize) -> JsonValue {
+        let empty = Map::new();
+        let properties = object.get("properties").and_then(JsonValue::as_object).unwrap_or(&empty);
+        let required: BTreeSet<&str> = object
+            .get("required")
+            .and_then(JsonValue::as_array)
+            .map(|names| names.iter().filter_map(JsonValue::as_str).collect())
+            .unwrap_or_default();
+        let mut value = Map::new();
+        for (name, property) in properties {
+            if !self.full && !required.contains(name.as_str()) {
+                continue;
+            }
+            let property_pointer = format!("{}/properties/{}", pointer, name.replace('~', "~0").replace('/', "~1"));
+            let property_path = format!("{}/{}", instance_path, name.replace('~', "~0").replace('/', "~1"));
+            value.insert(name.clone(), self.generate(property, &property_pointer, &property_path, depth + 1));
+        }
+        // Required names the properties do not describe take the schema of the others
+        let additional = object.get("additionalProperties").filter(|schema| schema.is_object());
+        let undescribed: Vec<&str> = required.iter().copied().filter(|name| !value.contains_key(*name)).collect();
+        for name in undescribed {
+            let property_path = format!("{}/{}", instance_path, name.replace('~', "~0").replace('/', "~1"));
+            let generated = match additional {
+                Some(schema) => self.generate(schema, &format!("{}/additionalProperties", pointer), &property_path, depth + 1),
+                None => JsonValue::from("example"),
+            };
+            value.insert(name.to_string(), generated);
+        }
+        JsonValue::Object(value)
+    }
+
+    fn array_value(&mut self, object: &Map<String, JsonValue>, pointer: &str, instance_path: &str, depth: usize) -> JsonValue {
+        let prefix = object.get("prefixItems").and_then(JsonValue::as_array).map(Vec::as_slice).unwrap_or_default();
+        let items = object.get("items").filter(|items| items.is_object());
+        let min = object.get("minItems").and_then(JsonValue::as_u64).unwrap_or(0) as usize;
+        let wanted = min.max(prefix.len()).max(usize::from(self.full && items.is_some()));
+        let mut values = Vec::new();
+        for index in 0..wanted {
+            let item_path = format!("{}/{}", instance_path, index);
+            let value = match (prefix.get(index), items) {
+                (Some(schema), _) => self.generate(schema, &format!("{}/prefixItems/{}", pointer, index), &item_path, depth + 1),
+                (None, Some(schema)) => self.generate(schema, &format!("{}/items", pointer), &item_path, depth + 1),
+                (None, None) => JsonValue::from("example"),
+            };
+            values.push(value);
+        }
+        JsonValue::Array(values)
+    }
+}
+
+fn generate(schema: &JsonValue, picks: &BTreeMap<String, usize>, full: bool) -> (JsonValue, Vec<Choice>) {
+    let mut generator = Generator { root: schema, picks, full, choices: Vec::new() };
+    let value = generator.generate(schema, "", "", 0);
+    (value, generator.choices)
+}
+
+/// One instance per branch of `schema`: `minimal` (required properties only), `full` (every
+/// property), and a variant of `full` for each further `enum` value and `oneOf`/`anyOf` arm,
+/// including those only reachable through another variant. Leaf values come from `examples`,
+/// then `default`, then the type's bounds and format. Names are derived from the schema, so
+/// regenerating an unchanged schema writes the same files; identical instances are kept once.
+pub fn fixture_suite(schema: &JsonValue) -> Vec<Fixture> {
+    let mut fixtures: Vec<Fixture> = Vec::new();
+    let mut add = |name: String, value: JsonValue| {
+        if fixtures.iter().any(|fixture| fixture.value == value) {
+            return;
+        }
+        let mut unique = name.clone();
+        let mut counter = 1;
+        while fixtures.iter().any(|fixture| fixture.name == unique) {
+            counter += 1;
+            unique = format!("{}-{}", name, counter);
+        }
+        fixtures.push(Fixture { name: unique, value });
+    };
+    add("minimal".to_string(), generate(schema, &BTreeMap::new(), false).0);
+
+    // Variants to generate, by the picks that make them; each choice is expanded once, in the
+    // first variant that reaches it
+    let mut queue = VecDeque::from([("full".to_string(), BTreeMap::new())]);
+    let mut expanded = BTreeSet::new();
+    while let Some((name, picks)) = queue.pop_front() {
+        let (value, choices) = generate(schema, &picks, true);
+        add(name, value);
+        for choice in choices {
+            if !expanded.insert(choice.schema_pointer.clone()) {
+                continue;
+            }
+            let location: Vec<&str> = choice.instance_path.split('/').filter(|segment| !segment.is_empty()).collect();
+            for (index, label) in choice.labels.iter().enumerate().skip(1) {
+                let mut variant = picks.clone();
+                variant.insert(choice.schema_pointer.clone(), index);
+                let name = slug(&location.iter().copied().chain([label.as_str()]).collect::<Vec<_>>().join("-"));
+                queue.push_back((if name.is_empty() { format!("option-{}", index + 1) } else { name }, variant));
+            }
+        }
+    }
+    fixtures
+}
+
+/// Splits `fixtures` into those `schema` accepts and those it rejects, with the first error.
+pub fn check_fixtures(schema: &JsonValue, display: &str, fixtures: Vec<Fixture>) -> Result<(Vec<Fixture>, Vec<Rejected>), AppError> {
+    let validator = compile_schema(schema, display)?;
+    let mut accepted = Vec::new();
+    let mut rejected = Vec::new();
+    for fixture in fixtures {
+        match validator.validate(&fixture.value) {
+            Ok(()) => accepted.push(fixture),
+            Err(error) => {
+                let at = error.instance_path.to_string();
+                let at = if at.is_empty() { "the root".to_string() } else { at };
+                rejected.push(Rejected { name: fixture.name, reason: format!("{} at {}", error, at) });
+            }
+        }
+    }
+    Ok((accepted, rejected))
+}
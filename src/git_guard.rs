@@ -0,0 +1,32 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+// Whether git reports uncommitted changes to `path`: modified, staged or untracked.
+// `None` when the file is not in a git work tree (or git is not installed)
+fn is_dirty(path: &Path) -> Option<bool> {
+    let dir = path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["status", "--porcelain", "--"])
+        .arg(path.file_name()?)
+        .output()
+        .ok()?;
+    output.status.success().then_some(!output.stdout.is_empty())
+}
+
+/// The files among `paths` with uncommitted changes, which an in-place rewrite could clobber
+/// beyond recovery. Files outside a git work tree are not guarded.
+pub fn dirty_files(paths: &[PathBuf]) -> Vec<PathBuf> {
+    paths.iter().filter(|path| is_dirty(path) == Some(true)).cloned().collect()
+}
+
+/// Message refusing to rewrite `dirty` files, naming them and the way past the guard.
+pub fn refusal(dirty: &[PathBuf]) -> String {
+    let mut message = String::from("Refusing to modify files with uncommitted changes:\n");
+    for path in dirty {
+        message.push_str(&format!("  {}\n", path.display()));
+    }
+    message.push_str("Commit or stash them first, preview with --diff, or pass --allow-dirty");
+    message
+}
@@ -0,0 +1,75 @@
+use std::path::PathBuf;
+use miette::{LabeledSpan, SourceSpan};
+
+use crate::error::{AppError, RelatedErrors, SchemaValidationError};
+
+// What makes two errors in one file the same problem: schema errors of one kind at the
+// same instance (e.g. both halves of a required pair are reported on their object), or
+// semantic errors from one check about the same reference
+#[derive(PartialEq, Eq)]
+struct GroupKey {
+    path: PathBuf,
+    cause: String,
+}
+
+fn group_key(error: &AppError) -> Option<GroupKey> {
+    match error {
+        AppError::SchemaValidationError(error) => {
+            let SchemaValidationError { path, instance_path, kind, .. } = &**error;
+            let kind = kind.split([' ', '{', '(']).next().unwrap_or_default();
+            let at = if instance_path.is_empty() { "/" } else { instance_path };
+            Some(GroupKey { path: path.clone(), cause: format!("{} at {}", kind, at) })
+        }
+        AppError::SemanticError { path, check, span, source_code, .. } => {
+            let reference = source_code.get(span.offset()..span.offset() + span.len()).unwrap_or_default();
+            Some(GroupKey { path: path.clone(), cause: format!("{} `{}`", check, reference) })
+        }
+        _ => None,
+    }
+}
+
+fn label(error: &AppError) -> Option<(String, SourceSpan, &str)> {
+    match error {
+        AppError::SchemaValidationError(error) => Some((error.label_message.clone(), error.error_span, &error.source_code)),
+        AppError::SemanticError { message, span, source_code, .. } => Some((message.clone(), *span, source_code)),
+        _ => None,
+    }
+}
+
+/// Folds errors that stem from one problem into a single `RelatedErrors` report with a label
+/// per occurrence, for human-readable output. The group takes the place of its first member;
+/// everything else is returned as it is.
+pub fn group_related(errors: Vec<AppError>) -> Vec<AppError> {
+    let mut groups: Vec<(Option<GroupKey>, Vec<AppError>)> = Vec::new();
+    for error in errors {
+        let key = group_key(&error);
+        match key.as_ref().and_then(|k| groups.iter().position(|(g, _)| g.as_ref() == Some(k))) {
+            Some(index) => groups[index].1.push(error),
+            None => groups.push((key, vec![error])),
+        }
+    }
+    groups
+        .into_iter()
+        .map(|(key, mut members)| match key {
+            Some(key) if members.len() > 1 => related_errors(key, members),
+            _ => members.remove(0),
+        })
+        .collect()
+}
+
+fn related_errors(key: GroupKey, members: Vec<AppError>) -> AppError {
+    let labels = members
+        .iter()
+        .filter_map(label)
+        .map(|(message, span, _)| LabeledSpan::new_with_span(Some(message), span))
+        .collect();
+    let source_code = members.iter().find_map(label).map(|(_, _, source)| source.to_string()).unwrap_or_default();
+    AppError::RelatedErrors(Box::new(RelatedErrors {
+        path: key.path,
+        message: key.cause,
+        count: members.len(),
+        labels,
+        source_code,
+        members,
+    }))
+}
@@ -0,0 +1,82 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use serde_json::{Map, Value as JsonValue};
+
+/// Block types whose labels the JSON syntax writes as nested objects, with their label count.
+/// Native HCL parses to the same nesting, so only these levels need normalizing.
+const LABELED_BLOCKS: &[(&str, usize)] = &[
+    ("resource", 2),
+    ("data", 2),
+    ("module", 1),
+    ("provider", 1),
+    ("variable", 1),
+    ("output", 1),
+    ("check", 1),
+];
+
+/// Whether `path` uses the JSON syntax of HCL (`main.tf.json`, `app.hcl.json`).
+pub fn is_hcl_json(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.ends_with(".tf.json") || name.ends_with(".hcl.json"))
+}
+
+/// Rewrites a document in HCL's JSON syntax into the shape native HCL parses to, so both
+/// validate against one schema: `"//"` comment properties are dropped, block types and labels
+/// written as arrays of objects are merged, and a single block body is an object rather than
+/// a one-item array.
+pub fn normalize(value: JsonValue) -> JsonValue {
+    match strip_comments(value) {
+        JsonValue::Object(map) => map
+            .into_iter()
+            .map(|(key, value)| {
+                let value = match LABELED_BLOCKS.iter().find(|(block, _)| *block == key) {
+                    Some((_, labels)) => merge_labels(value, *labels),
+                    None => value,
+                };
+                (key, value)
+            })
+            .collect(),
+        other => other,
+    }
+}
+
+fn strip_comments(value: JsonValue) -> JsonValue {
+    match value {
+        JsonValue::Object(map) => map.into_iter().filter(|(key, _)| key != "//").map(|(key, value)| (key, strip_comments(value))).collect(),
+        JsonValue::Array(items) => items.into_iter().map(strip_comments).collect(),
+        other => other,
+    }
+}
+
+// Merges `depth` levels of labels (each an object, or an array of objects) and flattens the
+// bodies below them: one body stays an object, several become an array, as for native HCL
+fn merge_labels(value: JsonValue, depth: usize) -> JsonValue {
+    let items = match value {
+        JsonValue::Array(items) => items,
+        other => vec![other],
+    };
+    if depth == 0 {
+        let mut bodies = items;
+        return if bodies.len() == 1 { bodies.remove(0) } else { JsonValue::Array(bodies) };
+    }
+    // Anything but objects at a label level is not a block; leave it for the schema to report
+    if !items.iter().all(JsonValue::is_object) {
+        return if items.len() == 1 { items.into_iter().next().unwrap_or_default() } else { JsonValue::Array(items) };
+    }
+    let mut grouped: BTreeMap<String, Vec<JsonValue>> = BTreeMap::new();
+    for (label, child) in items.into_iter().flat_map(|item| match item {
+        JsonValue::Object(map) => map,
+        _ => Map::new(),
+    }) {
+        let children = grouped.entry(label).or_default();
+        match child {
+            JsonValue::Array(nested) => children.extend(nested),
+            other => children.push(other),
+        }
+    }
+    grouped
+        .into_iter()
+        .map(|(label, children)| (label, merge_labels(JsonValue::Array(children), depth - 1)))
+        .collect()
+}
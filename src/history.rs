@@ -0,0 +1,185 @@
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::error::AppError;
+use crate::progress;
+
+/// Which revisions `check` validates instead of the working tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum History {
+    /// `--at REV`: the inputs as they were at one revision
+    At(String),
+    /// `--since REV`: that revision, then every later commit of the current branch that
+    /// changed an input
+    Since(String),
+}
+
+impl History {
+    fn revision(&self) -> &str {
+        match self {
+            History::At(rev) | History::Since(rev) => rev,
+        }
+    }
+}
+
+/// A commit the inputs are read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Revision {
+    pub commit: String,
+    /// How diagnostics name it, as `name:path`: the revision as given to `--at`, the short
+    /// hash for `--since`
+    pub name: String,
+    /// Commit date, `YYYY-MM-DD`
+    pub date: String,
+    pub subject: String,
+}
+
+// Runs git in `dir`, returning its stdout
+fn git(dir: &Path, args: &[&str]) -> Result<Vec<u8>, String> {
+    let output = Command::new("git").arg("-C").arg(dir).args(args).output().map_err(|e| format!("Cannot run git: {}", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(output.stdout)
+}
+
+const LOG_FORMAT: &str = "--format=%H%x09%h%x09%cs%x09%s";
+
+fn parse_log(output: &[u8]) -> Vec<Revision> {
+    String::from_utf8_lossy(output)
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(4, '\t');
+            let (commit, short, date) = (fields.next()?, fields.next()?, fields.next()?);
+            let subject = fields.next().unwrap_or_default();
+            Some(Revision { commit: commit.to_string(), name: short.to_string(), date: date.to_string(), subject: subject.to_string() })
+        })
+        .collect()
+}
+
+/// The commit `rev` names in the repository at `dir`, named by its short hash.
+pub fn resolve(dir: &Path, rev: &str) -> Result<Revision, String> {
+    let commit = format!("{}^{{commit}}", rev);
+    let output = git(dir, &["log", "-1", LOG_FORMAT, &commit, "--"]).map_err(|_| format!("{:?} is not a git revision", rev))?;
+    parse_log(&output).into_iter().next().ok_or_else(|| format!("{:?} is not a git revision", rev))
+}
+
+/// The revisions `--since rev` visits, oldest first: `rev` itself, then each commit after it
+/// in the history of HEAD that changed one of `inputs`.
+pub fn revisions_since(dir: &Path, rev: &str, inputs: &[PathBuf]) -> Result<Vec<Revision>, String> {
+    let first = resolve(dir, rev)?;
+    let range = format!("{}..HEAD", first.commit);
+    let mut args = vec!["log", "--reverse", LOG_FORMAT, range.as_str(), "--"];
+    let paths: Vec<String> = inputs.iter().map(|input| pathspec(dir, input)).collect();
+    args.extend(paths.iter().map(String::as_str));
+    let later = parse_log(&git(dir, &args)?);
+    Ok(std::iter::once(first).chain(later).collect())
+}
+
+// `input` relative to `dir`, without `./` noise, with forward slashes as git writes them
+fn pathspec(dir: &Path, input: &Path) -> String {
+    let absolute_dir = fs::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf());
+    let relative = input.strip_prefix(&absolute_dir).unwrap_or(input);
+    let relative: PathBuf = relative.components().filter(|c| !matches!(c, Component::CurDir)).collect();
+    let spec = relative.to_string_lossy().replace('\\', "/");
+    if spec.is_empty() { ".".to_string() } else { spec }
+}
+
+// Numbers the scratch trees of this process, so concurrent snapshots do not share one
+static SNAPSHOTS: AtomicUsize = AtomicUsize::new(0);
+
+/// The inputs as they were at one revision, copied out of the object store into a scratch
+/// tree with the same layout; the working tree is not touched. Removed on drop.
+#[derive(Debug)]
+pub struct Snapshot {
+    pub root: PathBuf,
+    pub revision: Revision,
+    /// Counterparts of the input files that existed at the revision, directories expanded,
+    /// for validating
+    pub inputs: Vec<PathBuf>,
+}
+
+impl Snapshot {
+    pub fn create(dir: &Path, revision: &Revision, inputs: &[PathBuf]) -> Result<Self, String> {
+        let unique = SNAPSHOTS.fetch_add(1, Ordering::Relaxed);
+        let root = std::env::temp_dir().join(format!("toml-and-jerry-history-{}-{}", std::process::id(), unique));
+        let _ = fs::remove_dir_all(&root);
+        let mut snapshot = Snapshot { root, revision: revision.clone(), inputs: Vec::new() };
+        for input in inputs {
+            let spec = pathspec(dir, input);
+            let listing = git(dir, &["ls-tree", "-r", "-z", "--name-only", &revision.commit, "--", &spec])?;
+            let files: Vec<String> = String::from_utf8_lossy(&listing).split('\0').filter(|f| !f.is_empty()).map(str::to_string).collect();
+            // A directory input is expanded to the files it held, as the validator takes files
+            for file in &files {
+                let content = git(dir, &["cat-file", "blob", &format!("{}:./{}", revision.commit, file)])?;
+                let copy = snapshot.root.join(file);
+                crate::paths::write_with_parents(&copy, &content).map_err(|e| format!("Cannot write {}: {}", copy.display(), e))?;
+                snapshot.inputs.push(copy);
+            }
+        }
+        Ok(snapshot)
+    }
+
+    /// `errors` with the scratch paths they name replaced by `name:path`, the way `git show`
+    /// spells a file at a revision.
+    pub fn relocate(&self, mut errors: Vec<AppError>) -> Vec<AppError> {
+        let relocate = |path: &Path| match path.strip_prefix(&self.root) {
+            Ok(relative) => PathBuf::from(format!("{}:{}", self.revision.name, relative.to_string_lossy().replace('\\', "/"))),
+            Err(_) => path.to_path_buf(),
+        };
+        for error in &mut errors {
+            error.map_paths(&relocate);
+        }
+        errors
+    }
+}
+
+impl Drop for Snapshot {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
+
+// One `--since` line: the revision and its counts, and whether it changed the outcome
+fn summary(revision: &Revision, errors: &[AppError], was_valid: Option<bool>) -> String {
+    let warnings = errors.iter().filter(|e| e.is_warning()).count();
+    let failing = errors.len() - warnings;
+    let change = match (was_valid, failing == 0) {
+        (Some(true), false) => " (became invalid)",
+        (Some(false), true) => " (became valid)",
+        _ => "",
+    };
+    format!("{} {} {}: {} error(s), {} warning(s){}", revision.name, revision.date, revision.subject, failing, warnings, change)
+}
+
+/// Runs `validate` on the inputs as they were at the `history` revisions, or on the working
+/// tree without one. Diagnostics name files as `revision:path`; `--since` also prints a line
+/// per revision, marking those that made the inputs invalid or valid again. `dir` is where
+/// git runs, and the inputs are relative to it.
+pub fn validate_history(
+    dir: &Path,
+    inputs: &[PathBuf],
+    history: Option<&History>,
+    validate: impl Fn(Vec<PathBuf>) -> Result<Vec<AppError>, AppError>,
+) -> Result<Vec<AppError>, AppError> {
+    let Some(history) = history else { return validate(inputs.to_vec()) };
+    let error = |message: String| AppError::HistoryError { revision: history.revision().to_string(), message };
+    let revisions = match history {
+        History::At(rev) => vec![Revision { name: rev.clone(), ..resolve(dir, rev).map_err(error)? }],
+        History::Since(rev) => revisions_since(dir, rev, inputs).map_err(error)?,
+    };
+    let mut collected = Vec::new();
+    let mut was_valid = None;
+    for revision in &revisions {
+        let snapshot = Snapshot::create(dir, revision, inputs).map_err(error)?;
+        let errors = if snapshot.inputs.is_empty() { Vec::new() } else { snapshot.relocate(validate(snapshot.inputs.clone())?) };
+        if let History::Since(_) = history {
+            progress::line(summary(revision, &errors, was_valid));
+            was_valid = Some(errors.iter().all(AppError::is_warning));
+        }
+        collected.extend(errors);
+    }
+    Ok(collected)
+}
@@ -0,0 +1,335 @@
+use std::collections::HashMap;
+use std::ops::Range;
+use serde_json::{Map, Value as JsonValue};
+
+/// A `.hjson` file read as the JSON it stands for.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HjsonDocument {
+    pub value: JsonValue,
+    // Byte range of the key (or, for array items, the value) behind each JSON pointer
+    spans: HashMap<String, Range<usize>>,
+}
+
+/// Why a `.hjson` file does not parse, and the byte range at fault.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HjsonError {
+    pub message: String,
+    pub span: Range<usize>,
+}
+
+// Characters that cannot start a key or a quoteless string
+const PUNCTUATORS: &[char] = &[',', ':', '[', ']', '{', '}'];
+
+fn escape_pointer_segment(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+// `true`, `false`, `null` or a JSON number; anything else on the line makes it a string
+fn literal(token: &str) -> Option<JsonValue> {
+    match token {
+        "true" => Some(JsonValue::Bool(true)),
+        "false" => Some(JsonValue::Bool(false)),
+        "null" => Some(JsonValue::Null),
+        _ if token.starts_with(|c: char| c == '-' || c.is_ascii_digit()) => {
+            serde_json::from_str::<serde_json::Number>(token).ok().map(JsonValue::Number)
+        }
+        _ => None,
+    }
+}
+
+struct Parser<'a> {
+    src: &'a str,
+    pos: usize,
+    spans: HashMap<String, Range<usize>>,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.src[self.pos..].chars().next()
+    }
+
+    fn rest(&self) -> &str {
+        &self.src[self.pos..]
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn error(&self, message: impl Into<String>, start: usize) -> HjsonError {
+        let end = self.pos.max(start + 1).min(self.src.len());
+        HjsonError { message: message.into(), span: start.min(end)..end }
+    }
+
+    // Whitespace, newlines and `#`, `//` and `/* */` comments
+    fn skip_space(&mut self) -> Result<(), HjsonError> {
+        loop {
+            let rest = self.rest();
+            if rest.starts_with('#') || rest.starts_with("//") {
+                self.pos += rest.find('\n').unwrap_or(rest.len());
+            } else if let Some(comment) = rest.strip_prefix("/*") {
+                match comment.find("*/") {
+                    Some(end) => self.pos += end + 4,
+                    None => return Err(self.error("unclosed `/*` comment", self.pos)),
+                }
+            } else if self.peek().is_some_and(char::is_whitespace) {
+                self.bump();
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    // A `"..."` or `'...'` string with JSON escapes
+    fn quoted(&mut self) -> Result<String, HjsonError> {
+        let start = self.pos;
+        let quote = self.bump();
+        let mut out = String::new();
+        loop {
+            match self.bump() {
+                None | Some('\n') => return Err(self.error("unclosed string", start)),
+                Some(c) if Some(c) == quote => return Ok(out),
+                Some('\\') => {
+                    let escape = self.pos - 1;
+                    match self.bump() {
+                        Some('n') => out.push('\n'),
+                        Some('r') => out.push('\r'),
+                        Some('t') => out.push('\t'),
+                        Some('b') => out.push('\u{8}'),
+                        Some('f') => out.push('\u{c}'),
+                        Some(c @ ('\\' | '/' | '"' | '\'')) => out.push(c),
+                        Some('u') => {
+                            let decoded = self.unicode_escape().ok_or_else(|| self.error("malformed `\\u` escape", escape))?;
+                            out.push(decoded);
+                        }
+                        _ => return Err(self.error("unknown escape in string", escape)),
+                    }
+                }
+                Some(c) => out.push(c),
+            }
+        }
+    }
+
+    // The four hex digits after `\u`, and a low surrogate's when they are a high one
+    fn unicode_escape(&mut self) -> Option<char> {
+        let hex = |parser: &mut Self| {
+            let digits = parser.rest().get(..4)?;
+            let unit = u32::from_str_radix(digits, 16).ok()?;
+            parser.pos += 4;
+            Some(unit)
+        };
+        let high = hex(self)?;
+        if !(0xD800..0xDC00).contains(&high) {
+            return char::from_u32(high);
+        }
+        if !self.rest().starts_with("\\u") {
+            return None;
+        }
+        self.pos += 2;
+        let low = hex(self)?;
+        char::from_u32(0x10000 + ((high - 0xD800) << 10) + low.checked_sub(0xDC00)?)
+    }
+
+    // `'''` strings: the text up to the closing `'''`, with the indentation of the opening
+    // quotes removed from each line and a leading and trailing line break dropped
+    fn multiline(&mut self) -> Result<String, HjsonError> {
+        let start = self.pos;
+        let line_start = self.src[..start].rfind('\n').map_or(0, |i| i + 1);
+        let indent = self.src[line_start..start].chars().count();
+        self.pos += 3;
+        while self.peek().is_some_and(|c| c.is_whitespace() && c != '\n') {
+            self.bump();
+        }
+        let mut out = String::new();
+        let mut at_line_start = self.peek() == Some('\n');
+        if at_line_start {
+            self.bump();
+        }
+        loop {
+            if at_line_start {
+                for _ in 0..indent {
+                    if !self.peek().is_some_and(|c| c == ' ' || c == '\t') {
+                        break;
+                    }
+                    self.bump();
+                }
+                at_line_start = false;
+            }
+            if self.rest().starts_with("'''") {
+                self.pos += 3;
+                if out.ends_with('\n') {
+                    out.pop();
+                }
+                return Ok(out);
+            }
+            match self.bump() {
+                None => return Err(self.error("unclosed `'''` string", start)),
+                Some('\r') => {}
+                Some('\n') => {
+                    out.push('\n');
+                    at_line_start = true;
+                }
+                Some(c) => out.push(c),
+            }
+        }
+    }
+
+    // A value without quotes: a literal when one is all there is before `,`, `]`, `}`, a
+    // comment or the end of the line; otherwise the rest of the line, as a string
+    fn quoteless(&mut self) -> JsonValue {
+        let rest = &self.src[self.pos..];
+        let line = &rest[..rest.find(['\n', '\r']).unwrap_or(rest.len())];
+        let mut token_end = line.find([',', ']', '}', '#']).unwrap_or(line.len());
+        for comment in ["//", "/*"] {
+            token_end = token_end.min(line.find(comment).unwrap_or(line.len()));
+        }
+        let token = line[..token_end].trim_end();
+        if let Some(value) = literal(token) {
+            self.pos += token.len();
+            return value;
+        }
+        let text = line.trim_end();
+        self.pos += text.len();
+        JsonValue::String(text.to_string())
+    }
+
+    fn key(&mut self) -> Result<String, HjsonError> {
+        let start = self.pos;
+        if matches!(self.peek(), Some('"' | '\'')) {
+            return self.quoted();
+        }
+        while self.peek().is_some_and(|c| !c.is_whitespace() && !PUNCTUATORS.contains(&c)) {
+            self.bump();
+        }
+        if self.pos == start {
+            let found = self.peek().map_or("end of file".to_string(), |c| format!("`{}`", c));
+            return Err(self.error(format!("expected a key, found {}", found), start));
+        }
+        Ok(self.src[start..self.pos].to_string())
+    }
+
+    // Members up to the closing `}`, or to the end of the file for a root without braces
+    fn members(&mut self, pointer: &str, braced: Option<usize>) -> Result<JsonValue, HjsonError> {
+        let mut object = Map::new();
+        loop {
+            self.skip_space()?;
+            match self.peek() {
+                None => {
+                    return match braced {
+                        Some(open) => Err(self.error("unclosed `{`", open)),
+                        None => Ok(JsonValue::Object(object)),
+                    };
+                }
+                Some('}') if braced.is_some() => {
+                    self.bump();
+                    return Ok(JsonValue::Object(object));
+                }
+                _ => {}
+            }
+            let key_start = self.pos;
+            let key = self.key()?;
+            let key_span = key_start..self.pos;
+            self.skip_space()?;
+            if self.bump() != Some(':') {
+                return Err(self.error(format!("expected `:` after key `{}`", key), key_start));
+            }
+            if object.contains_key(&key) {
+                return Err(HjsonError { message: format!("key `{}` is set twice", key), span: key_span });
+            }
+            let member_pointer = format!("{}/{}", pointer, escape_pointer_segment(&key));
+            self.skip_space()?;
+            let value = self.value(&member_pointer)?;
+            self.spans.insert(member_pointer, key_span);
+            object.insert(key, value);
+            self.skip_space()?;
+            if self.peek() == Some(',') {
+                self.bump();
+            }
+        }
+    }
+
+    fn items(&mut self, pointer: &str) -> Result<JsonValue, HjsonError> {
+        let open = self.pos;
+        self.bump();
+        let mut items = Vec::new();
+        loop {
+            self.skip_space()?;
+            match self.peek() {
+                None => return Err(self.error("unclosed `[`", open)),
+                Some(']') => {
+                    self.bump();
+                    return Ok(JsonValue::Array(items));
+                }
+                _ => {}
+            }
+            let item_pointer = format!("{}/{}", pointer, items.len());
+            let start = self.pos;
+            let value = self.value(&item_pointer)?;
+            self.spans.insert(item_pointer, start..self.pos);
+            items.push(value);
+            self.skip_space()?;
+            if self.peek() == Some(',') {
+                self.bump();
+            }
+        }
+    }
+
+    fn value(&mut self, pointer: &str) -> Result<JsonValue, HjsonError> {
+        match self.peek() {
+            Some('{') => {
+                let open = self.pos;
+                self.bump();
+                self.members(pointer, Some(open))
+            }
+            Some('[') => self.items(pointer),
+            Some('\'') if self.rest().starts_with("'''") => self.multiline().map(JsonValue::String),
+            Some('"' | '\'') => self.quoted().map(JsonValue::String),
+            Some(c) if PUNCTUATORS.contains(&c) => Err(self.error(format!("expected a value, found `{}`", c), self.pos)),
+            Some(_) => Ok(self.quoteless()),
+            None => Err(self.error("expected a value, found end of file", self.pos)),
+        }
+    }
+
+    // Whether the root is an object written without braces: it starts with `key:`
+    fn braceless_root(&mut self) -> bool {
+        let start = self.pos;
+        let is_member = self.key().is_ok() && self.skip_space().is_ok() && self.peek() == Some(':');
+        self.pos = start;
+        is_member
+    }
+}
+
+/// Parses an Hjson document: JSON plus `#`, `//` and `/* */` comments, unquoted keys, optional
+/// commas, quoteless and `'''` multiline strings, and an optional pair of braces around the
+/// root object. A key set twice in one object is an error.
+pub fn parse_hjson(content: &str) -> Result<HjsonDocument, HjsonError> {
+    let mut parser = Parser { src: content, pos: 0, spans: HashMap::new() };
+    parser.skip_space()?;
+    let value = match parser.peek() {
+        None => JsonValue::Object(Map::new()),
+        Some('{' | '[') => parser.value("")?,
+        Some(_) if parser.braceless_root() => parser.members("", None)?,
+        Some(_) => parser.value("")?,
+    };
+    parser.skip_space()?;
+    if parser.peek().is_some() {
+        return Err(parser.error("unexpected content after the root value", parser.pos));
+    }
+    Ok(HjsonDocument { value, spans: parser.spans })
+}
+
+impl HjsonDocument {
+    /// Byte range of the key or array item behind `pointer`, walking up to the closest
+    /// recorded ancestor.
+    pub fn span_for_pointer(&self, pointer: &str) -> Option<Range<usize>> {
+        let mut candidate = pointer;
+        loop {
+            if let Some(range) = self.spans.get(candidate) {
+                return Some(range.clone());
+            }
+            candidate = &candidate[..candidate.rfind('/')?];
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use crate::error::{AppError, Severity};
+use crate::grouping;
+use crate::merge;
+use crate::validation::PrintableError;
+
+/// How the human report arranges diagnostics (`--group-by`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GroupBy {
+    /// Under a header per file, each ending with the file's pass/fail line
+    #[default]
+    File,
+    /// One entry per distinct violation, listing every file it occurs in
+    Rule,
+}
+
+impl GroupBy {
+    /// Parses `--group-by`: `file` or `rule`.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "file" => Some(GroupBy::File),
+            "rule" => Some(GroupBy::Rule),
+            _ => None,
+        }
+    }
+}
+
+// Errors, and the warnings and info diagnostics that do not fail the run
+fn counts(errors: &[AppError]) -> (usize, usize) {
+    let failing = errors.iter().filter(|e| e.level() == Severity::Error).count();
+    (failing, errors.len() - failing)
+}
+
+fn tally(errors: usize, warnings: usize) -> String {
+    match (errors, warnings) {
+        (0, w) => format!("{} warning(s)", w),
+        (e, 0) => format!("{} error(s)", e),
+        (e, w) => format!("{} error(s), {} warning(s)", e, w),
+    }
+}
+
+/// The run's diagnostics as miette reports under a header per file, in the order files were
+/// first reported. Each file ends with `FAIL` when it has errors and `PASS` when it only has
+/// warnings.
+pub fn render_by_file(errors: Vec<AppError>) -> String {
+    let mut files: Vec<(String, Vec<AppError>)> = Vec::new();
+    for error in errors {
+        let file = PrintableError::from(&error).file_path;
+        match files.iter_mut().find(|(f, _)| *f == file) {
+            Some((_, members)) => members.push(error),
+            None => files.push((file, vec![error])),
+        }
+    }
+    let mut out = String::new();
+    for (file, members) in files {
+        let (errors, warnings) = counts(&members);
+        out.push_str(&format!("==> {} <==\n", file));
+        for error in grouping::group_related(members) {
+            out.push_str(&format!("{:?}\n", miette::Report::new(error)));
+        }
+        let verdict = if errors > 0 { "FAIL" } else { "PASS" };
+        out.push_str(&format!("{} {}: {}\n\n", verdict, file, tally(errors, warnings)));
+    }
+    out
+}
+
+// The diagnostic's message without the file it was found in, so the same violation in two
+// files reads the same
+fn file_independent_message(diagnostic: &PrintableError) -> String {
+    let quoted = format!("{:?}", Path::new(&diagnostic.file_path));
+    diagnostic
+        .message
+        .replace(&format!(" in file {}", quoted), "")
+        .replace(&format!("File {} ", quoted), "File ")
+}
+
+// Rule, instance path and message of a violation, leaving out the file
+type ClusterKey = (String, Option<String>, String);
+
+/// One entry per distinct violation (rule, instance path and message), most frequent first,
+/// with the `file:line:column` of every occurrence. Meant for runs over many files that
+/// repeat the same mistakes.
+pub fn render_by_rule(errors: &[AppError]) -> String {
+    let mut clusters: Vec<(ClusterKey, Vec<merge::ReportEntry>)> = Vec::new();
+    for error in errors {
+        let entry = merge::display_entry(error);
+        let key = (entry.diagnostic.rule_id.clone(), entry.diagnostic.json_path.clone(), file_independent_message(&entry.diagnostic));
+        match clusters.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, entries)) => entries.push(entry),
+            None => clusters.push((key, vec![entry])),
+        }
+    }
+    // Stable, so equally frequent violations keep the order they were found in
+    clusters.sort_by_key(|(_, entries)| std::cmp::Reverse(entries.len()));
+
+    let mut out = String::new();
+    for ((rule, json_path, message), entries) in clusters {
+        let at = json_path.filter(|p| !p.is_empty()).map(|p| format!(" (at {})", p)).unwrap_or_default();
+        out.push_str(&format!("{} [{}] x{}{}\n  {}\n", entries[0].level, rule, entries.len(), at, message));
+        for entry in entries {
+            let d = &entry.diagnostic;
+            match (d.line, d.column) {
+                (Some(line), Some(column)) => out.push_str(&format!("    {}:{}:{}\n", d.file_path, line, column)),
+                (Some(line), None) => out.push_str(&format!("    {}:{}\n", d.file_path, line)),
+                _ => out.push_str(&format!("    {}\n", d.file_path)),
+            }
+        }
+        out.push('\n');
+    }
+    let files: BTreeSet<String> = errors.iter().map(|e| PrintableError::from(e).file_path).collect();
+    let (errors, warnings) = counts(errors);
+    out.push_str(&format!("{} in {} file(s)\n", tally(errors, warnings), files.len()));
+    out
+}
@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+use std::ops::Range;
+use serde_json::{Map, Value as JsonValue};
+
+/// Which INI values become something other than strings (`--ini-coerce`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct IniCoercion {
+    /// `true`/`false`, `yes`/`no` and `on`/`off` (any case) become booleans
+    pub booleans: bool,
+    /// Values that read as integers or decimals become numbers
+    pub numbers: bool,
+}
+
+impl IniCoercion {
+    /// Parses the `--ini-coerce` values: `booleans`, `numbers`.
+    pub fn parse(kinds: &[String]) -> Result<Self, String> {
+        let mut coercion = IniCoercion::default();
+        for kind in kinds {
+            match kind.as_str() {
+                "booleans" => coercion.booleans = true,
+                "numbers" => coercion.numbers = true,
+                other => return Err(format!("Unknown --ini-coerce {:?}; expected booleans or numbers", other)),
+            }
+        }
+        Ok(coercion)
+    }
+
+    pub(crate) fn value(self, text: &str) -> JsonValue {
+        if self.booleans {
+            match text.to_ascii_lowercase().as_str() {
+                "true" | "yes" | "on" => return JsonValue::Bool(true),
+                "false" | "no" | "off" => return JsonValue::Bool(false),
+                _ => {}
+            }
+        }
+        if self.numbers {
+            if let Ok(integer) = text.parse::<i64>() {
+                return JsonValue::from(integer);
+            }
+            if let Some(number) = text.parse::<f64>().ok().filter(|n| n.is_finite()).and_then(serde_json::Number::from_f64) {
+                return JsonValue::Number(number);
+            }
+        }
+        JsonValue::String(text.to_string())
+    }
+}
+
+/// An INI file as schemas see it: keys before the first section at the top level, and each
+/// `[section]` an object of its keys. Values are strings unless coerced.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IniDocument {
+    pub value: JsonValue,
+    // Byte range of the key (or section header) behind each JSON pointer
+    spans: HashMap<String, Range<usize>>,
+}
+
+/// Why an INI file does not parse, and the byte range of the offending line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IniError {
+    pub message: String,
+    pub span: Range<usize>,
+}
+
+fn escape_pointer_segment(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+// A value without its surrounding quotes, if it has a matching pair
+fn unquote(value: &str) -> &str {
+    for quote in ['"', '\''] {
+        if let Some(inner) = value.strip_prefix(quote).and_then(|rest| rest.strip_suffix(quote)) {
+            return inner;
+        }
+    }
+    value
+}
+
+/// Parses `content`. Lines starting with `;` or `#` are comments; keys are separated from
+/// values by `=` or `:`, and a section may be repeated to add keys to it. A key set twice in
+/// one section is an error, as is a line that is neither a section, a key nor a comment.
+pub fn parse_ini(content: &str, coercion: IniCoercion) -> Result<IniDocument, IniError> {
+    let mut root = Map::new();
+    let mut spans = HashMap::new();
+    let mut section: Option<String> = None;
+    let mut offset = 0;
+    for line in content.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let text = line.trim_end_matches(['\n', '\r']);
+        let trimmed = text.trim();
+        let indent = text.len() - text.trim_start().len();
+        let line_span = start + indent..start + indent + trimmed.len();
+        if trimmed.is_empty() || trimmed.starts_with([';', '#']) {
+            continue;
+        }
+        if let Some(header) = trimmed.strip_prefix('[') {
+            let name = header
+                .strip_suffix(']')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .ok_or_else(|| IniError { message: format!("expected `[section]`, found `{}`", trimmed), span: line_span.clone() })?;
+            let pointer = format!("/{}", escape_pointer_segment(name));
+            match root.entry(name.to_string()).or_insert_with(|| JsonValue::Object(Map::new())) {
+                JsonValue::Object(_) => {}
+                _ => return Err(IniError { message: format!("section `{}` has the name of a key set above it", name), span: line_span }),
+            }
+            spans.entry(pointer).or_insert(line_span);
+            section = Some(name.to_string());
+            continue;
+        }
+        let Some(separator) = trimmed.find(['=', ':']) else {
+            return Err(IniError { message: format!("expected `key = value` or `[section]`, found `{}`", trimmed), span: line_span });
+        };
+        let key = trimmed[..separator].trim_end();
+        if key.is_empty() {
+            return Err(IniError { message: "key is missing before the separator".to_string(), span: line_span });
+        }
+        let value = coercion.value(unquote(trimmed[separator + 1..].trim()));
+        let (object, pointer) = match &section {
+            Some(name) => (
+                root.get_mut(name).and_then(JsonValue::as_object_mut).expect("sections are objects"),
+                format!("/{}/{}", escape_pointer_segment(name), escape_pointer_segment(key)),
+            ),
+            None => (&mut root, format!("/{}", escape_pointer_segment(key))),
+        };
+        if object.insert(key.to_string(), value).is_some() {
+            return Err(IniError { message: format!("key `{}` is set twice", key), span: line_span });
+        }
+        spans.insert(pointer, line_span.start..line_span.start + key.len());
+    }
+    Ok(IniDocument { value: JsonValue::Object(root), spans })
+}
+
+impl IniDocument {
+    /// Byte range of the key or section header behind `pointer`, walking up to the closest
+    /// recorded ancestor.
+    pub fn span_for_pointer(&self, pointer: &str) -> Option<Range<usize>> {
+        let mut candidate = pointer;
+        loop {
+            if let Some(range) = self.spans.get(candidate) {
+                return Some(range.clone());
+            }
+            candidate = &candidate[..candidate.rfind('/')?];
+        }
+    }
+}
@@ -0,0 +1,488 @@
+use std::collections::HashMap;
+use std::ops::Range;
+use serde_json::{Map, Value as JsonValue};
+
+/// Key that holds the arguments of a node that also has properties or children.
+pub const ARGS_KEY: &str = "$args";
+
+/// A `.kdl` file as schemas see it: an object with a key per node name. A node with only
+/// arguments is its argument (or an array of them), a node with properties or children is an
+/// object of both, with any arguments under `$args`, and a node with neither is `{}`. Nodes
+/// that share a name at one level become an array.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KdlDocument {
+    pub value: JsonValue,
+    // Byte range of the node name or property key behind each JSON pointer
+    spans: HashMap<String, Range<usize>>,
+}
+
+/// Why a `.kdl` file does not parse, and the byte range at fault.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KdlError {
+    pub message: String,
+    pub span: Range<usize>,
+}
+
+struct Node {
+    name: String,
+    name_span: Range<usize>,
+    args: Vec<JsonValue>,
+    props: Vec<(String, JsonValue, Range<usize>)>,
+    children: Option<Vec<Node>>,
+}
+
+// A value or name as written: quoted and raw strings, `#` keywords, or a bare word that is a
+// number, a v1 keyword (`true`, `null`) or an identifier
+enum Atom {
+    Quoted(String),
+    Keyword(JsonValue),
+    Bare(String),
+}
+
+// Characters that end a bare word
+fn is_delimiter(c: char) -> bool {
+    c.is_whitespace() || "\\/(){}<>;[]=,\"".contains(c)
+}
+
+fn is_newline(c: char) -> bool {
+    matches!(c, '\n' | '\r' | '\u{85}' | '\u{c}' | '\u{2028}' | '\u{2029}')
+}
+
+fn escape_pointer_segment(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+// Decimal, hexadecimal (`0x`), octal (`0o`) and binary (`0b`) numbers, with `_` separators
+fn parse_number(word: &str) -> Option<JsonValue> {
+    let digits = word.replace('_', "");
+    let (negative, unsigned) = match digits.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, digits.strip_prefix('+').unwrap_or(&digits)),
+    };
+    let radix = match unsigned.get(..2) {
+        Some("0x") => Some(16),
+        Some("0o") => Some(8),
+        Some("0b") => Some(2),
+        _ => None,
+    };
+    if let Some(radix) = radix {
+        let magnitude = i64::from_str_radix(&unsigned[2..], radix).ok()?;
+        return Some(JsonValue::from(if negative { -magnitude } else { magnitude }));
+    }
+    if !unsigned.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    if let Ok(integer) = digits.parse::<i64>() {
+        return Some(JsonValue::from(integer));
+    }
+    let float = digits.parse::<f64>().ok().filter(|f| f.is_finite())?;
+    serde_json::Number::from_f64(float).map(JsonValue::Number)
+}
+
+// An argument, or a property with its key and the key's span
+type Entry = (Option<(String, Range<usize>)>, JsonValue);
+
+struct Parser<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.src[self.pos..].chars().next()
+    }
+
+    fn rest(&self) -> &str {
+        &self.src[self.pos..]
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn eat(&mut self, text: &str) -> bool {
+        let found = self.rest().starts_with(text);
+        if found {
+            self.pos += text.len();
+        }
+        found
+    }
+
+    fn error(&self, message: impl Into<String>, start: usize) -> KdlError {
+        let end = self.pos.max(start + 1).min(self.src.len());
+        KdlError { message: message.into(), span: start.min(end)..end }
+    }
+
+    fn skip_to_line_end(&mut self) {
+        while self.peek().is_some_and(|c| !is_newline(c)) {
+            self.bump();
+        }
+    }
+
+    // `/* ... */`, which nests
+    fn skip_block_comment(&mut self) -> Result<(), KdlError> {
+        let start = self.pos;
+        self.pos += 2;
+        let mut depth = 1;
+        while depth > 0 {
+            if self.eat("/*") {
+                depth += 1;
+            } else if self.eat("*/") {
+                depth -= 1;
+            } else if self.bump().is_none() {
+                return Err(self.error("unclosed `/*` comment", start));
+            }
+        }
+        Ok(())
+    }
+
+    // Whitespace, block comments and `\` line continuations within a node; true if any
+    fn skip_node_space(&mut self) -> Result<bool, KdlError> {
+        let start = self.pos;
+        loop {
+            match self.peek() {
+                Some(c) if c.is_whitespace() && !is_newline(c) => {
+                    self.bump();
+                }
+                Some('\u{feff}') => {
+                    self.bump();
+                }
+                Some('/') if self.rest().starts_with("/*") => self.skip_block_comment()?,
+                Some('\\') => {
+                    let escape = self.pos;
+                    self.bump();
+                    while self.peek().is_some_and(|c| c.is_whitespace() && !is_newline(c)) {
+                        self.bump();
+                    }
+                    if self.rest().starts_with("//") {
+                        self.skip_to_line_end();
+                    }
+                    if !self.eat("\r\n") && !self.peek().is_some_and(is_newline) {
+                        return Err(self.error("`\\` continues a node only at the end of a line", escape));
+                    }
+                    if self.peek().is_some_and(is_newline) {
+                        self.bump();
+                    }
+                }
+                _ => return Ok(self.pos > start),
+            }
+        }
+    }
+
+    // Whitespace, newlines and comments between nodes
+    fn skip_line_space(&mut self) -> Result<(), KdlError> {
+        loop {
+            match self.peek() {
+                Some(c) if c.is_whitespace() || c == '\u{feff}' => {
+                    self.bump();
+                }
+                Some('/') if self.rest().starts_with("//") => self.skip_to_line_end(),
+                Some('/') if self.rest().starts_with("/*") => self.skip_block_comment()?,
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    // A `(type)` annotation; types are not checked, only skipped
+    fn skip_annotation(&mut self) -> Result<(), KdlError> {
+        if self.peek() != Some('(') {
+            return Ok(());
+        }
+        let start = self.pos;
+        match self.rest().find(')') {
+            Some(close) => {
+                self.pos += close + 1;
+                Ok(())
+            }
+            None => Err(self.error("unclosed `(` type annotation", start)),
+        }
+    }
+
+    fn quoted(&mut self) -> Result<String, KdlError> {
+        let start = self.pos;
+        self.bump();
+        let mut out = String::new();
+        loop {
+            match self.bump() {
+                None => return Err(self.error("unclosed string", start)),
+                Some('"') => return Ok(out),
+                Some('\\') => match self.bump() {
+                    Some('n') => out.push('\n'),
+                    Some('r') => out.push('\r'),
+                    Some('t') => out.push('\t'),
+                    Some('b') => out.push('\u{8}'),
+                    Some('f') => out.push('\u{c}'),
+                    Some('s') => out.push(' '),
+                    Some(c @ ('\\' | '"' | '/')) => out.push(c),
+                    Some('u') if self.eat("{") => {
+                        let escape = self.pos;
+                        let hex: String = std::iter::from_fn(|| self.bump()).take_while(|&c| c != '}').collect();
+                        match u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                            Some(decoded) => out.push(decoded),
+                            None => return Err(self.error(format!("malformed `\\u{{{}}}` escape", hex), escape)),
+                        }
+                    }
+                    // An escaped run of whitespace is dropped
+                    Some(c) if c.is_whitespace() => {
+                        while self.peek().is_some_and(char::is_whitespace) {
+                            self.bump();
+                        }
+                    }
+                    _ => return Err(self.error("unknown escape in string", self.pos.saturating_sub(2))),
+                },
+                Some(c) => out.push(c),
+            }
+        }
+    }
+
+    // `r#"..."#` (KDL 1) or `#"..."#` (KDL 2), with any number of hashes
+    fn raw(&mut self) -> Result<String, KdlError> {
+        let start = self.pos;
+        self.eat("r");
+        let hashes = self.rest().chars().take_while(|&c| c == '#').count();
+        self.pos += hashes;
+        if !self.eat("\"") {
+            return Err(self.error("malformed raw string", start));
+        }
+        let close = format!("\"{}", "#".repeat(hashes));
+        match self.rest().find(&close) {
+            Some(end) => {
+                let text = self.rest()[..end].to_string();
+                self.pos += end + close.len();
+                Ok(text)
+            }
+            None => Err(self.error("unclosed raw string", start)),
+        }
+    }
+
+    fn atom(&mut self) -> Result<Atom, KdlError> {
+        let start = self.pos;
+        let rest = self.rest();
+        if rest.starts_with('"') {
+            return self.quoted().map(Atom::Quoted);
+        }
+        let raw = rest.strip_prefix('r').unwrap_or(rest);
+        if raw.trim_start_matches('#').starts_with('"') && (raw.len() < rest.len() || rest.starts_with('#')) {
+            return self.raw().map(Atom::Quoted);
+        }
+        let hashed = self.eat("#");
+        let word_start = self.pos;
+        while self.peek().is_some_and(|c| !is_delimiter(c)) {
+            self.bump();
+        }
+        let word = &self.src[word_start..self.pos];
+        if hashed {
+            return match word {
+                "true" => Ok(Atom::Keyword(JsonValue::Bool(true))),
+                "false" => Ok(Atom::Keyword(JsonValue::Bool(false))),
+                "null" => Ok(Atom::Keyword(JsonValue::Null)),
+                "inf" | "-inf" | "nan" => Err(self.error(format!("`#{}` has no JSON value", word), start)),
+                _ => Err(self.error(format!("unknown keyword `#{}`", word), start)),
+            };
+        }
+        if word.is_empty() {
+            let found = self.peek().map_or("end of file".to_string(), |c| format!("`{}`", c));
+            return Err(self.error(format!("expected a value, found {}", found), start));
+        }
+        Ok(Atom::Bare(word.to_string()))
+    }
+
+    fn value(&mut self, atom: Atom, start: usize) -> Result<JsonValue, KdlError> {
+        match atom {
+            Atom::Quoted(text) => Ok(JsonValue::String(text)),
+            Atom::Keyword(value) => Ok(value),
+            Atom::Bare(word) => match word.as_str() {
+                "true" => Ok(JsonValue::Bool(true)),
+                "false" => Ok(JsonValue::Bool(false)),
+                "null" => Ok(JsonValue::Null),
+                _ if word.trim_start_matches(['+', '-']).starts_with(|c: char| c.is_ascii_digit()) => {
+                    parse_number(&word).ok_or_else(|| self.error(format!("malformed number `{}`", word), start))
+                }
+                // KDL 2 reads other bare identifiers as strings
+                _ => Ok(JsonValue::String(word)),
+            },
+        }
+    }
+
+    fn node(&mut self) -> Result<Node, KdlError> {
+        self.skip_annotation()?;
+        let name_start = self.pos;
+        let name = match self.atom()? {
+            Atom::Quoted(name) => name,
+            Atom::Bare(word) if parse_number(&word).is_none() => word,
+            _ => return Err(self.error("expected a node name", name_start)),
+        };
+        let mut node = Node { name, name_span: name_start..self.pos, args: Vec::new(), props: Vec::new(), children: None };
+        loop {
+            let spaced = self.skip_node_space()?;
+            match self.peek() {
+                None | Some('}') => return Ok(node),
+                Some(';') => {
+                    self.bump();
+                    return Ok(node);
+                }
+                Some(c) if is_newline(c) => return Ok(node),
+                Some('/') if self.rest().starts_with("//") => {
+                    self.skip_to_line_end();
+                    return Ok(node);
+                }
+                Some(_) if node.children.is_some() => return Err(self.error("expected the node to end after its children", self.pos)),
+                Some('{') => node.children = Some(self.block()?),
+                Some(_) if self.rest().starts_with("/-") => {
+                    // Slashdash: the next argument, property or children block is commented out
+                    self.pos += 2;
+                    self.skip_node_space()?;
+                    if self.peek() == Some('{') {
+                        self.block()?;
+                    } else {
+                        self.entry()?;
+                    }
+                }
+                Some(_) if !spaced => return Err(self.error("expected whitespace before the next entry", self.pos)),
+                Some(_) => match self.entry()? {
+                    (Some((key, span)), value) => node.props.push((key, value, span)),
+                    (None, value) => node.args.push(value),
+                },
+            }
+        }
+    }
+
+    fn entry(&mut self) -> Result<Entry, KdlError> {
+        self.skip_annotation()?;
+        let start = self.pos;
+        let atom = self.atom()?;
+        let key_span = start..self.pos;
+        if self.peek() == Some('=') {
+            let key = match atom {
+                Atom::Quoted(key) => key,
+                Atom::Bare(word) if parse_number(&word).is_none() => word,
+                _ => return Err(self.error("expected a property name before `=`", start)),
+            };
+            self.bump();
+            self.skip_annotation()?;
+            let value_start = self.pos;
+            let atom = self.atom()?;
+            return Ok((Some((key, key_span)), self.value(atom, value_start)?));
+        }
+        Ok((None, self.value(atom, start)?))
+    }
+
+    fn block(&mut self) -> Result<Vec<Node>, KdlError> {
+        let start = self.pos;
+        self.bump();
+        let nodes = self.nodes(true)?;
+        if !self.eat("}") {
+            return Err(self.error("unclosed `{`", start));
+        }
+        Ok(nodes)
+    }
+
+    fn nodes(&mut self, in_block: bool) -> Result<Vec<Node>, KdlError> {
+        let mut nodes = Vec::new();
+        loop {
+            self.skip_line_space()?;
+            match self.peek() {
+                None => return Ok(nodes),
+                Some('}') if in_block => return Ok(nodes),
+                Some('}') => return Err(self.error("unexpected `}`", self.pos)),
+                Some(';') => {
+                    self.bump();
+                }
+                Some(_) => {
+                    let slashdash = self.eat("/-");
+                    if slashdash {
+                        self.skip_line_space()?;
+                    }
+                    let node = self.node()?;
+                    if !slashdash {
+                        nodes.push(node);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn node_value(node: Node, pointer: &str, spans: &mut HashMap<String, Range<usize>>) -> Result<JsonValue, KdlError> {
+    if node.props.is_empty() && node.children.is_none() {
+        let mut args = node.args;
+        return Ok(match args.len() {
+            0 => JsonValue::Object(Map::new()),
+            1 => args.remove(0),
+            _ => JsonValue::Array(args),
+        });
+    }
+    let mut object = match node.children {
+        Some(children) => nodes_to_object(children, pointer, spans)?,
+        None => Map::new(),
+    };
+    let child_names: Vec<String> = object.keys().cloned().collect();
+    for (key, value, span) in node.props {
+        if child_names.contains(&key) {
+            return Err(KdlError { message: format!("`{}` is both a property and a child node of `{}`", key, node.name), span });
+        }
+        // The rightmost of repeated properties wins, as in KDL
+        spans.insert(format!("{}/{}", pointer, escape_pointer_segment(&key)), span);
+        object.insert(key, value);
+    }
+    if !node.args.is_empty() {
+        spans.insert(format!("{}/{}", pointer, ARGS_KEY), node.name_span);
+        object.insert(ARGS_KEY.to_string(), JsonValue::Array(node.args));
+    }
+    Ok(JsonValue::Object(object))
+}
+
+fn nodes_to_object(nodes: Vec<Node>, pointer: &str, spans: &mut HashMap<String, Range<usize>>) -> Result<Map<String, JsonValue>, KdlError> {
+    let mut groups: Vec<(String, Vec<Node>)> = Vec::new();
+    for node in nodes {
+        match groups.iter_mut().find(|(name, _)| *name == node.name) {
+            Some((_, group)) => group.push(node),
+            None => groups.push((node.name.clone(), vec![node])),
+        }
+    }
+    let mut object = Map::new();
+    for (name, mut group) in groups {
+        let node_pointer = format!("{}/{}", pointer, escape_pointer_segment(&name));
+        spans.insert(node_pointer.clone(), group[0].name_span.clone());
+        let value = if group.len() == 1 {
+            node_value(group.remove(0), &node_pointer, spans)?
+        } else {
+            let mut items = Vec::with_capacity(group.len());
+            for (index, node) in group.into_iter().enumerate() {
+                let item_pointer = format!("{}/{}", node_pointer, index);
+                spans.insert(item_pointer.clone(), node.name_span.clone());
+                items.push(node_value(node, &item_pointer, spans)?);
+            }
+            JsonValue::Array(items)
+        };
+        object.insert(name, value);
+    }
+    Ok(object)
+}
+
+/// Parses a KDL document (version 1, and the version 2 `#true`/`#null` keywords, `#"raw"#`
+/// strings and bare identifier strings). Type annotations are skipped, `/-` comments out the
+/// next node or entry, and infinities and NaN, which JSON cannot hold, are errors.
+pub fn parse_kdl(content: &str) -> Result<KdlDocument, KdlError> {
+    let mut parser = Parser { src: content, pos: 0 };
+    let nodes = parser.nodes(false)?;
+    let mut spans = HashMap::new();
+    let value = JsonValue::Object(nodes_to_object(nodes, "", &mut spans)?);
+    Ok(KdlDocument { value, spans })
+}
+
+impl KdlDocument {
+    /// Byte range of the node or property behind `pointer`, walking up to the closest
+    /// recorded ancestor.
+    pub fn span_for_pointer(&self, pointer: &str) -> Option<Range<usize>> {
+        let mut candidate = pointer;
+        loop {
+            if let Some(range) = self.spans.get(candidate) {
+                return Some(range.clone());
+            }
+            candidate = &candidate[..candidate.rfind('/')?];
+        }
+    }
+}
@@ -1,3 +1,84 @@
+pub mod ansible;
+pub mod arm;
+pub mod capabilities;
+pub mod ci;
+pub mod color;
+pub mod cloudformation;
+pub mod coercion;
+pub mod config_merge;
+pub mod consistency;
+pub mod containerfile;
+pub mod debug_bundle;
+pub mod descriptions;
+pub mod diagnostic;
+pub mod diff_base;
+pub mod duplicates;
+pub mod embedded;
+pub mod enum_map;
+pub mod envdoc;
 pub mod error;
+pub mod evaluator;
+pub mod expect_invalid;
+pub mod fallback;
+pub mod fix;
+pub mod fixtures;
+pub mod git_guard;
+pub mod history;
+pub mod grouping;
+pub mod hcl_json;
+pub mod hjson;
+pub mod human_report;
+pub mod ini;
+pub mod kdl;
+pub mod limits;
+pub mod merge;
+pub mod meta_check;
+pub mod ndjson;
+pub mod newlines;
+pub mod normalize;
+pub mod notify;
+pub mod openapi;
+pub mod parsers;
+pub mod paths;
+pub mod patch;
+pub mod presets;
+pub mod progress;
+pub mod project_config;
+pub mod properties;
+pub mod proto;
+pub mod provenance;
+pub mod quick;
+pub mod ratchet;
+pub mod renames;
+pub mod repl;
+pub mod report_hooks;
+pub mod report_schema;
+pub mod report_stream;
+pub mod required_files;
+pub mod result_log;
+pub mod rules_pack;
+pub mod run_record;
+pub mod scaffold;
 pub mod schema;
-pub mod validation; 
\ No newline at end of file
+pub mod schema_comment;
+pub mod schema_examples;
+pub mod schema_extract;
+pub mod schema_keys;
+pub mod schema_patterns;
+pub mod schema_unused;
+pub mod schema_usage;
+pub mod severity;
+pub mod shard;
+pub mod signing;
+pub mod source_map;
+pub mod stamp;
+pub mod status;
+pub mod summary;
+pub mod suppressions;
+pub mod template;
+pub mod triage;
+pub mod validation;
+pub mod versions;
+pub mod violation;
+pub mod worker;
+pub mod yaml;
\ No newline at end of file
@@ -0,0 +1,87 @@
+use serde_json::Value as JsonValue;
+
+/// Size and complexity limits that keep configs reviewable. `None` disables a limit.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Limits {
+    /// Deepest allowed nesting of objects/arrays; the document itself is depth 1
+    pub max_depth: Option<usize>,
+    /// Most keys allowed in one object
+    pub max_keys: Option<usize>,
+    /// Most lines allowed in one file
+    pub max_lines: Option<usize>,
+    /// Most items allowed in one array
+    pub max_array_len: Option<usize>,
+}
+
+/// One exceeded limit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LimitViolation {
+    /// `max-depth`, `max-keys`, `max-lines` or `max-array-len`
+    pub rule: &'static str,
+    /// JSON pointer of the offending object or array (empty for `max-lines`)
+    pub pointer: String,
+    pub message: String,
+}
+
+impl Limits {
+    /// Whether every limit is disabled.
+    pub fn is_empty(&self) -> bool {
+        *self == Limits::default()
+    }
+
+    /// `max-lines`: checked on the raw file, once per file.
+    pub fn check_lines(&self, content: &str) -> Option<LimitViolation> {
+        let max = self.max_lines?;
+        let lines = content.lines().count();
+        (lines > max).then(|| LimitViolation {
+            rule: "max-lines",
+            pointer: String::new(),
+            message: format!("file has {} lines (limit {})", lines, max),
+        })
+    }
+
+    /// `max-depth`, `max-keys` and `max-array-len` on a parsed document. Depth is reported
+    /// once per branch, at the first container past the limit.
+    pub fn check_instance(&self, instance: &JsonValue) -> Vec<LimitViolation> {
+        let mut violations = Vec::new();
+        self.walk(instance, "", 1, &mut violations);
+        violations
+    }
+
+    fn walk(&self, value: &JsonValue, pointer: &str, depth: usize, violations: &mut Vec<LimitViolation>) {
+        let children: Vec<(String, &JsonValue)> = match value {
+            JsonValue::Object(map) => {
+                if let Some(max) = self.max_keys.filter(|max| map.len() > *max) {
+                    violations.push(LimitViolation {
+                        rule: "max-keys",
+                        pointer: pointer.to_string(),
+                        message: format!("object has {} keys (limit {})", map.len(), max),
+                    });
+                }
+                map.iter().map(|(k, v)| (k.replace('~', "~0").replace('/', "~1"), v)).collect()
+            }
+            JsonValue::Array(items) => {
+                if let Some(max) = self.max_array_len.filter(|max| items.len() > *max) {
+                    violations.push(LimitViolation {
+                        rule: "max-array-len",
+                        pointer: pointer.to_string(),
+                        message: format!("array has {} items (limit {})", items.len(), max),
+                    });
+                }
+                items.iter().enumerate().map(|(i, v)| (i.to_string(), v)).collect()
+            }
+            _ => return,
+        };
+        if let Some(max) = self.max_depth.filter(|max| depth > *max) {
+            violations.push(LimitViolation {
+                rule: "max-depth",
+                pointer: pointer.to_string(),
+                message: format!("nesting depth {} exceeds the limit of {}", depth, max),
+            });
+            return;
+        }
+        for (segment, child) in children {
+            self.walk(child, &format!("{}/{}", pointer, segment), depth + 1, violations);
+        }
+    }
+}
@@ -1,18 +1,91 @@
-use clap::{Parser, Subcommand};
+use clap::{Args, CommandFactory, Parser, Subcommand};
 use miette::Result;
 use std::path::PathBuf;
-// SARIF imports temporarily disabled - will re-implement later
-// use serde_sarif::sarif::{
-//     ArtifactLocation, Location, Message, PhysicalLocation,
-//     Region, Result as SarifResult, Run, Sarif, Tool,
-//     ToolComponent,
-// };
 
+mod ansible;
+mod arm;
+mod capabilities;
+mod ci;
+mod color;
+mod cloudformation;
+mod coercion;
+mod config_merge;
+mod consistency;
+mod containerfile;
+mod debug_bundle;
+mod descriptions;
+mod diff_base;
+mod duplicates;
+mod embedded;
+mod enum_map;
+mod envdoc;
 mod error;
+mod evaluator;
+mod expect_invalid;
+mod fallback;
+mod fix;
+mod fixtures;
+mod git_guard;
+mod history;
+mod grouping;
+mod hcl_json;
+mod hjson;
+mod human_report;
+mod ini;
+mod kdl;
+mod limits;
+mod merge;
+mod meta_check;
+mod ndjson;
+mod newlines;
+mod normalize;
+mod notify;
+mod openapi;
+mod parsers;
+mod paths;
+mod patch;
+mod presets;
+mod progress;
+mod project_config;
+mod properties;
+mod proto;
+mod provenance;
+mod quick;
+mod ratchet;
+mod renames;
+mod repl;
+mod report_hooks;
+mod report_schema;
+mod report_stream;
+mod required_files;
+mod result_log;
+mod rules_pack;
+mod run_record;
+mod scaffold;
 mod schema;
-use schema::load_and_compile_schema;
+mod schema_comment;
+mod schema_examples;
+mod schema_extract;
+mod schema_keys;
+mod schema_patterns;
+mod schema_unused;
+mod schema_usage;
+mod severity;
+mod shard;
+mod signing;
+mod source_map;
+mod stamp;
+mod status;
+mod summary;
+mod suppressions;
+mod template;
+mod triage;
 mod validation;
-use validation::validate_inputs;
+mod versions;
+mod violation;
+mod worker;
+mod yaml;
+use validation::{validate_inputs_with_options, ValidationOptions};
 use error::AppError;
 
 #[derive(Parser)]
@@ -22,148 +95,2670 @@ use error::AppError;
     about = "Validate JSON/TOML/YAML/HCL configs against a JSON Schema or OpenAPI component schema"
 )]
 struct Cli {
+    /// Serve Bazel/Buck persistent worker requests on stdin/stdout (each request is a `check` command line)
+    #[arg(long = "persistent_worker")]
+    persistent_worker: bool,
+
+    /// Repeat a run recorded with `check --record-run`, or replay a `check --debug-bundle`
+    #[arg(long, value_name = "RUN")]
+    replay: Option<PathBuf>,
+
+    /// Guarantee no filesystem writes (no caches, baselines, reports or fixes) for hermetic
+    /// sandboxes; anything that would need to write fails instead
+    #[arg(long, global = true)]
+    no_write: bool,
+
+    /// Do not run the project config's `[report.hooks]` commands (implied by --no-write)
+    #[arg(long, global = true)]
+    no_hooks: bool,
+
+    /// Color diagnostics: auto | always | never. `auto` honours NO_COLOR and CLICOLOR_FORCE
+    #[arg(long, global = true, default_value = "auto", value_parser = ["auto", "always", "never"])]
+    color: String,
+
     #[command(subcommand)]
-    cmd: Cmd,
+    cmd: Option<Cmd>,
 }
 
 #[derive(Subcommand)]
 enum Cmd {
     /// Validate config files against a schema
-    Check {
-        /// Path(s) or glob
+    Check(Box<CheckArgs>),
+
+    /// Generate a starter JSON Schema from Rust types
+    Scaffold {
+        /// Path to a Rust crate exposing config structs
+        #[arg(default_value = ".")]
+        crate_path: PathBuf,
+
+        /// File to write the generated schema to
+        #[arg(long)]
+        out: PathBuf,
+
+        /// The struct or enum the config file deserializes into
+        #[arg(long, default_value = "Config")]
+        root: String,
+
+        /// Compare the generated schema with --out instead of writing it, failing when they
+        /// differ beyond titles, descriptions, examples and defaults
+        #[arg(long)]
+        check: bool,
+    },
+
+    /// Check a report file against its detached signature from `check --sign-report`
+    VerifyReport {
+        /// Report file written with --output
+        report: PathBuf,
+
+        /// Ed25519 public key (SPKI PEM) matching the signing key
+        #[arg(long)]
+        key: PathBuf,
+
+        /// Signature file; defaults to <report>.sig
+        #[arg(long)]
+        signature: Option<PathBuf>,
+    },
+
+    /// List supported input/output formats, schema drafts and diagnostic codes
+    Capabilities {
+        /// Output format: human | json
+        #[arg(long, default_value = "human")]
+        format: String,
+    },
+
+    /// Find identical or near-identical config blocks repeated across files
+    Duplicates {
+        /// Files or directories to scan
         #[arg(required = true)]
         inputs: Vec<PathBuf>,
 
-        /// JSON Schema file (local or URL) or OpenAPI spec
-        #[arg(short, long)]
-        schema: PathBuf,
+        /// Minimum similarity (0.0-1.0) of two blocks' values to group them
+        #[arg(long, default_value_t = 0.8)]
+        similarity: f64,
 
-        /// Output format: human | json | sarif
+        /// Ignore blocks with fewer leaf values than this
+        #[arg(long, default_value_t = 5)]
+        min_size: usize,
+
+        /// Only report blocks repeated in at least this many files
+        #[arg(long, default_value_t = 2)]
+        min_files: usize,
+
+        /// Output format: human | json
         #[arg(long, default_value = "human")]
         format: String,
     },
 
-    /// Generate a starter JSON Schema from Rust types
-    Scaffold {
-        /// Path to a Rust crate exposing config structs
-        #[arg(default_value = ".")]
-        crate_path: PathBuf,
+    /// Deep-merge config layers (base first) into one document: objects merge key by key,
+    /// other values are replaced by later layers
+    Merge {
+        /// Layers in any format `check` reads, lowest precedence first
+        #[arg(required = true)]
+        layers: Vec<PathBuf>,
 
-        /// File to write the generated schema to
+        /// last-wins | error-on-conflict (report paths later layers set to a different value)
+        #[arg(long, default_value = "last-wins", value_parser = ["last-wins", "error-on-conflict"])]
+        strategy: String,
+
+        /// Write the merged config to this file (json, or yaml/toml by extension) instead of stdout
+        #[arg(long, value_name = "FILE")]
+        out: Option<PathBuf>,
+    },
+
+    /// Combine json/sarif reports from several runs (schemas, shards, machines) into one
+    MergeReports {
+        /// Reports written by `check --format json|sarif`
+        #[arg(required = true)]
+        reports: Vec<PathBuf>,
+
+        /// Output format: json | sarif | csv
+        #[arg(long, default_value = "json")]
+        format: String,
+
+        /// Write the merged report to this file instead of stdout
+        #[arg(long, value_name = "FILE")]
+        output: Option<PathBuf>,
+
+        /// Remove this prefix from report paths, e.g. a CI machine's checkout directory
+        /// (repeatable; the current directory is always removed)
+        #[arg(long, value_name = "PREFIX")]
+        strip_prefix: Vec<String>,
+    },
+
+    /// Print a json/yaml/toml file in canonical form (sorted keys, one style per scalar) for
+    /// diffing and hashing; comments and key order are not kept
+    Normalize {
+        /// File to normalize (json, yaml or toml)
+        input: PathBuf,
+
+        /// Output format (default: the input's)
+        #[arg(long, value_parser = ["json", "yaml", "toml"])]
+        format: Option<String>,
+
+        /// Write the canonical form to this file instead of stdout
+        #[arg(long, value_name = "FILE")]
+        out: Option<PathBuf>,
+    },
+
+    /// Print a YAML/TOML example config with every key documented by its schema description
+    Render {
+        /// Example config (yaml or toml)
+        input: PathBuf,
+
+        /// JSON Schema file (local or URL)
+        #[arg(short, long)]
+        schema: PathBuf,
+
+        /// Write each key's description as the comment above it; without this flag the key
+        /// comments are removed
         #[arg(long)]
+        commented: bool,
+
+        /// Write the rendered example to this file instead of stdout
+        #[arg(long, value_name = "FILE")]
+        out: Option<PathBuf>,
+    },
+
+    /// Write one valid example file per schema branch (each enum value, each oneOf/anyOf arm)
+    /// for use as test fixtures
+    Fixtures {
+        /// JSON Schema file (local or URL)
+        #[arg(short, long)]
+        schema: PathBuf,
+
+        /// Directory to write the fixtures to
+        #[arg(long, value_name = "DIR")]
         out: PathBuf,
+
+        /// Format of the fixture files
+        #[arg(long, default_value = "json", value_parser = ["json", "yaml", "toml"])]
+        format: String,
+    },
+
+    /// Validate snippets typed or pasted at a prompt, query schema paths and try `:set`
+    /// tweaks, without writing files
+    Repl {
+        /// JSON Schema file (local or URL)
+        #[arg(short, long)]
+        schema: PathBuf,
+
+        /// Format of the snippets until `:format` changes it
+        #[arg(long, default_value = "yaml", value_parser = ["yaml", "json", "toml", "hcl"])]
+        format: String,
+    },
+
+    /// Scan a repository: file types, presets that apply, pass/fail counts, and a suggested
+    /// .toml-and-jerry.toml plus error baseline
+    Triage {
+        /// Directory to scan
+        #[arg(default_value = ".")]
+        root: PathBuf,
+
+        /// Output format: human | json
+        #[arg(long, default_value = "human")]
+        format: String,
+
+        /// Write the suggested .toml-and-jerry.toml and toml-and-jerry-baseline.json into this
+        /// directory (existing files are not overwritten)
+        #[arg(long, value_name = "DIR")]
+        write: Option<PathBuf>,
+    },
+
+    /// Inspect a schema
+    Schema {
+        #[command(subcommand)]
+        action: SchemaCmd,
+    },
+
+    /// Print the JSON Schema of the --format json report, for tools that consume reports
+    SchemaOfReport {
+        /// Write the schema to this file instead of stdout
+        #[arg(long, value_name = "FILE")]
+        out: Option<PathBuf>,
+    },
+
+    /// Inspect or clear cached state (downloaded schemas and rules packs)
+    Cache {
+        #[command(subcommand)]
+        action: CacheCmd,
+    },
+}
+
+#[derive(Args)]
+struct CheckArgs {
+    /// Path(s) or glob
+    #[arg(required = true)]
+    inputs: Vec<PathBuf>,
+
+    /// JSON Schema file (local or URL) or OpenAPI spec
+    #[arg(short, long, required_unless_present_any = ["openapi", "proto_descriptor", "docker_compose", "containerfile", "preset", "arm_template", "rules_pack", "schema_comments"])]
+    schema: Option<PathBuf>,
+
+    /// Output format: human | json | ndjson | sarif | csv | compact | codeclimate | azure | template
+    #[arg(long, default_value = "human")]
+    format: String,
+
+    /// Line per diagnostic for --format template, e.g. '{{file}}:{{line}} {{code}} {{message}}'.
+    /// Fields: file, line, column, code, type, message, path, severity; `{{field|json}}` and
+    /// `{{field|csv}}` quote the value
+    #[arg(long, value_name = "TEMPLATE")]
+    template: Option<String>,
+
+    /// Arrangement of the human report: file (a section per file with its pass/fail line) or
+    /// rule (each distinct violation once, with every file it occurs in)
+    #[arg(long, default_value = "file", value_parser = ["file", "rule"])]
+    group_by: String,
+
+    /// Schema to try when an input fails --schema, e.g. the previous version of a format
+    /// (repeatable, tried in order). Reports which schema matched, or the closest one's errors
+    #[arg(long, value_name = "SCHEMA", requires = "schema")]
+    schema_fallback: Vec<PathBuf>,
+
+    /// Validate each input against the schema named by its first-line `# tj-schema: <path>`
+    /// comment, without a fallback --schema. The comment is honoured without this flag too.
+    #[arg(long)]
+    schema_comments: bool,
+
+    /// Dialect for schemas without `$schema`: 4, 6, 7, 2019-09 or 2020-12 (default: the
+    /// project config's `default_draft`, else 2020-12). A warning names each assumed dialect
+    #[arg(long, value_name = "DRAFT")]
+    default_draft: Option<String>,
+
+    /// Check only the top level: the root's type, its required keys and the types of its
+    /// properties. Nested objects and keywords such as pattern, format and bounds are skipped,
+    /// for feedback on every keystroke; run without it for full validation
+    #[arg(long)]
+    quick: bool,
+
+    /// Backtracking steps a schema `pattern` may take on one value; a value that needs more
+    /// fails validation, so a catastrophic pattern cannot stall the run
+    #[arg(long, value_name = "STEPS", default_value_t = schema::DEFAULT_REGEX_BACKTRACK_LIMIT)]
+    regex_backtrack_limit: usize,
+
+    /// OpenAPI document whose operation payload schema the inputs are validated against
+    #[arg(long, conflicts_with = "schema", requires = "operation")]
+    openapi: Option<PathBuf>,
+
+    /// Operation to select from --openapi, e.g. 'POST /users'
+    #[arg(long, requires = "openapi")]
+    operation: Option<String>,
+
+    /// Payload part: requestBody | response | response:<status>
+    #[arg(long, default_value = "requestBody", requires = "openapi")]
+    part: String,
+
+    /// Media type to select from the operation content (defaults to application/json)
+    #[arg(long, requires = "openapi")]
+    media_type: Option<String>,
+
+    /// Compiled protobuf descriptor set; inputs are checked against the proto3 JSON mapping
+    #[arg(long, conflicts_with_all = ["schema", "openapi"], requires = "message")]
+    proto_descriptor: Option<PathBuf>,
+
+    /// Fully-qualified message name from --proto-descriptor, e.g. pkg.Config
+    #[arg(long, requires = "proto_descriptor")]
+    message: Option<String>,
+
+    /// `cue` executable used to export .cue files (defaults to `cue` on PATH)
+    #[arg(long)]
+    cue_binary: Option<PathBuf>,
+
+    /// `pkl` executable used to evaluate .pkl files (defaults to `pkl` on PATH)
+    #[arg(long)]
+    pkl_binary: Option<PathBuf>,
+
+    /// `nickel` executable used to export .ncl files (defaults to `nickel` on PATH)
+    #[arg(long)]
+    nickel_binary: Option<PathBuf>,
+
+    /// Validate docker-compose files against the bundled Compose schema
+    #[arg(long, conflicts_with_all = ["schema", "openapi", "proto_descriptor", "containerfile"])]
+    docker_compose: bool,
+
+    /// Validate Dockerfile/Containerfile metadata (labels, env, args) against --schema,
+    /// or the bundled Containerfile schema when no schema is given
+    #[arg(long, conflicts_with_all = ["openapi", "proto_descriptor"])]
+    containerfile: bool,
+
+    /// Validate well-known files with bundled/catalog schemas and extra checks
+    /// (ci, ansible, cloudformation).
+    /// Inputs may be directories, which are searched for files the preset knows about.
+    #[arg(long, conflicts_with_all = ["schema", "openapi", "proto_descriptor", "docker_compose", "containerfile"])]
+    preset: Option<String>,
+
+    /// What to do with YAML tags that have no mapping: ignore | string | error
+    #[arg(long, default_value = "ignore")]
+    yaml_unknown_tags: String,
+
+    /// INI values to read as other types than strings: booleans, numbers (comma-separated)
+    #[arg(long, value_name = "TYPES", value_delimiter = ',', value_parser = ["booleans", "numbers"])]
+    ini_coerce: Vec<String>,
+
+    /// Read dotted .properties keys as nested objects: `server.port` becomes `/server/port`
+    #[arg(long)]
+    properties_nest: bool,
+
+    /// .properties values to read as other types than strings: booleans, numbers (comma-separated)
+    #[arg(long, value_name = "TYPES", value_delimiter = ',', value_parser = ["booleans", "numbers"])]
+    properties_coerce: Vec<String>,
+
+    /// Expand a YAML tag into a single-key object, e.g. '!Ref=Ref' (repeatable)
+    #[arg(long = "yaml-tag-map")]
+    yaml_tag_maps: Vec<String>,
+
+    /// Built-in set of YAML tag mappings: cloudformation
+    #[arg(long)]
+    yaml_tag_profile: Option<String>,
+
+    /// Warn about objects/arrays nested deeper than this (the document itself is depth 1)
+    #[arg(long, value_name = "N")]
+    max_depth: Option<usize>,
+
+    /// Warn about objects with more keys than this
+    #[arg(long, value_name = "N")]
+    max_keys: Option<usize>,
+
+    /// Warn about files longer than this many lines
+    #[arg(long, value_name = "N")]
+    max_lines: Option<usize>,
+
+    /// Warn about arrays with more items than this
+    #[arg(long, value_name = "N")]
+    max_array_len: Option<usize>,
+
+    /// Warn about lines that do not end with this line ending: lf, crlf, or consistent (what
+    /// most of the file's lines use). `--fix` converts them
+    #[arg(long, value_name = "STYLE", value_parser = ["lf", "crlf", "consistent"])]
+    line_endings: Option<String>,
+
+    /// Warn about files that do not end with a line break. `--fix` adds it
+    #[arg(long)]
+    final_newline: bool,
+
+    /// Validate a string that holds a document of another format against its own schema:
+    /// POINTER=FORMAT:SCHEMA, e.g. '/data/config.yaml=yaml:schemas/app.json'. FORMAT is
+    /// json, yaml or toml; a `*` pointer segment matches any key (repeatable)
+    #[arg(long = "embedded", value_name = "RULE")]
+    embedded: Vec<String>,
+
+    /// Apply automatic fixes (such as `x-renamed-from` renames) to the input files in place
+    #[arg(long)]
+    fix: bool,
+
+    /// Write or refresh a `# tj: validated against SCHEMA@DIGEST on DATE — PASS|FAIL` comment
+    /// at the top of each input (formats without comments, such as JSON, are skipped)
+    #[arg(long)]
+    stamp: bool,
+
+    /// Warn about inputs whose --stamp comment is stale: the file changed after the stamp's
+    /// date, or the stamp names a different schema digest
+    #[arg(long)]
+    check_stamps: bool,
+
+    /// Let --fix rewrite files that have uncommitted git changes
+    #[arg(long, requires = "fix")]
+    allow_dirty: bool,
+
+    /// Print the changes --fix would make as unified diffs instead of writing them
+    #[arg(long, visible_alias = "dry-run", requires = "fix")]
+    diff: bool,
+
+    /// Write the changes --fix would make to DIR as one `.patch` per file (mirroring its path)
+    /// for `git apply` or review bots, leaving the files themselves untouched
+    #[arg(long, value_name = "DIR", requires = "fix", conflicts_with_all = ["diff", "fix_check"])]
+    fix_out: Option<PathBuf>,
+
+    /// Fail if --fix would change any file, without writing (for CI)
+    #[arg(long = "check", requires = "fix")]
+    fix_check: bool,
+
+    /// Write a JSON summary of what --fix changed (files, keys, rules satisfied) to this file
+    #[arg(long, value_name = "FILE", requires = "fix")]
+    fix_summary: Option<PathBuf>,
+
+    /// Write a commit-message changelog of what --fix changed to this file ('-' for stdout)
+    #[arg(long, value_name = "FILE", requires = "fix")]
+    fix_changelog: Option<PathBuf>,
+
+    /// Enum values the schema renamed: 'POINTER: OLD=NEW[, OLD=NEW...]', e.g.
+    /// '/log/level: warn=warning'. Old values are warned about; --fix rewrites them (repeatable)
+    #[arg(long = "map-enum", value_name = "RULE")]
+    map_enum: Vec<String>,
+
+    /// TOML file of `[enum."POINTER"]` tables mapping old enum values to new ones, like --map-enum
+    #[arg(long, value_name = "FILE")]
+    enum_map: Option<PathBuf>,
+
+    /// Rules pack to validate with: a pack directory, a .tar.gz path or URL, or name@version
+    /// (installed, or fetched from --rules-registry). Inputs may be directories.
+    #[arg(long, value_name = "PACK", conflicts_with_all = ["schema", "openapi", "proto_descriptor", "docker_compose", "containerfile", "preset", "arm_template"])]
+    rules_pack: Option<String>,
+
+    /// Registry that name@version rules packs are fetched from
+    /// (default: $TOML_AND_JERRY_RULES_REGISTRY)
+    #[arg(long, value_name = "URL", requires = "rules_pack")]
+    rules_registry: Option<String>,
+
+    /// Append each file's outcome to this NDJSON log as soon as it is validated (synced to
+    /// disk), so an interrupted run can be picked up with --resume
+    #[arg(long, value_name = "FILE")]
+    result_log: Option<PathBuf>,
+
+    /// Skip inputs the --result-log records as passing, unchanged and checked against the
+    /// same schema
+    #[arg(long, requires = "result_log")]
+    resume: bool,
+
+    /// Record the effective command line, environment and schema/input digests to this file
+    /// so `--replay` can repeat the run
+    #[arg(long, value_name = "FILE")]
+    record_run: Option<PathBuf>,
+
+    /// When the run fails, pack the files with diagnostics, the schemas, the project config
+    /// and the JSON report into this .tgz, which `--replay` runs again anywhere
+    #[arg(long, value_name = "FILE")]
+    debug_bundle: Option<PathBuf>,
+
+    /// Replace the values of secret-looking keys (passwords, tokens, API keys) in the files
+    /// packed by --debug-bundle
+    #[arg(long, requires = "debug_bundle")]
+    debug_bundle_redact: bool,
+
+    /// Write a JSON summary (outcome, exit code, diagnostic counts) to this file, even on success
+    #[arg(long, value_name = "FILE")]
+    status_file: Option<PathBuf>,
+
+    /// Post a run summary and the top diagnostics to this Slack/Teams incoming webhook
+    #[arg(long, value_name = "URL")]
+    notify_webhook: Option<String>,
+
+    /// When to post to --notify-webhook: failure | always
+    #[arg(long, default_value = "failure", value_parser = ["failure", "always"], requires = "notify_webhook")]
+    notify_on: String,
+
+    /// Number of diagnostics listed in the --notify-webhook message
+    #[arg(long, default_value_t = 10, requires = "notify_webhook")]
+    notify_top: usize,
+
+    /// Write the json/sarif/csv/template report to this file instead of stdout
+    #[arg(long, visible_alias = "output-file", value_name = "FILE")]
+    output: Option<PathBuf>,
+
+    /// Sign the report written to --output with this Ed25519 private key (PKCS#8 PEM);
+    /// the detached signature goes to <output>.sig
+    #[arg(long, value_name = "KEY", requires = "output")]
+    sign_report: Option<PathBuf>,
+
+    /// Include run provenance (tool version, schema and input digests, timestamps) in json/sarif reports
+    #[arg(long)]
+    provenance: bool,
+
+    /// Also record this machine's hostname in the provenance
+    #[arg(long, requires = "provenance")]
+    provenance_hostname: bool,
+
+    /// Print end-of-run statistics (files scanned/skipped/passed/failed, errors per rule,
+    /// elapsed time), and add them to json/sarif reports as `summary`
+    #[arg(long)]
+    summary: bool,
+
+    /// Write an RFC 6902 patch per input file for the fixable violations to this path
+    /// ('-' for stdout) instead of changing the files
+    #[arg(long, value_name = "FILE", conflicts_with = "fix")]
+    emit_patch: Option<PathBuf>,
+
+    /// Suppressions file of `[[suppress]]` entries (path glob, codes, instance_path, reason,
+    /// expires); inline `toml-and-jerry: ignore[...]` comments are always honoured
+    #[arg(long, value_name = "FILE")]
+    suppressions: Option<PathBuf>,
+
+    /// Level for a rule: 'RULE=error|warning|info|off', where RULE is a diagnostic code, a
+    /// semantic check or a schema keyword, e.g. 'required=error'. Only errors fail the run;
+    /// overrides the project config's [severity] table (repeatable)
+    #[arg(long = "severity", value_name = "RULE=LEVEL")]
+    severity: Vec<String>,
+
+    /// The inputs are bad examples: each must fail validation, and the run fails for any that
+    /// validates (see also `expect_invalid` globs in .toml-and-jerry.toml)
+    #[arg(long)]
+    expect_invalid: bool,
+
+    /// Report only diagnostics the change introduced: validate the base version of changed
+    /// inputs too (from this directory, or this git revision) and drop diagnostics with the
+    /// same rule, file and instance path
+    #[arg(long, value_name = "REF_OR_DIR")]
+    diff_base: Option<String>,
+
+    /// Validate the inputs as they were at this git revision (tag, branch or commit), read
+    /// from the repository without checking it out; files are reported as `REV:path`
+    #[arg(long, value_name = "REV", conflicts_with_all = ["since", "diff_base", "fix", "stamp", "result_log"])]
+    at: Option<String>,
+
+    /// Validate the inputs at this git revision and at each later commit of the current
+    /// branch that changed them, printing which commits made them invalid
+    #[arg(long, value_name = "REV", conflicts_with_all = ["diff_base", "fix", "stamp", "result_log"])]
+    since: Option<String>,
+
+    /// Validate only this shard of the inputs, e.g. `2/5` in the second of five parallel CI
+    /// jobs. Inputs are sorted and dealt out in turn, so the shards are disjoint and stable
+    #[arg(long, value_name = "INDEX/COUNT")]
+    shard: Option<String>,
+
+    /// Project config with per-directory budgets (default: .toml-and-jerry.toml, if present)
+    #[arg(long, value_name = "FILE")]
+    config: Option<PathBuf>,
+
+    /// Fail only when a directory's count of a diagnostic code rises above the one recorded
+    /// in this state file; lower counts are recorded, so the allowance tightens over time
+    #[arg(long, value_name = "STATE", conflicts_with = "shard")]
+    ratchet: Option<PathBuf>,
+
+    /// ARM template whose `parameters` section the inputs (parameter files) must satisfy
+    #[arg(long, conflicts_with_all = ["schema", "openapi", "proto_descriptor", "docker_compose", "containerfile", "preset"])]
+    arm_template: Option<PathBuf>,
+}
+
+impl CheckArgs {
+    fn validation_options(&self) -> Result<ValidationOptions, String> {
+        Ok(ValidationOptions {
+            cue_binary: self.cue_binary.clone(),
+            pkl_binary: self.pkl_binary.clone(),
+            nickel_binary: self.nickel_binary.clone(),
+            containerfiles: self.containerfile,
+            yaml_tags: self.yaml_tag_policy()?,
+            require_schema_comment: self.schema_comments && self.schema.is_none(),
+            limits: limits::Limits {
+                max_depth: self.max_depth,
+                max_keys: self.max_keys,
+                max_lines: self.max_lines,
+                max_array_len: self.max_array_len,
+            },
+            newlines: newlines::NewlineRules {
+                line_endings: match self.line_endings.as_deref() {
+                    Some(name) => Some(newlines::LineEndings::parse(name).ok_or_else(|| {
+                        format!("Unknown line ending style {:?}; expected lf, crlf or consistent", name)
+                    })?),
+                    None => None,
+                },
+                final_newline: self.final_newline,
+            },
+            embedded: self
+                .embedded
+                .iter()
+                .map(|spec| {
+                    embedded::EmbeddedRule::parse(spec)
+                        .ok_or_else(|| format!("Invalid --embedded {:?}; expected POINTER=json|yaml|toml:SCHEMA", spec))
+                })
+                .collect::<Result<_, _>>()?,
+            enum_mappings: self.enum_mappings()?,
+            ini_coercion: ini::IniCoercion::parse(&self.ini_coerce)?,
+            properties: properties::PropertiesOptions {
+                nest: self.properties_nest,
+                coercion: ini::IniCoercion::parse(&self.properties_coerce)?,
+            },
+            default_draft: match &self.default_draft {
+                Some(name) => Some(
+                    schema::parse_draft(name)
+                        .ok_or_else(|| format!("Unknown --default-draft {:?}; expected 4, 6, 7, 2019-09 or 2020-12", name))?,
+                ),
+                None => None,
+            },
+            quick: self.quick,
+            ..Default::default()
+        })
+    }
+
+    fn enum_mappings(&self) -> Result<Vec<enum_map::EnumMapping>, String> {
+        let mut mappings = match &self.enum_map {
+            Some(path) => enum_map::load_mappings(path)?,
+            None => Vec::new(),
+        };
+        for spec in &self.map_enum {
+            mappings.extend(
+                enum_map::parse_mapping(spec)
+                    .ok_or_else(|| format!("Invalid --map-enum {:?}; expected 'POINTER: OLD=NEW[, OLD=NEW...]'", spec))?,
+            );
+        }
+        Ok(mappings)
+    }
+
+    // Writes --fix-summary (JSON) and --fix-changelog (commit message text) when requested
+    fn write_fix_summary(&self, summary: &fix::FixSummary) -> Result<(), String> {
+        if let Some(path) = &self.fix_summary {
+            let rendered = serde_json::to_string_pretty(summary).map_err(|e| e.to_string())?;
+            paths::check_writable(path)
+                .and_then(|()| std::fs::write(path, rendered + "\n"))
+                .map_err(|e| format!("Failed to write fix summary to {}: {}", path.display(), e))?;
+        }
+        if let Some(path) = &self.fix_changelog {
+            let changelog = fix::render_changelog(summary);
+            if path.as_os_str() == "-" {
+                print!("{}", changelog);
+            } else {
+                paths::check_writable(path)
+                    .and_then(|()| std::fs::write(path, changelog))
+                    .map_err(|e| format!("Failed to write fix changelog to {}: {}", path.display(), e))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn yaml_tag_policy(&self) -> Result<yaml::TagPolicy, String> {
+        let unknown = yaml::UnknownTagMode::parse(&self.yaml_unknown_tags)
+            .ok_or_else(|| format!("Unknown --yaml-unknown-tags {:?}; expected ignore, string or error", self.yaml_unknown_tags))?;
+        let mut policy = yaml::TagPolicy { unknown, ..Default::default() };
+        if let Some(profile) = &self.yaml_tag_profile {
+            policy = policy
+                .with_profile(profile)
+                .ok_or_else(|| format!("Unknown --yaml-tag-profile {:?}; expected cloudformation", profile))?;
+        }
+        for spec in &self.yaml_tag_maps {
+            policy = policy
+                .with_mapping(spec)
+                .ok_or_else(|| format!("Invalid --yaml-tag-map {:?}; expected '!Tag=Key'", spec))?;
+        }
+        Ok(policy)
+    }
+
+    // Flags that write files, which --no-write rules out up front
+    fn writing_flags(&self) -> Vec<&'static str> {
+        let mut flags = Vec::new();
+        let mut add = |set: bool, flag| if set { flags.push(flag) };
+        add(self.fix && !self.diff && !self.fix_check && self.fix_out.is_none(), "--fix");
+        add(self.fix_out.is_some(), "--fix-out");
+        add(self.stamp, "--stamp");
+        add(self.output.is_some(), "--output");
+        add(self.status_file.is_some(), "--status-file");
+        add(self.record_run.is_some(), "--record-run");
+        add(self.debug_bundle.is_some(), "--debug-bundle");
+        add(self.result_log.is_some(), "--result-log");
+        add(self.emit_patch.as_ref().is_some_and(|p| p.as_os_str() != "-"), "--emit-patch");
+        add(self.fix_summary.is_some(), "--fix-summary");
+        add(self.fix_changelog.as_ref().is_some_and(|p| p.as_os_str() != "-"), "--fix-changelog");
+        flags
+    }
+
+    // The revisions --at or --since validates instead of the working tree
+    fn history(&self) -> Option<history::History> {
+        match (&self.at, &self.since) {
+            (Some(rev), _) => Some(history::History::At(rev.clone())),
+            (None, Some(rev)) => Some(history::History::Since(rev.clone())),
+            (None, None) => None,
+        }
+    }
+
+    // Narrows the inputs to --shard's share, if given
+    fn apply_shard(&mut self) -> Result<(), String> {
+        let Some(spec) = &self.shard else { return Ok(()) };
+        let shard = shard::Shard::parse(spec)?;
+        let selected = shard.select(&self.inputs);
+        progress::line(format_args!("Shard {}/{}: validating {} of {} input(s)", shard.index, shard.count, selected.len(), self.inputs.len()));
+        self.inputs = selected;
+        Ok(())
+    }
+
+    // Replaces the failures of files expected to be invalid (--expect-invalid, or `patterns`
+    // from the project config) with errors for those that validated
+    fn apply_expectations(&self, errors: Vec<AppError>, patterns: &[String], options: &ValidationOptions) -> Result<Vec<AppError>, String> {
+        let expectations = expect_invalid::ExpectInvalid::new(self.expect_invalid, patterns)?;
+        if expectations.is_empty() {
+            return Ok(errors);
+        }
+        let files: Vec<PathBuf> = provenance::input_artifacts(&self.inputs)
+            .into_iter()
+            .map(|artifact| PathBuf::from(artifact.uri))
+            .filter(|file| validation::skip_reason(file, options).is_none())
+            .collect();
+        Ok(expect_invalid::apply(&files, errors, |file| expectations.expects(file)))
+    }
+
+    // Whether --format ndjson can print diagnostics as files finish. Diffing against a base,
+    // schema fallbacks, --fix and rules packs (or presets) decide what to report only once the
+    // whole run is done, and --output gets the report in one piece
+    fn streams_ndjson(&self) -> bool {
+        self.format == "ndjson"
+            && self.output.is_none()
+            && self.diff_base.is_none()
+            && self.schema_fallback.is_empty()
+            && !self.fix
+            && self.preset.is_none()
+            && self.rules_pack.is_none()
+    }
+
+    // Drops suppressed diagnostics; expired suppressions become warnings instead
+    fn apply_suppressions(&self, errors: Vec<AppError>) -> Result<Vec<AppError>, String> {
+        let configured = match &self.suppressions {
+            Some(path) => suppressions::load_suppressions(path)?,
+            None => Vec::new(),
+        };
+        Ok(suppressions::apply_suppressions(errors, &configured, self.suppressions.as_deref(), &suppressions::today()))
+    }
+
+    // Takes the project config's defaults for the flags that were not given
+    fn apply_project_defaults(&mut self, project: &project_config::ProjectConfig) {
+        if self.default_draft.is_none() {
+            self.default_draft = project.default_draft.clone();
+        }
+        if self.line_endings.is_none() {
+            self.line_endings = project.line_endings.clone();
+        }
+        self.final_newline |= project.final_newline;
+    }
+
+    // The project config's [severity] table with --severity on top
+    fn severities(
+        &self,
+        configured: &std::collections::BTreeMap<String, severity::RuleSeverity>,
+    ) -> Result<std::collections::BTreeMap<String, severity::RuleSeverity>, String> {
+        let mut mapping = configured.clone();
+        for flag in &self.severity {
+            let (rule, level) = severity::parse_flag(flag)?;
+            mapping.insert(rule, level);
+        }
+        Ok(mapping)
+    }
+
+    fn rules_registry(&self) -> Option<String> {
+        self.rules_registry
+            .clone()
+            .or_else(|| std::env::var("TOML_AND_JERRY_RULES_REGISTRY").ok())
+    }
+
+    // The --default-draft dialect; validation_options() has already rejected unknown names
+    fn default_draft(&self) -> Option<jsonschema::Draft> {
+        self.default_draft.as_deref().and_then(schema::parse_draft)
+    }
+
+    // Warnings for the --schema and --schema-fallback documents that have no `$schema`
+    fn dialect_warnings(&self, schema_json: Option<&serde_json::Value>, fallbacks: &[FallbackSchema]) -> Vec<AppError> {
+        let primary = schema_json.and_then(|s| schema::assumed_dialect(s, &self.schema_display(), self.default_draft()));
+        let fallbacks = fallbacks
+            .iter()
+            .filter_map(|(path, _, s)| schema::assumed_dialect(s, &format!("{:?}", path), self.default_draft()));
+        primary.into_iter().chain(fallbacks).collect()
+    }
+
+    // What --result-log entries are keyed by besides the file: the schema as given and, for a
+    // schema file, its digest, so --resume validates everything again after the schema changes
+    fn result_log_schema(&self) -> String {
+        match self.schema.as_ref().filter(|path| path.is_file()) {
+            Some(path) => format!("{}@{}", self.schema_display(), provenance::file_artifact(path).sha256.unwrap_or_default()),
+            None => self.schema_display(),
+        }
+    }
+
+    // Human-readable description of where the schema comes from
+    fn schema_display(&self) -> String {
+        if let Some(schema) = &self.schema {
+            return format!("{:?}", schema);
+        }
+        if let (Some(spec), Some(operation)) = (&self.openapi, &self.operation) {
+            return format!("{:?} ({} {})", spec, operation, self.part);
+        }
+        if let (Some(descriptor), Some(message)) = (&self.proto_descriptor, &self.message) {
+            return format!("{:?} ({})", descriptor, message);
+        }
+        if self.docker_compose {
+            return "bundled:docker-compose".to_string();
+        }
+        if self.containerfile {
+            return "bundled:containerfile".to_string();
+        }
+        if let Some(preset) = &self.preset {
+            return format!("preset:{}", preset);
+        }
+        if let Some(pack) = &self.rules_pack {
+            return format!("rules-pack:{}", pack);
+        }
+        if let Some(template) = &self.arm_template {
+            return format!("{:?} (parameters)", template);
+        }
+        if self.schema_comments {
+            return "tj-schema comments".to_string();
+        }
+        "<none>".to_string()
+    }
+
+    // How the per-schema table names the run's own schema
+    fn gate_name(&self) -> String {
+        match &self.schema {
+            Some(schema) => schema.display().to_string(),
+            None => self.schema_display(),
+        }
+    }
+
+    // Human-readable report of the run's diagnostics, arranged by --group-by
+    fn render_human(&self, errors: Vec<AppError>) -> String {
+        match human_report::GroupBy::parse(&self.group_by).unwrap_or_default() {
+            human_report::GroupBy::File => human_report::render_by_file(errors),
+            human_report::GroupBy::Rule => human_report::render_by_rule(&errors),
+        }
+    }
+
+    // The `schema` of a --format json report: the schema or spec file, or a bundled schema.
+    // `None` when presets, rules packs or tj-schema comments pick a schema per file
+    fn report_schema_uri(&self) -> Option<String> {
+        let file = self.schema.as_ref().or(self.openapi.as_ref()).or(self.proto_descriptor.as_ref()).or(self.arm_template.as_ref());
+        if let Some(file) = file {
+            return Some(file.to_string_lossy().into_owned());
+        }
+        if self.preset.is_some() || self.rules_pack.is_some() || self.schema_comments {
+            return None;
+        }
+        Some(self.schema_display())
+    }
+}
+
+// Posts to --notify-webhook, if requested and the outcome calls for it. Like the status file,
+// a failed post is reported but does not change the exit code.
+fn notify_check(args: &CheckArgs, run_status: &status::RunStatus, top: &[String]) {
+    let Some(url) = &args.notify_webhook else { return };
+    let when = notify::NotifyWhen::parse(&args.notify_on).unwrap_or(notify::NotifyWhen::Failure);
+    if !when.should_notify(run_status) {
+        return;
+    }
+    if let Err(message) = notify::post_webhook(url, &notify::webhook_payload(run_status, top)) {
+        eprintln!("{}", message);
+    }
+}
+
+// Writes the --stamp comment into each input: PASS unless the file has a failing diagnostic.
+// Returns false if a file could not be stamped.
+fn stamp_inputs(
+    args: &CheckArgs,
+    parsers: &std::collections::BTreeMap<String, parsers::Parser>,
+    schema_document: Option<&serde_json::Value>,
+    diagnostics: &[AppError],
+) -> bool {
+    let schema = check_schema_artifacts(args, schema_document).into_iter().next();
+    let (schema, digest) = match schema {
+        Some(artifact) => (artifact.uri, artifact.sha256.map(|d| d[..12].to_string()).unwrap_or_else(|| "unknown".to_string())),
+        None => (args.schema_display(), "unknown".to_string()),
+    };
+    let failing: Vec<String> = diagnostics
+        .iter()
+        .filter(|d| d.level() == error::Severity::Error)
+        .map(|d| validation::PrintableError::from(d).file_path)
+        .collect();
+    let mut ok = true;
+    for artifact in provenance::input_artifacts(&args.inputs) {
+        let stamp = stamp::Stamp {
+            schema: schema.clone(),
+            digest: digest.clone(),
+            date: suppressions::today(),
+            passed: !failing.contains(&artifact.uri),
+        };
+        match stamp::stamp_file(std::path::Path::new(&artifact.uri), &stamp, parsers) {
+            Ok(true) => {}
+            Ok(false) => progress::line(format_args!("Not stamping {}: its format has no comments", artifact.uri)),
+            Err(message) => {
+                eprintln!("{}", message);
+                ok = false;
+            }
+        }
+    }
+    ok
+}
+
+// Writes --status-file, if requested. A failure here is reported but does not change the exit code.
+fn write_check_status(args: &CheckArgs, run_status: &status::RunStatus) {
+    if let Some(path) = &args.status_file {
+        if let Err(e) = status::write_status(path, run_status) {
+            eprintln!("Failed to write status file {}: {}", path.display(), e);
+        }
+    }
+}
+
+// Structured report for the machine-readable formats; `None` for human output
+fn render_report(
+    format: &str,
+    template: Option<&str>,
+    errors: &[AppError],
+    schema: Option<&str>,
+    provenance: Option<&provenance::Provenance>,
+    summary: Option<&summary::RunSummary>,
+) -> Option<Result<String, Box<dyn std::error::Error>>> {
+    match format {
+        "template" => Some(match template {
+            Some(text) => template::Template::parse(text).map(|t| t.render_all(errors)).map_err(|e| e.into()),
+            None => Err("--format template needs --template".into()),
+        }),
+        // The versioned report shape `schema-of-report` describes
+        "json" => {
+            let report = report_schema::JsonReport { provenance, summary, ..report_schema::JsonReport::new(schema, errors) };
+            Some(serde_json::to_string_pretty(&report).map_err(|e| e.into()))
+        }
+        // One compact JSON object per line, as streamed while validating
+        "ndjson" => Some(Ok(errors
+            .iter()
+            .map(|e| report_stream::ndjson_line(&e.into()))
+            .collect::<Vec<_>>()
+            .join("\n"))),
+        // One row per diagnostic for spreadsheets; provenance has no place in a flat table
+        "csv" => Some(Ok(merge::to_csv(&errors.iter().map(merge::report_entry).collect::<Vec<_>>()).trim_end().to_string())),
+        "codeclimate" => {
+            let entries: Vec<merge::ReportEntry> = errors.iter().map(merge::report_entry).collect();
+            Some(serde_json::to_string_pretty(&merge::to_codeclimate(&entries)).map_err(|e| e.into()))
+        }
+        // gcc-style lines for editor quickfix lists
+        "compact" => Some(Ok(merge::to_compact(&errors.iter().map(merge::display_entry).collect::<Vec<_>>()).trim_end().to_string())),
+        // Azure Pipelines logging commands; the agent turns them into issues on the run
+        "azure" => Some(Ok(merge::to_azure(&errors.iter().map(merge::report_entry).collect::<Vec<_>>()).trim_end().to_string())),
+        "sarif" => Some(errors_to_sarif(errors).and_then(|sarif| {
+            if provenance.is_none() && summary.is_none() {
+                return Ok(sarif);
+            }
+            let mut sarif: serde_json::Value = serde_json::from_str(&sarif)?;
+            let mut properties = serde_json::Map::new();
+            if let Some(provenance) = provenance {
+                properties.insert("provenance".to_string(), serde_json::json!(provenance));
+            }
+            if let Some(summary) = summary {
+                properties.insert("summary".to_string(), serde_json::json!(summary));
+            }
+            sarif["runs"][0]["properties"] = serde_json::Value::Object(properties);
+            Ok(serde_json::to_string_pretty(&sarif)?)
+        })),
+        _ => None,
+    }
+}
+
+// Schemas the check ran against, identified by content digest
+fn check_schema_artifacts(args: &CheckArgs, schema_document: Option<&serde_json::Value>) -> Vec<provenance::DigestedArtifact> {
+    if let Some(preset) = args.preset.as_deref().and_then(presets::find_preset) {
+        return preset
+            .rules
+            .iter()
+            .map(|rule| match rule.schema {
+                presets::SchemaSource::Catalog(url) => match schema::load_schema_json(&PathBuf::from(url)) {
+                    Ok(schema) => provenance::schema_artifact(url, &schema),
+                    Err(_) => provenance::DigestedArtifact { uri: url.to_string(), sha256: None },
+                },
+            })
+            .collect();
+    }
+    if let Some(spec) = &args.rules_pack {
+        let Ok(pack) = rules_pack::RulesPack::open(spec, args.rules_registry().as_deref()) else { return Vec::new() };
+        return pack
+            .manifest
+            .rules
+            .iter()
+            .map(|rule| {
+                let location = pack.locate(&rule.schema);
+                match schema::load_schema_json(&location) {
+                    Ok(schema) => provenance::schema_artifact(&location.to_string_lossy(), &schema),
+                    Err(_) => provenance::DigestedArtifact { uri: location.to_string_lossy().into_owned(), sha256: None },
+                }
+            })
+            .collect();
+    }
+    let spec_file = args.openapi.as_ref().or(args.proto_descriptor.as_ref()).or(args.arm_template.as_ref());
+    match (spec_file, schema_document) {
+        (Some(path), _) => vec![provenance::file_artifact(path)],
+        (None, Some(schema)) => {
+            let uri = match &args.schema {
+                Some(path) => path.to_string_lossy().into_owned(),
+                None => args.schema_display(),
+            };
+            let mut artifacts = vec![provenance::schema_artifact(&uri, schema)];
+            artifacts.extend(args.schema_fallback.iter().map(|fallback| match schema::load_schema_json(fallback) {
+                Ok(schema) => provenance::schema_artifact(&fallback.to_string_lossy(), &schema),
+                Err(_) => provenance::DigestedArtifact { uri: fallback.to_string_lossy().into_owned(), sha256: None },
+            }));
+            artifacts
+        }
+        (None, None) => Vec::new(),
+    }
+}
+
+fn check_provenance(
+    args: &CheckArgs,
+    schema_document: Option<&serde_json::Value>,
+    started_at: std::time::SystemTime,
+) -> provenance::Provenance {
+    provenance::Provenance {
+        tool: provenance::ToolInfo::current(),
+        schemas: check_schema_artifacts(args, schema_document),
+        inputs: provenance::input_artifacts(&args.inputs),
+        started_at: provenance::rfc3339(started_at),
+        finished_at: provenance::rfc3339(std::time::SystemTime::now()),
+        hostname: if args.provenance_hostname { provenance::hostname() } else { None },
+    }
+}
+
+// Prints --format ndjson lines while validating, leaving out what the end of the run drops
+// anyway: suppressed diagnostics, failures of files expected to be invalid, and diagnostics
+// that a source map moves to a template
+fn start_ndjson_stream(
+    args: &CheckArgs,
+    expect_invalid: &[String],
+    severities: &std::collections::BTreeMap<String, severity::RuleSeverity>,
+) -> Result<report_stream::Streamed, String> {
+    let configured = match &args.suppressions {
+        Some(path) => suppressions::load_suppressions(path)?,
+        None => Vec::new(),
+    };
+    let expectations = expect_invalid::ExpectInvalid::new(args.expect_invalid, expect_invalid)?;
+    let today = suppressions::today();
+    let severities = severities.clone();
+    let streamed = report_stream::Streamed::default();
+    let printer = streamed.clone();
+    report_stream::stream_to(move |errors| {
+        for error in errors {
+            let mut printable = validation::PrintableError::from(error);
+            let expected = !error.is_warning() && expectations.expects(&printable.file_path);
+            let mapped = source_map::sidecar_path(std::path::Path::new(&printable.file_path)).is_file();
+            let level = severity::severity_for(&severities, error).map(severity::RuleSeverity::level);
+            if expected || mapped || level == Some(None) || suppressions::is_suppressed(error, &configured, &today) {
+                continue;
+            }
+            if let Some(Some(level)) = level {
+                printable.severity = Some(level);
+            }
+            printer.print(report_stream::ndjson_line(&printable));
+        }
+    });
+    Ok(streamed)
+}
+
+// Prints the report, or writes it to --output (and signs it with --sign-report)
+fn emit_report(args: &CheckArgs, report: &str) -> Result<(), AppError> {
+    let Some(output) = &args.output else {
+        // An ndjson report without diagnostics (or with all of them streamed) has no lines
+        if !report.is_empty() {
+            println!("{}", report);
+        }
+        return Ok(());
+    };
+    paths::check_writable(output).and_then(|()| std::fs::write(output, format!("{}\n", report))).map_err(|e| AppError::ReportSigningError {
+        path: output.to_string_lossy().into_owned(),
+        message: format!("could not write report: {}", e),
+    })?;
+    println!("Report written to {}", output.display());
+    if let Some(key) = &args.sign_report {
+        let signature = signing::sign_report_file(output, key)?;
+        println!("Signature written to {}", signature.display());
+    }
+    Ok(())
+}
+
+// Writes `{ "<file>": [<RFC 6902 operations>] }` to `path`, or stdout for '-'
+fn write_patches(path: &PathBuf, patches: &std::collections::BTreeMap<String, Vec<serde_json::Value>>) -> std::io::Result<()> {
+    let rendered = serde_json::to_string_pretty(patches)?;
+    if path.as_os_str() == "-" {
+        println!("{}", rendered);
+        Ok(())
+    } else {
+        paths::check_writable(path)?;
+        std::fs::write(path, rendered + "\n")
+    }
+}
+
+// Packs what a failing run saw: the files with diagnostics, the schemas it resolved, the
+// project config and the JSON report
+fn write_debug_bundle(
+    args: &CheckArgs,
+    path: &std::path::Path,
+    given_inputs: &[PathBuf],
+    recorded_args: &[String],
+    schema_document: Option<&serde_json::Value>,
+    errors: &[AppError],
+) -> Result<debug_bundle::BundleManifest, AppError> {
+    let mut schemas = Vec::new();
+    if let (Some(schema), Some(document)) = (&args.schema, schema_document) {
+        schemas.push((schema.to_string_lossy().into_owned(), document.clone()));
+    }
+    for fallback in &args.schema_fallback {
+        if let Ok(document) = schema::load_schema_json(fallback) {
+            schemas.push((fallback.to_string_lossy().into_owned(), document));
+        }
+    }
+    let schema_paths: Vec<PathBuf> = schemas.iter().map(|(original, _)| PathBuf::from(original)).collect();
+    let mut offending: Vec<PathBuf> = errors
+        .iter()
+        .map(|error| PathBuf::from(validation::PrintableError::from(error).file_path))
+        .filter(|file| file.is_file() && !schema_paths.contains(file))
+        .collect();
+    offending.sort();
+    offending.dedup();
+    let config = args
+        .config
+        .clone()
+        .or_else(|| std::path::Path::new(project_config::PROJECT_CONFIG_FILE).is_file().then(|| PathBuf::from(project_config::PROJECT_CONFIG_FILE)));
+    let report = render_report("json", None, errors, args.report_schema_uri().as_deref(), None, None)
+        .and_then(Result::ok)
+        .unwrap_or_default();
+    debug_bundle::write_bundle(
+        path,
+        &debug_bundle::BundleSources {
+            args: recorded_args,
+            given_inputs,
+            offending: &offending,
+            schemas: &schemas,
+            config: config.as_deref(),
+            report: &report,
+            redact: args.debug_bundle_redact,
+        },
+    )
+}
+
+// Compiles the schema selected by the check flags. The raw schema document is returned
+// too when there is one, for features that read annotations (`x-renamed-from`).
+fn compile_check_schema(args: &CheckArgs) -> Result<CompiledSchema, AppError> {
+    if let (Some(spec), Some(operation)) = (&args.openapi, &args.operation) {
+        let part = openapi::PayloadPart::parse(&args.part).ok_or_else(|| AppError::OpenApiError {
+            spec: spec.to_string_lossy().into_owned(),
+            message: format!("Unknown --part {:?}; expected requestBody, response or response:<status>", args.part),
+        })?;
+        return openapi::compile_operation_validator(spec, operation, &part, args.media_type.as_deref()).map(|v| (v, None));
+    }
+    if let (Some(descriptor), Some(message)) = (&args.proto_descriptor, &args.message) {
+        return proto::compile_message_validator(descriptor, message).map(|v| (v, None));
+    }
+    if args.docker_compose {
+        return presets::compile_bundled_schema("docker-compose").map(|v| (v, presets::bundled_schema("docker-compose")));
+    }
+    if args.containerfile && args.schema.is_none() {
+        return presets::compile_bundled_schema("containerfile").map(|v| (v, presets::bundled_schema("containerfile")));
+    }
+    if let Some(template) = &args.arm_template {
+        return arm::compile_parameters_validator(template).map(|v| (v, None));
+    }
+    match &args.schema {
+        Some(schema) => {
+            let schema_json = schema::load_schema_json(schema)?;
+            let validator = quick::compile(&schema_json, &schema.to_string_lossy(), args.default_draft(), args.quick)?;
+            Ok((validator, Some(schema_json)))
+        }
+        // Every input brings its own schema; this one is never used
+        None if args.schema_comments => schema::compile_schema(&serde_json::Value::Bool(true), "tj-schema comments").map(|v| (v, None)),
+        None => Err(AppError::InvalidSchemaPath {
+            path_display: "no --schema, --openapi or --proto-descriptor given".to_string(),
+        }),
+    }
+}
+
+type FallbackSchema = (PathBuf, jsonschema::Validator, serde_json::Value);
+
+// Compiles the --schema-fallback schemas, in order
+fn compile_fallback_schemas(args: &CheckArgs) -> Result<Vec<FallbackSchema>, AppError> {
+    args.schema_fallback
+        .iter()
+        .map(|path| {
+            let schema_json = schema::load_schema_json(path)?;
+            let validator = quick::compile(&schema_json, &path.to_string_lossy(), args.default_draft(), args.quick)?;
+            Ok((path.clone(), validator, schema_json))
+        })
+        .collect()
+}
+
+// The --schema validator followed by the fallbacks, each with its own rename rules
+fn fallback_candidates<'a>(
+    args: &CheckArgs,
+    primary: &'a jsonschema::Validator,
+    options: &ValidationOptions,
+    fallbacks: &'a [FallbackSchema],
+) -> Vec<fallback::SchemaCandidate<'a>> {
+    let mut candidates = vec![fallback::SchemaCandidate { display: args.schema_display(), validator: primary, options: options.clone() }];
+    candidates.extend(fallbacks.iter().map(|(path, validator, schema_json)| fallback::SchemaCandidate {
+        display: format!("{:?}", path),
+        validator,
+        options: ValidationOptions { rename_rules: renames::collect_rename_rules(schema_json), ..options.clone() },
+    }));
+    candidates
+}
+
+#[derive(Subcommand)]
+enum SchemaCmd {
+    /// Export every valid path with its types, enum values and description
+    /// (for autocomplete and editor snippets)
+    Keys {
+        /// JSON Schema file (local or URL)
+        schema: PathBuf,
+
+        /// Output format: human | json
+        #[arg(long, default_value = "human")]
+        format: String,
+    },
+
+    /// Document the environment variable that overrides each config key, with its type,
+    /// default and description
+    Envdoc {
+        /// JSON Schema file (local or URL)
+        #[arg(short, long)]
+        schema: PathBuf,
+
+        /// Prefix of every variable, e.g. APP_
+        #[arg(long, default_value = "")]
+        prefix: String,
+
+        /// Joins the keys of nested objects, e.g. '__' for APP_DATABASE__PORT
+        #[arg(long, default_value = "_")]
+        separator: String,
+
+        /// Output format: markdown | json
+        #[arg(long, default_value = "markdown")]
+        format: String,
+    },
+
+    /// Report, for each schema property, how many documents set it, the most common values
+    /// and which defaults are always overridden
+    Usage {
+        /// JSON Schema file (local or URL)
+        #[arg(short, long)]
+        schema: PathBuf,
+
+        /// Documents or directories of documents to analyse
+        #[arg(required = true)]
+        inputs: Vec<PathBuf>,
+
+        /// Output format: human | json
+        #[arg(long, default_value = "human")]
+        format: String,
     },
+
+    /// Validate a schema document against the meta-schema of each dialect and list the
+    /// keywords each would ignore. Fails unless it is valid under all of them
+    Check {
+        /// JSON Schema file (local or URL)
+        schema: PathBuf,
+
+        /// Dialects to check: 4, 6, 7, 2019-09, 2020-12 (repeatable or comma-separated)
+        #[arg(long = "against-meta", value_name = "DIALECT", value_delimiter = ',', required = true)]
+        against_meta: Vec<String>,
+
+        /// Output format: human | json
+        #[arg(long, default_value = "human")]
+        format: String,
+    },
+
+    /// List `definitions`/`$defs` entries that no `$ref` reaches from the schema root, as
+    /// warnings, so dead definitions can be pruned
+    Unused {
+        /// JSON Schema file (local or URL)
+        schema: PathBuf,
+
+        /// Output format: human | json | ndjson | sarif | csv | compact | codeclimate | azure
+        #[arg(long, default_value = "human")]
+        format: String,
+    },
+
+    /// Lint a schema document: warns about `pattern` regexes that can backtrack exponentially,
+    /// and fails when an `examples` entry does not match the subschema it belongs to
+    Lint {
+        /// JSON Schema file (local or URL)
+        schema: PathBuf,
+
+        /// Output format: human | json | ndjson | sarif | csv | compact | codeclimate | azure
+        #[arg(long, default_value = "human")]
+        format: String,
+    },
+
+    /// Copy a subschema into a standalone schema, with the definitions it references, so
+    /// partial documents can be validated against just that fragment
+    Extract {
+        /// JSON Schema file (local or URL)
+        schema: PathBuf,
+
+        /// JSON pointer of the subschema, e.g. '#/definitions/Database'
+        pointer: String,
+
+        /// Write the extracted schema to this file instead of stdout
+        #[arg(long, value_name = "FILE")]
+        out: Option<PathBuf>,
+    },
+
+    /// Copy the comments documenting keys in an example config into the schema as the
+    /// `description` of each key
+    SyncDescriptions {
+        /// JSON Schema file (local or URL)
+        schema: PathBuf,
+
+        /// Canonical example config (yaml or toml) whose key comments are the documentation
+        #[arg(long, value_name = "FILE")]
+        example: PathBuf,
+
+        /// Write the updated schema to this file (may be the schema itself) instead of stdout
+        #[arg(long, value_name = "FILE")]
+        out: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum CacheCmd {
+    /// List cached files and their sizes
+    Ls,
+    /// Delete all cached files
+    Clear,
+    /// Print the cache and data directories
+    Path,
+}
+
+// SARIF 2.1.0 with one result per diagnostic, located at its first label
+fn errors_to_sarif(errors: &[AppError]) -> Result<String, Box<dyn std::error::Error>> {
+    let entries: Vec<merge::ReportEntry> = errors.iter().map(merge::report_entry).collect();
+    Ok(serde_json::to_string_pretty(&merge::to_sarif(&entries))?)
+}
+
+type CompiledSchema = (jsonschema::Validator, Option<serde_json::Value>);
+
+// Key for reusing a compiled --schema between worker requests: its location plus a content
+// digest, so an edited schema is recompiled
+fn worker_schema_key(args: &CheckArgs) -> Option<String> {
+    let schema = args.schema.as_ref()?;
+    let digest = provenance::file_artifact(schema).sha256.unwrap_or_default();
+    Some(format!(
+        "{}#{}#{}#{}",
+        schema.display(),
+        digest,
+        args.default_draft.as_deref().unwrap_or_default(),
+        args.regex_backtrack_limit
+    ))
+}
+
+// Runs `validate` on the inputs at the --at/--since revisions, or with --diff-base, or as
+// they are
+fn validate_checked_inputs(
+    args: &CheckArgs,
+    validate: impl Fn(Vec<PathBuf>) -> Result<Vec<AppError>, AppError>,
+) -> Result<Vec<AppError>, AppError> {
+    match args.history() {
+        Some(history) => history::validate_history(std::path::Path::new("."), &args.inputs, Some(&history), validate),
+        None => {
+            let base = args.diff_base.as_deref().map(diff_base::DiffBase::parse);
+            diff_base::validate_against_base(&args.inputs, base.as_ref(), validate)
+        }
+    }
+}
+
+// Diagnostics of the project config's `[[require]]` and `[[consistent]]` rules
+fn project_rule_diagnostics(project: &project_config::ProjectConfig, options: &ValidationOptions) -> Result<Vec<AppError>, String> {
+    let mut found = required_files::check_required_files(&project.require, options)?;
+    found.extend(consistency::check_consistency(&project.consistent)?);
+    Ok(found)
+}
+
+// Key for reusing a loaded project config between worker requests: the file it comes from
+// in the request's directory plus a content digest, so an edited config is reloaded
+fn worker_config_key(args: &CheckArgs) -> String {
+    let path = args.config.clone().unwrap_or_else(|| PathBuf::from(project_config::PROJECT_CONFIG_FILE));
+    let path = std::env::current_dir().unwrap_or_default().join(path);
+    let digest = provenance::file_artifact(&path).sha256.unwrap_or_default();
+    format!("{}#{}", path.display(), digest)
+}
+
+// One `check` request of the persistent worker, through the same steps as `check` minus the
+// ones that write or post. Returns the exit code and the text the build system shows for the
+// action.
+fn worker_check(
+    mut args: CheckArgs,
+    skip_hooks: bool,
+    schema_cache: &mut std::collections::HashMap<String, CompiledSchema>,
+    config_cache: &mut std::collections::HashMap<String, project_config::ProjectConfig>,
+) -> (i32, String) {
+    schema::set_regex_backtrack_limit(args.regex_backtrack_limit);
+    let config_key = worker_config_key(&args);
+    if !config_cache.contains_key(&config_key) {
+        match project_config::load_project_config(args.config.as_deref()) {
+            Ok(project) => {
+                config_cache.insert(config_key.clone(), project);
+            }
+            Err(message) => {
+                write_check_status(&args, &status::RunStatus::aborted(2, message.clone()));
+                return (2, message);
+            }
+        }
+    }
+    let project = &config_cache[&config_key];
+    args.apply_project_defaults(project);
+    let args = &args;
+    let severities = match args.severities(&project.severity) {
+        Ok(severities) => severities,
+        Err(message) => {
+            write_check_status(args, &status::RunStatus::aborted(2, message.clone()));
+            return (2, message);
+        }
+    };
+    let options = match args.validation_options() {
+        Ok(options) => ValidationOptions {
+            parsers: project.parsers.clone(),
+            schema_versions: project.schema_versions.clone(),
+            ..options
+        },
+        Err(message) => return (2, message),
+    };
+    let validation_result = if let Some(preset) = &args.preset {
+        validate_checked_inputs(args, |inputs| presets::run_preset(preset, &inputs, &options))
+    } else if let Some(spec) = &args.rules_pack {
+        rules_pack::RulesPack::open(spec, args.rules_registry().as_deref()).and_then(|pack| {
+            validate_checked_inputs(args, |inputs| {
+                rules_pack::run_rules_pack(&pack, &inputs, &options)
+            })
+        })
+    } else {
+        let key = worker_schema_key(args);
+        if let Some(key) = &key {
+            if !schema_cache.contains_key(key) {
+                match compile_check_schema(args) {
+                    Ok(compiled) => {
+                        schema_cache.insert(key.clone(), compiled);
+                    }
+                    Err(e) => return (1, format!("{:?}", miette::Report::new(e))),
+                }
+            }
+        }
+        let uncached;
+        let (validator, schema_json) = match &key {
+            Some(key) => &schema_cache[key],
+            None => {
+                uncached = match compile_check_schema(args) {
+                    Ok(compiled) => compiled,
+                    Err(e) => return (1, format!("{:?}", miette::Report::new(e))),
+                };
+                &uncached
+            }
+        };
+        let options = ValidationOptions {
+            rename_rules: schema_json.as_ref().map(renames::collect_rename_rules).unwrap_or_default(),
+            ..options.clone()
+        };
+        let fallbacks = match compile_fallback_schemas(args) {
+            Ok(fallbacks) => fallbacks,
+            Err(e) => return (1, format!("{:?}", miette::Report::new(e))),
+        };
+        let candidates = fallback_candidates(args, validator, &options, &fallbacks);
+        let dialect_warnings = args.dialect_warnings(schema_json.as_ref(), &fallbacks);
+        validate_checked_inputs(args, |inputs| {
+            if fallbacks.is_empty() {
+                validate_inputs_with_options(inputs, validator, &options)
+            } else {
+                fallback::validate_with_fallback(&inputs, &candidates)
+            }
+        })
+        .map(|errors| dialect_warnings.into_iter().chain(errors).collect::<Vec<_>>())
+    };
+
+    match validation_result {
+        Ok(collected_errors) => {
+            let collected_errors = match args
+                .apply_expectations(collected_errors, &project.expect_invalid, &options)
+                .and_then(|mut errors| {
+                    errors.extend(project_rule_diagnostics(project, &options)?);
+                    args.apply_suppressions(errors)
+                })
+                .map(|errors| severity::apply(&severities, errors))
+            {
+                Ok(kept) => kept,
+                Err(message) => {
+                    write_check_status(args, &status::RunStatus::aborted(2, message.clone()));
+                    return (2, message);
+                }
+            };
+            let collected_errors = source_map::map_to_templates(collected_errors);
+            let mut run_status = status::RunStatus::from_diagnostics(&collected_errors);
+            // With budgets, errors only fail the run once their directory is over its allowance
+            if !project.budget.is_empty() {
+                let failing = project_config::evaluate_budgets(&project.budget, &collected_errors).fails();
+                run_status.outcome = if failing { status::Outcome::Failed } else { status::Outcome::Passed };
+                run_status.exit_code = i32::from(failing);
+            }
+            let mut output = String::new();
+            let run_hooks = !skip_hooks && !project.report.hooks.is_empty();
+            let reported_diagnostics: Vec<validation::PrintableError> =
+                if run_hooks { collected_errors.iter().map(validation::PrintableError::from).collect() } else { Vec::new() };
+            match render_report(&args.format, args.template.as_deref(), &collected_errors, args.report_schema_uri().as_deref(), None, None) {
+                Some(Ok(report)) if args.output.is_some() => {
+                    if let Err(e) = emit_report(args, &report) {
+                        output.push_str(&format!("{:?}\n", miette::Report::new(e)));
+                    }
+                }
+                Some(Ok(report)) => output.push_str(&format!("{}\n", report)),
+                Some(Err(e)) => output.push_str(&format!("Failed to generate {} output: {}\n", args.format, e)),
+                None => output.push_str(&args.render_human(collected_errors)),
+            }
+            // A failing hook still fails a run that passed, as it does for `check`
+            if run_hooks
+                && report_hooks::run_hooks(&project.report.hooks, &report_hooks::hook_payload(&run_status, &reported_diagnostics))
+                && run_status.outcome == status::Outcome::Passed
+            {
+                run_status.outcome = status::Outcome::Error;
+                run_status.exit_code = 1;
+            }
+            write_check_status(args, &run_status);
+            (run_status.exit_code, output)
+        }
+        Err(fatal_err) => {
+            write_check_status(args, &status::RunStatus::aborted(1, fatal_err.to_string()));
+            (1, format!("{:?}", miette::Report::new(fatal_err)))
+        }
+    }
+}
+
+// Answers worker requests until the build system closes stdin. Returns the process exit code.
+fn run_persistent_worker(skip_hooks: bool) -> i32 {
+    let mut protocol_out = match worker::protocol_stdout() {
+        Ok(out) => out,
+        Err(e) => {
+            eprintln!("Cannot set up worker stdout: {}", e);
+            return 1;
+        }
+    };
+    let mut schema_cache = std::collections::HashMap::new();
+    let mut config_cache = std::collections::HashMap::new();
+    let served = worker::serve(std::io::stdin(), &mut protocol_out, |request| {
+        let argv = std::iter::once("toml-and-jerry".to_string()).chain(request.arguments.iter().cloned());
+        let original_dir = std::env::current_dir().ok();
+        if !request.sandbox_dir.is_empty() {
+            if let Err(e) = std::env::set_current_dir(&request.sandbox_dir) {
+                return (1, format!("Cannot enter sandbox {}: {}", request.sandbox_dir, e));
+            }
+        }
+        let response = match Cli::try_parse_from(argv) {
+            Ok(Cli { cmd: Some(Cmd::Check(args)), no_hooks, no_write, .. }) => {
+                worker_check(*args, skip_hooks || no_hooks || no_write, &mut schema_cache, &mut config_cache)
+            }
+            Ok(_) => (2, "The persistent worker only runs `check` requests".to_string()),
+            Err(e) => (2, e.to_string()),
+        };
+        if let Some(dir) = original_dir {
+            let _ = std::env::set_current_dir(dir);
+        }
+        response
+    });
+    match served {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("Worker protocol error: {}", e);
+            1
+        }
+    }
 }
 
-fn errors_to_sarif(_errors: &[AppError]) -> Result<String, Box<dyn std::error::Error>> {
-    // SARIF implementation temporarily disabled - will re-implement later
-    // For now, return a minimal valid SARIF structure
-    let minimal_sarif = serde_json::json!({
-        "version": "2.1.0",
-        "runs": [{
-            "tool": {
-                "driver": {
-                    "name": "toml-and-jerry",
-                    "version": env!("CARGO_PKG_VERSION"),
-                    "informationUri": "https://github.com/coryjanowski/toml-and-jerry"
+// Re-parses the command line stored in a run record, restoring its environment and directory
+fn replayed_cli(path: &std::path::Path) -> (Cli, run_record::RunRecord) {
+    let record = match run_record::read_record(path) {
+        Ok(record) => record,
+        Err(e) => {
+            eprintln!("{:?}", miette::Report::new(e));
+            exit(2);
+        }
+    };
+    for (name, value) in &record.env {
+        std::env::set_var(name, value);
+    }
+    if let Err(e) = std::env::set_current_dir(&record.cwd) {
+        eprintln!("Replay: cannot enter recorded directory {}: {}", record.cwd.display(), e);
+    }
+    let cli = Cli::parse_from(std::iter::once("toml-and-jerry".to_string()).chain(record.args.iter().cloned()));
+    route_progress(&cli);
+    progress::line(format_args!("Replaying: toml-and-jerry {}", record.args.join(" ")));
+    (cli, record)
+}
+
+// Unpacks a debug bundle into the directory `exit` and the end of `main` remove, and
+// re-parses its replay command line from inside it. The arguments are filtered again, as the
+// bundle may not be the one this tool wrote, and only a `check` that writes nothing replays
+fn replayed_bundle(path: &std::path::Path) -> (Cli, Vec<String>) {
+    let (unpacked, manifest) = match debug_bundle::unpack_bundle(path) {
+        Ok(unpacked) => unpacked,
+        Err(e) => {
+            eprintln!("{:?}", miette::Report::new(e));
+            exit(2);
+        }
+    };
+    let root = unpacked.path().to_path_buf();
+    *UNPACKED_BUNDLE.lock().unwrap_or_else(|e| e.into_inner()) = Some(unpacked);
+    if let Err(e) = std::env::set_current_dir(&root) {
+        eprintln!("Replay: cannot enter unpacked bundle {}: {}", root.display(), e);
+        exit(2);
+    }
+    if manifest.tool_version != env!("CARGO_PKG_VERSION") {
+        eprintln!(
+            "Replay differs from the bundled run: recorded with version {}, replaying with {}",
+            manifest.tool_version,
+            env!("CARGO_PKG_VERSION")
+        );
+    }
+    let replay_args = debug_bundle::without_dropped_flags(&manifest.replay_args);
+    let cli = match Cli::try_parse_from(std::iter::once("toml-and-jerry".to_string()).chain(replay_args.iter().cloned())) {
+        Ok(cli) => cli,
+        Err(e) => {
+            let _ = e.print();
+            exit(2);
+        }
+    };
+    match &cli.cmd {
+        Some(Cmd::Check(args)) if cli.replay.is_none() && !cli.persistent_worker && args.writing_flags().is_empty() => {}
+        _ => {
+            eprintln!("Replay: the bundle does not hold a check that writes nothing: toml-and-jerry {}", replay_args.join(" "));
+            exit(2);
+        }
+    }
+    route_progress(&cli);
+    progress::line(format_args!("Replaying bundle in {}: toml-and-jerry {}", root.display(), replay_args.join(" ")));
+    (cli, replay_args)
+}
+
+// The directory a bundle replay runs in
+static UNPACKED_BUNDLE: std::sync::Mutex<Option<tempfile::TempDir>> = std::sync::Mutex::new(None);
+
+// `std::process::exit` runs no destructors, so the replayed bundle is removed first
+fn exit(code: i32) -> ! {
+    drop(UNPACKED_BUNDLE.lock().unwrap_or_else(|e| e.into_inner()).take());
+    std::process::exit(code)
+}
+
+// A report printed on stdout should parse as it is, so progress goes to stderr
+fn route_progress(cli: &Cli) {
+    if let Some(Cmd::Check(args)) = &cli.cmd {
+        if args.output.is_none() && args.format != "human" {
+            progress::to_stderr();
+        }
+    }
+}
+
+fn main() -> Result<()> {
+    let result = run();
+    drop(UNPACKED_BUNDLE.lock().unwrap_or_else(|e| e.into_inner()).take());
+    result
+}
+
+fn run() -> Result<()> {
+    let cli = Cli::parse();
+    color::install_report_handler(color::ColorChoice::parse(&cli.color).unwrap_or(color::ColorChoice::Auto).with_env());
+    progress::set_handler(progress::LineProgress);
+    if cli.no_write {
+        paths::forbid_writes();
+    }
+    // Taken before a replay swaps in the recorded command line, so it still applies then
+    let skip_hooks = cli.no_hooks || cli.no_write;
+    if cli.persistent_worker {
+        exit(run_persistent_worker(skip_hooks));
+    }
+    let replaying_bundle = cli.replay.as_deref().is_some_and(debug_bundle::is_bundle);
+    let (cli, recorded_args, replay) = match &cli.replay {
+        Some(path) if replaying_bundle => {
+            let (cli, args) = replayed_bundle(path);
+            (cli, args, None)
+        }
+        Some(path) => {
+            let (cli, record) = replayed_cli(path);
+            (cli, record.args.clone(), Some(record))
+        }
+        None => {
+            let args: Vec<String> = std::env::args().skip(1).collect();
+            (cli, run_record::args_without_record_flag(&args), None)
+        }
+    };
+    route_progress(&cli);
+    // A bundle carries its config, and replaying someone's bundle must not run their hooks
+    let skip_hooks = skip_hooks || cli.no_hooks || replaying_bundle;
+    let Some(cmd) = cli.cmd else {
+        let _ = Cli::command().print_help();
+        exit(2);
+    };
+    let mut has_errors = false;
+
+    match cmd {
+        Cmd::Check(mut args) => {
+            schema::set_regex_backtrack_limit(args.regex_backtrack_limit);
+            // As typed, before sharding or --resume narrow them, so a bundle can replace them
+            let given_inputs = args.inputs.clone();
+            let writing = args.writing_flags();
+            if paths::writes_forbidden() && !writing.is_empty() {
+                eprintln!("--no-write cannot be combined with {}, which write files", writing.join(", "));
+                exit(2);
+            }
+            if let Err(message) = args.apply_shard() {
+                eprintln!("{}", message);
+                write_check_status(&args, &status::RunStatus::aborted(2, message));
+                exit(2);
+            }
+            let mut project = match project_config::load_project_config(args.config.as_deref()) {
+                Ok(project) => project,
+                Err(message) => {
+                    eprintln!("{}", message);
+                    write_check_status(&args, &status::RunStatus::aborted(2, message));
+                    exit(2);
+                }
+            };
+            if skip_hooks {
+                project.report.hooks.clear();
+            }
+            args.apply_project_defaults(&project);
+            let severities = match args.severities(&project.severity) {
+                Ok(severities) => severities,
+                Err(message) => {
+                    eprintln!("{}", message);
+                    write_check_status(&args, &status::RunStatus::aborted(2, message));
+                    exit(2);
+                }
+            };
+            let options = match args.validation_options() {
+                Ok(options) => options,
+                Err(message) => {
+                    eprintln!("{}", message);
+                    write_check_status(&args, &status::RunStatus::aborted(2, message));
+                    exit(2);
+                }
+            };
+            if let Some(log_path) = args.result_log.clone() {
+                let schema = args.result_log_schema();
+                if args.resume {
+                    let passed = result_log::already_passed(&result_log::read_log(&log_path), &schema, &args.inputs);
+                    args.inputs.retain(|input| !passed.contains(input));
+                    progress::line(format_args!("Resuming: skipping {} file(s) {} records as passing", passed.len(), log_path.display()));
+                }
+                match result_log::ResultLog::open(&log_path, schema) {
+                    Ok(log) => progress::set_handler(result_log::LoggingProgress { inner: progress::LineProgress, log }),
+                    Err(e) => {
+                        let message = format!("Cannot open result log {}: {}", log_path.display(), e);
+                        eprintln!("{}", message);
+                        write_check_status(&args, &status::RunStatus::aborted(2, message));
+                        exit(2);
+                    }
+                }
+            }
+            let options = ValidationOptions {
+                parsers: project.parsers.clone(),
+                schema_versions: project.schema_versions.clone(),
+                ..options
+            };
+            let streamed = if args.streams_ndjson() {
+                match start_ndjson_stream(&args, &project.expect_invalid, &severities) {
+                    Ok(streamed) => Some(streamed),
+                    Err(message) => {
+                        eprintln!("{}", message);
+                        write_check_status(&args, &status::RunStatus::aborted(2, message));
+                        exit(2);
+                    }
+                }
+            } else {
+                None
+            };
+            let started_at = std::time::SystemTime::now();
+            let mut schema_document = None;
+            summary::collect_gates();
+            let validation_result = if let Some(preset) = &args.preset {
+                progress::line(format_args!("Validating inputs against schema {} (output format: {})", args.schema_display(), args.format));
+                validate_checked_inputs(&args, |inputs| presets::run_preset(preset, &inputs, &options))
+            } else if let Some(spec) = &args.rules_pack {
+                let pack = match rules_pack::RulesPack::open(spec, args.rules_registry().as_deref()) {
+                    Ok(pack) => pack,
+                    Err(e) => {
+                        write_check_status(&args, &status::RunStatus::aborted(1, e.to_string()));
+                        eprintln!("{:?}", miette::Report::new(e));
+                        exit(1);
+                    }
+                };
+                progress::line(format_args!("Validating inputs with rules pack {} (output format: {})", pack.describe(), args.format));
+                validate_checked_inputs(&args, |inputs| {
+                    rules_pack::run_rules_pack(&pack, &inputs, &options)
+                })
+            } else {
+                let (compiled_schema, schema_json) = match compile_check_schema(&args) {
+                    Ok(s) => s,
+                    Err(e) => {
+                        write_check_status(&args, &status::RunStatus::aborted(1, e.to_string()));
+                        eprintln!("{:?}", miette::Report::new(e));
+                        exit(1);
+                    }
+                };
+                progress::line(format_args!("Validating inputs against schema {} (output format: {})", args.schema_display(), args.format));
+                progress::line("Schema loaded and compiled successfully.");
+                let options = ValidationOptions {
+                    rename_rules: schema_json.as_ref().map(renames::collect_rename_rules).unwrap_or_default(),
+                    ..options.clone()
+                };
+                if let Some(patch_path) = &args.emit_patch {
+                    match &schema_json {
+                        Some(schema_json) => {
+                            let patches = patch::patches_for_inputs(schema_json, &args.inputs, &options.yaml_tags);
+                            if let Err(e) = write_patches(patch_path, &patches) {
+                                eprintln!("Failed to write patch to {}: {}", patch_path.display(), e);
+                                has_errors = true;
+                            }
+                        }
+                        None => eprintln!("--emit-patch needs a schema document (--schema, --docker-compose or --containerfile); skipping"),
+                    }
+                }
+                let fallbacks = match compile_fallback_schemas(&args) {
+                    Ok(fallbacks) => fallbacks,
+                    Err(e) => {
+                        write_check_status(&args, &status::RunStatus::aborted(1, e.to_string()));
+                        eprintln!("{:?}", miette::Report::new(e));
+                        exit(1);
+                    }
+                };
+                let candidates = fallback_candidates(&args, &compiled_schema, &options, &fallbacks);
+                let dialect_warnings = args.dialect_warnings(schema_json.as_ref(), &fallbacks);
+                schema_document = schema_json;
+                validate_checked_inputs(&args, |inputs| {
+                    if fallbacks.is_empty() {
+                        validate_inputs_with_options(inputs, &compiled_schema, &options)
+                    } else {
+                        fallback::validate_with_fallback(&inputs, &candidates)
+                    }
+                })
+                .map(|errors| dialect_warnings.into_iter().chain(errors).collect::<Vec<_>>())
+            };
+            report_stream::stop();
+            let gate_files = summary::take_gates();
+            let format = args.format.clone();
+
+            if args.record_run.is_some() || replay.is_some() {
+                let schemas = check_schema_artifacts(&args, schema_document.as_ref());
+                let inputs = provenance::input_artifacts(&args.inputs);
+                if let Some(record) = &replay {
+                    for difference in run_record::replay_drift(record, env!("CARGO_PKG_VERSION"), &schemas, &inputs) {
+                        eprintln!("Replay differs from the recorded run: {}", difference);
+                    }
                 }
-            },
-            "results": []
-        }]
-    });
-    
-    Ok(serde_json::to_string_pretty(&minimal_sarif)?)
-}
-
-fn main() -> Result<()> {
-    let cli = Cli::parse();
-    let mut has_errors = false;
-
-    match cli.cmd {
-        Cmd::Check {
-            inputs,
-            schema,
-            format,
-        } => {
-            let compiled_schema = match load_and_compile_schema(&schema) {
-                Ok(s) => s,
-                Err(e) => {
-                    eprintln!("{:?}", miette::Report::new(e));
-                    std::process::exit(1);
+                if let Some(path) = &args.record_run {
+                    let record = run_record::RunRecord {
+                        tool_version: env!("CARGO_PKG_VERSION").to_string(),
+                        args: recorded_args.clone(),
+                        cwd: std::env::current_dir().unwrap_or_default(),
+                        env: run_record::recorded_env(),
+                        schemas,
+                        inputs,
+                    };
+                    match run_record::write_record(path, &record) {
+                        Ok(()) => progress::line(format_args!("Run recorded to {}", path.display())),
+                        Err(e) => {
+                            eprintln!("{:?}", miette::Report::new(e));
+                            has_errors = true;
+                        }
+                    }
                 }
-            };
-            println!("Validating inputs against schema {:?} (output format: {})", schema, format);
-            println!("Schema loaded and compiled successfully.");
+            }
 
-            match validate_inputs(inputs, &compiled_schema) {
+            let mut run_status;
+            let mut top_diagnostics = Vec::new();
+            let mut reported_diagnostics = Vec::new();
+            match validation_result {
                 Ok(collected_errors) => {
-                    if !collected_errors.is_empty() {
-                        has_errors = true;
-                        match format.as_str() {
-                            "json" => {
-                                let printable_errors: Vec<validation::PrintableError> = collected_errors
-                                    .iter()
-                                    .map(|e| e.into())
-                                    .collect();
-                                match serde_json::to_string_pretty(&printable_errors) {
-                                    Ok(json_output) => println!("{}", json_output),
-                                    Err(e) => {
-                                        eprintln!("Failed to serialize errors to JSON: {}", e);
-                                        for err in collected_errors {
-                                            eprintln!("{:?}", miette::Report::new(err));
-                                        }
+                    // Bad examples are settled first, so --fix never "repairs" one
+                    let mut collected_errors = match args.apply_expectations(collected_errors, &project.expect_invalid, &options) {
+                        Ok(errors) => errors,
+                        Err(message) => {
+                            eprintln!("{}", message);
+                            write_check_status(&args, &status::RunStatus::aborted(2, message));
+                            exit(2);
+                        }
+                    };
+                    if args.fix && (args.diff || args.fix_check) {
+                        match fix::plan_fixes(&collected_errors) {
+                            Ok(planned) => {
+                                for p in &planned {
+                                    if args.diff {
+                                        print!("{}", fix::unified_diff(p));
+                                    } else {
+                                        progress::line(format_args!("Would fix {} issue(s) in {}", p.count, p.path.display()));
                                     }
                                 }
+                                if args.fix_check && !planned.is_empty() {
+                                    has_errors = true;
+                                }
+                            }
+                            Err(e) => {
+                                eprintln!("Failed to plan fixes: {}", e);
+                                has_errors = true;
+                            }
+                        }
+                    } else if let (true, Some(dir)) = (args.fix, &args.fix_out) {
+                        match fix::write_patches(&collected_errors, dir) {
+                            Ok(written) => {
+                                for (patch, count) in &written {
+                                    progress::line(format_args!("Wrote {} fix(es) to {}", count, patch.display()));
+                                }
                             }
-                            "sarif" => {
-                                match errors_to_sarif(&collected_errors) {
-                                    Ok(sarif_output) => println!("{}", sarif_output),
-                                    Err(e) => {
-                                        eprintln!("Failed to generate SARIF output: {}", e);
-                                        for err in collected_errors {
-                                            eprintln!("{:?}", miette::Report::new(err));
+                            Err(e) => {
+                                eprintln!("Failed to write patches to {}: {}", dir.display(), e);
+                                has_errors = true;
+                            }
+                        }
+                    } else if args.fix {
+                        if !args.allow_dirty {
+                            let targets: Vec<_> = fix::collect_fixes(&collected_errors).into_keys().collect();
+                            let dirty = git_guard::dirty_files(&targets);
+                            if !dirty.is_empty() {
+                                let message = git_guard::refusal(&dirty);
+                                eprintln!("{}", message);
+                                write_check_status(&args, &status::RunStatus::aborted(2, message));
+                                exit(2);
+                            }
+                        }
+                        match fix::apply_fixes(&collected_errors) {
+                            Ok(fixed) => {
+                                for (path, count) in &fixed {
+                                    progress::line(format_args!("Fixed {} issue(s) in {}", count, path.display()));
+                                    for error in collected_errors.iter().filter(|e| fix::fix_for(e).is_some_and(|f| &f.path == path)) {
+                                        if let Some(change) = fix::describe_change(error) {
+                                            progress::line(format_args!("  {}", change));
                                         }
                                     }
                                 }
+                                if let Err(e) = args.write_fix_summary(&fix::summarize(&collected_errors, &fixed)) {
+                                    eprintln!("{}", e);
+                                    has_errors = true;
+                                }
+                                let fixed_paths: Vec<_> = fixed.into_iter().map(|(path, _)| path).collect();
+                                collected_errors.retain(|e| !(fix::fix_for(e).is_some_and(|f| fixed_paths.contains(&f.path))));
                             }
-                            _ => {
-                                println!("\n--- Validation Summary ---");
-                                for err in collected_errors {
+                            Err(e) => {
+                                eprintln!("Failed to apply fixes: {}", e);
+                                has_errors = true;
+                            }
+                        }
+                    }
+                    if args.check_stamps {
+                        let digest = check_schema_artifacts(&args, schema_document.as_ref()).into_iter().next().and_then(|a| a.sha256);
+                        for artifact in provenance::input_artifacts(&args.inputs) {
+                            collected_errors.extend(stamp::check_stamp(std::path::Path::new(&artifact.uri), digest.as_deref()));
+                        }
+                    }
+                    match project_rule_diagnostics(&project, &options) {
+                        Ok(found) => collected_errors.extend(found),
+                        Err(message) => {
+                            eprintln!("{}", message);
+                            write_check_status(&args, &status::RunStatus::aborted(2, message));
+                            exit(2);
+                        }
+                    }
+                    collected_errors = match args.apply_suppressions(collected_errors) {
+                        Ok(kept) => kept,
+                        Err(message) => {
+                            eprintln!("{}", message);
+                            write_check_status(&args, &status::RunStatus::aborted(2, message));
+                            exit(2);
+                        }
+                    };
+                    collected_errors = severity::apply(&severities, collected_errors);
+                    if args.stamp && !stamp_inputs(&args, &project.parsers, schema_document.as_ref(), &collected_errors) {
+                        has_errors = true;
+                    }
+                    collected_errors = source_map::map_to_templates(collected_errors);
+                    // With budgets, errors only fail the run once their directory is over its allowance
+                    let budget_report = (!project.budget.is_empty())
+                        .then(|| project_config::evaluate_budgets(&project.budget, &collected_errors));
+                    let ratchet_result = match &args.ratchet {
+                        Some(path) => match ratchet::apply_ratchet(path, &collected_errors) {
+                            Ok(result) => Some(result),
+                            Err(message) => {
+                                eprintln!("{}", message);
+                                write_check_status(&args, &status::RunStatus::aborted(2, message));
+                                exit(2);
+                            }
+                        },
+                        None => None,
+                    };
+                    let failing = match (&ratchet_result, &budget_report) {
+                        // Recorded errors are tolerated; only regressions (and areas over budget) fail
+                        (Some((regressions, _)), report) => {
+                            !regressions.is_empty() || report.as_ref().is_some_and(|r| r.usage.iter().any(|u| u.exceeded()))
+                        }
+                        (None, Some(report)) => report.fails(),
+                        // Warnings (e.g. renamed properties) are reported but do not fail the run
+                        (None, None) => collected_errors.iter().any(|e| e.level() == error::Severity::Error),
+                    };
+                    if failing {
+                        has_errors = true;
+                        if let Some(path) = &args.debug_bundle {
+                            match write_debug_bundle(&args, path, &given_inputs, &recorded_args, schema_document.as_ref(), &collected_errors) {
+                                Ok(manifest) => progress::line(format_args!("Debug bundle with {} file(s) written to {}", manifest.files.len(), path.display())),
+                                Err(e) => eprintln!("{:?}", miette::Report::new(e)),
+                            }
+                        }
+                    }
+                    run_status = status::RunStatus::from_diagnostics(&collected_errors);
+                    if budget_report.is_some() || ratchet_result.is_some() {
+                        run_status.outcome = if failing { status::Outcome::Failed } else { status::Outcome::Passed };
+                        run_status.exit_code = i32::from(failing);
+                    }
+                    top_diagnostics = notify::top_diagnostics(&collected_errors, args.notify_top);
+                    if !project.report.hooks.is_empty() {
+                        reported_diagnostics = collected_errors.iter().map(validation::PrintableError::from).collect();
+                    }
+                    let provenance = args
+                        .provenance
+                        .then(|| check_provenance(&args, schema_document.as_ref(), started_at));
+                    let gates = summary::gate_breakdown(&gate_files, &args.gate_name(), &collected_errors);
+                    let summary = args.summary.then(|| summary::RunSummary {
+                        gates: gates.clone(),
+                        ..summary::RunSummary::new(&args.inputs, &options, &collected_errors, started_at.elapsed().unwrap_or_default())
+                    });
+                    let report_schema = args.report_schema_uri();
+                    if !collected_errors.is_empty() {
+                        match render_report(&format, args.template.as_deref(), &collected_errors, report_schema.as_deref(), provenance.as_ref(), summary.as_ref()) {
+                            Some(Ok(report)) => {
+                                let report = match &streamed {
+                                    Some(streamed) => streamed.remainder(&report),
+                                    None => report,
+                                };
+                                if let Err(e) = emit_report(&args, &report) {
+                                    eprintln!("{:?}", miette::Report::new(e));
+                                    has_errors = true;
+                                }
+                            }
+                            Some(Err(e)) => {
+                                eprintln!("Failed to generate {} output: {}", format, e);
+                                for err in grouping::group_related(collected_errors) {
                                     eprintln!("{:?}", miette::Report::new(err));
                                 }
                             }
+                            None => {
+                                println!("\n--- Validation Summary ---");
+                                eprint!("{}", args.render_human(collected_errors));
+                            }
                         }
                     } else {
-                        if format == "json" {
-                            println!("[]");
-                        } else if format == "sarif" {
-                            // Empty SARIF report for no errors
-                            match errors_to_sarif(&[]) {
-                                Ok(sarif_output) => println!("{}", sarif_output),
-                                Err(e) => eprintln!("Failed to generate SARIF output: {}", e),
+                        match render_report(&format, args.template.as_deref(), &[], report_schema.as_deref(), provenance.as_ref(), summary.as_ref()) {
+                            Some(Ok(report)) => {
+                                if let Err(e) = emit_report(&args, &report) {
+                                    eprintln!("{:?}", miette::Report::new(e));
+                                    has_errors = true;
+                                }
+                            }
+                            Some(Err(e)) => eprintln!("Failed to generate {} output: {}", format, e),
+                            None => {
+                                println!("\n--- Validation Summary ---");
+                                println!("All processed files are valid!");
                             }
-                        } else {
-                            println!("\n--- Validation Summary ---");
-                            println!("All processed files are valid!");
+                        }
+                    }
+                    if let Some(report) = &budget_report {
+                        report.render_human().lines().for_each(progress::line);
+                    }
+                    if let Some((_, lines)) = &ratchet_result {
+                        lines.iter().for_each(progress::line);
+                    }
+                    if format == "human" {
+                        match summary {
+                            Some(summary) => print!("{}", summary.render_human()),
+                            None if !gates.is_empty() => print!("{}", summary::render_gates(&gates)),
+                            None => {}
                         }
                     }
                 }
                 Err(fatal_err) => {
+                    run_status = status::RunStatus::aborted(1, fatal_err.to_string());
                     eprintln!("{:?}", miette::Report::new(fatal_err));
                     has_errors = true;
                 }
             }
+            if !project.report.hooks.is_empty()
+                && report_hooks::run_hooks(&project.report.hooks, &report_hooks::hook_payload(&run_status, &reported_diagnostics))
+            {
+                has_errors = true;
+            }
+            // Failures after validation (e.g. an unwritable report) still fail the run
+            if has_errors && run_status.outcome == status::Outcome::Passed {
+                run_status.outcome = status::Outcome::Error;
+                run_status.exit_code = 1;
+            }
+            write_check_status(&args, &run_status);
+            notify_check(&args, &run_status, &top_diagnostics);
         }
-        Cmd::Scaffold { crate_path, out } => {
-            println!(
-                "Would scaffold schema from crate {:?} into file {:?}",
-                crate_path, out
-            );
+        Cmd::Merge { layers, strategy, out } => {
+            let strategy = config_merge::MergeStrategy::parse(&strategy).unwrap_or_default();
+            let loaded = match layers.iter().map(|path| config_merge::load_layer(path)).collect::<Result<Vec<_>, _>>() {
+                Ok(loaded) => loaded,
+                Err(message) => {
+                    eprintln!("{}", message);
+                    exit(2);
+                }
+            };
+            match config_merge::merge_layers(&loaded, strategy) {
+                Ok(merged) => match config_merge::render_merged(&merged, out.as_deref()) {
+                    Ok(rendered) => match &out {
+                        Some(path) => {
+                            if let Err(e) = paths::check_writable(path).and_then(|()| std::fs::write(path, rendered)) {
+                                eprintln!("Failed to write {}: {}", path.display(), e);
+                                has_errors = true;
+                            }
+                        }
+                        None => print!("{}", rendered),
+                    },
+                    Err(e) => {
+                        eprintln!("Failed to render the merged config: {}", e);
+                        has_errors = true;
+                    }
+                },
+                Err(conflicts) => {
+                    for conflict in conflicts {
+                        eprintln!("{:?}", miette::Report::new(conflict));
+                    }
+                    has_errors = true;
+                }
+            }
+        }
+        Cmd::Normalize { input, format, out } => {
+            let Some(input_format) = normalize::format_for(&input) else {
+                eprintln!("Cannot normalize {}: only json, yaml and toml files can be read", input.display());
+                exit(2);
+            };
+            let content = match std::fs::read_to_string(&input) {
+                Ok(content) => content,
+                Err(e) => {
+                    eprintln!("Cannot read {}: {}", input.display(), e);
+                    exit(2);
+                }
+            };
+            let format = format.as_deref().unwrap_or(input_format);
+            match normalize::parse(&content, input_format).and_then(|documents| normalize::normalize(&documents, format)) {
+                Ok(canonical) => match &out {
+                    Some(path) => {
+                        if let Err(e) = paths::check_writable(path).and_then(|()| std::fs::write(path, canonical)) {
+                            eprintln!("Failed to write {}: {}", path.display(), e);
+                            has_errors = true;
+                        }
+                    }
+                    None => print!("{}", canonical),
+                },
+                Err(e) => {
+                    eprintln!("Cannot normalize {}: {}", input.display(), e);
+                    has_errors = true;
+                }
+            }
+        }
+        Cmd::Render { input, schema, commented, out } => {
+            let Some(layout) = descriptions::Layout::for_path(&input) else {
+                eprintln!("Cannot render {}: only yaml and toml examples carry comments", input.display());
+                exit(2);
+            };
+            let content = match std::fs::read_to_string(&input) {
+                Ok(content) => content,
+                Err(e) => {
+                    eprintln!("Cannot read {}: {}", input.display(), e);
+                    exit(2);
+                }
+            };
+            match schema::load_schema_json(&schema) {
+                Ok(schema_json) => {
+                    let rendered = descriptions::render(&content, layout, &schema_json, commented);
+                    match &out {
+                        Some(path) => {
+                            if let Err(e) = paths::check_writable(path).and_then(|()| std::fs::write(path, rendered)) {
+                                eprintln!("Failed to write {}: {}", path.display(), e);
+                                has_errors = true;
+                            }
+                        }
+                        None => print!("{}", rendered),
+                    }
+                }
+                Err(e) => {
+                    eprintln!("{:?}", miette::Report::new(e));
+                    has_errors = true;
+                }
+            }
+        }
+        Cmd::Fixtures { schema, out, format } => {
+            let suite = schema::load_schema_json(&schema).and_then(|schema_json| {
+                fixtures::check_fixtures(&schema_json, &schema.to_string_lossy(), fixtures::fixture_suite(&schema_json))
+            });
+            match suite {
+                Ok((accepted, rejected)) => {
+                    for fixture in &accepted {
+                        let path = out.join(format!("{}.{}", fixture.name, format));
+                        let written = config_merge::render_merged(&fixture.value, Some(&path))
+                            .and_then(|content| paths::write_with_parents(&path, content.as_bytes()).map_err(|e| e.to_string()));
+                        if let Err(e) = written {
+                            eprintln!("Failed to write {}: {}", path.display(), e);
+                            has_errors = true;
+                        }
+                    }
+                    println!("Wrote {} fixture(s) to {}", accepted.len(), out.display());
+                    for fixture in rejected {
+                        eprintln!("Skipped fixture {}: the schema rejects the generated instance: {}", fixture.name, fixture.reason);
+                        has_errors = true;
+                    }
+                }
+                Err(e) => {
+                    eprintln!("{:?}", miette::Report::new(e));
+                    has_errors = true;
+                }
+            }
+        }
+        Cmd::Scaffold { crate_path, out, root, check } => match scaffold::scaffold_schema(&crate_path, &root) {
+            Ok(generated) if check => match schema::load_schema_json(&out) {
+                Ok(committed) => {
+                    let drifts = scaffold::schema_drift(&generated, &committed);
+                    if drifts.is_empty() {
+                        println!("{} matches the {} types of {}", out.display(), root, crate_path.display());
+                    } else {
+                        print!("{}", scaffold::render_drift(&drifts, &out.display().to_string()));
+                        eprintln!("{} is out of date: {} difference(s); run `toml-and-jerry scaffold` without --check to regenerate it", out.display(), drifts.len());
+                        has_errors = true;
+                    }
+                }
+                Err(e) => {
+                    eprintln!("{:?}", miette::Report::new(e));
+                    has_errors = true;
+                }
+            },
+            Ok(generated) => {
+                let mut content = serde_json::to_string_pretty(&generated).expect("schemas serialize");
+                content.push('\n');
+                if let Err(e) = paths::check_writable(&out).and_then(|()| std::fs::write(&out, content)) {
+                    eprintln!("Failed to write {}: {}", out.display(), e);
+                    has_errors = true;
+                }
+            }
+            Err(message) => {
+                eprintln!("Cannot scaffold a schema: {}", message);
+                exit(2);
+            }
+        },
+        Cmd::VerifyReport { report, key, signature } => {
+            match signing::verify_report_file(&report, signature.as_deref(), &key) {
+                Ok(true) => println!("Signature OK: {}", report.display()),
+                Ok(false) => {
+                    eprintln!("Signature does NOT match {}: the report was modified or signed with another key", report.display());
+                    has_errors = true;
+                }
+                Err(e) => {
+                    eprintln!("{:?}", miette::Report::new(e));
+                    has_errors = true;
+                }
+            }
+        }
+        Cmd::Capabilities { format } => {
+            let capabilities = capabilities::capabilities();
+            match format.as_str() {
+                "json" => match serde_json::to_string_pretty(&capabilities) {
+                    Ok(json_output) => println!("{}", json_output),
+                    Err(e) => {
+                        eprintln!("Failed to serialize capabilities to JSON: {}", e);
+                        has_errors = true;
+                    }
+                },
+                _ => print!("{}", capabilities::render_human(&capabilities)),
+            }
+        }
+        Cmd::Duplicates { inputs, similarity, min_size, min_files, format } => {
+            let options = duplicates::DuplicateOptions { similarity: similarity.clamp(0.0, 1.0), min_size, min_files };
+            let groups = duplicates::find_duplicates(&duplicates::input_files(&inputs), &options, &yaml::TagPolicy::default());
+            match format.as_str() {
+                "json" => match serde_json::to_string_pretty(&groups) {
+                    Ok(json_output) => println!("{}", json_output),
+                    Err(e) => {
+                        eprintln!("Failed to serialize duplicates to JSON: {}", e);
+                        has_errors = true;
+                    }
+                },
+                _ => print!("{}", duplicates::render_human(&groups)),
+            }
+        }
+        Cmd::MergeReports { reports, format, output, mut strip_prefix } => {
+            if let Ok(cwd) = std::env::current_dir() {
+                strip_prefix.push(cwd.to_string_lossy().into_owned());
+            }
+            match reports.iter().map(|path| merge::load_report(path)).collect::<Result<Vec<_>, _>>() {
+                Ok(loaded) => {
+                    let merged = merge::merge_reports(loaded, &strip_prefix);
+                    let rendered = match format.as_str() {
+                        "sarif" => serde_json::to_string_pretty(&merge::to_sarif(&merged)),
+                        "csv" => Ok(merge::to_csv(&merged).trim_end().to_string()),
+                        _ => serde_json::to_string_pretty(&report_schema::JsonReport::from_printable(
+                            None,
+                            merged.iter().map(|e| e.diagnostic.clone()).collect(),
+                        )),
+                    };
+                    match (rendered, &output) {
+                        (Ok(report), Some(path)) => match paths::check_writable(path).and_then(|()| std::fs::write(path, format!("{}\n", report))) {
+                            Ok(()) => println!("Merged {} diagnostic(s) from {} report(s) into {}", merged.len(), reports.len(), path.display()),
+                            Err(e) => {
+                                eprintln!("Failed to write {}: {}", path.display(), e);
+                                has_errors = true;
+                            }
+                        },
+                        (Ok(report), None) => println!("{}", report),
+                        (Err(e), _) => {
+                            eprintln!("Failed to serialize merged report: {}", e);
+                            has_errors = true;
+                        }
+                    }
+                }
+                Err(message) => {
+                    eprintln!("{}", message);
+                    has_errors = true;
+                }
+            }
+        }
+        Cmd::Repl { schema, format } => {
+            let compiled = schema::load_schema_json(&schema).and_then(|schema_json| {
+                let validator = schema::compile_schema(&schema_json, &schema.to_string_lossy())?;
+                Ok((schema_json, validator))
+            });
+            match compiled {
+                Ok((schema_json, validator)) => {
+                    // Replies are the only output: no progress lines, and no per-file progress either
+                    progress::quiet();
+                    progress::clear_handler();
+                    let interactive = std::io::IsTerminal::is_terminal(&std::io::stdin());
+                    if interactive {
+                        println!("Validating against {}. :help lists the commands, :quit leaves.", schema.display());
+                    }
+                    let result = repl::Session::new(schema_json, validator, &format)
+                        .and_then(|mut session| repl::run(&mut session, std::io::stdin().lock(), std::io::stdout(), interactive));
+                    if let Err(e) = result {
+                        eprintln!("repl: {}", e);
+                        has_errors = true;
+                    }
+                }
+                Err(e) => {
+                    eprintln!("{:?}", miette::Report::new(e));
+                    has_errors = true;
+                }
+            }
+        }
+        Cmd::Triage { root, format, write } => {
+            let report = triage::triage(&root);
+            match format.as_str() {
+                "json" => match serde_json::to_string_pretty(&report) {
+                    Ok(json_output) => println!("{}", json_output),
+                    Err(e) => {
+                        eprintln!("Failed to serialize triage report to JSON: {}", e);
+                        has_errors = true;
+                    }
+                },
+                _ => {
+                    print!("{}", triage::render_human(&report));
+                    if write.is_none() {
+                        println!("\nSuggested {}:\n\n{}", project_config::PROJECT_CONFIG_FILE, report.suggested_config);
+                    }
+                }
+            }
+            if let Some(dir) = &write {
+                match triage::write_suggestions(&report, dir) {
+                    Ok(written) => {
+                        for path in written {
+                            println!("Wrote {}", path.display());
+                        }
+                    }
+                    Err(message) => {
+                        eprintln!("{}", message);
+                        has_errors = true;
+                    }
+                }
+            }
+        }
+        Cmd::SchemaOfReport { out } => {
+            let rendered = serde_json::to_string_pretty(&report_schema::report_schema()).unwrap_or_default();
+            match &out {
+                Some(path) => {
+                    if let Err(e) = paths::check_writable(path).and_then(|()| std::fs::write(path, rendered + "\n")) {
+                        eprintln!("Failed to write {}: {}", path.display(), e);
+                        has_errors = true;
+                    }
+                }
+                None => println!("{}", rendered),
+            }
+        }
+        Cmd::Schema { action: SchemaCmd::Keys { schema, format } } => match schema::load_schema_json(&schema) {
+            Ok(schema_json) => {
+                let keys = schema_keys::schema_keys(&schema_json, &schema.to_string_lossy());
+                match format.as_str() {
+                    "json" => match serde_json::to_string_pretty(&keys) {
+                        Ok(json_output) => println!("{}", json_output),
+                        Err(e) => {
+                            eprintln!("Failed to serialize schema keys to JSON: {}", e);
+                            has_errors = true;
+                        }
+                    },
+                    _ => print!("{}", schema_keys::render_human(&keys)),
+                }
+            }
+            Err(e) => {
+                eprintln!("{:?}", miette::Report::new(e));
+                has_errors = true;
+            }
+        },
+        Cmd::Schema { action: SchemaCmd::Envdoc { schema, prefix, separator, format } } => match schema::load_schema_json(&schema) {
+            Ok(schema_json) => {
+                let keys = schema_keys::schema_keys(&schema_json, &schema.to_string_lossy());
+                let doc = envdoc::env_doc(&keys, &prefix, &separator);
+                match format.as_str() {
+                    "json" => match serde_json::to_string_pretty(&doc) {
+                        Ok(json_output) => println!("{}", json_output),
+                        Err(e) => {
+                            eprintln!("Failed to serialize environment variables to JSON: {}", e);
+                            has_errors = true;
+                        }
+                    },
+                    _ => print!("{}", envdoc::render_markdown(&doc)),
+                }
+            }
+            Err(e) => {
+                eprintln!("{:?}", miette::Report::new(e));
+                has_errors = true;
+            }
+        },
+        Cmd::Schema { action: SchemaCmd::Usage { schema, inputs, format } } => match schema::load_schema_json(&schema) {
+            Ok(schema_json) => {
+                let files = duplicates::input_files(&inputs);
+                let usage = schema_usage::schema_usage(&schema_json, &schema.to_string_lossy(), &files, &yaml::TagPolicy::default());
+                match format.as_str() {
+                    "json" => match serde_json::to_string_pretty(&usage) {
+                        Ok(json_output) => println!("{}", json_output),
+                        Err(e) => {
+                            eprintln!("Failed to serialize schema usage to JSON: {}", e);
+                            has_errors = true;
+                        }
+                    },
+                    _ => print!("{}", schema_usage::render_human(&usage)),
+                }
+            }
+            Err(e) => {
+                eprintln!("{:?}", miette::Report::new(e));
+                has_errors = true;
+            }
+        },
+        Cmd::Schema { action: SchemaCmd::Unused { schema, format } } => match schema::load_schema_json(&schema) {
+            Ok(schema_json) => {
+                // Labels point into the file as written; a remote schema is shown re-serialized
+                let content = std::fs::read_to_string(&schema)
+                    .unwrap_or_else(|_| serde_json::to_string_pretty(&schema_json).unwrap_or_default());
+                let unused = schema_unused::unused_definition_diagnostics(&schema_json, &schema.to_string_lossy(), &content);
+                match render_report(&format, None, &unused, Some(&schema.to_string_lossy()), None, None) {
+                    Some(Ok(report)) => {
+                        if !report.is_empty() {
+                            println!("{}", report);
+                        }
+                    }
+                    Some(Err(e)) => {
+                        eprintln!("Failed to render the report: {}", e);
+                        has_errors = true;
+                    }
+                    None => {
+                        let count = unused.len();
+                        for warning in unused {
+                            eprintln!("{:?}", miette::Report::new(warning));
+                        }
+                        println!("{} unused definition(s) in {}", count, schema.display());
+                    }
+                }
+            }
+            Err(e) => {
+                eprintln!("{:?}", miette::Report::new(e));
+                has_errors = true;
+            }
+        },
+        Cmd::Schema { action: SchemaCmd::Lint { schema, format } } => match schema::load_schema_json(&schema) {
+            Ok(schema_json) => {
+                // Labels point into the file as written; a remote schema is shown re-serialized
+                let content = std::fs::read_to_string(&schema)
+                    .unwrap_or_else(|_| serde_json::to_string_pretty(&schema_json).unwrap_or_default());
+                let display = schema.to_string_lossy();
+                let mut problems = schema_patterns::unsafe_pattern_diagnostics(&schema_json, &display, &content);
+                problems.extend(schema_examples::invalid_example_diagnostics(&schema_json, &display, &content));
+                if problems.iter().any(|p| !p.is_warning()) {
+                    has_errors = true;
+                }
+                match render_report(&format, None, &problems, Some(&schema.to_string_lossy()), None, None) {
+                    Some(Ok(report)) => {
+                        if !report.is_empty() {
+                            println!("{}", report);
+                        }
+                    }
+                    Some(Err(e)) => {
+                        eprintln!("Failed to render the report: {}", e);
+                        has_errors = true;
+                    }
+                    None => {
+                        let count = problems.len();
+                        for problem in problems {
+                            eprintln!("{:?}", miette::Report::new(problem));
+                        }
+                        println!("{} problem(s) in {}", count, schema.display());
+                    }
+                }
+            }
+            Err(e) => {
+                eprintln!("{:?}", miette::Report::new(e));
+                has_errors = true;
+            }
+        },
+        Cmd::Schema { action: SchemaCmd::Extract { schema, pointer, out } } => match schema::load_schema_json(&schema) {
+            Ok(schema_json) => match schema_extract::extract_fragment(&schema_json, &pointer) {
+                Ok(fragment) => {
+                    let rendered = serde_json::to_string_pretty(&fragment).unwrap_or_default();
+                    match &out {
+                        Some(path) => {
+                            if let Err(e) = paths::check_writable(path).and_then(|()| std::fs::write(path, rendered + "\n")) {
+                                eprintln!("Failed to write {}: {}", path.display(), e);
+                                has_errors = true;
+                            } else {
+                                println!("Extracted {} from {} into {}", pointer, schema.display(), path.display());
+                            }
+                        }
+                        None => println!("{}", rendered),
+                    }
+                }
+                Err(message) => {
+                    eprintln!("{}", message);
+                    has_errors = true;
+                }
+            },
+            Err(e) => {
+                eprintln!("{:?}", miette::Report::new(e));
+                has_errors = true;
+            }
+        },
+        Cmd::Schema { action: SchemaCmd::SyncDescriptions { schema, example, out } } => {
+            let Some(layout) = descriptions::Layout::for_path(&example) else {
+                eprintln!("{} has no comments to sync; the example must be yaml or toml", example.display());
+                exit(2);
+            };
+            let content = match std::fs::read_to_string(&example) {
+                Ok(content) => content,
+                Err(e) => {
+                    eprintln!("Cannot read {}: {}", example.display(), e);
+                    exit(2);
+                }
+            };
+            match schema::load_schema_json(&schema) {
+                Ok(mut schema_json) => {
+                    let outcome = descriptions::sync_descriptions(&mut schema_json, &descriptions::key_comments(&content, layout));
+                    for path in &outcome.updated {
+                        eprintln!("updated   {}", path);
+                    }
+                    for path in &outcome.unknown {
+                        eprintln!("not in schema  {}", path);
+                    }
+                    eprintln!("{} description(s) updated, {} already in sync", outcome.updated.len(), outcome.unchanged.len());
+                    let rendered = serde_json::to_string_pretty(&schema_json).unwrap_or_default();
+                    match &out {
+                        Some(path) => {
+                            if let Err(e) = paths::check_writable(path).and_then(|()| std::fs::write(path, rendered + "\n")) {
+                                eprintln!("Failed to write {}: {}", path.display(), e);
+                                has_errors = true;
+                            }
+                        }
+                        None => println!("{}", rendered),
+                    }
+                }
+                Err(e) => {
+                    eprintln!("{:?}", miette::Report::new(e));
+                    has_errors = true;
+                }
+            }
+        }
+        Cmd::Schema { action: SchemaCmd::Check { schema, against_meta, format } } => {
+            let drafts = match against_meta
+                .iter()
+                .map(|name| {
+                    schema::parse_draft(name)
+                        .ok_or_else(|| format!("Unknown --against-meta {:?}; expected 4, 6, 7, 2019-09 or 2020-12", name))
+                })
+                .collect::<Result<Vec<_>, _>>()
+            {
+                Ok(drafts) => drafts,
+                Err(message) => {
+                    eprintln!("{}", message);
+                    exit(2);
+                }
+            };
+            match schema::load_schema_json(&schema) {
+                Ok(schema_json) => {
+                    let check = meta_check::check_against_meta(&schema_json, &schema.to_string_lossy(), &drafts);
+                    match format.as_str() {
+                        "json" => match serde_json::to_string_pretty(&check) {
+                            Ok(json_output) => println!("{}", json_output),
+                            Err(e) => {
+                                eprintln!("Failed to serialize meta-schema check to JSON: {}", e);
+                                has_errors = true;
+                            }
+                        },
+                        _ => print!("{}", meta_check::render_human(&check)),
+                    }
+                    if !check.all_valid() {
+                        has_errors = true;
+                    }
+                }
+                Err(e) => {
+                    eprintln!("{:?}", miette::Report::new(e));
+                    has_errors = true;
+                }
+            }
         }
+        Cmd::Cache { action } => match action {
+            CacheCmd::Ls => match paths::list_cache_entries() {
+                Ok(entries) => {
+                    if entries.is_empty() {
+                        println!("Cache is empty ({})", paths::cache_dir().display());
+                    }
+                    let mut total = 0;
+                    for entry in &entries {
+                        total += entry.size;
+                        println!("{:>10}  {}", entry.size, entry.path.display());
+                    }
+                    if !entries.is_empty() {
+                        println!("{} file(s), {} bytes", entries.len(), total);
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Failed to list cache: {}", e);
+                    has_errors = true;
+                }
+            },
+            CacheCmd::Clear => match paths::clear_cache() {
+                Ok(freed) => println!("Cleared {} ({} bytes freed)", paths::cache_dir().display(), freed),
+                Err(e) => {
+                    eprintln!("Failed to clear cache: {}", e);
+                    has_errors = true;
+                }
+            },
+            CacheCmd::Path => {
+                println!("cache: {}", paths::cache_dir().display());
+                println!("data: {}", paths::data_dir().display());
+            }
+        },
     }
 
     if has_errors {
-        std::process::exit(1);
+        exit(1);
     }
     Ok(())
 }
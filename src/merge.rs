@@ -0,0 +1,311 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+use serde_json::{json, Value as JsonValue};
+
+use crate::capabilities;
+use crate::error::{AppError, Category};
+use crate::provenance;
+use crate::template;
+use crate::validation::PrintableError;
+use crate::violation::Violation;
+
+/// One diagnostic read back from a report, with the SARIF level it had (or its code's default).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReportEntry {
+    pub diagnostic: PrintableError,
+    /// `error`, `warning` or `note`
+    pub level: String,
+}
+
+fn default_level(rule_id: &str) -> String {
+    capabilities::diagnostic_code(rule_id).map_or("error", |d| d.default_severity).to_string()
+}
+
+fn from_sarif_result(result: &JsonValue) -> ReportEntry {
+    let location = &result["locations"][0]["physicalLocation"];
+    let text = |value: &JsonValue| value.as_str().map(str::to_string);
+    let number = |value: &JsonValue| value.as_u64().map(|n| n as usize);
+    let rule_id = text(&result["ruleId"]).unwrap_or_else(|| "N/A".to_string());
+    let level = text(&result["level"]).unwrap_or_else(|| default_level(&rule_id));
+    ReportEntry {
+        diagnostic: PrintableError {
+            severity: serde_json::from_value(json!(level)).ok(),
+            category: serde_json::from_value(result["properties"]["category"].clone()).ok(),
+            file_path: text(&location["artifactLocation"]["uri"]).unwrap_or_default(),
+            error_type: text(&result["properties"]["errorType"]).unwrap_or_else(|| "UnknownError".to_string()),
+            message: text(&result["message"]["text"]).unwrap_or_default(),
+            line: number(&location["region"]["startLine"]),
+            column: number(&location["region"]["startColumn"]),
+            json_path: text(&result["properties"]["jsonPath"]),
+            rule_id,
+            snippet: None,
+            violation: result["properties"]["keyword"].as_str().map(|keyword| Violation {
+                keyword: keyword.to_string(),
+                expected: result["properties"].get("expected").cloned(),
+                actual: result["properties"].get("actual").cloned(),
+            }),
+        },
+        level,
+    }
+}
+
+/// A diagnostic from this run as a report entry, positioned at its first label.
+pub fn report_entry(error: &AppError) -> ReportEntry {
+    ReportEntry { level: error.level().sarif_level().to_string(), diagnostic: PrintableError::from(error) }
+}
+
+/// Like `report_entry`, with the column counted in display cells (see
+/// `template::display_line_column`), for formats read by editors.
+pub fn display_entry(error: &AppError) -> ReportEntry {
+    let mut entry = report_entry(error);
+    if let Some((_, column)) = template::display_line_column(error) {
+        entry.diagnostic.column = Some(column);
+    }
+    entry
+}
+
+/// Reads a `check` report: a json array, a json report with provenance (`{"errors": [...]}`)
+/// or SARIF (results of every run).
+pub fn load_report(path: &Path) -> Result<Vec<ReportEntry>, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("Cannot read report {}: {}", path.display(), e))?;
+    let report: JsonValue = serde_json::from_str(&content).map_err(|e| format!("Invalid report {}: {}", path.display(), e))?;
+    if let Some(runs) = report.get("runs").and_then(JsonValue::as_array) {
+        let results = runs.iter().filter_map(|run| run["results"].as_array()).flatten();
+        return Ok(results.map(from_sarif_result).collect());
+    }
+    let errors = report.get("errors").cloned().unwrap_or(report);
+    let diagnostics: Vec<PrintableError> =
+        serde_json::from_value(errors).map_err(|e| format!("Invalid report {}: {}", path.display(), e))?;
+    Ok(diagnostics
+        .into_iter()
+        .map(|mut diagnostic| {
+            let level = diagnostic.severity.map_or_else(|| default_level(&diagnostic.rule_id), |s| s.sarif_level().to_string());
+            // Older reports leave the severity out; take it from the level, as SARIF results do
+            diagnostic.severity = diagnostic.severity.or_else(|| serde_json::from_value(json!(level)).ok());
+            ReportEntry { level, diagnostic }
+        })
+        .collect())
+}
+
+/// Makes a report path comparable across runs: drops `file://`, uses `/` separators, removes
+/// the first matching prefix (e.g. each CI machine's checkout directory) and a leading `./`.
+pub fn normalize_path(path: &str, strip_prefixes: &[String]) -> String {
+    let mut path = path.strip_prefix("file://").unwrap_or(path).replace('\\', "/");
+    for prefix in strip_prefixes {
+        let prefix = prefix.replace('\\', "/");
+        if let Some(rest) = path.strip_prefix(prefix.trim_end_matches('/')) {
+            if rest.is_empty() || rest.starts_with('/') {
+                path = rest.trim_start_matches('/').to_string();
+                break;
+            }
+        }
+    }
+    while let Some(rest) = path.strip_prefix("./") {
+        path = rest.to_string();
+    }
+    path
+}
+
+/// Merges the entries of several reports: paths normalized, exact repeats (same file, code,
+/// position, instance path and message) dropped, ordered by file and position.
+pub fn merge_reports(reports: Vec<Vec<ReportEntry>>, strip_prefixes: &[String]) -> Vec<ReportEntry> {
+    let mut seen = BTreeSet::new();
+    let mut merged = Vec::new();
+    for mut entry in reports.into_iter().flatten() {
+        entry.diagnostic.file_path = normalize_path(&entry.diagnostic.file_path, strip_prefixes);
+        let d = &entry.diagnostic;
+        let key = (d.file_path.clone(), d.rule_id.clone(), d.line, d.column, d.json_path.clone(), d.message.clone());
+        if seen.insert(key) {
+            merged.push(entry);
+        }
+    }
+    merged.sort_by(|a, b| {
+        let key = |e: &ReportEntry| (e.diagnostic.file_path.clone(), e.diagnostic.line, e.diagnostic.column);
+        key(a).cmp(&key(b))
+    });
+    merged
+}
+
+/// SARIF 2.1.0 log with one run holding every entry.
+pub fn to_sarif(entries: &[ReportEntry]) -> JsonValue {
+    let rule_ids: BTreeSet<&str> = entries.iter().map(|e| e.diagnostic.rule_id.as_str()).collect();
+    let rules: Vec<JsonValue> = rule_ids
+        .into_iter()
+        .map(|id| match capabilities::diagnostic_code(id) {
+            Some(code) => json!({
+                "id": id,
+                "shortDescription": { "text": code.description },
+                "defaultConfiguration": { "level": code.default_severity },
+            }),
+            None => json!({ "id": id }),
+        })
+        .collect();
+    let results: Vec<JsonValue> = entries
+        .iter()
+        .map(|entry| {
+            let d = &entry.diagnostic;
+            let mut location = json!({ "artifactLocation": { "uri": d.file_path } });
+            if let Some(line) = d.line {
+                location["region"] = json!({ "startLine": line, "startColumn": d.column.unwrap_or(1) });
+            }
+            // The whole lines around the region, so viewers can show it without the file
+            if let Some(snippet) = &d.snippet {
+                location["contextRegion"] = json!({
+                    "startLine": snippet.start_line,
+                    "endLine": snippet.start_line + snippet.text.lines().count().max(1) - 1,
+                    "snippet": { "text": snippet.text },
+                });
+            }
+            let mut properties = json!({ "errorType": d.error_type });
+            if let Some(json_path) = &d.json_path {
+                properties["jsonPath"] = json!(json_path);
+            }
+            if let Some(category) = d.category {
+                properties["category"] = json!(category);
+            }
+            if let Some(violation) = &d.violation {
+                properties["keyword"] = json!(violation.keyword);
+                if let Some(expected) = &violation.expected {
+                    properties["expected"] = expected.clone();
+                }
+                if let Some(actual) = &violation.actual {
+                    properties["actual"] = actual.clone();
+                }
+            }
+            json!({
+                "ruleId": d.rule_id,
+                "level": entry.level,
+                "message": { "text": d.message },
+                "locations": [{ "physicalLocation": location }],
+                "properties": properties,
+            })
+        })
+        .collect();
+    json!({
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "toml-and-jerry",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": "https://github.com/coryjanowski/toml-and-jerry",
+                    "rules": rules,
+                }
+            },
+            "results": results,
+        }]
+    })
+}
+
+// Quotes a CSV field when it holds a separator, quote or line break (RFC 4180)
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Code Climate engine issues, as a JSON array, for analysis platforms (GitLab code quality
+/// among them) that import that format. The fingerprint hashes the file, code, instance path
+/// and message, so an issue keeps it when lines above it move.
+pub fn to_codeclimate(entries: &[ReportEntry]) -> JsonValue {
+    let issues: Vec<JsonValue> = entries
+        .iter()
+        .map(|entry| {
+            let d = &entry.diagnostic;
+            let category = match d.category {
+                Some(Category::Style) => "Style",
+                _ => "Bug Risk",
+            };
+            let severity = if entry.level == "error" { "major" } else { "minor" };
+            let location = match (d.line, d.column) {
+                (Some(line), Some(column)) => json!({ "path": d.file_path, "positions": { "begin": { "line": line, "column": column } } }),
+                (Some(line), None) => json!({ "path": d.file_path, "lines": { "begin": line } }),
+                _ => json!({ "path": d.file_path, "lines": { "begin": 1 } }),
+            };
+            let identity = [d.file_path.as_str(), &d.rule_id, d.json_path.as_deref().unwrap_or_default(), &d.message].join("\0");
+            json!({
+                "type": "issue",
+                "check_name": d.rule_id,
+                "description": d.message,
+                "categories": [category],
+                "location": location,
+                "severity": severity,
+                "fingerprint": provenance::hex_sha256(identity.as_bytes()),
+            })
+        })
+        .collect();
+    JsonValue::Array(issues)
+}
+
+/// One `file:line:col: rule_id: message` line per entry, as gcc prints them, for editor
+/// quickfix lists. Entries without a position print `file: rule_id: message`.
+pub fn to_compact(entries: &[ReportEntry]) -> String {
+    let mut out = String::new();
+    for entry in entries {
+        let d = &entry.diagnostic;
+        let position = match (d.line, d.column) {
+            (Some(line), Some(column)) => format!(":{}:{}", line, column),
+            (Some(line), None) => format!(":{}", line),
+            _ => String::new(),
+        };
+        let message = d.message.split_whitespace().collect::<Vec<_>>().join(" ");
+        out.push_str(&format!("{}{}: {}: {}\n", d.file_path, position, d.rule_id, message));
+    }
+    out
+}
+
+// Escapes a logging command value the way the Azure Pipelines agent unescapes it
+fn azure_escape(value: &str, property: bool) -> String {
+    let escaped = value.replace('%', "%AZP25").replace('\r', "%0D").replace('\n', "%0A");
+    if property {
+        escaped.replace(';', "%3B").replace(']', "%5D")
+    } else {
+        escaped
+    }
+}
+
+/// One `##vso[task.logissue]` logging command per entry, so Azure Pipelines shows the
+/// diagnostics as issues on the run and inline on the file and line.
+pub fn to_azure(entries: &[ReportEntry]) -> String {
+    let mut out = String::new();
+    for entry in entries {
+        let d = &entry.diagnostic;
+        let kind = if entry.level == "error" { "error" } else { "warning" };
+        let mut properties = vec![format!("type={}", kind), format!("sourcepath={}", azure_escape(&d.file_path, true))];
+        if let Some(line) = d.line {
+            properties.push(format!("linenumber={}", line));
+        }
+        if let Some(column) = d.column {
+            properties.push(format!("columnnumber={}", column));
+        }
+        properties.push(format!("code={}", azure_escape(&d.rule_id, true)));
+        out.push_str(&format!("##vso[task.logissue {};]{}\n", properties.join(";"), azure_escape(&d.message, false)));
+    }
+    out
+}
+
+/// CSV with a header row and one row per entry: file, line, column, severity, category,
+/// rule_id, instance_path, message.
+pub fn to_csv(entries: &[ReportEntry]) -> String {
+    let mut out = String::from("file,line,column,severity,category,rule_id,instance_path,message\n");
+    for entry in entries {
+        let d = &entry.diagnostic;
+        let number = |n: Option<usize>| n.map(|n| n.to_string()).unwrap_or_default();
+        let row = [
+            csv_field(&d.file_path),
+            number(d.line),
+            number(d.column),
+            csv_field(&entry.level),
+            d.category.map(|c| c.as_str().to_string()).unwrap_or_default(),
+            csv_field(&d.rule_id),
+            csv_field(d.json_path.as_deref().unwrap_or_default()),
+            csv_field(&d.message),
+        ];
+        out.push_str(&row.join(","));
+        out.push('\n');
+    }
+    out
+}
@@ -0,0 +1,155 @@
+use std::collections::BTreeSet;
+use jsonschema::Draft;
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+
+use crate::schema;
+
+/// How one schema document fares under one dialect's meta-schema.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DialectResult {
+    /// e.g. `draft-07`, `2020-12`
+    pub dialect: &'static str,
+    pub valid: bool,
+    /// The meta-schema violation, as `pointer: message`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Keywords the document uses that this dialect does not define, so a validator ignores them
+    pub unknown_keywords: Vec<String>,
+}
+
+/// The portability matrix for one schema document.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetaCheck {
+    pub schema: String,
+    pub dialects: Vec<DialectResult>,
+}
+
+impl MetaCheck {
+    /// Valid under every dialect checked.
+    pub fn all_valid(&self) -> bool {
+        self.dialects.iter().all(|d| d.valid)
+    }
+}
+
+const DRAFT4_KEYWORDS: &[&str] = &[
+    "$schema", "$ref", "id", "title", "description", "default", "multipleOf", "maximum", "exclusiveMaximum",
+    "minimum", "exclusiveMinimum", "maxLength", "minLength", "pattern", "additionalItems", "items", "maxItems",
+    "minItems", "uniqueItems", "maxProperties", "minProperties", "required", "additionalProperties",
+    "definitions", "properties", "patternProperties", "dependencies", "enum", "type", "allOf", "anyOf", "oneOf",
+    "not", "format",
+];
+const DRAFT6_ADDED: &[&str] = &["$id", "const", "contains", "propertyNames", "examples"];
+const DRAFT7_ADDED: &[&str] = &["$comment", "if", "then", "else", "readOnly", "writeOnly", "contentMediaType", "contentEncoding"];
+const DRAFT201909_ADDED: &[&str] = &[
+    "$anchor", "$defs", "$vocabulary", "dependentRequired", "dependentSchemas", "unevaluatedItems",
+    "unevaluatedProperties", "maxContains", "minContains", "deprecated", "contentSchema",
+];
+const DRAFT202012_ADDED: &[&str] = &["prefixItems", "$dynamicRef", "$dynamicAnchor"];
+
+// Keywords each dialect defines
+fn keywords(draft: Draft) -> BTreeSet<&'static str> {
+    let mut known: BTreeSet<&str> = DRAFT4_KEYWORDS.iter().copied().collect();
+    if draft == Draft::Draft4 {
+        return known;
+    }
+    known.remove("id");
+    known.extend(DRAFT6_ADDED);
+    if draft == Draft::Draft6 {
+        return known;
+    }
+    known.extend(DRAFT7_ADDED);
+    if draft == Draft::Draft7 {
+        return known;
+    }
+    known.extend(DRAFT201909_ADDED);
+    if draft == Draft::Draft201909 {
+        known.extend(["$recursiveRef", "$recursiveAnchor"]);
+        return known;
+    }
+    known.extend(DRAFT202012_ADDED);
+    known
+}
+
+// Keywords whose value is a map of names to subschemas
+const SCHEMA_MAPS: &[&str] = &["properties", "patternProperties", "definitions", "$defs", "dependentSchemas", "dependencies"];
+// Keywords whose value is a subschema, or a list of them
+const SUBSCHEMAS: &[&str] = &[
+    "items", "additionalItems", "additionalProperties", "not", "if", "then", "else", "contains", "propertyNames",
+    "unevaluatedItems", "unevaluatedProperties", "contentSchema", "allOf", "anyOf", "oneOf", "prefixItems",
+];
+
+// Every keyword used anywhere in the document; `x-` annotations are not keywords
+fn used_keywords(schema: &JsonValue, used: &mut BTreeSet<String>) {
+    let Some(object) = schema.as_object() else { return };
+    for (key, value) in object {
+        if key.starts_with("x-") {
+            continue;
+        }
+        used.insert(key.clone());
+        if SCHEMA_MAPS.contains(&key.as_str()) {
+            for child in value.as_object().into_iter().flat_map(|m| m.values()) {
+                used_keywords(child, used);
+            }
+        } else if SUBSCHEMAS.contains(&key.as_str()) {
+            match value {
+                JsonValue::Array(items) => items.iter().for_each(|item| used_keywords(item, used)),
+                other => used_keywords(other, used),
+            }
+        }
+    }
+}
+
+fn meta_error(schema: &JsonValue, draft: Draft) -> Option<String> {
+    let result = match draft {
+        Draft::Draft4 => jsonschema::draft4::meta::validate(schema),
+        Draft::Draft6 => jsonschema::draft6::meta::validate(schema),
+        Draft::Draft7 => jsonschema::draft7::meta::validate(schema),
+        Draft::Draft201909 => jsonschema::draft201909::meta::validate(schema),
+        _ => jsonschema::draft202012::meta::validate(schema),
+    };
+    result.err().map(|e| {
+        let pointer = e.instance_path.to_string();
+        format!("{}: {}", if pointer.is_empty() { "/" } else { &pointer }, e)
+    })
+}
+
+/// Validates `schema` against the meta-schema of each dialect, in the order given, and lists
+/// the keywords each dialect would ignore.
+pub fn check_against_meta(schema: &JsonValue, source_display: &str, drafts: &[Draft]) -> MetaCheck {
+    let mut used = BTreeSet::new();
+    used_keywords(schema, &mut used);
+    let dialects = drafts
+        .iter()
+        .map(|&draft| {
+            let known = keywords(draft);
+            let error = meta_error(schema, draft);
+            DialectResult {
+                dialect: schema::draft_name(draft),
+                valid: error.is_none(),
+                error,
+                unknown_keywords: used.iter().filter(|k| !known.contains(k.as_str())).cloned().collect(),
+            }
+        })
+        .collect();
+    MetaCheck { schema: source_display.to_string(), dialects }
+}
+
+/// One line per dialect, e.g. `draft-07   valid    ignores: prefixItems`.
+pub fn render_human(check: &MetaCheck) -> String {
+    let mut out = format!("Schema {} against meta-schemas:\n", check.schema);
+    for result in &check.dialects {
+        let mut line = format!("  {:<10} {:<8}", result.dialect, if result.valid { "valid" } else { "invalid" });
+        if let Some(error) = &result.error {
+            line.push_str(&format!(" {}", error));
+        }
+        if !result.unknown_keywords.is_empty() {
+            line.push_str(&format!(" ignores: {}", result.unknown_keywords.join(", ")));
+        }
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out
+}
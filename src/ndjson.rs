@@ -0,0 +1,41 @@
+use std::ops::Range;
+use serde_json::Value as JsonValue;
+
+use crate::config_merge;
+
+/// One non-blank line of a `.ndjson` / `.jsonl` file.
+#[derive(Debug)]
+pub struct Record {
+    /// 1-based line number
+    pub line: usize,
+    /// Byte range of the line in the file, without its line break
+    pub span: Range<usize>,
+    pub value: Result<JsonValue, serde_json::Error>,
+}
+
+/// The records of a JSON Lines file, one per non-blank line. Each line is parsed on its own, so
+/// a malformed record does not hide the ones after it. `\r\n` line endings are accepted.
+pub fn records(content: &str) -> Vec<Record> {
+    let mut records = Vec::new();
+    let mut offset = 0;
+    for (index, raw) in content.split('\n').enumerate() {
+        let start = offset;
+        offset += raw.len() + 1;
+        let text = raw.strip_suffix('\r').unwrap_or(raw);
+        if text.trim().is_empty() {
+            continue;
+        }
+        records.push(Record { line: index + 1, span: start..start + text.len(), value: serde_json::from_str(text) });
+    }
+    records
+}
+
+/// Byte range in the file of the key `pointer` ends in, looked up only within the record at
+/// `record` (every record tends to repeat the same keys); the whole record when the pointer is
+/// the root or its key cannot be found.
+pub fn pointer_span(content: &str, record: &Range<usize>, pointer: &str) -> Range<usize> {
+    match config_merge::pointer_span(&content[record.clone()], pointer) {
+        Some(span) => record.start + span.start..record.start + span.end,
+        None => record.clone(),
+    }
+}
@@ -0,0 +1,169 @@
+use std::ops::Range;
+use std::path::Path;
+use miette::{LabeledSpan, SourceSpan};
+
+use crate::error::AppError;
+
+// Offending lines labelled per diagnostic; the message counts all of them
+const MAX_LABELS: usize = 5;
+
+/// Line ending rule for `--line-endings`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineEndings {
+    Lf,
+    Crlf,
+    /// Whichever ending most of the file's lines use
+    Consistent,
+}
+
+impl LineEndings {
+    /// Parses `--line-endings`: `lf`, `crlf` or `consistent`.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "lf" => Some(LineEndings::Lf),
+            "crlf" => Some(LineEndings::Crlf),
+            "consistent" => Some(LineEndings::Consistent),
+            _ => None,
+        }
+    }
+}
+
+/// Line break style of a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Newline {
+    Lf,
+    Crlf,
+}
+
+impl Newline {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Newline::Lf => "\n",
+            Newline::Crlf => "\r\n",
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Newline::Lf => "LF",
+            Newline::Crlf => "CRLF",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "LF" => Some(Newline::Lf),
+            "CRLF" => Some(Newline::Crlf),
+            _ => None,
+        }
+    }
+}
+
+/// Byte range of every line break in `content`, with its style.
+pub fn line_breaks(content: &str) -> Vec<(Range<usize>, Newline)> {
+    content
+        .match_indices('\n')
+        .map(|(i, _)| match i.checked_sub(1).filter(|&before| content.as_bytes()[before] == b'\r') {
+            Some(start) => (start..i + 1, Newline::Crlf),
+            None => (i..i + 1, Newline::Lf),
+        })
+        .collect()
+}
+
+/// The style most of the file's line breaks use (the first one's on a tie); LF without any.
+pub fn usual_newline(content: &str) -> Newline {
+    let breaks = line_breaks(content);
+    let crlf = breaks.iter().filter(|(_, n)| *n == Newline::Crlf).count();
+    match breaks.first() {
+        Some(_) if crlf * 2 > breaks.len() => Newline::Crlf,
+        Some((_, first)) if crlf * 2 == breaks.len() => *first,
+        _ => Newline::Lf,
+    }
+}
+
+/// Line breaks that are not `expected`.
+pub fn offending_breaks(content: &str, expected: Newline) -> Vec<Range<usize>> {
+    line_breaks(content).into_iter().filter(|(_, n)| *n != expected).map(|(range, _)| range).collect()
+}
+
+/// Rules from `--line-endings`/`--final-newline` or the project config. Empty checks nothing.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NewlineRules {
+    pub line_endings: Option<LineEndings>,
+    pub final_newline: bool,
+}
+
+impl NewlineRules {
+    pub fn is_empty(&self) -> bool {
+        *self == NewlineRules::default()
+    }
+
+    fn expected(&self, content: &str) -> Newline {
+        match self.line_endings {
+            Some(LineEndings::Lf) => Newline::Lf,
+            Some(LineEndings::Crlf) => Newline::Crlf,
+            _ => usual_newline(content),
+        }
+    }
+
+    /// Warnings for `content` of the file at `path`: lines with the wrong ending (one
+    /// diagnostic for all of them) and a missing final newline.
+    pub fn check(&self, path: &Path, content: &str) -> Vec<AppError> {
+        let mut found = Vec::new();
+        if self.is_empty() {
+            return found;
+        }
+        let expected = self.expected(content);
+        if self.line_endings.is_some() {
+            let offending = offending_breaks(content, expected);
+            if !offending.is_empty() {
+                let labels = offending
+                    .iter()
+                    .take(MAX_LABELS)
+                    .map(|range| {
+                        // The line's text, or the bare line break for an empty line
+                        let start = content[..range.start].rfind('\n').map_or(0, |i| i + 1);
+                        let span = if start < range.start { start..range.start } else { range.clone() };
+                        let actual = if expected == Newline::Lf { Newline::Crlf } else { Newline::Lf };
+                        LabeledSpan::new(Some(format!("ends with {}", actual.name())), span.start, span.len())
+                    })
+                    .collect();
+                found.push(AppError::InconsistentLineEndings {
+                    path: path.to_path_buf(),
+                    expected: expected.name().to_string(),
+                    count: offending.len(),
+                    labels,
+                    source_code: content.to_string(),
+                });
+            }
+        }
+        if self.final_newline && !content.is_empty() && !content.ends_with('\n') {
+            let start = content.rfind('\n').map_or(0, |i| i + 1);
+            found.push(AppError::MissingFinalNewline {
+                path: path.to_path_buf(),
+                newline: expected.name().to_string(),
+                span: SourceSpan::new(start.into(), content.len() - start),
+                source_code: content.to_string(),
+            });
+        }
+        found
+    }
+}
+
+/// The edits that fix a line ending or final newline diagnostic, as (range, replacement).
+pub fn fix_edits(error: &AppError) -> Vec<(Range<usize>, String)> {
+    match error {
+        AppError::InconsistentLineEndings { expected, source_code, .. } => {
+            let Some(expected) = Newline::from_name(expected) else { return Vec::new() };
+            offending_breaks(source_code, expected)
+                .into_iter()
+                .map(|range| (range, expected.as_str().to_string()))
+                .collect()
+        }
+        AppError::MissingFinalNewline { newline, source_code, .. } => {
+            let newline = Newline::from_name(newline).unwrap_or(Newline::Lf);
+            vec![(source_code.len()..source_code.len(), newline.as_str().to_string())]
+        }
+        _ => Vec::new(),
+    }
+}
@@ -0,0 +1,92 @@
+use std::path::Path;
+use serde_json::{Map, Value as JsonValue};
+
+use crate::yaml;
+
+/// The format `path`'s extension names: `json`, `yaml` (also `.yml`) or `toml`.
+pub fn format_for(path: &Path) -> Option<&'static str> {
+    match path.extension().and_then(|e| e.to_str())? {
+        "json" => Some("json"),
+        "yaml" | "yml" => Some("yaml"),
+        "toml" => Some("toml"),
+        _ => None,
+    }
+}
+
+// TOML dates and times have no JSON type; they are kept as the strings TOML writes them as
+fn toml_to_json(value: toml::Value) -> JsonValue {
+    match value {
+        toml::Value::String(s) => JsonValue::String(s),
+        toml::Value::Integer(i) => JsonValue::from(i),
+        toml::Value::Float(f) => serde_json::Number::from_f64(f).map_or(JsonValue::Null, JsonValue::Number),
+        toml::Value::Boolean(b) => JsonValue::Bool(b),
+        toml::Value::Datetime(d) => JsonValue::String(d.to_string()),
+        toml::Value::Array(items) => JsonValue::Array(items.into_iter().map(toml_to_json).collect()),
+        toml::Value::Table(table) => JsonValue::Object(table.into_iter().map(|(k, v)| (k, toml_to_json(v))).collect()),
+    }
+}
+
+/// The documents in `content`, read as `format`: one, or one per document of a YAML stream.
+pub fn parse(content: &str, format: &str) -> Result<Vec<JsonValue>, String> {
+    match format {
+        "json" => serde_json::from_str(content).map(|value| vec![value]).map_err(|e| e.to_string()),
+        "yaml" => yaml::parse_documents(content)
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(|document| yaml::to_json_with_policy(document, &yaml::TagPolicy::default()).map_err(|tag| format!("unknown YAML tag {}", tag)))
+            .collect(),
+        "toml" => toml::from_str::<toml::Value>(content).map(|value| vec![toml_to_json(value)]).map_err(|e| e.message().to_string()),
+        other => Err(format!("unsupported format {:?}; expected json, yaml or toml", other)),
+    }
+}
+
+/// `value` with the keys of every object in sorted order, whether or not serde_json keeps
+/// maps in insertion order.
+pub fn sorted(value: JsonValue) -> JsonValue {
+    match value {
+        JsonValue::Object(map) => {
+            let mut entries: Vec<(String, JsonValue)> = map.into_iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            JsonValue::Object(entries.into_iter().map(|(k, v)| (k, sorted(v))).collect::<Map<_, _>>())
+        }
+        JsonValue::Array(items) => JsonValue::Array(items.into_iter().map(sorted).collect()),
+        other => other,
+    }
+}
+
+// Pointer of the first null in `value`, which TOML cannot write
+fn find_null(value: &JsonValue, pointer: &str) -> Option<String> {
+    match value {
+        JsonValue::Null => Some(if pointer.is_empty() { "/".to_string() } else { pointer.to_string() }),
+        JsonValue::Object(map) => map.iter().find_map(|(k, v)| find_null(v, &format!("{}/{}", pointer, k.replace('~', "~0").replace('/', "~1")))),
+        JsonValue::Array(items) => items.iter().enumerate().find_map(|(i, v)| find_null(v, &format!("{}/{}", pointer, i))),
+        _ => None,
+    }
+}
+
+/// The canonical text of `documents` in `format`: keys sorted at every level, two-space
+/// indentation, and one style per scalar (the serializer's), so equal data gives equal bytes
+/// whatever format or layout it was written in. Only YAML holds more than one document.
+pub fn normalize(documents: &[JsonValue], format: &str) -> Result<String, String> {
+    if documents.len() != 1 && format != "yaml" {
+        return Err(format!("{} documents cannot be written as one {} document", documents.len(), format));
+    }
+    let documents: Vec<JsonValue> = documents.iter().cloned().map(sorted).collect();
+    match format {
+        "json" => serde_json::to_string_pretty(&documents[0]).map(|json| json + "\n").map_err(|e| e.to_string()),
+        "yaml" => {
+            let rendered: Result<Vec<String>, String> = documents.iter().map(|d| serde_yaml::to_string(d).map_err(|e| e.to_string())).collect();
+            Ok(rendered?.join("---\n"))
+        }
+        "toml" => {
+            if !documents[0].is_object() {
+                return Err("only a table can be written as a TOML document".to_string());
+            }
+            if let Some(pointer) = find_null(&documents[0], "") {
+                return Err(format!("null at {} cannot be written as TOML", pointer));
+            }
+            toml::to_string_pretty(&documents[0]).map_err(|e| e.to_string())
+        }
+        other => Err(format!("unsupported format {:?}; expected json, yaml or toml", other)),
+    }
+}
@@ -0,0 +1,81 @@
+use serde_json::json;
+
+use crate::error::AppError;
+use crate::status::{Outcome, RunStatus};
+use crate::validation::PrintableError;
+
+/// When `--notify-webhook` posts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotifyWhen {
+    /// Only when the run failed or could not complete
+    Failure,
+    Always,
+}
+
+impl NotifyWhen {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "failure" => Some(NotifyWhen::Failure),
+            "always" => Some(NotifyWhen::Always),
+            _ => None,
+        }
+    }
+
+    pub fn should_notify(self, status: &RunStatus) -> bool {
+        self == NotifyWhen::Always || status.outcome != Outcome::Passed
+    }
+}
+
+/// `file: message` lines for the first `limit` diagnostics, failing ones before warnings.
+pub fn top_diagnostics(diagnostics: &[AppError], limit: usize) -> Vec<String> {
+    let (warnings, errors): (Vec<&AppError>, Vec<&AppError>) = diagnostics.iter().partition(|d| d.is_warning());
+    errors
+        .into_iter()
+        .chain(warnings)
+        .take(limit)
+        .map(|d| {
+            let printable = PrintableError::from(d);
+            format!("{}: {}", printable.file_path, printable.message)
+        })
+        .collect()
+}
+
+/// Message text for the run: a one-line summary, then the top diagnostics.
+pub fn summary_text(status: &RunStatus, top: &[String]) -> String {
+    let headline = match status.outcome {
+        Outcome::Passed => "passed",
+        Outcome::Failed => "failed",
+        Outcome::Error => "could not complete",
+    };
+    let mut text = format!(
+        "toml-and-jerry check {}: {} error(s), {} warning(s) in {} file(s)",
+        headline, status.errors, status.warnings, status.files_with_diagnostics
+    );
+    if let Some(message) = &status.message {
+        text.push_str(&format!("\n{}", message));
+    }
+    for line in top {
+        text.push_str(&format!("\n• `{}`", line));
+    }
+    let remaining = (status.errors + status.warnings).saturating_sub(top.len());
+    if remaining > 0 && !top.is_empty() {
+        text.push_str(&format!("\n…and {} more", remaining));
+    }
+    text
+}
+
+/// Body for Slack incoming webhooks; Teams incoming webhooks accept the same `text` payload.
+pub fn webhook_payload(status: &RunStatus, top: &[String]) -> serde_json::Value {
+    json!({ "text": summary_text(status, top) })
+}
+
+/// Posts the payload to `url`.
+pub fn post_webhook(url: &str, payload: &serde_json::Value) -> Result<(), String> {
+    reqwest::blocking::Client::new()
+        .post(url)
+        .json(payload)
+        .send()
+        .and_then(|r| r.error_for_status())
+        .map(|_| ())
+        .map_err(|e| format!("Failed to notify webhook {}: {}", url, e))
+}
@@ -0,0 +1,155 @@
+use std::fs;
+use std::path::Path;
+use serde_json::{json, Value as JsonValue};
+use jsonschema::Validator;
+
+use crate::error::AppError;
+use crate::yaml;
+
+/// Which part of an operation a payload fixture represents.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PayloadPart {
+    RequestBody,
+    /// A response body for the given status code (`200`, `4XX`, `default`)
+    Response(String),
+}
+
+impl PayloadPart {
+    /// Parses `requestBody`, `response` (defaults to 200) or `response:<status>`.
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.split_once(':') {
+            None if raw == "requestBody" => Some(PayloadPart::RequestBody),
+            None if raw == "response" => Some(PayloadPart::Response("200".to_string())),
+            Some(("response", status)) if !status.is_empty() => Some(PayloadPart::Response(status.to_string())),
+            _ => None,
+        }
+    }
+}
+
+// Escapes a JSON pointer segment per RFC 6901
+fn escape_pointer_segment(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+fn spec_error(spec_path: &Path, message: String) -> AppError {
+    AppError::OpenApiError {
+        spec: spec_path.to_string_lossy().into_owned(),
+        message,
+    }
+}
+
+/// Loads an OpenAPI document (JSON or YAML) into a JSON value.
+pub fn load_spec(spec_path: &Path) -> Result<JsonValue, AppError> {
+    let content = fs::read_to_string(spec_path).map_err(|e| AppError::FileReadError {
+        path: spec_path.to_path_buf(),
+        source: e,
+        span: None,
+    })?;
+    // YAML is a superset of JSON, so one parser covers both spellings of the spec
+    let document: serde_yaml::Value = serde_yaml::from_str(&content)
+        .map_err(|e| spec_error(spec_path, format!("Failed to parse OpenAPI document: {}", e)))?;
+    // Unquoted status codes (`200:`) are integer keys in YAML; to_json stringifies them
+    Ok(yaml::to_json(document))
+}
+
+// Picks the media type entry to validate against. An explicit choice must exist;
+// otherwise prefer application/json, then any +json/json type, then the first entry.
+fn select_media_type<'a>(
+    content: &'a serde_json::Map<String, JsonValue>,
+    requested: Option<&str>,
+) -> Option<&'a str> {
+    if let Some(requested) = requested {
+        return content.get_key_value(requested).map(|(k, _)| k.as_str());
+    }
+    if content.contains_key("application/json") {
+        return Some("application/json");
+    }
+    content
+        .keys()
+        .find(|k| k.ends_with("json"))
+        .or_else(|| content.keys().next())
+        .map(|k| k.as_str())
+}
+
+/// Returns a JSON pointer (into the spec document) to the schema of the given
+/// operation part, e.g. `/paths/~1users/post/requestBody/content/application~1json/schema`.
+pub fn resolve_payload_schema_pointer(
+    spec: &JsonValue,
+    spec_path: &Path,
+    operation: &str,
+    part: &PayloadPart,
+    media_type: Option<&str>,
+) -> Result<String, AppError> {
+    let (method, route) = operation
+        .trim()
+        .split_once(char::is_whitespace)
+        .ok_or_else(|| spec_error(spec_path, format!("Operation {:?} must look like 'POST /users'", operation)))?;
+    let method = method.to_ascii_lowercase();
+    let route = route.trim();
+
+    let operation_obj = spec
+        .get("paths")
+        .and_then(|p| p.get(route))
+        .and_then(|p| p.get(&method))
+        .ok_or_else(|| spec_error(spec_path, format!("Operation {} {} not found in spec", method.to_uppercase(), route)))?;
+
+    let mut pointer = format!("/paths/{}/{}", escape_pointer_segment(route), method);
+    let mut holder = match part {
+        PayloadPart::RequestBody => {
+            pointer.push_str("/requestBody");
+            operation_obj.get("requestBody")
+        }
+        PayloadPart::Response(status) => {
+            pointer.push_str(&format!("/responses/{}", escape_pointer_segment(status)));
+            operation_obj.get("responses").and_then(|r| r.get(status))
+        }
+    }
+    .ok_or_else(|| spec_error(spec_path, format!("Operation {} has no {:?}", operation, part)))?;
+
+    // requestBody and response objects may themselves be $refs into components
+    if let Some(reference) = holder.get("$ref").and_then(|r| r.as_str()) {
+        let target = reference
+            .strip_prefix('#')
+            .ok_or_else(|| spec_error(spec_path, format!("External reference {:?} is not supported", reference)))?;
+        holder = spec
+            .pointer(target)
+            .ok_or_else(|| spec_error(spec_path, format!("Unresolvable reference {:?}", reference)))?;
+        pointer = target.to_string();
+    }
+
+    let content = holder
+        .get("content")
+        .and_then(|c| c.as_object())
+        .ok_or_else(|| spec_error(spec_path, format!("{} declares no content", pointer)))?;
+    let media = select_media_type(content, media_type).ok_or_else(|| {
+        spec_error(
+            spec_path,
+            format!("Media type {:?} not declared for {}", media_type.unwrap_or("application/json"), pointer),
+        )
+    })?;
+    if content.get(media).and_then(|m| m.get("schema")).is_none() {
+        return Err(spec_error(spec_path, format!("{} {} has no schema", pointer, media)));
+    }
+    Ok(format!("{}/content/{}/schema", pointer, escape_pointer_segment(media)))
+}
+
+/// Compiles a validator for one operation's payload. The spec document becomes the
+/// root resource so that `#/components/...` references resolve without rewriting.
+pub fn compile_operation_validator(
+    spec_path: &Path,
+    operation: &str,
+    part: &PayloadPart,
+    media_type: Option<&str>,
+) -> Result<Validator, AppError> {
+    let spec = load_spec(spec_path)?;
+    let pointer = resolve_payload_schema_pointer(&spec, spec_path, operation, part, media_type)?;
+
+    let mut root = spec;
+    if let Some(obj) = root.as_object_mut() {
+        obj.insert("$ref".to_string(), json!(format!("#{}", pointer)));
+    }
+    Validator::new(&root).map_err(|e| AppError::SchemaCompileError {
+        source_display: format!("{} ({})", spec_path.display(), operation),
+        source: Box::new(e),
+    })
+}
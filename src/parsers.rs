@@ -0,0 +1,87 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use serde::Deserialize;
+
+/// A parser an extension can be mapped to in the project config's `[parsers]` table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Parser {
+    Json,
+    Yaml,
+    /// YAML with `{{ ... }}`, `{% ... %}` and `{# ... #}` template syntax masked out first
+    YamlTemplate,
+    Toml,
+    Hcl,
+    Ini,
+    Properties,
+    Kdl,
+    Hjson,
+    /// JSON Lines: one JSON value per line, each validated on its own
+    Ndjson,
+    Cue,
+    Pkl,
+    Nickel,
+}
+
+impl Parser {
+    /// The extension whose handling this parser selects.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Parser::Json => "json",
+            Parser::Yaml => "yaml",
+            Parser::YamlTemplate => "yaml-template",
+            Parser::Toml => "toml",
+            Parser::Hcl => "hcl",
+            Parser::Ini => "ini",
+            Parser::Properties => "properties",
+            Parser::Kdl => "kdl",
+            Parser::Hjson => "hjson",
+            Parser::Ndjson => "ndjson",
+            Parser::Cue => "cue",
+            Parser::Pkl => "pkl",
+            Parser::Nickel => "ncl",
+        }
+    }
+}
+
+/// The extension that decides how `path` is parsed: its own, unless `overrides` remaps it.
+/// Override keys may be written with or without the leading dot (`conf`, `.conf`).
+pub fn parser_for<'a>(path: &'a Path, overrides: &BTreeMap<String, Parser>) -> Option<&'a str> {
+    let extension = path.extension().and_then(|ext| ext.to_str())?;
+    let mapped = overrides.iter().find(|(key, _)| key.trim_start_matches('.') == extension);
+    Some(mapped.map_or(extension, |(_, parser)| parser.extension()))
+}
+
+/// Replaces template syntax so the rest parses as YAML, keeping every byte offset: expressions
+/// (`{{ ... }}`) become `_` so a templated value is still a scalar, and statements and comments
+/// (`{% ... %}`, `{# ... #}`) become spaces.
+pub fn mask_templates(content: &str) -> String {
+    let mut masked = String::with_capacity(content.len());
+    let mut rest = content;
+    while let Some(start) = rest.find('{') {
+        let (open, close, fill) = match rest[start..].get(..2) {
+            Some("{{") => ("{{", "}}", '_'),
+            Some("{%") => ("{%", "%}", ' '),
+            Some("{#") => ("{#", "#}", ' '),
+            _ => {
+                masked.push_str(&rest[..=start]);
+                rest = &rest[start + 1..];
+                continue;
+            }
+        };
+        let Some(end) = rest[start + open.len()..].find(close).map(|i| start + open.len() + i + close.len()) else {
+            break;
+        };
+        masked.push_str(&rest[..start]);
+        // One fill character per byte, keeping newlines so line numbers stay right
+        for c in rest[start..end].chars() {
+            match c {
+                '\n' => masked.push('\n'),
+                _ => masked.push_str(&fill.to_string().repeat(c.len_utf8())),
+            }
+        }
+        rest = &rest[end..];
+    }
+    masked.push_str(rest);
+    masked
+}
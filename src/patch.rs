@@ -0,0 +1,149 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde_json::{json, Value as JsonValue};
+
+use crate::hcl_json;
+use crate::hjson;
+use crate::ini;
+use crate::kdl;
+use crate::properties;
+use crate::renames::{renamed_from, MAX_REF_DEPTH};
+use crate::yaml;
+
+fn escape_pointer_segment(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+fn child_pointer(pointer: &str, key: &str) -> String {
+    format!("{}/{}", pointer, escape_pointer_segment(key))
+}
+
+// Follows a local `$ref` so `default` and `properties` behind it are visible
+fn resolve<'a>(root: &'a JsonValue, schema: &'a JsonValue) -> &'a JsonValue {
+    let mut current = schema;
+    for _ in 0..MAX_REF_DEPTH {
+        match current.get("$ref").and_then(|r| r.as_str()).and_then(|r| r.strip_prefix('#')).and_then(|t| root.pointer(t)) {
+            Some(target) => current = target,
+            None => break,
+        }
+    }
+    current
+}
+
+fn collect_operations(root: &JsonValue, schema: &JsonValue, instance: &JsonValue, pointer: &str, depth: usize, ops: &mut Vec<JsonValue>) {
+    if depth > MAX_REF_DEPTH {
+        return;
+    }
+    let schema = resolve(root, schema);
+    // Only `allOf` branches always apply; `anyOf`/`oneOf` are ambiguous and left alone
+    if let Some(JsonValue::Array(branches)) = schema.get("allOf") {
+        for branch in branches {
+            collect_operations(root, branch, instance, pointer, depth + 1, ops);
+        }
+    }
+
+    match instance {
+        JsonValue::Object(map) => {
+            let empty = serde_json::Map::new();
+            let properties = schema.get("properties").and_then(|p| p.as_object()).unwrap_or(&empty);
+            let mut present: BTreeSet<&str> = map.keys().map(String::as_str).collect();
+            let mut moved: BTreeMap<&str, &str> = BTreeMap::new();
+
+            for (name, property) in properties {
+                for old in renamed_from(property) {
+                    if present.contains(old.as_str()) && !present.contains(name.as_str()) {
+                        let old = map.get_key_value(&old).map(|(k, _)| k.as_str()).unwrap_or_default();
+                        ops.push(json!({ "op": "move", "from": child_pointer(pointer, old), "path": child_pointer(pointer, name) }));
+                        present.remove(old);
+                        present.insert(name);
+                        moved.insert(old, name);
+                    }
+                }
+            }
+
+            // Removing is only safe when the schema plainly forbids every unlisted key
+            let closed = schema.get("additionalProperties") == Some(&JsonValue::Bool(false))
+                && schema.get("patternProperties").is_none();
+            if closed {
+                for key in map.keys().filter(|k| !properties.contains_key(*k) && !moved.contains_key(k.as_str())) {
+                    ops.push(json!({ "op": "remove", "path": child_pointer(pointer, key) }));
+                }
+            }
+
+            if let Some(JsonValue::Array(required)) = schema.get("required") {
+                for name in required.iter().filter_map(|r| r.as_str()) {
+                    if present.contains(name) {
+                        continue;
+                    }
+                    if let Some(default) = properties.get(name).and_then(|p| resolve(root, p).get("default")) {
+                        ops.push(json!({ "op": "add", "path": child_pointer(pointer, name), "value": default }));
+                    }
+                }
+            }
+
+            for (key, child) in map {
+                let key = moved.get(key.as_str()).copied().unwrap_or(key.as_str());
+                if let Some(property) = properties.get(key) {
+                    collect_operations(root, property, child, &child_pointer(pointer, key), depth + 1, ops);
+                } else if !closed {
+                    if let Some(additional) = schema.get("additionalProperties").filter(|a| a.is_object()) {
+                        collect_operations(root, additional, child, &child_pointer(pointer, key), depth + 1, ops);
+                    }
+                }
+            }
+        }
+        JsonValue::Array(items) => {
+            if let Some(item_schema) = schema.get("items").filter(|i| i.is_object()) {
+                for (index, item) in items.iter().enumerate() {
+                    collect_operations(root, item_schema, item, &format!("{}/{}", pointer, index), depth + 1, ops);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// RFC 6902 operations that make `instance` closer to valid against `schema`:
+/// `move` for `x-renamed-from` keys, `remove` for properties a closed object forbids and
+/// `add` for missing required properties that declare a `default`. Applied in order.
+pub fn patch_for_instance(schema: &JsonValue, instance: &JsonValue) -> Vec<JsonValue> {
+    let mut ops = Vec::new();
+    collect_operations(schema, schema, instance, "", 0, &mut ops);
+    ops
+}
+
+// Parses a single-document input into the JSON value schemas see. Multi-document YAML
+// has no single JSON document to patch, so it is skipped.
+pub(crate) fn load_instance(path: &Path, tags: &yaml::TagPolicy) -> Option<JsonValue> {
+    let content = fs::read_to_string(path).ok()?;
+    match path.extension().and_then(|e| e.to_str())? {
+        "json" if hcl_json::is_hcl_json(path) => serde_json::from_str(&content).ok().map(hcl_json::normalize),
+        "json" => serde_json::from_str(&content).ok(),
+        "yaml" | "yml" => {
+            let mut documents = yaml::parse_documents(&content).ok()?;
+            if documents.len() > 1 {
+                return None;
+            }
+            yaml::to_json_with_policy(documents.pop().unwrap_or_default(), tags).ok()
+        }
+        "toml" => serde_json::to_value(toml::from_str::<toml::Value>(&content).ok()?).ok(),
+        "hcl" => hcl::from_str::<JsonValue>(&content).ok(),
+        "ini" | "cfg" => ini::parse_ini(&content, ini::IniCoercion::default()).ok().map(|document| document.value),
+        "properties" => properties::parse_properties(&content, properties::PropertiesOptions::default()).ok().map(|document| document.value),
+        "kdl" => kdl::parse_kdl(&content).ok().map(|document| document.value),
+        "hjson" => hjson::parse_hjson(&content).ok().map(|document| document.value),
+        _ => None,
+    }
+}
+
+/// Patches for each input file that has fixable violations, keyed by the file's path.
+pub fn patches_for_inputs(schema: &JsonValue, inputs: &[PathBuf], tags: &yaml::TagPolicy) -> BTreeMap<String, Vec<JsonValue>> {
+    inputs
+        .iter()
+        .filter_map(|input| {
+            let ops = patch_for_instance(schema, &load_instance(input, tags)?);
+            (!ops.is_empty()).then(|| (input.to_string_lossy().into_owned(), ops))
+        })
+        .collect()
+}
@@ -0,0 +1,164 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+const APP_DIR_NAME: &str = "toml-and-jerry";
+
+// Returns the value of an environment variable only if it is set to an absolute path.
+// The XDG spec says relative values must be ignored.
+fn absolute_env_dir(var: &str) -> Option<PathBuf> {
+    env::var_os(var)
+        .map(PathBuf::from)
+        .filter(|p| p.is_absolute())
+}
+
+fn home_dir() -> Option<PathBuf> {
+    absolute_env_dir("HOME").or_else(|| absolute_env_dir("USERPROFILE"))
+}
+
+// Base directory for disposable data (downloaded schemas and rules packs)
+fn platform_cache_base() -> Option<PathBuf> {
+    if let Some(dir) = absolute_env_dir("XDG_CACHE_HOME") {
+        return Some(dir);
+    }
+    if cfg!(target_os = "macos") {
+        home_dir().map(|h| h.join("Library").join("Caches"))
+    } else if cfg!(windows) {
+        absolute_env_dir("LOCALAPPDATA")
+    } else {
+        home_dir().map(|h| h.join(".cache"))
+    }
+}
+
+// Base directory for state the user cares about (installed rules packs)
+fn platform_data_base() -> Option<PathBuf> {
+    if let Some(dir) = absolute_env_dir("XDG_DATA_HOME") {
+        return Some(dir);
+    }
+    if cfg!(target_os = "macos") {
+        home_dir().map(|h| h.join("Library").join("Application Support"))
+    } else if cfg!(windows) {
+        absolute_env_dir("APPDATA")
+    } else {
+        home_dir().map(|h| h.join(".local").join("share"))
+    }
+}
+
+/// Root of all cached state. `TOML_AND_JERRY_CACHE_DIR` overrides the platform default.
+pub fn cache_dir() -> PathBuf {
+    absolute_env_dir("TOML_AND_JERRY_CACHE_DIR")
+        .or_else(|| platform_cache_base().map(|b| b.join(APP_DIR_NAME)))
+        .unwrap_or_else(|| env::temp_dir().join(APP_DIR_NAME).join("cache"))
+}
+
+/// Root of persistent (non-cache) state such as installed rules packs.
+pub fn data_dir() -> PathBuf {
+    absolute_env_dir("TOML_AND_JERRY_DATA_DIR")
+        .or_else(|| platform_data_base().map(|b| b.join(APP_DIR_NAME)))
+        .unwrap_or_else(|| env::temp_dir().join(APP_DIR_NAME).join("data"))
+}
+
+/// Remote schemas fetched over HTTP(S).
+pub fn schema_cache_dir() -> PathBuf {
+    cache_dir().join("schemas")
+}
+
+/// Rules packs unpacked from a `.tar.gz` path or URL.
+pub fn rule_pack_cache_dir() -> PathBuf {
+    cache_dir().join("rule-packs")
+}
+
+// The directories under `cache_dir` this tool fills. `TOML_AND_JERRY_CACHE_DIR` may point at
+// a directory that holds other things, so `cache ls` and `cache clear` only look in these
+fn managed_cache_dirs() -> [PathBuf; 2] {
+    [schema_cache_dir(), rule_pack_cache_dir()]
+}
+
+// FNV-1a, used to derive stable file names from URLs. std's DefaultHasher is not
+// guaranteed to be stable across Rust releases, which would orphan cache entries.
+pub fn stable_hash(input: &str) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in input.as_bytes() {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+/// Path of the cache entry for a remote schema URL.
+pub fn schema_cache_entry(url: &str) -> PathBuf {
+    schema_cache_dir().join(format!("{:016x}.json", stable_hash(url)))
+}
+
+#[derive(Debug)]
+pub struct CacheEntry {
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+/// Lists every file stored in the cache directories this tool fills, sorted by path.
+pub fn list_cache_entries() -> io::Result<Vec<CacheEntry>> {
+    let mut entries = Vec::new();
+    for root in managed_cache_dirs().iter().filter(|root| root.exists()) {
+        for entry in walkdir::WalkDir::new(root).sort_by_file_name() {
+            let entry = entry.map_err(io::Error::from)?;
+            if entry.file_type().is_file() {
+                let size = entry.metadata().map_err(io::Error::from)?.len();
+                entries.push(CacheEntry {
+                    path: entry.into_path(),
+                    size,
+                });
+            }
+        }
+    }
+    entries.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(entries)
+}
+
+/// Removes the cache directories this tool fills, and the cache directory itself when that
+/// leaves it empty; anything else in it is kept. Returns the number of bytes freed.
+pub fn clear_cache() -> io::Result<u64> {
+    let freed = list_cache_entries()?.iter().map(|e| e.size).sum();
+    for root in managed_cache_dirs().iter().filter(|root| root.exists()) {
+        check_writable(root)?;
+        fs::remove_dir_all(root)?;
+    }
+    // Fails, as it should, while anything else is left in it
+    let _ = fs::remove_dir(cache_dir());
+    Ok(freed)
+}
+
+static WRITES_FORBIDDEN: AtomicBool = AtomicBool::new(false);
+
+/// Forbids every filesystem write for the rest of the process (`--no-write`).
+pub fn forbid_writes() {
+    WRITES_FORBIDDEN.store(true, Ordering::Relaxed);
+}
+
+/// Whether `--no-write` is in effect.
+pub fn writes_forbidden() -> bool {
+    WRITES_FORBIDDEN.load(Ordering::Relaxed)
+}
+
+/// Fails when `--no-write` is in effect; every write goes through this first, so a feature
+/// that needs to write stops with an error instead of touching the filesystem.
+pub fn check_writable(path: &Path) -> io::Result<()> {
+    if writes_forbidden() {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("--no-write forbids writing {}", path.display()),
+        ));
+    }
+    Ok(())
+}
+
+/// Writes `contents` to `path`, creating parent directories as needed.
+pub fn write_with_parents(path: &Path, contents: &[u8]) -> io::Result<()> {
+    check_writable(path)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, contents)
+}
@@ -0,0 +1,217 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde_json::Value as JsonValue;
+use jsonschema::Validator;
+
+use crate::ansible;
+use crate::ci;
+use crate::cloudformation;
+use crate::error::AppError;
+use crate::progress;
+use crate::schema::load_and_compile_schema;
+use crate::summary;
+use crate::validation::{validate_inputs_with_options, ValidationOptions};
+use crate::yaml;
+
+/// Where a preset rule gets its schema from.
+#[derive(Debug, Clone, Copy)]
+pub enum SchemaSource {
+    /// A published schema (e.g. SchemaStore), fetched once and kept in the schema cache
+    Catalog(&'static str),
+}
+
+/// Extra checks a schema cannot express (cross-references, naming rules).
+/// Receives the file path, its raw content and the parsed document.
+pub type SemanticCheck = fn(&Path, &str, &JsonValue) -> Vec<AppError>;
+
+/// Maps one kind of file to the schema it must satisfy.
+pub struct PresetRule {
+    pub name: &'static str,
+    pub matches: fn(&Path) -> bool,
+    pub schema: SchemaSource,
+    pub check: Option<SemanticCheck>,
+}
+
+/// A named bundle of rules selected with `--preset`.
+pub struct Preset {
+    pub name: &'static str,
+    pub rules: &'static [PresetRule],
+    /// YAML tag profile applied on top of the user's tag policy
+    pub tag_profile: Option<&'static str>,
+}
+
+const PRESETS: &[Preset] = &[
+    Preset {
+        name: "ci",
+        rules: ci::CI_RULES,
+        tag_profile: None,
+    },
+    Preset {
+        name: "ansible",
+        rules: ansible::ANSIBLE_RULES,
+        tag_profile: None,
+    },
+    Preset {
+        name: "cloudformation",
+        rules: cloudformation::CLOUDFORMATION_RULES,
+        tag_profile: Some("cloudformation"),
+    },
+];
+
+/// Looks up a preset by name.
+pub fn find_preset(name: &str) -> Option<&'static Preset> {
+    PRESETS.iter().find(|p| p.name == name)
+}
+
+/// Names of all presets, in a stable order.
+pub fn preset_names() -> impl Iterator<Item = &'static str> {
+    PRESETS.iter().map(|p| p.name)
+}
+
+/// Schemas shipped inside the binary so common file types validate without network access.
+const BUNDLED_SCHEMAS: &[(&str, &str)] = &[
+    ("docker-compose", include_str!("../schemas/docker-compose.schema.json")),
+    ("containerfile", include_str!("../schemas/containerfile.schema.json")),
+];
+
+/// Names of all bundled schemas, in a stable order.
+pub fn bundled_schema_names() -> impl Iterator<Item = &'static str> {
+    BUNDLED_SCHEMAS.iter().map(|(name, _)| *name)
+}
+
+/// Parsed JSON of a bundled schema.
+pub fn bundled_schema(name: &str) -> Option<JsonValue> {
+    BUNDLED_SCHEMAS
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, source)| serde_json::from_str(source).expect("bundled schemas are valid JSON"))
+}
+
+/// Compiles a bundled schema by name.
+pub fn compile_bundled_schema(name: &str) -> Result<Validator, AppError> {
+    let source_display = format!("bundled:{}", name);
+    let schema = bundled_schema(name).ok_or_else(|| AppError::InvalidSchemaPath {
+        path_display: source_display.clone(),
+    })?;
+    Validator::new(&schema).map_err(|e| AppError::SchemaCompileError { source_display, source: Box::new(e) })
+}
+
+pub(crate) fn compile_schema_source(source: SchemaSource) -> Result<Validator, AppError> {
+    match source {
+        SchemaSource::Catalog(url) => load_and_compile_schema(&PathBuf::from(url)),
+    }
+}
+
+// Expands directories to the files under them (including hidden dirs such as .github,
+// honouring .gitignore) and keeps only files some rule of the preset recognises.
+fn collect_preset_inputs(preset: &Preset, inputs: &[PathBuf]) -> BTreeMap<usize, Vec<PathBuf>> {
+    let mut grouped: BTreeMap<usize, Vec<PathBuf>> = BTreeMap::new();
+    let mut candidates = Vec::new();
+    for input in inputs {
+        if input.is_dir() {
+            let walker = ignore::WalkBuilder::new(input).hidden(false).build();
+            for entry in walker.flatten() {
+                if entry.file_type().is_some_and(|t| t.is_file()) {
+                    candidates.push(entry.into_path());
+                }
+            }
+        } else {
+            candidates.push(input.clone());
+        }
+    }
+    for candidate in candidates {
+        match preset.rules.iter().position(|rule| (rule.matches)(&candidate)) {
+            Some(index) => grouped.entry(index).or_default().push(candidate),
+            None if inputs.contains(&candidate) => {
+                progress::line(format_args!("Skipping file not covered by preset {}: {:?}", preset.name, candidate));
+            }
+            None => {}
+        }
+    }
+    grouped
+}
+
+/// Validates `inputs` (files or directories) with every rule of the named preset:
+/// schema validation first, then the rule's semantic checks on files that parsed.
+pub fn run_preset(
+    name: &str,
+    inputs: &[PathBuf],
+    options: &ValidationOptions,
+) -> Result<Vec<AppError>, AppError> {
+    let preset = find_preset(name).ok_or_else(|| AppError::UnknownPreset {
+        name: name.to_string(),
+        available: preset_names().collect::<Vec<_>>().join(", "),
+    })?;
+
+    let profiled_options;
+    let options = match preset.tag_profile {
+        Some(profile) => {
+            profiled_options = ValidationOptions {
+                yaml_tags: options
+                    .yaml_tags
+                    .clone()
+                    .with_profile(profile)
+                    .expect("preset tag profiles are built in"),
+                ..options.clone()
+            };
+            &profiled_options
+        }
+        None => options,
+    };
+
+    let mut collected_errors = Vec::new();
+    for (index, files) in collect_preset_inputs(preset, inputs) {
+        let rule = &preset.rules[index];
+        progress::line(format_args!("Preset {}: {} file(s) as {}", preset.name, files.len(), rule.name));
+        for file in &files {
+            summary::record_gate(file, Some(format!("preset {}: {}", preset.name, rule.name)));
+        }
+        let validator = compile_schema_source(rule.schema)?;
+        collected_errors.extend(validate_inputs_with_options(files.clone(), &validator, options)?);
+        if let Some(check) = rule.check {
+            run_semantic_checks(&[check], &files, options, &mut collected_errors);
+        }
+    }
+    Ok(collected_errors)
+}
+
+/// Semantic checks that rule packs can reference by name.
+const SEMANTIC_CHECKS: &[(&str, SemanticCheck)] = &[
+    ("github-needs", ci::check_github_needs),
+    ("gitlab-needs", ci::check_gitlab_needs),
+    ("azure-depends-on", ci::check_azure_depends_on_references),
+    ("ansible-play-hosts", ansible::check_plays_have_hosts),
+    ("cfn-references", cloudformation::check_references),
+];
+
+/// Looks up a named semantic check.
+pub fn find_semantic_check(name: &str) -> Option<SemanticCheck> {
+    SEMANTIC_CHECKS.iter().find(|(n, _)| *n == name).map(|(_, check)| *check)
+}
+
+/// Names of all semantic checks, in a stable order.
+pub fn semantic_check_names() -> impl Iterator<Item = &'static str> {
+    SEMANTIC_CHECKS.iter().map(|(name, _)| *name)
+}
+
+/// Runs `checks` on every YAML/JSON document of `files`. Files that fail to parse are
+/// skipped, since schema validation has already reported them.
+pub(crate) fn run_semantic_checks(
+    checks: &[SemanticCheck],
+    files: &[PathBuf],
+    options: &ValidationOptions,
+    collected_errors: &mut Vec<AppError>,
+) {
+    for file in files {
+        let Ok(content) = fs::read_to_string(file) else { continue };
+        let Ok(documents) = yaml::parse_documents(&content) else { continue };
+        for document in documents {
+            if let Ok(document) = yaml::to_json_with_policy(document, &options.yaml_tags) {
+                for check in checks {
+                    collected_errors.extend(check(file, &content, &document));
+                }
+            }
+        }
+    }
+}
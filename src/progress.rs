@@ -0,0 +1,84 @@
+use std::fmt::Display;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use crate::error::AppError;
+
+/// Progress callbacks for embedders that drive their own progress UI (GUIs, IDE plugins)
+/// instead of reading the CLI's output. Every method does nothing unless overridden.
+pub trait ProgressHandler: Send {
+    /// Validation of `path` is about to start.
+    fn file_started(&mut self, _path: &Path) {}
+    /// Validation of `path` is done; `diagnostics` are the file's (empty when it is valid).
+    fn file_finished(&mut self, _path: &Path, _diagnostics: &[AppError]) {}
+    /// A `validate_inputs_with_options` run is done, with all of its diagnostics.
+    fn run_finished(&mut self, _diagnostics: &[AppError]) {}
+}
+
+/// The CLI's handler: a `Processing file:` progress line as each file starts.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LineProgress;
+
+impl ProgressHandler for LineProgress {
+    fn file_started(&mut self, path: &Path) {
+        line(format_args!("Processing file: {:?}", path));
+    }
+}
+
+static HANDLER: Mutex<Option<Box<dyn ProgressHandler>>> = Mutex::new(None);
+
+static TO_STDERR: AtomicBool = AtomicBool::new(false);
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// Sends progress lines to stderr from now on, so a json/sarif/csv/template report printed
+/// on stdout can be parsed as it is.
+pub fn to_stderr() {
+    TO_STDERR.store(true, Ordering::Relaxed);
+}
+
+/// Drops progress lines from now on, for commands whose output is a conversation (`repl`).
+pub fn quiet() {
+    QUIET.store(true, Ordering::Relaxed);
+}
+
+/// Prints a progress line (`Processing file: ...`): stdout by default, stderr after `to_stderr`,
+/// nowhere after `quiet`.
+pub fn line(message: impl Display) {
+    if QUIET.load(Ordering::Relaxed) {
+        return;
+    }
+    if TO_STDERR.load(Ordering::Relaxed) {
+        eprintln!("{}", message);
+    } else {
+        println!("{}", message);
+    }
+}
+
+/// Reports the files validated from now on to `handler`, replacing any handler set before.
+pub fn set_handler(handler: impl ProgressHandler + 'static) {
+    *HANDLER.lock().unwrap_or_else(|e| e.into_inner()) = Some(Box::new(handler));
+}
+
+/// Stops reporting to the handler `set_handler` installed.
+pub fn clear_handler() {
+    HANDLER.lock().unwrap_or_else(|e| e.into_inner()).take();
+}
+
+fn notify(event: impl FnOnce(&mut dyn ProgressHandler)) {
+    if let Some(handler) = HANDLER.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+        event(handler.as_mut());
+    }
+}
+
+pub(crate) fn file_started(path: &Path) {
+    notify(|handler| handler.file_started(path));
+}
+
+pub(crate) fn file_finished(path: &Path, diagnostics: &[AppError]) {
+    notify(|handler| handler.file_finished(path, diagnostics));
+}
+
+pub(crate) fn run_finished(diagnostics: &[AppError]) {
+    notify(|handler| handler.run_finished(diagnostics));
+}
@@ -0,0 +1,159 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::Deserialize;
+
+use crate::consistency::ConsistencyRule;
+use crate::error::AppError;
+use crate::parsers::Parser;
+use crate::report_hooks::ReportConfig;
+use crate::required_files::RequiredFile;
+use crate::severity::RuleSeverity;
+use crate::validation::PrintableError;
+
+/// Project config read from the current directory unless `--config` names another file.
+pub const PROJECT_CONFIG_FILE: &str = ".toml-and-jerry.toml";
+
+/// Allowance of diagnostics for one directory, e.g. `[budget."deploy/team-a"] max_errors = 10`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Budget {
+    /// Failing diagnostics allowed before the run fails; unset means none
+    #[serde(default)]
+    pub max_errors: Option<usize>,
+    /// Warnings allowed; unset means no limit
+    #[serde(default)]
+    pub max_warnings: Option<usize>,
+}
+
+/// Settings shared by everyone running checks in a repository.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ProjectConfig {
+    /// Budgets keyed by directory (relative, `/`-separated)
+    #[serde(default)]
+    pub budget: BTreeMap<String, Budget>,
+    /// Parsers for extensions the file name does not give away, e.g. `conf = "toml"`
+    #[serde(default)]
+    pub parsers: BTreeMap<String, Parser>,
+    /// Dialect for schemas without `$schema`, e.g. `"7"`; `--default-draft` takes precedence
+    #[serde(default)]
+    pub default_draft: Option<String>,
+    /// Schema per config version, selected by a document's `x-config-version` or `version` key
+    #[serde(default)]
+    pub schema_versions: BTreeMap<String, PathBuf>,
+    /// Post-run report processors, `[report.hooks.NAME]`
+    #[serde(default)]
+    pub report: ReportConfig,
+    /// Globs for files that must fail validation, e.g. `["schemas/examples/bad/**"]`
+    #[serde(default)]
+    pub expect_invalid: Vec<String>,
+    /// Line ending every file must use: `lf`, `crlf` or `consistent`; `--line-endings` takes precedence
+    #[serde(default)]
+    pub line_endings: Option<String>,
+    /// Every file must end with a line break, as with `--final-newline`
+    #[serde(default)]
+    pub final_newline: bool,
+    /// Levels per rule, e.g. `required = "error"`; `--severity` takes precedence
+    #[serde(default)]
+    pub severity: BTreeMap<String, RuleSeverity>,
+    /// Files every matching directory must contain, `[[require]]`
+    #[serde(default)]
+    pub require: Vec<RequiredFile>,
+    /// Values that must match across files, `[[consistent]]`
+    #[serde(default)]
+    pub consistent: Vec<ConsistencyRule>,
+}
+
+/// Loads `path`, or `.toml-and-jerry.toml` when it exists and no path is given.
+pub fn load_project_config(path: Option<&Path>) -> Result<ProjectConfig, String> {
+    let path = match path {
+        Some(path) => path.to_path_buf(),
+        None if Path::new(PROJECT_CONFIG_FILE).is_file() => PathBuf::from(PROJECT_CONFIG_FILE),
+        None => return Ok(ProjectConfig::default()),
+    };
+    let content = fs::read_to_string(&path).map_err(|e| format!("Cannot read project config {}: {}", path.display(), e))?;
+    toml::from_str(&content).map_err(|e| format!("Invalid project config {}: {}", path.display(), e))
+}
+
+/// Diagnostics counted against one budget.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BudgetUsage {
+    pub area: String,
+    pub budget: Budget,
+    pub errors: usize,
+    pub warnings: usize,
+}
+
+impl BudgetUsage {
+    pub fn exceeded(&self) -> bool {
+        self.errors > self.budget.max_errors.unwrap_or(0)
+            || self.budget.max_warnings.is_some_and(|max| self.warnings > max)
+    }
+}
+
+/// How a run's diagnostics fit the budgets.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BudgetReport {
+    pub usage: Vec<BudgetUsage>,
+    /// Failing diagnostics in files no budget covers; these fail the run as usual
+    pub unbudgeted_errors: usize,
+}
+
+impl BudgetReport {
+    /// The run fails when an area is over budget or an error falls outside every budget.
+    pub fn fails(&self) -> bool {
+        self.unbudgeted_errors > 0 || self.usage.iter().any(BudgetUsage::exceeded)
+    }
+
+    /// One line per area, e.g. `deploy/team-a: 7/10 errors, 2 warnings (ok)`.
+    pub fn render_human(&self) -> String {
+        let mut out = String::from("\n--- Budgets ---\n");
+        for usage in &self.usage {
+            let limit = |count: usize, max: Option<usize>, noun: &str| match max {
+                Some(max) => format!("{}/{} {}", count, max, noun),
+                None => format!("{} {}", count, noun),
+            };
+            out.push_str(&format!(
+                "{}: {}, {} ({})\n",
+                usage.area,
+                limit(usage.errors, Some(usage.budget.max_errors.unwrap_or(0)), "errors"),
+                limit(usage.warnings, usage.budget.max_warnings, "warnings"),
+                if usage.exceeded() { "over budget" } else { "ok" }
+            ));
+        }
+        if self.unbudgeted_errors > 0 {
+            out.push_str(&format!("outside any budget: {} errors\n", self.unbudgeted_errors));
+        }
+        out
+    }
+}
+
+// Whether `file` is inside directory `area`; whole path components only
+fn in_area(file: &str, area: &str) -> bool {
+    let area = area.trim_matches('/');
+    area.is_empty() || area == "." || file == area || file.starts_with(&format!("{}/", area))
+}
+
+/// Counts each diagnostic against the budget of the deepest directory containing its file.
+pub fn evaluate_budgets(budgets: &BTreeMap<String, Budget>, diagnostics: &[AppError]) -> BudgetReport {
+    let mut usage: Vec<BudgetUsage> = budgets
+        .iter()
+        .map(|(area, budget)| BudgetUsage { area: area.clone(), budget: budget.clone(), errors: 0, warnings: 0 })
+        .collect();
+    let mut unbudgeted_errors = 0;
+    for diagnostic in diagnostics {
+        let file = PrintableError::from(diagnostic).file_path.replace('\\', "/");
+        let file = file.trim_start_matches("./");
+        let area = usage
+            .iter_mut()
+            .filter(|u| in_area(file, &u.area))
+            .max_by_key(|u| u.area.trim_matches('/').len());
+        match area {
+            Some(area) if diagnostic.is_warning() => area.warnings += 1,
+            Some(area) => area.errors += 1,
+            None if !diagnostic.is_warning() => unbudgeted_errors += 1,
+            None => {}
+        }
+    }
+    BudgetReport { usage, unbudgeted_errors }
+}
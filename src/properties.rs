@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+use std::ops::Range;
+use serde_json::{Map, Value as JsonValue};
+
+use crate::ini::IniCoercion;
+
+/// How `.properties` files are read (`--properties-nest`, `--properties-coerce`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PropertiesOptions {
+    /// Dotted keys become nested objects: `server.port` is `/server/port`, not `/server.port`
+    pub nest: bool,
+    /// Values read as booleans or numbers rather than strings
+    pub coercion: IniCoercion,
+}
+
+/// A `.properties` file as schemas see it: an object of its keys, flat or nested by their
+/// dots. Values are strings unless coerced.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PropertiesDocument {
+    pub value: JsonValue,
+    // Byte range of the key behind each JSON pointer; nested objects point at the first key
+    // that created them
+    spans: HashMap<String, Range<usize>>,
+}
+
+/// Why a `.properties` file does not parse, and the byte range of the offending line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropertiesError {
+    pub message: String,
+    pub span: Range<usize>,
+}
+
+fn escape_pointer_segment(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+// A line continues onto the next when it ends with an odd number of backslashes
+fn continues(line: &str) -> bool {
+    line.chars().rev().take_while(|&c| c == '\\').count() % 2 == 1
+}
+
+// Resolves `\t`, `\n`, `\r`, `\f` and `\uXXXX`; any other escaped character stands for itself
+fn unescape(raw: &str) -> Result<String, String> {
+    let mut out = String::with_capacity(raw.len());
+    let mut chars = raw.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => out.push('\t'),
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some('f') => out.push('\u{c}'),
+            Some('u') => {
+                let hex: String = chars.by_ref().take(4).collect();
+                let code = u32::from_str_radix(&hex, 16).ok().filter(|_| hex.len() == 4);
+                match code.and_then(char::from_u32) {
+                    Some(decoded) => out.push(decoded),
+                    None => return Err(format!("malformed `\\u{}` escape", hex)),
+                }
+            }
+            Some(other) => out.push(other),
+            None => {}
+        }
+    }
+    Ok(out)
+}
+
+// Splits a logical line into its raw key and raw value: the key ends at the first unescaped
+// `=`, `:` or whitespace, and one `=` or `:` with whitespace around it separates the two
+fn split_entry(line: &str) -> (&str, &str) {
+    let mut escaped = false;
+    let mut key_end = line.len();
+    for (index, c) in line.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == '=' || c == ':' || c.is_whitespace() {
+            key_end = index;
+            break;
+        }
+    }
+    let rest = line[key_end..].trim_start();
+    let rest = rest.strip_prefix(['=', ':']).map_or(rest, str::trim_start);
+    (&line[..key_end], rest)
+}
+
+/// Parses `content` the way `java.util.Properties` loads it: lines starting with `#` or `!`
+/// are comments, a trailing backslash continues a line, and keys are separated from values by
+/// `=`, `:` or whitespace. Unlike Java, a key set twice is an error, as is (when nesting) a key
+/// that both holds a value and has dotted keys under it.
+pub fn parse_properties(content: &str, options: PropertiesOptions) -> Result<PropertiesDocument, PropertiesError> {
+    let mut root = Map::new();
+    let mut spans = HashMap::new();
+    let mut lines = content.split_inclusive('\n');
+    let mut offset = 0;
+    while let Some(line) = lines.next() {
+        let start = offset;
+        offset += line.len();
+        let text = line.trim_end_matches(['\n', '\r']);
+        let trimmed = text.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with(['#', '!']) {
+            continue;
+        }
+        let key_start = start + text.len() - trimmed.len();
+        let line_span = key_start..key_start + trimmed.trim_end().len();
+        // The logical line, with continuations joined and their leading whitespace dropped
+        let mut logical = trimmed.to_string();
+        while continues(&logical) {
+            logical.pop();
+            let Some(next) = lines.next() else { break };
+            offset += next.len();
+            logical.push_str(next.trim_end_matches(['\n', '\r']).trim_start());
+        }
+
+        let (raw_key, raw_value) = split_entry(&logical);
+        let error = |message: String| PropertiesError { message, span: line_span.clone() };
+        let key = unescape(raw_key).map_err(error)?;
+        if key.is_empty() {
+            return Err(error("key is missing before the separator".to_string()));
+        }
+        let value = options.coercion.value(&unescape(raw_value).map_err(error)?);
+        let key_span = key_start..key_start + raw_key.len().min(line_span.len());
+
+        let segments: Vec<&str> = if options.nest { key.split('.').collect() } else { vec![key.as_str()] };
+        if segments.iter().any(|segment| segment.is_empty()) {
+            return Err(error(format!("key `{}` has an empty segment between its dots", key)));
+        }
+        let (leaf, parents) = segments.split_last().expect("split yields a segment");
+        let mut object = &mut root;
+        let mut pointer = String::new();
+        for (depth, parent) in parents.iter().enumerate() {
+            pointer.push('/');
+            pointer.push_str(&escape_pointer_segment(parent));
+            spans.entry(pointer.clone()).or_insert_with(|| key_span.clone());
+            object = match object.entry(parent.to_string()).or_insert_with(|| JsonValue::Object(Map::new())) {
+                JsonValue::Object(child) => child,
+                _ => {
+                    let prefix = segments[..=depth].join(".");
+                    return Err(error(format!("key `{}` nests under `{}`, which is set to a value", key, prefix)));
+                }
+            };
+        }
+        pointer.push('/');
+        pointer.push_str(&escape_pointer_segment(leaf));
+        match object.get(*leaf) {
+            Some(JsonValue::Object(_)) => {
+                return Err(error(format!("key `{}` is set to a value, but dotted keys nest under it", key)));
+            }
+            Some(_) => return Err(error(format!("key `{}` is set twice", key))),
+            None => {}
+        }
+        object.insert(leaf.to_string(), value);
+        spans.insert(pointer, key_span);
+    }
+    Ok(PropertiesDocument { value: JsonValue::Object(root), spans })
+}
+
+impl PropertiesDocument {
+    /// Byte range of the key behind `pointer`, walking up to the closest recorded ancestor.
+    pub fn span_for_pointer(&self, pointer: &str) -> Option<Range<usize>> {
+        let mut candidate = pointer;
+        loop {
+            if let Some(range) = self.spans.get(candidate) {
+                return Some(range.clone());
+            }
+            candidate = &candidate[..candidate.rfind('/')?];
+        }
+    }
+}
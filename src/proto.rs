@@ -0,0 +1,244 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use serde_json::{json, Map, Value as JsonValue};
+use jsonschema::Validator;
+use prost::Message;
+use prost_types::field_descriptor_proto::{Label, Type};
+use prost_types::{DescriptorProto, EnumDescriptorProto, FieldDescriptorProto, FileDescriptorSet};
+
+use crate::error::AppError;
+
+fn descriptor_error(descriptor_path: &Path, message: String) -> AppError {
+    AppError::ProtoDescriptorError {
+        descriptor: descriptor_path.to_string_lossy().into_owned(),
+        message,
+    }
+}
+
+// Every message and enum in the set, keyed by fully-qualified name with a leading dot
+// (the same spelling `type_name` uses on fields).
+struct DescriptorIndex<'a> {
+    messages: HashMap<String, &'a DescriptorProto>,
+    enums: HashMap<String, &'a EnumDescriptorProto>,
+}
+
+impl<'a> DescriptorIndex<'a> {
+    fn build(set: &'a FileDescriptorSet) -> Self {
+        let mut index = DescriptorIndex { messages: HashMap::new(), enums: HashMap::new() };
+        for file in &set.file {
+            let prefix = match file.package() {
+                "" => String::new(),
+                package => format!(".{}", package),
+            };
+            for message in &file.message_type {
+                index.add_message(&prefix, message);
+            }
+            for enum_type in &file.enum_type {
+                index.enums.insert(format!("{}.{}", prefix, enum_type.name()), enum_type);
+            }
+        }
+        index
+    }
+
+    fn add_message(&mut self, prefix: &str, message: &'a DescriptorProto) {
+        let full_name = format!("{}.{}", prefix, message.name());
+        for nested in &message.nested_type {
+            self.add_message(&full_name, nested);
+        }
+        for enum_type in &message.enum_type {
+            self.enums.insert(format!("{}.{}", full_name, enum_type.name()), enum_type);
+        }
+        self.messages.insert(full_name, message);
+    }
+}
+
+fn int64_schema(signed: bool) -> JsonValue {
+    // 64-bit integers are strings in the canonical JSON mapping, but parsers accept numbers too
+    let pattern = if signed { "^-?[0-9]+$" } else { "^[0-9]+$" };
+    let mut integer = json!({ "type": "integer" });
+    if !signed {
+        integer["minimum"] = json!(0);
+    }
+    json!({ "anyOf": [integer, { "type": "string", "pattern": pattern }] })
+}
+
+fn float_schema() -> JsonValue {
+    json!({ "anyOf": [
+        { "type": "number" },
+        { "type": "string", "enum": ["NaN", "Infinity", "-Infinity"] }
+    ] })
+}
+
+// Schemas for google.protobuf.* types that have a special JSON representation
+fn well_known_type_schema(type_name: &str) -> Option<JsonValue> {
+    let schema = match type_name {
+        ".google.protobuf.Timestamp" => json!({ "type": "string", "format": "date-time" }),
+        ".google.protobuf.Duration" => json!({ "type": "string", "pattern": "^-?[0-9]+(\\.[0-9]{1,9})?s$" }),
+        ".google.protobuf.FieldMask" => json!({ "type": "string" }),
+        ".google.protobuf.Struct" => json!({ "type": "object" }),
+        ".google.protobuf.ListValue" => json!({ "type": "array" }),
+        ".google.protobuf.Value" => json!({}),
+        ".google.protobuf.Empty" => json!({ "type": "object", "maxProperties": 0 }),
+        ".google.protobuf.Any" => json!({ "type": "object", "required": ["@type"] }),
+        ".google.protobuf.BoolValue" => json!({ "type": "boolean" }),
+        ".google.protobuf.StringValue" | ".google.protobuf.BytesValue" => json!({ "type": "string" }),
+        ".google.protobuf.Int32Value" | ".google.protobuf.UInt32Value" => json!({ "type": "integer" }),
+        ".google.protobuf.Int64Value" => int64_schema(true),
+        ".google.protobuf.UInt64Value" => int64_schema(false),
+        ".google.protobuf.FloatValue" | ".google.protobuf.DoubleValue" => float_schema(),
+        _ => return None,
+    };
+    Some(schema)
+}
+
+fn enum_schema(enum_type: &EnumDescriptorProto) -> JsonValue {
+    let names: Vec<&str> = enum_type.value.iter().map(|v| v.name()).collect();
+    let numbers: Vec<i32> = enum_type.value.iter().map(|v| v.number()).collect();
+    json!({ "anyOf": [
+        { "type": "string", "enum": names },
+        { "type": "integer", "enum": numbers }
+    ] })
+}
+
+struct SchemaBuilder<'a> {
+    index: DescriptorIndex<'a>,
+    defs: Map<String, JsonValue>,
+}
+
+impl<'a> SchemaBuilder<'a> {
+    // Schema for a single (non-repeated) value of the field's type
+    fn singular_schema(&mut self, field: &FieldDescriptorProto) -> Result<JsonValue, String> {
+        let schema = match field.r#type() {
+            Type::Double | Type::Float => float_schema(),
+            Type::Int64 | Type::Sint64 | Type::Sfixed64 => int64_schema(true),
+            Type::Uint64 | Type::Fixed64 => int64_schema(false),
+            Type::Int32 | Type::Sint32 | Type::Sfixed32 => {
+                json!({ "type": "integer", "minimum": i32::MIN, "maximum": i32::MAX })
+            }
+            Type::Uint32 | Type::Fixed32 => json!({ "type": "integer", "minimum": 0, "maximum": u32::MAX }),
+            Type::Bool => json!({ "type": "boolean" }),
+            Type::String => json!({ "type": "string" }),
+            Type::Bytes => json!({ "type": "string", "contentEncoding": "base64" }),
+            Type::Enum => {
+                let enum_type = self
+                    .index
+                    .enums
+                    .get(field.type_name())
+                    .ok_or_else(|| format!("Unknown enum type {}", field.type_name()))?;
+                enum_schema(enum_type)
+            }
+            Type::Message | Type::Group => {
+                if let Some(schema) = well_known_type_schema(field.type_name()) {
+                    schema
+                } else {
+                    self.message_ref(field.type_name())?
+                }
+            }
+        };
+        Ok(schema)
+    }
+
+    fn field_schema(&mut self, field: &FieldDescriptorProto) -> Result<JsonValue, String> {
+        if field.label() != Label::Repeated {
+            return self.singular_schema(field);
+        }
+        // Map fields are repeated synthetic `*Entry` messages; their JSON form is an object
+        if field.r#type() == Type::Message {
+            if let Some(entry) = self.index.messages.get(field.type_name()).copied() {
+                if entry.options.as_ref().map(|o| o.map_entry()).unwrap_or(false) {
+                    let value_field = entry
+                        .field
+                        .iter()
+                        .find(|f| f.number() == 2)
+                        .ok_or_else(|| format!("Map entry {} has no value field", field.type_name()))?;
+                    let value_schema = self.singular_schema(value_field)?;
+                    return Ok(json!({ "type": "object", "additionalProperties": value_schema }));
+                }
+            }
+        }
+        let item_schema = self.singular_schema(field)?;
+        Ok(json!({ "type": "array", "items": item_schema }))
+    }
+
+    // Registers the message under $defs (once) and returns a reference to it
+    fn message_ref(&mut self, type_name: &str) -> Result<JsonValue, String> {
+        let def_name = type_name.trim_start_matches('.').to_string();
+        if !self.defs.contains_key(&def_name) {
+            let message = *self
+                .index
+                .messages
+                .get(type_name)
+                .ok_or_else(|| format!("Unknown message type {}", type_name))?;
+            // Placeholder first so that recursive messages terminate
+            self.defs.insert(def_name.clone(), json!({}));
+            let schema = self.message_schema(message)?;
+            self.defs.insert(def_name.clone(), schema);
+        }
+        Ok(json!({ "$ref": format!("#/$defs/{}", def_name) }))
+    }
+
+    fn message_schema(&mut self, message: &DescriptorProto) -> Result<JsonValue, String> {
+        let mut properties = Map::new();
+        let mut required = Vec::new();
+        for field in &message.field {
+            let schema = self.field_schema(field)?;
+            // Parsers must accept both the lowerCamelCase JSON name and the original field name
+            let json_name = match field.json_name() {
+                "" => field.name().to_string(),
+                name => name.to_string(),
+            };
+            if json_name != field.name() {
+                properties.insert(field.name().to_string(), schema.clone());
+            }
+            properties.insert(json_name.clone(), schema);
+            // Only proto2 `required` fields have mandatory presence
+            if field.label() == Label::Required {
+                required.push(json_name);
+            }
+        }
+        Ok(json!({
+            "type": "object",
+            "properties": properties,
+            "required": required,
+            "additionalProperties": false
+        }))
+    }
+}
+
+/// Translates one message of a descriptor set into a JSON Schema that follows the
+/// protobuf JSON mapping. `message` may be given with or without a leading dot.
+pub fn message_json_schema(set: &FileDescriptorSet, message: &str) -> Result<JsonValue, String> {
+    let mut builder = SchemaBuilder { index: DescriptorIndex::build(set), defs: Map::new() };
+    let type_name = format!(".{}", message.trim_start_matches('.'));
+    if !builder.index.messages.contains_key(&type_name) {
+        return Err(format!("Message {} not found in descriptor set", message.trim_start_matches('.')));
+    }
+    let root = builder.message_ref(&type_name)?;
+    Ok(json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "$ref": root["$ref"],
+        "$defs": builder.defs
+    }))
+}
+
+/// Reads a binary `FileDescriptorSet` (as produced by `protoc --descriptor_set_out`).
+pub fn load_descriptor_set(descriptor_path: &Path) -> Result<FileDescriptorSet, AppError> {
+    let bytes = fs::read(descriptor_path).map_err(|e| AppError::FileReadError {
+        path: descriptor_path.to_path_buf(),
+        source: e,
+        span: None,
+    })?;
+    FileDescriptorSet::decode(bytes.as_slice())
+        .map_err(|e| descriptor_error(descriptor_path, format!("Not a valid FileDescriptorSet: {}", e)))
+}
+
+/// Compiles a validator for `message` from the descriptor set at `descriptor_path`.
+pub fn compile_message_validator(descriptor_path: &Path, message: &str) -> Result<Validator, AppError> {
+    let set = load_descriptor_set(descriptor_path)?;
+    let schema = message_json_schema(&set, message).map_err(|m| descriptor_error(descriptor_path, m))?;
+    Validator::new(&schema).map_err(|e| AppError::SchemaCompileError {
+        source_display: format!("{} ({})", descriptor_path.display(), message),
+        source: Box::new(e),
+    })
+}
@@ -0,0 +1,113 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use sha2::{Digest, Sha256};
+
+/// Where the tool that produced a report came from.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolInfo {
+    pub name: &'static str,
+    pub version: &'static str,
+    /// Commit the binary was built from, when the build set `TOML_AND_JERRY_BUILD_HASH`
+    pub build_hash: Option<&'static str>,
+}
+
+impl ToolInfo {
+    pub fn current() -> Self {
+        ToolInfo {
+            name: env!("CARGO_PKG_NAME"),
+            version: env!("CARGO_PKG_VERSION"),
+            build_hash: option_env!("TOML_AND_JERRY_BUILD_HASH"),
+        }
+    }
+}
+
+/// A schema or input file identified by its content digest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DigestedArtifact {
+    pub uri: String,
+    /// Hex SHA-256; `None` when the artifact could not be read
+    pub sha256: Option<String>,
+}
+
+/// What was validated, with what, when and where.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Provenance {
+    pub tool: ToolInfo,
+    pub schemas: Vec<DigestedArtifact>,
+    pub inputs: Vec<DigestedArtifact>,
+    pub started_at: String,
+    pub finished_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hostname: Option<String>,
+}
+
+pub(crate) fn hex_sha256(bytes: &[u8]) -> String {
+    Sha256::digest(bytes).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Digest of a file's bytes.
+pub fn file_artifact(path: &Path) -> DigestedArtifact {
+    DigestedArtifact {
+        uri: path.to_string_lossy().into_owned(),
+        sha256: fs::read(path).ok().map(|bytes| hex_sha256(&bytes)),
+    }
+}
+
+/// Digest of a schema document, taken over its compact JSON serialization so remote and
+/// bundled schemas get a stable identity regardless of how they were formatted.
+pub fn schema_artifact(uri: &str, schema: &JsonValue) -> DigestedArtifact {
+    DigestedArtifact {
+        uri: uri.to_string(),
+        sha256: serde_json::to_vec(schema).ok().map(|bytes| hex_sha256(&bytes)),
+    }
+}
+
+/// Digests of every input file, expanding directories in a stable order.
+pub fn input_artifacts(inputs: &[PathBuf]) -> Vec<DigestedArtifact> {
+    let mut files = Vec::new();
+    for input in inputs {
+        if input.is_dir() {
+            let walker = walkdir::WalkDir::new(input).sort_by_file_name();
+            files.extend(walker.into_iter().flatten().filter(|e| e.file_type().is_file()).map(|e| e.into_path()));
+        } else {
+            files.push(input.clone());
+        }
+    }
+    files.iter().map(|f| file_artifact(f)).collect()
+}
+
+/// Name of this machine from `HOSTNAME`/`COMPUTERNAME` or `/etc/hostname`.
+pub fn hostname() -> Option<String> {
+    std::env::var("HOSTNAME")
+        .or_else(|_| std::env::var("COMPUTERNAME"))
+        .ok()
+        .or_else(|| fs::read_to_string("/etc/hostname").ok())
+        .map(|h| h.trim().to_string())
+        .filter(|h| !h.is_empty())
+}
+
+/// RFC 3339 UTC timestamp (`2024-05-01T12:00:00Z`) with second precision.
+pub fn rfc3339(time: SystemTime) -> String {
+    let secs = time.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let (days, rem) = (secs / 86_400, secs % 86_400);
+    // Civil-from-days (Howard Hinnant's algorithm), valid for all post-1970 dates
+    let z = days as i64 + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year, month, day, rem / 3_600, rem % 3_600 / 60, rem % 60
+    )
+}
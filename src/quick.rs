@@ -0,0 +1,79 @@
+use jsonschema::{Draft, Validator};
+use serde_json::{Map, Value as JsonValue};
+
+use crate::error::AppError;
+use crate::renames::MAX_REF_DEPTH;
+use crate::schema;
+
+// `schema` with local `$ref`s at its top followed, so a `$ref`'d root or property shows its type
+fn resolve<'a>(root: &'a JsonValue, mut schema: &'a JsonValue) -> &'a JsonValue {
+    for _ in 0..MAX_REF_DEPTH {
+        match schema.get("$ref").and_then(|r| r.as_str()).and_then(|r| r.strip_prefix('#')).and_then(|p| root.pointer(p)) {
+            Some(target) => schema = target,
+            None => break,
+        }
+    }
+    schema
+}
+
+// The object-level parts of `schema` and of its `allOf` branches, which all apply at the root
+fn parts<'a>(root: &'a JsonValue, schema: &'a JsonValue, depth: usize, into: &mut Vec<&'a JsonValue>) {
+    let schema = resolve(root, schema);
+    into.push(schema);
+    if depth < MAX_REF_DEPTH {
+        for branch in schema.get("allOf").and_then(|b| b.as_array()).into_iter().flatten() {
+            parts(root, branch, depth + 1, into);
+        }
+    }
+}
+
+/// What `--quick` checks of `schema`: the root's `type` and `required` keys and the `type` of
+/// each root property, with `$ref`s and root `allOf` branches merged in. Nested properties,
+/// combinators, patterns, formats and bounds are dropped, so the result is cheap to validate
+/// on every keystroke. The `$schema` dialect is kept.
+pub fn shallow_schema(schema: &JsonValue) -> JsonValue {
+    if !schema.is_object() {
+        return schema.clone();
+    }
+    let mut found = Vec::new();
+    parts(schema, schema, 0, &mut found);
+    let mut shallow = Map::new();
+    if let Some(dialect) = schema.get("$schema") {
+        shallow.insert("$schema".to_string(), dialect.clone());
+    }
+    if let Some(root_type) = found.iter().find_map(|part| part.get("type")) {
+        shallow.insert("type".to_string(), root_type.clone());
+    }
+    let mut required: Vec<JsonValue> = Vec::new();
+    let mut properties = Map::new();
+    for part in &found {
+        for name in part.get("required").and_then(|r| r.as_array()).into_iter().flatten() {
+            if !required.contains(name) {
+                required.push(name.clone());
+            }
+        }
+        for (name, property) in part.get("properties").and_then(|p| p.as_object()).into_iter().flatten() {
+            let property = resolve(schema, property);
+            let entry = properties.entry(name.clone()).or_insert_with(|| JsonValue::Object(Map::new()));
+            if let (Some(property_type), Some(entry)) = (property.get("type"), entry.as_object_mut()) {
+                entry.entry("type").or_insert_with(|| property_type.clone());
+            }
+        }
+    }
+    if !required.is_empty() {
+        shallow.insert("required".to_string(), JsonValue::Array(required));
+    }
+    if !properties.is_empty() {
+        shallow.insert("properties".to_string(), JsonValue::Object(properties));
+    }
+    JsonValue::Object(shallow)
+}
+
+/// Compiles `schema_json` for validation, or only its `shallow_schema` when `quick`.
+pub fn compile(schema_json: &JsonValue, source_display: &str, default_draft: Option<Draft>, quick: bool) -> Result<Validator, AppError> {
+    if quick {
+        schema::compile_schema_with_draft(&shallow_schema(schema_json), source_display, default_draft)
+    } else {
+        schema::compile_schema_with_draft(schema_json, source_display, default_draft)
+    }
+}
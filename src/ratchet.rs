@@ -0,0 +1,97 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use miette::Diagnostic;
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+use crate::validation::PrintableError;
+
+/// Failing-diagnostic counts recorded by `--ratchet`: directory -> diagnostic code -> count.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RatchetState {
+    pub counts: BTreeMap<String, BTreeMap<String, usize>>,
+}
+
+/// A (directory, code) pair with more errors than recorded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Regression {
+    pub area: String,
+    pub code: String,
+    pub recorded: usize,
+    pub current: usize,
+}
+
+impl RatchetState {
+    /// Counts failing diagnostics by the directory of their file and their code.
+    pub fn from_diagnostics(diagnostics: &[AppError]) -> Self {
+        let mut counts: BTreeMap<String, BTreeMap<String, usize>> = BTreeMap::new();
+        for diagnostic in diagnostics.iter().filter(|d| !d.is_warning()) {
+            let file = PrintableError::from(diagnostic).file_path.replace('\\', "/");
+            let area = match file.trim_start_matches("./").rsplit_once('/') {
+                Some((dir, _)) => dir.to_string(),
+                None => ".".to_string(),
+            };
+            let code = diagnostic.code().map_or_else(|| "unknown".to_string(), |c| c.to_string());
+            *counts.entry(area).or_default().entry(code).or_insert(0) += 1;
+        }
+        RatchetState { counts }
+    }
+
+    pub fn total(&self) -> usize {
+        self.counts.values().flat_map(|codes| codes.values()).sum()
+    }
+
+    /// Pairs whose `current` count exceeds this recorded state; unrecorded pairs allow zero.
+    pub fn regressions(&self, current: &RatchetState) -> Vec<Regression> {
+        let mut regressions = Vec::new();
+        for (area, codes) in &current.counts {
+            for (code, count) in codes {
+                let recorded = self.counts.get(area).and_then(|c| c.get(code)).copied().unwrap_or(0);
+                if *count > recorded {
+                    regressions.push(Regression { area: area.clone(), code: code.clone(), recorded, current: *count });
+                }
+            }
+        }
+        regressions
+    }
+}
+
+/// Reads a ratchet state; `None` when the file does not exist yet.
+pub fn load_state(path: &Path) -> Result<Option<RatchetState>, String> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(path).map_err(|e| format!("Cannot read ratchet state {}: {}", path.display(), e))?;
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|e| format!("Invalid ratchet state {}: {}", path.display(), e))
+}
+
+/// Writes the state as pretty JSON, meant to be committed next to the configs.
+pub fn write_state(path: &Path, state: &RatchetState) -> std::io::Result<()> {
+    let rendered = serde_json::to_string_pretty(state)?;
+    crate::paths::check_writable(path)?;
+    fs::write(path, format!("{}\n", rendered))
+}
+
+/// Compares the run with the recorded state and, when nothing regressed, records the new
+/// (equal or lower) counts so the allowance tightens. Returns the regressions and the
+/// summary lines to print.
+pub fn apply_ratchet(path: &Path, diagnostics: &[AppError]) -> Result<(Vec<Regression>, Vec<String>), String> {
+    let current = RatchetState::from_diagnostics(diagnostics);
+    let recorded = load_state(path)?;
+    let regressions = recorded.as_ref().map(|r| r.regressions(&current)).unwrap_or_default();
+    let mut lines: Vec<String> = regressions
+        .iter()
+        .map(|r| format!("Ratchet: {} {} has {} error(s), {} recorded", r.area, r.code, r.current, r.recorded))
+        .collect();
+    if regressions.is_empty() && recorded.as_ref() != Some(&current) {
+        write_state(path, &current).map_err(|e| format!("Cannot write ratchet state {}: {}", path.display(), e))?;
+        lines.push(match &recorded {
+            Some(recorded) => format!("Ratchet tightened: {} -> {} error(s), recorded in {}", recorded.total(), current.total(), path.display()),
+            None => format!("Ratchet started at {} error(s), recorded in {}", current.total(), path.display()),
+        });
+    }
+    Ok((regressions, lines))
+}
@@ -0,0 +1,169 @@
+use std::ops::Range;
+use serde_json::Value as JsonValue;
+
+/// One step of the instance location a rename applies under.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathSegment {
+    Key(String),
+    /// Any array index or additionalProperties key
+    Any,
+}
+
+/// A property renamed in the schema: objects at `parent` should use `to` instead of `from`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenameRule {
+    pub parent: Vec<PathSegment>,
+    pub from: String,
+    pub to: String,
+}
+
+/// A config that still uses an old property name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenameHit {
+    /// JSON pointer of the object holding the old key
+    pub parent_pointer: String,
+    pub from: String,
+    pub to: String,
+}
+
+// Deep enough for real schemas while stopping self-referential $refs
+pub(crate) const MAX_REF_DEPTH: usize = 32;
+
+pub(crate) fn renamed_from(schema: &JsonValue) -> Vec<String> {
+    match schema.get("x-renamed-from") {
+        Some(JsonValue::String(old)) => vec![old.clone()],
+        Some(JsonValue::Array(olds)) => olds.iter().filter_map(|o| o.as_str().map(str::to_string)).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn walk_schema(root: &JsonValue, schema: &JsonValue, path: &[PathSegment], depth: usize, rules: &mut Vec<RenameRule>) {
+    if depth > MAX_REF_DEPTH {
+        return;
+    }
+    if let Some(target) = schema.get("$ref").and_then(|r| r.as_str()).and_then(|r| r.strip_prefix('#')) {
+        if let Some(resolved) = root.pointer(target) {
+            walk_schema(root, resolved, path, depth + 1, rules);
+        }
+    }
+    if let Some(JsonValue::Object(properties)) = schema.get("properties") {
+        for (name, property) in properties {
+            for old in renamed_from(property) {
+                let rule = RenameRule { parent: path.to_vec(), from: old, to: name.clone() };
+                if !rules.contains(&rule) {
+                    rules.push(rule);
+                }
+            }
+            let mut child = path.to_vec();
+            child.push(PathSegment::Key(name.clone()));
+            walk_schema(root, property, &child, depth, rules);
+        }
+    }
+    for key in ["items", "additionalProperties"] {
+        if let Some(sub) = schema.get(key).filter(|s| s.is_object()) {
+            let mut child = path.to_vec();
+            child.push(PathSegment::Any);
+            walk_schema(root, sub, &child, depth, rules);
+        }
+    }
+    for key in ["allOf", "anyOf", "oneOf"] {
+        if let Some(JsonValue::Array(branches)) = schema.get(key) {
+            for branch in branches {
+                walk_schema(root, branch, path, depth, rules);
+            }
+        }
+    }
+}
+
+/// Collects every `x-renamed-from` annotation reachable from the schema root.
+pub fn collect_rename_rules(schema: &JsonValue) -> Vec<RenameRule> {
+    let mut rules = Vec::new();
+    walk_schema(schema, schema, &[], 0, &mut rules);
+    rules
+}
+
+fn escape_pointer_segment(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+fn visit_instance(
+    value: &JsonValue,
+    pointer: &str,
+    path: &mut Vec<String>,
+    rules: &[RenameRule],
+    hits: &mut Vec<RenameHit>,
+) {
+    match value {
+        JsonValue::Object(map) => {
+            for rule in rules.iter().filter(|r| parent_matches(&r.parent, path)) {
+                if map.contains_key(&rule.from) {
+                    hits.push(RenameHit { parent_pointer: pointer.to_string(), from: rule.from.clone(), to: rule.to.clone() });
+                }
+            }
+            for (key, child) in map {
+                path.push(key.clone());
+                visit_instance(child, &format!("{}/{}", pointer, escape_pointer_segment(key)), path, rules, hits);
+                path.pop();
+            }
+        }
+        JsonValue::Array(items) => {
+            for (index, child) in items.iter().enumerate() {
+                path.push(index.to_string());
+                visit_instance(child, &format!("{}/{}", pointer, index), path, rules, hits);
+                path.pop();
+            }
+        }
+        _ => {}
+    }
+}
+
+fn parent_matches(pattern: &[PathSegment], path: &[String]) -> bool {
+    pattern.len() == path.len()
+        && pattern.iter().zip(path).all(|(segment, actual)| match segment {
+            PathSegment::Any => true,
+            PathSegment::Key(key) => key == actual,
+        })
+}
+
+/// Finds objects in `instance` that still use a renamed property's old name.
+pub fn find_renamed_properties(instance: &JsonValue, rules: &[RenameRule]) -> Vec<RenameHit> {
+    let mut hits = Vec::new();
+    if !rules.is_empty() {
+        visit_instance(instance, "", &mut Vec::new(), rules, &mut hits);
+    }
+    hits
+}
+
+fn is_key_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '-'
+}
+
+// `key` at byte `start`, if it is written as a key there (not part of a longer name or a value)
+fn key_span_at(content: &str, key: &str, start: usize) -> Option<Range<usize>> {
+    let end = start + key.len();
+    let before = content[..start].chars().next_back();
+    let after_raw = &content[end..];
+    let (quoted_before, after) = match (before, after_raw.chars().next()) {
+        (Some(q @ '"'), Some(c)) | (Some(q @ '\''), Some(c)) if c == q => (true, &after_raw[1..]),
+        _ => (false, after_raw),
+    };
+    if !quoted_before && before.is_some_and(is_key_char) {
+        return None;
+    }
+    if !quoted_before && after_raw.chars().next().is_some_and(is_key_char) {
+        return None;
+    }
+    let next = after.trim_start_matches([' ', '\t']).chars().next();
+    matches!(next, Some(':') | Some('=')).then_some(start..end)
+}
+
+/// Byte range of `key` where it is written as a key (`"key":`, `key:`, `key =`),
+/// excluding any quotes, so replacing the range renames it without touching formatting.
+pub fn find_key_span(content: &str, key: &str) -> Option<Range<usize>> {
+    content.match_indices(key).find_map(|(start, _)| key_span_at(content, key, start))
+}
+
+/// Every place `key` is written as a key, in file order.
+pub fn find_key_spans(content: &str, key: &str) -> Vec<Range<usize>> {
+    content.match_indices(key).filter_map(|(start, _)| key_span_at(content, key, start)).collect()
+}
@@ -0,0 +1,235 @@
+use std::fmt;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use jsonschema::Validator;
+use miette::{MietteHandler, ReportHandler};
+use serde_json::Value as JsonValue;
+
+use crate::color::{self, ColorChoice};
+use crate::config_merge;
+use crate::error::AppError;
+use crate::patch::load_instance;
+use crate::paths;
+use crate::renames;
+use crate::schema_keys;
+use crate::validation::{validate_inputs_with_options, ValidationOptions};
+
+// Sessions of one process each get their own scratch directory
+static SESSIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// Snippet formats the REPL can validate.
+pub const FORMATS: &[&str] = &["yaml", "json", "toml", "hcl"];
+
+const HELP: &str = "\
+Type or paste a snippet, then `.` on a line of its own (or :check) to validate it.
+  :format yaml|json|toml|hcl   format of the snippet (now: {format})
+  :check                       validate the snippet
+  :set PATH=VALUE              set a value (JSON, else a string) and validate again,
+                               e.g. :set server.port=8080 or :set tags.0=\"web\"
+  :keys [PREFIX]               schema paths, optionally only those under PREFIX
+  :show                        print the snippet
+  :clear                       start a new snippet
+  :help                        this help
+  :quit                        leave
+";
+
+/// An interactive session against one schema. Snippets are validated with the same
+/// pipeline as `check`, from a scratch file in a private temporary directory.
+pub struct Session {
+    schema: JsonValue,
+    validator: Validator,
+    options: ValidationOptions,
+    format: String,
+    snippet: Vec<String>,
+    // A checked snippet is kept for :set; the next line typed starts a new one
+    checked: bool,
+    dir: PathBuf,
+}
+
+impl Session {
+    pub fn new(schema: JsonValue, validator: Validator, format: &str) -> io::Result<Self> {
+        let session = SESSIONS.fetch_add(1, Ordering::Relaxed);
+        let dir = std::env::temp_dir().join(format!("toml-and-jerry-repl-{}-{}", std::process::id(), session));
+        paths::check_writable(&dir)?;
+        fs::create_dir_all(&dir)?;
+        let options = ValidationOptions { rename_rules: renames::collect_rename_rules(&schema), ..ValidationOptions::default() };
+        Ok(Session { schema, validator, options, format: format.to_string(), snippet: Vec::new(), checked: false, dir })
+    }
+
+    fn scratch_file(&self) -> PathBuf {
+        self.dir.join(format!("snippet.{}", self.format))
+    }
+
+    fn write_snippet(&self) -> io::Result<PathBuf> {
+        let path = self.scratch_file();
+        let mut text = self.snippet.join("\n");
+        text.push('\n');
+        paths::check_writable(&path)?;
+        fs::write(&path, text)?;
+        Ok(path)
+    }
+
+    /// Validates the snippet; the reports name the scratch file `snippet.<format>`.
+    pub fn check(&mut self) -> io::Result<String> {
+        self.checked = true;
+        let path = self.write_snippet()?;
+        let mut errors = match validate_inputs_with_options(vec![path.clone()], &self.validator, &self.options) {
+            Ok(errors) => errors,
+            Err(e) => vec![e],
+        };
+        if errors.is_empty() {
+            return Ok("valid\n".to_string());
+        }
+        self.explain_schema_errors(&path, &mut errors);
+        // Messages are not wrapped: the terminal wraps them at its own width
+        let handler = color::handler_opts(ColorChoice::Auto.with_env()).wrap_lines(false).build();
+        let mut out = String::new();
+        for error in errors.iter_mut() {
+            error.map_paths(&|p: &Path| p.strip_prefix(&self.dir).map_or_else(|_| p.to_path_buf(), Path::to_path_buf));
+            out.push_str(&format!("{}\n", Rendered(&handler, error)));
+        }
+        out.push_str(&format!("{} diagnostic(s)\n", errors.len()));
+        Ok(out)
+    }
+
+    // Schema errors carry the validator's own message (`80 is less than the minimum of 1024`)
+    // instead of the generic one, as the person typing has no report to look the keyword up in
+    fn explain_schema_errors(&self, scratch: &Path, errors: &mut [AppError]) {
+        let Some(document) = load_instance(scratch, &self.options.yaml_tags) else {
+            return;
+        };
+        for error in errors.iter_mut() {
+            if let AppError::SchemaValidationError(error) = error {
+                if let Some(found) = self.validator.iter_errors(&document).find(|e| e.instance_path.to_string() == error.instance_path) {
+                    error.message = found.to_string();
+                }
+            }
+        }
+    }
+
+    // Applies `PATH=VALUE` to the parsed snippet and writes it back in the snippet's format
+    fn set(&mut self, assignment: &str) -> Result<(), String> {
+        let (path, raw) = assignment.split_once('=').ok_or("expected PATH=VALUE")?;
+        let value = serde_json::from_str(raw.trim()).unwrap_or_else(|_| JsonValue::String(raw.trim().to_string()));
+        if self.format == "hcl" {
+            return Err(":set rewrites the snippet, which works for yaml, json and toml only".to_string());
+        }
+        let scratch = self.write_snippet().map_err(|e| e.to_string())?;
+        let mut document = if self.snippet.iter().all(|line| line.trim().is_empty()) {
+            JsonValue::Object(Default::default())
+        } else {
+            load_instance(&scratch, &self.options.yaml_tags).ok_or_else(|| format!("the snippet is not a single {} document", self.format))?
+        };
+        set_path(&mut document, path.trim(), value)?;
+        let rendered = config_merge::render_merged(&document, Some(&scratch))?;
+        self.snippet = rendered.lines().map(str::to_string).collect();
+        Ok(())
+    }
+
+    fn keys(&self, prefix: &str) -> String {
+        let mut keys = schema_keys::schema_keys(&self.schema, "repl");
+        keys.keys.retain(|key| key.path.starts_with(prefix));
+        match schema_keys::render_human(&keys) {
+            listing if listing.is_empty() => format!("no schema paths start with {:?}\n", prefix),
+            listing => listing,
+        }
+    }
+
+    /// Handles one line of input; `None` once the session should end.
+    pub fn handle(&mut self, line: &str) -> Option<String> {
+        let command = line.trim();
+        let (name, argument) = command.split_once(char::is_whitespace).map_or((command, ""), |(n, a)| (n, a.trim()));
+        let reply = match name {
+            "." | ":check" => self.check().unwrap_or_else(|e| format!("cannot write the scratch file: {}\n", e)),
+            ":quit" | ":q" | ":exit" => return None,
+            ":help" | ":h" => HELP.replace("{format}", &self.format),
+            ":format" if FORMATS.contains(&argument) => {
+                self.format = argument.to_string();
+                format!("format: {}\n", self.format)
+            }
+            ":format" => format!("unknown format {:?}; expected one of {}\n", argument, FORMATS.join(", ")),
+            ":set" => match self.set(argument) {
+                Ok(()) => self.check().unwrap_or_else(|e| format!("cannot write the scratch file: {}\n", e)),
+                Err(message) => format!("{}\n", message),
+            },
+            ":keys" => self.keys(argument),
+            ":show" => self.snippet.iter().map(|line| format!("{}\n", line)).collect(),
+            ":clear" => {
+                self.snippet.clear();
+                self.checked = false;
+                String::new()
+            }
+            _ if name.starts_with(':') => format!("unknown command {}; :help lists them\n", name),
+            _ => {
+                if self.checked {
+                    self.snippet.clear();
+                    self.checked = false;
+                }
+                self.snippet.push(line.to_string());
+                String::new()
+            }
+        };
+        Some(reply)
+    }
+}
+
+struct Rendered<'a>(&'a MietteHandler, &'a AppError);
+
+impl fmt::Display for Rendered<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.debug(self.1, f)
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        fs::remove_dir_all(&self.dir).ok();
+    }
+}
+
+/// Sets the value at a dotted path (`server.port`, `tags.0`), creating objects on the way.
+/// Numeric segments index into arrays; one past the end appends.
+pub fn set_path(document: &mut JsonValue, path: &str, value: JsonValue) -> Result<(), String> {
+    if path.is_empty() {
+        return Err("expected PATH=VALUE".to_string());
+    }
+    let mut current = document;
+    for segment in path.split('.') {
+        if current.is_null() {
+            *current = JsonValue::Object(Default::default());
+        }
+        current = match current {
+            JsonValue::Object(object) => object.entry(segment).or_insert(JsonValue::Null),
+            JsonValue::Array(items) => {
+                let index: usize = segment.parse().map_err(|_| format!("{:?} is not an index into an array", segment))?;
+                if index == items.len() {
+                    items.push(JsonValue::Null);
+                }
+                items.get_mut(index).ok_or_else(|| format!("index {} is past the end of the array", index))?
+            }
+            _ => return Err(format!("cannot set {:?} inside a scalar in {:?}", segment, path)),
+        };
+    }
+    *current = value;
+    Ok(())
+}
+
+/// Reads lines until `:quit` or end of input, writing replies (and, when `prompt` is set, a
+/// `format>` prompt before each line).
+pub fn run(session: &mut Session, input: impl BufRead, mut output: impl Write, prompt: bool) -> io::Result<()> {
+    let mut lines = input.lines();
+    loop {
+        if prompt {
+            write!(output, "{}> ", session.format)?;
+            output.flush()?;
+        }
+        let Some(line) = lines.next().transpose()? else { break };
+        match session.handle(&line) {
+            Some(reply) => output.write_all(reply.as_bytes())?,
+            None => break,
+        }
+    }
+    Ok(())
+}
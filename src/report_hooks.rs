@@ -0,0 +1,108 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::status::RunStatus;
+use crate::validation::PrintableError;
+
+/// The project config's `[report]` table.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ReportConfig {
+    /// Report processors keyed by name, run in name order after every `check`
+    #[serde(default)]
+    pub hooks: BTreeMap<String, ReportHook>,
+}
+
+/// An external command that receives the final report as JSON on stdin, e.g.
+/// `[report.hooks.tickets] command = ["./scripts/open-tickets", "--project", "CFG"]`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ReportHook {
+    /// Program and arguments; not run through a shell
+    pub command: Vec<String>,
+    /// Seconds the hook may run before it is killed
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+    #[serde(default)]
+    pub on_failure: FailurePolicy,
+}
+
+fn default_timeout_secs() -> u64 {
+    30
+}
+
+/// What a failing, timed-out or missing hook does to the run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FailurePolicy {
+    /// Print the failure; the exit code is unchanged
+    #[default]
+    Warn,
+    /// Fail the run
+    Fail,
+    /// Say nothing
+    Ignore,
+}
+
+/// The report hooks receive: the run status and every diagnostic as in `--format json`.
+pub fn hook_payload(status: &RunStatus, diagnostics: &[PrintableError]) -> serde_json::Value {
+    json!({ "status": status, "diagnostics": diagnostics })
+}
+
+/// Runs `hook` with `payload` on stdin. `Err` says why it failed.
+pub fn run_hook(name: &str, hook: &ReportHook, payload: &serde_json::Value) -> Result<(), String> {
+    let (program, args) = hook.command.split_first().ok_or_else(|| format!("Report hook {:?} has an empty command", name))?;
+    let mut child = Command::new(program)
+        .args(args)
+        .env("TOML_AND_JERRY_HOOK", name)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .map_err(|e| format!("Report hook {:?} could not start {:?}: {}", name, program, e))?;
+    // Written from a thread so a hook that stops reading cannot block the timeout
+    let input = payload.to_string();
+    let mut stdin = child.stdin.take();
+    let writer = thread::spawn(move || stdin.as_mut().map(|s| s.write_all(input.as_bytes())));
+
+    let deadline = Instant::now() + Duration::from_secs(hook.timeout_secs);
+    let status = loop {
+        match child.try_wait().map_err(|e| format!("Report hook {:?}: {}", name, e))? {
+            Some(status) => break status,
+            None if Instant::now() >= deadline => {
+                child.kill().ok();
+                child.wait().ok();
+                return Err(format!("Report hook {:?} timed out after {}s", name, hook.timeout_secs));
+            }
+            None => thread::sleep(Duration::from_millis(20)),
+        }
+    };
+    writer.join().ok();
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("Report hook {:?} failed ({})", name, status))
+    }
+}
+
+/// Runs every hook in name order. Returns whether a hook with `on_failure = "fail"` failed.
+pub fn run_hooks(hooks: &BTreeMap<String, ReportHook>, payload: &serde_json::Value) -> bool {
+    let mut failed = false;
+    for (name, hook) in hooks {
+        let Err(message) = run_hook(name, hook, payload) else { continue };
+        match hook.on_failure {
+            FailurePolicy::Warn => eprintln!("{}", message),
+            FailurePolicy::Fail => {
+                eprintln!("{}", message);
+                failed = true;
+            }
+            FailurePolicy::Ignore => {}
+        }
+    }
+    failed
+}
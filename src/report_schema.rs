@@ -0,0 +1,177 @@
+use serde::Serialize;
+use serde_json::{json, Value as JsonValue};
+
+use crate::error::AppError;
+use crate::provenance::{self, Provenance, ToolInfo};
+use crate::summary::RunSummary;
+use crate::validation::PrintableError;
+
+/// Version of the `--format json` report shape. Any change to the shape, including a new
+/// diagnostic field, bumps it along with `report_schema`.
+pub const REPORT_VERSION: u32 = 5;
+
+/// A `--format json` report: the diagnostics of one run and what produced them.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonReport<'a> {
+    pub report_version: u32,
+    pub tool: ToolInfo,
+    /// The schema the inputs were checked against; `None` when presets, rules packs or
+    /// tj-schema comments pick one per file
+    pub schema: Option<&'a str>,
+    pub generated_at: String,
+    pub errors: Vec<PrintableError>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<&'a Provenance>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<&'a RunSummary>,
+}
+
+impl<'a> JsonReport<'a> {
+    pub fn new(schema: Option<&'a str>, errors: &[AppError]) -> Self {
+        JsonReport::from_printable(schema, errors.iter().map(PrintableError::from).collect())
+    }
+
+    /// A report of diagnostics read back from other reports, as `merge-reports` writes.
+    pub fn from_printable(schema: Option<&'a str>, errors: Vec<PrintableError>) -> Self {
+        JsonReport {
+            report_version: REPORT_VERSION,
+            tool: ToolInfo::current(),
+            schema,
+            generated_at: provenance::rfc3339(std::time::SystemTime::now()),
+            errors,
+            provenance: None,
+            summary: None,
+        }
+    }
+}
+
+fn nullable(type_name: &str) -> JsonValue {
+    json!({ "type": [type_name, "null"] })
+}
+
+/// JSON Schema (2020-12) of the `--format json` report of this `REPORT_VERSION`. Objects
+/// are closed, so a report with fields the schema does not list fails validation.
+pub fn report_schema() -> JsonValue {
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "$id": format!("urn:toml-and-jerry:report:{}", REPORT_VERSION),
+        "title": "toml-and-jerry JSON report",
+        "description": "Diagnostics of one toml-and-jerry run (--format json) with the tool, schema and time that produced them.",
+        "type": "object",
+        "required": ["reportVersion", "tool", "schema", "generatedAt", "errors"],
+        "properties": {
+            "reportVersion": { "const": REPORT_VERSION, "description": "Version of this report shape" },
+            "tool": { "$ref": "#/$defs/Tool" },
+            "schema": {
+                "type": ["string", "null"],
+                "description": "Schema the inputs were checked against; null when presets, rules packs or tj-schema comments pick one per file"
+            },
+            "generatedAt": { "type": "string", "format": "date-time", "description": "When the report was written (RFC 3339, UTC)" },
+            "errors": { "type": "array", "items": { "$ref": "#/$defs/Diagnostic" } },
+            "provenance": { "$ref": "#/$defs/Provenance" },
+            "summary": { "$ref": "#/$defs/Summary" }
+        },
+        "additionalProperties": false,
+        "$defs": {
+            "Tool": {
+                "type": "object",
+                "required": ["name", "version", "buildHash"],
+                "properties": {
+                    "name": { "type": "string" },
+                    "version": { "type": "string" },
+                    "buildHash": nullable("string")
+                },
+                "additionalProperties": false
+            },
+            "Diagnostic": {
+                "type": "object",
+                "required": ["filePath", "errorType", "message", "line", "column", "jsonPath", "ruleId"],
+                "properties": {
+                    "filePath": { "type": "string" },
+                    "errorType": { "type": "string" },
+                    "message": { "type": "string" },
+                    "line": nullable("integer"),
+                    "column": nullable("integer"),
+                    "jsonPath": nullable("string"),
+                    "ruleId": { "type": "string", "description": "Diagnostic code, e.g. app::schema::validation_error" },
+                    "severity": { "enum": ["error", "warning", "info"], "description": "Absent in diagnostics merged from older reports" },
+                    "category": { "enum": ["io", "parse", "schema", "policy", "style"] },
+                    "snippet": { "$ref": "#/$defs/Snippet" },
+                    "violation": { "$ref": "#/$defs/Violation" }
+                },
+                "additionalProperties": false
+            },
+            "Snippet": {
+                "type": "object",
+                "description": "Source lines the diagnostic points at; absent when it points at no particular place",
+                "required": ["startLine", "text", "caretStart", "caretEnd"],
+                "properties": {
+                    "startLine": { "type": "integer", "minimum": 1 },
+                    "text": { "type": "string", "description": "The lines, joined with \\n" },
+                    "caretStart": { "type": "integer", "minimum": 1, "description": "Byte column on the first line where the marked range starts" },
+                    "caretEnd": { "type": "integer", "minimum": 1, "description": "Byte column on the last line just past the marked range" }
+                },
+                "additionalProperties": false
+            },
+            "Violation": {
+                "type": "object",
+                "description": "What a schema validation error is about; absent for other diagnostics",
+                "required": ["keyword"],
+                "properties": {
+                    "keyword": { "type": "string", "description": "The failing schema keyword, e.g. minimum" },
+                    "expected": { "description": "The keyword's requirement: type(s), enum values, limit, missing property, pattern or format" },
+                    "actual": { "description": "The offending value; absent when it is long" }
+                },
+                "additionalProperties": false
+            },
+            "Artifact": {
+                "type": "object",
+                "required": ["uri", "sha256"],
+                "properties": { "uri": { "type": "string" }, "sha256": nullable("string") },
+                "additionalProperties": false
+            },
+            "Provenance": {
+                "type": "object",
+                "required": ["tool", "schemas", "inputs", "startedAt", "finishedAt"],
+                "properties": {
+                    "tool": { "$ref": "#/$defs/Tool" },
+                    "schemas": { "type": "array", "items": { "$ref": "#/$defs/Artifact" } },
+                    "inputs": { "type": "array", "items": { "$ref": "#/$defs/Artifact" } },
+                    "startedAt": { "type": "string", "format": "date-time" },
+                    "finishedAt": { "type": "string", "format": "date-time" },
+                    "hostname": { "type": "string" }
+                },
+                "additionalProperties": false
+            },
+            "Summary": {
+                "type": "object",
+                "required": ["filesScanned", "filesSkipped", "filesPassed", "filesFailed", "errors", "warnings", "errorsByRule", "elapsedMs"],
+                "properties": {
+                    "filesScanned": { "type": "integer" },
+                    "filesSkipped": { "type": "integer" },
+                    "filesPassed": { "type": "integer" },
+                    "filesFailed": { "type": "integer" },
+                    "errors": { "type": "integer" },
+                    "warnings": { "type": "integer" },
+                    "errorsByRule": { "type": "object", "additionalProperties": { "type": "integer" } },
+                    "elapsedMs": { "type": "integer" },
+                    "gates": { "type": "array", "items": { "$ref": "#/$defs/Gate" } }
+                },
+                "additionalProperties": false
+            },
+            "Gate": {
+                "type": "object",
+                "required": ["gate", "filesChecked", "filesFailed", "errors", "warnings"],
+                "properties": {
+                    "gate": { "type": "string", "description": "Schema, preset rule or rules pack rule" },
+                    "filesChecked": { "type": "integer" },
+                    "filesFailed": { "type": "integer" },
+                    "errors": { "type": "integer" },
+                    "warnings": { "type": "integer" }
+                },
+                "additionalProperties": false
+            }
+        }
+    })
+}
@@ -0,0 +1,61 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::error::AppError;
+use crate::validation::PrintableError;
+
+type Sink = Box<dyn FnMut(&[AppError]) + Send>;
+
+static SINK: Mutex<Option<Sink>> = Mutex::new(None);
+
+/// Hands each file's diagnostics to `sink` as soon as validation finishes the file, until `stop`.
+pub fn stream_to(sink: impl FnMut(&[AppError]) + Send + 'static) {
+    *SINK.lock().unwrap_or_else(|e| e.into_inner()) = Some(Box::new(sink));
+}
+
+pub fn stop() {
+    SINK.lock().unwrap_or_else(|e| e.into_inner()).take();
+}
+
+/// Called by validation with the diagnostics of the file it just finished; does nothing
+/// unless a sink is set.
+pub fn file_done(errors: &[AppError]) {
+    if errors.is_empty() {
+        return;
+    }
+    if let Some(sink) = SINK.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+        sink(errors);
+    }
+}
+
+/// One diagnostic as an NDJSON line (compact JSON, no line break).
+pub fn ndjson_line(error: &PrintableError) -> String {
+    serde_json::to_string(error).unwrap_or_default()
+}
+
+/// NDJSON lines already printed while streaming, so the end of the run prints only the rest.
+#[derive(Debug, Clone, Default)]
+pub struct Streamed(Arc<Mutex<HashMap<String, usize>>>);
+
+impl Streamed {
+    pub fn print(&self, line: String) {
+        println!("{}", line);
+        *self.0.lock().unwrap_or_else(|e| e.into_inner()).entry(line).or_insert(0) += 1;
+    }
+
+    /// The lines of a full NDJSON `report` that were not printed yet.
+    pub fn remainder(&self, report: &str) -> String {
+        let mut printed = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        report
+            .lines()
+            .filter(|line| match printed.get_mut(*line) {
+                Some(count) if *count > 0 => {
+                    *count -= 1;
+                    false
+                }
+                _ => true,
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
@@ -0,0 +1,74 @@
+use std::path::PathBuf;
+use globset::GlobBuilder;
+use serde::Deserialize;
+
+use crate::error::AppError;
+use crate::schema::load_and_compile_schema;
+use crate::validation::{validate_inputs_with_options, ValidationOptions};
+
+/// A file every matching directory must contain, e.g.
+/// `[[require]] dirs = "services/*"  file = "service.yaml"  schema = "schemas/service.json"`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RequiredFile {
+    /// Glob of directories, relative to the current directory; `*` stays within one directory
+    pub dirs: String,
+    /// File name (or relative path) each of those directories must contain
+    pub file: String,
+    /// Schema the files must conform to, if any
+    #[serde(default)]
+    pub schema: Option<PathBuf>,
+}
+
+// Where to start looking for directories: the glob's segments before the first wildcard
+fn glob_base(pattern: &str) -> PathBuf {
+    let literal: Vec<&str> = pattern.split('/').take_while(|segment| !segment.contains(['*', '?', '[', '{'])).collect();
+    if literal.is_empty() {
+        PathBuf::from(".")
+    } else {
+        PathBuf::from(literal.join("/"))
+    }
+}
+
+/// Directories matching `pattern`, sorted. Ignored directories (`.gitignore`) are skipped.
+pub fn matching_dirs(pattern: &str) -> Result<Vec<PathBuf>, String> {
+    let pattern = pattern.trim_end_matches('/');
+    let matcher = GlobBuilder::new(pattern)
+        .literal_separator(true)
+        .build()
+        .map_err(|e| format!("Invalid [[require]] dirs {:?}: {}", pattern, e))?
+        .compile_matcher();
+    let mut dirs: Vec<PathBuf> = ignore::WalkBuilder::new(glob_base(pattern))
+        .hidden(false)
+        .build()
+        .flatten()
+        .filter(|entry| entry.file_type().is_some_and(|t| t.is_dir()))
+        .map(|entry| entry.path().strip_prefix(".").unwrap_or(entry.path()).to_path_buf())
+        .filter(|dir| matcher.is_match(dir.to_string_lossy().replace('\\', "/")))
+        .collect();
+    dirs.sort();
+    Ok(dirs)
+}
+
+/// Checks every `[[require]]` entry: a diagnostic for each directory without its file, and
+/// the diagnostics of validating the files that exist against the entry's schema.
+pub fn check_required_files(rules: &[RequiredFile], options: &ValidationOptions) -> Result<Vec<AppError>, String> {
+    let mut errors = Vec::new();
+    for rule in rules {
+        let mut present = Vec::new();
+        for dir in matching_dirs(&rule.dirs)? {
+            let path = dir.join(&rule.file);
+            if path.is_file() {
+                present.push(path);
+            } else {
+                errors.push(AppError::MissingRequiredFile { path, dirs: rule.dirs.clone() });
+            }
+        }
+        let Some(schema) = rule.schema.as_ref().filter(|_| !present.is_empty()) else { continue };
+        match load_and_compile_schema(schema).and_then(|validator| validate_inputs_with_options(present, &validator, options)) {
+            Ok(found) => errors.extend(found),
+            Err(e) => errors.push(e),
+        }
+    }
+    Ok(errors)
+}
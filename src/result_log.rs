@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+use crate::progress::ProgressHandler;
+use crate::provenance;
+
+/// One line of a `--result-log`: the outcome of a file, written as soon as it was validated.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileResult {
+    pub path: String,
+    /// Digest of the file as it was validated
+    pub sha256: Option<String>,
+    /// The schema it was validated against, as `ResultLog::open` was given it
+    pub schema: String,
+    pub errors: usize,
+    pub warnings: usize,
+    pub finished_at: String,
+}
+
+/// An append-only NDJSON log of file outcomes. Each line is written with a single append
+/// and synced before the next file starts, so an interrupted run loses at most the file it
+/// was on, and runs sharing a log (shards) do not interleave their lines.
+#[derive(Debug)]
+pub struct ResultLog {
+    path: PathBuf,
+    file: File,
+    schema: String,
+}
+
+impl ResultLog {
+    /// Opens `path` for appending, creating it if needed. `schema` identifies what the files
+    /// are validated against; `--resume` skips only files logged with the same one.
+    pub fn open(path: &Path, schema: String) -> io::Result<Self> {
+        crate::paths::check_writable(path)?;
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(ResultLog { path: path.to_path_buf(), file, schema })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Appends the outcome of `path` and syncs it to disk.
+    pub fn append(&mut self, path: &Path, diagnostics: &[AppError]) -> io::Result<()> {
+        let warnings = diagnostics.iter().filter(|d| d.is_warning()).count();
+        let entry = FileResult {
+            path: path.to_string_lossy().into_owned(),
+            sha256: provenance::file_artifact(path).sha256,
+            schema: self.schema.clone(),
+            errors: diagnostics.len() - warnings,
+            warnings,
+            finished_at: provenance::rfc3339(SystemTime::now()),
+        };
+        let mut line = serde_json::to_string(&entry)?;
+        line.push('\n');
+        self.file.write_all(line.as_bytes())?;
+        self.file.sync_data()
+    }
+}
+
+/// The entries of a result log, oldest first. A missing log has none; lines that do not
+/// parse (the last one of an interrupted write) are skipped.
+pub fn read_log(path: &Path) -> Vec<FileResult> {
+    let Ok(content) = fs::read_to_string(path) else { return Vec::new() };
+    content.lines().filter_map(|line| serde_json::from_str(line).ok()).collect()
+}
+
+/// The `inputs` `--resume` can skip: those whose latest entry in `entries` is for the same
+/// schema, reported nothing, and whose digest still matches. Files that had diagnostics are
+/// validated again, so the run reports them.
+pub fn already_passed(entries: &[FileResult], schema: &str, inputs: &[PathBuf]) -> Vec<PathBuf> {
+    let latest: HashMap<&str, &FileResult> = entries.iter().map(|entry| (entry.path.as_str(), entry)).collect();
+    inputs
+        .iter()
+        .filter(|input| {
+            latest.get(input.to_string_lossy().as_ref()).is_some_and(|entry| {
+                entry.schema == schema
+                    && entry.errors + entry.warnings == 0
+                    && entry.sha256.is_some()
+                    && entry.sha256 == provenance::file_artifact(input).sha256
+            })
+        })
+        .cloned()
+        .collect()
+}
+
+/// A progress handler that logs every finished file to a `ResultLog`, then hands each event
+/// on to `inner`.
+pub struct LoggingProgress<H> {
+    pub inner: H,
+    pub log: ResultLog,
+}
+
+impl<H: ProgressHandler> ProgressHandler for LoggingProgress<H> {
+    fn file_started(&mut self, path: &Path) {
+        self.inner.file_started(path);
+    }
+
+    fn file_finished(&mut self, path: &Path, diagnostics: &[AppError]) {
+        if let Err(e) = self.log.append(path, diagnostics) {
+            eprintln!("Cannot append to result log {}: {}", self.log.path().display(), e);
+        }
+        self.inner.file_finished(path, diagnostics);
+    }
+
+    fn run_finished(&mut self, diagnostics: &[AppError]) {
+        self.inner.run_finished(diagnostics);
+    }
+}
@@ -0,0 +1,334 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::Deserialize;
+
+use crate::error::AppError;
+use crate::expect_invalid;
+use crate::paths;
+use crate::presets::{self, SemanticCheck};
+use crate::progress;
+use crate::schema::load_and_compile_schema;
+use crate::severity;
+use crate::summary;
+use crate::validation::{validate_inputs_with_options, ValidationOptions};
+
+/// File name of the manifest at the root of every rules pack.
+pub const MANIFEST_FILE: &str = "pack.toml";
+
+/// How a diagnostic is treated once a pack maps it.
+pub use crate::severity::RuleSeverity as Severity;
+
+/// One kind of file the pack governs.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PackRule {
+    pub name: String,
+    /// Glob patterns, matched against paths relative to the input directory
+    pub files: Vec<String>,
+    /// Schema path relative to the pack root, or a URL
+    pub schema: String,
+    /// Named semantic checks (see `presets::semantic_check_names`)
+    #[serde(default)]
+    pub checks: Vec<String>,
+    /// Documentation for the rule, relative to the pack root or a URL
+    pub docs: Option<String>,
+    /// The rule's files are bad examples: each must fail, and their failures are not reported
+    #[serde(default)]
+    pub expect_invalid: bool,
+}
+
+/// Contents of `pack.toml`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PackManifest {
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub description: String,
+    pub docs: Option<String>,
+    #[serde(default)]
+    pub rules: Vec<PackRule>,
+    /// Keyed by diagnostic code (`app::schema::validation_error`) or semantic check name
+    #[serde(default)]
+    pub severity: BTreeMap<String, Severity>,
+}
+
+/// A rules pack loaded from disk.
+#[derive(Debug, Clone)]
+pub struct RulesPack {
+    pub root: PathBuf,
+    pub manifest: PackManifest,
+}
+
+fn pack_error(pack: &str, message: impl Into<String>) -> AppError {
+    AppError::RulesPackError {
+        pack: pack.to_string(),
+        message: message.into(),
+    }
+}
+
+fn is_url(location: &str) -> bool {
+    location.starts_with("http://") || location.starts_with("https://")
+}
+
+fn is_archive(location: &str) -> bool {
+    location.ends_with(".tar.gz") || location.ends_with(".tgz")
+}
+
+/// Where `name@version` is installed once fetched from a registry.
+pub fn installed_pack_dir(name: &str, version: &str) -> PathBuf {
+    paths::data_dir().join("rule-packs").join(name).join(version)
+}
+
+// Splits `org-standards@1.2.0`; the version is optional
+fn parse_pack_reference(spec: &str) -> (&str, Option<&str>) {
+    match spec.split_once('@') {
+        Some((name, version)) => (name, Some(version)),
+        None => (spec, None),
+    }
+}
+
+// Numeric-aware ordering so 1.10.0 sorts after 1.9.0
+fn version_key(version: &str) -> Vec<(u64, String)> {
+    version
+        .split(['.', '-'])
+        .map(|part| (part.parse().unwrap_or(0), part.to_string()))
+        .collect()
+}
+
+fn latest_installed_version(name: &str) -> Option<String> {
+    let dir = paths::data_dir().join("rule-packs").join(name);
+    fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .filter(|e| e.path().join(MANIFEST_FILE).is_file())
+        .filter_map(|e| e.file_name().into_string().ok())
+        .max_by_key(|v| version_key(v))
+}
+
+// A tarball may wrap the pack in a single top-level directory
+fn pack_root_in(dir: &Path) -> PathBuf {
+    if dir.join(MANIFEST_FILE).is_file() {
+        return dir.to_path_buf();
+    }
+    let children: Vec<_> = fs::read_dir(dir).into_iter().flatten().flatten().map(|e| e.path()).collect();
+    match children.as_slice() {
+        [only] if only.join(MANIFEST_FILE).is_file() => only.clone(),
+        _ => dir.to_path_buf(),
+    }
+}
+
+fn unpack_archive(spec: &str, bytes: &[u8], destination: &Path) -> Result<(), AppError> {
+    let unpack_err = |e: std::io::Error| pack_error(spec, format!("could not unpack archive: {}", e));
+    fs::create_dir_all(destination).map_err(unpack_err)?;
+    // `unpack` refuses entries that would escape the destination
+    tar::Archive::new(flate2::read::GzDecoder::new(bytes)).unpack(destination).map_err(unpack_err)
+}
+
+fn read_archive(spec: &str, location: &str) -> Result<Vec<u8>, AppError> {
+    if is_url(location) {
+        let response = reqwest::blocking::get(location)
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| pack_error(spec, format!("could not download {}: {}", location, e)))?;
+        response
+            .bytes()
+            .map(|b| b.to_vec())
+            .map_err(|e| pack_error(spec, format!("could not download {}: {}", location, e)))
+    } else {
+        fs::read(location).map_err(|e| pack_error(spec, format!("could not read {}: {}", location, e)))
+    }
+}
+
+/// Finds the directory of the pack named by `spec`:
+/// a pack directory, a `.tar.gz` path or URL, or `name@version`. `name@version` is looked
+/// up among installed packs and otherwise fetched from `registry` as
+/// `<registry>/<name>/<name>-<version>.tar.gz`. A bare `name` uses the newest installed version.
+pub fn resolve_pack(spec: &str, registry: Option<&str>) -> Result<PathBuf, AppError> {
+    if Path::new(spec).is_dir() {
+        return Ok(PathBuf::from(spec));
+    }
+    if is_archive(spec) {
+        let destination = paths::rule_pack_cache_dir().join(format!("{:016x}", paths::stable_hash(spec)));
+        if !destination.exists() {
+            unpack_archive(spec, &read_archive(spec, spec)?, &destination)?;
+        }
+        return Ok(pack_root_in(&destination));
+    }
+
+    let (name, version) = parse_pack_reference(spec);
+    let version = match version {
+        Some(version) => version.to_string(),
+        None => latest_installed_version(name)
+            .ok_or_else(|| pack_error(spec, "not installed; pin a version (name@version) to fetch it from a registry"))?,
+    };
+    let installed = installed_pack_dir(name, &version);
+    if !installed.join(MANIFEST_FILE).is_file() {
+        let registry = registry.ok_or_else(|| {
+            pack_error(spec, "not installed and no registry configured (--rules-registry or TOML_AND_JERRY_RULES_REGISTRY)")
+        })?;
+        let url = format!("{}/{}/{}-{}.tar.gz", registry.trim_end_matches('/'), name, name, version);
+        let staging = installed.with_file_name(format!("{}.partial", version));
+        crate::paths::check_writable(&installed).map_err(|e| pack_error(spec, e.to_string()))?;
+        let _ = fs::remove_dir_all(&staging);
+        unpack_archive(spec, &read_archive(spec, &url)?, &staging)?;
+        let root = pack_root_in(&staging);
+        if let Some(parent) = installed.parent() {
+            fs::create_dir_all(parent).map_err(|e| pack_error(spec, e.to_string()))?;
+        }
+        fs::rename(&root, &installed).map_err(|e| pack_error(spec, format!("could not install pack: {}", e)))?;
+        let _ = fs::remove_dir_all(&staging);
+    }
+    Ok(installed)
+}
+
+/// Loads and checks the manifest of the pack at `root`. When `spec` pins a name or
+/// version, the manifest must match it.
+pub fn load_pack(spec: &str, root: &Path) -> Result<RulesPack, AppError> {
+    let manifest_path = root.join(MANIFEST_FILE);
+    let content = fs::read_to_string(&manifest_path)
+        .map_err(|e| pack_error(spec, format!("could not read {}: {}", manifest_path.display(), e)))?;
+    let manifest: PackManifest = toml::from_str(&content)
+        .map_err(|e| pack_error(spec, format!("invalid {}: {}", MANIFEST_FILE, e)))?;
+
+    if !Path::new(spec).exists() && !is_archive(spec) {
+        let (name, version) = parse_pack_reference(spec);
+        if manifest.name != name || version.is_some_and(|v| v != manifest.version) {
+            return Err(pack_error(
+                spec,
+                format!("pack declares itself as {}@{}", manifest.name, manifest.version),
+            ));
+        }
+    }
+    for rule in &manifest.rules {
+        if let Some(unknown) = rule.checks.iter().find(|c| presets::find_semantic_check(c).is_none()) {
+            return Err(pack_error(
+                spec,
+                format!(
+                    "rule {:?} uses unknown check {:?} (available: {})",
+                    rule.name,
+                    unknown,
+                    presets::semantic_check_names().collect::<Vec<_>>().join(", ")
+                ),
+            ));
+        }
+    }
+    Ok(RulesPack { root: root.to_path_buf(), manifest })
+}
+
+impl RulesPack {
+    /// Resolves and loads a pack in one step.
+    pub fn open(spec: &str, registry: Option<&str>) -> Result<Self, AppError> {
+        load_pack(spec, &resolve_pack(spec, registry)?)
+    }
+
+    /// Location a rule's schema or docs path refers to: URLs as-is, others under the pack root.
+    pub fn locate(&self, location: &str) -> PathBuf {
+        if is_url(location) {
+            PathBuf::from(location)
+        } else {
+            self.root.join(location)
+        }
+    }
+
+    /// `name@version`, then the manifest's description and docs when it has them.
+    pub fn describe(&self) -> String {
+        let mut out = format!("{}@{}", self.manifest.name, self.manifest.version);
+        if !self.manifest.description.is_empty() {
+            out.push_str(&format!(": {}", self.manifest.description));
+        }
+        if let Some(docs) = &self.manifest.docs {
+            out.push_str(&format!(" (docs: {})", self.locate(docs).display()));
+        }
+        out
+    }
+
+    /// Applies the pack's severity mapping: `off` drops diagnostics, `warning` and `info` keep
+    /// them without failing the run.
+    pub fn apply_severity(&self, errors: Vec<AppError>) -> Vec<AppError> {
+        severity::apply(&self.manifest.severity, errors)
+    }
+}
+
+fn rule_matcher(pack: &RulesPack, rule: &PackRule) -> Result<GlobSet, AppError> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in &rule.files {
+        let glob = Glob::new(pattern)
+            .map_err(|e| pack_error(&pack.manifest.name, format!("rule {:?}: {}", rule.name, e)))?;
+        builder.add(glob);
+    }
+    builder
+        .build()
+        .map_err(|e| pack_error(&pack.manifest.name, format!("rule {:?}: {}", rule.name, e)))
+}
+
+// Files under each input with the path rules are matched against (relative to a directory input)
+fn candidate_files(inputs: &[PathBuf]) -> Vec<(PathBuf, PathBuf)> {
+    let mut candidates = Vec::new();
+    for input in inputs {
+        if input.is_dir() {
+            for entry in ignore::WalkBuilder::new(input).hidden(false).build().flatten() {
+                if entry.file_type().is_some_and(|t| t.is_file()) {
+                    let relative = entry.path().strip_prefix(input).unwrap_or(entry.path()).to_path_buf();
+                    candidates.push((entry.into_path(), relative));
+                }
+            }
+        } else {
+            let relative = input.strip_prefix(".").unwrap_or(input).to_path_buf();
+            candidates.push((input.clone(), relative));
+        }
+    }
+    candidates
+}
+
+/// Validates `inputs` (files or directories) with every rule of the pack: each file is
+/// checked by the first rule whose globs match it, then severities are mapped.
+pub fn run_rules_pack(
+    pack: &RulesPack,
+    inputs: &[PathBuf],
+    options: &ValidationOptions,
+) -> Result<Vec<AppError>, AppError> {
+    let matchers = pack
+        .manifest
+        .rules
+        .iter()
+        .map(|rule| rule_matcher(pack, rule))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut grouped: BTreeMap<usize, Vec<PathBuf>> = BTreeMap::new();
+    for (file, relative) in candidate_files(inputs) {
+        match matchers.iter().position(|m| m.is_match(&relative)) {
+            Some(index) => grouped.entry(index).or_default().push(file),
+            None if inputs.contains(&file) => {
+                progress::line(format_args!("Skipping file not covered by rules pack {}: {:?}", pack.manifest.name, file));
+            }
+            None => {}
+        }
+    }
+
+    let mut collected_errors = Vec::new();
+    for (index, files) in grouped {
+        let rule = &pack.manifest.rules[index];
+        match &rule.docs {
+            Some(docs) => progress::line(format_args!(
+                "Rules pack {}: {} file(s) as {} (docs: {})",
+                pack.manifest.name,
+                files.len(),
+                rule.name,
+                pack.locate(docs).display()
+            )),
+            None => progress::line(format_args!("Rules pack {}: {} file(s) as {}", pack.manifest.name, files.len(), rule.name)),
+        }
+        for file in &files {
+            summary::record_gate(file, Some(format!("rules pack {}: {}", pack.manifest.name, rule.name)));
+        }
+        let validator = load_and_compile_schema(&pack.locate(&rule.schema))?;
+        let mut rule_errors = validate_inputs_with_options(files.clone(), &validator, options)?;
+        let checks: Vec<SemanticCheck> = rule.checks.iter().filter_map(|c| presets::find_semantic_check(c)).collect();
+        presets::run_semantic_checks(&checks, &files, options, &mut rule_errors);
+        if rule.expect_invalid {
+            rule_errors = expect_invalid::apply(&files, rule_errors, |file| files.iter().any(|f| f.to_string_lossy() == file));
+        }
+        collected_errors.extend(rule_errors);
+    }
+    Ok(pack.apply_severity(collected_errors))
+}
@@ -0,0 +1,102 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+use crate::provenance::DigestedArtifact;
+
+/// Environment variables that change what a run does (state locations, registries).
+const RECORDED_ENV_PREFIXES: &[&str] = &["TOML_AND_JERRY_", "XDG_CACHE_HOME", "XDG_DATA_HOME"];
+
+/// Everything needed to repeat a `check` run: the command line, where and with which
+/// environment it ran, and digests of the schemas and inputs it saw.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunRecord {
+    pub tool_version: String,
+    /// Arguments after the program name, without `--record-run`
+    pub args: Vec<String>,
+    pub cwd: PathBuf,
+    pub env: BTreeMap<String, String>,
+    pub schemas: Vec<DigestedArtifact>,
+    pub inputs: Vec<DigestedArtifact>,
+}
+
+fn record_error(path: &Path, message: impl Into<String>) -> AppError {
+    AppError::RunRecordError {
+        path: path.to_string_lossy().into_owned(),
+        message: message.into(),
+    }
+}
+
+/// Drops `--record-run <file>` / `--record-run=<file>` so a replay does not overwrite the record.
+pub fn args_without_record_flag(args: &[String]) -> Vec<String> {
+    let mut kept = Vec::new();
+    let mut skip_next = false;
+    for arg in args {
+        if skip_next {
+            skip_next = false;
+        } else if arg == "--record-run" {
+            skip_next = true;
+        } else if !arg.starts_with("--record-run=") {
+            kept.push(arg.clone());
+        }
+    }
+    kept
+}
+
+/// The current values of the environment variables a record keeps.
+pub fn recorded_env() -> BTreeMap<String, String> {
+    std::env::vars()
+        .filter(|(name, _)| RECORDED_ENV_PREFIXES.iter().any(|prefix| name.starts_with(prefix)))
+        .collect()
+}
+
+/// Writes the record as pretty JSON.
+pub fn write_record(path: &Path, record: &RunRecord) -> Result<(), AppError> {
+    let rendered = serde_json::to_string_pretty(record).map_err(|e| record_error(path, e.to_string()))?;
+    crate::paths::check_writable(path)
+        .and_then(|()| fs::write(path, rendered + "\n"))
+        .map_err(|e| record_error(path, format!("could not write record: {}", e)))
+}
+
+/// Reads a record written by `--record-run`.
+pub fn read_record(path: &Path) -> Result<RunRecord, AppError> {
+    let content = fs::read_to_string(path).map_err(|e| AppError::FileReadError {
+        path: path.to_path_buf(),
+        source: e,
+        span: None,
+    })?;
+    serde_json::from_str(&content).map_err(|e| record_error(path, format!("not a run record: {}", e)))
+}
+
+fn artifact_drift(kind: &str, recorded: &[DigestedArtifact], current: &[DigestedArtifact], out: &mut Vec<String>) {
+    for artifact in recorded {
+        match current.iter().find(|c| c.uri == artifact.uri) {
+            None => out.push(format!("{} {} is no longer part of the run", kind, artifact.uri)),
+            Some(now) if now.sha256 != artifact.sha256 => out.push(format!("{} {} has changed since it was recorded", kind, artifact.uri)),
+            Some(_) => {}
+        }
+    }
+    for artifact in current.iter().filter(|c| !recorded.iter().any(|r| r.uri == c.uri)) {
+        out.push(format!("{} {} was not part of the recorded run", kind, artifact.uri));
+    }
+}
+
+/// Human-readable differences between a recorded run and the replay, empty when the
+/// replay saw exactly the same tool version, schemas and inputs.
+pub fn replay_drift(
+    recorded: &RunRecord,
+    tool_version: &str,
+    schemas: &[DigestedArtifact],
+    inputs: &[DigestedArtifact],
+) -> Vec<String> {
+    let mut drift = Vec::new();
+    if recorded.tool_version != tool_version {
+        drift.push(format!("recorded with version {}, replaying with {}", recorded.tool_version, tool_version));
+    }
+    artifact_drift("schema", &recorded.schemas, schemas, &mut drift);
+    artifact_drift("input", &recorded.inputs, inputs, &mut drift);
+    drift
+}
@@ -0,0 +1,784 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::Path;
+use serde_json::{json, Map, Value as JsonValue};
+
+// Just enough of Rust's lexical grammar to read type declarations: comments are dropped
+// except `///` doc comments, and literals other than strings are kept as words
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Punct(char),
+    Str(String),
+    Doc(String),
+}
+
+fn tokenize(source: &str) -> Vec<Token> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    let rest = |i: usize, n: usize| -> String { chars[i..(i + n).min(chars.len())].iter().collect() };
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if rest(i, 2) == "//" {
+            let end = chars[i..].iter().position(|&c| c == '\n').map_or(chars.len(), |p| i + p);
+            let line: String = chars[i..end].iter().collect();
+            if line.starts_with("///") && !line.starts_with("////") {
+                let text = &line[3..];
+                tokens.push(Token::Doc(text.strip_prefix(' ').unwrap_or(text).trim_end().to_string()));
+            }
+            i = end;
+        } else if rest(i, 2) == "/*" {
+            let mut depth = 0;
+            while i < chars.len() {
+                match rest(i, 2).as_str() {
+                    "/*" => (depth, i) = (depth + 1, i + 2),
+                    "*/" => {
+                        (depth, i) = (depth - 1, i + 2);
+                        if depth == 0 {
+                            break;
+                        }
+                    }
+                    _ => i += 1,
+                }
+            }
+        } else if c == '"' || (c == 'r' && matches!(chars.get(i + 1), Some('"' | '#')) && chars[i + 1..].iter().find(|&&c| c != '#') == Some(&'"')) {
+            // A string, or a raw string `r#"..."#` with as many `#`s closing it
+            let raw = c == 'r';
+            let hashes = if raw { chars[i + 1..].iter().take_while(|&&c| c == '#').count() } else { 0 };
+            i += if raw { hashes + 2 } else { 1 };
+            let mut text = String::new();
+            while i < chars.len() {
+                if !raw && chars[i] == '\\' {
+                    text.extend(chars.get(i + 1));
+                    i += 2;
+                } else if chars[i] == '"' && chars[i + 1..].iter().take(hashes).filter(|&&c| c == '#').count() == hashes {
+                    i += 1 + hashes;
+                    break;
+                } else {
+                    text.push(chars[i]);
+                    i += 1;
+                }
+            }
+            tokens.push(Token::Str(text));
+        } else if c == '\'' {
+            // A char literal (`'a'`, `'\n'`) or a lifetime (`'a`), neither of which matters here
+            i += 1;
+            if chars.get(i) == Some(&'\\') {
+                i += 1;
+            }
+            i += 1;
+            if chars.get(i) == Some(&'\'') {
+                i += 1;
+            } else {
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+            }
+        } else if c.is_alphanumeric() || c == '_' {
+            // Raw identifiers, `r#type`, name the same as plain ones
+            if rest(i, 2) == "r#" {
+                i += 2;
+            }
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            tokens.push(Token::Word(word));
+        } else {
+            tokens.push(Token::Punct(c));
+            i += 1;
+        }
+    }
+    tokens
+}
+
+// A type as written: `Option<Vec<String>>` is `Option` with the argument `Vec<String>`
+#[derive(Debug, Clone, PartialEq)]
+enum TypeExpr {
+    Path { name: String, args: Vec<TypeExpr> },
+    Array(Box<TypeExpr>),
+    Tuple(Vec<TypeExpr>),
+}
+
+/// The `#[serde(...)]` settings the schema depends on.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Serde {
+    rename: Option<String>,
+    rename_all: Option<String>,
+    tag: Option<String>,
+    untagged: bool,
+    default: bool,
+    deny_unknown_fields: bool,
+    skip: bool,
+    flatten: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Field {
+    name: String,
+    ty: TypeExpr,
+    docs: Vec<String>,
+    serde: Serde,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Body {
+    Named(Vec<Field>),
+    Tuple(Vec<TypeExpr>),
+    Unit,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Variant {
+    name: String,
+    body: Body,
+    docs: Vec<String>,
+    serde: Serde,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Shape {
+    Struct(Body),
+    Enum(Vec<Variant>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Item {
+    docs: Vec<String>,
+    serde: Serde,
+    shape: Shape,
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn is_punct(&self, c: char) -> bool {
+        self.peek() == Some(&Token::Punct(c))
+    }
+
+    fn is_word(&self, word: &str) -> bool {
+        matches!(self.peek(), Some(Token::Word(w)) if w == word)
+    }
+
+    fn word(&mut self) -> Option<String> {
+        match self.peek() {
+            Some(Token::Word(w)) => {
+                let w = w.clone();
+                self.pos += 1;
+                Some(w)
+            }
+            _ => None,
+        }
+    }
+
+    // Skips a balanced group starting at the current `open`
+    fn skip_group(&mut self, open: char, close: char) {
+        let mut depth = 0;
+        while let Some(token) = self.peek() {
+            match token {
+                Token::Punct(c) if *c == open => depth += 1,
+                Token::Punct(c) if *c == close => depth -= 1,
+                _ => {}
+            }
+            self.pos += 1;
+            if depth == 0 {
+                return;
+            }
+        }
+    }
+
+    // Doc comments and `#[...]` attributes before an item, field or variant
+    fn attributes(&mut self) -> (Vec<String>, Serde) {
+        let mut docs = Vec::new();
+        let mut serde = Serde::default();
+        loop {
+            match self.peek() {
+                Some(Token::Doc(text)) => {
+                    docs.push(text.clone());
+                    self.pos += 1;
+                }
+                Some(Token::Punct('#')) if self.tokens.get(self.pos + 1) == Some(&Token::Punct('[')) => {
+                    self.pos += 1;
+                    let start = self.pos;
+                    self.skip_group('[', ']');
+                    let attribute = &self.tokens[start + 1..self.pos - 1];
+                    if attribute.first() == Some(&Token::Word("serde".to_string())) {
+                        read_serde(&attribute[1..], &mut serde);
+                    }
+                }
+                _ => return (docs, serde),
+            }
+        }
+    }
+
+    fn visibility(&mut self) {
+        if self.eat_word("pub") && self.is_punct('(') {
+            self.skip_group('(', ')');
+        }
+    }
+
+    fn eat_word(&mut self, word: &str) -> bool {
+        let found = self.is_word(word);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    // Generics and where clauses do not change the schema
+    fn skip_generics(&mut self) {
+        if self.is_punct('<') {
+            self.skip_group('<', '>');
+        }
+        if self.is_word("where") {
+            while self.peek().is_some() && !self.is_punct('{') && !self.is_punct(';') && !self.is_punct('(') {
+                self.pos += 1;
+            }
+        }
+    }
+
+    fn ty(&mut self) -> TypeExpr {
+        // References and `dyn`/`impl` bounds read as what they point at
+        while self.is_punct('&') || self.is_word("mut") || self.is_word("dyn") || self.is_word("impl") {
+            self.pos += 1;
+        }
+        if self.is_punct('[') {
+            self.pos += 1;
+            let element = self.ty();
+            while self.peek().is_some() && !self.is_punct(']') {
+                if self.is_punct('[') {
+                    self.skip_group('[', ']');
+                } else {
+                    self.pos += 1;
+                }
+            }
+            self.pos += 1;
+            return TypeExpr::Array(Box::new(element));
+        }
+        if self.is_punct('(') {
+            self.pos += 1;
+            let mut elements = Vec::new();
+            while self.peek().is_some() && !self.is_punct(')') {
+                let start = self.pos;
+                elements.push(self.ty());
+                self.separator(start);
+            }
+            self.pos += 1;
+            return TypeExpr::Tuple(elements);
+        }
+        let mut name = String::new();
+        let mut args = Vec::new();
+        while let Some(segment) = self.word() {
+            name = segment;
+            if self.is_punct('<') {
+                self.pos += 1;
+                while self.peek().is_some() && !self.is_punct('>') {
+                    let start = self.pos;
+                    args.push(self.ty());
+                    self.separator(start);
+                }
+                self.pos += 1;
+            }
+            if self.is_punct(':') && self.tokens.get(self.pos + 1) == Some(&Token::Punct(':')) {
+                self.pos += 2;
+                args.clear();
+            } else {
+                break;
+            }
+        }
+        TypeExpr::Path { name, args }
+    }
+
+    fn eat_punct(&mut self, c: char) -> bool {
+        let found = self.is_punct(c);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    // Moves past the `,` after a type argument; a type this parser does not understand, such
+    // as `Fn(u8) -> u8`, is passed over a token at a time
+    fn separator(&mut self, start: usize) {
+        if !self.eat_punct(',') && self.pos == start {
+            self.pos += 1;
+        }
+    }
+
+    // Skips to the `,` or closing bracket that ends a field, variant or type
+    fn skip_to_separator(&mut self) {
+        while let Some(token) = self.peek() {
+            match token {
+                Token::Punct(',' | '}' | ')') => return,
+                Token::Punct('(') => self.skip_group('(', ')'),
+                Token::Punct('[') => self.skip_group('[', ']'),
+                Token::Punct('{') => self.skip_group('{', '}'),
+                Token::Punct('<') => self.skip_group('<', '>'),
+                _ => self.pos += 1,
+            }
+        }
+    }
+
+    fn named_fields(&mut self) -> Vec<Field> {
+        self.pos += 1;
+        let mut fields = Vec::new();
+        while self.peek().is_some() && !self.is_punct('}') {
+            let (docs, serde) = self.attributes();
+            self.visibility();
+            let Some(name) = self.word() else {
+                self.skip_to_separator();
+                self.eat_punct(',');
+                continue;
+            };
+            self.eat_punct(':');
+            let ty = self.ty();
+            self.skip_to_separator();
+            self.eat_punct(',');
+            fields.push(Field { name, ty, docs, serde });
+        }
+        self.pos += 1;
+        fields
+    }
+
+    fn tuple_fields(&mut self) -> Vec<TypeExpr> {
+        self.pos += 1;
+        let mut types = Vec::new();
+        while self.peek().is_some() && !self.is_punct(')') {
+            self.attributes();
+            self.visibility();
+            types.push(self.ty());
+            self.skip_to_separator();
+            self.eat_punct(',');
+        }
+        self.pos += 1;
+        types
+    }
+
+    fn body(&mut self) -> Body {
+        if self.is_punct('{') {
+            Body::Named(self.named_fields())
+        } else if self.is_punct('(') {
+            Body::Tuple(self.tuple_fields())
+        } else {
+            Body::Unit
+        }
+    }
+
+    fn variants(&mut self) -> Vec<Variant> {
+        self.pos += 1;
+        let mut variants = Vec::new();
+        while self.peek().is_some() && !self.is_punct('}') {
+            let (docs, serde) = self.attributes();
+            let Some(name) = self.word() else {
+                self.skip_to_separator();
+                self.eat_punct(',');
+                continue;
+            };
+            let body = self.body();
+            // An explicit discriminant, `= 1`
+            self.skip_to_separator();
+            self.eat_punct(',');
+            variants.push(Variant { name, body, docs, serde });
+        }
+        self.pos += 1;
+        variants
+    }
+
+    /// Every struct and enum declared in the tokens, modules included, by name; the first
+    /// declaration of a name wins.
+    fn items(&mut self) -> HashMap<String, Item> {
+        let mut items = HashMap::new();
+        while self.peek().is_some() {
+            let (docs, serde) = self.attributes();
+            self.visibility();
+            let is_struct = self.is_word("struct");
+            if !is_struct && !self.is_word("enum") {
+                self.pos += 1;
+                continue;
+            }
+            self.pos += 1;
+            let Some(name) = self.word() else { continue };
+            self.skip_generics();
+            let shape = if is_struct {
+                Shape::Struct(self.body())
+            } else if self.is_punct('{') {
+                Shape::Enum(self.variants())
+            } else {
+                continue;
+            };
+            items.entry(name).or_insert(Item { docs, serde, shape });
+        }
+        items
+    }
+}
+
+// Reads the comma-separated `key = "value"` and `key` settings of `#[serde(...)]`
+fn read_serde(tokens: &[Token], serde: &mut Serde) {
+    let inner = match (tokens.first(), tokens.last()) {
+        (Some(Token::Punct('(')), Some(Token::Punct(')'))) => &tokens[1..tokens.len() - 1],
+        _ => return,
+    };
+    let end = Token::Punct(',');
+    let mut depth = 0;
+    let mut setting: Vec<&Token> = Vec::new();
+    for token in inner.iter().chain(std::iter::once(&end)) {
+        match token {
+            Token::Punct('(') => depth += 1,
+            Token::Punct(')') => depth -= 1,
+            Token::Punct(',') if depth == 0 => {
+                let value = match setting.as_slice() {
+                    [_, Token::Punct('='), Token::Str(value)] => Some(value.clone()),
+                    _ => None,
+                };
+                if let Some(Token::Word(key)) = setting.first() {
+                    match (key.as_str(), value) {
+                        ("rename", Some(value)) => serde.rename = Some(value),
+                        ("rename_all", Some(value)) => serde.rename_all = Some(value),
+                        ("tag", Some(value)) => serde.tag = Some(value),
+                        ("untagged", _) => serde.untagged = true,
+                        ("default", _) => serde.default = true,
+                        ("deny_unknown_fields", _) => serde.deny_unknown_fields = true,
+                        ("skip" | "skip_deserializing", _) => serde.skip = true,
+                        ("flatten", _) => serde.flatten = true,
+                        _ => {}
+                    }
+                }
+                setting.clear();
+                continue;
+            }
+            _ => {}
+        }
+        setting.push(token);
+    }
+}
+
+// Splits `log_level` or `LogLevel` into lowercase words
+fn words(name: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    for c in name.chars() {
+        if c == '_' || c == '-' {
+            words.push(std::mem::take(&mut current));
+        } else if c.is_uppercase() && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+            current.extend(c.to_lowercase());
+        } else {
+            current.extend(c.to_lowercase());
+        }
+    }
+    words.push(current);
+    words.retain(|word| !word.is_empty());
+    words
+}
+
+fn capitalized(word: &str) -> String {
+    let mut chars = word.chars();
+    chars.next().map_or_else(String::new, |first| first.to_uppercase().chain(chars).collect())
+}
+
+/// `name` as serde's `rename_all = "rule"` spells it; unknown rules leave it as written.
+fn rename_all(name: &str, rule: Option<&str>) -> String {
+    let words = words(name);
+    match rule {
+        Some("lowercase") => name.to_lowercase(),
+        Some("UPPERCASE") => name.to_uppercase(),
+        Some("PascalCase") => words.iter().map(|w| capitalized(w)).collect(),
+        Some("camelCase") => words.iter().enumerate().map(|(i, w)| if i == 0 { w.clone() } else { capitalized(w) }).collect(),
+        Some("snake_case") => words.join("_"),
+        Some("SCREAMING_SNAKE_CASE") => words.join("_").to_uppercase(),
+        Some("kebab-case") => words.join("-"),
+        Some("SCREAMING-KEBAB-CASE") => words.join("-").to_uppercase(),
+        _ => name.to_string(),
+    }
+}
+
+fn with_docs(mut schema: JsonValue, docs: &[String]) -> JsonValue {
+    let text = docs.join("\n").trim().to_string();
+    if let (JsonValue::Object(object), false) = (&mut schema, text.is_empty()) {
+        object.insert("description".to_string(), json!(text));
+    }
+    schema
+}
+
+struct Generator<'a> {
+    items: &'a HashMap<String, Item>,
+    root: &'a str,
+    defs: BTreeMap<String, JsonValue>,
+    queued: BTreeSet<String>,
+}
+
+impl Generator<'_> {
+    fn reference(&mut self, name: &str) -> JsonValue {
+        if name == self.root {
+            return json!({ "$ref": "#" });
+        }
+        self.queued.insert(name.to_string());
+        json!({ "$ref": format!("#/$defs/{}", name) })
+    }
+
+    fn type_schema(&mut self, ty: &TypeExpr) -> JsonValue {
+        let (name, args) = match ty {
+            TypeExpr::Array(element) => return json!({ "type": "array", "items": self.type_schema(element) }),
+            TypeExpr::Tuple(elements) if elements.is_empty() => return json!({ "type": "null" }),
+            TypeExpr::Tuple(elements) => {
+                let items: Vec<JsonValue> = elements.iter().map(|e| self.type_schema(e)).collect();
+                return json!({ "type": "array", "prefixItems": items, "items": false });
+            }
+            TypeExpr::Path { name, args } => (name.as_str(), args.as_slice()),
+        };
+        let arg = |index: usize| args.get(index).cloned();
+        match name {
+            "String" | "str" | "char" | "PathBuf" | "Path" | "OsString" => json!({ "type": "string" }),
+            "bool" => json!({ "type": "boolean" }),
+            "u8" => json!({ "type": "integer", "minimum": 0, "maximum": u8::MAX }),
+            "u16" => json!({ "type": "integer", "minimum": 0, "maximum": u16::MAX }),
+            "u32" => json!({ "type": "integer", "minimum": 0, "maximum": u32::MAX }),
+            "u64" | "u128" | "usize" => json!({ "type": "integer", "minimum": 0 }),
+            "i8" => json!({ "type": "integer", "minimum": i8::MIN, "maximum": i8::MAX }),
+            "i16" => json!({ "type": "integer", "minimum": i16::MIN, "maximum": i16::MAX }),
+            "i32" => json!({ "type": "integer", "minimum": i32::MIN, "maximum": i32::MAX }),
+            "i64" | "i128" | "isize" => json!({ "type": "integer" }),
+            "f32" | "f64" => json!({ "type": "number" }),
+            "Option" | "Box" | "Rc" | "Arc" | "Cow" => match args.last() {
+                Some(inner) => self.type_schema(inner),
+                None => json!({}),
+            },
+            "Vec" | "VecDeque" | "LinkedList" => match arg(0) {
+                Some(inner) => json!({ "type": "array", "items": self.type_schema(&inner) }),
+                None => json!({ "type": "array" }),
+            },
+            "HashSet" | "BTreeSet" => match arg(0) {
+                Some(inner) => json!({ "type": "array", "items": self.type_schema(&inner), "uniqueItems": true }),
+                None => json!({ "type": "array", "uniqueItems": true }),
+            },
+            "HashMap" | "BTreeMap" | "IndexMap" => match arg(1) {
+                Some(value) => json!({ "type": "object", "additionalProperties": self.type_schema(&value) }),
+                None => json!({ "type": "object" }),
+            },
+            "Map" => json!({ "type": "object" }),
+            name if self.items.contains_key(name) => self.reference(name),
+            // `serde_json::Value` and types from other crates accept anything
+            _ => json!({}),
+        }
+    }
+
+    // Properties and required names of named fields, with flattened structs merged in
+    fn fields(&mut self, fields: &[Field], container: &Serde, properties: &mut Map<String, JsonValue>, required: &mut Vec<JsonValue>) {
+        let items = self.items;
+        for field in fields.iter().filter(|f| !f.serde.skip) {
+            if field.serde.flatten {
+                if let TypeExpr::Path { name, .. } = &field.ty {
+                    if let Some(Item { serde, shape: Shape::Struct(Body::Named(inner)), .. }) = items.get(name) {
+                        self.fields(inner, serde, properties, required);
+                    }
+                }
+                continue;
+            }
+            let key = field.serde.rename.clone().unwrap_or_else(|| rename_all(&field.name, container.rename_all.as_deref()));
+            let optional = matches!(&field.ty, TypeExpr::Path { name, .. } if name == "Option");
+            if !(optional || field.serde.default || container.default) {
+                required.push(json!(key));
+            }
+            let schema = self.type_schema(&field.ty);
+            properties.insert(key, with_docs(schema, &field.docs));
+        }
+    }
+
+    fn object(&mut self, fields: &[Field], container: &Serde) -> JsonValue {
+        let mut properties = Map::new();
+        let mut required = Vec::new();
+        self.fields(fields, container, &mut properties, &mut required);
+        let mut schema = json!({ "type": "object", "properties": properties });
+        if !required.is_empty() {
+            schema["required"] = JsonValue::Array(required);
+        }
+        if container.deny_unknown_fields {
+            schema["additionalProperties"] = json!(false);
+        }
+        schema
+    }
+
+    fn body_schema(&mut self, body: &Body, container: &Serde) -> JsonValue {
+        match body {
+            Body::Named(fields) => self.object(fields, container),
+            Body::Tuple(types) if types.len() == 1 => self.type_schema(&types[0]),
+            Body::Tuple(types) => self.type_schema(&TypeExpr::Tuple(types.clone())),
+            Body::Unit => json!({ "type": "null" }),
+        }
+    }
+
+    fn enum_schema(&mut self, variants: &[Variant], container: &Serde) -> JsonValue {
+        let variants: Vec<&Variant> = variants.iter().filter(|v| !v.serde.skip).collect();
+        let name = |v: &Variant| v.serde.rename.clone().unwrap_or_else(|| rename_all(&v.name, container.rename_all.as_deref()));
+        if !container.untagged && variants.iter().all(|v| v.body == Body::Unit) {
+            let names: Vec<String> = variants.iter().map(|v| name(v)).collect();
+            return match &container.tag {
+                Some(tag) => json!({ "type": "object", "properties": { tag.as_str(): { "enum": names } }, "required": [tag] }),
+                None => json!({ "type": "string", "enum": names }),
+            };
+        }
+        let mut choices = Vec::new();
+        for variant in variants {
+            let key = name(variant);
+            let payload = self.body_schema(&variant.body, &variant.serde);
+            let choice = if container.untagged {
+                payload
+            } else if let Some(tag) = &container.tag {
+                let tagged = json!({ "type": "object", "properties": { tag.as_str(): { "const": key } }, "required": [tag] });
+                match (&variant.body, payload) {
+                    (Body::Unit, _) => tagged,
+                    (Body::Named(_), JsonValue::Object(mut object)) => {
+                        let mut required = vec![json!(tag)];
+                        required.extend(object.remove("required").and_then(|r| r.as_array().cloned()).unwrap_or_default());
+                        if let Some(JsonValue::Object(properties)) = object.get_mut("properties") {
+                            properties.insert(tag.clone(), json!({ "const": key }));
+                        }
+                        object.insert("required".to_string(), JsonValue::Array(required));
+                        JsonValue::Object(object)
+                    }
+                    (_, payload) => json!({ "allOf": [tagged, payload] }),
+                }
+            } else if variant.body == Body::Unit {
+                json!({ "const": key })
+            } else {
+                json!({ "type": "object", "properties": { key.as_str(): payload }, "required": [key], "additionalProperties": false })
+            };
+            choices.push(with_docs(choice, &variant.docs));
+        }
+        if container.untagged {
+            json!({ "anyOf": choices })
+        } else {
+            json!({ "oneOf": choices })
+        }
+    }
+
+    fn item_schema(&mut self, name: &str) -> JsonValue {
+        let items = self.items;
+        let Some(item) = items.get(name) else { return json!({}) };
+        let schema = match &item.shape {
+            Shape::Struct(body) => self.body_schema(body, &item.serde),
+            Shape::Enum(variants) => self.enum_schema(variants, &item.serde),
+        };
+        with_docs(schema, &item.docs)
+    }
+}
+
+/// Reads the structs and enums declared in the `.rs` files under `crate_path/src`.
+fn read_items(crate_path: &Path) -> Result<HashMap<String, Item>, String> {
+    let src = crate_path.join("src");
+    if !src.is_dir() {
+        return Err(format!("{} has no src directory", crate_path.display()));
+    }
+    let mut items = HashMap::new();
+    let walker = walkdir::WalkDir::new(&src).sort_by_file_name();
+    for entry in walker.into_iter().flatten().filter(|e| e.path().extension().is_some_and(|ext| ext == "rs")) {
+        let source = fs::read_to_string(entry.path()).map_err(|e| format!("Cannot read {}: {}", entry.path().display(), e))?;
+        for (name, item) in (Parser { tokens: tokenize(&source), pos: 0 }).items() {
+            items.entry(name).or_insert(item);
+        }
+    }
+    Ok(items)
+}
+
+/// A JSON Schema (2020-12) for the config type `root` of the crate at `crate_path`, as serde
+/// reads it: field names after `rename`/`rename_all`, `Option` and `default` fields not
+/// required, `deny_unknown_fields` closing the object, and enums tagged as serde tags them.
+/// Types the root refers to go into `$defs`; doc comments become descriptions.
+pub fn scaffold_schema(crate_path: &Path, root: &str) -> Result<JsonValue, String> {
+    let items = read_items(crate_path)?;
+    if !items.contains_key(root) {
+        return Err(format!("No struct or enum named {} under {}", root, crate_path.join("src").display()));
+    }
+    let mut generator = Generator { items: &items, root, defs: BTreeMap::new(), queued: BTreeSet::new() };
+    let mut schema = generator.item_schema(root);
+    while let Some(name) = generator.queued.iter().find(|name| !generator.defs.contains_key(*name)).cloned() {
+        let def = generator.item_schema(&name);
+        generator.defs.insert(name, def);
+    }
+    if let JsonValue::Object(object) = &mut schema {
+        object.insert("$schema".to_string(), json!("https://json-schema.org/draft/2020-12/schema"));
+        object.insert("title".to_string(), json!(root));
+        if !generator.defs.is_empty() {
+            object.insert("$defs".to_string(), json!(generator.defs));
+        }
+    }
+    Ok(schema)
+}
+
+// Keywords that describe rather than constrain; drift in these does not matter
+const ANNOTATIONS: &[&str] = &["$schema", "$id", "$comment", "title", "description", "examples", "default"];
+
+// Keywords whose value maps names to subschemas, so its keys are never annotations
+const SCHEMA_MAPS: &[&str] = &["properties", "patternProperties", "$defs", "definitions", "dependentSchemas"];
+
+/// A place where the generated and the committed schema differ; `None` where one lacks it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Drift {
+    pub pointer: String,
+    pub generated: Option<JsonValue>,
+    pub committed: Option<JsonValue>,
+}
+
+fn compare(pointer: &str, generated: &JsonValue, committed: &JsonValue, names: bool, drifts: &mut Vec<Drift>) {
+    let differ = |drifts: &mut Vec<Drift>| {
+        drifts.push(Drift { pointer: pointer.to_string(), generated: Some(generated.clone()), committed: Some(committed.clone()) })
+    };
+    match (generated, committed) {
+        (JsonValue::Object(a), JsonValue::Object(b)) => {
+            let keys: BTreeSet<&String> = a.keys().chain(b.keys()).collect();
+            for key in keys.into_iter().filter(|key| names || !ANNOTATIONS.contains(&key.as_str())) {
+                let child = format!("{}/{}", pointer, key.replace('~', "~0").replace('/', "~1"));
+                let maps = !names && SCHEMA_MAPS.contains(&key.as_str());
+                match (a.get(key), b.get(key)) {
+                    (Some(a), Some(b)) => compare(&child, a, b, maps, drifts),
+                    (a, b) => drifts.push(Drift { pointer: child, generated: a.cloned(), committed: b.cloned() }),
+                }
+            }
+        }
+        // The order of required names and enum values carries no meaning
+        (JsonValue::Array(a), JsonValue::Array(b)) if pointer.ends_with("/required") || pointer.ends_with("/enum") => {
+            let set = |values: &Vec<JsonValue>| values.iter().map(JsonValue::to_string).collect::<BTreeSet<_>>();
+            if set(a) != set(b) {
+                differ(drifts);
+            }
+        }
+        (JsonValue::Array(a), JsonValue::Array(b)) if a.len() == b.len() => {
+            for (index, (a, b)) in a.iter().zip(b).enumerate() {
+                compare(&format!("{}/{}", pointer, index), a, b, false, drifts);
+            }
+        }
+        (a, b) if a != b => differ(drifts),
+        _ => {}
+    }
+}
+
+/// Where `generated` and `committed` differ as schemas: annotations (titles, descriptions,
+/// examples, defaults) are ignored, as is the order of `required` names and `enum` values.
+pub fn schema_drift(generated: &JsonValue, committed: &JsonValue) -> Vec<Drift> {
+    let mut drifts = Vec::new();
+    compare("", generated, committed, false, &mut drifts);
+    drifts
+}
+
+/// One line per drift, e.g. `/properties/port/minimum: 0 in the generated schema, missing in schema.json`.
+pub fn render_drift(drifts: &[Drift], committed_name: &str) -> String {
+    let shown = |value: &Option<JsonValue>| value.as_ref().map_or_else(|| "missing".to_string(), JsonValue::to_string);
+    drifts
+        .iter()
+        .map(|drift| {
+            let pointer = if drift.pointer.is_empty() { "/" } else { drift.pointer.as_str() };
+            format!("{}: {} in the generated schema, {} in {}\n", pointer, shown(&drift.generated), shown(&drift.committed), committed_name)
+        })
+        .collect()
+}
@@ -1,13 +1,92 @@
 use std::path::PathBuf;
 use std::fs;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
 use serde_json::Value as JsonValue;
-use jsonschema::Validator; // Changed from JSONSchema to Validator in newer versions
-use miette::Result; // Result from miette
+use jsonschema::{Draft, PatternOptions, Validator}; // Changed from JSONSchema to Validator in newer versions
+use miette::{Result, SourceSpan}; // Result from miette
 
 use crate::error::AppError; // Assuming error.rs is in src/ and AppError is pub
+use crate::paths;
 
 // Function to load and compile a JSON schema from a PathBuf (local or URL)
 pub fn load_and_compile_schema(schema_path: &PathBuf) -> Result<Validator, AppError> { // Changed return type
+    let schema_json = load_schema_json(schema_path)?;
+    compile_schema(&schema_json, &schema_path.to_string_lossy())
+}
+
+// Compiles an already-loaded schema document; `source_display` names it in errors
+pub fn compile_schema(schema_json: &JsonValue, source_display: &str) -> Result<Validator, AppError> {
+    compile_schema_with_draft(schema_json, source_display, None)
+}
+
+/// Backtracking steps a `pattern` may take on one value by default (`--regex-backtrack-limit`).
+pub const DEFAULT_REGEX_BACKTRACK_LIMIT: usize = 1_000_000;
+
+static REGEX_BACKTRACK_LIMIT: AtomicUsize = AtomicUsize::new(DEFAULT_REGEX_BACKTRACK_LIMIT);
+
+/// Bounds the work of every `pattern` and `patternProperties` regex of schemas compiled from
+/// now on: a value that takes more backtracking steps fails validation instead of stalling
+/// the run.
+pub fn set_regex_backtrack_limit(limit: usize) {
+    REGEX_BACKTRACK_LIMIT.store(limit, Ordering::Relaxed);
+}
+
+/// Compiles a schema document, using `default_draft` when it has no `$schema` of its own.
+pub fn compile_schema_with_draft(schema_json: &JsonValue, source_display: &str, default_draft: Option<Draft>) -> Result<Validator, AppError> {
+    let limit = REGEX_BACKTRACK_LIMIT.load(Ordering::Relaxed);
+    let mut options = jsonschema::options().with_pattern_options(PatternOptions::fancy_regex().backtrack_limit(limit));
+    if let Some(draft) = default_draft.filter(|_| !declares_dialect(schema_json)) {
+        options = options.with_draft(draft);
+    }
+    options.build(schema_json).map_err(|e| AppError::SchemaCompileError {
+        source_display: source_display.to_string(),
+        source: Box::new(e),
+    })
+}
+
+/// Dialect names accepted by `--default-draft` and `default_draft`: `4`, `6`, `7`,
+/// `2019-09` or `2020-12`, optionally prefixed with `draft` (`draft7`, `draft-07`).
+pub fn parse_draft(name: &str) -> Option<Draft> {
+    match name.trim().trim_start_matches("draft").trim_start_matches('-') {
+        "4" | "04" => Some(Draft::Draft4),
+        "6" | "06" => Some(Draft::Draft6),
+        "7" | "07" => Some(Draft::Draft7),
+        "2019-09" => Some(Draft::Draft201909),
+        "2020-12" => Some(Draft::Draft202012),
+        _ => None,
+    }
+}
+
+/// Short name of a dialect, e.g. `draft-07` or `2020-12`.
+pub fn draft_name(draft: Draft) -> &'static str {
+    match draft {
+        Draft::Draft4 => "draft-04",
+        Draft::Draft6 => "draft-06",
+        Draft::Draft7 => "draft-07",
+        Draft::Draft201909 => "2019-09",
+        _ => "2020-12",
+    }
+}
+
+// Whether the document names its dialect; boolean schemas have nothing to assume
+fn declares_dialect(schema_json: &JsonValue) -> bool {
+    !schema_json.is_object() || schema_json.get("$schema").is_some_and(JsonValue::is_string)
+}
+
+/// The warning for a schema whose dialect had to be assumed because it has no `$schema`.
+pub fn assumed_dialect(schema_json: &JsonValue, source_display: &str, default_draft: Option<Draft>) -> Option<AppError> {
+    (!declares_dialect(schema_json)).then(|| AppError::AssumedDialect {
+        schema: source_display.to_string(),
+        dialect: draft_name(default_draft.unwrap_or_default()).to_string(),
+    })
+}
+
+/// How long a remote schema is read from the cache before it is fetched again.
+pub const SCHEMA_CACHE_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+// Reads a schema document (local path or URL) without compiling it
+pub fn load_schema_json(schema_path: &PathBuf) -> Result<JsonValue, AppError> {
     let schema_content: String;
     let source_display = schema_path.to_string_lossy().to_string();
 
@@ -16,25 +95,58 @@ pub fn load_and_compile_schema(schema_path: &PathBuf) -> Result<Validator, AppEr
         let url_str = schema_path.to_str().ok_or_else(|| AppError::InvalidSchemaPath {
             path_display: source_display.clone(),
         })?;
-        schema_content = reqwest::blocking::get(url_str)
-            .map_err(|e| AppError::SchemaFetchError { url: source_display.clone(), source: e })?
-            .text()
-            .map_err(|e| AppError::SchemaFetchError { url: source_display.clone(), source: e })?;
+        let cache_entry = paths::schema_cache_entry(url_str);
+        let cached = fs::read_to_string(&cache_entry).ok();
+        let fresh = fs::metadata(&cache_entry)
+            .and_then(|metadata| metadata.modified())
+            .is_ok_and(|modified| modified.elapsed().is_ok_and(|age| age < SCHEMA_CACHE_MAX_AGE));
+        match cached {
+            Some(cached) if fresh => schema_content = cached,
+            cached => {
+                // Error statuses fail here, so an error page is never cached as the schema
+                let fetched = reqwest::blocking::get(url_str)
+                    .and_then(|response| response.error_for_status())
+                    .and_then(|response| response.text());
+                schema_content = match (fetched, cached) {
+                    (Ok(fetched), _) => {
+                        // A failed cache write only costs a refetch next time, so it is not fatal
+                        let _ = paths::write_with_parents(&cache_entry, fetched.as_bytes());
+                        fetched
+                    }
+                    // An expired copy still beats failing while the server is unreachable
+                    (Err(_), Some(stale)) => stale,
+                    (Err(e), None) => return Err(AppError::SchemaFetchError { url: source_display, source: e }),
+                };
+            }
+        }
     } else {
         schema_content = fs::read_to_string(schema_path)
             .map_err(|e| AppError::FileReadError { path: schema_path.clone(), source: e, span: None })?;
     }
 
-    let schema_json: JsonValue = serde_json::from_str(&schema_content)
+    let schema_json = serde_json::from_str(&schema_content)
         .map_err(|e| AppError::SchemaParseError { source_display: source_display.clone(), source: e })?;
-    
-    // Use Validator::new instead of JSONSchema::compile
-    Validator::new(&schema_json)
-        .map_err(|e| {
-            AppError::SchemaCompileError {
-                source_display,
-                source: e, 
-            }
-        })
+    check_shape(&schema_json, &source_display, &schema_content)?;
+    Ok(schema_json)
+}
+
+/// Rejects documents that parse as JSON but cannot be a schema (arrays, strings, numbers,
+/// `null`), pointing at the top-level value (its opening `[` for arrays) instead of leaving it
+/// to the compiler.
+pub fn check_shape(schema_json: &JsonValue, source_display: &str, content: &str) -> Result<(), AppError> {
+    let kind = match schema_json {
+        JsonValue::Object(_) | JsonValue::Bool(_) => return Ok(()),
+        JsonValue::Array(_) => "an array",
+        JsonValue::String(_) => "a string",
+        JsonValue::Number(_) => "a number",
+        JsonValue::Null => "null",
+    };
+    let start = content.len() - content.trim_start().len();
+    let len = if schema_json.is_array() { 1 } else { content.trim().len() };
+    Err(AppError::InvalidSchemaShape {
+        source_display: source_display.to_string(),
+        kind,
+        span: SourceSpan::new(start.into(), len),
+        source_code: content.to_string(),
+    })
 }
- 
\ No newline at end of file
@@ -0,0 +1,60 @@
+use std::path::{Path, PathBuf};
+use jsonschema::Validator;
+
+use crate::error::AppError;
+use crate::quick;
+use crate::renames;
+use crate::schema;
+use crate::validation::ValidationOptions;
+
+/// Marker of a first-line schema binding: `# tj-schema: ../schemas/service.json`
+/// (or `// tj-schema: ...` for JSON and HCL).
+pub const SCHEMA_COMMENT: &str = "tj-schema:";
+
+// Byte range of the annotation comment (the whole first line) and the schema it names
+fn first_line_annotation(content: &str) -> Option<(std::ops::Range<usize>, &str)> {
+    let bom = if content.starts_with('\u{feff}') { '\u{feff}'.len_utf8() } else { 0 };
+    let line_end = content[bom..].find('\n').map_or(content.len(), |i| i + bom);
+    let line = content[bom..line_end].trim();
+    let comment = line.strip_prefix("//").or_else(|| line.strip_prefix('#'))?;
+    let target = comment.trim_start().strip_prefix(SCHEMA_COMMENT)?.trim();
+    (!target.is_empty()).then_some((bom..line_end, target))
+}
+
+/// Schema named by the file's first-line comment. Relative paths are resolved against the
+/// file's directory; URLs are kept as they are.
+pub fn annotated_schema_path(input: &Path, content: &str) -> Option<PathBuf> {
+    let (_, target) = first_line_annotation(content)?;
+    if target.starts_with("http://") || target.starts_with("https://") {
+        return Some(PathBuf::from(target));
+    }
+    Some(input.parent().unwrap_or(Path::new("")).join(target))
+}
+
+/// `content` with the annotation line blanked out (same length, so spans still line up),
+/// for formats such as plain JSON that have no comment syntax of their own.
+pub fn without_annotation(content: &str) -> String {
+    match first_line_annotation(content) {
+        Some((range, _)) => {
+            let mut blanked = content.to_string();
+            blanked.replace_range(range.clone(), &" ".repeat(range.len()));
+            blanked
+        }
+        None => content.to_string(),
+    }
+}
+
+/// Compiles an annotated schema and the options to validate its files with: the
+/// `x-renamed-from` rules come from that schema, not the one given on the command line.
+/// Also returns the warning for a schema whose dialect had to be assumed.
+pub fn compile_annotated(
+    schema_path: &PathBuf,
+    options: &ValidationOptions,
+) -> Result<(Validator, ValidationOptions, Option<AppError>), AppError> {
+    let schema_json = schema::load_schema_json(schema_path)?;
+    let display = schema_path.to_string_lossy();
+    let validator = quick::compile(&schema_json, &display, options.default_draft, options.quick)?;
+    let dialect_warning = schema::assumed_dialect(&schema_json, &display, options.default_draft);
+    let options = ValidationOptions { rename_rules: renames::collect_rename_rules(&schema_json), ..options.clone() };
+    Ok((validator, options, dialect_warning))
+}
@@ -0,0 +1,74 @@
+use miette::SourceSpan;
+use serde_json::{Map, Value as JsonValue};
+
+use crate::config_merge::pointer_span;
+use crate::error::{AppError, InvalidSchemaExample};
+use crate::schema::compile_schema;
+use crate::schema_extract::extract_fragment;
+
+// Keywords whose value maps names to subschemas, so a property called `examples` is not
+// mistaken for the keyword
+const SCHEMA_MAPS: &[&str] = &["properties", "patternProperties", "$defs", "definitions", "dependentSchemas"];
+
+pub(crate) fn escape_pointer_segment(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+/// Every object in `value` that sits where a subschema can, with its JSON pointer; what
+/// `enum`, `const`, `default` and `examples` hold is data, not schema, and is skipped.
+pub(crate) fn subschemas<'a>(value: &'a JsonValue, pointer: String, found: &mut Vec<(String, &'a Map<String, JsonValue>)>) {
+    match value {
+        JsonValue::Object(object) => {
+            found.push((pointer.clone(), object));
+            for (key, child) in object {
+                let child_pointer = format!("{}/{}", pointer, escape_pointer_segment(key));
+                match (key.as_str(), child) {
+                    ("enum" | "const" | "default" | "examples", _) => {}
+                    (map, JsonValue::Object(children)) if SCHEMA_MAPS.contains(&map) => {
+                        for (name, subschema) in children {
+                            subschemas(subschema, format!("{}/{}", child_pointer, escape_pointer_segment(name)), found);
+                        }
+                    }
+                    _ => subschemas(child, child_pointer, found),
+                }
+            }
+        }
+        JsonValue::Array(items) => {
+            for (index, item) in items.iter().enumerate() {
+                subschemas(item, format!("{}/{}", pointer, index), found);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// An error for each `examples` entry of `schema` that the subschema it sits in rejects,
+/// labelled at the `examples` key in `content` (the schema's text) when it can be found there.
+/// Subschemas that cannot stand alone (a `$ref` to the root, a reference that does not
+/// resolve, a compile error) are skipped; `schema check` reports those.
+pub fn invalid_example_diagnostics(schema: &JsonValue, display: &str, content: &str) -> Vec<AppError> {
+    let mut found = Vec::new();
+    subschemas(schema, String::new(), &mut found);
+    let mut errors = Vec::new();
+    for (pointer, subschema) in found {
+        let Some(JsonValue::Array(examples)) = subschema.get("examples") else { continue };
+        let fragment = if pointer.is_empty() { Ok(schema.clone()) } else { extract_fragment(schema, &pointer) };
+        let Some(validator) = fragment.ok().and_then(|fragment| compile_schema(&fragment, display).ok()) else { continue };
+        let examples_pointer = format!("{}/examples", pointer);
+        for (index, example) in examples.iter().enumerate() {
+            let Err(error) = validator.validate(example) else { continue };
+            let at = error.instance_path.to_string();
+            let message = if at.is_empty() { error.to_string() } else { format!("{} (at {})", error, at) };
+            let span = pointer_span(content, &examples_pointer).unwrap_or(0..0);
+            errors.push(AppError::InvalidSchemaExample(Box::new(InvalidSchemaExample {
+                schema: display.to_string(),
+                pointer: examples_pointer.clone(),
+                index,
+                message,
+                span: SourceSpan::new(span.start.into(), span.len()),
+                source_code: content.to_string(),
+            })));
+        }
+    }
+    errors
+}
@@ -0,0 +1,143 @@
+use std::collections::BTreeSet;
+use serde_json::Value as JsonValue;
+
+// Keywords whose values are data, so a `$ref` key inside them is not a reference
+const DATA_KEYWORDS: &[&str] = &["enum", "const", "default", "examples"];
+
+// Local `$ref` targets (JSON pointers, without the leading `#`) anywhere under `value`
+pub(crate) fn local_refs(value: &JsonValue, refs: &mut Vec<String>) {
+    match value {
+        JsonValue::Object(object) => {
+            for (key, child) in object {
+                match (key.as_str(), child) {
+                    ("$ref", JsonValue::String(target)) => refs.extend(target.strip_prefix('#').map(str::to_string)),
+                    (keyword, _) if DATA_KEYWORDS.contains(&keyword) => {}
+                    _ => local_refs(child, refs),
+                }
+            }
+        }
+        JsonValue::Array(items) => items.iter().for_each(|item| local_refs(item, refs)),
+        _ => {}
+    }
+}
+
+fn rewrite_refs(value: &mut JsonValue, relocate: &impl Fn(&str) -> Option<String>) {
+    match value {
+        JsonValue::Object(object) => {
+            for (key, child) in object.iter_mut() {
+                match child {
+                    JsonValue::String(target) if key == "$ref" => {
+                        if let Some(moved) = target.strip_prefix('#').and_then(relocate) {
+                            *target = format!("#{}", moved);
+                        }
+                    }
+                    _ if DATA_KEYWORDS.contains(&key.as_str()) => {}
+                    other => rewrite_refs(other, relocate),
+                }
+            }
+        }
+        JsonValue::Array(items) => items.iter_mut().for_each(|item| rewrite_refs(item, relocate)),
+        _ => {}
+    }
+}
+
+// What a referenced location is copied with: its whole `definitions`/`$defs` entry, so
+// references to other parts of that entry keep working, or else the location itself
+pub(crate) fn copy_unit(pointer: &str) -> String {
+    let mut segments = pointer.splitn(4, '/');
+    match (segments.next(), segments.next(), segments.next()) {
+        (Some(""), Some(keyword @ ("definitions" | "$defs")), Some(name)) => format!("/{}/{}", keyword, name),
+        _ => pointer.to_string(),
+    }
+}
+
+// Where `target` ends up: under the longest copied location that contains it
+fn relocated(placed: &[(String, String)], target: &str) -> Option<String> {
+    placed
+        .iter()
+        .filter(|(from, _)| target == from || target.strip_prefix(from.as_str()).is_some_and(|rest| rest.starts_with('/')))
+        .max_by_key(|(from, _)| from.len())
+        .map(|(from, to)| format!("{}{}", to, &target[from.len()..]))
+}
+
+// A definition name for the copy of `unit` that no other definition uses yet
+fn unique_name(unit: &str, taken: &mut BTreeSet<String>) -> String {
+    let last = unit.rsplit('/').next().unwrap_or_default().replace("~1", "/").replace("~0", "~");
+    let base = if last.is_empty() { "fragment".to_string() } else { last };
+    let mut name = base.clone();
+    let mut n = 1;
+    while taken.contains(&name) {
+        n += 1;
+        name = format!("{}_{}", base, n);
+    }
+    taken.insert(name.clone());
+    name
+}
+
+/// Copies the subschema at `pointer` (`#/definitions/Database` or `/definitions/Database`)
+/// into a standalone schema. Definitions it references are copied into its `$defs` (or
+/// `definitions`, when the source uses that) and every local `$ref` is rewritten to match;
+/// the source's `$schema` is kept so the dialect does not change.
+pub fn extract_fragment(schema: &JsonValue, pointer: &str) -> Result<JsonValue, String> {
+    let fragment_pointer = pointer.strip_prefix('#').unwrap_or(pointer);
+    if !fragment_pointer.starts_with('/') {
+        return Err(format!("{:?} is not a JSON pointer into the schema; expected e.g. '#/definitions/Database'", pointer));
+    }
+    let fragment = schema
+        .pointer(fragment_pointer)
+        .ok_or_else(|| format!("The schema has nothing at {:?}", pointer))?
+        .clone();
+    let defs_keyword = if schema.get("definitions").is_some() && schema.get("$defs").is_none() { "definitions" } else { "$defs" };
+
+    let mut taken: BTreeSet<String> = fragment
+        .get(defs_keyword)
+        .and_then(JsonValue::as_object)
+        .map(|defs| defs.keys().cloned().collect())
+        .unwrap_or_default();
+    let mut placed = vec![(fragment_pointer.to_string(), String::new())];
+    let mut copies = Vec::new();
+    let mut pending = vec![fragment.clone()];
+    while let Some(value) = pending.pop() {
+        let mut refs = Vec::new();
+        local_refs(&value, &mut refs);
+        for target in refs {
+            if relocated(&placed, &target).is_some() {
+                continue;
+            }
+            if target.is_empty() {
+                return Err(format!("{:?} refers to the schema root ('#'), which cannot be carried into the fragment", pointer));
+            }
+            let unit = copy_unit(&target);
+            let copied = schema
+                .pointer(&unit)
+                .ok_or_else(|| format!("$ref \"#{}\" does not resolve in the schema", target))?;
+            let name = unique_name(&unit, &mut taken);
+            placed.push((unit, format!("/{}/{}", defs_keyword, name.replace('~', "~0").replace('/', "~1"))));
+            copies.push((name, copied.clone()));
+            pending.push(copied.clone());
+        }
+    }
+
+    let relocate = |target: &str| relocated(&placed, target);
+    let mut extracted = fragment;
+    rewrite_refs(&mut extracted, &relocate);
+    let Some(object) = extracted.as_object_mut() else {
+        // A boolean subschema has no references to carry along
+        return Ok(extracted);
+    };
+    if !copies.is_empty() {
+        let defs = object
+            .entry(defs_keyword)
+            .or_insert_with(|| JsonValue::Object(Default::default()))
+            .as_object_mut()
+            .ok_or_else(|| format!("The fragment's {:?} is not an object", defs_keyword))?;
+        for (name, mut copied) in copies {
+            rewrite_refs(&mut copied, &relocate);
+            defs.insert(name, copied);
+        }
+    }
+    if let Some(dialect) = schema.get("$schema") {
+        object.entry("$schema").or_insert_with(|| dialect.clone());
+    }
+    Ok(extracted)
+}
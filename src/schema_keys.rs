@@ -0,0 +1,175 @@
+use std::collections::BTreeMap;
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+
+use crate::renames::MAX_REF_DEPTH;
+
+/// One valid config path with what the schema says about it.
+///
+/// Paths are dotted keys; `[]` stands for any array item and `*` for any key of a map
+/// (`additionalProperties`), e.g. `workers[].threads` or `labels.*`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaKey {
+    pub path: String,
+    /// JSON types the value may have; empty when the schema does not say
+    pub types: Vec<String>,
+    /// Allowed values (`enum`, or `const` as a single value)
+    #[serde(rename = "enum", skip_serializing_if = "Vec::is_empty")]
+    pub enum_values: Vec<JsonValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<JsonValue>,
+    /// Listed in the parent's `required`
+    pub required: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub deprecated: bool,
+}
+
+/// Export of every path a schema allows, sorted by path.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaKeys {
+    pub schema: String,
+    pub keys: Vec<SchemaKey>,
+}
+
+fn push_unique<T: PartialEq>(into: &mut Vec<T>, value: T) {
+    if !into.contains(&value) {
+        into.push(value);
+    }
+}
+
+// Folds what one schema (or branch) says about `key` into it
+fn describe(key: &mut SchemaKey, schema: &JsonValue) {
+    match schema.get("type") {
+        Some(JsonValue::String(t)) => push_unique(&mut key.types, t.clone()),
+        Some(JsonValue::Array(types)) => {
+            for t in types.iter().filter_map(|t| t.as_str()) {
+                push_unique(&mut key.types, t.to_string());
+            }
+        }
+        _ => {
+            if schema.get("properties").is_some() {
+                push_unique(&mut key.types, "object".to_string());
+            } else if schema.get("items").is_some() {
+                push_unique(&mut key.types, "array".to_string());
+            }
+        }
+    }
+    if let Some(JsonValue::Array(values)) = schema.get("enum") {
+        for value in values {
+            push_unique(&mut key.enum_values, value.clone());
+        }
+    }
+    if let Some(value) = schema.get("const") {
+        push_unique(&mut key.enum_values, value.clone());
+    }
+    if key.description.is_none() {
+        key.description = schema.get("description").and_then(|d| d.as_str()).map(str::to_string);
+    }
+    if key.default.is_none() {
+        key.default = schema.get("default").cloned();
+    }
+    key.deprecated |= schema.get("deprecated").and_then(|d| d.as_bool()).unwrap_or(false);
+}
+
+fn join(parent: &str, segment: &str) -> String {
+    if parent.is_empty() {
+        segment.to_string()
+    } else if segment == "[]" {
+        format!("{}[]", parent)
+    } else {
+        format!("{}.{}", parent, segment)
+    }
+}
+
+struct Walker<'a> {
+    root: &'a JsonValue,
+    keys: BTreeMap<String, SchemaKey>,
+}
+
+impl Walker<'_> {
+    fn entry(&mut self, path: &str, schema: &JsonValue, required: bool) {
+        let key = self.keys.entry(path.to_string()).or_insert_with(|| SchemaKey { path: path.to_string(), ..Default::default() });
+        key.required |= required;
+        describe(key, schema);
+    }
+
+    // Describes `schema` at `path` (already entered by the caller) and walks its children
+    fn walk(&mut self, schema: &JsonValue, path: &str, depth: usize) {
+        if depth > MAX_REF_DEPTH {
+            return;
+        }
+        if let Some(target) = schema.get("$ref").and_then(|r| r.as_str()).and_then(|r| r.strip_prefix('#')) {
+            if let Some(resolved) = self.root.pointer(target) {
+                if !path.is_empty() {
+                    self.entry(path, resolved, false);
+                }
+                self.walk(resolved, path, depth + 1);
+            }
+        }
+        for combinator in ["allOf", "anyOf", "oneOf"] {
+            if let Some(JsonValue::Array(branches)) = schema.get(combinator) {
+                for branch in branches {
+                    if !path.is_empty() {
+                        self.entry(path, branch, false);
+                    }
+                    self.walk(branch, path, depth + 1);
+                }
+            }
+        }
+        let required: Vec<&str> = schema
+            .get("required")
+            .and_then(|r| r.as_array())
+            .map(|r| r.iter().filter_map(|n| n.as_str()).collect())
+            .unwrap_or_default();
+        if let Some(JsonValue::Object(properties)) = schema.get("properties") {
+            for (name, property) in properties {
+                let child = join(path, name);
+                self.entry(&child, property, required.contains(&name.as_str()));
+                self.walk(property, &child, depth);
+            }
+        }
+        if let Some(additional @ JsonValue::Object(_)) = schema.get("additionalProperties") {
+            let child = join(path, "*");
+            self.entry(&child, additional, false);
+            self.walk(additional, &child, depth);
+        }
+        if let Some(items @ JsonValue::Object(_)) = schema.get("items") {
+            let child = join(path, "[]");
+            self.entry(&child, items, false);
+            self.walk(items, &child, depth);
+        }
+    }
+}
+
+/// Flattens a schema into the paths it allows. Local `$ref`s and `allOf`/`anyOf`/`oneOf`
+/// branches are merged into the paths they apply to.
+pub fn schema_keys(schema: &JsonValue, schema_display: &str) -> SchemaKeys {
+    let mut walker = Walker { root: schema, keys: BTreeMap::new() };
+    walker.walk(schema, "", 0);
+    SchemaKeys { schema: schema_display.to_string(), keys: walker.keys.into_values().collect() }
+}
+
+/// Plain-text rendering for `schema keys --format human`.
+pub fn render_human(keys: &SchemaKeys) -> String {
+    let mut out = String::new();
+    for key in &keys.keys {
+        let mut line = format!("{:<40} {}", key.path, key.types.join("|"));
+        if key.required {
+            line.push_str(" (required)");
+        }
+        if !key.enum_values.is_empty() {
+            let values: Vec<String> = key.enum_values.iter().map(|v| v.to_string()).collect();
+            line.push_str(&format!(" [{}]", values.join(", ")));
+        }
+        if let Some(description) = &key.description {
+            line.push_str(&format!("  {}", description));
+        }
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out
+}
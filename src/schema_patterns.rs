@@ -0,0 +1,162 @@
+use miette::SourceSpan;
+use serde_json::Value as JsonValue;
+
+use crate::config_merge::pointer_span;
+use crate::error::{AppError, UnsafePattern};
+use crate::schema_examples::{escape_pointer_segment, subschemas};
+
+// A group as the scanner sees it: the text written before it, whether something inside
+// repeats without bound, and its top-level alternatives as written
+#[derive(Default)]
+struct Group {
+    before: String,
+    unbounded_inside: bool,
+    alternatives: Vec<String>,
+}
+
+// Whether the quantifier starting at `rest` repeats without bound, and how many chars it takes
+// (including a lazy `?` or possessive `+` suffix). `None` when `rest` starts no quantifier.
+fn quantifier(rest: &[char]) -> Option<(bool, usize)> {
+    let (unbounded, mut len) = match rest.first()? {
+        '*' | '+' => (true, 1),
+        '?' => (false, 1),
+        '{' => {
+            // `{n}`, `{n,}` or `{n,m}`; anything else is a literal brace
+            let close = rest.iter().position(|&c| c == '}')?;
+            let body: String = rest[1..close].iter().collect();
+            let (min, max) = body.split_once(',').unwrap_or((body.as_str(), "0"));
+            let digits = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+            if min.is_empty() || !digits(min) || !digits(max) {
+                return None;
+            }
+            (max.is_empty(), close + 1)
+        }
+        _ => return None,
+    };
+    if matches!(rest.get(len), Some('?' | '+')) {
+        len += 1;
+    }
+    Some((unbounded, len))
+}
+
+// Two alternatives can match the same text when they are equal, one starts with the other,
+// or one starts with `.`
+fn overlapping(alternatives: &[String]) -> bool {
+    alternatives.iter().enumerate().any(|(i, a)| {
+        alternatives[i + 1..].iter().any(|b| a.starts_with(b.as_str()) || b.starts_with(a.as_str()) || a.starts_with('.') || b.starts_with('.'))
+    })
+}
+
+/// Why `pattern` can backtrack exponentially, if it has one of the constructs that do: a
+/// group that repeats without bound around something that also does (`(a+)+`, `(\w*,?)*`),
+/// or a repeated group whose alternatives match the same text (`(a|ab)*`). A heuristic:
+/// it finds the usual culprits, not every slow pattern.
+pub fn catastrophic_reason(pattern: &str) -> Option<String> {
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut stack = vec![Group::default()];
+    let mut current = String::new();
+    let mut closed: Option<Group> = None;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if let Some((unbounded, len)) = quantifier(&chars[i..]) {
+            let quantified: String = chars[i..i + len].iter().collect();
+            if unbounded {
+                if let Some(group) = &closed {
+                    if group.unbounded_inside {
+                        return Some(format!("a group repeated with `{}` around a quantifier that also repeats without bound", quantified));
+                    }
+                    if group.alternatives.len() > 1 && overlapping(&group.alternatives) {
+                        return Some(format!("a group repeated with `{}` whose alternatives can match the same text", quantified));
+                    }
+                }
+                stack.last_mut()?.unbounded_inside = true;
+            }
+            current.push_str(&quantified);
+            closed = None;
+            i += len;
+            continue;
+        }
+        closed = None;
+        match c {
+            '\\' => {
+                current.extend(chars.get(i..i + 2).unwrap_or(&chars[i..]));
+                i += 2;
+                continue;
+            }
+            '[' => {
+                // A class ends at the first unescaped `]`, as in ECMA-262
+                let mut end = i + 1;
+                while end < chars.len() && chars[end] != ']' {
+                    end += if chars[end] == '\\' { 2 } else { 1 };
+                }
+                let end = (end + 1).min(chars.len());
+                current.extend(&chars[i..end]);
+                i = end;
+                continue;
+            }
+            '(' => {
+                stack.push(Group { before: std::mem::take(&mut current), ..Group::default() });
+                // Skip `?:`, `?=`, `?<=`, `?<name>` and the like
+                if chars.get(i + 1) == Some(&'?') {
+                    i += 2;
+                    match (chars.get(i), chars.get(i + 1)) {
+                        (Some('<'), Some('=' | '!')) => i += 2,
+                        (Some('<'), _) | (Some('P'), Some('<')) => {
+                            while i < chars.len() && chars[i] != '>' {
+                                i += 1;
+                            }
+                            i += 1;
+                        }
+                        _ => i += 1,
+                    }
+                    continue;
+                }
+            }
+            ')' if stack.len() > 1 => {
+                let mut group = stack.pop()?;
+                group.alternatives.push(std::mem::take(&mut current));
+                stack.last_mut()?.unbounded_inside |= group.unbounded_inside;
+                current = format!("{}({})", std::mem::take(&mut group.before), group.alternatives.join("|"));
+                closed = Some(group);
+            }
+            '|' => stack.last_mut()?.alternatives.push(std::mem::take(&mut current)),
+            c => current.push(c),
+        }
+        i += 1;
+    }
+    None
+}
+
+/// A warning for each `pattern` and `patternProperties` regex of `schema` that can backtrack
+/// exponentially, labelled at its key in `content` (the schema's text) when it can be found.
+pub fn unsafe_pattern_diagnostics(schema: &JsonValue, display: &str, content: &str) -> Vec<AppError> {
+    let mut found = Vec::new();
+    subschemas(schema, String::new(), &mut found);
+    let mut patterns = Vec::new();
+    for (pointer, subschema) in found {
+        if let Some(JsonValue::String(pattern)) = subschema.get("pattern") {
+            patterns.push((format!("{}/pattern", pointer), pattern.clone()));
+        }
+        if let Some(JsonValue::Object(properties)) = subschema.get("patternProperties") {
+            for pattern in properties.keys() {
+                patterns.push((format!("{}/patternProperties/{}", pointer, escape_pointer_segment(pattern)), pattern.clone()));
+            }
+        }
+    }
+    patterns
+        .into_iter()
+        .filter_map(|(pointer, pattern)| {
+            let reason = catastrophic_reason(&pattern)?;
+            let span = pointer_span(content, &pointer).unwrap_or(0..0);
+            Some(AppError::UnsafePattern(Box::new(UnsafePattern {
+                schema: display.to_string(),
+                pointer,
+                pattern,
+                reason,
+                span: SourceSpan::new(span.start.into(), span.len()),
+                source_code: content.to_string(),
+            })))
+        })
+        .collect()
+}
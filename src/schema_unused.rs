@@ -0,0 +1,96 @@
+use std::collections::{BTreeMap, BTreeSet};
+use miette::SourceSpan;
+use serde_json::Value as JsonValue;
+
+use crate::config_merge::pointer_span;
+use crate::error::AppError;
+use crate::schema_extract::{copy_unit, local_refs};
+
+const DEFINITION_KEYWORDS: &[&str] = &["definitions", "$defs"];
+
+fn escape_pointer_segment(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+// Pointer of every top-level `definitions`/`$defs` entry, e.g. `/$defs/Database`
+fn definition_pointers(schema: &JsonValue) -> Vec<String> {
+    DEFINITION_KEYWORDS
+        .iter()
+        .filter_map(|keyword| Some((keyword, schema.get(*keyword)?.as_object()?)))
+        .flat_map(|(keyword, defs)| defs.keys().map(move |name| format!("/{}/{}", keyword, escape_pointer_segment(name))))
+        .collect()
+}
+
+// Plain-name fragments (`$anchor: name`, or a draft-7 `$id: "#name"`) anywhere in `value`
+fn anchors(value: &JsonValue, found: &mut Vec<String>) {
+    match value {
+        JsonValue::Object(object) => {
+            for (key, child) in object {
+                match (key.as_str(), child) {
+                    ("$anchor", JsonValue::String(name)) => found.push(name.clone()),
+                    ("$id" | "id", JsonValue::String(id)) => found.extend(id.strip_prefix('#').map(str::to_string)),
+                    ("enum" | "const" | "default" | "examples", _) => {}
+                    _ => anchors(child, found),
+                }
+            }
+        }
+        JsonValue::Array(items) => items.iter().for_each(|item| anchors(item, found)),
+        _ => {}
+    }
+}
+
+/// Top-level `definitions`/`$defs` entries that no `$ref` reaches from the schema root,
+/// directly or through other definitions, as JSON pointers. References by JSON pointer and
+/// by `$anchor` count; remote references are not followed.
+pub fn unused_definitions(schema: &JsonValue) -> Vec<String> {
+    let definitions = definition_pointers(schema);
+    let mut by_anchor = BTreeMap::new();
+    for pointer in &definitions {
+        let mut names = Vec::new();
+        if let Some(definition) = schema.pointer(pointer) {
+            anchors(definition, &mut names);
+        }
+        for name in names {
+            by_anchor.entry(name).or_insert_with(|| pointer.clone());
+        }
+    }
+
+    // The root without its definitions, which only count once something refers to them
+    let mut root = schema.clone();
+    if let Some(object) = root.as_object_mut() {
+        for keyword in DEFINITION_KEYWORDS {
+            object.remove(*keyword);
+        }
+    }
+    let mut reached = BTreeSet::new();
+    let mut pending = vec![root];
+    while let Some(value) = pending.pop() {
+        let mut refs = Vec::new();
+        local_refs(&value, &mut refs);
+        for target in refs {
+            let unit = if target.starts_with('/') { copy_unit(&target) } else { by_anchor.get(&target).cloned().unwrap_or_default() };
+            if !definitions.contains(&unit) || !reached.insert(unit.clone()) {
+                continue;
+            }
+            pending.extend(schema.pointer(&unit).cloned());
+        }
+    }
+    definitions.into_iter().filter(|pointer| !reached.contains(pointer)).collect()
+}
+
+/// A warning for each unused definition of `schema`, labelled at its key in `content` (the
+/// schema's text) when it can be found there.
+pub fn unused_definition_diagnostics(schema: &JsonValue, display: &str, content: &str) -> Vec<AppError> {
+    unused_definitions(schema)
+        .into_iter()
+        .map(|pointer| {
+            let span = pointer_span(content, &pointer).unwrap_or(0..0);
+            AppError::UnusedDefinition {
+                schema: display.to_string(),
+                pointer,
+                span: SourceSpan::new(span.start.into(), span.len()),
+                source_code: content.to_string(),
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,180 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+
+use crate::patch::load_instance;
+use crate::schema_keys::schema_keys;
+use crate::yaml;
+
+/// Values listed per property; the rest only count towards `distinctValues`.
+pub const TOP_VALUES: usize = 10;
+
+/// How often one scalar value appears at a property.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ValueCount {
+    pub value: JsonValue,
+    pub count: usize,
+}
+
+/// How the corpus uses one schema path.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PropertyUsage {
+    pub path: String,
+    /// Documents with at least one value at the path
+    pub set_in: usize,
+    /// Most common scalar values, most frequent first
+    pub values: Vec<ValueCount>,
+    pub distinct_values: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<JsonValue>,
+    /// Every document sets the property, and none to its default
+    pub default_always_overridden: bool,
+}
+
+/// Property usage of a schema across a corpus of documents.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaUsage {
+    pub schema: String,
+    pub documents: usize,
+    /// Files that could not be parsed (or hold several YAML documents) and were skipped
+    pub skipped: Vec<String>,
+    pub properties: Vec<PropertyUsage>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Step {
+    Key(String),
+    AnyKey,
+    Items,
+}
+
+// `workers[].labels.*` -> Key(workers), Items, Key(labels), AnyKey
+fn steps(path: &str) -> Vec<Step> {
+    let mut steps = Vec::new();
+    for segment in path.split('.') {
+        let name = segment.trim_end_matches("[]");
+        match name {
+            "" => {}
+            "*" => steps.push(Step::AnyKey),
+            _ => steps.push(Step::Key(name.to_string())),
+        }
+        for _ in 0..(segment.len() - name.len()) / 2 {
+            steps.push(Step::Items);
+        }
+    }
+    steps
+}
+
+fn collect<'a>(value: &'a JsonValue, steps: &[Step], found: &mut Vec<&'a JsonValue>) {
+    let Some((first, rest)) = steps.split_first() else {
+        found.push(value);
+        return;
+    };
+    match (first, value) {
+        (Step::Key(name), JsonValue::Object(map)) => {
+            if let Some(child) = map.get(name) {
+                collect(child, rest, found);
+            }
+        }
+        (Step::AnyKey, JsonValue::Object(map)) => map.values().for_each(|child| collect(child, rest, found)),
+        (Step::Items, JsonValue::Array(items)) => items.iter().for_each(|child| collect(child, rest, found)),
+        _ => {}
+    }
+}
+
+/// Values at a `schema keys` path (`[]` any array item, `*` any map key).
+pub fn values_at<'a>(document: &'a JsonValue, path: &str) -> Vec<&'a JsonValue> {
+    let mut found = Vec::new();
+    collect(document, &steps(path), &mut found);
+    found
+}
+
+#[derive(Default)]
+struct Tally {
+    set_in: usize,
+    values: Vec<ValueCount>,
+    set_to_default: bool,
+}
+
+/// Counts, for every path of `schema`, how many documents set it and to what.
+pub fn schema_usage(schema: &JsonValue, schema_display: &str, files: &[PathBuf], tags: &yaml::TagPolicy) -> SchemaUsage {
+    let keys = schema_keys(schema, schema_display).keys;
+    let mut tallies: BTreeMap<&str, Tally> = BTreeMap::new();
+    let mut documents = 0;
+    let mut skipped = Vec::new();
+    for file in files {
+        let Some(document) = load_instance(file, tags) else {
+            skipped.push(file.to_string_lossy().into_owned());
+            continue;
+        };
+        documents += 1;
+        for key in &keys {
+            let found = values_at(&document, &key.path);
+            if found.is_empty() {
+                continue;
+            }
+            let tally = tallies.entry(key.path.as_str()).or_default();
+            tally.set_in += 1;
+            for value in found {
+                tally.set_to_default |= key.default.as_ref() == Some(value);
+                if value.is_object() || value.is_array() {
+                    continue;
+                }
+                match tally.values.iter_mut().find(|v| v.value == *value) {
+                    Some(existing) => existing.count += 1,
+                    None => tally.values.push(ValueCount { value: value.clone(), count: 1 }),
+                }
+            }
+        }
+    }
+
+    let properties = keys
+        .iter()
+        .map(|key| {
+            let tally = tallies.remove(key.path.as_str()).unwrap_or_default();
+            let mut values = tally.values;
+            values.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.to_string().cmp(&b.value.to_string())));
+            let distinct_values = values.len();
+            values.truncate(TOP_VALUES);
+            PropertyUsage {
+                path: key.path.clone(),
+                set_in: tally.set_in,
+                values,
+                distinct_values,
+                default: key.default.clone(),
+                default_always_overridden: key.default.is_some() && documents > 0 && tally.set_in == documents && !tally.set_to_default,
+            }
+        })
+        .collect();
+    SchemaUsage { schema: schema_display.to_string(), documents, skipped, properties }
+}
+
+/// Plain-text rendering for `schema usage --format human`.
+pub fn render_human(usage: &SchemaUsage) -> String {
+    let mut out = format!("{} document(s) checked against {}\n", usage.documents, usage.schema);
+    for file in &usage.skipped {
+        out.push_str(&format!("skipped (not parseable): {}\n", file));
+    }
+    out.push('\n');
+    for property in &usage.properties {
+        let mut line = format!("{:<40} {}/{}", property.path, property.set_in, usage.documents);
+        if !property.values.is_empty() {
+            let values: Vec<String> = property.values.iter().map(|v| format!("{} ({})", v.value, v.count)).collect();
+            line.push_str(&format!("  {}", values.join(", ")));
+            if property.distinct_values > property.values.len() {
+                line.push_str(&format!(", ... {} distinct", property.distinct_values));
+            }
+        }
+        if property.default_always_overridden {
+            line.push_str("  [default always overridden]");
+        } else if property.set_in == 0 {
+            line.push_str("  [never set]");
+        }
+        out.push_str(&line);
+        out.push('\n');
+    }
+    out
+}
@@ -0,0 +1,89 @@
+use std::collections::BTreeMap;
+use miette::Diagnostic;
+use serde::Deserialize;
+
+use crate::error::{AppError, Severity};
+
+/// Level a diagnostic is mapped to by `--severity RULE=LEVEL`, the project config's
+/// `[severity]` table or a rules pack's manifest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleSeverity {
+    Error,
+    /// Reported, but does not fail the run
+    Warning,
+    /// Reported as a note; does not fail the run
+    Info,
+    /// Not reported at all
+    Off,
+}
+
+impl RuleSeverity {
+    /// Parses `error`, `warning`, `info` or `off`.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "error" => Some(RuleSeverity::Error),
+            "warning" => Some(RuleSeverity::Warning),
+            "info" => Some(RuleSeverity::Info),
+            "off" => Some(RuleSeverity::Off),
+            _ => None,
+        }
+    }
+
+    /// The level mapped diagnostics are reported at; `None` for `off`.
+    pub fn level(self) -> Option<Severity> {
+        match self {
+            RuleSeverity::Error => Some(Severity::Error),
+            RuleSeverity::Warning => Some(Severity::Warning),
+            RuleSeverity::Info => Some(Severity::Info),
+            RuleSeverity::Off => None,
+        }
+    }
+}
+
+/// Parses one `--severity` value, `RULE=LEVEL`.
+pub fn parse_flag(flag: &str) -> Result<(String, RuleSeverity), String> {
+    let (rule, level) = flag.split_once('=').ok_or_else(|| format!("--severity {:?}: expected RULE=LEVEL", flag))?;
+    let level = RuleSeverity::parse(level.trim())
+        .ok_or_else(|| format!("--severity {:?}: level must be error, warning, info or off", flag))?;
+    match rule.trim() {
+        "" => Err(format!("--severity {:?}: expected RULE=LEVEL", flag)),
+        rule => Ok((rule.to_string(), level)),
+    }
+}
+
+/// Names a severity mapping can use for a diagnostic, most specific first: the failing
+/// schema keyword (`required`, `enum`, ...) or semantic check, then the diagnostic code.
+pub fn rule_names(error: &AppError) -> Vec<String> {
+    let error = error.unwrapped();
+    let mut names = Vec::new();
+    match error {
+        AppError::SchemaValidationError(error) => names.push(error.violation.keyword.clone()),
+        AppError::CoercibleType(error) => names.push(error.violation.keyword.clone()),
+        AppError::SemanticError { check, .. } => names.push(check.clone()),
+        _ => {}
+    }
+    names.extend(error.code().map(|code| code.to_string()));
+    names
+}
+
+/// The mapped severity for a diagnostic, if `mapping` names one of its rules.
+pub fn severity_for(mapping: &BTreeMap<String, RuleSeverity>, error: &AppError) -> Option<RuleSeverity> {
+    rule_names(error).iter().find_map(|name| mapping.get(name).copied())
+}
+
+/// Applies `mapping`: `off` drops diagnostics, the other levels report them at that level.
+/// Unmapped diagnostics keep theirs.
+pub fn apply(mapping: &BTreeMap<String, RuleSeverity>, errors: Vec<AppError>) -> Vec<AppError> {
+    if mapping.is_empty() {
+        return errors;
+    }
+    errors
+        .into_iter()
+        .filter_map(|error| match severity_for(mapping, &error).map(RuleSeverity::level) {
+            None => Some(error),
+            Some(None) => None,
+            Some(Some(level)) => Some(error.into_level(level)),
+        })
+        .collect()
+}
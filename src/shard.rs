@@ -0,0 +1,37 @@
+use std::path::PathBuf;
+
+/// One of `count` parallel CI jobs, from `--shard INDEX/COUNT` (1-based).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Shard {
+    pub index: usize,
+    pub count: usize,
+}
+
+impl Shard {
+    /// Parses `2/5`: the second of five shards.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let invalid = || format!("Invalid --shard {:?}; expected INDEX/COUNT with 1 <= INDEX <= COUNT, e.g. 2/5", spec);
+        let (index, count) = spec.split_once('/').ok_or_else(invalid)?;
+        let index: usize = index.trim().parse().map_err(|_| invalid())?;
+        let count: usize = count.trim().parse().map_err(|_| invalid())?;
+        if index == 0 || index > count {
+            return Err(invalid());
+        }
+        Ok(Shard { index, count })
+    }
+
+    /// This shard's inputs. The list is de-duplicated and sorted before the files are dealt
+    /// out in turn, so the assignment does not depend on argument order and the shards are
+    /// disjoint, cover every input and differ in size by at most one.
+    pub fn select(&self, inputs: &[PathBuf]) -> Vec<PathBuf> {
+        let mut sorted = inputs.to_vec();
+        sorted.sort();
+        sorted.dedup();
+        sorted
+            .into_iter()
+            .enumerate()
+            .filter(|(position, _)| position % self.count == self.index - 1)
+            .map(|(_, input)| input)
+            .collect()
+    }
+}
@@ -0,0 +1,100 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use ed25519_dalek::pkcs8::{DecodePrivateKey, DecodePublicKey};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+
+const ALGORITHM: &str = "ed25519";
+
+/// Detached signature over the exact bytes of a report file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReportSignature {
+    pub algorithm: String,
+    /// Hex-encoded signature
+    pub signature: String,
+}
+
+fn signing_error(path: &Path, message: impl Into<String>) -> AppError {
+    AppError::ReportSigningError {
+        path: path.to_string_lossy().into_owned(),
+        message: message.into(),
+    }
+}
+
+fn read_text(path: &Path) -> Result<String, AppError> {
+    fs::read_to_string(path).map_err(|e| AppError::FileReadError {
+        path: path.to_path_buf(),
+        source: e,
+        span: None,
+    })
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// Where the detached signature of `report` lives by default: `<report>.sig`.
+pub fn signature_path(report: &Path) -> PathBuf {
+    let mut path = report.as_os_str().to_owned();
+    path.push(".sig");
+    PathBuf::from(path)
+}
+
+/// Signs `report` with an Ed25519 private key in PKCS#8 PEM form
+/// (`openssl genpkey -algorithm ed25519`).
+pub fn sign_report(report: &[u8], private_key_pem: &Path) -> Result<ReportSignature, AppError> {
+    let key = SigningKey::from_pkcs8_pem(&read_text(private_key_pem)?)
+        .map_err(|e| signing_error(private_key_pem, format!("not an Ed25519 PKCS#8 private key: {}", e)))?;
+    Ok(ReportSignature {
+        algorithm: ALGORITHM.to_string(),
+        signature: to_hex(&key.sign(report).to_bytes()),
+    })
+}
+
+/// Checks `signature` against `report` with an Ed25519 public key in SPKI PEM form
+/// (`openssl pkey -pubout`). `Ok(false)` means the report or signature was altered
+/// or signed with a different key.
+pub fn verify_report(report: &[u8], signature: &ReportSignature, public_key_pem: &Path) -> Result<bool, AppError> {
+    let key = VerifyingKey::from_public_key_pem(&read_text(public_key_pem)?)
+        .map_err(|e| signing_error(public_key_pem, format!("not an Ed25519 public key: {}", e)))?;
+    if signature.algorithm != ALGORITHM {
+        return Err(signing_error(public_key_pem, format!("unsupported signature algorithm {:?}", signature.algorithm)));
+    }
+    let Some(signature) = from_hex(&signature.signature).and_then(|bytes| Signature::from_slice(&bytes).ok()) else {
+        return Ok(false);
+    };
+    Ok(key.verify(report, &signature).is_ok())
+}
+
+/// Signs the report file at `report` and writes the signature next to it. Returns the signature path.
+pub fn sign_report_file(report: &Path, private_key_pem: &Path) -> Result<PathBuf, AppError> {
+    let bytes = fs::read(report).map_err(|e| AppError::FileReadError { path: report.to_path_buf(), source: e, span: None })?;
+    let signature = sign_report(&bytes, private_key_pem)?;
+    let path = signature_path(report);
+    let rendered = serde_json::to_string_pretty(&signature).expect("signatures serialize");
+    crate::paths::check_writable(&path)
+        .and_then(|()| fs::write(&path, rendered + "\n"))
+        .map_err(|e| signing_error(&path, e.to_string()))?;
+    Ok(path)
+}
+
+/// Verifies the report file at `report` against its detached signature (`<report>.sig` unless given).
+pub fn verify_report_file(report: &Path, signature: Option<&Path>, public_key_pem: &Path) -> Result<bool, AppError> {
+    let bytes = fs::read(report).map_err(|e| AppError::FileReadError { path: report.to_path_buf(), source: e, span: None })?;
+    let signature_path = signature.map(Path::to_path_buf).unwrap_or_else(|| signature_path(report));
+    let signature: ReportSignature = serde_json::from_str(&read_text(&signature_path)?)
+        .map_err(|e| signing_error(&signature_path, format!("malformed signature file: {}", e)))?;
+    verify_report(&bytes, &signature, public_key_pem)
+}
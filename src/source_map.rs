@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use miette::SourceSpan;
+use serde::Deserialize;
+
+use crate::error::{AppError, MappedToTemplate};
+use crate::template;
+use crate::validation::{offset_for_line_col, PrintableError};
+
+/// Sidecar (`<generated file>.map`) saying which template lines produced a generated config:
+///
+/// ```json
+/// { "mappings": [{ "generated": [1, 12], "source": "templates/app.yaml.j2", "line": 3 }] }
+/// ```
+///
+/// Generated lines 1 to 12 come from the template's lines 3 to 14. `source` is relative to the
+/// map's directory.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SourceMap {
+    pub mappings: Vec<Mapping>,
+}
+
+/// One run of generated lines and the template line the first of them came from.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Mapping {
+    /// First and last generated line, 1-based and inclusive
+    pub generated: [usize; 2],
+    pub source: PathBuf,
+    /// Template line of the first generated line
+    pub line: usize,
+}
+
+impl SourceMap {
+    /// Template file and line that produced `generated_line`.
+    pub fn original(&self, generated_line: usize) -> Option<(&Path, usize)> {
+        self.mappings
+            .iter()
+            .find(|m| (m.generated[0]..=m.generated[1]).contains(&generated_line))
+            .map(|m| (m.source.as_path(), m.line + (generated_line - m.generated[0])))
+    }
+}
+
+/// The sidecar map of `generated`: the same path with `.map` appended.
+pub fn sidecar_path(generated: &Path) -> PathBuf {
+    let mut path = generated.as_os_str().to_owned();
+    path.push(".map");
+    PathBuf::from(path)
+}
+
+/// Reads the sidecar map of `generated`; `Ok(None)` when it has none.
+pub fn load_source_map(generated: &Path) -> Result<Option<SourceMap>, String> {
+    let path = sidecar_path(generated);
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(&path).map_err(|e| format!("Cannot read source map {}: {}", path.display(), e))?;
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|e| format!("Invalid source map {}: {}", path.display(), e))
+}
+
+// The diagnostic moved to the template line, if its file has a map that covers the line
+fn map_error(error: AppError, maps: &mut HashMap<String, Option<SourceMap>>) -> AppError {
+    let generated = PrintableError::from(&error).file_path;
+    let Some((line, column)) = template::line_column(&error) else { return error };
+    let map = maps.entry(generated.clone()).or_insert_with(|| {
+        load_source_map(Path::new(&generated)).unwrap_or_else(|message| {
+            eprintln!("{}; reporting {} as generated", message, generated);
+            None
+        })
+    });
+    let Some((source, template_line)) = map.as_ref().and_then(|m| m.original(line)) else { return error };
+    let template = Path::new(&generated).parent().unwrap_or(Path::new("")).join(source);
+    let Ok(source_code) = fs::read_to_string(&template) else { return error };
+    let start = offset_for_line_col(&source_code, template_line, 1);
+    let len = source_code[start..].find('\n').unwrap_or(source_code.len() - start);
+    AppError::MappedToTemplate(Box::new(MappedToTemplate {
+        template,
+        message: error.to_string(),
+        generated_at: format!("{}:{}:{}", generated, line, column),
+        span: SourceSpan::new(start.into(), len),
+        source_code,
+        generated: vec![error],
+    }))
+}
+
+/// Reports diagnostics in generated files at the template line that produced them, for every
+/// file with a sidecar source map; the generated location stays attached as a related report.
+pub fn map_to_templates(errors: Vec<AppError>) -> Vec<AppError> {
+    let mut maps = HashMap::new();
+    errors.into_iter().map(|error| map_error(error, &mut maps)).collect()
+}
@@ -0,0 +1,143 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+use miette::SourceSpan;
+
+use crate::error::AppError;
+use crate::parsers::{self, Parser};
+use crate::schema_comment::SCHEMA_COMMENT;
+
+/// Marker of a validation stamp comment:
+/// `# tj: validated against schema.json@3f2a9c1b04de on 2024-05-01 — PASS`.
+pub const STAMP_MARKER: &str = "tj: validated against ";
+
+/// What a stamp records about the last `check --stamp` of a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stamp {
+    pub schema: String,
+    /// Leading hex digits of the schema's SHA-256
+    pub digest: String,
+    /// UTC date, `YYYY-MM-DD`
+    pub date: String,
+    pub passed: bool,
+}
+
+impl Stamp {
+    /// The stamp as a comment line, without the line break.
+    pub fn render(&self, comment: &str) -> String {
+        format!(
+            "{} {}{}@{} on {} — {}",
+            comment,
+            STAMP_MARKER,
+            self.schema,
+            self.digest,
+            self.date,
+            if self.passed { "PASS" } else { "FAIL" }
+        )
+    }
+
+    fn parse(line: &str) -> Option<Stamp> {
+        let rest = &line[line.find(STAMP_MARKER)? + STAMP_MARKER.len()..];
+        let (identity, rest) = rest.rsplit_once(" on ")?;
+        let (date, result) = rest.split_once(" — ")?;
+        let (schema, digest) = identity.rsplit_once('@')?;
+        let passed = match result.trim() {
+            "PASS" => true,
+            "FAIL" => false,
+            _ => return None,
+        };
+        Some(Stamp { schema: schema.to_string(), digest: digest.to_string(), date: date.to_string(), passed })
+    }
+}
+
+/// Line comment syntax of the format `path` is read as; `None` for formats without comments (JSON).
+pub fn comment_prefix(path: &Path, overrides: &BTreeMap<String, Parser>) -> Option<&'static str> {
+    match parsers::parser_for(path, overrides)? {
+        "yaml" | "yml" | "yaml-template" | "toml" | "hcl" | "tf" | "ncl" => Some("#"),
+        "ini" | "cfg" => Some(";"),
+        "properties" | "hjson" => Some("#"),
+        "cue" | "pkl" | "kdl" => Some("//"),
+        _ => None,
+    }
+}
+
+// Byte ranges of the lines (without line breaks) of the comment block a file starts with
+fn leading_comment_lines(content: &str) -> Vec<Range<usize>> {
+    let mut lines = Vec::new();
+    let mut offset = if content.starts_with('\u{feff}') { '\u{feff}'.len_utf8() } else { 0 };
+    for line in content[offset..].split_inclusive('\n') {
+        let text = line.trim_end_matches(['\n', '\r']);
+        let trimmed = text.trim_start();
+        if !(trimmed.starts_with('#') || trimmed.starts_with("//")) {
+            break;
+        }
+        lines.push(offset..offset + text.len());
+        offset += line.len();
+    }
+    lines
+}
+
+/// The stamp in the file's leading comment block, and where its line is.
+pub fn find_stamp(content: &str) -> Option<(Range<usize>, Stamp)> {
+    leading_comment_lines(content)
+        .into_iter()
+        .find_map(|range| Stamp::parse(&content[range.clone()]).map(|stamp| (range, stamp)))
+}
+
+/// `content` with `stamp_line` replacing its existing stamp, or inserted at the top (after
+/// a first-line `tj-schema:` comment, which must stay first). Nothing else changes.
+pub fn with_stamp(content: &str, stamp_line: &str) -> String {
+    let mut stamped = content.to_string();
+    if let Some((range, _)) = find_stamp(content) {
+        stamped.replace_range(range, stamp_line);
+        return stamped;
+    }
+    let first_line = leading_comment_lines(content).into_iter().next().filter(|l| content[l.clone()].contains(SCHEMA_COMMENT));
+    let at = match first_line {
+        Some(line) => content[line.end..].find('\n').map_or(content.len(), |i| line.end + i + 1),
+        None if content.starts_with('\u{feff}') => '\u{feff}'.len_utf8(),
+        None => 0,
+    };
+    let line_break = if at == content.len() && !content.is_empty() && !content.ends_with('\n') { "\n" } else { "" };
+    stamped.insert_str(at, &format!("{}{}\n", line_break, stamp_line));
+    stamped
+}
+
+/// Writes or refreshes the stamp of `path`. `Ok(false)` when its format has no comments.
+pub fn stamp_file(path: &Path, stamp: &Stamp, overrides: &BTreeMap<String, Parser>) -> Result<bool, String> {
+    let Some(comment) = comment_prefix(path, overrides) else { return Ok(false) };
+    let content = fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+    let stamped = with_stamp(&content, &stamp.render(comment));
+    if stamped != content {
+        crate::paths::check_writable(path).map_err(|e| e.to_string())?;
+        fs::write(path, stamped).map_err(|e| format!("Cannot stamp {}: {}", path.display(), e))?;
+    }
+    Ok(true)
+}
+
+/// Why a stamp no longer vouches for its file: the file changed on a later day than the stamp,
+/// or the stamp names a different schema digest than `schema_digest` (the current schema's
+/// hex SHA-256, when known).
+pub fn stale_reason(stamp: &Stamp, modified_date: &str, schema_digest: Option<&str>) -> Option<String> {
+    if let Some(digest) = schema_digest.filter(|d| !d.starts_with(stamp.digest.as_str()) || stamp.digest.is_empty()) {
+        return Some(format!("it records schema digest {}, but the schema is now {}", stamp.digest, &digest[..digest.len().min(12)]));
+    }
+    (modified_date > stamp.date.as_str())
+        .then(|| format!("the file was modified on {}, after the stamp of {}", modified_date, stamp.date))
+}
+
+/// A warning for a stamped file whose stamp is stale; `None` for unstamped or current files.
+pub fn check_stamp(path: &Path, schema_digest: Option<&str>) -> Option<AppError> {
+    let content = fs::read_to_string(path).ok()?;
+    let (range, stamp) = find_stamp(&content)?;
+    let modified = fs::metadata(path).and_then(|m| m.modified()).ok()?;
+    let modified_date = &crate::provenance::rfc3339(modified)[..10];
+    let reason = stale_reason(&stamp, modified_date, schema_digest)?;
+    Some(AppError::StaleStamp {
+        path: path.to_path_buf(),
+        reason,
+        span: SourceSpan::new(range.start.into(), range.len()),
+        source_code: content,
+    })
+}
@@ -0,0 +1,89 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use miette::Diagnostic;
+use serde::Serialize;
+
+use crate::error::{AppError, Category, Severity};
+
+/// How a run ended, independent of the exact exit code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Outcome {
+    /// Every input is valid (warnings allowed)
+    Passed,
+    /// At least one input has a failing diagnostic
+    Failed,
+    /// The run could not complete (bad flags, unusable schema, unwritable output)
+    Error,
+}
+
+/// Summary written by `--status-file` for build systems that want a declared output.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunStatus {
+    pub outcome: Outcome,
+    pub exit_code: i32,
+    /// Failing diagnostics
+    pub errors: usize,
+    /// Diagnostics that do not fail the run
+    pub warnings: usize,
+    /// Diagnostic counts keyed by code
+    pub by_code: BTreeMap<String, usize>,
+    /// Diagnostic counts keyed by category
+    pub by_category: BTreeMap<Category, usize>,
+    /// Files with at least one diagnostic
+    pub files_with_diagnostics: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl RunStatus {
+    /// Status of a run that finished validating and produced `diagnostics`.
+    pub fn from_diagnostics(diagnostics: &[AppError]) -> Self {
+        let errors = diagnostics.iter().filter(|d| d.level() == Severity::Error).count();
+        let warnings = diagnostics.len() - errors;
+        let mut by_code = BTreeMap::new();
+        let mut by_category = BTreeMap::new();
+        let mut files = Vec::new();
+        for diagnostic in diagnostics {
+            let code = diagnostic.code().map_or_else(|| "unknown".to_string(), |c| c.to_string());
+            *by_code.entry(code).or_insert(0) += 1;
+            *by_category.entry(diagnostic.category()).or_insert(0) += 1;
+            let file = crate::validation::PrintableError::from(diagnostic).file_path;
+            if !files.contains(&file) {
+                files.push(file);
+            }
+        }
+        RunStatus {
+            outcome: if errors > 0 { Outcome::Failed } else { Outcome::Passed },
+            exit_code: i32::from(errors > 0),
+            errors,
+            warnings,
+            by_code,
+            by_category,
+            files_with_diagnostics: files.len(),
+            message: None,
+        }
+    }
+
+    /// Status of a run that stopped before (or while) validating.
+    pub fn aborted(exit_code: i32, message: impl Into<String>) -> Self {
+        RunStatus {
+            outcome: Outcome::Error,
+            exit_code,
+            errors: 0,
+            warnings: 0,
+            by_code: BTreeMap::new(),
+            by_category: BTreeMap::new(),
+            files_with_diagnostics: 0,
+            message: Some(message.into()),
+        }
+    }
+}
+
+/// Writes the status as pretty JSON.
+pub fn write_status(path: &Path, status: &RunStatus) -> std::io::Result<()> {
+    let rendered = serde_json::to_string_pretty(status)?;
+    crate::paths::check_writable(path)?;
+    std::fs::write(path, rendered + "\n")
+}
@@ -0,0 +1,172 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use serde::Serialize;
+
+use crate::error::AppError;
+use crate::validation::{self, PrintableError, ValidationOptions};
+
+/// End-of-run statistics for `check --summary`, printed after the human report and added to
+/// json/sarif reports as `summary`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunSummary {
+    /// Inputs that were read and validated
+    pub files_scanned: usize,
+    /// Inputs of a type `check` does not read
+    pub files_skipped: usize,
+    /// Scanned files without failing diagnostics (warnings allowed)
+    pub files_passed: usize,
+    pub files_failed: usize,
+    /// Failing diagnostics
+    pub errors: usize,
+    pub warnings: usize,
+    /// Failing diagnostics keyed by rule id
+    pub errors_by_rule: BTreeMap<String, usize>,
+    pub elapsed_ms: u64,
+    /// Counts per schema or preset rule, when more than one ran
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub gates: Vec<GateSummary>,
+}
+
+/// How the files one schema (or preset or rules pack rule) validated fared.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GateSummary {
+    pub gate: String,
+    pub files_checked: usize,
+    /// Files with failing diagnostics
+    pub files_failed: usize,
+    pub errors: usize,
+    pub warnings: usize,
+}
+
+// The gate of each file validated since `collect_gates`, or None while not collecting; `None`
+// gates are the run's own schema
+static GATES: Mutex<Option<BTreeMap<PathBuf, Option<String>>>> = Mutex::new(None);
+
+/// Starts recording which gate validates each file, for `gate_breakdown`.
+pub fn collect_gates() {
+    *GATES.lock().unwrap_or_else(|e| e.into_inner()) = Some(BTreeMap::new());
+}
+
+/// Called by validation, presets and rules packs as they pick the schema for `file`: `gate`
+/// names the preset rule or bound schema, `None` the run's own schema, which does not replace
+/// a gate recorded earlier. Does nothing unless collecting.
+pub fn record_gate(file: &Path, gate: Option<String>) {
+    if let Some(gates) = GATES.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+        match gate {
+            Some(gate) => {
+                gates.insert(file.to_path_buf(), Some(gate));
+            }
+            None => {
+                gates.entry(file.to_path_buf()).or_insert(None);
+            }
+        }
+    }
+}
+
+/// The gates recorded since `collect_gates`, ending the recording.
+pub fn take_gates() -> BTreeMap<PathBuf, Option<String>> {
+    GATES.lock().unwrap_or_else(|e| e.into_inner()).take().unwrap_or_default()
+}
+
+/// Files checked and failed per gate, in the order gates are named, with `default_gate`
+/// naming the run's own schema. Empty unless files went through more than one gate.
+pub fn gate_breakdown(gates: &BTreeMap<PathBuf, Option<String>>, default_gate: &str, diagnostics: &[AppError]) -> Vec<GateSummary> {
+    let mut by_gate: BTreeMap<&str, GateSummary> = BTreeMap::new();
+    let mut gate_of = BTreeMap::new();
+    for (file, gate) in gates {
+        let gate = gate.as_deref().unwrap_or(default_gate);
+        let entry = by_gate.entry(gate).or_insert_with(|| GateSummary {
+            gate: gate.to_string(),
+            files_checked: 0,
+            files_failed: 0,
+            errors: 0,
+            warnings: 0,
+        });
+        entry.files_checked += 1;
+        gate_of.insert(file.to_string_lossy().into_owned(), gate);
+    }
+    if by_gate.len() < 2 {
+        return Vec::new();
+    }
+    let mut failed_files = BTreeSet::new();
+    for diagnostic in diagnostics {
+        let file_path = PrintableError::from(diagnostic).file_path;
+        // Diagnostics about schemas and templates belong to no gate
+        let Some(gate) = gate_of.get(&file_path) else { continue };
+        let entry = by_gate.get_mut(gate).expect("every recorded gate has an entry");
+        if diagnostic.is_warning() {
+            entry.warnings += 1;
+        } else {
+            entry.errors += 1;
+            if failed_files.insert(file_path) {
+                entry.files_failed += 1;
+            }
+        }
+    }
+    by_gate.into_values().collect()
+}
+
+/// The per-gate table of the human report.
+pub fn render_gates(gates: &[GateSummary]) -> String {
+    let mut out = format!("\n--- Results by Schema ---\n  {:<40} {:>6} {:>6} {:>6} {:>8}\n", "Schema", "Files", "Failed", "Errors", "Warnings");
+    for gate in gates {
+        out.push_str(&format!(
+            "  {:<40} {:>6} {:>6} {:>6} {:>8}\n",
+            gate.gate, gate.files_checked, gate.files_failed, gate.errors, gate.warnings
+        ));
+    }
+    out
+}
+
+impl RunSummary {
+    /// Summarizes a run over `inputs` that ended with `diagnostics`.
+    pub fn new(inputs: &[PathBuf], options: &ValidationOptions, diagnostics: &[AppError], elapsed: Duration) -> Self {
+        let (skipped, scanned): (Vec<_>, Vec<_>) =
+            inputs.iter().partition(|input| validation::skip_reason(input, options).is_some());
+        let mut failed_files = BTreeSet::new();
+        let mut errors_by_rule = BTreeMap::new();
+        let mut warnings = 0;
+        for diagnostic in diagnostics {
+            if diagnostic.is_warning() {
+                warnings += 1;
+                continue;
+            }
+            let printable = PrintableError::from(diagnostic);
+            *errors_by_rule.entry(printable.rule_id).or_insert(0) += 1;
+            failed_files.insert(printable.file_path);
+        }
+        // Only inputs count; a failing schema or template is not a scanned file
+        let files_failed = scanned.iter().filter(|input| failed_files.contains(&*input.to_string_lossy())).count();
+        RunSummary {
+            files_scanned: scanned.len(),
+            files_skipped: skipped.len(),
+            files_passed: scanned.len() - files_failed,
+            files_failed,
+            errors: diagnostics.len() - warnings,
+            warnings,
+            errors_by_rule,
+            elapsed_ms: u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX),
+            gates: Vec::new(),
+        }
+    }
+
+    /// The summary section of the human report.
+    pub fn render_human(&self) -> String {
+        let mut out = format!(
+            "\n--- Run Summary ---\nFiles: {} scanned, {} skipped, {} passed, {} failed\nDiagnostics: {} error(s), {} warning(s)\n",
+            self.files_scanned, self.files_skipped, self.files_passed, self.files_failed, self.errors, self.warnings
+        );
+        for (rule, count) in &self.errors_by_rule {
+            out.push_str(&format!("  {:<40} {}\n", rule, count));
+        }
+        out.push_str(&format!("Elapsed: {:.2}s\n", self.elapsed_ms as f64 / 1000.0));
+        if !self.gates.is_empty() {
+            out.push_str(&render_gates(&self.gates));
+        }
+        out
+    }
+}
@@ -0,0 +1,245 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use globset::Glob;
+use miette::Diagnostic;
+use serde::Deserialize;
+
+use crate::error::AppError;
+use crate::renames;
+use crate::validation::PrintableError;
+
+/// Marker that starts an inline suppression comment, e.g.
+/// `# toml-and-jerry: ignore[app::schema::validation_error] reason="legacy" expires=2025-06-01`.
+pub const INLINE_MARKER: &str = "toml-and-jerry: ignore";
+
+/// One suppression, from an inline comment or a suppressions file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct Suppression {
+    /// Glob for the files it covers (suppressions files only)
+    #[serde(default)]
+    pub path: Option<String>,
+    /// Diagnostic codes (or semantic check names) it covers; empty covers every code
+    #[serde(default, alias = "code")]
+    pub codes: Vec<String>,
+    /// Only diagnostics at this JSON pointer
+    #[serde(default)]
+    pub instance_path: Option<String>,
+    #[serde(default)]
+    pub reason: Option<String>,
+    /// `YYYY-MM-DD`; from this day on the suppression no longer applies
+    #[serde(default)]
+    pub expires: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct SuppressionsFile {
+    #[serde(default)]
+    suppress: Vec<Suppression>,
+}
+
+impl Suppression {
+    /// Whether the suppression has expired on `today` (`YYYY-MM-DD`).
+    pub fn is_expired(&self, today: &str) -> bool {
+        self.expires.as_deref().is_some_and(|expires| expires <= today)
+    }
+
+    fn covers(&self, code: &str, check: Option<&str>, instance_path: Option<&str>) -> bool {
+        let code_matches = self.codes.is_empty() || self.codes.iter().any(|c| c == code || Some(c.as_str()) == check);
+        let path_matches = match (&self.instance_path, instance_path) {
+            (None, _) => true,
+            (Some(wanted), Some(actual)) => wanted == actual,
+            (Some(_), None) => false,
+        };
+        code_matches && path_matches
+    }
+
+    fn describe(&self) -> String {
+        match &self.reason {
+            Some(reason) => format!("suppression ({})", reason),
+            None => "suppression".to_string(),
+        }
+    }
+}
+
+/// Today's UTC date as `YYYY-MM-DD`, for comparing against `expires`.
+pub fn today() -> String {
+    crate::provenance::rfc3339(std::time::SystemTime::now())[..10].to_string()
+}
+
+fn is_iso_date(value: &str) -> bool {
+    let bytes = value.as_bytes();
+    bytes.len() == 10
+        && bytes[4] == b'-'
+        && bytes[7] == b'-'
+        && bytes.iter().enumerate().all(|(i, b)| i == 4 || i == 7 || b.is_ascii_digit())
+}
+
+/// Loads a suppressions file (`[[suppress]]` tables).
+pub fn load_suppressions(path: &Path) -> Result<Vec<Suppression>, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("Cannot read suppressions file {}: {}", path.display(), e))?;
+    let file: SuppressionsFile = toml::from_str(&content).map_err(|e| format!("Invalid suppressions file {}: {}", path.display(), e))?;
+    for suppression in &file.suppress {
+        if let Some(expires) = suppression.expires.as_deref().filter(|e| !is_iso_date(e)) {
+            return Err(format!("Invalid suppressions file {}: expires {:?} is not a YYYY-MM-DD date", path.display(), expires));
+        }
+    }
+    Ok(file.suppress)
+}
+
+// Value of `key=value` or `key="quoted value"` in an inline comment
+fn comment_value(text: &str, key: &str) -> Option<String> {
+    let start = text.find(&format!("{}=", key))? + key.len() + 1;
+    let rest = &text[start..];
+    match rest.strip_prefix('"') {
+        Some(quoted) => quoted.split('"').next().map(str::to_string),
+        None => rest.split_whitespace().next().map(str::to_string),
+    }
+}
+
+/// Parses an inline suppression from one line, if it has the marker.
+pub fn parse_inline(line: &str) -> Option<Suppression> {
+    let after = &line[line.find(INLINE_MARKER)? + INLINE_MARKER.len()..];
+    // `ignore-file` and friends are not this marker
+    if after.starts_with(|c: char| c.is_alphanumeric() || c == '-') {
+        return None;
+    }
+    let codes = match after.strip_prefix('[') {
+        Some(list) => list
+            .split(']')
+            .next()
+            .unwrap_or_default()
+            .split(',')
+            .map(|c| c.trim().to_string())
+            .filter(|c| !c.is_empty())
+            .collect(),
+        None => Vec::new(),
+    };
+    Some(Suppression {
+        codes,
+        reason: comment_value(after, "reason"),
+        expires: comment_value(after, "expires").filter(|e| is_iso_date(e)),
+        ..Default::default()
+    })
+}
+
+// File, code, check name, instance path and 1-based line of a diagnostic
+struct Located {
+    file: String,
+    code: String,
+    check: Option<String>,
+    instance_path: Option<String>,
+    line: Option<usize>,
+}
+
+fn locate(error: &AppError) -> Located {
+    // Suppressions name the original code, not a severity mapping's wrapper
+    let inner = error.unwrapped();
+    let printable = PrintableError::from(inner);
+    let check = match inner {
+        AppError::SemanticError { check, .. } => Some(check.clone()),
+        _ => None,
+    };
+    let line = inner.labels().and_then(|mut labels| labels.next()).and_then(|label| {
+        let content = fs::read_to_string(&printable.file_path).ok()?;
+        let mut offset = label.offset().min(content.len());
+        // A label over the whole file says nothing about the line; look for the offending key
+        if offset == 0 && label.len() >= content.len() {
+            let key = printable.json_path.as_deref().and_then(|p| p.rsplit('/').next()).filter(|k| !k.is_empty());
+            if let Some(range) = key.and_then(|k| renames::find_key_span(&content, k)) {
+                offset = range.start;
+            }
+        }
+        Some(content.as_bytes()[..offset].iter().filter(|b| **b == b'\n').count() + 1)
+    });
+    Located {
+        file: printable.file_path,
+        code: printable.rule_id,
+        check,
+        instance_path: printable.json_path,
+        line,
+    }
+}
+
+fn expired_warning(file: &str, location: String, suppression: &Suppression) -> AppError {
+    AppError::ExpiredSuppression {
+        path: PathBuf::from(file),
+        location,
+        expires: suppression.expires.clone().unwrap_or_default(),
+        reason: suppression.reason.clone().unwrap_or_else(|| "no reason given".to_string()),
+    }
+}
+
+// What the suppressions say about one diagnostic
+enum Verdict {
+    Suppressed,
+    Kept,
+    /// Covered by an expired inline suppression, which earns a warning of its own
+    Expired(AppError),
+}
+
+fn verdict(error: &AppError, configured: &[Suppression], today: &str) -> Verdict {
+    let located = locate(error);
+    let covers = |s: &Suppression| s.covers(&located.code, located.check.as_deref(), located.instance_path.as_deref());
+
+    let from_config = configured.iter().any(|s| {
+        !s.is_expired(today)
+            && covers(s)
+            && s.path.as_deref().is_none_or(|glob| {
+                Glob::new(glob).map(|g| g.compile_matcher().is_match(&located.file)).unwrap_or(false)
+            })
+    });
+    if from_config {
+        return Verdict::Suppressed;
+    }
+
+    let inline = located.line.and_then(|line| {
+        let content = fs::read_to_string(&located.file).ok()?;
+        let lines: Vec<&str> = content.lines().collect();
+        [line, line.saturating_sub(1)]
+            .into_iter()
+            .filter(|l| *l >= 1)
+            .filter_map(|l| lines.get(l - 1).and_then(|text| parse_inline(text)).map(|s| (l, s)))
+            .find(|(_, s)| covers(s))
+    });
+    match inline {
+        Some((line, suppression)) if suppression.is_expired(today) => {
+            Verdict::Expired(expired_warning(&located.file, format!("line {}: {}", line, suppression.describe()), &suppression))
+        }
+        Some(_) => Verdict::Suppressed,
+        None => Verdict::Kept,
+    }
+}
+
+/// Whether `apply_suppressions` would drop `error`, for callers that report diagnostics one at
+/// a time.
+pub fn is_suppressed(error: &AppError, configured: &[Suppression], today: &str) -> bool {
+    matches!(verdict(error, configured, today), Verdict::Suppressed)
+}
+
+/// Drops diagnostics covered by an unexpired suppression, from `configured` entries or from
+/// an inline comment on the diagnostic's line or the line above. Expired suppressions no
+/// longer apply and add an `ExpiredSuppression` warning instead.
+pub fn apply_suppressions(errors: Vec<AppError>, configured: &[Suppression], suppressions_file: Option<&Path>, today: &str) -> Vec<AppError> {
+    let mut kept = Vec::new();
+    let mut nags = Vec::new();
+    let file_display = suppressions_file.map(|p| p.display().to_string()).unwrap_or_default();
+
+    for (index, suppression) in configured.iter().enumerate() {
+        if suppression.is_expired(today) {
+            nags.push(expired_warning(&file_display, format!("entry {} of {}", index + 1, file_display), suppression));
+        }
+    }
+
+    for error in errors {
+        match verdict(&error, configured, today) {
+            Verdict::Suppressed => {}
+            Verdict::Kept => kept.push(error),
+            Verdict::Expired(nag) => {
+                nags.push(nag);
+                kept.push(error);
+            }
+        }
+    }
+    kept.extend(nags);
+    kept
+}
@@ -0,0 +1,184 @@
+use miette::{Diagnostic, SourceSpan};
+use unicode_width::UnicodeWidthChar;
+
+use crate::error::AppError;
+use crate::validation::{PrintableError, Snippet};
+
+/// Fields a `--template` placeholder can name.
+pub const FIELDS: &[&str] = &["file", "line", "column", "code", "type", "message", "path", "severity", "category"];
+
+// Escapes a placeholder's value for the target format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Filter {
+    None,
+    /// A quoted JSON string, for hand-built JSON payloads such as Slack messages
+    Json,
+    /// Quoted (with `""` for `"`) when it holds a comma, quote or newline
+    Csv,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Text(String),
+    Field { name: String, filter: Filter },
+}
+
+/// A parsed `--template`: literal text with `{{field}}` or `{{field|json}}` / `{{field|csv}}`
+/// placeholders. `\n` and `\t` in the text stand for a newline and a tab.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("\\n", "\n").replace("\\t", "\t")
+}
+
+impl Template {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut parts = Vec::new();
+        let mut rest = text;
+        while let Some(start) = rest.find("{{") {
+            if start > 0 {
+                parts.push(Part::Text(unescape(&rest[..start])));
+            }
+            let end = rest[start..].find("}}").ok_or_else(|| format!("Unclosed placeholder in template {:?}", text))?;
+            let placeholder = rest[start + 2..start + end].trim();
+            let (name, filter) = match placeholder.split_once('|') {
+                Some((name, filter)) => (name.trim(), filter.trim()),
+                None => (placeholder, ""),
+            };
+            if !FIELDS.contains(&name) {
+                return Err(format!("Unknown template field {:?}; expected one of {}", name, FIELDS.join(", ")));
+            }
+            let filter = match filter {
+                "" => Filter::None,
+                "json" => Filter::Json,
+                "csv" => Filter::Csv,
+                other => return Err(format!("Unknown template filter {:?}; expected json or csv", other)),
+            };
+            parts.push(Part::Field { name: name.to_string(), filter });
+            rest = &rest[start + end + 2..];
+        }
+        if !rest.is_empty() {
+            parts.push(Part::Text(unescape(rest)));
+        }
+        Ok(Template { parts })
+    }
+
+    /// Renders one diagnostic. Missing values (e.g. `line` of an unreadable file) are empty.
+    pub fn render(&self, error: &AppError) -> String {
+        let printable = PrintableError::from(error);
+        let position = display_line_column(error);
+        let mut out = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Text(text) => out.push_str(text),
+                Part::Field { name, filter } => {
+                    let value = match name.as_str() {
+                        "file" => printable.file_path.clone(),
+                        "line" => position.map(|(line, _)| line.to_string()).unwrap_or_default(),
+                        "column" => position.map(|(_, column)| column.to_string()).unwrap_or_default(),
+                        "code" => printable.rule_id.clone(),
+                        "type" => printable.error_type.clone(),
+                        "message" => printable.message.clone(),
+                        "path" => printable.json_path.clone().unwrap_or_default(),
+                        "severity" => error.level().as_str().to_string(),
+                        "category" => error.category().as_str().to_string(),
+                        _ => String::new(),
+                    };
+                    out.push_str(&apply_filter(&value, *filter));
+                }
+            }
+        }
+        out
+    }
+
+    /// One rendered line per diagnostic.
+    pub fn render_all(&self, errors: &[AppError]) -> String {
+        errors.iter().map(|e| self.render(e)).collect::<Vec<_>>().join("\n")
+    }
+}
+
+fn apply_filter(value: &str, filter: Filter) -> String {
+    match filter {
+        Filter::None => value.to_string(),
+        Filter::Json => serde_json::Value::String(value.to_string()).to_string(),
+        Filter::Csv if value.contains([',', '"', '\n']) => format!("\"{}\"", value.replace('"', "\"\"")),
+        Filter::Csv => value.to_string(),
+    }
+}
+
+/// 1-based line and column of a diagnostic's first label, from its attached source.
+pub fn line_column(error: &AppError) -> Option<(usize, usize)> {
+    let label = error.labels()?.next()?;
+    let contents = error.source_code()?.read_span(label.inner(), 0, 0).ok()?;
+    Some((contents.line() + 1, contents.column() + 1))
+}
+
+/// Lines of source a snippet holds at most; longer labels are cut off after them.
+pub const SNIPPET_MAX_LINES: usize = 5;
+
+/// The source lines under a diagnostic's first label, with the columns it covers. A label
+/// over the whole file points at nothing in particular and has no snippet.
+pub fn snippet(error: &AppError) -> Option<Snippet> {
+    let label = error.labels()?.next()?;
+    let source = error.source_code()?;
+    let contents = source.read_span(label.inner(), 0, 0).ok()?;
+    // The label's bytes only; widen to the start of its first line and past its last one
+    let line_start = label.offset() - contents.column();
+    let label_end = label.offset() + label.len() - line_start;
+    let widened = source.read_span(&SourceSpan::new(line_start.into(), label_end), 0, 1).ok()?;
+    let data = widened.data();
+    if label.offset() == 0 && label.len() >= data.len() {
+        return None;
+    }
+    let last_line_from = if label.is_empty() { label_end } else { label_end - 1 };
+    let lines_end = data[last_line_from.min(data.len())..]
+        .iter()
+        .position(|&b| b == b'\n')
+        .map_or(data.len(), |i| last_line_from + i);
+    let text = String::from_utf8_lossy(&data[..lines_end]);
+    let lines: Vec<&str> = text.lines().take(SNIPPET_MAX_LINES).collect();
+    let last = lines.last()?;
+    let caret_start = contents.column() + 1;
+    let end = SourceSpan::new((label.offset() + label.len()).into(), 0);
+    let caret_end = match source.read_span(&end, 0, 0) {
+        Ok(end) if end.line() == contents.line() + lines.len() - 1 => end.column() + 1,
+        // The label runs on past the last line kept, or ends with it
+        _ => last.len() + 1,
+    };
+    Some(Snippet {
+        start_line: contents.line() + 1,
+        text: lines.join("\n"),
+        caret_start,
+        // An empty label still marks one column
+        caret_end: if lines.len() == 1 { caret_end.max(caret_start + 1) } else { caret_end },
+    })
+}
+
+/// Display width of `text`: wide CJK characters and most emoji take two cells, and a tab
+/// moves to the next multiple of 8, as Vim counts virtual columns.
+pub fn display_width(text: &str) -> usize {
+    text.chars().fold(0, |width, c| match c {
+        '\t' => (width / 8 + 1) * 8,
+        c => width + UnicodeWidthChar::width(c).unwrap_or(0),
+    })
+}
+
+/// Like `line_column`, but the column counts the display cells before the label rather than
+/// bytes, so it matches what editors and terminals point at on lines with multi-byte or
+/// wide characters.
+pub fn display_line_column(error: &AppError) -> Option<(usize, usize)> {
+    let label = error.labels()?.next()?;
+    let source = error.source_code()?;
+    let contents = source.read_span(label.inner(), 0, 0).ok()?;
+    let bytes_before = contents.column();
+    if bytes_before == 0 {
+        return Some((contents.line() + 1, 1));
+    }
+    // Read from the start of the line; the contents run on to its end
+    let line = source.read_span(&SourceSpan::new((label.offset() - bytes_before).into(), bytes_before), 0, 0).ok()?;
+    let before = &line.data()[..bytes_before.min(line.data().len())];
+    Some((contents.line() + 1, display_width(&String::from_utf8_lossy(before)) + 1))
+}
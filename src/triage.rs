@@ -0,0 +1,218 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+
+use crate::capabilities;
+use crate::error::AppError;
+use crate::presets::{self, SchemaSource};
+use crate::project_config::PROJECT_CONFIG_FILE;
+use crate::ratchet::RatchetState;
+use crate::validation::{validate_inputs, validate_inputs_with_options, PrintableError, ValidationOptions};
+
+/// File name of the baseline `triage --write` emits, for `check --ratchet`.
+pub const BASELINE_FILE: &str = "toml-and-jerry-baseline.json";
+
+/// How many files of one input format the scan found.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileTypeCount {
+    pub format: &'static str,
+    pub files: usize,
+}
+
+/// A preset rule that would apply to some of the files, and how they fare.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaMatch {
+    pub preset: &'static str,
+    pub rule: &'static str,
+    pub schema: String,
+    pub files: usize,
+    pub passed: usize,
+    pub failed: usize,
+    /// Why the files could not be checked (e.g. the catalog schema could not be fetched)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Result of `triage`: what is there, what would apply, and a starting configuration.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TriageReport {
+    pub root: String,
+    pub file_types: Vec<FileTypeCount>,
+    pub schemas: Vec<SchemaMatch>,
+    /// Config files no preset covers; only their syntax was checked
+    pub unmatched_files: usize,
+    /// Unmatched files that do not parse
+    pub syntax_failures: usize,
+    /// Contents for `.toml-and-jerry.toml`
+    pub suggested_config: String,
+    /// Today's error counts, for `check --ratchet`
+    pub baseline: RatchetState,
+}
+
+fn schema_display(source: SchemaSource) -> String {
+    match source {
+        SchemaSource::Catalog(url) => url.to_string(),
+    }
+}
+
+// Files with at least one failing diagnostic
+fn failing_files(diagnostics: &[AppError]) -> BTreeSet<String> {
+    diagnostics.iter().filter(|d| !d.is_warning()).map(|d| PrintableError::from(d).file_path).collect()
+}
+
+fn preset_options(preset: &presets::Preset) -> ValidationOptions {
+    let mut options = ValidationOptions::default();
+    if let Some(profile) = preset.tag_profile {
+        options.yaml_tags = options.yaml_tags.with_profile(profile).expect("preset tag profiles are built in");
+    }
+    options
+}
+
+/// Scans `root` (honouring .gitignore, including hidden directories such as .github).
+pub fn triage(root: &Path) -> TriageReport {
+    let files: Vec<PathBuf> = ignore::WalkBuilder::new(root)
+        .hidden(false)
+        .build()
+        .flatten()
+        .filter(|e| e.file_type().is_some_and(|t| t.is_file()))
+        .map(|e| e.into_path())
+        .filter(|p| capabilities::input_format(p).is_some())
+        .collect();
+
+    let mut by_format: BTreeMap<&'static str, usize> = BTreeMap::new();
+    for file in &files {
+        *by_format.entry(capabilities::input_format(file).unwrap_or_default()).or_insert(0) += 1;
+    }
+
+    let mut diagnostics = Vec::new();
+    let mut schemas = Vec::new();
+    let mut covered: BTreeSet<&PathBuf> = BTreeSet::new();
+    let mut commands = Vec::new();
+    for preset in presets::preset_names().filter_map(presets::find_preset) {
+        let options = preset_options(preset);
+        let mut preset_used = false;
+        for rule in preset.rules {
+            let matched: Vec<PathBuf> = files.iter().filter(|f| !covered.contains(f) && (rule.matches)(f)).cloned().collect();
+            if matched.is_empty() {
+                continue;
+            }
+            preset_used = true;
+            covered.extend(files.iter().filter(|f| matched.contains(f)));
+            let mut schema_match = SchemaMatch {
+                preset: preset.name,
+                rule: rule.name,
+                schema: schema_display(rule.schema),
+                files: matched.len(),
+                passed: 0,
+                failed: 0,
+                error: None,
+            };
+            let result = presets::compile_schema_source(rule.schema)
+                .and_then(|validator| validate_inputs_with_options(matched.clone(), &validator, &options));
+            match result {
+                Ok(mut found) => {
+                    if let Some(check) = rule.check {
+                        presets::run_semantic_checks(&[check], &matched, &options, &mut found);
+                    }
+                    let failing = failing_files(&found);
+                    schema_match.failed = matched.iter().filter(|f| failing.contains(&*f.to_string_lossy())).count();
+                    schema_match.passed = matched.len() - schema_match.failed;
+                    diagnostics.extend(found);
+                }
+                Err(e) => schema_match.error = Some(e.to_string()),
+            }
+            schemas.push(schema_match);
+        }
+        if preset_used {
+            commands.push(format!("toml-and-jerry check --preset {} {}", preset.name, root.display()));
+        }
+    }
+
+    // Everything else at least has to parse
+    let unmatched: Vec<PathBuf> = files
+        .iter()
+        .filter(|f| !covered.contains(f) && matches!(capabilities::input_format(f), Some("json" | "yaml" | "toml" | "hcl" | "hcl-json")))
+        .cloned()
+        .collect();
+    let accept_all = jsonschema::Validator::new(&serde_json::json!(true)).expect("`true` is a valid schema");
+    let syntax = validate_inputs(unmatched.clone(), &accept_all).unwrap_or_default();
+    let syntax_failures = failing_files(&syntax).len();
+    diagnostics.extend(syntax);
+
+    let baseline = RatchetState::from_diagnostics(&diagnostics);
+    TriageReport {
+        root: root.display().to_string(),
+        file_types: by_format.into_iter().map(|(format, files)| FileTypeCount { format, files }).collect(),
+        schemas,
+        unmatched_files: unmatched.len(),
+        syntax_failures,
+        suggested_config: suggested_config(root, &commands, &baseline),
+        baseline,
+    }
+}
+
+// `.toml-and-jerry.toml` with the checks to run and budgets at today's error counts
+fn suggested_config(root: &Path, commands: &[String], baseline: &RatchetState) -> String {
+    let mut out = format!("# Suggested by `toml-and-jerry triage {}`\n", root.display());
+    if !commands.is_empty() {
+        out.push_str("#\n# Checks that apply to this repository:\n");
+        for command in commands {
+            out.push_str(&format!("#   {}\n", command));
+        }
+    }
+    if !baseline.counts.is_empty() {
+        out.push_str("#\n# Budgets at today's error counts; lower them as directories are cleaned up.\n");
+        for (area, codes) in &baseline.counts {
+            out.push_str(&format!("\n[budget.{:?}]\nmax_errors = {}\n", area, codes.values().sum::<usize>()));
+        }
+    }
+    out
+}
+
+/// Plain-text rendering for `triage --format human`.
+pub fn render_human(report: &TriageReport) -> String {
+    let mut out = format!("Triage of {}\n\nFile types:\n", report.root);
+    for count in &report.file_types {
+        out.push_str(&format!("  {:<14} {}\n", count.format, count.files));
+    }
+    out.push_str("\nSchemas that would apply:\n");
+    if report.schemas.is_empty() {
+        out.push_str("  none of the presets recognise these files\n");
+    }
+    for schema in &report.schemas {
+        let outcome = match &schema.error {
+            Some(error) => format!("not checked: {}", error),
+            None => format!("{} pass, {} fail", schema.passed, schema.failed),
+        };
+        out.push_str(&format!(
+            "  {} ({} preset, {}): {} file(s), {}\n",
+            schema.rule, schema.preset, schema.schema, schema.files, outcome
+        ));
+    }
+    out.push_str(&format!(
+        "\nOther config files: {} (syntax only), {} do not parse\n",
+        report.unmatched_files, report.syntax_failures
+    ));
+    out.push_str(&format!("Errors in the baseline: {}\n", report.baseline.total()));
+    out
+}
+
+/// Writes `.toml-and-jerry.toml` and the baseline into `dir`. Existing files are left alone.
+pub fn write_suggestions(report: &TriageReport, dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let baseline = serde_json::to_string_pretty(&report.baseline).map_err(|e| e.to_string())?;
+    let mut written = Vec::new();
+    for (name, content) in [(PROJECT_CONFIG_FILE, report.suggested_config.clone()), (BASELINE_FILE, format!("{}\n", baseline))] {
+        let path = dir.join(name);
+        if path.exists() {
+            return Err(format!("{} already exists; not overwriting it", path.display()));
+        }
+        crate::paths::check_writable(&path)
+            .and_then(|()| std::fs::write(&path, content))
+            .map_err(|e| format!("Cannot write {}: {}", path.display(), e))?;
+        written.push(path);
+    }
+    Ok(written)
+}
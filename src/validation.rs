@@ -1,37 +1,423 @@
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 use std::fs;
 use serde_json::Value as JsonValue;
 use jsonschema::Validator;
 use miette::{Result, SourceSpan, Diagnostic};
 use json_spanned_value::spanned::Value as SpannedJsonValue;
 use toml_edit::{DocumentMut, Item as TomlEditItem, Value as TomlEditValue};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-use crate::error::AppError;
+use crate::coercion;
+use crate::config_merge;
+use crate::containerfile;
+use crate::embedded::{self, EmbeddedFormat};
+use crate::enum_map;
+use crate::error::{AppError, Category, LimitExceeded, RenamedEnumValue, RenamedProperty, SchemaValidationError, Severity, UnrepresentableValue};
+use crate::evaluator::{self, Evaluator};
+use crate::hcl_json;
+use crate::hjson;
+use crate::ini;
+use crate::kdl;
+use crate::limits;
+use crate::ndjson;
+use crate::newlines;
+use crate::parsers::{self, Parser};
+use crate::progress;
+use crate::properties;
+use crate::renames;
+use crate::report_stream;
+use crate::schema;
+use crate::schema_comment;
+use crate::summary;
+use crate::template;
+use crate::versions;
+use crate::violation::Violation;
+use crate::yaml;
+
+/// Knobs for `validate_inputs_with_options`. `Default` matches `validate_inputs`.
+#[derive(Debug, Clone, Default)]
+pub struct ValidationOptions {
+    /// Explicit `cue` executable; PATH lookup when `None`
+    pub cue_binary: Option<PathBuf>,
+    /// Explicit `pkl` executable; PATH lookup when `None`
+    pub pkl_binary: Option<PathBuf>,
+    /// Explicit `nickel` executable; PATH lookup when `None`
+    pub nickel_binary: Option<PathBuf>,
+    /// Treat Dockerfiles/Containerfiles as inputs and validate their metadata
+    pub containerfiles: bool,
+    /// How custom YAML tags (`!Ref`, `!Sub`, ...) are converted before validation
+    pub yaml_tags: yaml::TagPolicy,
+    /// `x-renamed-from` annotations collected from the schema
+    pub rename_rules: Vec<renames::RenameRule>,
+    /// Enum values renamed by `--map-enum`, reported (and fixable) like renamed properties
+    pub enum_mappings: Vec<enum_map::EnumMapping>,
+    /// Strings that hold documents of another format, validated against their own schema
+    pub embedded: Vec<embedded::EmbeddedRule>,
+    /// Size/complexity limits reported as warnings
+    pub limits: limits::Limits,
+    /// Line ending and final newline rules, reported as warnings
+    pub newlines: newlines::NewlineRules,
+    /// Inputs without a `tj-schema:` comment are an error (no fallback schema was given)
+    pub require_schema_comment: bool,
+    /// Extensions read with another format's parser, from the project config's `[parsers]`
+    pub parsers: BTreeMap<String, Parser>,
+    /// Dialect for schemas without `$schema`; the validator's newest draft when `None`
+    pub default_draft: Option<jsonschema::Draft>,
+    /// Schemas keyed by the config version documents declare, from `[schema_versions]`
+    pub schema_versions: BTreeMap<String, PathBuf>,
+    /// INI values read as booleans or numbers rather than strings
+    pub ini_coercion: ini::IniCoercion,
+    /// How `.properties` values are typed and whether dotted keys nest
+    pub properties: properties::PropertiesOptions,
+    /// Schemas bound per file check only the root's types and required keys (`--quick`)
+    pub quick: bool,
+}
+
+// Byte offset of a 1-based line/column position, clamped to the content length
+pub(crate) fn offset_for_line_col(content: &str, line: usize, column: usize) -> usize {
+    let mut offset = 0;
+    for (i, line_content) in content.lines().enumerate() {
+        if i + 1 < line { offset += line_content.len() + 1; } else { break; }
+    }
+    (offset + column.saturating_sub(1)).min(content.len())
+}
+
+// Byte offset of a 1-based line and character column (YAML marks count characters, not bytes)
+pub(crate) fn offset_for_line_char(content: &str, line: usize, column: usize) -> usize {
+    let line_start = offset_for_line_col(content, line, 1);
+    content[line_start..]
+        .char_indices()
+        .nth(column.saturating_sub(1))
+        .map_or(content.len(), |(i, _)| line_start + i)
+}
+
+// Span of the character at byte `offset`, moved back to where that character starts, so a
+// label never splits a multi-byte character
+pub(crate) fn char_span(content: &str, offset: usize) -> SourceSpan {
+    let mut start = offset.min(content.len());
+    while !content.is_char_boundary(start) {
+        start -= 1;
+    }
+    let len = content[start..].chars().next().map_or(0, char::len_utf8);
+    SourceSpan::new(start.into(), len)
+}
+
+/// Span of `reference` as it appears under `key` (e.g. a job id listed in `needs:`),
+/// falling back to its first occurrence anywhere and then to the whole file.
+pub fn reference_span(content: &str, key: &str, reference: &str) -> SourceSpan {
+    let key_offset = content.find(&format!("{}:", key)).unwrap_or(0);
+    let offset = content[key_offset..]
+        .find(reference)
+        .map(|o| o + key_offset)
+        .or_else(|| content.find(reference));
+    match offset {
+        Some(o) if !reference.is_empty() => SourceSpan::new(o.into(), reference.len()),
+        _ => SourceSpan::new(0.into(), content.len()),
+    }
+}
+
+// Warns about properties still using a name the schema marks as `x-renamed-from`
+fn check_renames(
+    options: &ValidationOptions,
+    input_path: &Path,
+    file_content: &str,
+    instance: &JsonValue,
+    collected_errors: &mut Vec<AppError>,
+) {
+    for hit in renames::find_renamed_properties(instance, &options.rename_rules) {
+        let span = renames::find_key_span(file_content, &hit.from)
+            .map(|range| SourceSpan::new(range.start.into(), range.len()))
+            .unwrap_or_else(|| SourceSpan::new(0.into(), file_content.len()));
+        collected_errors.push(AppError::RenamedProperty(Box::new(RenamedProperty {
+            path: input_path.to_path_buf(),
+            from: hit.from,
+            to: hit.to,
+            instance_path: hit.parent_pointer,
+            span,
+            source_code: file_content.to_string(),
+        })));
+    }
+}
+
+// Warns about values still using an enum value renamed by `--map-enum`
+fn check_enum_mappings(
+    options: &ValidationOptions,
+    input_path: &Path,
+    file_content: &str,
+    instance: &JsonValue,
+    collected_errors: &mut Vec<AppError>,
+) {
+    let hits = enum_map::find_old_enum_values(instance, &options.enum_mappings);
+    let spans = enum_map::locate_hits(file_content, &hits);
+    for (hit, span) in hits.into_iter().zip(spans) {
+        let span = span
+            .map(|range| SourceSpan::new(range.start.into(), range.len()))
+            .unwrap_or_else(|| SourceSpan::new(0.into(), file_content.len()));
+        collected_errors.push(AppError::RenamedEnumValue(Box::new(RenamedEnumValue {
+            path: input_path.to_path_buf(),
+            from: hit.from,
+            to: hit.to,
+            instance_path: hit.pointer,
+            span,
+            source_code: file_content.to_string(),
+        })));
+    }
+}
+
+// Warns about files past the size/complexity limits; `instance` is `None` for the
+// file-level `max-lines` check
+fn check_limits(
+    options: &ValidationOptions,
+    input_path: &Path,
+    file_content: &str,
+    instance: Option<&JsonValue>,
+    collected_errors: &mut Vec<AppError>,
+) {
+    // Most runs set no limits; the walk over the document is skipped for them
+    if options.limits.is_empty() {
+        return;
+    }
+    let violations = match instance {
+        Some(instance) => options.limits.check_instance(instance),
+        None => options.limits.check_lines(file_content).into_iter().collect(),
+    };
+    for violation in violations {
+        let span = if violation.rule == "max-lines" {
+            let max = options.limits.max_lines.unwrap_or_default();
+            let start = offset_for_line_col(file_content, max + 1, 1);
+            let len = file_content[start..].find('\n').unwrap_or(file_content.len() - start);
+            SourceSpan::new(start.into(), len.max(1))
+        } else {
+            // The key holding the offending container, or the whole file for the root
+            violation
+                .pointer
+                .rsplit('/')
+                .next()
+                .filter(|key| !key.is_empty())
+                .and_then(|key| renames::find_key_span(file_content, &key.replace("~1", "/").replace("~0", "~")))
+                .map(|range| SourceSpan::new(range.start.into(), range.len()))
+                .unwrap_or_else(|| SourceSpan::new(0.into(), file_content.len()))
+        };
+        collected_errors.push(AppError::LimitExceeded(Box::new(LimitExceeded {
+            path: input_path.to_path_buf(),
+            rule: violation.rule.to_string(),
+            message: violation.message,
+            instance_path: violation.pointer,
+            span,
+            source_code: file_content.to_string(),
+        })));
+    }
+}
+
+// Validates the documents embedded in string values (`--embedded` rules) against their
+// sub-schemas; spans are mapped through the string literal into the outer file
+fn check_embedded(
+    options: &ValidationOptions,
+    schemas: &mut HashMap<PathBuf, Validator>,
+    input_path: &Path,
+    file_content: &str,
+    instance: &JsonValue,
+    collected_errors: &mut Vec<AppError>,
+) {
+    for rule in &options.embedded {
+        if !schemas.contains_key(&rule.schema) {
+            match schema::load_and_compile_schema(&rule.schema) {
+                Ok(validator) => {
+                    schemas.insert(rule.schema.clone(), validator);
+                }
+                Err(e) => {
+                    collected_errors.push(e);
+                    continue;
+                }
+            }
+        }
+        let validator = &schemas[&rule.schema];
+        for (pointer, text) in embedded::matching_strings(instance, &rule.pointer) {
+            let key = pointer.rsplit('/').next().unwrap_or_default().to_string();
+            // Falls back to the key holding the string when the literal cannot be mapped
+            let outer_span = |inner_span: SourceSpan| {
+                embedded::map_span(file_content, text, inner_span).unwrap_or_else(|| reference_span(file_content, "", &key))
+            };
+            let label = format!("Embedded {} at `{}`", rule.format.name(), pointer);
+            let parsed: Result<JsonValue, AppError> = match rule.format {
+                EmbeddedFormat::Json => serde_json::from_str(text).map_err(|e| {
+                    let offset = offset_for_line_col(text, e.line(), e.column());
+                    AppError::JsonParseError {
+                        path: input_path.to_path_buf(),
+                        message: format!("{}: {}", label, e),
+                        span: outer_span(char_span(text, offset)),
+                        source_code: file_content.to_string(),
+                        source: e,
+                    }
+                }),
+                EmbeddedFormat::Yaml => yaml::parse_documents(text)
+                    .map_err(|e| (e.to_string(), e.location().map_or(0, |l| l.index())))
+                    .and_then(|documents| {
+                        let document = documents.into_iter().next().unwrap_or(serde_yaml::Value::Null);
+                        yaml::to_json_with_policy(document, &options.yaml_tags)
+                            .map_err(|tag| (format!("Unknown YAML tag {}", tag), text.find(&tag).unwrap_or(0)))
+                    })
+                    .map_err(|(message, offset)| AppError::YamlParseError {
+                        path: input_path.to_path_buf(),
+                        message: format!("{}: {}", label, message),
+                        span: outer_span(char_span(text, offset)),
+                        source_code: file_content.to_string(),
+                    }),
+                EmbeddedFormat::Toml => match text.parse::<DocumentMut>() {
+                    Ok(_) => Ok(toml::from_str::<toml::Value>(text)
+                        .ok()
+                        .and_then(|v| serde_json::to_value(v).ok())
+                        .unwrap_or(JsonValue::Null)),
+                    Err(e) => Err(AppError::TomlParseError {
+                        path: input_path.to_path_buf(),
+                        message: format!("{}: {}", label, e.message()),
+                        span: outer_span(convert_toml_edit_span(e.span()).unwrap_or_else(|| SourceSpan::new(0.into(), 1))),
+                        source_code: file_content.to_string(),
+                    }),
+                },
+            };
+            let value = match parsed {
+                Ok(value) => value,
+                Err(e) => {
+                    collected_errors.push(e);
+                    continue;
+                }
+            };
+            if let Err(validation_error) = validator.validate(&value) {
+                let inner_path = validation_error.instance_path.to_string();
+                // The offending key inside the embedded text, or all of it for a root error
+                let inner_span = match inner_path.rsplit('/').next().filter(|k| !k.is_empty()) {
+                    Some(inner_key) => reference_span(text, "", inner_key),
+                    None => SourceSpan::new(0.into(), text.len()),
+                };
+                let kind_str = format!("{:?}", validation_error.kind);
+                collected_errors.push(AppError::SchemaValidationError(Box::new(SchemaValidationError {
+                    path: input_path.to_path_buf(),
+                    message: format!("{} does not match {}", label, rule.schema.display()),
+                    source_code: file_content.to_string(),
+                    error_span: outer_span(inner_span),
+                    label_message: format!("Field `{}`: {}", inner_path, kind_str),
+                    instance_path: format!("{}{}", pointer, inner_path),
+                    kind: kind_str,
+                    schema_path: validation_error.schema_path.to_string(),
+                    violation: Violation::from_error(&validation_error),
+                })));
+            }
+        }
+    }
+}
+
+// `error`, located in a part of `file_content` that starts at `start` (such as an NDJSON
+// record), with its span and source moved into the whole file
+fn moved_into_file(mut error: AppError, file_content: &str, start: usize) -> AppError {
+    let (span, source_code) = match &mut error {
+        AppError::JsonParseError { span, source_code, .. }
+        | AppError::YamlParseError { span, source_code, .. }
+        | AppError::TomlParseError { span, source_code, .. } => (span, source_code),
+        AppError::SchemaValidationError(boxed) => (&mut boxed.error_span, &mut boxed.source_code),
+        AppError::CoercibleType(boxed) => (&mut boxed.span, &mut boxed.source_code),
+        AppError::RenamedProperty(boxed) => (&mut boxed.span, &mut boxed.source_code),
+        AppError::RenamedEnumValue(boxed) => (&mut boxed.span, &mut boxed.source_code),
+        _ => return error,
+    };
+    *span = SourceSpan::new((start + span.offset()).into(), span.len());
+    *source_code = file_content.to_string();
+    error
+}
+
+// Schema validation error for formats without span information: labels the whole file
+fn whole_file_validation_error(
+    input_path: &Path,
+    file_content: &str,
+    validation_error: &jsonschema::ValidationError,
+) -> AppError {
+    let error_json_path = validation_error.instance_path.to_string();
+    let kind_str = format!("{:?}", validation_error.kind);
+    AppError::SchemaValidationError(Box::new(SchemaValidationError {
+        path: input_path.to_path_buf(),
+        message: "Schema validation failed".to_string(),
+        source_code: file_content.to_string(),
+        error_span: SourceSpan::new(0.into(), file_content.len()),
+        label_message: format!("Field `{}`: {}", error_json_path, kind_str),
+        instance_path: error_json_path,
+        kind: kind_str,
+        schema_path: validation_error.schema_path.to_string(),
+        violation: Violation::from_error(validation_error),
+    }))
+}
+
+// Validates the LABEL/ENV/ARG/... metadata of a Containerfile, pointing at the instruction
+fn validate_containerfile(
+    input_path: &Path,
+    file_content: &str,
+    compiled_schema: &Validator,
+    collected_errors: &mut Vec<AppError>,
+) {
+    let metadata = containerfile::parse_containerfile(file_content);
+    if let Err(validation_error) = compiled_schema.validate(&metadata.value) {
+        let mut error = whole_file_validation_error(input_path, file_content, &validation_error);
+        if let AppError::SchemaValidationError(error) = &mut error {
+            if let Some(range) = metadata.span_for_pointer(&error.instance_path) {
+                error.error_span = convert_toml_edit_span(Some(range)).unwrap_or(error.error_span);
+            }
+        }
+        collected_errors.push(error);
+    }
+}
+
+// Renders a file with an external evaluator and validates the JSON it produces
+fn validate_evaluated_file(
+    evaluator: &Evaluator,
+    binary: Option<&std::path::Path>,
+    input_path: &Path,
+    file_content: &str,
+    compiled_schema: &Validator,
+    collected_errors: &mut Vec<AppError>,
+) {
+    match evaluator.evaluate(binary, input_path) {
+        Ok(rendered) => {
+            if let Err(validation_error) = compiled_schema.validate(&rendered) {
+                collected_errors.push(whole_file_validation_error(input_path, file_content, &validation_error));
+            }
+        }
+        Err(failure) => {
+            let span = match failure.position {
+                Some((line, column)) => char_span(file_content, offset_for_line_col(file_content, line, column)),
+                None => SourceSpan::new(0.into(), file_content.len()),
+            };
+            collected_errors.push(AppError::EvaluationError {
+                path: input_path.to_path_buf(),
+                tool: evaluator.tool.to_string(),
+                message: failure.message,
+                span,
+                source_code: file_content.to_string(),
+            });
+        }
+    }
+}
 
 // Helper function to convert json_spanned_value span tuple to miette::SourceSpan
 fn convert_json_span(span_tuple: (usize, usize)) -> SourceSpan {
     let (start, end) = span_tuple;
     let length = if end > start { end - start } else { 1 };
-    SourceSpan::new(start.into(), length.into())
+    SourceSpan::new(start.into(), length)
 }
 
-// Function to find span for a JSON pointer path - simplified version that returns the span tuple
-fn find_span_for_json_path(current_value: &SpannedJsonValue, path: &str) -> Option<(usize, usize)> {
+// Span of the value behind a JSON pointer: the whole document for the root, otherwise the
+// scalar (or the key of the object or array) it names
+fn find_span_for_json_path(current_value: &SpannedJsonValue, content: &str, path: &str) -> Option<(usize, usize)> {
     if path.is_empty() || path == "/" { // Root element
         return Some(current_value.span());
     }
-    
-    // For now, return the span of the root value as a fallback
-    // TODO: Implement proper path traversal for json-spanned-value
-    Some(current_value.span())
+    config_merge::value_span(content, path).map(|range| (range.start, range.end))
 }
 
 // Helper to convert toml_edit::Span to miette::SourceSpan
 fn convert_toml_edit_span(toml_span: Option<std::ops::Range<usize>>) -> Option<SourceSpan> {
     toml_span.map(|range| {
         let length = if range.end > range.start { range.end - range.start } else { 1 };
-        SourceSpan::new(range.start.into(), length.into())
+        SourceSpan::new(range.start.into(), length)
     })
 }
 
@@ -83,7 +469,7 @@ fn find_span_for_toml_path(mut current_item: &TomlEditItem, path: &str) -> Optio
     current_item.span()
 }
 
-#[derive(Serialize)] // Ensure PrintableError can be serialized to JSON
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)] // Deserialize to read reports back for merge-reports
 #[serde(rename_all = "camelCase")]
 pub struct PrintableError { // Made PrintableError public
     pub file_path: String,
@@ -93,12 +479,66 @@ pub struct PrintableError { // Made PrintableError public
     pub column: Option<usize>,
     pub json_path: Option<String>, // For schema validation errors
     pub rule_id: String, // From AppError diagnostic code
+    // Absent from reports written before diagnostics carried them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<Severity>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<Category>,
+    /// The offending source, so reports can be shown without the files at hand
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<Snippet>,
+    /// For schema validation errors: the failing keyword, what it expected and what it got
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub violation: Option<Violation>,
+}
+
+/// Source lines a diagnostic points at, with the columns it covers marked.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Snippet {
+    /// 1-based line number of the first line of `text`
+    pub start_line: usize,
+    /// The lines, joined with `\n`
+    pub text: String,
+    /// 1-based byte column on the first line where the marked range starts
+    pub caret_start: usize,
+    /// 1-based byte column on the last line just past the marked range
+    pub caret_end: usize,
+}
+
+// The JSON pointer of the value a diagnostic is about, for those that carry one
+fn instance_path(error: &AppError) -> Option<&str> {
+    match error {
+        AppError::SchemaValidationError(error) => Some(&error.instance_path),
+        AppError::CoercibleType(error) => Some(&error.instance_path),
+        AppError::LimitExceeded(error) => Some(&error.instance_path),
+        AppError::UnrepresentableValue(error) => Some(&error.instance_path),
+        AppError::RenamedEnumValue(error) => Some(&error.instance_path),
+        _ => None,
+    }
 }
 
 impl From<&AppError> for PrintableError {
     fn from(app_error: &AppError) -> Self {
-        let line = None;
-        let column = None;
+        // 1-based, at the first label, counted in bytes like `SourceSpan` offsets
+        let (line, column) = template::line_column(app_error).unzip();
+        let snippet = template::snippet(app_error);
+        // Mapped diagnostics keep their own rule and message; the file and position move to the template
+        if let AppError::MappedToTemplate(mapped) = app_error {
+            if let Some(original) = mapped.generated.first() {
+                return PrintableError {
+                    file_path: mapped.template.to_string_lossy().into_owned(),
+                    line,
+                    column,
+                    snippet,
+                    ..PrintableError::from(original)
+                };
+            }
+        }
+        // Severity mappings keep the rule of what they wrap; only the level changes
+        if let AppError::Downgraded { inner } | AppError::Informational { inner } | AppError::Escalated { inner } = app_error {
+            return PrintableError { severity: Some(app_error.level()), ..PrintableError::from(inner.as_ref()) };
+        }
         let mut json_path = None;
         let error_type = app_error.to_string().split_once(':').map_or_else(|| "UnknownError".to_string(), |(et, _)| et.to_string());
         let rule_id = app_error.code().map_or_else(|| "N/A".to_string(), |c| c.to_string());
@@ -106,55 +546,93 @@ impl From<&AppError> for PrintableError {
             AppError::YamlParseError { span: _, .. } |
             AppError::JsonParseError { span: _, .. } |
             AppError::TomlParseError { span: _, .. } |
-            AppError::HclParseError { span: _, .. } => {
-                // For miette SourceSpan, we don't directly get line/col easily without source code context.
-                // This is a simplification. A more robust way would be to calculate line/col from offset and source.
-                // For now, we are not populating line/column from these parse errors directly here.
-                // The primary message from the error itself (e.g., e.to_string()) is used.
-                app_error.to_string() // Or a more specific message field if available
+            AppError::HclParseError { span: _, .. } |
+            AppError::IniParseError { span: _, .. } |
+            AppError::PropertiesParseError { span: _, .. } |
+            AppError::KdlParseError { span: _, .. } |
+            AppError::HjsonParseError { span: _, .. } => {
+                // The position comes from the span; the message is the parser's own
+                app_error.to_string()
             }
-            AppError::SchemaValidationError { instance_path,  .. } => {
-                json_path = Some(instance_path.clone());
+            AppError::SchemaValidationError { .. } |
+            AppError::CoercibleType { .. } |
+            AppError::LimitExceeded { .. } |
+            AppError::UnrepresentableValue { .. } |
+            AppError::RenamedEnumValue { .. } => {
+                json_path = instance_path(app_error).map(str::to_string);
                 // The main message for SchemaValidationError is already formatted in its creation.
                 app_error.to_string()
             }
             _ => app_error.to_string(),
         };
         
-        // Try to extract line/column from spans if possible (simplistic for now)
-        match app_error {
-            AppError::YamlParseError { span: _, .. } |
-            AppError::JsonParseError { span: _, .. } |
-            AppError::TomlParseError { span: _, .. } |
-            AppError::HclParseError { span: _, .. } |
-            AppError::SchemaValidationError { error_span: _, .. } => {
-                // This is a placeholder. True line/col from SourceSpan needs the source text.
-                // We will use the diagnostic information from miette for this, if possible,
-                // or pass the source text to this conversion.
-                // For a simple JSON report now, we might omit line/col or make them optional.
-            }
-            _ => {}
-        }
-
         PrintableError {
             file_path: match app_error {
                 AppError::FileReadError { path, .. } => path.to_string_lossy().into_owned(),
                 AppError::SchemaFetchError { url, .. } => url.clone(),
                 AppError::SchemaParseError { source_display, .. } => source_display.clone(),
                 AppError::SchemaCompileError { source_display, .. } => source_display.clone(),
+                AppError::InvalidSchemaShape { source_display, .. } => source_display.clone(),
                 AppError::YamlParseError { path, .. } => path.to_string_lossy().into_owned(),
-                AppError::SchemaValidationError { path, .. } => path.to_string_lossy().into_owned(),
+                AppError::SchemaValidationError(error) => error.path.to_string_lossy().into_owned(),
+                AppError::CoercibleType(error) => error.path.to_string_lossy().into_owned(),
                 AppError::JsonParseError { path, .. } => path.to_string_lossy().into_owned(),
                 AppError::TomlParseError { path, .. } => path.to_string_lossy().into_owned(),
                 AppError::HclParseError { path, .. } => path.to_string_lossy().into_owned(),
+                AppError::IniParseError { path, .. } => path.to_string_lossy().into_owned(),
+                AppError::PropertiesParseError { path, .. } => path.to_string_lossy().into_owned(),
+                AppError::KdlParseError { path, .. } => path.to_string_lossy().into_owned(),
+                AppError::HjsonParseError { path, .. } => path.to_string_lossy().into_owned(),
                 AppError::InvalidSchemaPath { path_display } => path_display.clone(),
+                AppError::OpenApiError { spec, .. } => spec.clone(),
+                AppError::ProtoDescriptorError { descriptor, .. } => descriptor.clone(),
+                AppError::EvaluationError { path, .. } => path.to_string_lossy().into_owned(),
+                AppError::SemanticError { path, .. } => path.to_string_lossy().into_owned(),
+                AppError::UnknownPreset { name, .. } => name.clone(),
+                AppError::ArmTemplateError { template, .. } => template.clone(),
+                AppError::ReportSigningError { path, .. } => path.clone(),
+                AppError::RenamedProperty(error) => error.path.to_string_lossy().into_owned(),
+                AppError::RenamedEnumValue(error) => error.path.to_string_lossy().into_owned(),
+                AppError::ExpiredSuppression { path, .. } => path.to_string_lossy().into_owned(),
+                AppError::LimitExceeded(error) => error.path.to_string_lossy().into_owned(),
+                AppError::UnrepresentableValue(error) => error.path.to_string_lossy().into_owned(),
+                AppError::RulesPackError { pack, .. } => pack.clone(),
+                AppError::RunRecordError { path, .. } => path.clone(),
+                AppError::DebugBundleError { path, .. } => path.clone(),
+                AppError::DiffBaseError { base, .. } => base.clone(),
+                AppError::HistoryError { revision, .. } => revision.clone(),
+                AppError::RelatedErrors(error) => error.path.to_string_lossy().into_owned(),
+                AppError::AssumedDialect { schema, .. } => schema.clone(),
+                AppError::UnknownConfigVersion { path, .. } => path.to_string_lossy().into_owned(),
+                AppError::StaleStamp { path, .. } => path.to_string_lossy().into_owned(),
+                AppError::UnexpectedlyValid { path } => path.to_string_lossy().into_owned(),
+                AppError::MissingRequiredFile { path, .. } => path.to_string_lossy().into_owned(),
+                AppError::InconsistentValue(error) => error.path.to_string_lossy().into_owned(),
+                AppError::MergeConflict(error) => error.path.to_string_lossy().into_owned(),
+                AppError::InconsistentLineEndings { path, .. } => path.to_string_lossy().into_owned(),
+                AppError::MissingFinalNewline { path, .. } => path.to_string_lossy().into_owned(),
+                AppError::UnusedDefinition { schema, .. } => schema.clone(),
+                AppError::InvalidSchemaExample(error) => error.schema.clone(),
+                AppError::UnsafePattern(error) => error.schema.clone(),
+                AppError::Downgraded { inner } | AppError::Informational { inner } | AppError::Escalated { inner } => {
+                    PrintableError::from(inner.as_ref()).file_path
+                }
+                AppError::MappedToTemplate(error) => error.template.to_string_lossy().into_owned(),
             },
             error_type,
             message,
-            line, // Will be None for now mostly
-            column, // Will be None for now mostly
+            line,
+            column,
             json_path,
             rule_id,
+            severity: Some(app_error.level()),
+            category: Some(app_error.category()),
+            snippet,
+            violation: match app_error {
+                AppError::SchemaValidationError(error) => Some(error.violation.clone()),
+                AppError::CoercibleType(error) => Some(error.violation.clone()),
+                _ => None,
+            },
         }
     }
 }
@@ -163,13 +641,31 @@ pub fn validate_inputs(
     inputs: Vec<PathBuf>,
     compiled_schema: &Validator,
 ) -> Result<Vec<AppError>, AppError> { // format_arg removed, main will handle formatting
-    
+    validate_inputs_with_options(inputs, compiled_schema, &ValidationOptions::default())
+}
+
+pub fn validate_inputs_with_options(
+    inputs: Vec<PathBuf>,
+    compiled_schema: &Validator,
+    options: &ValidationOptions,
+) -> Result<Vec<AppError>, AppError> {
     let mut collected_errors: Vec<AppError> = Vec::new();
+    // Schemas bound by `tj-schema:` comments or config versions, compiled once per run
+    let mut annotated: HashMap<PathBuf, (Validator, ValidationOptions)> = HashMap::new();
+    let mut embedded_schemas: HashMap<PathBuf, Validator> = HashMap::new();
+    let (default_schema, default_options) = (compiled_schema, options);
 
+    // Diagnostics before this index have been handed to a streaming report
+    let mut streamed = 0;
+    let mut previous: Option<PathBuf> = None;
     for input_path in inputs {
-        println!("Processing file: {:?}", input_path);
+        if let Some(finished) = previous.replace(input_path.clone()) {
+            progress::file_finished(&finished, &collected_errors[streamed..]);
+        }
+        report_stream::file_done(&collected_errors[streamed..]);
+        streamed = collected_errors.len();
+        progress::file_started(&input_path);
 
-        let extension = input_path.extension().and_then(|ext| ext.to_str());
         let file_content = match fs::read_to_string(&input_path) {
             Ok(c) => c,
             Err(e) => {
@@ -182,54 +678,130 @@ pub fn validate_inputs(
             }
         };
 
+        // A `tj-schema:` comment wins over a declared config version
+        let bound_schema = match schema_comment::annotated_schema_path(&input_path, &file_content) {
+            Some(schema_path) => Some(schema_path),
+            None => match versions::pinned_schema(&input_path, &file_content, &default_options.schema_versions, &default_options.parsers) {
+                Ok(schema_path) => schema_path,
+                Err(e) => {
+                    collected_errors.push(e);
+                    continue;
+                }
+            },
+        };
+        let (compiled_schema, options) = match bound_schema {
+            Some(schema_path) => {
+                if !annotated.contains_key(&schema_path) {
+                    match schema_comment::compile_annotated(&schema_path, default_options) {
+                        Ok((validator, file_options, dialect_warning)) => {
+                            collected_errors.extend(dialect_warning);
+                            annotated.insert(schema_path.clone(), (validator, file_options));
+                        }
+                        Err(e) => {
+                            collected_errors.push(e);
+                            continue;
+                        }
+                    }
+                }
+                summary::record_gate(&input_path, Some(schema_path.display().to_string()));
+                let (validator, file_options) = &annotated[&schema_path];
+                (validator, file_options)
+            }
+            None if default_options.require_schema_comment => {
+                collected_errors.push(AppError::InvalidSchemaPath {
+                    path_display: format!("{} has no `# tj-schema:` comment and no --schema was given", input_path.display()),
+                });
+                continue;
+            }
+            None => {
+                summary::record_gate(&input_path, None);
+                (default_schema, default_options)
+            }
+        };
+
+        collected_errors.extend(options.newlines.check(&input_path, &file_content));
+
+        let extension = parsers::parser_for(&input_path, &options.parsers);
+        let file_content = match extension {
+            Some("yaml-template") => parsers::mask_templates(&file_content),
+            _ => file_content,
+        };
+
+        check_limits(options, &input_path, &file_content, None, &mut collected_errors);
+
+        if options.containerfiles && containerfile::is_containerfile(&input_path) {
+            validate_containerfile(&input_path, &file_content, compiled_schema, &mut collected_errors);
+            continue;
+        }
+
         match extension {
-            Some("yaml") | Some("yml") => {
-                match serde_yaml::from_str::<serde_yaml::Value>(&file_content) {
-                    Ok(parsed_yaml) => {
-                        let json_value_for_validation: JsonValue = match serde_yaml::from_value(parsed_yaml) {
-                            Ok(v) => v,
-                            Err(_convert_err) => {
-                                let err_span = SourceSpan::new(0.into(), file_content.len().into());
-                                collected_errors.push(AppError::YamlParseError {
+            Some("yaml") | Some("yml") | Some("yaml-template") => {
+                match yaml::parse_documents(&file_content) {
+                    Ok(mut documents) => {
+                        // An empty stream is a single null document, as it was for single-document parsing
+                        if documents.is_empty() {
+                            documents.push(serde_yaml::Value::Null);
+                        }
+                        let multi_document = documents.len() > 1;
+                        let document_ranges = yaml::document_ranges(&file_content);
+                        for (index, document) in documents.into_iter().enumerate() {
+                            let document_source = document_ranges.get(index).map_or(file_content.as_str(), |range| &file_content[range.clone()]);
+                            if let Some(found) = yaml::find_unrepresentable(&document, document_source) {
+                                let key = found.pointer.rsplit('/').next().unwrap_or_default().replace("~1", "/").replace("~0", "~");
+                                collected_errors.push(AppError::UnrepresentableValue(Box::new(UnrepresentableValue {
                                     path: input_path.clone(),
-                                    message: "Internal error: Failed to convert parsed YAML to JSON for validation".to_string(),
-                                    span: err_span,
+                                    construct: found.construct,
+                                    message: found.message,
+                                    instance_path: found.pointer,
+                                    span: reference_span(&file_content, &key, &found.needle),
                                     source_code: file_content.clone(),
-                                });
+                                })));
                                 continue;
                             }
-                        };
-                        let validation_result = compiled_schema.validate(&json_value_for_validation);
-                        if let Err(validation_error) = validation_result {
-                            // In jsonschema 0.30.0, ValidationError has basic fields but doesn't iterate
-                            // Let's just report the single error from the validation failure
-                            let fallback_span = SourceSpan::new(0.into(), file_content.len().into());
-                            let error_json_path = validation_error.instance_path.to_string();
-                            let kind_str = format!("{:?}", validation_error.kind);
-                            collected_errors.push(AppError::SchemaValidationError {
-                                path: input_path.clone(),
-                                message: "Schema validation failed".to_string(),
-                                source_code: file_content.clone(),
-                                error_span: fallback_span,
-                                label_message: format!("Field `{}`: {}", error_json_path, kind_str),
-                                instance_path: error_json_path,
-                                kind: kind_str,
-                            });
+                            let json_value_for_validation = match yaml::to_json_with_policy(document, &options.yaml_tags) {
+                                Ok(v) => v,
+                                Err(tag) => {
+                                    collected_errors.push(AppError::YamlParseError {
+                                        path: input_path.clone(),
+                                        message: format!("Unknown YAML tag {} (see --yaml-unknown-tags)", tag),
+                                        span: reference_span(&file_content, "", &tag),
+                                        source_code: file_content.clone(),
+                                    });
+                                    break;
+                                }
+                            };
+                            check_renames(options, &input_path, &file_content, &json_value_for_validation, &mut collected_errors);
+                            check_enum_mappings(options, &input_path, &file_content, &json_value_for_validation, &mut collected_errors);
+                            check_embedded(options, &mut embedded_schemas, &input_path, &file_content, &json_value_for_validation, &mut collected_errors);
+                            check_limits(options, &input_path, &file_content, Some(&json_value_for_validation), &mut collected_errors);
+                            let validation_result = compiled_schema.validate(&json_value_for_validation);
+                            if let Err(validation_error) = validation_result {
+                                // In jsonschema 0.30.0, ValidationError has basic fields but doesn't iterate
+                                // Let's just report the single error from the validation failure
+                                let mut error = whole_file_validation_error(&input_path, &file_content, &validation_error);
+                                if let AppError::SchemaValidationError(boxed) = &mut error {
+                                    let SchemaValidationError { label_message, error_span, instance_path, .. } = &mut **boxed;
+                                    let document = document_ranges.get(index).cloned().unwrap_or(0..file_content.len());
+                                    if let Some(range) = config_merge::value_span(&file_content[document.clone()], instance_path) {
+                                        *error_span = SourceSpan::new((document.start + range.start).into(), range.len());
+                                    }
+                                    if multi_document {
+                                        *label_message = format!("Document {}: {}", index + 1, label_message);
+                                    }
+                                }
+                                collected_errors.push(coercion::explain(error));
+                            }
                         }
                     }
                     Err(e) => {
                         if let Some(location) = e.location() {
-                            let mut offset = 0;
-                            for (i, line_content) in file_content.lines().enumerate() {
-                                if i < location.line() -1 { offset += line_content.len() + 1; } else { break; }
-                            }
-                            offset += location.column() -1;
-                            let err_span = SourceSpan::new(offset.into(), 1usize.into());
+                            let offset = offset_for_line_char(&file_content, location.line(), location.column());
+                            let err_span = char_span(&file_content, offset);
                             collected_errors.push(AppError::YamlParseError {
                                 path: input_path.clone(), message: e.to_string(), span: err_span, source_code: file_content.clone(),
                             });
                         } else {
-                            let err_span = SourceSpan::new(0.into(), file_content.len().into());
+                            let err_span = SourceSpan::new(0.into(), file_content.len());
                             collected_errors.push(AppError::YamlParseError {
                                 path: input_path.clone(), message: format!("YAML parsing error: {}", e), span: err_span, source_code: file_content.clone(),
                             });
@@ -238,17 +810,21 @@ pub fn validate_inputs(
                 }
             }
             Some("json") => {
-                match json_spanned_value::from_str::<SpannedJsonValue>(&file_content) {
+                // JSON has no comments, so a `// tj-schema:` line is blanked before parsing
+                let json_content = schema_comment::without_annotation(&file_content);
+                match json_spanned_value::from_str::<SpannedJsonValue>(&json_content) {
                     Ok(spanned_json_doc) => {
                         // For json-spanned-value, we need to convert the spanned value to a regular JsonValue
                         // Let's use the simpler approach of re-parsing the JSON string
-                        let plain_json_value: JsonValue = match serde_json::from_str(&file_content) {
+                        let plain_json_value: JsonValue = match serde_json::from_str(&json_content) {
+                            // `.tf.json` files take the shape native HCL parses to
+                            Ok(val) if hcl_json::is_hcl_json(&input_path) => hcl_json::normalize(val),
                             Ok(val) => val,
                             Err(e) => {
                                 collected_errors.push(AppError::JsonParseError {
                                     path: input_path.clone(),
                                     message: "Failed to parse JSON for validation".to_string(),
-                                    span: SourceSpan::new(0.into(), file_content.len().into()),
+                                    span: SourceSpan::new(0.into(), file_content.len()),
                                     source_code: file_content.clone(),
                                     source: e,
                                 });
@@ -256,14 +832,18 @@ pub fn validate_inputs(
                             }
                         };
                         
+                        check_renames(options, &input_path, &file_content, &plain_json_value, &mut collected_errors);
+                        check_enum_mappings(options, &input_path, &file_content, &plain_json_value, &mut collected_errors);
+                        check_embedded(options, &mut embedded_schemas, &input_path, &file_content, &plain_json_value, &mut collected_errors);
+                        check_limits(options, &input_path, &file_content, Some(&plain_json_value), &mut collected_errors);
                         let validation_result = compiled_schema.validate(&plain_json_value);
                         if let Err(validation_error) = validation_result {
                             let error_json_path = validation_error.instance_path.to_string();
-                            let target_jspan = find_span_for_json_path(&spanned_json_doc, &error_json_path);
-                            let target_miette_span = target_jspan.map(|s| convert_json_span(s))
-                                .unwrap_or_else(|| SourceSpan::new(0.into(), file_content.len().into()));
+                            let target_jspan = find_span_for_json_path(&spanned_json_doc, &file_content, &error_json_path);
+                            let target_miette_span = target_jspan.map(convert_json_span)
+                                .unwrap_or_else(|| SourceSpan::new(0.into(), file_content.len()));
                             let kind_str = format!("{:?}", validation_error.kind);
-                            collected_errors.push(AppError::SchemaValidationError {
+                            collected_errors.push(coercion::explain(AppError::SchemaValidationError(Box::new(SchemaValidationError {
                                 path: input_path.clone(),
                                 message: "Schema validation failed".to_string(),
                                 source_code: file_content.clone(),
@@ -271,16 +851,15 @@ pub fn validate_inputs(
                                 label_message: format!("Field `{}`: {}", error_json_path, kind_str),
                                 instance_path: error_json_path,
                                 kind: kind_str,
-                            });
+                                schema_path: validation_error.schema_path.to_string(),
+                                violation: Violation::from_error(&validation_error),
+                            }))));
                         }
                     }
                     Err(e) => {
-                        let line = e.line(); let column = e.column(); let mut offset = 0;
-                        for (i, line_content) in file_content.lines().enumerate() {
-                            if i < line - 1 { offset += line_content.len() + 1; } else { break; }
-                        }
-                        offset += column - 1;
-                        let err_span = SourceSpan::new(offset.into(), 1usize.into());
+                        // serde_json counts columns in bytes
+                        let offset = offset_for_line_col(&file_content, e.line(), e.column());
+                        let err_span = char_span(&file_content, offset);
                         collected_errors.push(AppError::JsonParseError {
                             path: input_path.clone(), 
                             message: e.to_string(), 
@@ -292,10 +871,10 @@ pub fn validate_inputs(
                 }
             }
             Some("toml") => {
-                println!("Detected TOML file: {:?}", input_path);
+                progress::line(format_args!("Detected TOML file: {:?}", input_path));
                 match file_content.parse::<DocumentMut>() {
                     Ok(toml_doc) => {
-                        println!("TOML content parsed into DocumentMut successfully.");
+                        progress::line("TOML content parsed into DocumentMut successfully.");
                         // Convert DocumentMut to serde_json::Value for validation
                         // Use to_string() and re-parse approach since toml_doc.root is private
                         let toml_as_string = toml_doc.to_string();
@@ -303,7 +882,7 @@ pub fn validate_inputs(
                             Ok(toml_value) => match serde_json::to_value(toml_value) {
                                 Ok(json_val) => json_val,
                                 Err(_) => {
-                                    let err_span = SourceSpan::new(0.into(), file_content.len().into());
+                                    let err_span = SourceSpan::new(0.into(), file_content.len());
                                     collected_errors.push(AppError::TomlParseError {
                                         path: input_path.clone(),
                                         message: "Internal error: Failed to convert TOML to JSON for validation".to_string(),
@@ -314,7 +893,7 @@ pub fn validate_inputs(
                                 }
                             },
                             Err(_) => {
-                                let err_span = SourceSpan::new(0.into(), file_content.len().into());
+                                let err_span = SourceSpan::new(0.into(), file_content.len());
                                 collected_errors.push(AppError::TomlParseError {
                                     path: input_path.clone(),
                                     message: "Internal error: Failed to re-parse TOML string for validation".to_string(),
@@ -324,12 +903,16 @@ pub fn validate_inputs(
                                 continue;
                             }
                         };
+                        check_renames(options, &input_path, &file_content, &json_value_for_validation, &mut collected_errors);
+                        check_enum_mappings(options, &input_path, &file_content, &json_value_for_validation, &mut collected_errors);
+                        check_embedded(options, &mut embedded_schemas, &input_path, &file_content, &json_value_for_validation, &mut collected_errors);
+                        check_limits(options, &input_path, &file_content, Some(&json_value_for_validation), &mut collected_errors);
                         let validation_result = compiled_schema.validate(&json_value_for_validation);
                         if let Err(validation_error) = validation_result {
                             let error_json_path = validation_error.instance_path.to_string();
                             let target_toml_span_range = find_span_for_toml_path(toml_doc.as_item(), &error_json_path);
                             let target_miette_span = convert_toml_edit_span(target_toml_span_range)
-                                .unwrap_or_else(|| SourceSpan::new(0.into(), file_content.len().into()));
+                                .unwrap_or_else(|| SourceSpan::new(0.into(), file_content.len()));
 
                             let kind_str = format!("{:?}", validation_error.kind);
                             let label_msg = if error_json_path.is_empty() || error_json_path == "/" {
@@ -338,7 +921,7 @@ pub fn validate_inputs(
                                 format!("Field `{}`: {}", error_json_path, kind_str)
                             };
 
-                            collected_errors.push(AppError::SchemaValidationError {
+                            collected_errors.push(coercion::explain(AppError::SchemaValidationError(Box::new(SchemaValidationError {
                                 path: input_path.clone(),
                                 message: "Schema validation failed".to_string(),
                                 source_code: file_content.clone(),
@@ -346,9 +929,11 @@ pub fn validate_inputs(
                                 label_message: label_msg,
                                 instance_path: error_json_path,
                                 kind: kind_str,
-                            });
+                                schema_path: validation_error.schema_path.to_string(),
+                                violation: Violation::from_error(&validation_error),
+                            }))));
                         } else {
-                            println!("File {:?} is valid against the schema.", input_path);
+                            progress::line(format_args!("File {:?} is valid against the schema.", input_path));
                         }
                     }
                     Err(e) => {
@@ -359,8 +944,8 @@ pub fn validate_inputs(
                             message: e.message().to_string(),
                             span: e.span().map(|range| { // Use range here
                                 let length = if range.end > range.start { range.end - range.start } else { 1 };
-                                SourceSpan::new(range.start.into(), length.into())
-                            }).unwrap_or_else(|| SourceSpan::new(0.into(), file_content.len().into())),
+                                SourceSpan::new(range.start.into(), length)
+                            }).unwrap_or_else(|| SourceSpan::new(0.into(), file_content.len())),
                             source_code: file_content.clone(),
                         });
                     }
@@ -370,12 +955,16 @@ pub fn validate_inputs(
                 // HCL parsing using the hcl-rs API
                 match hcl::from_str::<JsonValue>(&file_content) {
                     Ok(hcl_json_value_for_validation) => {
+                        check_renames(options, &input_path, &file_content, &hcl_json_value_for_validation, &mut collected_errors);
+                        check_enum_mappings(options, &input_path, &file_content, &hcl_json_value_for_validation, &mut collected_errors);
+                        check_embedded(options, &mut embedded_schemas, &input_path, &file_content, &hcl_json_value_for_validation, &mut collected_errors);
+                        check_limits(options, &input_path, &file_content, Some(&hcl_json_value_for_validation), &mut collected_errors);
                         let validation_result = compiled_schema.validate(&hcl_json_value_for_validation);
                         if let Err(validation_error) = validation_result {
-                            let fallback_span = SourceSpan::new(0.into(), file_content.len().into());
+                            let fallback_span = SourceSpan::new(0.into(), file_content.len());
                             let error_json_path = validation_error.instance_path.to_string();
                             let kind_str = format!("{:?}", validation_error.kind);
-                            collected_errors.push(AppError::SchemaValidationError {
+                            collected_errors.push(coercion::explain(AppError::SchemaValidationError(Box::new(SchemaValidationError {
                                 path: input_path.clone(), 
                                 message: "Schema validation failed".to_string(),
                                 source_code: file_content.clone(), 
@@ -383,11 +972,13 @@ pub fn validate_inputs(
                                 label_message: format!("Field `{}`: {}", error_json_path, kind_str),
                                 instance_path: error_json_path, 
                                 kind: kind_str,
-                            });
+                                schema_path: validation_error.schema_path.to_string(),
+                                violation: Violation::from_error(&validation_error),
+                            }))));
                         }
                     }
                     Err(e) => {
-                        let err_span = SourceSpan::new(0.into(), file_content.len().into());
+                        let err_span = SourceSpan::new(0.into(), file_content.len());
                         collected_errors.push(AppError::HclParseError {
                             path: input_path.clone(), 
                             message: format!("HCL parsing failed: {}", e), 
@@ -397,13 +988,222 @@ pub fn validate_inputs(
                     }
                 }
             }
-            Some(ext) => {
-                println!("Skipping unsupported file type ({}): {:?}", ext, input_path);
+            Some("ini") | Some("cfg") => {
+                match ini::parse_ini(&file_content, options.ini_coercion) {
+                    Ok(document) => {
+                        check_renames(options, &input_path, &file_content, &document.value, &mut collected_errors);
+                        check_enum_mappings(options, &input_path, &file_content, &document.value, &mut collected_errors);
+                        check_embedded(options, &mut embedded_schemas, &input_path, &file_content, &document.value, &mut collected_errors);
+                        check_limits(options, &input_path, &file_content, Some(&document.value), &mut collected_errors);
+                        if let Err(validation_error) = compiled_schema.validate(&document.value) {
+                            let mut error = whole_file_validation_error(&input_path, &file_content, &validation_error);
+                            if let AppError::SchemaValidationError(boxed) = &mut error {
+                                let SchemaValidationError { error_span, instance_path, .. } = &mut **boxed;
+                                if let Some(range) = document.span_for_pointer(instance_path) {
+                                    *error_span = SourceSpan::new(range.start.into(), range.len());
+                                }
+                            }
+                            collected_errors.push(coercion::explain_untyped(error, options.ini_coercion, "--ini-coerce"));
+                        }
+                    }
+                    Err(e) => {
+                        collected_errors.push(AppError::IniParseError {
+                            path: input_path.clone(),
+                            message: e.message,
+                            span: SourceSpan::new(e.span.start.into(), e.span.len()),
+                            source_code: file_content.clone(),
+                        });
+                    }
+                }
             }
-            None => {
-                println!("Skipping file without extension: {:?}", input_path);
+            Some("properties") => {
+                match properties::parse_properties(&file_content, options.properties) {
+                    Ok(document) => {
+                        check_renames(options, &input_path, &file_content, &document.value, &mut collected_errors);
+                        check_enum_mappings(options, &input_path, &file_content, &document.value, &mut collected_errors);
+                        check_embedded(options, &mut embedded_schemas, &input_path, &file_content, &document.value, &mut collected_errors);
+                        check_limits(options, &input_path, &file_content, Some(&document.value), &mut collected_errors);
+                        if let Err(validation_error) = compiled_schema.validate(&document.value) {
+                            let mut error = whole_file_validation_error(&input_path, &file_content, &validation_error);
+                            if let AppError::SchemaValidationError(boxed) = &mut error {
+                                let SchemaValidationError { error_span, instance_path, .. } = &mut **boxed;
+                                if let Some(range) = document.span_for_pointer(instance_path) {
+                                    *error_span = SourceSpan::new(range.start.into(), range.len());
+                                }
+                            }
+                            collected_errors.push(coercion::explain_untyped(error, options.properties.coercion, "--properties-coerce"));
+                        }
+                    }
+                    Err(e) => {
+                        collected_errors.push(AppError::PropertiesParseError {
+                            path: input_path.clone(),
+                            message: e.message,
+                            span: SourceSpan::new(e.span.start.into(), e.span.len()),
+                            source_code: file_content.clone(),
+                        });
+                    }
+                }
+            }
+            Some("kdl") => {
+                match kdl::parse_kdl(&file_content) {
+                    Ok(document) => {
+                        check_renames(options, &input_path, &file_content, &document.value, &mut collected_errors);
+                        check_enum_mappings(options, &input_path, &file_content, &document.value, &mut collected_errors);
+                        check_embedded(options, &mut embedded_schemas, &input_path, &file_content, &document.value, &mut collected_errors);
+                        check_limits(options, &input_path, &file_content, Some(&document.value), &mut collected_errors);
+                        if let Err(validation_error) = compiled_schema.validate(&document.value) {
+                            let mut error = whole_file_validation_error(&input_path, &file_content, &validation_error);
+                            if let AppError::SchemaValidationError(boxed) = &mut error {
+                                let SchemaValidationError { error_span, instance_path, .. } = &mut **boxed;
+                                if let Some(range) = document.span_for_pointer(instance_path) {
+                                    *error_span = SourceSpan::new(range.start.into(), range.len());
+                                }
+                            }
+                            collected_errors.push(coercion::explain(error));
+                        }
+                    }
+                    Err(e) => {
+                        collected_errors.push(AppError::KdlParseError {
+                            path: input_path.clone(),
+                            message: e.message,
+                            span: SourceSpan::new(e.span.start.into(), e.span.len()),
+                            source_code: file_content.clone(),
+                        });
+                    }
+                }
+            }
+            Some("hjson") => {
+                match hjson::parse_hjson(&file_content) {
+                    Ok(document) => {
+                        check_renames(options, &input_path, &file_content, &document.value, &mut collected_errors);
+                        check_enum_mappings(options, &input_path, &file_content, &document.value, &mut collected_errors);
+                        check_embedded(options, &mut embedded_schemas, &input_path, &file_content, &document.value, &mut collected_errors);
+                        check_limits(options, &input_path, &file_content, Some(&document.value), &mut collected_errors);
+                        if let Err(validation_error) = compiled_schema.validate(&document.value) {
+                            let mut error = whole_file_validation_error(&input_path, &file_content, &validation_error);
+                            if let AppError::SchemaValidationError(boxed) = &mut error {
+                                let SchemaValidationError { error_span, instance_path, .. } = &mut **boxed;
+                                if let Some(range) = document.span_for_pointer(instance_path) {
+                                    *error_span = SourceSpan::new(range.start.into(), range.len());
+                                }
+                            }
+                            collected_errors.push(coercion::explain(error));
+                        }
+                    }
+                    Err(e) => {
+                        collected_errors.push(AppError::HjsonParseError {
+                            path: input_path.clone(),
+                            message: e.message,
+                            span: SourceSpan::new(e.span.start.into(), e.span.len()),
+                            source_code: file_content.clone(),
+                        });
+                    }
+                }
+            }
+            Some("ndjson" | "jsonl") => {
+                for ndjson::Record { line, span, value } in ndjson::records(&file_content) {
+                    let value = match value {
+                        Ok(value) => value,
+                        Err(e) => {
+                            // serde_json positions are relative to the record's own line
+                            let offset = (span.start + e.column().saturating_sub(1)).min(span.end);
+                            let message = e.to_string();
+                            let reason = message.rsplit_once(" at line ").map_or(message.as_str(), |(reason, _)| reason);
+                            collected_errors.push(AppError::JsonParseError {
+                                path: input_path.clone(),
+                                message: format!("Record on line {}, column {}: {}", line, e.column(), reason),
+                                span: char_span(&file_content, offset),
+                                source_code: file_content.clone(),
+                                source: e,
+                            });
+                            continue;
+                        }
+                    };
+                    // Every record repeats the same keys, so what is found in one is located
+                    // within its own line and then moved into the file
+                    let record = &file_content[span.clone()];
+                    let mut record_errors = Vec::new();
+                    check_renames(options, &input_path, record, &value, &mut record_errors);
+                    check_enum_mappings(options, &input_path, record, &value, &mut record_errors);
+                    check_embedded(options, &mut embedded_schemas, &input_path, record, &value, &mut record_errors);
+                    let mut limit_errors = Vec::new();
+                    check_limits(options, &input_path, &file_content, Some(&value), &mut limit_errors);
+                    for mut error in limit_errors {
+                        if let AppError::LimitExceeded(boxed) = &mut error {
+                            let LimitExceeded { span: error_span, instance_path, .. } = &mut **boxed;
+                            let key = ndjson::pointer_span(&file_content, &span, instance_path);
+                            *error_span = SourceSpan::new(key.start.into(), key.len());
+                        }
+                        collected_errors.push(error);
+                    }
+                    if let Err(validation_error) = compiled_schema.validate(&value) {
+                        let mut error = whole_file_validation_error(&input_path, record, &validation_error);
+                        if let AppError::SchemaValidationError(boxed) = &mut error {
+                            let SchemaValidationError { message, error_span, label_message, instance_path, .. } = &mut **boxed;
+                            let key = ndjson::pointer_span(record, &(0..record.len()), instance_path);
+                            *error_span = SourceSpan::new(key.start.into(), key.len());
+                            *message = format!("Record on line {} failed schema validation", line);
+                            *label_message = format!("Line {}: {}", line, label_message);
+                        }
+                        record_errors.push(coercion::explain(error));
+                    }
+                    collected_errors.extend(record_errors.into_iter().map(|error| moved_into_file(error, &file_content, span.start)));
+                }
+            }
+            Some("cue") => {
+                validate_evaluated_file(
+                    &evaluator::CUE,
+                    options.cue_binary.as_deref(),
+                    &input_path,
+                    &file_content,
+                    compiled_schema,
+                    &mut collected_errors,
+                );
+            }
+            Some("pkl") => {
+                validate_evaluated_file(
+                    &evaluator::PKL,
+                    options.pkl_binary.as_deref(),
+                    &input_path,
+                    &file_content,
+                    compiled_schema,
+                    &mut collected_errors,
+                );
+            }
+            Some("ncl") => {
+                // Nickel contract and type errors surface as evaluation diagnostics
+                validate_evaluated_file(
+                    &evaluator::NICKEL,
+                    options.nickel_binary.as_deref(),
+                    &input_path,
+                    &file_content,
+                    compiled_schema,
+                    &mut collected_errors,
+                );
+            }
+            _ => {
+                let reason = skip_reason(&input_path, options).unwrap_or_default();
+                progress::line(format_args!("Skipping {}: {:?}", reason, input_path));
             }
         }
     }
+    if let Some(finished) = previous {
+        progress::file_finished(&finished, &collected_errors[streamed..]);
+    }
+    report_stream::file_done(&collected_errors[streamed..]);
+    progress::run_finished(&collected_errors);
     Ok(collected_errors)
+}
+
+/// Why `validate_inputs_with_options` skips `input`, e.g. `unsupported file type (xml)`;
+/// `None` for the files it validates.
+pub fn skip_reason(input: &Path, options: &ValidationOptions) -> Option<String> {
+    if options.containerfiles && containerfile::is_containerfile(input) {
+        return None;
+    }
+    match parsers::parser_for(input, &options.parsers) {
+        Some("yaml" | "yml" | "yaml-template" | "json" | "toml" | "hcl" | "ini" | "cfg" | "properties" | "kdl" | "hjson" | "ndjson" | "jsonl" | "cue" | "pkl" | "ncl") => None,
+        Some(ext) => Some(format!("unsupported file type ({})", ext)),
+        None => Some("file without extension".to_string()),
+    }
 } 
\ No newline at end of file
@@ -0,0 +1,67 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use miette::SourceSpan;
+use serde_json::Value as JsonValue;
+
+use crate::error::AppError;
+use crate::parsers;
+use crate::renames;
+use crate::yaml;
+
+/// Top-level keys a document declares its config version with, in order of precedence.
+pub const VERSION_KEYS: &[&str] = &["x-config-version", "version"];
+
+// Top level of the document as JSON, for the formats a version can be read from cheaply.
+// Unparsable documents give `None`; validation reports them as usual
+fn top_level(extension: Option<&str>, content: &str) -> Option<JsonValue> {
+    match extension? {
+        "json" => serde_json::from_str(content).ok(),
+        "yaml" | "yml" | "yaml-template" => {
+            let content = parsers::mask_templates(content);
+            let first = yaml::parse_documents(&content).ok()?.into_iter().next()?;
+            serde_json::to_value(first).ok()
+        }
+        "toml" => toml::from_str::<toml::Value>(content).ok().and_then(|v| serde_json::to_value(v).ok()),
+        _ => None,
+    }
+}
+
+/// The version the document declares, and the key it used. Numbers count as versions too,
+/// so `version: 2` and `version: "2"` are the same.
+pub fn declared_version(extension: Option<&str>, content: &str) -> Option<(&'static str, String)> {
+    let document = top_level(extension, content)?;
+    VERSION_KEYS.iter().find_map(|&key| match document.get(key)? {
+        JsonValue::String(version) => Some((key, version.clone())),
+        JsonValue::Number(version) => Some((key, version.to_string())),
+        _ => None,
+    })
+}
+
+/// Schema pinned by the document's declared version in the project config's
+/// `[schema_versions]` table. `Ok(None)` when the table is empty or the document declares no
+/// version; an error when it declares one the table does not know.
+pub fn pinned_schema(
+    input: &Path,
+    content: &str,
+    schema_versions: &BTreeMap<String, PathBuf>,
+    parsers: &BTreeMap<String, parsers::Parser>,
+) -> Result<Option<PathBuf>, AppError> {
+    if schema_versions.is_empty() {
+        return Ok(None);
+    }
+    let Some((key, version)) = declared_version(parsers::parser_for(input, parsers), content) else {
+        return Ok(None);
+    };
+    match schema_versions.get(&version) {
+        Some(schema) => Ok(Some(schema.clone())),
+        None => Err(AppError::UnknownConfigVersion {
+            path: input.to_path_buf(),
+            span: renames::find_key_span(content, key)
+                .map(|range| SourceSpan::new(range.start.into(), range.len()))
+                .unwrap_or_else(|| SourceSpan::new(0.into(), content.len())),
+            known: schema_versions.keys().map(|v| format!("`{}`", v)).collect::<Vec<_>>().join(", "),
+            version,
+            source_code: content.to_string(),
+        }),
+    }
+}
@@ -0,0 +1,65 @@
+use jsonschema::error::{TypeKind, ValidationErrorKind};
+use jsonschema::ValidationError;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+
+// Instance values longer than this (as JSON) are left out of reports; the file has them
+const MAX_ACTUAL_LEN: usize = 200;
+
+/// What a schema validation failure was about: the keyword that failed, what it asked for
+/// and the value it got, so reports do not have to be parsed for them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Violation {
+    /// The failing keyword as the schema spells it: `type`, `minimum`, `required`, ...
+    pub keyword: String,
+    /// The keyword's requirement: the type(s), the enum values, the limit, the missing
+    /// property, the pattern or format. Absent for keywords without one (`anyOf`, `not`, ...)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected: Option<JsonValue>,
+    /// The offending value, unless it is longer than 200 characters as JSON
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actual: Option<JsonValue>,
+}
+
+// The requirement a keyword states, taken from the error rather than the schema so `$ref`s
+// need no resolving
+fn expected(kind: &ValidationErrorKind) -> Option<JsonValue> {
+    match kind {
+        ValidationErrorKind::Type { kind: TypeKind::Single(expected) } => Some(json!(expected.to_string())),
+        ValidationErrorKind::Type { kind: TypeKind::Multiple(expected) } => {
+            Some(json!(expected.iter().map(|t| t.to_string()).collect::<Vec<_>>()))
+        }
+        ValidationErrorKind::Enum { options } => Some(options.clone()),
+        ValidationErrorKind::Constant { expected_value } => Some(expected_value.clone()),
+        ValidationErrorKind::Required { property } => Some(property.clone()),
+        ValidationErrorKind::Minimum { limit } => Some(json!(limit)),
+        ValidationErrorKind::Maximum { limit } => Some(json!(limit)),
+        ValidationErrorKind::ExclusiveMinimum { limit } => Some(json!(limit)),
+        ValidationErrorKind::ExclusiveMaximum { limit } => Some(json!(limit)),
+        ValidationErrorKind::MultipleOf { multiple_of } => Some(json!(multiple_of)),
+        ValidationErrorKind::MinLength { limit } => Some(json!(limit)),
+        ValidationErrorKind::MaxLength { limit } => Some(json!(limit)),
+        ValidationErrorKind::MinItems { limit } => Some(json!(limit)),
+        ValidationErrorKind::MaxItems { limit } => Some(json!(limit)),
+        ValidationErrorKind::MinProperties { limit } => Some(json!(limit)),
+        ValidationErrorKind::MaxProperties { limit } => Some(json!(limit)),
+        ValidationErrorKind::Pattern { pattern } => Some(json!(pattern)),
+        ValidationErrorKind::Format { format } => Some(json!(format)),
+        _ => None,
+    }
+}
+
+impl Violation {
+    /// The detail of `error`. The keyword is the last segment of its schema path (the
+    /// keyword location), which names it the way the schema does (`const`, not `Constant`).
+    pub fn from_error(error: &ValidationError) -> Self {
+        let schema_path = error.schema_path.to_string();
+        let actual = error.instance.as_ref();
+        Violation {
+            keyword: schema_path.rsplit('/').next().unwrap_or_default().to_string(),
+            expected: expected(&error.kind),
+            actual: serde_json::to_string(actual).ok().filter(|text| text.len() <= MAX_ACTUAL_LEN).map(|_| actual.clone()),
+        }
+    }
+}
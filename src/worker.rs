@@ -0,0 +1,160 @@
+use std::io::{self, BufRead, BufReader, Read, Write};
+use prost::Message;
+use serde::{Deserialize, Serialize};
+
+/// An input file of a work request (Bazel's `worker_protocol.proto`). Unused, since inputs
+/// are read from disk, but kept so proto requests decode in full.
+#[derive(Clone, PartialEq, Message)]
+pub struct WorkInput {
+    #[prost(string, tag = "1")]
+    pub path: String,
+    #[prost(bytes = "vec", tag = "2")]
+    pub digest: Vec<u8>,
+}
+
+/// One action to run: the arguments a non-worker invocation would get after the tool name.
+#[derive(Clone, PartialEq, Message, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WorkRequest {
+    #[prost(string, repeated, tag = "1")]
+    pub arguments: Vec<String>,
+    #[prost(message, repeated, tag = "2")]
+    #[serde(skip)]
+    pub inputs: Vec<WorkInput>,
+    /// Non-zero only for multiplex workers; echoed in the response
+    #[prost(int32, tag = "3")]
+    pub request_id: i32,
+    #[prost(bool, tag = "4")]
+    pub cancel: bool,
+    #[prost(int32, tag = "5")]
+    pub verbosity: i32,
+    #[prost(string, tag = "6")]
+    pub sandbox_dir: String,
+}
+
+/// Result of one action; `output` is shown to the user by the build system.
+#[derive(Clone, PartialEq, Message, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkResponse {
+    #[prost(int32, tag = "1")]
+    pub exit_code: i32,
+    #[prost(string, tag = "2")]
+    pub output: String,
+    #[prost(int32, tag = "3")]
+    pub request_id: i32,
+    #[prost(bool, tag = "4")]
+    pub was_cancelled: bool,
+}
+
+/// Wire format of the worker protocol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Protocol {
+    /// Varint length-delimited `WorkRequest`/`WorkResponse` messages (Bazel's default)
+    Proto,
+    /// One JSON object per request/response (`requires-worker-protocol: json`)
+    Json,
+}
+
+// Reads a protobuf varint; `None` at a clean end of stream
+fn read_varint(reader: &mut impl Read) -> io::Result<Option<u64>> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let mut byte = [0u8];
+        if reader.read(&mut byte)? == 0 {
+            return if shift == 0 { Ok(None) } else { Err(io::ErrorKind::UnexpectedEof.into()) };
+        }
+        value |= u64::from(byte[0] & 0x7f) << shift;
+        if byte[0] & 0x80 == 0 {
+            return Ok(Some(value));
+        }
+    }
+    Err(io::Error::new(io::ErrorKind::InvalidData, "varint too long"))
+}
+
+fn read_proto_request(reader: &mut impl Read) -> io::Result<Option<WorkRequest>> {
+    let Some(length) = read_varint(reader)? else { return Ok(None) };
+    let mut buffer = vec![0u8; length as usize];
+    reader.read_exact(&mut buffer)?;
+    WorkRequest::decode(buffer.as_slice())
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+fn write_response(writer: &mut dyn Write, protocol: Protocol, response: &WorkResponse) -> io::Result<()> {
+    match protocol {
+        Protocol::Proto => writer.write_all(&response.encode_length_delimited_to_vec())?,
+        Protocol::Json => {
+            serde_json::to_writer(&mut *writer, response)?;
+            writer.write_all(b"\n")?;
+        }
+    }
+    writer.flush()
+}
+
+/// Guesses the protocol from the first request: JSON requests start with `{`.
+pub fn detect_protocol(reader: &mut impl BufRead) -> io::Result<Option<Protocol>> {
+    loop {
+        let buffer = reader.fill_buf()?;
+        let Some(&first) = buffer.first() else { return Ok(None) };
+        if first.is_ascii_whitespace() {
+            reader.consume(1);
+            continue;
+        }
+        return Ok(Some(if first == b'{' { Protocol::Json } else { Protocol::Proto }));
+    }
+}
+
+/// Answers work requests from `reader` on `writer` until the build system closes the
+/// stream. `handle` returns the exit code and output of one request.
+pub fn serve(
+    reader: impl Read,
+    writer: &mut impl Write,
+    mut handle: impl FnMut(&WorkRequest) -> (i32, String),
+) -> io::Result<()> {
+    let mut reader = BufReader::new(reader);
+    let Some(protocol) = detect_protocol(&mut reader)? else { return Ok(()) };
+    let mut respond = |request: WorkRequest, writer: &mut dyn Write| -> io::Result<()> {
+        // Requests are handled in order, so a cancel arrives after its request is done
+        if request.cancel {
+            return Ok(());
+        }
+        let (exit_code, output) = handle(&request);
+        let response = WorkResponse { exit_code, output, request_id: request.request_id, was_cancelled: false };
+        write_response(writer, protocol, &response)
+    };
+    match protocol {
+        Protocol::Proto => {
+            while let Some(request) = read_proto_request(&mut reader)? {
+                respond(request, &mut *writer)?;
+            }
+        }
+        Protocol::Json => {
+            for request in serde_json::Deserializer::from_reader(reader).into_iter::<WorkRequest>() {
+                respond(request.map_err(io::Error::from)?, &mut *writer)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Takes over stdout for protocol messages and points file descriptor 1 at stderr, so
+/// progress lines printed while handling requests cannot corrupt the protocol stream.
+#[cfg(unix)]
+pub fn protocol_stdout() -> io::Result<Box<dyn Write>> {
+    use std::os::unix::io::FromRawFd;
+    io::stdout().flush()?;
+    // SAFETY: plain descriptor duplication; the duplicate is owned by the returned File
+    unsafe {
+        let protocol_fd = libc::dup(1);
+        if protocol_fd < 0 || libc::dup2(2, 1) < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Box::new(std::fs::File::from_raw_fd(protocol_fd)))
+    }
+}
+
+/// Without descriptor redirection, stdout is shared with progress output.
+#[cfg(not(unix))]
+pub fn protocol_stdout() -> io::Result<Box<dyn Write>> {
+    Ok(Box::new(io::stdout()))
+}
@@ -0,0 +1,408 @@
+use std::collections::BTreeMap;
+use std::ops::Range;
+use serde::Deserialize;
+use serde_json::Value as JsonValue;
+use serde_yaml::Value as YamlValue;
+
+/// Parses every document of a (possibly multi-document) YAML stream.
+pub fn parse_documents(content: &str) -> Result<Vec<YamlValue>, serde_yaml::Error> {
+    serde_yaml::Deserializer::from_str(content)
+        .map(YamlValue::deserialize)
+        .collect()
+}
+
+/// Byte range of each document in a YAML stream, split at `---` lines, in the order
+/// `parse_documents` returns them.
+pub fn document_ranges(content: &str) -> Vec<Range<usize>> {
+    let mut starts = Vec::new();
+    let mut offset = 0;
+    let mut seen_content = false;
+    for line in content.split_inclusive('\n') {
+        let text = line.trim_end();
+        if text == "---" || text.starts_with("--- ") {
+            // A leading `---` opens the first document rather than ending an empty one
+            if seen_content {
+                starts.push(offset);
+            } else if starts.is_empty() {
+                starts.push(0);
+            }
+            seen_content = true;
+        } else if !text.is_empty() && !text.starts_with('#') {
+            seen_content = true;
+        }
+        offset += line.len();
+    }
+    if starts.first() != Some(&0) {
+        starts.insert(0, 0);
+    }
+    starts.iter().enumerate().map(|(i, &start)| start..starts.get(i + 1).copied().unwrap_or(content.len())).collect()
+}
+
+// Ansible Vault ciphertext (`!vault |`) is an opaque string as far as schemas are concerned
+fn is_vault_tag(tag: &serde_yaml::value::Tag) -> bool {
+    // Tag equality ignores the leading `!`
+    tag == "vault"
+}
+
+/// What to do with a tag that has no mapping.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UnknownTagMode {
+    /// Drop the tag and keep its value
+    #[default]
+    Ignore,
+    /// Replace the node with a string (the scalar text, or the YAML of a collection)
+    String,
+    /// Reject the document
+    Error,
+}
+
+impl UnknownTagMode {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw {
+            "ignore" => Some(UnknownTagMode::Ignore),
+            "string" => Some(UnknownTagMode::String),
+            "error" => Some(UnknownTagMode::Error),
+            _ => None,
+        }
+    }
+}
+
+/// How a mapped tag is expanded: `!Tag value` becomes `{ key: value }`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagMapping {
+    pub key: String,
+    /// Split a scalar on the first `.` into a two-item list (`!GetAtt Res.Attr`)
+    pub split_dotted: bool,
+}
+
+/// Policy for custom YAML tags such as `!Ref` or `!Sub`.
+#[derive(Debug, Clone, Default)]
+pub struct TagPolicy {
+    pub unknown: UnknownTagMode,
+    /// Keyed by tag name without the leading `!`
+    pub mappings: BTreeMap<String, TagMapping>,
+}
+
+// CloudFormation short-form intrinsic functions and their long-form keys
+const CLOUDFORMATION_INTRINSICS: &[&str] = &[
+    "Base64", "Cidr", "FindInMap", "GetAZs", "ImportValue", "Join", "Select", "Split", "Sub",
+    "Transform", "And", "Equals", "If", "Not", "Or", "ToJsonString", "Length",
+];
+
+impl TagPolicy {
+    /// Adds the mappings of a built-in profile. Only `cloudformation` exists today.
+    pub fn with_profile(mut self, profile: &str) -> Option<Self> {
+        match profile {
+            "cloudformation" => {
+                for name in CLOUDFORMATION_INTRINSICS {
+                    self.mappings.insert(name.to_string(), TagMapping { key: format!("Fn::{}", name), split_dotted: false });
+                }
+                self.mappings.insert("GetAtt".to_string(), TagMapping { key: "Fn::GetAtt".to_string(), split_dotted: true });
+                self.mappings.insert("Ref".to_string(), TagMapping { key: "Ref".to_string(), split_dotted: false });
+                self.mappings.insert("Condition".to_string(), TagMapping { key: "Condition".to_string(), split_dotted: false });
+                Some(self)
+            }
+            _ => None,
+        }
+    }
+
+    /// Adds a user mapping written as `!Tag=Key` (the `!` is optional).
+    pub fn with_mapping(mut self, spec: &str) -> Option<Self> {
+        let (tag, key) = spec.split_once('=')?;
+        let tag = tag.trim().trim_start_matches('!');
+        if tag.is_empty() || key.trim().is_empty() {
+            return None;
+        }
+        self.mappings.insert(tag.to_string(), TagMapping { key: key.trim().to_string(), split_dotted: false });
+        Some(self)
+    }
+}
+
+/// Converts a YAML value to JSON for validation using the default tag policy
+/// (see `to_json_with_policy`).
+pub fn to_json(value: YamlValue) -> JsonValue {
+    to_json_with_policy(value, &TagPolicy::default()).expect("the default tag policy never rejects tags")
+}
+
+/// Converts a YAML value to JSON for validation.
+///
+/// Non-string mapping keys (`200:`, `true:`) become their YAML spelling, since JSON
+/// object keys must be strings. `!vault` values are always plain strings; other tags
+/// are expanded through the policy's mappings or handled per its unknown-tag mode.
+/// Returns the offending tag (with its `!`) when the policy rejects it.
+pub fn to_json_with_policy(value: YamlValue, policy: &TagPolicy) -> Result<JsonValue, String> {
+    let json = match value {
+        YamlValue::Null => JsonValue::Null,
+        YamlValue::Bool(b) => JsonValue::Bool(b),
+        YamlValue::Number(n) => serde_json::to_value(n).unwrap_or(JsonValue::Null),
+        YamlValue::String(s) => JsonValue::String(s),
+        YamlValue::Sequence(seq) => JsonValue::Array(
+            seq.into_iter()
+                .map(|v| to_json_with_policy(v, policy))
+                .collect::<Result<_, _>>()?,
+        ),
+        YamlValue::Mapping(map) => JsonValue::Object(
+            map.into_iter()
+                .map(|(k, v)| Ok((key_to_string(k), to_json_with_policy(v, policy)?)))
+                .collect::<Result<_, String>>()?,
+        ),
+        YamlValue::Tagged(tagged) if is_vault_tag(&tagged.tag) => match tagged.value {
+            YamlValue::String(ciphertext) => JsonValue::String(ciphertext),
+            other => to_json_with_policy(other, policy)?,
+        },
+        YamlValue::Tagged(tagged) => {
+            let tag_name = tagged.tag.to_string();
+            let tag_name = tag_name.trim_start_matches('!');
+            if let Some(mapping) = policy.mappings.get(tag_name) {
+                let inner = match (mapping.split_dotted, tagged.value) {
+                    (true, YamlValue::String(dotted)) => match dotted.split_once('.') {
+                        Some((head, tail)) => serde_json::json!([head, tail]),
+                        None => JsonValue::String(dotted),
+                    },
+                    (_, other) => to_json_with_policy(other, policy)?,
+                };
+                let mut expanded = serde_json::Map::new();
+                expanded.insert(mapping.key.clone(), inner);
+                JsonValue::Object(expanded)
+            } else {
+                match policy.unknown {
+                    UnknownTagMode::Ignore => to_json_with_policy(tagged.value, policy)?,
+                    UnknownTagMode::String => JsonValue::String(scalar_text(tagged.value)),
+                    UnknownTagMode::Error => return Err(format!("!{}", tag_name)),
+                }
+            }
+        }
+    };
+    Ok(json)
+}
+
+// Text of a scalar as written, or the YAML rendering of a collection
+fn scalar_text(value: YamlValue) -> String {
+    match value {
+        YamlValue::String(s) => s,
+        YamlValue::Null => String::new(),
+        other => serde_yaml::to_string(&other).unwrap_or_default().trim_end().to_string(),
+    }
+}
+
+fn key_to_string(key: YamlValue) -> String {
+    match key {
+        YamlValue::String(s) => s,
+        other => serde_yaml::to_string(&other).unwrap_or_default().trim().to_string(),
+    }
+}
+
+/// A YAML construct that JSON cannot hold faithfully, so validating the converted document
+/// would check something other than what the file says.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unrepresentable {
+    /// Short name: `complex key`, `colliding keys`, `!!set`, `!!binary` or `non-finite number`
+    pub construct: String,
+    pub message: String,
+    /// JSON pointer of the mapping or value holding the construct
+    pub pointer: String,
+    /// Text to look for in the source to place the label
+    pub needle: String,
+}
+
+// Tag name without `!`/`!!` or the `tag:yaml.org,2002:` prefix
+fn core_tag_name(tag: &serde_yaml::value::Tag) -> String {
+    let text = tag.to_string();
+    let text = text.trim_start_matches('!');
+    text.strip_prefix("tag:yaml.org,2002:").unwrap_or(text).to_string()
+}
+
+// First scalar inside a value, as written, to locate a complex key in the source
+fn first_scalar(value: &YamlValue) -> Option<String> {
+    match value {
+        YamlValue::Sequence(items) => items.iter().find_map(first_scalar),
+        YamlValue::Mapping(map) => map.iter().find_map(|(k, v)| first_scalar(k).or_else(|| first_scalar(v))),
+        YamlValue::Tagged(tagged) => first_scalar(&tagged.value),
+        other => Some(key_to_string(other.clone())),
+    }
+}
+
+/// Finds the first construct (in document order) that `to_json_with_policy` cannot convert
+/// without losing information: collection keys, keys that collide once stringified (`1` and
+/// `"1"`), `!!set` and `!!binary` values, and `.nan`/`.inf`. Plain scalar keys such as `200:`
+/// are fine; they become their YAML spelling. `source` is the document's text: serde_yaml
+/// resolves core-schema tags itself, so `!!set` and `!!binary` are looked for there.
+pub fn find_unrepresentable(value: &YamlValue, source: &str) -> Option<Unrepresentable> {
+    find_core_tag(source).or_else(|| find_unrepresentable_at(value, ""))
+}
+
+// Something a block-style line opens at a column: a sequence item or a `key:`
+enum BlockToken {
+    Item,
+    Key(String),
+}
+
+// The `- ` items and `key:` a line opens, with their columns, e.g. `  - name: x` is an item at
+// 2 and the key `name` at 4
+fn block_tokens(line: &str) -> Vec<(usize, BlockToken)> {
+    let mut tokens = Vec::new();
+    let mut column = line.len() - line.trim_start_matches(' ').len();
+    loop {
+        let rest = &line[column..];
+        if rest == "-" || rest.starts_with("- ") {
+            tokens.push((column, BlockToken::Item));
+            column += 1 + rest[1..].len() - rest[1..].trim_start_matches(' ').len();
+            continue;
+        }
+        let key = match rest.chars().next() {
+            Some(quote @ ('"' | '\'')) => rest[1..].find(quote).and_then(|end| {
+                let after = &rest[end + 2..];
+                (after.starts_with(':')).then(|| rest[1..end + 1].to_string())
+            }),
+            Some(c) if !"#!&*?|>[]{}%@`".contains(c) => {
+                let end = rest.find(": ").or_else(|| rest.strip_suffix(':').map(str::len));
+                end.map(|end| rest[..end].trim_end().to_string()).filter(|key| !key.contains(" #"))
+            }
+            _ => None,
+        };
+        if let Some(key) = key {
+            tokens.push((column, BlockToken::Key(key)));
+        }
+        return tokens;
+    }
+}
+
+// Position among its siblings of the sequence item opened at `column` on `lines[index]`
+fn item_index(lines: &[&str], index: usize, column: usize) -> usize {
+    let mut count = 0;
+    for line in lines[..index].iter().rev() {
+        let tokens = block_tokens(line);
+        let Some((indent, _)) = tokens.first() else { continue };
+        let item_here = tokens.iter().any(|(c, token)| *c == column && matches!(token, BlockToken::Item));
+        if *indent < column {
+            return count + usize::from(item_here);
+        }
+        if *indent == column {
+            if !item_here {
+                return count;
+            }
+            count += 1;
+        }
+    }
+    count
+}
+
+// JSON pointer of the value at `column` of `lines[index]`, from the block structure above it
+fn block_pointer(lines: &[&str], index: usize, column: usize) -> String {
+    let mut segments = Vec::new();
+    let mut column = column;
+    let mut index = index;
+    loop {
+        let tokens = block_tokens(lines[index]);
+        for (token_column, token) in tokens.iter().rev().filter(|(c, _)| *c < column) {
+            segments.push(match token {
+                BlockToken::Key(key) => key.replace('~', "~0").replace('/', "~1"),
+                BlockToken::Item => item_index(lines, index, *token_column).to_string(),
+            });
+        }
+        column = match tokens.first() {
+            Some((indent, _)) => (*indent).min(column),
+            None => column,
+        };
+        // The closest line above that opens something to the left of this one
+        let parent = lines[..index].iter().rposition(|line| {
+            let indent = line.len() - line.trim_start_matches(' ').len();
+            indent < column && !line.trim().is_empty() && !line.trim_start().starts_with('#')
+        });
+        match parent {
+            Some(parent) => index = parent,
+            None => break,
+        }
+    }
+    segments.iter().rev().map(|segment| format!("/{}", segment)).collect()
+}
+
+// The first `!!set` or `!!binary` tag written in block-style YAML, outside comments
+fn find_core_tag(source: &str) -> Option<Unrepresentable> {
+    let lines: Vec<&str> = source.lines().collect();
+    for (index, line) in lines.iter().enumerate() {
+        let code = line.find(" #").map_or(*line, |comment| &line[..comment]);
+        for (name, message) in [
+            ("set", "a YAML set has no JSON equivalent (it would become a mapping of nulls)"),
+            ("binary", "binary data would be validated as its base64 text"),
+        ] {
+            let needle = format!("!!{}", name);
+            let found = code.match_indices(&needle).find(|(at, _)| {
+                let before = code[..*at].chars().next_back();
+                let after = code[at + needle.len()..].chars().next();
+                before.is_none_or(|c| c.is_whitespace()) && after.is_none_or(|c| c.is_whitespace())
+            });
+            if let Some((column, _)) = found {
+                return Some(Unrepresentable {
+                    construct: needle.clone(),
+                    message: message.to_string(),
+                    pointer: block_pointer(&lines, index, column),
+                    needle,
+                });
+            }
+        }
+    }
+    None
+}
+
+fn find_unrepresentable_at(value: &YamlValue, pointer: &str) -> Option<Unrepresentable> {
+    match value {
+        YamlValue::Number(n) if n.as_f64().is_some_and(|f| !f.is_finite()) => Some(Unrepresentable {
+            construct: "non-finite number".to_string(),
+            message: format!("`{}` has no JSON number equivalent", n),
+            pointer: pointer.to_string(),
+            needle: if n.as_f64().is_some_and(f64::is_nan) { ".nan".to_string() } else { ".inf".to_string() },
+        }),
+        YamlValue::Sequence(items) => items
+            .iter()
+            .enumerate()
+            .find_map(|(index, item)| find_unrepresentable_at(item, &format!("{}/{}", pointer, index))),
+        YamlValue::Mapping(map) => {
+            let mut seen: Vec<(String, &YamlValue)> = Vec::new();
+            for (key, child) in map {
+                if matches!(key, YamlValue::Sequence(_) | YamlValue::Mapping(_)) {
+                    return Some(Unrepresentable {
+                        construct: "complex key".to_string(),
+                        message: "a sequence or mapping used as a key cannot be a JSON object key".to_string(),
+                        pointer: pointer.to_string(),
+                        needle: first_scalar(key).unwrap_or_default(),
+                    });
+                }
+                let name = key_to_string(key.clone());
+                if let Some((_, earlier)) = seen.iter().find(|(seen_name, _)| *seen_name == name) {
+                    return Some(Unrepresentable {
+                        construct: "colliding keys".to_string(),
+                        message: format!(
+                            "keys {} and {} both become `{}` in JSON",
+                            serde_yaml::to_string(earlier).unwrap_or_default().trim(),
+                            serde_yaml::to_string(key).unwrap_or_default().trim(),
+                            name
+                        ),
+                        pointer: pointer.to_string(),
+                        needle: name,
+                    });
+                }
+                let child_pointer = format!("{}/{}", pointer, name.replace('~', "~0").replace('/', "~1"));
+                if let Some(found) = find_unrepresentable_at(child, &child_pointer) {
+                    return Some(found);
+                }
+                seen.push((name, key));
+            }
+            None
+        }
+        YamlValue::Tagged(tagged) => match core_tag_name(&tagged.tag).as_str() {
+            name @ ("set" | "binary") => Some(Unrepresentable {
+                construct: format!("!!{}", name),
+                message: if name == "set" {
+                    "a YAML set has no JSON equivalent (it would become a mapping of nulls)".to_string()
+                } else {
+                    "binary data would be validated as its base64 text".to_string()
+                },
+                pointer: pointer.to_string(),
+                needle: format!("!!{}", name),
+            }),
+            _ => find_unrepresentable_at(&tagged.value, pointer),
+        },
+        _ => None,
+    }
+}
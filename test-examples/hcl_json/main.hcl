@@ -0,0 +1,14 @@
+# comment
+resource "aws_instance" "web" {
+  ami           = "ami-123"
+  instance_type = "t3.micro"
+}
+
+resource "aws_instance" "db" {
+  ami           = "ami-456"
+  instance_type = "t3.large"
+}
+
+variable "region" {
+  default = "eu-west-1"
+}
@@ -0,0 +1,5 @@
+name: {{ .Values.name }}
+version: "2.0.0"
+{% if production %}
+port: 8443
+{% endif %}
@@ -0,0 +1,46 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use serde::Deserialize;
+
+/// Settings of the example service.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Name shown in logs
+    pub name: String,
+    pub server: Server,
+    #[serde(default)]
+    pub log_level: LogLevel,
+    pub data_dir: Option<PathBuf>,
+    #[serde(default)]
+    pub features: Vec<String>,
+    #[serde(default)]
+    pub upstreams: HashMap<String, Upstream>,
+    #[serde(skip)]
+    pub loaded_from: Option<PathBuf>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Server {
+    pub host: String,
+    pub port: u16,
+    pub max_connections: Option<u32>,
+    #[serde(rename = "tls")]
+    pub tls_enabled: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Debug,
+    #[default]
+    Info,
+    Warn,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Upstream {
+    pub url: String,
+    pub weight: f64,
+}
@@ -0,0 +1,7 @@
+name:    "my-app-cue"
+version: "1.4.0"
+port:    8443
+database: {
+	host: "cue-db.example.com"
+	port: 5432
+}
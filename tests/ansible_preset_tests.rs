@@ -0,0 +1,55 @@
+use std::path::{Path, PathBuf};
+use serde_json::json;
+use toml_and_jerry::ansible::{check_plays_have_hosts, ANSIBLE_RULES};
+use toml_and_jerry::error::AppError;
+use toml_and_jerry::validation::validate_inputs;
+use toml_and_jerry::yaml;
+use jsonschema::Validator;
+
+#[test]
+fn test_ansible_rules_match_layout() {
+    let rule_for = |p: &str| ANSIBLE_RULES.iter().find(|r| (r.matches)(Path::new(p))).map(|r| r.name);
+    assert_eq!(rule_for("playbooks/site.yml"), Some("ansible-playbook"));
+    assert_eq!(rule_for("site.yml"), Some("ansible-playbook"));
+    assert_eq!(rule_for("roles/web/meta/argument_specs.yml"), Some("ansible-role-argument-spec"));
+    assert_eq!(rule_for("inventory/hosts.yml"), Some("ansible-inventory"));
+    assert_eq!(rule_for("inventory/group_vars/all.yml"), None);
+}
+
+#[test]
+fn test_vault_strings_are_opaque_and_documents_are_split() {
+    let content = std::fs::read_to_string("test-examples/ansible/playbooks/site.yml").unwrap();
+    let documents = yaml::parse_documents(&content).expect("Playbook with !vault should parse");
+    assert_eq!(documents.len(), 2);
+    let first = yaml::to_json(documents[0].clone());
+    let password = first[0]["vars"]["db_password"].as_str().expect("Vault value should be a string");
+    assert!(password.starts_with("$ANSIBLE_VAULT"));
+}
+
+#[test]
+fn test_every_document_is_validated() {
+    // The second document is a bare import, which this schema rejects
+    let validator = Validator::new(&json!({ "type": "array", "items": { "required": ["hosts"] } })).unwrap();
+    let errors = validate_inputs(vec![PathBuf::from("test-examples/ansible/playbooks/site.yml")], &validator).unwrap();
+    assert!(errors.iter().any(|e| matches!(
+        e,
+        AppError::SchemaValidationError(error) if error.label_message.starts_with("Document 1")
+    )));
+    assert!(errors.iter().any(|e| matches!(
+        e,
+        AppError::SchemaValidationError(error) if error.label_message.starts_with("Document 2")
+    )));
+}
+
+#[test]
+fn test_plays_without_hosts_are_reported() {
+    let path = "test-examples/ansible/playbooks/site.yml";
+    let content = std::fs::read_to_string(path).unwrap();
+    let documents = yaml::parse_documents(&content).unwrap();
+    let errors = check_plays_have_hosts(Path::new(path), &content, &yaml::to_json(documents[0].clone()));
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(&errors[0], AppError::SemanticError { check, .. } if check == "play-hosts"));
+
+    let errors = check_plays_have_hosts(Path::new(path), &content, &yaml::to_json(documents[1].clone()));
+    assert!(errors.is_empty(), "import_playbook entries are plays too");
+}
@@ -0,0 +1,43 @@
+use std::path::{Path, PathBuf};
+use serde_json::json;
+use toml_and_jerry::arm::{compile_parameters_validator, parameters_file_schema};
+use toml_and_jerry::error::AppError;
+use toml_and_jerry::validation::validate_inputs;
+use jsonschema::Validator;
+
+fn template_validator() -> Validator {
+    compile_parameters_validator(Path::new("test-examples/arm/template.json")).expect("Template should compile")
+}
+
+#[test]
+fn test_valid_parameters_file() {
+    let errors = validate_inputs(vec![PathBuf::from("test-examples/arm/valid.parameters.json")], &template_validator()).unwrap();
+    assert!(errors.is_empty(), "Valid parameters file should pass: {:?}", errors);
+}
+
+#[test]
+fn test_invalid_parameters_file() {
+    let errors = validate_inputs(vec![PathBuf::from("test-examples/arm/invalid.parameters.json")], &template_validator()).unwrap();
+    assert!(errors.iter().any(|e| matches!(e, AppError::SchemaValidationError { .. })));
+}
+
+#[test]
+fn test_missing_mistyped_and_unknown_parameters() {
+    let template: serde_json::Value = serde_json::from_str(&std::fs::read_to_string("test-examples/arm/template.json").unwrap()).unwrap();
+    let validator = Validator::new(&parameters_file_schema(&template).unwrap()).unwrap();
+    let base = json!({ "siteName": { "value": "web" }, "instanceCount": { "value": 1 }, "adminPassword": { "value": "x" } });
+
+    assert!(validator.is_valid(&json!({ "parameters": base })));
+
+    let mut missing = base.clone();
+    missing.as_object_mut().unwrap().remove("adminPassword");
+    assert!(!validator.is_valid(&json!({ "parameters": missing })), "Parameters without defaults are required");
+
+    let mut mistyped = base.clone();
+    mistyped["instanceCount"] = json!({ "value": 11 });
+    assert!(!validator.is_valid(&json!({ "parameters": mistyped })), "maxValue should be enforced");
+
+    let mut unknown = base.clone();
+    unknown["location"] = json!({ "value": "westeurope" });
+    assert!(!validator.is_valid(&json!({ "parameters": unknown })), "Undeclared parameters should be rejected");
+}
@@ -0,0 +1,47 @@
+use std::process::Command;
+use toml_and_jerry::error::{Category, Severity};
+use toml_and_jerry::merge::{self, ReportEntry};
+use toml_and_jerry::validation::PrintableError;
+
+#[test]
+fn test_azure_escapes_property_and_message_values() {
+    let entry = ReportEntry {
+        diagnostic: PrintableError {
+            file_path: "configs/a;b].json".to_string(),
+            error_type: "SchemaValidationError".to_string(),
+            message: "100% wrong\nsecond line".to_string(),
+            line: Some(3),
+            column: Some(7),
+            json_path: Some("/port".to_string()),
+            rule_id: "app::schema::validation".to_string(),
+            severity: Some(Severity::Error),
+            category: Some(Category::Schema),
+            snippet: None,
+            violation: None,
+        },
+        level: "error".to_string(),
+    };
+
+    assert_eq!(
+        merge::to_azure(&[entry]),
+        "##vso[task.logissue type=error;sourcepath=configs/a%3Bb%5D.json;linenumber=3;columnnumber=7;code=app::schema::validation;]100%AZP25 wrong%0Asecond line\n"
+    );
+}
+
+#[test]
+fn test_azure_format_prints_logissue_lines_with_line_numbers() {
+    let output = Command::new("cargo")
+        .args(["run", "--", "check", "--schema", "test-examples/schema.json", "--format", "azure"])
+        .args(["test-examples/invalid-config.json", "test-examples/valid-config.json"])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert!(!lines.is_empty(), "{}", stdout);
+    for line in lines {
+        assert!(line.starts_with("##vso[task.logissue type=error;sourcepath=test-examples/invalid-config.json;linenumber="), "{}", line);
+        assert!(line.contains(";code=app::"), "{}", line);
+    }
+}
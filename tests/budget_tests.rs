@@ -0,0 +1,61 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use jsonschema::Validator;
+use toml_and_jerry::error::AppError;
+use toml_and_jerry::project_config::{evaluate_budgets, load_project_config};
+use toml_and_jerry::validation::validate_inputs;
+
+const INPUTS: [&str; 3] = [
+    "test-examples/budgets/team-a/api.json",
+    "test-examples/budgets/team-a/worker.json",
+    "test-examples/budgets/team-b/api.json",
+];
+
+fn diagnostics(inputs: &[&str]) -> Vec<AppError> {
+    let schema: serde_json::Value = serde_json::from_str(&std::fs::read_to_string("test-examples/schema.json").unwrap()).unwrap();
+    let validator = Validator::new(&schema).unwrap();
+    validate_inputs(inputs.iter().map(PathBuf::from).collect(), &validator).unwrap()
+}
+
+#[test]
+fn test_errors_within_budget_pass() {
+    let config = load_project_config(Some(Path::new("test-examples/budgets/within.toml"))).unwrap();
+    let report = evaluate_budgets(&config.budget, &diagnostics(&INPUTS));
+    let counts: Vec<(&str, usize)> = report.usage.iter().map(|u| (u.area.as_str(), u.errors)).collect();
+    assert_eq!(counts, [("test-examples/budgets/team-a", 2), ("test-examples/budgets/team-b", 1)]);
+    assert!(!report.fails());
+    assert!(report.render_human().contains("test-examples/budgets/team-a: 2/2 errors, 0 warnings (ok)"));
+}
+
+#[test]
+fn test_deepest_budget_counts_and_can_be_exceeded() {
+    let config = load_project_config(Some(Path::new("test-examples/budgets/over.toml"))).unwrap();
+    let report = evaluate_budgets(&config.budget, &diagnostics(&INPUTS));
+    let counts: Vec<(usize, bool)> = report.usage.iter().map(|u| (u.errors, u.exceeded())).collect();
+    // team-b's error counts against the parent directory, team-a's against its own budget
+    assert_eq!(counts, [(1, false), (2, true)]);
+    assert!(report.fails());
+}
+
+#[test]
+fn test_errors_outside_every_budget_still_fail() {
+    let config = load_project_config(Some(Path::new("test-examples/budgets/within.toml"))).unwrap();
+    let report = evaluate_budgets(&config.budget, &diagnostics(&["test-examples/missing-required-fields.json"]));
+    assert_eq!(report.unbudgeted_errors, 1);
+    assert!(report.fails());
+}
+
+#[test]
+fn test_cli_budget_decides_exit_code() {
+    let run = |config: &str| {
+        Command::new("cargo")
+            .args(["run", "--", "check", "--schema", "test-examples/schema.json", "--config", config])
+            .args(INPUTS)
+            .output()
+            .expect("Failed to execute command")
+    };
+    let within = run("test-examples/budgets/within.toml");
+    assert!(within.status.success(), "stderr: {}", String::from_utf8_lossy(&within.stderr));
+    assert!(String::from_utf8_lossy(&within.stdout).contains("--- Budgets ---"));
+    assert!(!run("test-examples/budgets/over.toml").status.success());
+}
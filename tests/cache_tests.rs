@@ -0,0 +1,99 @@
+use std::process::Command;
+
+fn temp_cache_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("toml-and-jerry-{}-{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).expect("Failed to create temp dir");
+    dir
+}
+
+#[test]
+fn test_cache_path_respects_xdg_cache_home() {
+    let xdg = temp_cache_dir("xdg");
+    let output = Command::new("cargo")
+        .args(["run", "--", "cache", "path"])
+        .env("XDG_CACHE_HOME", &xdg)
+        .env_remove("TOML_AND_JERRY_CACHE_DIR")
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let expected = xdg.join("toml-and-jerry");
+    assert!(stdout.contains(&expected.display().to_string()), "Cache path should live under XDG_CACHE_HOME");
+
+    std::fs::remove_dir_all(&xdg).ok();
+}
+
+#[test]
+fn test_cache_ls_and_clear() {
+    let cache = temp_cache_dir("explicit");
+    std::fs::create_dir_all(cache.join("schemas")).unwrap();
+    std::fs::write(cache.join("schemas").join("abc.json"), "{}").unwrap();
+
+    let output = Command::new("cargo")
+        .args(["run", "--", "cache", "ls"])
+        .env("TOML_AND_JERRY_CACHE_DIR", &cache)
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("abc.json"), "Cached schema should be listed");
+
+    let output = Command::new("cargo")
+        .args(["run", "--", "cache", "clear"])
+        .env("TOML_AND_JERRY_CACHE_DIR", &cache)
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+    assert!(!cache.exists(), "Cache directory should be removed");
+}
+
+#[test]
+fn test_cache_clear_keeps_files_it_did_not_write() {
+    let cache = temp_cache_dir("shared");
+    std::fs::create_dir_all(cache.join("schemas")).unwrap();
+    std::fs::write(cache.join("schemas").join("abc.json"), "{}").unwrap();
+    std::fs::create_dir_all(cache.join("rule-packs").join("0123")).unwrap();
+    std::fs::write(cache.join("rule-packs").join("0123").join("pack.toml"), "").unwrap();
+    std::fs::write(cache.join("notes.txt"), "mine").unwrap();
+
+    let output = Command::new("cargo")
+        .args(["run", "--", "cache", "clear"])
+        .env("TOML_AND_JERRY_CACHE_DIR", &cache)
+        .output()
+        .expect("Failed to execute command");
+    let kept = cache.join("notes.txt").exists();
+    let cleared = !cache.join("schemas").exists() && !cache.join("rule-packs").exists();
+    std::fs::remove_dir_all(&cache).ok();
+
+    assert!(output.status.success());
+    assert!(cleared, "The schema and rules pack caches should be removed");
+    assert!(kept, "Other files in TOML_AND_JERRY_CACHE_DIR should be kept");
+}
+
+#[test]
+fn test_error_responses_are_not_cached() {
+    let cache = temp_cache_dir("error-status");
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/schema.json", listener.local_addr().unwrap());
+    let server = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut request = [0; 1024];
+        let _ = std::io::Read::read(&mut stream, &mut request);
+        let body = "{\"error\": \"not found\"}";
+        let response = format!("HTTP/1.1 404 Not Found\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", body.len(), body);
+        std::io::Write::write_all(&mut stream, response.as_bytes()).unwrap();
+    });
+
+    let output = Command::new("cargo")
+        .args(["run", "--", "check", "--schema", &url, "test-examples/valid-config.json"])
+        .env("TOML_AND_JERRY_CACHE_DIR", &cache)
+        .output()
+        .expect("Failed to execute command");
+    server.join().unwrap();
+    let cached = cache.join("schemas").exists();
+    std::fs::remove_dir_all(&cache).ok();
+
+    assert!(!output.status.success(), "A 404 should fail the run: {}", String::from_utf8_lossy(&output.stdout));
+    assert!(!cached, "The error page should not be cached as the schema");
+}
@@ -0,0 +1,30 @@
+use std::process::Command;
+use toml_and_jerry::capabilities::capabilities;
+
+#[test]
+fn test_capabilities_cover_formats_and_codes() {
+    let caps = capabilities();
+    assert!(caps.input_formats.iter().any(|f| f.name == "toml" && f.extensions == ["toml"]));
+    assert_eq!(caps.output_formats, ["human", "json", "ndjson", "sarif", "csv", "compact", "codeclimate", "azure", "template"]);
+    assert!(caps.presets.contains(&"cloudformation"));
+
+    let renamed = caps.diagnostics.iter().find(|d| d.code == "app::schema::renamed_property").unwrap();
+    assert_eq!(renamed.default_severity, "warning");
+    let mut codes: Vec<_> = caps.diagnostics.iter().map(|d| d.code).collect();
+    codes.sort();
+    codes.dedup();
+    assert_eq!(codes.len(), caps.diagnostics.len(), "Diagnostic codes should be listed once");
+}
+
+#[test]
+fn test_capabilities_json_output() {
+    let output = Command::new("cargo")
+        .args(["run", "--", "capabilities", "--format", "json"])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+    let listing: serde_json::Value = serde_json::from_slice(&output.stdout).expect("Output should be JSON");
+    assert_eq!(listing["version"], env!("CARGO_PKG_VERSION"));
+    assert!(listing["diagnostics"].as_array().unwrap().iter().any(|d| d["code"] == "app::schema::validation_error"));
+    assert!(listing["schemaDrafts"].as_array().unwrap().len() >= 5);
+}
@@ -0,0 +1,38 @@
+use std::path::PathBuf;
+use std::process::Command;
+use toml_and_jerry::error::{AppError, Category, Severity};
+use toml_and_jerry::validation::PrintableError;
+
+#[test]
+fn test_wrappers_take_the_category_of_what_they_wrap() {
+    let inner = AppError::UnexpectedlyValid { path: PathBuf::from("bad.json") };
+    assert_eq!(inner.category(), Category::Policy);
+    assert_eq!(inner.level(), Severity::Error);
+
+    let downgraded = AppError::Downgraded { inner: Box::new(inner) };
+    assert_eq!(downgraded.category(), Category::Policy);
+    assert_eq!(downgraded.level(), Severity::Warning);
+}
+
+#[test]
+fn test_reports_without_severity_or_category_still_load() {
+    let old: PrintableError = serde_json::from_str(
+        r#"{"filePath": "a.json", "errorType": "X", "message": "m", "line": null, "column": null, "jsonPath": null, "ruleId": "app::io::read_file"}"#,
+    )
+    .unwrap();
+    assert_eq!(old.severity, None);
+    assert_eq!(old.category, None);
+}
+
+#[test]
+fn test_json_report_carries_severity_and_category() {
+    let output = Command::new("cargo")
+        .args(["run", "--", "check", "test-examples/invalid-config.json", "--schema", "test-examples/schema.json", "--format", "json"])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).expect("stdout should be the report");
+    assert_eq!(report["errors"][0]["severity"], "error");
+    assert_eq!(report["errors"][0]["category"], "schema");
+}
@@ -0,0 +1,55 @@
+use std::path::Path;
+use toml_and_jerry::ci::{check_azure_depends_on_references, check_github_needs, check_gitlab_needs, CI_RULES};
+use toml_and_jerry::error::AppError;
+use serde_json::Value as JsonValue;
+
+fn load(path: &str) -> (String, JsonValue) {
+    let content = std::fs::read_to_string(path).expect("Failed to read fixture");
+    let document = serde_yaml::from_str(&content).expect("Fixture should be valid YAML");
+    (content, document)
+}
+
+fn missing_references(errors: &[AppError]) -> Vec<String> {
+    errors
+        .iter()
+        .map(|e| match e {
+            AppError::SemanticError { span, source_code, .. } => {
+                source_code[span.offset()..span.offset() + span.len()].to_string()
+            }
+            other => panic!("Unexpected error {:?}", other),
+        })
+        .collect()
+}
+
+#[test]
+fn test_ci_rules_match_well_known_paths() {
+    let rule_for = |p: &str| CI_RULES.iter().find(|r| (r.matches)(Path::new(p))).map(|r| r.name);
+    assert_eq!(rule_for("repo/.github/workflows/build.yml"), Some("github-workflow"));
+    assert_eq!(rule_for(".gitlab-ci.yml"), Some("gitlab-ci"));
+    assert_eq!(rule_for("azure-pipelines.yml"), Some("azure-pipelines"));
+    assert_eq!(rule_for("config/app.yml"), None);
+}
+
+#[test]
+fn test_github_needs_must_reference_existing_jobs() {
+    let path = "test-examples/ci/.github/workflows/build.yml";
+    let (content, document) = load(path);
+    let errors = check_github_needs(Path::new(path), &content, &document);
+    assert_eq!(missing_references(&errors), vec!["lint"]);
+}
+
+#[test]
+fn test_gitlab_needs_must_reference_existing_jobs() {
+    let path = "test-examples/ci/.gitlab-ci.yml";
+    let (content, document) = load(path);
+    let errors = check_gitlab_needs(Path::new(path), &content, &document);
+    assert_eq!(missing_references(&errors), vec!["compile"]);
+}
+
+#[test]
+fn test_azure_depends_on_must_reference_siblings() {
+    let path = "test-examples/ci/azure-pipelines.yml";
+    let (content, document) = load(path);
+    let errors = check_azure_depends_on_references(Path::new(path), &content, &document);
+    assert_eq!(missing_references(&errors), vec!["Package"]);
+}
@@ -0,0 +1,31 @@
+use std::path::Path;
+use toml_and_jerry::cloudformation::{check_references, CLOUDFORMATION_RULES};
+use toml_and_jerry::error::AppError;
+use toml_and_jerry::yaml::{parse_documents, to_json_with_policy, TagPolicy};
+
+#[test]
+fn test_template_paths_are_recognised() {
+    let matches = |p: &str| CLOUDFORMATION_RULES.iter().any(|r| (r.matches)(Path::new(p)));
+    assert!(matches("infra/app.template.yaml"));
+    assert!(matches("cloudformation/network.yml"));
+    assert!(matches("template.json"));
+    assert!(!matches("config/app.yaml"));
+}
+
+#[test]
+fn test_short_form_references_are_checked() {
+    let path = "test-examples/cloudformation/app.template.yaml";
+    let content = std::fs::read_to_string(path).unwrap();
+    let policy = TagPolicy::default().with_profile("cloudformation").unwrap();
+    let document = to_json_with_policy(parse_documents(&content).unwrap().remove(0), &policy).unwrap();
+
+    let errors = check_references(Path::new(path), &content, &document);
+    assert_eq!(errors.len(), 1, "Only the misspelled GetAtt target should be reported: {:?}", errors);
+    match &errors[0] {
+        AppError::SemanticError { message, span, source_code, .. } => {
+            assert!(message.contains("Buckett"));
+            assert_eq!(&source_code[span.offset()..span.offset() + span.len()], "Buckett");
+        }
+        other => panic!("Unexpected error {:?}", other),
+    }
+}
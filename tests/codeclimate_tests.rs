@@ -0,0 +1,42 @@
+use std::process::Command;
+
+fn check(inputs: &[&str]) -> (bool, serde_json::Value) {
+    let output = Command::new("cargo")
+        .args(["run", "--", "check", "--schema", "test-examples/schema.json", "--format", "codeclimate"])
+        .args(inputs)
+        .output()
+        .expect("Failed to execute command");
+    let issues = serde_json::from_slice(&output.stdout).expect("stdout should be a JSON array");
+    (output.status.success(), issues)
+}
+
+#[test]
+fn test_codeclimate_issue_fields() {
+    let (success, issues) = check(&["test-examples/invalid-config.json"]);
+
+    assert!(!success);
+    let issue = &issues[0];
+    assert_eq!(issue["type"], "issue");
+    assert_eq!(issue["check_name"], "app::schema::validation_error");
+    assert_eq!(issue["categories"], serde_json::json!(["Bug Risk"]));
+    assert_eq!(issue["severity"], "major");
+    assert_eq!(issue["location"]["path"], "test-examples/invalid-config.json");
+    assert!(issue["location"]["positions"]["begin"]["line"].as_u64().is_some(), "{}", issue);
+    assert_eq!(issue["fingerprint"].as_str().map(str::len), Some(64));
+}
+
+#[test]
+fn test_codeclimate_fingerprints_are_stable() {
+    let (_, first) = check(&["test-examples/invalid-config.json"]);
+    let (_, second) = check(&["test-examples/invalid-config.json"]);
+
+    assert_eq!(first[0]["fingerprint"], second[0]["fingerprint"]);
+}
+
+#[test]
+fn test_codeclimate_without_issues_is_an_empty_array() {
+    let (success, issues) = check(&["test-examples/valid-config.json"]);
+
+    assert!(success);
+    assert_eq!(issues, serde_json::json!([]));
+}
@@ -0,0 +1,95 @@
+use std::path::PathBuf;
+use std::process::Command;
+use serde_json::json;
+use toml_and_jerry::coercion::coerce;
+use toml_and_jerry::error::{AppError, CoercibleType};
+use toml_and_jerry::fix::{apply_edits, fixes_for};
+use toml_and_jerry::schema::load_and_compile_schema;
+use toml_and_jerry::validation::{validate_inputs, PrintableError};
+
+const SCHEMA: &str = "test-examples/coercion/schema.json";
+
+fn validate(file: &str) -> Vec<AppError> {
+    let validator = load_and_compile_schema(&PathBuf::from(SCHEMA)).unwrap();
+    validate_inputs(vec![PathBuf::from(file)], &validator).unwrap()
+}
+
+#[test]
+fn test_coercions_are_lexical_only() {
+    let to = |actual, expected| coerce(&actual, &expected).map(|c| (c.expected, c.found, c.value));
+    assert_eq!(to(json!("8080"), json!("integer")), Some(("integer", "string", json!(8080))));
+    assert_eq!(to(json!("1.5"), json!(["null", "number"])), Some(("number", "string", json!(1.5))));
+    assert_eq!(to(json!("false"), json!("boolean")), Some(("boolean", "string", json!(false))));
+    assert_eq!(to(json!(1.1), json!("string")), Some(("string", "number", json!("1.1"))));
+    assert_eq!(to(json!(8080.0), json!("integer")), Some(("integer", "number", json!(8080))));
+    assert_eq!(to(json!("eighty"), json!("integer")), None);
+    assert_eq!(to(json!("NaN"), json!("number")), None, "Not how a config writes a number");
+    assert_eq!(to(json!("yes"), json!("boolean")), None);
+    assert_eq!(to(json!(8080.5), json!("integer")), None);
+}
+
+#[test]
+fn test_each_format_gets_a_fix_at_the_value() {
+    for (file, written, fixed) in [
+        ("test-examples/coercion/service.yaml", "\"8080\"", "name: billing\nport: 8080\n"),
+        ("test-examples/coercion/service.json", "\"true\"", "{\n  \"name\": \"billing\",\n  \"debug\": true\n}\n"),
+        ("test-examples/coercion/service.toml", "2", "name = \"billing\"\nversion = \"2\"\n"),
+        ("test-examples/coercion/service.hjson", "\"8080\"", "# Billing service\n{\n  name: billing\n  port: 8080\n}\n"),
+    ] {
+        let errors = validate(file);
+        let [error @ AppError::CoercibleType(coercible)] = &errors[..] else { panic!("{}: {:?}", file, errors) };
+        let CoercibleType { span, source_code, .. } = coercible.as_ref();
+        assert_eq!(&source_code[span.offset()..span.offset() + span.len()], written, "{}", file);
+        assert_eq!(apply_edits(source_code, &fixes_for(error)), fixed);
+    }
+}
+
+#[test]
+fn test_report_keeps_the_type_violation() {
+    let errors = validate("test-examples/coercion/service.yaml");
+    let printable = PrintableError::from(&errors[0]);
+    assert_eq!(printable.rule_id, "app::schema::coercible_type");
+    assert_eq!(printable.json_path.as_deref(), Some("/port"));
+    assert_eq!(printable.violation.unwrap().keyword, "type");
+    assert!(errors[0].to_string().contains("is written as string, but the schema expects integer"), "{}", errors[0]);
+}
+
+#[test]
+fn test_unlocated_values_get_a_hint_but_no_fix() {
+    let errors = validate("test-examples/coercion/hosts.yaml");
+    let [error @ AppError::CoercibleType(coercible)] = &errors[..] else { panic!("{:?}", errors) };
+    assert_eq!(coercible.replacement, None);
+    assert_eq!(coercible.hint, "write it as `\"10\"`");
+    assert!(fixes_for(error).is_empty(), "Array elements are not located");
+
+    let errors = validate("test-examples/coercion/not-a-number.yaml");
+    assert!(matches!(&errors[..], [AppError::SchemaValidationError { .. }]), "{:?}", errors);
+}
+
+#[test]
+fn test_untyped_formats_name_the_coercion_flag() {
+    let errors = validate("test-examples/coercion/service.ini");
+    let [error @ AppError::CoercibleType(coercible)] = &errors[..] else { panic!("{:?}", errors) };
+    assert_eq!(coercible.replacement, None, "Unquoting does not make an INI value a number");
+    assert_eq!(coercible.hint, "pass `--ini-coerce numbers` to read values like this one as numbers");
+    assert!(fixes_for(error).is_empty());
+}
+
+#[test]
+fn test_fix_unquotes_the_value() {
+    let dir = std::env::temp_dir().join(format!("toml-and-jerry-coercion-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let input = dir.join("service.yaml");
+    std::fs::copy("test-examples/coercion/service.yaml", &input).unwrap();
+
+    let output = Command::new("cargo")
+        .args(["run", "--", "check", "--schema", SCHEMA, "--fix"])
+        .arg(&input)
+        .output()
+        .expect("Failed to execute command");
+    let fixed = std::fs::read_to_string(&input).unwrap();
+    std::fs::remove_dir_all(&dir).ok();
+
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(fixed, "name: billing\nport: 8080\n");
+}
@@ -0,0 +1,36 @@
+use std::process::Command;
+use toml_and_jerry::color::ColorChoice;
+
+#[test]
+fn test_environment_settles_auto_only() {
+    assert_eq!(ColorChoice::Auto.resolve(None, None), ColorChoice::Auto);
+    assert_eq!(ColorChoice::Auto.resolve(Some("1"), None), ColorChoice::Never);
+    assert_eq!(ColorChoice::Auto.resolve(Some(""), None), ColorChoice::Auto, "An empty NO_COLOR is unset");
+    assert_eq!(ColorChoice::Auto.resolve(None, Some("1")), ColorChoice::Always);
+    assert_eq!(ColorChoice::Auto.resolve(None, Some("0")), ColorChoice::Auto);
+    assert_eq!(ColorChoice::Auto.resolve(Some("1"), Some("1")), ColorChoice::Never, "NO_COLOR wins");
+    assert_eq!(ColorChoice::Always.resolve(Some("1"), None), ColorChoice::Always, "The flag beats the environment");
+    assert_eq!(ColorChoice::Never.resolve(None, Some("1")), ColorChoice::Never);
+}
+
+#[test]
+fn test_parse() {
+    assert_eq!(ColorChoice::parse("never"), Some(ColorChoice::Never));
+    assert_eq!(ColorChoice::parse("sometimes"), None);
+}
+
+fn check_stderr(color: &str) -> String {
+    let output = Command::new("cargo")
+        .args(["run", "--", "check", "test-examples/invalid-config.json", "--schema", "test-examples/schema.json", "--color", color])
+        .env_remove("NO_COLOR")
+        .env_remove("CLICOLOR_FORCE")
+        .output()
+        .expect("Failed to execute command");
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[test]
+fn test_color_flag_controls_ansi_sequences() {
+    assert!(check_stderr("always").contains('\u{1b}'), "--color always should color even when captured");
+    assert!(!check_stderr("never").contains("\u{1b}["), "--color never should not emit ANSI sequences");
+}
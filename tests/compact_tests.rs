@@ -0,0 +1,51 @@
+use std::process::Command;
+use toml_and_jerry::merge::{self, ReportEntry};
+use toml_and_jerry::validation::PrintableError;
+
+fn entry(line: Option<usize>, column: Option<usize>, message: &str) -> ReportEntry {
+    ReportEntry {
+        diagnostic: PrintableError {
+            file_path: "configs/app.yaml".to_string(),
+            error_type: "SchemaValidationError".to_string(),
+            message: message.to_string(),
+            line,
+            column,
+            json_path: None,
+            rule_id: "app::schema::validation_error".to_string(),
+            severity: None,
+            category: None,
+            snippet: None,
+            violation: None,
+        },
+        level: "error".to_string(),
+    }
+}
+
+#[test]
+fn test_compact_lines_are_quickfix_style() {
+    let compact = merge::to_compact(&[entry(Some(4), Some(9), "\"eighty\" is not of type\n\"integer\""), entry(None, None, "unreadable")]);
+
+    assert_eq!(
+        compact,
+        "configs/app.yaml:4:9: app::schema::validation_error: \"eighty\" is not of type \"integer\"\n\
+         configs/app.yaml: app::schema::validation_error: unreadable\n"
+    );
+}
+
+#[test]
+fn test_compact_format_prints_one_line_per_error() {
+    let output = Command::new("cargo")
+        .args(["run", "--", "check", "test-examples/invalid-config.json", "--schema", "test-examples/schema.json", "--format", "compact"])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 1, "{}", stdout);
+    let (position, rest) = lines[0].split_once(": ").unwrap();
+    let mut parts = position.split(':');
+    assert_eq!(parts.next(), Some("test-examples/invalid-config.json"));
+    assert!(parts.all(|n| n.parse::<usize>().is_ok()), "{}", lines[0]);
+    assert!(rest.starts_with("app::schema::validation_error: "), "{}", lines[0]);
+}
@@ -0,0 +1,64 @@
+use std::path::PathBuf;
+use std::process::Command;
+use serde_json::json;
+use toml_and_jerry::config_merge::{load_layer, merge_layers, render_merged, MergeStrategy};
+use toml_and_jerry::error::{AppError, MergeConflict};
+
+fn layers(names: &[&str]) -> Vec<toml_and_jerry::config_merge::Layer> {
+    names.iter().map(|name| load_layer(&PathBuf::from(format!("test-examples/config-merge/{}", name))).unwrap()).collect()
+}
+
+#[test]
+fn test_last_wins_deep_merges_layers() {
+    let merged = merge_layers(&layers(&["base.yaml", "prod.toml", "override.json"]), MergeStrategy::LastWins).unwrap();
+    assert_eq!(merged, json!({
+        "name": "my-app",
+        "port": 8080,
+        "debug": false,
+        "database": { "host": "db.prod.internal", "port": 5432, "pool": 20 }
+    }));
+}
+
+#[test]
+fn test_error_on_conflict_reports_both_files() {
+    let conflicts = merge_layers(&layers(&["base.yaml", "prod.toml", "override.json"]), MergeStrategy::ErrorOnConflict).unwrap_err();
+    assert_eq!(conflicts.len(), 1, "Equal values (port, pool) are not conflicts: {:?}", conflicts);
+    match &conflicts[0] {
+        AppError::MergeConflict(conflict) => {
+            let MergeConflict { path, pointer, value, span, source_code, earlier } = conflict.as_ref();
+            assert_eq!(path, &PathBuf::from("test-examples/config-merge/prod.toml"));
+            assert_eq!(pointer, "/database/host");
+            assert_eq!(value, "\"db.prod.internal\"");
+            assert_eq!(&source_code[span.offset()..span.offset() + span.len()], "host");
+            match &earlier[..] {
+                [AppError::MergeConflict(earlier)] => {
+                    let MergeConflict { path, value, span, source_code, .. } = earlier.as_ref();
+                    assert_eq!(path, &PathBuf::from("test-examples/config-merge/base.yaml"));
+                    assert_eq!(value, "\"localhost\"");
+                    assert!(source_code[span.offset()..].starts_with("host: localhost"));
+                }
+                other => panic!("Expected the earlier layer's location, got {:?}", other),
+            }
+        }
+        other => panic!("Expected MergeConflict, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_strategy_names_and_rendering() {
+    assert_eq!(MergeStrategy::parse("error-on-conflict"), Some(MergeStrategy::ErrorOnConflict));
+    assert_eq!(MergeStrategy::parse("first-wins"), None);
+    let merged = json!({ "port": 8080 });
+    assert_eq!(render_merged(&merged, Some(PathBuf::from("out.toml").as_path())).unwrap(), "port = 8080\n");
+    assert_eq!(render_merged(&merged, Some(PathBuf::from("out.yaml").as_path())).unwrap(), "port: 8080\n");
+}
+
+#[test]
+fn test_cli_fails_on_conflict() {
+    let output = Command::new("cargo")
+        .args(["run", "--", "merge", "test-examples/config-merge/base.yaml", "test-examples/config-merge/prod.toml", "--strategy", "error-on-conflict"])
+        .output()
+        .expect("Failed to execute command");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("app::merge::conflict"));
+}
@@ -0,0 +1,59 @@
+use std::path::Path;
+use std::process::Command;
+use toml_and_jerry::consistency::{check_consistency, Comparison, ConsistencyRule, ValueSelector};
+use toml_and_jerry::error::{AppError, InconsistentValue};
+
+fn selector(file: &str, path: &str) -> ValueSelector {
+    ValueSelector { file: format!("test-examples/consistency/{}", file).into(), path: path.to_string() }
+}
+
+fn rule(right: ValueSelector, compare: Comparison) -> ConsistencyRule {
+    ConsistencyRule { left: selector("Cargo.toml", "/package/version"), right, compare }
+}
+
+#[test]
+fn test_matching_values_pass() {
+    let rules = [
+        rule(selector("chart.json", "/appVersion"), Comparison::Equal),
+        rule(selector("app.yaml", "/image"), Comparison::Contains),
+        rule(selector("app.yaml", "/version"), Comparison::NotEqual),
+    ];
+    assert_eq!(check_consistency(&rules).unwrap().len(), 0);
+}
+
+#[test]
+fn test_mismatch_is_labelled_in_both_files() {
+    let errors = check_consistency(&[rule(selector("app.yaml", "/version"), Comparison::Equal)]).unwrap();
+    assert_eq!(errors.len(), 1, "{:?}", errors);
+    let AppError::InconsistentValue(error) = &errors[0] else { panic!("{:?}", errors) };
+    let InconsistentValue { path, value, requirement, span, source_code, other, .. } = error.as_ref();
+    assert_eq!(path, Path::new("test-examples/consistency/app.yaml"));
+    assert_eq!(value, "\"1.3.9\"");
+    assert!(requirement.contains("must equal `/package/version`") && requirement.ends_with("(\"1.4.0\")"), "{}", requirement);
+    assert_eq!(&source_code[span.offset()..span.offset() + span.len()], "version");
+
+    let AppError::InconsistentValue(error) = &other[0] else { panic!("{:?}", other) };
+    let InconsistentValue { path, span, source_code, .. } = error.as_ref();
+    assert_eq!(path, Path::new("test-examples/consistency/Cargo.toml"));
+    assert_eq!(&source_code[span.offset()..span.offset() + span.len()], "version");
+}
+
+#[test]
+fn test_unset_values_and_unreadable_files() {
+    let errors = check_consistency(&[rule(selector("chart.json", "/version"), Comparison::NotEqual)]).unwrap();
+    assert!(matches!(&errors[..], [AppError::InconsistentValue(error)] if error.value == "unset"), "{:?}", errors);
+    assert!(check_consistency(&[rule(selector("missing.yaml", "/version"), Comparison::Equal)]).is_err());
+}
+
+#[test]
+fn test_cli_reports_mismatches_from_project_config() {
+    let output = Command::new("cargo")
+        .args(["run", "--", "check", "--schema", "test-examples/schema.json", "test-examples/valid-config.json"])
+        .args(["--config", "test-examples/consistency/project.toml"])
+        .output()
+        .expect("Failed to execute command");
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("app::consistency::mismatch"), "{}", stderr);
+    assert!(stderr.contains("app.yaml") && !stderr.contains("chart.json"), "{}", stderr);
+}
@@ -0,0 +1,52 @@
+use std::path::PathBuf;
+use serde_json::json;
+use toml_and_jerry::containerfile::{is_containerfile, parse_containerfile};
+use toml_and_jerry::error::AppError;
+use toml_and_jerry::presets::compile_bundled_schema;
+use toml_and_jerry::validation::{validate_inputs, validate_inputs_with_options, ValidationOptions};
+use jsonschema::Validator;
+
+#[test]
+fn test_containerfile_names_are_detected() {
+    assert!(is_containerfile(&PathBuf::from("Dockerfile")));
+    assert!(is_containerfile(&PathBuf::from("deploy/Containerfile.dev")));
+    assert!(is_containerfile(&PathBuf::from("api.dockerfile")));
+    assert!(!is_containerfile(&PathBuf::from("docker-compose.yml")));
+}
+
+#[test]
+fn test_containerfile_metadata_is_extracted() {
+    let content = std::fs::read_to_string("test-examples/containers/Dockerfile").unwrap();
+    let metadata = parse_containerfile(&content);
+    assert_eq!(metadata.value["from"][0], json!({ "image": "rust:1.80", "alias": "build" }));
+    assert_eq!(metadata.value["labels"]["org.opencontainers.image.licenses"], json!("MIT"));
+    assert_eq!(metadata.value["args"]["PROFILE"], json!("release"));
+    assert_eq!(metadata.value["expose"], json!(["8080/tcp"]));
+    assert_eq!(metadata.value["user"], json!("nonroot"));
+    assert!(metadata.span_for_pointer("/labels/org.opencontainers.image.source").is_some());
+}
+
+#[test]
+fn test_missing_required_label_points_at_label_instruction() {
+    let schema_content = std::fs::read_to_string("test-examples/containers/required-labels.schema.json").unwrap();
+    let validator = Validator::new(&serde_json::from_str(&schema_content).unwrap()).unwrap();
+    let options = ValidationOptions { containerfiles: true, ..Default::default() };
+    let errors = validate_inputs_with_options(vec![PathBuf::from("test-examples/containers/Dockerfile")], &validator, &options).unwrap();
+    match errors.first() {
+        Some(AppError::SchemaValidationError(error)) => {
+            assert_eq!(error.instance_path, "/labels");
+            assert!(error.source_code[error.error_span.offset()..].starts_with("LABEL"));
+        }
+        other => panic!("Expected a schema validation error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_bundled_compose_schema() {
+    let validator = compile_bundled_schema("docker-compose").expect("Bundled schema should compile");
+    let errors = validate_inputs(vec![PathBuf::from("test-examples/containers/docker-compose.yml")], &validator).unwrap();
+    assert!(errors.is_empty(), "Valid compose file should pass: {:?}", errors);
+
+    let errors = validate_inputs(vec![PathBuf::from("test-examples/containers/invalid-compose.yml")], &validator).unwrap();
+    assert!(errors.iter().any(|e| matches!(e, AppError::SchemaValidationError { .. })));
+}
@@ -0,0 +1,144 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::process::Command;
+use serde_json::json;
+use toml_and_jerry::debug_bundle::{bundled_path, redact_report, redact_secrets, replay_args, unpack_bundle, without_dropped_flags};
+
+const SCHEMA: &str = "test-examples/debug-bundle/schema.json";
+
+fn strings(args: &[&str]) -> Vec<String> {
+    args.iter().map(|a| a.to_string()).collect()
+}
+
+#[test]
+fn test_redaction_keeps_the_layout() {
+    let content = "database:\n  password: hunter2\n  api_key = \"abc\"\n  \"authToken\": \"t\",\n  user: bob\n  tokens:\n    - a\n";
+    let (redacted, count) = redact_secrets(content);
+    assert_eq!(
+        redacted,
+        "database:\n  password: \"REDACTED\"\n  api_key = \"REDACTED\"\n  \"authToken\": \"REDACTED\",\n  user: bob\n  tokens:\n    - a\n"
+    );
+    assert_eq!(count, 3, "Lists and nested values are left alone");
+}
+
+#[test]
+fn test_report_redaction_covers_snippets_and_values() {
+    let report = json!({
+        "errors": [
+            {
+                "jsonPath": "/database/password",
+                "snippet": { "startLine": 4, "text": "  user: bob\n  password: 12", "caretStart": 13, "caretEnd": 15 },
+                "violation": { "keyword": "type", "expected": "string", "actual": 12 }
+            },
+            { "jsonPath": "/port", "violation": { "keyword": "minimum", "expected": 1024, "actual": 80 } }
+        ]
+    });
+    let redacted: serde_json::Value = serde_json::from_str(&redact_report(&report.to_string())).unwrap();
+    assert_eq!(redacted["errors"][0]["snippet"]["text"], "  user: bob\n  password: \"REDACTED\"");
+    assert_eq!(redacted["errors"][0]["violation"]["actual"], "REDACTED");
+    assert_eq!(redacted["errors"][1]["violation"]["actual"], 80, "Values of other keys are kept");
+}
+
+#[test]
+fn test_replay_args_point_at_bundled_copies() {
+    let args = strings(&["check", "a.yaml", "b.yaml", "--schema=s.json", "--debug-bundle", "out.tgz", "--debug-bundle-redact", "--config", "ci.toml", "--format", "json"]);
+    let schemas = BTreeMap::from([("s.json".to_string(), "schemas/s.json".to_string())]);
+    let given = [PathBuf::from("a.yaml"), PathBuf::from("b.yaml")];
+    assert_eq!(
+        replay_args(&args, &given, &schemas, &["inputs/b.yaml".to_string()]),
+        strings(&["check", "--schema", "schemas/s.json", "--format", "json", "inputs/b.yaml"])
+    );
+    let writing = strings(&["check", "a.yaml", "--output", "r.json", "--status-file=s.json", "--notify-webhook", "https://hooks", "--notify-top", "3"]);
+    let writing = [writing, strings(&["--ratchet", "r.toml", "--stamp", "--fix", "--fix-out", "patches", "--emit-patch=p.json"])].concat();
+    let writing = [writing, strings(&["--fix", "--allow-dirty", "--diff-base", "main", "--cue-binary=/tmp/evil"])].concat();
+    assert_eq!(replay_args(&writing, &given, &schemas, &[]), strings(&["check", "a.yaml"]), "Nothing is written, posted or run");
+    assert_eq!(without_dropped_flags(&writing), strings(&["check", "a.yaml"]));
+
+    let files = strings(&["check", "a.yaml", "--suppressions", "ignore.toml", "--embedded=/data/app=yaml:app.json", "--openapi", "api.yaml"]);
+    let copies = BTreeMap::from([
+        ("ignore.toml".to_string(), "files/ignore.toml".to_string()),
+        ("app.json".to_string(), "files/app.json".to_string()),
+    ]);
+    assert_eq!(
+        replay_args(&files, &given, &copies, &[]),
+        strings(&["check", "a.yaml", "--suppressions", "files/ignore.toml", "--embedded", "/data/app=yaml:files/app.json", "--openapi", "api.yaml"])
+    );
+    assert_eq!(bundled_path("inputs", "../shared/app.toml"), "inputs/_parent/shared/app.toml");
+    assert_eq!(bundled_path("schemas", "https://example.com/app.json"), "schemas/example.com/app.json");
+}
+
+#[test]
+fn test_failing_run_bundles_and_replays() {
+    let dir = std::env::temp_dir().join(format!("toml-and-jerry-debug-bundle-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let bundle = dir.join("failure.tgz");
+
+    let output = Command::new("cargo")
+        .args(["run", "--", "check", "--schema", SCHEMA, "test-examples/debug-bundle/billing.yaml", "test-examples/debug-bundle/search.yaml"])
+        .args(["--debug-bundle-redact", "--debug-bundle"])
+        .arg(&bundle)
+        .output()
+        .expect("Failed to execute command");
+    assert_eq!(output.status.code(), Some(1), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let (unpacked, manifest) = unpack_bundle(&bundle).unwrap();
+    let root = unpacked.path();
+    let bundled: Vec<_> = manifest.files.iter().map(|f| (f.kind.as_str(), f.bundled.as_str(), f.redacted)).collect();
+    assert_eq!(
+        bundled,
+        [("input", "inputs/test-examples/debug-bundle/billing.yaml", true), ("schema", "schemas/test-examples/debug-bundle/schema.json", false)],
+        "Only the failing input is packed"
+    );
+    let billing = std::fs::read_to_string(root.join("inputs/test-examples/debug-bundle/billing.yaml")).unwrap();
+    assert!(billing.contains("password: \"REDACTED\"") && !billing.contains("hunter2"), "{}", billing);
+    let report: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(root.join("report.json")).unwrap()).unwrap();
+    assert_eq!(report["errors"].as_array().unwrap().len(), 1);
+
+    let replay = Command::new("cargo").args(["run", "--", "--replay"]).arg(&bundle).output().expect("Failed to execute command");
+    std::fs::remove_dir_all(&dir).ok();
+
+    let stdout = String::from_utf8_lossy(&replay.stdout);
+    assert_eq!(replay.status.code(), Some(1), "{}", stdout);
+    assert!(stdout.contains("inputs/test-examples/debug-bundle/billing.yaml"), "{}", stdout);
+}
+
+#[test]
+fn test_replay_ignores_what_a_hand_made_bundle_adds() {
+    let dir = std::env::temp_dir().join(format!("toml-and-jerry-hand-made-bundle-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let marker = dir.join("hook-ran");
+    let manifest = json!({
+        "toolVersion": env!("CARGO_PKG_VERSION"),
+        "args": [],
+        "replayArgs": ["check", "--schema", "schema.json", "bad.yaml", "--fix", "--cue-binary", "/bin/sh"],
+        "cwd": "/",
+        "env": {},
+        "files": []
+    });
+    let config = format!("[report.hooks.evil]\ncommand = [\"touch\", {:?}]\n", marker.to_string_lossy());
+    let entries = [
+        ("bundle.json", manifest.to_string()),
+        (".toml-and-jerry.toml", config),
+        ("schema.json", r#"{"type": "object", "properties": {"port": {"type": "integer"}}}"#.to_string()),
+        ("bad.yaml", "port: high\n".to_string()),
+    ];
+    let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default()));
+    for (name, data) in entries {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, name, data.as_bytes()).unwrap();
+    }
+    let bundle = dir.join("hand-made.tgz");
+    std::fs::write(&bundle, builder.into_inner().unwrap().finish().unwrap()).unwrap();
+
+    let replay = Command::new("cargo").args(["run", "--", "--replay"]).arg(&bundle).output().expect("Failed to execute command");
+    let hook_ran = marker.exists();
+    std::fs::remove_dir_all(&dir).ok();
+
+    let stdout = String::from_utf8_lossy(&replay.stdout);
+    assert_eq!(replay.status.code(), Some(1), "{}{}", stdout, String::from_utf8_lossy(&replay.stderr));
+    assert!(!hook_ran, "The bundled config's report hooks do not run");
+    assert!(stdout.contains("toml-and-jerry check --schema schema.json bad.yaml\n"), "--fix and --cue-binary are dropped: {}", stdout);
+}
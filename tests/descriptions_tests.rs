@@ -0,0 +1,90 @@
+use std::process::Command;
+use toml_and_jerry::descriptions::{self, Layout};
+
+fn schema() -> serde_json::Value {
+    serde_json::from_str(&std::fs::read_to_string("test-examples/descriptions/schema.json").unwrap()).unwrap()
+}
+
+fn comments(path: &str, layout: Layout) -> Vec<(String, Option<String>)> {
+    let content = std::fs::read_to_string(path).unwrap();
+    descriptions::key_comments(&content, layout)
+        .into_iter()
+        .map(|key| (descriptions::display_path(&key.path), key.comment))
+        .collect()
+}
+
+#[test]
+fn test_yaml_comments_above_and_after_keys() {
+    let found = comments("test-examples/descriptions/example.yaml", Layout::Yaml);
+    let comment = |path: &str| found.iter().find(|(p, _)| p == path).and_then(|(_, c)| c.clone());
+
+    assert_eq!(comment("server").as_deref(), Some("Network settings of the HTTP server"));
+    assert_eq!(comment("server.host").as_deref(), Some("Interface to listen on"));
+    assert_eq!(comment("server.port").as_deref(), Some("TCP port, 1024 or above"));
+    assert_eq!(comment("workers[].threads").as_deref(), Some("Threads per worker process"));
+    assert_eq!(comment("log_level"), None);
+}
+
+#[test]
+fn test_toml_comments_follow_tables() {
+    let found = comments("test-examples/descriptions/example.toml", Layout::Toml);
+
+    assert!(found.contains(&("log_level".to_string(), Some("debug, info or warn".to_string()))), "{:?}", found);
+    assert!(found.contains(&("server".to_string(), Some("Network settings of the HTTP server".to_string()))), "{:?}", found);
+    assert!(found.contains(&("server.host".to_string(), Some("Interface to listen on".to_string()))), "{:?}", found);
+    assert!(found.contains(&("workers[].threads".to_string(), Some("Threads per worker process".to_string()))), "{:?}", found);
+}
+
+#[test]
+fn test_sync_sets_descriptions_through_refs() {
+    let mut schema = schema();
+    let content = std::fs::read_to_string("test-examples/descriptions/example.yaml").unwrap();
+    let outcome = descriptions::sync_descriptions(&mut schema, &descriptions::key_comments(&content, Layout::Yaml));
+
+    assert_eq!(schema["properties"]["server"]["properties"]["host"]["description"], "Interface to listen on");
+    assert_eq!(schema["properties"]["server"]["properties"]["port"]["description"], "TCP port, 1024 or above");
+    assert_eq!(schema["definitions"]["Worker"]["properties"]["threads"]["description"], "Threads per worker process");
+    assert_eq!(outcome.unknown, ["retries"]);
+
+    let again = descriptions::sync_descriptions(&mut schema, &descriptions::key_comments(&content, Layout::Yaml));
+    assert!(again.updated.is_empty(), "{:?}", again);
+}
+
+#[test]
+fn test_render_commented_writes_descriptions_back() {
+    let mut schema = schema();
+    schema["properties"]["log_level"]["description"] = "Least severe level that is logged".into();
+    schema["properties"]["server"]["properties"]["port"]["description"] = "Port to listen on".into();
+    let content = std::fs::read_to_string("test-examples/descriptions/example.yaml").unwrap();
+
+    let rendered = descriptions::render(&content, Layout::Yaml, &schema, true);
+
+    assert!(rendered.contains("  # Old wording\n  host: 0.0.0.0\n"), "{}", rendered);
+    assert!(rendered.contains("  port: 8080 # Port to listen on\n"), "{}", rendered);
+    assert!(rendered.contains("# Least severe level that is logged\nlog_level: info\n"), "{}", rendered);
+    // Keys the schema does not describe keep their comments
+    assert!(rendered.contains("# Network settings of the HTTP server\nserver:\n"), "{}", rendered);
+}
+
+#[test]
+fn test_render_without_commented_strips_key_comments() {
+    let content = std::fs::read_to_string("test-examples/descriptions/example.toml").unwrap();
+
+    let rendered = descriptions::render(&content, Layout::Toml, &schema(), false);
+
+    assert_eq!(rendered, "log_level = \"info\"\n\n[server]\nhost = \"0.0.0.0\"\nport = 8080\n\n[[workers]]\nthreads = 4\n");
+}
+
+#[test]
+fn test_sync_descriptions_command_prints_updated_schema() {
+    let output = Command::new("cargo")
+        .args(["run", "--", "schema", "sync-descriptions", "test-examples/descriptions/schema.json"])
+        .args(["--example", "test-examples/descriptions/example.toml"])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let schema: serde_json::Value = serde_json::from_slice(&output.stdout).expect("stdout should be the schema");
+    assert_eq!(schema["properties"]["log_level"]["description"], "debug, info or warn");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("description(s) updated"));
+}
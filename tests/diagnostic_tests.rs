@@ -0,0 +1,60 @@
+use std::fs;
+use std::path::PathBuf;
+use toml_and_jerry::diagnostic::{diagnostics, Diagnostic};
+use toml_and_jerry::enum_map::load_mappings;
+use toml_and_jerry::error::{AppError, Severity};
+use toml_and_jerry::schema::load_and_compile_schema;
+use toml_and_jerry::validation::{validate_inputs, validate_inputs_with_options, ValidationOptions};
+
+#[test]
+fn test_schema_error_as_diagnostic() {
+    let validator = load_and_compile_schema(&PathBuf::from("test-examples/schema.json")).unwrap();
+    let errors = validate_inputs(vec![PathBuf::from("test-examples/severity/config.json")], &validator).unwrap();
+    let diagnostic = Diagnostic::from(&errors[0]);
+    assert_eq!(diagnostic.code, "app::schema::validation_error");
+    assert_eq!(diagnostic.severity, Severity::Error);
+    assert_eq!(diagnostic.file, "test-examples/severity/config.json");
+    assert_eq!(diagnostic.instance_path.as_deref(), Some("/port"));
+    assert_eq!(diagnostic.schema_path.as_deref(), Some("/properties/port/minimum"));
+    let range = diagnostic.range.expect("schema errors point into the file");
+    assert_eq!((range.start.line, range.end.line), (4, 4));
+    let content = fs::read_to_string("test-examples/severity/config.json").unwrap();
+    assert!(content[range.offset..range.offset + range.length].contains("80"), "{:?}", range);
+}
+
+#[test]
+fn test_fixable_diagnostics_suggest_their_edits() {
+    let validator = load_and_compile_schema(&PathBuf::from("test-examples/enum_map/schema.json")).unwrap();
+    let options = ValidationOptions {
+        enum_mappings: load_mappings(&PathBuf::from("test-examples/enum_map/rules.toml")).unwrap(),
+        ..Default::default()
+    };
+    let errors = validate_inputs_with_options(vec![PathBuf::from("test-examples/enum_map/services.yaml")], &validator, &options).unwrap();
+    // Mappings apply in the rules file's key order, so `err` is reported before `warn`
+    let renamed = errors
+        .iter()
+        .find(|e| matches!(e, AppError::RenamedEnumValue(error) if error.from == "warn"))
+        .expect("a renamed enum value");
+    let diagnostic = Diagnostic::from(renamed);
+    assert_eq!(diagnostic.severity, Severity::Warning);
+    assert_eq!(diagnostic.schema_path, None);
+
+    let fix = &diagnostic.suggestions[0];
+    assert!(fix.message.contains("`warn` -> `warning`"), "{}", fix.message);
+    let content = fs::read_to_string("test-examples/enum_map/services.yaml").unwrap();
+    let edit = &fix.edits[0];
+    assert_eq!((&content[edit.offset..edit.offset + edit.length], edit.replacement.as_str()), ("warn", "warning"));
+    assert!(diagnostic.suggestions[1].edits.is_empty(), "the help text follows as advice");
+}
+
+#[test]
+fn test_diagnostics_round_trip_through_serde() {
+    let validator = load_and_compile_schema(&PathBuf::from("test-examples/schema.json")).unwrap();
+    let errors = validate_inputs(vec![PathBuf::from("test-examples/severity/config.json")], &validator).unwrap();
+    let all = diagnostics(&errors);
+    let json = serde_json::to_value(&all).unwrap();
+    assert_eq!(json[0]["schemaPath"], "/properties/port/minimum");
+    assert_eq!(json[0]["range"]["start"]["line"], 4);
+    let read: Vec<Diagnostic> = serde_json::from_value(json).unwrap();
+    assert_eq!(read, all);
+}
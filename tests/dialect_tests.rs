@@ -0,0 +1,33 @@
+use std::path::PathBuf;
+use serde_json::json;
+use toml_and_jerry::error::AppError;
+use toml_and_jerry::schema::{assumed_dialect, compile_schema_with_draft, load_schema_json, parse_draft};
+
+#[test]
+fn test_parses_draft_names() {
+    assert_eq!(parse_draft("7"), Some(jsonschema::Draft::Draft7));
+    assert_eq!(parse_draft("draft-04"), Some(jsonschema::Draft::Draft4));
+    assert_eq!(parse_draft("2019-09"), Some(jsonschema::Draft::Draft201909));
+    assert_eq!(parse_draft("8"), None);
+}
+
+#[test]
+fn test_default_draft_applies_to_schemas_without_dialect() {
+    let schema = load_schema_json(&PathBuf::from("test-examples/dialect/no-dialect.schema.json")).unwrap();
+    let validator = compile_schema_with_draft(&schema, "no-dialect", parse_draft("4")).expect("Draft 4 schema should compile");
+    assert!(!validator.is_valid(&json!({ "limit": 10 })), "Draft 4 boolean exclusiveMaximum excludes the maximum");
+    assert!(validator.is_valid(&json!({ "limit": 9 })));
+
+    let warning = assumed_dialect(&schema, "no-dialect", parse_draft("4")).expect("Missing $schema should warn");
+    assert!(matches!(&warning, AppError::AssumedDialect { dialect, .. } if dialect == "draft-04"));
+    assert!(warning.is_warning());
+}
+
+#[test]
+fn test_declared_dialect_is_not_overridden() {
+    let schema = json!({ "$schema": "http://json-schema.org/draft-07/schema#", "type": "object" });
+    assert!(assumed_dialect(&schema, "declared", parse_draft("4")).is_none());
+    assert!(assumed_dialect(&json!(true), "boolean", None).is_none());
+    let warning = assumed_dialect(&json!({ "type": "object" }), "bare", None).unwrap();
+    assert!(warning.to_string().contains("2020-12"), "{}", warning);
+}
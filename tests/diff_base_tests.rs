@@ -0,0 +1,86 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use toml_and_jerry::diff_base::{validate_against_base, DiffBase};
+use toml_and_jerry::schema::load_and_compile_schema;
+use toml_and_jerry::validation::{validate_inputs_with_options, ValidationOptions};
+
+const INPUT: &str = "test-examples/invalid-config.json";
+
+// Diagnostics for INPUT that are new relative to a base checkout holding `base_content` (or no file)
+fn new_diagnostic_count(name: &str, base_content: Option<&str>) -> usize {
+    let base = std::env::temp_dir().join(format!("toml-and-jerry-diff-{}-{}", name, std::process::id()));
+    fs::create_dir_all(base.join("test-examples")).unwrap();
+    if let Some(content) = base_content {
+        fs::write(base.join(INPUT), content).unwrap();
+    }
+    let validator = load_and_compile_schema(&PathBuf::from("test-examples/schema.json")).unwrap();
+    let diagnostics = validate_against_base(&[PathBuf::from(INPUT)], Some(&DiffBase::Dir(base.clone())), |inputs| {
+        validate_inputs_with_options(inputs, &validator, &ValidationOptions::default())
+    })
+    .expect("Diff validation should run");
+    fs::remove_dir_all(&base).ok();
+    diagnostics.len()
+}
+
+#[test]
+fn test_unchanged_files_report_nothing() {
+    let current = fs::read_to_string(INPUT).unwrap();
+    assert_eq!(new_diagnostic_count("same", Some(&current)), 0);
+}
+
+#[test]
+fn test_diagnostics_present_in_base_are_dropped() {
+    let base = fs::read_to_string(INPUT).unwrap().replace("\"my-app\"", "\"old-name\"");
+    assert_eq!(new_diagnostic_count("preexisting", Some(&base)), 0);
+}
+
+#[test]
+fn test_diagnostics_introduced_by_the_change_are_kept() {
+    let valid = fs::read_to_string("test-examples/valid-config.json").unwrap();
+    assert_eq!(new_diagnostic_count("introduced", Some(&valid)), 1);
+    assert_eq!(new_diagnostic_count("new-file", None), 1);
+}
+
+#[test]
+fn test_parse_prefers_directories_over_revisions() {
+    assert_eq!(DiffBase::parse("test-examples"), DiffBase::Dir(PathBuf::from("test-examples")));
+    assert_eq!(DiffBase::parse("origin/main"), DiffBase::GitRef("origin/main".to_string()));
+}
+
+fn git(dir: &Path, args: &[&str]) {
+    let status = Command::new("git").arg("-C").arg(dir).args(args).status().expect("git should run");
+    assert!(status.success(), "git {:?} failed", args);
+}
+
+const VALID: &str = "{\"name\": \"billing\", \"version\": \"1.0.0\", \"port\": 8080}\n";
+const INVALID: &str = "{\"name\": \"billing\", \"version\": \"1.0.0\", \"port\": 80}\n";
+
+// Exit code of `check --diff-base HEAD` in a scratch repository whose committed app.json is
+// `committed` and whose working tree holds `edited`
+fn check_against_head(name: &str, committed: &str, edited: &str) -> Option<i32> {
+    let dir = std::env::temp_dir().join(format!("toml-and-jerry-diff-git-{}-{}", name, std::process::id()));
+    fs::remove_dir_all(&dir).ok();
+    fs::create_dir_all(&dir).unwrap();
+    git(&dir, &["init", "-q"]);
+    fs::write(dir.join("app.json"), committed).unwrap();
+    git(&dir, &["add", "."]);
+    git(&dir, &["-c", "user.name=test", "-c", "user.email=test@example.com", "commit", "-qm", "Add app config"]);
+    fs::write(dir.join("app.json"), edited).unwrap();
+
+    let schema = Path::new(env!("CARGO_MANIFEST_DIR")).join("test-examples/schema.json");
+    let output = Command::new(env!("CARGO_BIN_EXE_toml-and-jerry"))
+        .args(["check", "app.json", "--diff-base", "HEAD", "--schema"])
+        .arg(&schema)
+        .current_dir(&dir)
+        .output()
+        .expect("Failed to execute command");
+    fs::remove_dir_all(&dir).ok();
+    output.status.code()
+}
+
+#[test]
+fn test_diff_base_git_revision() {
+    assert_eq!(check_against_head("unchanged", INVALID, INVALID), Some(0), "An error already committed is not new");
+    assert_eq!(check_against_head("introduced", VALID, INVALID), Some(1));
+}
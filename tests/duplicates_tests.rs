@@ -0,0 +1,49 @@
+use std::path::PathBuf;
+use std::process::Command;
+use toml_and_jerry::duplicates::{find_duplicates, input_files, DuplicateOptions};
+use toml_and_jerry::yaml::TagPolicy;
+
+fn fixture_files() -> Vec<PathBuf> {
+    input_files(&[PathBuf::from("test-examples/duplicates")])
+}
+
+#[test]
+fn test_identical_blocks_across_formats() {
+    let options = DuplicateOptions { similarity: 1.0, ..Default::default() };
+    let groups = find_duplicates(&fixture_files(), &options, &TagPolicy::default());
+    assert_eq!(groups.len(), 1, "{:?}", groups);
+    let files: Vec<_> = groups[0].occurrences.iter().map(|o| (o.file.as_str(), o.pointer.as_str())).collect();
+    assert_eq!(files, [("test-examples/duplicates/billing.yaml", "/database"), ("test-examples/duplicates/payments.toml", "/database")]);
+    assert_eq!(groups[0].size, 6);
+    assert_eq!(groups[0].similarity, 1.0);
+}
+
+#[test]
+fn test_near_identical_blocks_join_the_group() {
+    // search.yaml differs in one of six values: 5 shared of 7 distinct leaves
+    let options = DuplicateOptions { similarity: 0.7, ..Default::default() };
+    let groups = find_duplicates(&fixture_files(), &options, &TagPolicy::default());
+    assert_eq!(groups.len(), 1, "Nested and whole-file blocks should not be reported separately: {:?}", groups);
+    assert_eq!(groups[0].occurrences.len(), 3);
+    assert!((groups[0].similarity - 5.0 / 7.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_thresholds_filter_groups() {
+    let too_big = DuplicateOptions { min_size: 7, ..Default::default() };
+    assert!(find_duplicates(&fixture_files(), &too_big, &TagPolicy::default()).is_empty());
+    let too_many_files = DuplicateOptions { min_files: 4, ..Default::default() };
+    assert!(find_duplicates(&fixture_files(), &too_many_files, &TagPolicy::default()).is_empty());
+}
+
+#[test]
+fn test_duplicates_json_output() {
+    let output = Command::new("cargo")
+        .args(["run", "--", "duplicates", "test-examples/duplicates", "--format", "json"])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+    let groups: serde_json::Value = serde_json::from_slice(&output.stdout).expect("Output should be JSON");
+    // At the default 0.8 similarity only the identical copies are grouped
+    assert_eq!(groups[0]["occurrences"].as_array().unwrap().len(), 2);
+}
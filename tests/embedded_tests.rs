@@ -0,0 +1,72 @@
+use std::path::PathBuf;
+use jsonschema::Validator;
+use miette::SourceSpan;
+use serde_json::json;
+use toml_and_jerry::embedded::{map_span, matching_strings, EmbeddedFormat, EmbeddedRule};
+use toml_and_jerry::error::AppError;
+use toml_and_jerry::validation::{validate_inputs_with_options, ValidationOptions};
+
+fn validate_configmap(rules: &[&str]) -> Vec<AppError> {
+    let options = ValidationOptions {
+        embedded: rules.iter().map(|r| EmbeddedRule::parse(r).expect("Rule should parse")).collect(),
+        ..Default::default()
+    };
+    let accept_all = Validator::new(&json!(true)).unwrap();
+    validate_inputs_with_options(vec![PathBuf::from("test-examples/embedded/configmap.yaml")], &accept_all, &options).unwrap()
+}
+
+fn labelled_text(error: &AppError) -> String {
+    match error {
+        AppError::SchemaValidationError(error) => {
+            error.source_code[error.error_span.offset()..error.error_span.offset() + error.error_span.len()].to_string()
+        }
+        AppError::YamlParseError { source_code, span, .. } => source_code[span.offset()..span.offset() + span.len()].to_string(),
+        other => panic!("Unexpected diagnostic {:?}", other),
+    }
+}
+
+#[test]
+fn test_parses_rules() {
+    let rule = EmbeddedRule::parse("/data/config.yaml=yaml:schemas/app.json").unwrap();
+    assert_eq!(rule.pointer, "/data/config.yaml");
+    assert_eq!(rule.format, EmbeddedFormat::Yaml);
+    assert_eq!(rule.schema, PathBuf::from("schemas/app.json"));
+    assert!(EmbeddedRule::parse("data=yaml:app.json").is_none());
+    assert!(EmbeddedRule::parse("/data=xml:app.json").is_none());
+}
+
+#[test]
+fn test_wildcards_match_keys_and_indexes() {
+    let instance = json!({ "data": { "a/b": "x", "n": 1 }, "list": ["y", "z"] });
+    let found: Vec<_> = matching_strings(&instance, "/data/*").into_iter().map(|(p, _)| p).collect();
+    assert_eq!(found, ["/data/a~1b"]);
+    assert_eq!(matching_strings(&instance, "/list/1"), [("/list/1".to_string(), "z")]);
+}
+
+#[test]
+fn test_spans_map_through_block_scalars() {
+    let outer = "data:\n  conf: |\n    a: 1\n    b: 2\n";
+    let span = map_span(outer, "a: 1\nb: 2\n", SourceSpan::new(5.into(), 1)).unwrap();
+    assert_eq!(&outer[span.offset()..span.offset() + 1], "b");
+    assert!(map_span(outer, "{\"escaped\": 1}", SourceSpan::new(0.into(), 1)).is_none());
+}
+
+#[test]
+fn test_embedded_yaml_is_validated_with_nested_spans() {
+    let errors = validate_configmap(&["/data/config.yaml=yaml:test-examples/embedded/app.schema.json"]);
+    assert_eq!(errors.len(), 1, "{:?}", errors);
+    assert!(matches!(&errors[0], AppError::SchemaValidationError(error) if error.instance_path == "/data/config.yaml/workers"));
+    assert_eq!(labelled_text(&errors[0]), "workers");
+}
+
+#[test]
+fn test_embedded_toml_and_parse_errors() {
+    let errors = validate_configmap(&[
+        "/data/settings.toml=toml:test-examples/embedded/settings.schema.json",
+        "/data/broken.yaml=yaml:test-examples/embedded/app.schema.json",
+    ]);
+    assert_eq!(errors.len(), 2, "{:?}", errors);
+    assert!(matches!(&errors[0], AppError::SchemaValidationError(error) if error.instance_path == "/data/settings.toml/log/level"));
+    assert_eq!(labelled_text(&errors[0]), "level");
+    assert!(matches!(&errors[1], AppError::YamlParseError { message, .. } if message.contains("/data/broken.yaml")));
+}
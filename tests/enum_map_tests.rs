@@ -0,0 +1,65 @@
+use std::fs;
+use std::path::PathBuf;
+use serde_json::json;
+use toml_and_jerry::enum_map::{find_old_enum_values, load_mappings, locate_hits, parse_mapping, EnumMapping};
+use toml_and_jerry::error::AppError;
+use toml_and_jerry::fix::{apply_edits, describe_change, fix_for};
+use toml_and_jerry::schema::{compile_schema, load_schema_json};
+use toml_and_jerry::validation::{validate_inputs_with_options, ValidationOptions};
+
+#[test]
+fn test_parses_inline_mappings() {
+    let mappings = parse_mapping("/log/level: warn=warning, err = error").expect("Mapping should parse");
+    let pairs: Vec<_> = mappings.iter().map(|m| (m.pointer.as_str(), m.from.as_str(), m.to.as_str())).collect();
+    assert_eq!(pairs, vec![("/log/level", "warn", "warning"), ("/log/level", "err", "error")]);
+    assert!(parse_mapping("log/level: warn=warning").is_none(), "Pointer must start with /");
+    assert!(parse_mapping("/log/level: warn").is_none(), "Pairs need OLD=NEW");
+}
+
+#[test]
+fn test_loads_rules_file() {
+    let mappings = load_mappings(&PathBuf::from("test-examples/enum_map/rules.toml")).expect("Rules file should load");
+    assert_eq!(mappings.len(), 2);
+    assert!(mappings.iter().all(|m| m.pointer == "/services/*/log_level"));
+}
+
+#[test]
+fn test_finds_old_values_only_at_mapped_pointers() {
+    let mappings = vec![EnumMapping { pointer: "/log/level".into(), from: "warn".into(), to: "warning".into() }];
+    let hits = find_old_enum_values(&json!({ "log": { "level": "warn", "mode": "warn" }, "level": "warn" }), &mappings);
+    let pointers: Vec<_> = hits.iter().map(|h| h.pointer.as_str()).collect();
+    assert_eq!(pointers, vec!["/log/level"]);
+}
+
+#[test]
+fn test_ambiguous_values_are_not_located() {
+    let mappings = vec![EnumMapping { pointer: "/a/level".into(), from: "warn".into(), to: "warning".into() }];
+    let content = "{\"a\": {\"level\": \"warn\"}, \"b\": {\"level\": \"warn\"}}";
+    let hits = find_old_enum_values(&serde_json::from_str(content).unwrap(), &mappings);
+    assert_eq!(locate_hits(content, &hits), vec![None], "Two `level: warn` pairs for one hit must not be guessed");
+}
+
+#[test]
+fn test_renamed_enum_values_are_fixable_warnings() {
+    let schema_path = "test-examples/enum_map/schema.json";
+    let schema = load_schema_json(&PathBuf::from(schema_path)).unwrap();
+    let validator = compile_schema(&schema, schema_path).unwrap();
+    let options = ValidationOptions {
+        enum_mappings: load_mappings(&PathBuf::from("test-examples/enum_map/rules.toml")).unwrap(),
+        ..Default::default()
+    };
+    let errors = validate_inputs_with_options(vec![PathBuf::from("test-examples/enum_map/services.yaml")], &validator, &options).unwrap();
+
+    let renamed: Vec<_> = errors.iter().filter(|e| matches!(e, AppError::RenamedEnumValue { .. })).collect();
+    assert_eq!(renamed.len(), 2, "Expected both old values to be reported: {:?}", errors);
+    assert!(renamed.iter().all(|e| e.is_warning()));
+    let changes: Vec<_> = renamed.iter().filter_map(|e| describe_change(e)).collect();
+    assert!(changes.contains(&"/services/api/log_level: `warn` -> `warning`".to_string()), "{:?}", changes);
+
+    let fixes: Vec<_> = renamed.iter().filter_map(|e| fix_for(e)).collect();
+    let original = fs::read_to_string("test-examples/enum_map/services.yaml").unwrap();
+    let fixed = apply_edits(&original, &fixes);
+    assert!(fixed.contains("log_level: warning # noisy otherwise"), "{}", fixed);
+    assert!(fixed.contains("log_level: \"error\""), "{}", fixed);
+    assert!(fixed.contains("mode: warn\n"), "Unmapped keys keep their value");
+}
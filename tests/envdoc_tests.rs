@@ -0,0 +1,50 @@
+use std::path::PathBuf;
+use std::process::Command;
+use serde_json::json;
+use toml_and_jerry::envdoc::{env_doc, env_name, render_markdown};
+use toml_and_jerry::schema::load_schema_json;
+use toml_and_jerry::schema_keys::schema_keys;
+
+const SCHEMA: &str = "test-examples/envdoc/schema.json";
+
+#[test]
+fn test_names_are_screaming_snake_case() {
+    assert_eq!(env_name("APP_", "_", "database.maxConnections"), "APP_DATABASE_MAX_CONNECTIONS");
+    assert_eq!(env_name("APP_", "__", "database.max-connections"), "APP_DATABASE__MAX_CONNECTIONS");
+    assert_eq!(env_name("", "_", "v2Api"), "V2_API");
+}
+
+#[test]
+fn test_lists_only_addressable_keys() {
+    let schema = load_schema_json(&PathBuf::from(SCHEMA)).unwrap();
+    let doc = env_doc(&schema_keys(&schema, SCHEMA), "APP_", "_");
+    let names: Vec<_> = doc.variables.iter().map(|v| v.name.as_str()).collect();
+    // `database` has its properties listed instead; items of `hosts` and entries of `features` are set whole
+    assert_eq!(names, ["APP_DATABASE_HOST", "APP_DATABASE_MAX_CONNECTIONS", "APP_FEATURES", "APP_HOSTS", "APP_LOG_LEVEL"]);
+
+    let host = &doc.variables[0];
+    assert_eq!((host.path.as_str(), host.required), ("database.host", true));
+    assert_eq!(doc.variables[4].default, Some(json!("info")));
+}
+
+#[test]
+fn test_markdown_table_escapes_cells() {
+    let schema = load_schema_json(&PathBuf::from(SCHEMA)).unwrap();
+    let table = render_markdown(&env_doc(&schema_keys(&schema, SCHEMA), "APP_", "_"));
+    assert!(table.starts_with("| Variable | Config key | Type | Default | Description |\n"), "{}", table);
+    assert!(table.contains("| `APP_DATABASE_MAX_CONNECTIONS` | `database.max-connections` | integer | `10` | Pool size \\| per instance |\n"), "{}", table);
+    assert!(table.contains("| `APP_DATABASE_HOST` | `database.host` | string |  | **Required.** Database host |\n"), "{}", table);
+}
+
+#[test]
+fn test_cli_envdoc_json() {
+    let output = Command::new("cargo")
+        .args(["run", "--", "schema", "envdoc", "--schema", SCHEMA, "--prefix", "APP_", "--format", "json"])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let doc: serde_json::Value = serde_json::from_slice(&output.stdout).expect("Output should be JSON");
+    assert_eq!(doc["prefix"], "APP_");
+    assert_eq!(doc["variables"][4]["name"], "APP_LOG_LEVEL");
+    assert_eq!(doc["variables"][4]["path"], "logLevel");
+}
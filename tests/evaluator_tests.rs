@@ -0,0 +1,100 @@
+use std::path::PathBuf;
+use toml_and_jerry::error::AppError;
+use toml_and_jerry::validation::{validate_inputs_with_options, ValidationOptions};
+use jsonschema::Validator;
+use serde_json::Value as JsonValue;
+
+fn create_validator_from_schema_file(schema_path: &str) -> Validator {
+    let schema_content = std::fs::read_to_string(schema_path).expect("Failed to read schema file");
+    let schema: JsonValue = serde_json::from_str(&schema_content).expect("Failed to parse schema JSON");
+    Validator::new(&schema).expect("Failed to create validator")
+}
+
+// Writes an executable shell script standing in for an evaluator CLI
+#[cfg(unix)]
+fn stub_evaluator(name: &str, script: &str) -> PathBuf {
+    use std::os::unix::fs::PermissionsExt;
+    let dir = std::env::temp_dir().join(format!("toml-and-jerry-evaluators-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(name);
+    std::fs::write(&path, format!("#!/bin/sh\n{}\n", script)).unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    path
+}
+
+#[test]
+fn test_missing_cue_binary_is_reported() {
+    let validator = create_validator_from_schema_file("test-examples/schema.json");
+    let options = ValidationOptions {
+        cue_binary: Some(PathBuf::from("/nonexistent/cue")),
+        ..Default::default()
+    };
+    let errors = validate_inputs_with_options(vec![PathBuf::from("test-examples/valid-config.cue")], &validator, &options).unwrap();
+    assert!(errors.iter().any(|e| matches!(e, AppError::EvaluationError { tool, .. } if tool == "CUE")));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_cue_export_output_is_validated() {
+    let validator = create_validator_from_schema_file("test-examples/schema.json");
+    let cue = stub_evaluator("cue", r#"echo '{"name": "my-app-cue", "version": "1.4.0", "port": 80}'"#);
+    let options = ValidationOptions { cue_binary: Some(cue), ..Default::default() };
+    let errors = validate_inputs_with_options(vec![PathBuf::from("test-examples/valid-config.cue")], &validator, &options).unwrap();
+    assert!(errors.iter().any(|e| matches!(e, AppError::SchemaValidationError { .. })), "Port below minimum should fail");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_cue_error_position_is_mapped() {
+    let validator = create_validator_from_schema_file("test-examples/schema.json");
+    let cue = stub_evaluator(
+        "cue-failing",
+        r#"echo 'port: conflicting values 8443 and "x":' >&2; echo '    ./valid-config.cue:3:10' >&2; exit 1"#,
+    );
+    let options = ValidationOptions { cue_binary: Some(cue), ..Default::default() };
+    let errors = validate_inputs_with_options(vec![PathBuf::from("test-examples/valid-config.cue")], &validator, &options).unwrap();
+    match errors.first() {
+        Some(AppError::EvaluationError { span, .. }) => assert_eq!(span.offset(), 48, "Span should point at line 3, column 10"),
+        other => panic!("Expected an evaluation error, got {:?}", other),
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_pkl_error_position_is_mapped() {
+    let validator = create_validator_from_schema_file("test-examples/schema.json");
+    let pkl = stub_evaluator(
+        "pkl-failing",
+        r#"cat >&2 <<'OUT'
+–– Pkl Error ––
+Cannot find property `prot`.
+
+3 | port = prot
+           ^^^^
+at valid-config#port (file:///work/valid-config.pkl, line 3)
+OUT
+exit 1"#,
+    );
+    let options = ValidationOptions { pkl_binary: Some(pkl), ..Default::default() };
+    let errors = validate_inputs_with_options(vec![PathBuf::from("test-examples/valid-config.pkl")], &validator, &options).unwrap();
+    match errors.first() {
+        Some(AppError::EvaluationError { tool, span, .. }) => {
+            assert_eq!(tool, "Pkl");
+            assert_eq!(span.offset(), 45, "Span should point at line 3, column 8");
+        }
+        other => panic!("Expected an evaluation error, got {:?}", other),
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_nickel_type_error_is_a_parse_diagnostic() {
+    let validator = create_validator_from_schema_file("test-examples/schema.json");
+    let nickel = stub_evaluator(
+        "nickel-failing",
+        r#"echo 'error: contract broken by a value' >&2; echo '  ┌─ valid-config.ncl:4:19' >&2; exit 1"#,
+    );
+    let options = ValidationOptions { nickel_binary: Some(nickel), ..Default::default() };
+    let errors = validate_inputs_with_options(vec![PathBuf::from("test-examples/valid-config.ncl")], &validator, &options).unwrap();
+    assert!(matches!(errors.first(), Some(AppError::EvaluationError { tool, .. }) if tool == "Nickel"));
+}
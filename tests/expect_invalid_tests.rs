@@ -0,0 +1,57 @@
+use std::path::PathBuf;
+use std::process::Command;
+use toml_and_jerry::error::AppError;
+use toml_and_jerry::expect_invalid::{apply, ExpectInvalid};
+use toml_and_jerry::schema::load_and_compile_schema;
+use toml_and_jerry::validation::validate_inputs;
+
+const VALID: &str = "test-examples/valid-config.json";
+const BAD: &str = "test-examples/expect-invalid/bad/port-too-low.json";
+
+fn check(args: &[&str]) -> std::process::Output {
+    Command::new("cargo")
+        .args(["run", "--", "check", "--schema", "test-examples/schema.json"])
+        .args(args)
+        .output()
+        .expect("Failed to execute command")
+}
+
+#[test]
+fn test_expected_failures_are_dropped_and_valid_files_reported() {
+    let validator = load_and_compile_schema(&PathBuf::from("test-examples/schema.json")).unwrap();
+    let files = vec![PathBuf::from(VALID), PathBuf::from(BAD)];
+    let errors = validate_inputs(files.clone(), &validator).unwrap();
+    assert_eq!(errors.len(), 1);
+
+    let expectations = ExpectInvalid::new(false, &["test-examples/expect-invalid/bad/**".to_string()]).unwrap();
+    assert!(apply(&files, errors, |file| expectations.expects(file)).is_empty());
+
+    let errors = validate_inputs(files.clone(), &validator).unwrap();
+    let everything = ExpectInvalid::new(true, &[]).unwrap();
+    let kept = apply(&files, errors, |file| everything.expects(file));
+    assert_eq!(kept.len(), 1);
+    assert!(matches!(&kept[0], AppError::UnexpectedlyValid { path } if path == &PathBuf::from(VALID)));
+    assert!(!kept[0].is_warning());
+}
+
+#[test]
+fn test_expectations_reject_bad_globs() {
+    assert!(ExpectInvalid::new(false, &[]).unwrap().is_empty());
+    assert!(ExpectInvalid::new(false, &["bad/[".to_string()]).is_err());
+}
+
+#[test]
+fn test_cli_expect_invalid_flag() {
+    let output = check(&["--expect-invalid", BAD]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let output = check(&["--expect-invalid", VALID]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("app::expect::unexpectedly_valid"));
+}
+
+#[test]
+fn test_cli_project_config_globs() {
+    let output = check(&["--config", "test-examples/expect-invalid/project.toml", VALID, BAD]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+}
@@ -0,0 +1,49 @@
+use std::path::PathBuf;
+use std::process::Command;
+use toml_and_jerry::error::AppError;
+use toml_and_jerry::fallback::{validate_file, FallbackOutcome, SchemaCandidate};
+use toml_and_jerry::schema::load_and_compile_schema;
+use toml_and_jerry::validation::ValidationOptions;
+
+fn outcome_for(file: &str) -> (FallbackOutcome, Vec<AppError>) {
+    let v2 = load_and_compile_schema(&PathBuf::from("test-examples/fallback/v2.schema.json")).unwrap();
+    let v1 = load_and_compile_schema(&PathBuf::from("test-examples/fallback/v1.schema.json")).unwrap();
+    let candidates = [
+        SchemaCandidate { display: "v2".to_string(), validator: &v2, options: ValidationOptions::default() },
+        SchemaCandidate { display: "v1".to_string(), validator: &v1, options: ValidationOptions::default() },
+    ];
+    validate_file(&PathBuf::from(format!("test-examples/fallback/{}", file)), &candidates).unwrap()
+}
+
+#[test]
+fn test_first_schema_matches() {
+    let (outcome, errors) = outcome_for("current.yaml");
+    assert_eq!(outcome, FallbackOutcome::Matched(0));
+    assert!(errors.is_empty());
+}
+
+#[test]
+fn test_falls_back_to_older_schema() {
+    let (outcome, errors) = outcome_for("legacy.yaml");
+    assert_eq!(outcome, FallbackOutcome::Matched(1));
+    assert!(errors.is_empty());
+}
+
+#[test]
+fn test_reports_errors_of_the_closest_schema() {
+    let (outcome, errors) = outcome_for("broken.yaml");
+    assert_eq!(outcome, FallbackOutcome::Closest(1));
+    assert!(matches!(&errors[..], [AppError::SchemaValidationError(error)] if error.instance_path == "/port"));
+}
+
+#[test]
+fn test_schema_fallback_flag() {
+    let output = Command::new("cargo")
+        .args(["run", "--", "check", "test-examples/fallback/legacy.yaml", "test-examples/fallback/current.yaml"])
+        .args(["--schema", "test-examples/fallback/v2.schema.json", "--schema-fallback", "test-examples/fallback/v1.schema.json"])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("test-examples/fallback/legacy.yaml: matches \"test-examples/fallback/v1.schema.json\""), "{}", stdout);
+}
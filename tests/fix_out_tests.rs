@@ -0,0 +1,65 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use toml_and_jerry::enum_map::load_mappings;
+use toml_and_jerry::fix::{patch_path, write_patches};
+use toml_and_jerry::schema::load_and_compile_schema;
+use toml_and_jerry::validation::{validate_inputs_with_options, ValidationOptions};
+
+const SERVICES: &str = "test-examples/enum_map/services.yaml";
+
+fn out_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("toml-and-jerry-fix-out-{}-{}", name, std::process::id()));
+    fs::remove_dir_all(&dir).ok();
+    dir
+}
+
+#[test]
+fn test_patch_paths_mirror_the_input() {
+    let dir = Path::new("patches");
+    assert_eq!(patch_path(dir, Path::new("configs/app.yaml")), Path::new("patches/configs/app.yaml.patch"));
+    assert_eq!(patch_path(dir, Path::new("./app.yaml")), Path::new("patches/app.yaml.patch"));
+    assert_eq!(patch_path(dir, Path::new("/etc/app/../app.toml")), Path::new("patches/etc/app/app.toml.patch"));
+}
+
+#[test]
+fn test_patches_are_written_and_inputs_left_alone() {
+    let before = fs::read_to_string(SERVICES).unwrap();
+    let validator = load_and_compile_schema(&PathBuf::from("test-examples/enum_map/schema.json")).unwrap();
+    let options = ValidationOptions {
+        enum_mappings: load_mappings(&PathBuf::from("test-examples/enum_map/rules.toml")).unwrap(),
+        ..Default::default()
+    };
+    let errors = validate_inputs_with_options(vec![PathBuf::from(SERVICES)], &validator, &options).unwrap();
+
+    let dir = out_dir("lib");
+    let written = write_patches(&errors, &dir).unwrap();
+    assert_eq!(written, vec![(dir.join("test-examples/enum_map/services.yaml.patch"), 2)]);
+    let patch = fs::read_to_string(&written[0].0).unwrap();
+    assert!(patch.starts_with("--- a/test-examples/enum_map/services.yaml\n+++ b/test-examples/enum_map/services.yaml\n"), "{}", patch);
+    assert!(patch.contains("-    log_level: warn # noisy otherwise\n+    log_level: warning # noisy otherwise\n"), "{}", patch);
+    assert_eq!(fs::read_to_string(SERVICES).unwrap(), before);
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn test_cli_fix_out_writes_patches_instead_of_fixing() {
+    let before = fs::read_to_string(SERVICES).unwrap();
+    let dir = out_dir("cli");
+    let output = Command::new("cargo")
+        .args(["run", "--", "check", "--schema", "test-examples/enum_map/schema.json", SERVICES])
+        .args(["--enum-map", "test-examples/enum_map/rules.toml", "--fix", "--fix-out"])
+        .arg(&dir)
+        .output()
+        .expect("Failed to execute command");
+    assert!(dir.join("test-examples/enum_map/services.yaml.patch").is_file(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(fs::read_to_string(SERVICES).unwrap(), before, "--fix-out never touches the inputs");
+    fs::remove_dir_all(&dir).ok();
+
+    let output = Command::new("cargo")
+        .args(["run", "--", "check", "--schema", "test-examples/enum_map/schema.json", SERVICES])
+        .args(["--fix", "--diff", "--fix-out", "patches"])
+        .output()
+        .expect("Failed to execute command");
+    assert_eq!(output.status.code(), Some(2), "--fix-out and --diff conflict");
+}
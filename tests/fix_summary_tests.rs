@@ -0,0 +1,50 @@
+use std::path::PathBuf;
+use toml_and_jerry::enum_map::load_mappings;
+use toml_and_jerry::fix::{render_changelog, summarize};
+use toml_and_jerry::schema::{compile_schema, load_schema_json};
+use toml_and_jerry::validation::{validate_inputs_with_options, ValidationOptions};
+
+fn fixable_errors() -> Vec<toml_and_jerry::error::AppError> {
+    let schema_path = "test-examples/enum_map/schema.json";
+    let schema = load_schema_json(&PathBuf::from(schema_path)).unwrap();
+    let validator = compile_schema(&schema, schema_path).unwrap();
+    let options = ValidationOptions {
+        enum_mappings: load_mappings(&PathBuf::from("test-examples/enum_map/rules.toml")).unwrap(),
+        ..Default::default()
+    };
+    validate_inputs_with_options(vec![PathBuf::from("test-examples/enum_map/services.yaml")], &validator, &options).unwrap()
+}
+
+#[test]
+fn test_summary_lists_files_keys_and_rules() {
+    let errors = fixable_errors();
+    let summary = summarize(&errors, &[(PathBuf::from("test-examples/enum_map/services.yaml"), 2)]);
+    assert_eq!(summary.total_changes, 2);
+    assert_eq!(summary.rules_satisfied, vec!["app::schema::renamed_enum_value"]);
+    assert_eq!(summary.files.len(), 1);
+    let change = summary.files[0].changes.iter().find(|c| c.pointer == "/services/api/log_level").expect("api change");
+    assert_eq!((change.kind, change.from.as_str(), change.to.as_str()), ("value", "warn", "warning"));
+
+    let json = serde_json::to_value(&summary).unwrap();
+    assert_eq!(json["files"][0]["path"], "test-examples/enum_map/services.yaml");
+    assert_eq!(json["rulesSatisfied"][0], "app::schema::renamed_enum_value");
+}
+
+#[test]
+fn test_changelog_reads_as_commit_message() {
+    let errors = fixable_errors();
+    let summary = summarize(&errors, &[(PathBuf::from("test-examples/enum_map/services.yaml"), 2)]);
+    let changelog = render_changelog(&summary);
+    let mut lines = changelog.lines();
+    assert_eq!(lines.next(), Some("Apply 2 automatic fixes in 1 file"));
+    assert_eq!(lines.next(), Some(""), "Subject is separated from the body");
+    assert!(changelog.contains("Rules satisfied: app::schema::renamed_enum_value\n"), "{}", changelog);
+    assert!(changelog.contains("- /services/api/log_level: change `warn` to `warning`\n"), "{}", changelog);
+}
+
+#[test]
+fn test_files_not_fixed_are_left_out() {
+    let summary = summarize(&fixable_errors(), &[]);
+    assert_eq!(summary.total_changes, 0);
+    assert!(summary.files.is_empty() && summary.rules_satisfied.is_empty());
+}
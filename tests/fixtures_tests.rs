@@ -0,0 +1,61 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use serde_json::json;
+use toml_and_jerry::fixtures::{check_fixtures, fixture_suite};
+use toml_and_jerry::schema::load_schema_json;
+
+const SCHEMA: &str = "test-examples/fixtures/schema.json";
+
+#[test]
+fn test_one_fixture_per_branch() {
+    let schema = load_schema_json(&PathBuf::from(SCHEMA)).unwrap();
+    let suite = fixture_suite(&schema);
+    let names: Vec<&str> = suite.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(names, ["minimal", "full", "auth-basic", "mode-safe", "auth-level-b"], "the enum inside the Basic arm is reached through it");
+
+    assert_eq!(suite[0].value, json!({ "name": "billing", "port": 8080, "auth": { "kind": "token", "token": "examplexxxxx" } }), "examples and defaults fill leaves");
+    assert_eq!(suite[2].value["auth"]["kind"], "basic");
+    assert_eq!(suite[4].value["auth"]["level"], "b");
+
+    let (accepted, rejected) = check_fixtures(&schema, SCHEMA, suite.clone()).unwrap();
+    assert!(rejected.is_empty(), "{:?}", rejected);
+    assert_eq!(accepted, suite);
+    assert_eq!(fixture_suite(&schema), suite, "generation is deterministic");
+}
+
+#[test]
+fn test_unsatisfied_keywords_are_rejected() {
+    let schema = json!({ "type": "object", "required": ["id"], "properties": { "id": { "type": "string", "pattern": "^[0-9]+$" } } });
+    let (accepted, rejected) = check_fixtures(&schema, "inline", fixture_suite(&schema)).unwrap();
+    assert!(accepted.is_empty());
+    assert_eq!(rejected[0].name, "minimal");
+    assert!(rejected[0].reason.contains("/id"), "{}", rejected[0].reason);
+
+    let with_example = json!({ "type": "object", "required": ["id"], "properties": { "id": { "type": "string", "pattern": "^[0-9]+$", "examples": ["42"] } } });
+    let (accepted, rejected) = check_fixtures(&with_example, "inline", fixture_suite(&with_example)).unwrap();
+    assert!(rejected.is_empty() && accepted.len() == 1, "{:?}", rejected);
+}
+
+#[test]
+fn test_cli_writes_fixtures_that_pass_check() {
+    let out = std::env::temp_dir().join(format!("toml-and-jerry-fixtures-{}", std::process::id()));
+    fs::remove_dir_all(&out).ok();
+    let output = Command::new("cargo")
+        .args(["run", "--", "fixtures", "--schema", SCHEMA, "--format", "yaml", "--out"])
+        .arg(&out)
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let mut written: Vec<String> = fs::read_dir(&out).unwrap().map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned()).collect();
+    written.sort();
+    assert_eq!(written, ["auth-basic.yaml", "auth-level-b.yaml", "full.yaml", "minimal.yaml", "mode-safe.yaml"]);
+    let check = Command::new("cargo")
+        .args(["run", "--", "check", "--schema", SCHEMA])
+        .args(written.iter().map(|name| out.join(name)))
+        .output()
+        .expect("Failed to execute command");
+    fs::remove_dir_all(&out).ok();
+    assert!(check.status.success(), "{}", String::from_utf8_lossy(&check.stderr));
+}
@@ -0,0 +1,78 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use toml_and_jerry::fix::{plan_fixes, unified_diff};
+use toml_and_jerry::git_guard::{dirty_files, refusal};
+use toml_and_jerry::renames::collect_rename_rules;
+use toml_and_jerry::schema::{compile_schema, load_schema_json};
+use toml_and_jerry::validation::{validate_inputs_with_options, ValidationOptions};
+
+fn git(dir: &Path, args: &[&str]) {
+    let status = Command::new("git").arg("-C").arg(dir).args(args).status().expect("git should run");
+    assert!(status.success(), "git {:?} failed", args);
+}
+
+// A scratch repository with `committed.toml` committed and `edited.toml` changed after the commit
+fn scratch_repo(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("toml-and-jerry-guard-{}-{}", name, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    git(&dir, &["init", "-q"]);
+    fs::write(dir.join("committed.toml"), "port = 80\n").unwrap();
+    fs::write(dir.join("edited.toml"), "port = 80\n").unwrap();
+    git(&dir, &["add", "."]);
+    git(&dir, &["-c", "user.name=test", "-c", "user.email=test@example.com", "commit", "-qm", "init"]);
+    fs::write(dir.join("edited.toml"), "port = 8080\n").unwrap();
+    fs::write(dir.join("new.toml"), "port = 1\n").unwrap();
+    dir
+}
+
+#[test]
+fn test_only_files_with_uncommitted_changes_are_dirty() {
+    let dir = scratch_repo("status");
+    let dirty = dirty_files(&[dir.join("committed.toml"), dir.join("edited.toml"), dir.join("new.toml")]);
+    fs::remove_dir_all(&dir).ok();
+    assert_eq!(dirty, vec![dir.join("edited.toml"), dir.join("new.toml")]);
+    assert!(refusal(&dirty).contains("--allow-dirty"));
+}
+
+#[test]
+fn test_files_outside_a_repository_are_not_guarded() {
+    let dir = std::env::temp_dir().join(format!("toml-and-jerry-guard-norepo-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("config.toml"), "port = 80\n").unwrap();
+    let dirty = dirty_files(&[dir.join("config.toml")]);
+    fs::remove_dir_all(&dir).ok();
+    assert!(dirty.is_empty());
+}
+
+#[test]
+fn test_dry_run_diff_leaves_file_untouched() {
+    let schema_path = "test-examples/renames/schema.json";
+    let schema = load_schema_json(&PathBuf::from(schema_path)).unwrap();
+    let validator = compile_schema(&schema, schema_path).unwrap();
+    let options = ValidationOptions { rename_rules: collect_rename_rules(&schema), ..Default::default() };
+    let input = PathBuf::from("test-examples/renames/legacy.toml");
+    let before = fs::read_to_string(&input).unwrap();
+    let errors = validate_inputs_with_options(vec![input.clone()], &validator, &options).unwrap();
+
+    let planned = plan_fixes(&errors).unwrap();
+    assert_eq!(planned.len(), 1);
+    let diff = unified_diff(&planned[0]);
+    assert!(diff.starts_with("--- a/test-examples/renames/legacy.toml\n+++ b/test-examples/renames/legacy.toml\n"), "{}", diff);
+    assert!(diff.lines().any(|l| l.starts_with('-') && !l.starts_with("---")), "{}", diff);
+    assert!(diff.lines().any(|l| l.starts_with('+') && !l.starts_with("+++")), "{}", diff);
+    assert_eq!(fs::read_to_string(&input).unwrap(), before, "Planning must not write");
+}
+
+#[test]
+fn test_fix_check_fails_without_writing() {
+    let input = "test-examples/renames/legacy.toml";
+    let before = fs::read_to_string(input).unwrap();
+    let output = Command::new("cargo")
+        .args(["run", "--", "check", input, "--schema", "test-examples/renames/schema.json", "--fix", "--check", "--diff"])
+        .output()
+        .expect("Failed to execute command");
+    assert!(!output.status.success(), "Pending fixes must fail --check");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("+++ b/test-examples/renames/legacy.toml"));
+    assert_eq!(fs::read_to_string(input).unwrap(), before);
+}
@@ -0,0 +1,39 @@
+use std::path::PathBuf;
+use miette::{Diagnostic, SourceSpan};
+use toml_and_jerry::error::{AppError, RelatedErrors, SchemaValidationError};
+use toml_and_jerry::grouping::group_related;
+use toml_and_jerry::violation::Violation;
+
+const SOURCE: &str = "{\"tls\": {\"enabled\": true}}";
+
+fn missing(path: &str, property: &str) -> AppError {
+    AppError::SchemaValidationError(Box::new(SchemaValidationError {
+        path: PathBuf::from(path),
+        message: "Schema validation failed".to_string(),
+        source_code: SOURCE.to_string(),
+        error_span: SourceSpan::new(8.into(), 17),
+        label_message: format!("missing `{}`", property),
+        instance_path: "/tls".to_string(),
+        schema_path: "/properties/tls/required".to_string(),
+        kind: format!("Required {{ property: String(\"{}\") }}", property),
+        violation: Violation { keyword: "required".to_string(), expected: Some(property.into()), actual: None },
+    }))
+}
+
+#[test]
+fn test_required_pair_becomes_one_report() {
+    let grouped = group_related(vec![missing("a.json", "cert"), missing("a.json", "key")]);
+    assert_eq!(grouped.len(), 1);
+    let AppError::RelatedErrors(error) = &grouped[0] else { panic!("{:?}", grouped) };
+    let RelatedErrors { count, message, members, .. } = error.as_ref();
+    assert_eq!((*count, message.as_str(), members.len()), (2, "Required at /tls", 2));
+    let labels: Vec<_> = grouped[0].labels().unwrap().map(|l| l.label().unwrap_or_default().to_string()).collect();
+    assert_eq!(labels, ["missing `cert`", "missing `key`"]);
+}
+
+#[test]
+fn test_unrelated_errors_stay_separate() {
+    let grouped = group_related(vec![missing("a.json", "cert"), missing("b.json", "cert")]);
+    assert!(grouped.iter().all(|e| matches!(e, AppError::SchemaValidationError { .. })));
+    assert_eq!(grouped.len(), 2);
+}
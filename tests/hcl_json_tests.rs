@@ -0,0 +1,39 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde_json::{json, Value as JsonValue};
+use toml_and_jerry::hcl_json::{is_hcl_json, normalize};
+use toml_and_jerry::schema::{compile_schema, load_schema_json};
+use toml_and_jerry::validation::validate_inputs;
+
+#[test]
+fn test_detects_hcl_json_file_names() {
+    assert!(is_hcl_json(Path::new("infra/main.tf.json")));
+    assert!(is_hcl_json(Path::new("app.hcl.json")));
+    assert!(!is_hcl_json(Path::new("package.json")));
+}
+
+#[test]
+fn test_json_syntax_normalizes_to_native_shape() {
+    let native: JsonValue = hcl::from_str(&fs::read_to_string("test-examples/hcl_json/main.hcl").unwrap()).unwrap();
+    let generated: JsonValue = serde_json::from_str(&fs::read_to_string("test-examples/hcl_json/main.tf.json").unwrap()).unwrap();
+    assert_eq!(normalize(generated), native);
+}
+
+#[test]
+fn test_repeated_bodies_stay_an_array() {
+    let value = json!({ "provider": { "aws": [{ "region": "a" }, { "region": "b" }] }, "locals": [{ "x": 1 }] });
+    assert_eq!(
+        normalize(value),
+        json!({ "provider": { "aws": [{ "region": "a" }, { "region": "b" }] }, "locals": [{ "x": 1 }] })
+    );
+}
+
+#[test]
+fn test_both_syntaxes_validate_against_one_schema() {
+    let schema_path = "test-examples/hcl_json/schema.json";
+    let validator = compile_schema(&load_schema_json(&PathBuf::from(schema_path)).unwrap(), schema_path).unwrap();
+    for input in ["test-examples/hcl_json/main.hcl", "test-examples/hcl_json/main.tf.json"] {
+        let errors = validate_inputs(vec![PathBuf::from(input)], &validator).unwrap();
+        assert!(errors.is_empty(), "{} should be valid: {:?}", input, errors);
+    }
+}
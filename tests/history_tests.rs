@@ -0,0 +1,78 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use toml_and_jerry::error::AppError;
+use toml_and_jerry::history::{revisions_since, validate_history, History};
+use toml_and_jerry::schema::load_and_compile_schema;
+use toml_and_jerry::validation::{validate_inputs, PrintableError};
+
+fn git(dir: &Path, args: &[&str]) {
+    let status = Command::new("git").arg("-C").arg(dir).args(args).status().expect("git should run");
+    assert!(status.success(), "git {:?} failed", args);
+}
+
+fn commit(dir: &Path, file: &str, content: &str, message: &str) {
+    fs::create_dir_all(dir.join(file).parent().unwrap()).unwrap();
+    fs::write(dir.join(file), content).unwrap();
+    git(dir, &["add", "."]);
+    git(dir, &["-c", "user.name=test", "-c", "user.email=test@example.com", "commit", "-qm", message]);
+}
+
+const VALID: &str = "{\"name\": \"billing\", \"version\": \"1.0.0\", \"port\": 8080}\n";
+const INVALID: &str = "{\"name\": \"billing\", \"version\": \"1.0.0\", \"port\": 80}\n";
+
+// A repository where configs/app.json is valid, made invalid, left alone, and fixed, in
+// that order; the working tree has an uncommitted invalid edit
+fn scratch_repo(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("toml-and-jerry-history-test-{}-{}", name, std::process::id()));
+    fs::remove_dir_all(&dir).ok();
+    fs::create_dir_all(&dir).unwrap();
+    git(&dir, &["init", "-q"]);
+    commit(&dir, "configs/app.json", VALID, "Add app config");
+    git(&dir, &["tag", "v1"]);
+    commit(&dir, "configs/app.json", INVALID, "Move to port 80");
+    commit(&dir, "README.md", "docs\n", "Write docs");
+    commit(&dir, "configs/app.json", VALID, "Back to 8080");
+    fs::write(dir.join("configs/app.json"), INVALID).unwrap();
+    dir
+}
+
+fn check(dir: &Path, history: History) -> Result<Vec<AppError>, AppError> {
+    let validator = load_and_compile_schema(&PathBuf::from("test-examples/schema.json")).unwrap();
+    validate_history(dir, &[PathBuf::from("configs")], Some(&history), |inputs| validate_inputs(inputs, &validator))
+}
+
+#[test]
+fn test_at_reads_the_revision_not_the_working_tree() {
+    let dir = scratch_repo("at");
+    let at_tag = check(&dir, History::At("v1".to_string())).unwrap();
+    let at_parent = check(&dir, History::At("HEAD~2".to_string())).unwrap();
+    let working_tree = fs::read_to_string(dir.join("configs/app.json")).unwrap();
+    fs::remove_dir_all(&dir).ok();
+
+    assert!(at_tag.is_empty(), "{:?}", at_tag);
+    assert_eq!(at_parent.len(), 1);
+    assert_eq!(PrintableError::from(&at_parent[0]).file_path, "HEAD~2:configs/app.json", "files are named as git show names them");
+    assert_eq!(working_tree, INVALID, "the working tree is left as it was");
+}
+
+#[test]
+fn test_since_visits_the_commits_that_changed_the_inputs() {
+    let dir = scratch_repo("since");
+    let revisions = revisions_since(&dir, "v1", &[PathBuf::from("configs")]).unwrap();
+    let errors = check(&dir, History::Since("v1".to_string())).unwrap();
+    fs::remove_dir_all(&dir).ok();
+
+    let subjects: Vec<&str> = revisions.iter().map(|r| r.subject.as_str()).collect();
+    assert_eq!(subjects, ["Add app config", "Move to port 80", "Back to 8080"], "commits that did not touch the inputs are skipped");
+    assert_eq!(errors.len(), 1, "only the revision that moved to port 80 fails");
+    assert_eq!(PrintableError::from(&errors[0]).file_path, format!("{}:configs/app.json", revisions[1].name));
+}
+
+#[test]
+fn test_unknown_revision_is_an_error() {
+    let dir = scratch_repo("unknown");
+    let result = check(&dir, History::At("no-such-tag".to_string()));
+    fs::remove_dir_all(&dir).ok();
+    assert!(matches!(result, Err(AppError::HistoryError { ref revision, .. }) if revision == "no-such-tag"), "{:?}", result);
+}
@@ -0,0 +1,79 @@
+use std::path::PathBuf;
+use std::process::Command;
+use serde_json::json;
+use toml_and_jerry::error::{AppError, SchemaValidationError};
+use toml_and_jerry::hjson::parse_hjson;
+use toml_and_jerry::schema::load_and_compile_schema;
+use toml_and_jerry::validation::validate_inputs;
+
+const SCHEMA: &str = "test-examples/hjson/schema.json";
+
+fn validate(file: &str) -> Vec<AppError> {
+    let validator = load_and_compile_schema(&PathBuf::from(SCHEMA)).unwrap();
+    validate_inputs(vec![PathBuf::from(file)], &validator).unwrap()
+}
+
+#[test]
+fn test_hjson_reads_as_json() {
+    let content = "# comment\n{\n  name: billing service\n  port: 8080 # http\n  \"quoted key\": 'it\\'s'\n  path: /srv // kept\n  list: [1, 2, 3]\n  nested: { flag: true }\n}\n";
+    let document = parse_hjson(content).unwrap();
+    assert_eq!(
+        document.value,
+        json!({
+            "name": "billing service",
+            "port": 8080,
+            "quoted key": "it's",
+            "path": "/srv // kept",
+            "list": [1, 2, 3],
+            "nested": { "flag": true }
+        })
+    );
+    assert_eq!(document.span_for_pointer("/port"), Some(38..42));
+    assert_eq!(document.span_for_pointer("/list/1"), Some(113..114));
+}
+
+#[test]
+fn test_braceless_roots_and_multiline_strings() {
+    let content = "name: app\nmotd:\n  '''\n  first\n    second\n  '''\n";
+    assert_eq!(parse_hjson(content).unwrap().value, json!({ "name": "app", "motd": "first\n  second" }));
+    assert_eq!(parse_hjson("").unwrap().value, json!({}));
+    assert_eq!(parse_hjson("[true, null]").unwrap().value, json!([true, null]));
+}
+
+#[test]
+fn test_malformed_documents_are_errors() {
+    let error = parse_hjson("a: 1\na: 2\n").unwrap_err();
+    assert_eq!((error.message.as_str(), error.span), ("key `a` is set twice", 5..6));
+    assert!(parse_hjson("{ a: 1\n").unwrap_err().message.contains("unclosed `{`"));
+    assert!(parse_hjson("a: \"open\n").unwrap_err().message.contains("unclosed string"));
+    assert!(parse_hjson("{ a: 1 } b").unwrap_err().message.contains("after the root value"));
+    // Quoteless strings run to the end of the line, closing bracket included
+    assert!(parse_hjson("list: [a, b]\n").is_err());
+}
+
+#[test]
+fn test_hjson_files_validate() {
+    let errors = validate("test-examples/hjson/service.hjson");
+    assert!(errors.is_empty(), "{:?}", errors);
+
+    let errors = validate("test-examples/hjson/low-port.hjson");
+    let [AppError::SchemaValidationError(error)] = &errors[..] else { panic!("{:?}", errors) };
+    let SchemaValidationError { error_span, source_code, .. } = error.as_ref();
+    assert_eq!(&source_code[error_span.offset()..error_span.offset() + error_span.len()], "port");
+
+    let errors = validate("test-examples/hjson/broken.hjson");
+    let [AppError::HjsonParseError { span, .. }] = &errors[..] else { panic!("{:?}", errors) };
+    assert_eq!(span.offset(), 0, "Points at the unclosed brace");
+}
+
+#[test]
+fn test_cli_reports_hjson_parse_errors() {
+    let output = Command::new("cargo")
+        .args(["run", "--", "check", "--schema", SCHEMA, "test-examples/hjson/broken.hjson", "--format", "json"])
+        .output()
+        .expect("Failed to execute command");
+    assert!(!output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).expect("stdout should be the report");
+    assert_eq!(report["errors"][0]["ruleId"], "app::hjson::parse_error");
+    assert_eq!(report["errors"][0]["category"], "parse");
+}
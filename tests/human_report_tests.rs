@@ -0,0 +1,58 @@
+use std::path::PathBuf;
+use std::process::Command;
+use toml_and_jerry::human_report::{render_by_file, render_by_rule, GroupBy};
+use toml_and_jerry::schema::load_and_compile_schema;
+use toml_and_jerry::validation::validate_inputs;
+
+const FILES: [&str; 3] = ["test-examples/group-by/api.json", "test-examples/group-by/web.toml", "test-examples/group-by/worker.yaml"];
+
+fn diagnostics() -> Vec<toml_and_jerry::error::AppError> {
+    let validator = load_and_compile_schema(&PathBuf::from("test-examples/schema.json")).unwrap();
+    validate_inputs(FILES.iter().map(PathBuf::from).collect(), &validator).unwrap()
+}
+
+#[test]
+fn test_group_by_parses_its_names() {
+    assert_eq!(GroupBy::parse("file"), Some(GroupBy::File));
+    assert_eq!(GroupBy::parse("rule"), Some(GroupBy::Rule));
+    assert_eq!(GroupBy::parse("severity"), None);
+}
+
+#[test]
+fn test_by_file_puts_each_files_reports_under_its_header() {
+    let report = render_by_file(diagnostics());
+    for file in FILES {
+        let header = report.find(&format!("==> {} <==", file)).unwrap_or_else(|| panic!("No header for {}", file));
+        let verdict = report.find(&format!("FAIL {}:", file)).unwrap();
+        assert!(header < verdict);
+    }
+    // Validation reports a file's first schema error only, so worker.yaml's version is not listed
+    assert!(report.contains("FAIL test-examples/group-by/worker.yaml: 1 error(s)"), "{}", report);
+    assert!(report.contains("FAIL test-examples/group-by/api.json: 1 error(s)"), "{}", report);
+}
+
+#[test]
+fn test_by_rule_clusters_the_same_violation_across_files() {
+    let report = render_by_rule(&diagnostics());
+    let first = report.lines().next().unwrap();
+    assert_eq!(first, "error [app::schema::validation_error] x3 (at /port)");
+    assert_eq!(report.lines().nth(1).unwrap(), "  Schema validation error: Schema validation failed", "The file is left out of the message");
+    for file in FILES {
+        assert!(report.contains(&format!("    {}:", file)), "{} should be listed with its position:\n{}", file, report);
+    }
+    assert!(report.trim_end().ends_with("3 error(s) in 3 file(s)"));
+}
+
+#[test]
+fn test_cli_group_by_rule() {
+    let output = Command::new("cargo")
+        .args(["run", "--", "check", "--schema", "test-examples/schema.json", "--group-by", "rule"])
+        .args(FILES)
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("x3 (at /port)"), "{}", stderr);
+    assert!(!stderr.contains("==> "), "Rule grouping has no file sections");
+}
@@ -0,0 +1,76 @@
+use std::path::PathBuf;
+use std::process::Command;
+use serde_json::json;
+use toml_and_jerry::error::{AppError, SchemaValidationError};
+use toml_and_jerry::ini::{parse_ini, IniCoercion};
+use toml_and_jerry::schema::load_and_compile_schema;
+use toml_and_jerry::validation::{validate_inputs_with_options, ValidationOptions};
+
+const SCHEMA: &str = "test-examples/ini/schema.json";
+
+fn validate(file: &str, coercion: IniCoercion) -> Vec<AppError> {
+    let validator = load_and_compile_schema(&PathBuf::from(SCHEMA)).unwrap();
+    let options = ValidationOptions { ini_coercion: coercion, ..Default::default() };
+    validate_inputs_with_options(vec![PathBuf::from(file)], &validator, &options).unwrap()
+}
+
+const BOTH: IniCoercion = IniCoercion { booleans: true, numbers: true };
+
+#[test]
+fn test_sections_become_objects_of_strings() {
+    let document = parse_ini("; comment\nname = billing\n\n[server]\nhost: \"a b\"\nport = 8080\n", IniCoercion::default()).unwrap();
+    assert_eq!(document.value, json!({ "name": "billing", "server": { "host": "a b", "port": "8080" } }));
+    assert_eq!(document.span_for_pointer("/server/port"), Some(47..51));
+    assert_eq!(document.span_for_pointer("/server/missing"), Some(26..34), "Falls back to the section header");
+}
+
+#[test]
+fn test_coercion_reads_booleans_and_numbers() {
+    let document = parse_ini("[s]\na = yes\nb = Off\nc = 42\nd = 1.5\ne = 1.2.3\n", BOTH).unwrap();
+    assert_eq!(document.value, json!({ "s": { "a": true, "b": false, "c": 42, "d": 1.5, "e": "1.2.3" } }));
+    let numbers_only = IniCoercion::parse(&["numbers".to_string()]).unwrap();
+    assert_eq!(parse_ini("a = yes\nb = 7\n", numbers_only).unwrap().value, json!({ "a": "yes", "b": 7 }));
+    assert!(IniCoercion::parse(&["dates".to_string()]).is_err());
+}
+
+#[test]
+fn test_malformed_lines_are_errors() {
+    let error = parse_ini("[server\n", IniCoercion::default()).unwrap_err();
+    assert_eq!(error.span, 0..7);
+    assert!(parse_ini("[a]\nx = 1\nx = 2\n", IniCoercion::default()).unwrap_err().message.contains("set twice"));
+    assert!(parse_ini("just words\n", IniCoercion::default()).is_err());
+    assert!(parse_ini("= 1\n", IniCoercion::default()).is_err());
+}
+
+#[test]
+fn test_ini_and_cfg_inputs_are_validated() {
+    assert!(validate("test-examples/ini/app.ini", BOTH).is_empty());
+    let errors = validate("test-examples/ini/app.ini", IniCoercion::default());
+    assert!(
+        matches!(&errors[0], AppError::CoercibleType(error) if error.instance_path == "/server/port" && error.hint.contains("--ini-coerce numbers")),
+        "Without coercion every value is a string: {:?}",
+        errors
+    );
+
+    let errors = validate("test-examples/ini/low-port.cfg", BOTH);
+    let AppError::SchemaValidationError(error) = &errors[0] else { panic!("{:?}", errors) };
+    let SchemaValidationError { error_span, source_code, .. } = error.as_ref();
+    assert_eq!(&source_code[error_span.offset()..error_span.offset() + error_span.len()], "port");
+
+    let errors = validate("test-examples/ini/broken.ini", BOTH);
+    assert!(matches!(&errors[..], [AppError::IniParseError { .. }]), "{:?}", errors);
+}
+
+#[test]
+fn test_cli_ini_coerce_flag() {
+    let check = |extra: &[&str]| {
+        Command::new("cargo")
+            .args(["run", "--", "check", "--schema", SCHEMA, "test-examples/ini/app.ini"])
+            .args(extra)
+            .output()
+            .expect("Failed to execute command")
+    };
+    assert_eq!(check(&[]).status.code(), Some(1));
+    let output = check(&["--ini-coerce", "booleans,numbers"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+}
@@ -58,9 +58,9 @@ fn test_json_output_format() {
     assert!(!output.status.success(), "Command should fail for invalid file");
     let stdout = String::from_utf8_lossy(&output.stdout);
     
-    // Find the JSON array in the output (skip the processing messages)
+    // Find the JSON report in the output (skip the processing messages)
     let json_lines: Vec<&str> = stdout.lines()
-        .skip_while(|line| !line.trim().starts_with("["))
+        .skip_while(|line| !line.trim().starts_with("{"))
         .collect();
     let json_output_str = json_lines.join("\n");
     
@@ -68,8 +68,8 @@ fn test_json_output_format() {
     let json_output: serde_json::Value = serde_json::from_str(&json_output_str)
         .expect("Output should be valid JSON");
     
-    assert!(json_output.is_array(), "JSON output should be an array");
-    assert!(!json_output.as_array().unwrap().is_empty(), "JSON output should contain errors");
+    assert!(json_output["errors"].is_array(), "JSON report should hold an errors array");
+    assert!(!json_output["errors"].as_array().unwrap().is_empty(), "JSON output should contain errors");
 }
 
 #[test]
@@ -126,4 +126,51 @@ fn test_nonexistent_schema() {
         .expect("Failed to execute command");
 
     assert!(!output.status.success(), "Command should fail for nonexistent schema");
-} 
\ No newline at end of file
+} 
+#[test]
+fn test_json_stdout_is_report_only() {
+    let output = Command::new("cargo")
+        .args(&["run", "--", "check", "test-examples/invalid-config.json", "--schema", "test-examples/schema.json", "--format", "json"])
+        .output()
+        .expect("Failed to execute command");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json_output: serde_json::Value = serde_json::from_str(&stdout)
+        .expect("stdout should be exactly the JSON report");
+    assert_eq!(json_output["reportVersion"], 5);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Processing file:"));
+}
+
+#[test]
+fn test_json_diagnostics_carry_positions() {
+    let output = Command::new("cargo")
+        .args(&["run", "--", "check", "test-examples/invalid-syntax.json", "--schema", "test-examples/schema.json", "--format", "json"])
+        .output()
+        .expect("Failed to execute command");
+
+    let json_output: serde_json::Value = serde_json::from_slice(&output.stdout).expect("stdout should be the JSON report");
+    let diagnostic = &json_output["errors"][0];
+    assert_eq!(diagnostic["line"], 4, "{}", diagnostic);
+    assert!(diagnostic["column"].as_u64().is_some_and(|column| column > 0), "{}", diagnostic);
+    assert_eq!(diagnostic["snippet"]["startLine"], 4, "{}", diagnostic);
+    assert!(diagnostic["snippet"]["text"].as_str().unwrap().contains("\"port\": 8080"), "{}", diagnostic);
+}
+
+#[test]
+fn test_output_file_writes_report() {
+    let dir = std::env::temp_dir().join(format!("tj-output-file-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let report = dir.join("report.json");
+    let output = Command::new("cargo")
+        .args(&["run", "--", "check", "test-examples/invalid-config.json", "--schema", "test-examples/schema.json", "--format", "json", "--output-file"])
+        .arg(&report)
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success(), "Command should fail for invalid file");
+    let written = std::fs::read_to_string(&report).expect("report file should be written");
+    let json_output: serde_json::Value = serde_json::from_str(&written).expect("report file should be valid JSON");
+    assert!(!json_output["errors"].as_array().unwrap().is_empty());
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("reportVersion"));
+    std::fs::remove_dir_all(&dir).ok();
+}
@@ -0,0 +1,90 @@
+use std::path::PathBuf;
+use std::process::Command;
+use miette::Diagnostic;
+use toml_and_jerry::error::{AppError, SchemaValidationError};
+use toml_and_jerry::ndjson::records;
+use toml_and_jerry::renames::collect_rename_rules;
+use toml_and_jerry::schema::{compile_schema, load_and_compile_schema, load_schema_json};
+use toml_and_jerry::validation::{validate_inputs, validate_inputs_with_options, PrintableError, ValidationOptions};
+
+const SCHEMA: &str = "test-examples/jsonl/schema.json";
+
+fn validate(file: &str) -> Vec<AppError> {
+    let validator = load_and_compile_schema(&PathBuf::from(SCHEMA)).unwrap();
+    validate_inputs(vec![PathBuf::from(file)], &validator).unwrap()
+}
+
+#[test]
+fn test_each_line_is_a_record() {
+    let content = "{\"a\": 1}\r\n\n  \n{\"a\": 2}\n{oops\n";
+    let parsed = records(content);
+    let lines: Vec<_> = parsed.iter().map(|r| (r.line, &content[r.span.clone()], r.value.is_ok())).collect();
+    assert_eq!(lines, [(1, "{\"a\": 1}", true), (4, "{\"a\": 2}", true), (5, "{oops", false)], "Blank lines are skipped");
+}
+
+#[test]
+fn test_valid_stream_passes() {
+    let errors = validate("test-examples/jsonl/events.ndjson");
+    assert!(errors.is_empty(), "{:?}", errors);
+}
+
+#[test]
+fn test_errors_carry_line_and_record_path() {
+    let errors = validate("test-examples/jsonl/bad-events.jsonl");
+    let reported: Vec<_> = errors
+        .iter()
+        .map(PrintableError::from)
+        .map(|e| (e.rule_id, e.line, e.json_path))
+        .collect();
+    assert_eq!(
+        reported,
+        [
+            ("app::schema::validation_error".to_string(), Some(2), Some("/payload/amount".to_string())),
+            ("app::json::parse_error".to_string(), Some(3), None),
+            ("app::schema::validation_error".to_string(), Some(4), Some("/type".to_string())),
+        ],
+        "Every record is checked, even after a malformed one"
+    );
+    let AppError::SchemaValidationError(error) = &errors[0] else { panic!("{:?}", errors) };
+    let SchemaValidationError { error_span, source_code, message, .. } = error.as_ref();
+    assert_eq!(&source_code[error_span.offset()..error_span.offset() + error_span.len()], "amount");
+    assert_eq!(message, "Record on line 2 failed schema validation");
+    assert!(errors[1].to_string().contains("Record on line 3, column"), "{}", errors[1]);
+}
+
+#[test]
+fn test_records_get_the_same_checks_as_json_files() {
+    let schema = load_schema_json(&PathBuf::from("test-examples/jsonl/renamed.schema.json")).unwrap();
+    let validator = compile_schema(&schema, "test-examples/jsonl/renamed.schema.json").unwrap();
+    let options = ValidationOptions { rename_rules: collect_rename_rules(&schema), ..Default::default() };
+    let errors = validate_inputs_with_options(vec![PathBuf::from("test-examples/jsonl/legacy-events.jsonl")], &validator, &options).unwrap();
+    let located: Vec<_> = errors
+        .iter()
+        .map(|error| {
+            let printable = PrintableError::from(error);
+            let span = error.labels().and_then(|mut labels| labels.next()).map(|label| *label.inner()).unwrap();
+            let source = std::fs::read_to_string("test-examples/jsonl/legacy-events.jsonl").unwrap();
+            (printable.rule_id, printable.line, source[span.offset()..span.offset() + span.len()].to_string())
+        })
+        .collect();
+    assert_eq!(
+        located,
+        [
+            ("app::schema::renamed_property".to_string(), Some(2), "type".to_string()),
+            ("app::schema::coercible_type".to_string(), Some(3), "\"3\"".to_string()),
+        ],
+        "Renames and coercion hints point into their own record"
+    );
+}
+
+#[test]
+fn test_cli_reports_each_record() {
+    let output = Command::new("cargo")
+        .args(["run", "--", "check", "--schema", SCHEMA, "test-examples/jsonl/bad-events.jsonl", "--format", "json"])
+        .output()
+        .expect("Failed to execute command");
+    assert!(!output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).expect("stdout should be the report");
+    let lines: Vec<_> = report["errors"].as_array().unwrap().iter().map(|e| e["line"].clone()).collect();
+    assert_eq!(lines, [2, 3, 4]);
+}
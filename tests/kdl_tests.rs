@@ -0,0 +1,78 @@
+use std::path::PathBuf;
+use std::process::Command;
+use serde_json::json;
+use toml_and_jerry::error::{AppError, SchemaValidationError};
+use toml_and_jerry::kdl::parse_kdl;
+use toml_and_jerry::schema::load_and_compile_schema;
+use toml_and_jerry::validation::validate_inputs;
+
+const SCHEMA: &str = "test-examples/kdl/schema.json";
+
+fn validate(file: &str) -> Vec<AppError> {
+    let validator = load_and_compile_schema(&PathBuf::from(SCHEMA)).unwrap();
+    validate_inputs(vec![PathBuf::from(file)], &validator).unwrap()
+}
+
+#[test]
+fn test_nodes_map_to_json() {
+    let content = "name \"app\"\nports 80 443 0x1F90\nempty\nlayout split=\"vertical\" 1 {\n    pane size=1; pane\n}\n";
+    let document = parse_kdl(content).unwrap();
+    assert_eq!(
+        document.value,
+        json!({
+            "name": "app",
+            "ports": [80, 443, 8080],
+            "empty": {},
+            "layout": { "$args": [1], "split": "vertical", "pane": [{ "size": 1 }, {}] }
+        })
+    );
+    assert_eq!(document.span_for_pointer("/layout/split"), Some(44..49));
+    assert_eq!(document.span_for_pointer("/layout/pane/1"), Some(82..86));
+    assert_eq!(document.span_for_pointer("/ports/2"), Some(11..16), "Arguments point at their node");
+}
+
+#[test]
+fn test_comments_keywords_and_strings() {
+    let content = "/* block /* nested */ */\na #true\n/-b 1\nc /-1 2 #null\nd r#\"raw \"q\"\"# \\\n  \"\\u{e9}\\t\"\ne (u8)5 f=null; g false\n";
+    assert_eq!(
+        parse_kdl(content).unwrap().value,
+        json!({ "a": true, "c": [2, null], "d": ["raw \"q\"", "é\t"], "e": { "$args": [5], "f": null }, "g": false })
+    );
+}
+
+#[test]
+fn test_malformed_documents_are_errors() {
+    let error = parse_kdl("node \"open\n").unwrap_err();
+    assert_eq!((error.message.as_str(), error.span), ("unclosed string", 5..11));
+    assert!(parse_kdl("node {\n  child\n").unwrap_err().message.contains("unclosed `{`"));
+    assert!(parse_kdl("size 1x\n").unwrap_err().message.contains("malformed number"));
+    assert!(parse_kdl("ratio #nan\n").unwrap_err().message.contains("no JSON value"));
+    let error = parse_kdl("node x=1 {\n    x 2\n}\n").unwrap_err();
+    assert_eq!((error.message.as_str(), error.span), ("`x` is both a property and a child node of `node`", 5..6));
+}
+
+#[test]
+fn test_kdl_files_validate() {
+    let errors = validate("test-examples/kdl/config.kdl");
+    assert!(errors.is_empty(), "{:?}", errors);
+
+    let errors = validate("test-examples/kdl/bad-mode.kdl");
+    let [AppError::SchemaValidationError(error)] = &errors[..] else { panic!("{:?}", errors) };
+    let SchemaValidationError { error_span, source_code, .. } = error.as_ref();
+    assert_eq!(&source_code[error_span.offset()..error_span.offset() + error_span.len()], "default_mode");
+
+    let errors = validate("test-examples/kdl/broken.kdl");
+    assert!(matches!(&errors[..], [AppError::KdlParseError { .. }]), "{:?}", errors);
+}
+
+#[test]
+fn test_cli_reports_kdl_parse_errors() {
+    let output = Command::new("cargo")
+        .args(["run", "--", "check", "--schema", SCHEMA, "test-examples/kdl/broken.kdl", "--format", "json"])
+        .output()
+        .expect("Failed to execute command");
+    assert!(!output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).expect("stdout should be the report");
+    assert_eq!(report["errors"][0]["ruleId"], "app::kdl::parse_error");
+    assert_eq!(report["errors"][0]["category"], "parse");
+}
@@ -0,0 +1,47 @@
+use std::path::PathBuf;
+use jsonschema::Validator;
+use miette::Diagnostic;
+use serde_json::json;
+use toml_and_jerry::error::{AppError, LimitExceeded};
+use toml_and_jerry::limits::Limits;
+use toml_and_jerry::validation::{validate_inputs_with_options, ValidationOptions};
+
+// (rule, instance path, labelled text) of each limit warning for the fixture
+fn limit_warnings(limits: Limits) -> Vec<(String, String, String)> {
+    let accept_all = Validator::new(&json!(true)).unwrap();
+    let options = ValidationOptions { limits, ..Default::default() };
+    let errors = validate_inputs_with_options(vec![PathBuf::from("test-examples/limits/deep.yaml")], &accept_all, &options).unwrap();
+    errors
+        .iter()
+        .map(|error| match error {
+            AppError::LimitExceeded(limit) => {
+                let LimitExceeded { rule, instance_path, span, source_code, .. } = limit.as_ref();
+                assert!(error.is_warning());
+                assert_eq!(error.code().unwrap().to_string(), "app::lint::limit_exceeded");
+                let text = source_code[span.offset()..span.offset() + span.len()].to_string();
+                (rule.clone(), instance_path.clone(), text)
+            }
+            other => panic!("Unexpected diagnostic {:?}", other),
+        })
+        .collect()
+}
+
+#[test]
+fn test_no_limits_no_warnings() {
+    assert!(limit_warnings(Limits::default()).is_empty());
+}
+
+#[test]
+fn test_depth_is_reported_at_the_first_container_past_the_limit() {
+    let warnings = limit_warnings(Limits { max_depth: Some(3), ..Default::default() });
+    assert_eq!(warnings, [("max-depth".to_string(), "/settings/network/proxy".to_string(), "proxy".to_string())]);
+}
+
+#[test]
+fn test_keys_array_length_and_lines() {
+    let warnings = limit_warnings(Limits { max_keys: Some(2), max_array_len: Some(3), max_lines: Some(5), ..Default::default() });
+    let rules: Vec<_> = warnings.iter().map(|(rule, pointer, _)| (rule.as_str(), pointer.as_str())).collect();
+    assert_eq!(rules, [("max-lines", ""), ("max-keys", ""), ("max-array-len", "/hosts")]);
+    assert_eq!(warnings[0].2, "        host: a");
+    assert_eq!(warnings[2].2, "hosts");
+}
@@ -0,0 +1,82 @@
+use std::path::Path;
+use std::process::Command;
+use toml_and_jerry::merge::{load_report, merge_reports, normalize_path, to_csv, to_sarif};
+
+const CHECKOUTS: [&str; 2] = ["/home/runner/work/app", "/builds/app"];
+
+fn prefixes() -> Vec<String> {
+    CHECKOUTS.iter().map(|p| p.to_string()).collect()
+}
+
+#[test]
+fn test_normalize_path() {
+    assert_eq!(normalize_path("file:///builds/app/configs/a.yaml", &prefixes()), "configs/a.yaml");
+    assert_eq!(normalize_path(".\\configs\\a.yaml", &prefixes()), "configs/a.yaml");
+    // Only whole path components are stripped
+    assert_eq!(normalize_path("/builds/application/a.yaml", &prefixes()), "/builds/application/a.yaml");
+}
+
+#[test]
+fn test_merge_deduplicates_across_formats() {
+    let a = load_report(Path::new("test-examples/merge/shard-a.json")).unwrap();
+    let b = load_report(Path::new("test-examples/merge/shard-b.sarif")).unwrap();
+    assert_eq!((a.len(), b.len()), (2, 2));
+
+    let merged = merge_reports(vec![a, b], &prefixes());
+    let files: Vec<(&str, &str)> = merged.iter().map(|e| (e.diagnostic.file_path.as_str(), e.level.as_str())).collect();
+    assert_eq!(files, [("configs/api.yaml", "error"), ("configs/db.toml", "warning"), ("configs/web.json", "error")]);
+    assert_eq!(merged[2].diagnostic.line, Some(4));
+}
+
+#[test]
+fn test_merged_sarif_round_trips() {
+    let a = load_report(Path::new("test-examples/merge/shard-a.json")).unwrap();
+    let b = load_report(Path::new("test-examples/merge/shard-b.sarif")).unwrap();
+    let merged = merge_reports(vec![a, b], &prefixes());
+    let sarif = to_sarif(&merged);
+    assert_eq!(sarif["runs"][0]["results"].as_array().unwrap().len(), 3);
+    assert_eq!(sarif["runs"][0]["tool"]["driver"]["rules"].as_array().unwrap().len(), 3);
+
+    let path = std::env::temp_dir().join(format!("toml-and-jerry-merge-{}.sarif", std::process::id()));
+    std::fs::write(&path, sarif.to_string()).unwrap();
+    let reloaded = load_report(&path).unwrap();
+    std::fs::remove_file(&path).ok();
+    assert_eq!(reloaded, merged);
+}
+
+#[test]
+fn test_cli_merge_reports() {
+    let output = Command::new("cargo")
+        .args(["run", "--", "merge-reports", "test-examples/merge/shard-a.json", "test-examples/merge/shard-b.sarif"])
+        .args(["--format", "sarif", "--strip-prefix", CHECKOUTS[0], "--strip-prefix", CHECKOUTS[1]])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let sarif: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(sarif["version"], "2.1.0");
+    assert_eq!(sarif["runs"][0]["results"].as_array().unwrap().len(), 3);
+}
+
+#[test]
+fn test_csv_has_one_row_per_entry() {
+    let merged = merge_reports(vec![load_report(Path::new("test-examples/merge/shard-a.json")).unwrap()], &prefixes());
+    let csv = to_csv(&merged);
+    let mut lines = csv.lines();
+    assert_eq!(lines.next(), Some("file,line,column,severity,category,rule_id,instance_path,message"));
+    assert_eq!(lines.count(), merged.len());
+}
+
+#[test]
+fn test_csv_check_output_quotes_messages() {
+    let output = Command::new("cargo")
+        .args(["run", "--", "check", "test-examples/invalid-config.json", "--schema", "test-examples/schema.json", "--format", "csv"])
+        .output()
+        .expect("Failed to execute command");
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let rows: Vec<&str> = stdout.lines().skip_while(|l| !l.starts_with("file,")).collect();
+    assert_eq!(rows[0], "file,line,column,severity,category,rule_id,instance_path,message");
+    assert!(rows.len() > 1, "{}", stdout);
+    assert!(rows[1].starts_with("test-examples/invalid-config.json,"), "{}", rows[1]);
+    assert!(rows[1].contains(",app::schema::validation_error,"), "{}", rows[1]);
+}
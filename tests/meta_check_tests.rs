@@ -0,0 +1,37 @@
+use std::path::PathBuf;
+use std::process::Command;
+use toml_and_jerry::meta_check::check_against_meta;
+use toml_and_jerry::schema::{load_schema_json, parse_draft};
+
+fn check(dialects: &[&str]) -> toml_and_jerry::meta_check::MetaCheck {
+    let schema = load_schema_json(&PathBuf::from("test-examples/dialect/modern.schema.json")).unwrap();
+    let drafts: Vec<_> = dialects.iter().map(|d| parse_draft(d).unwrap()).collect();
+    check_against_meta(&schema, "modern", &drafts)
+}
+
+#[test]
+fn test_matrix_reports_validity_per_dialect() {
+    let result = check(&["4", "7", "2020-12"]);
+    let validity: Vec<_> = result.dialects.iter().map(|d| (d.dialect, d.valid)).collect();
+    assert_eq!(validity, [("draft-04", false), ("draft-07", true), ("2020-12", true)]);
+    assert!(result.dialects[0].error.as_deref().is_some_and(|e| e.contains("exclusiveMaximum")), "{:?}", result.dialects[0]);
+    assert!(!result.all_valid());
+}
+
+#[test]
+fn test_lists_keywords_a_dialect_ignores() {
+    let result = check(&["7", "2020-12"]);
+    assert_eq!(result.dialects[0].unknown_keywords, ["prefixItems"]);
+    assert!(result.dialects[1].unknown_keywords.is_empty(), "x- annotations are not keywords");
+}
+
+#[test]
+fn test_schema_check_command_prints_matrix() {
+    let output = Command::new("cargo")
+        .args(["run", "--", "schema", "check", "test-examples/dialect/modern.schema.json", "--against-meta", "7,2020-12"])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "Valid under both dialects");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("draft-07   valid    ignores: prefixItems"), "{}", stdout);
+}
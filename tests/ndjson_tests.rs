@@ -0,0 +1,39 @@
+use std::process::Command;
+use toml_and_jerry::report_stream::Streamed;
+
+fn check(args: &[&str]) -> std::process::Output {
+    Command::new("cargo")
+        .args(["run", "--", "check", "--schema", "test-examples/schema.json", "--format", "ndjson"])
+        .args(args)
+        .output()
+        .expect("Failed to execute command")
+}
+
+#[test]
+fn test_remainder_leaves_out_streamed_lines() {
+    let streamed = Streamed::default();
+    streamed.print(r#"{"a":1}"#.to_string());
+    assert_eq!(streamed.remainder("{\"a\":1}\n{\"a\":1}\n{\"b\":2}"), "{\"a\":1}\n{\"b\":2}");
+}
+
+#[test]
+fn test_ndjson_prints_one_diagnostic_per_line() {
+    let output = check(&["test-examples/invalid-config.json", "test-examples/invalid-types.toml", "test-examples/valid-config.json"]);
+
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<serde_json::Value> = stdout
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("Every stdout line should be a JSON object"))
+        .collect();
+    assert_eq!(lines.len(), 2, "{}", stdout);
+    assert_eq!(lines[0]["filePath"], "test-examples/invalid-config.json");
+    assert_eq!(lines[1]["filePath"], "test-examples/invalid-types.toml");
+}
+
+#[test]
+fn test_ndjson_without_diagnostics_prints_nothing() {
+    let output = check(&["test-examples/valid-config.json"]);
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty(), "{}", String::from_utf8_lossy(&output.stdout));
+}
@@ -0,0 +1,68 @@
+use std::path::Path;
+use std::process::Command;
+use toml_and_jerry::error::AppError;
+use toml_and_jerry::fix::{apply_edits, fixes_for};
+use toml_and_jerry::newlines::{usual_newline, LineEndings, Newline, NewlineRules};
+
+const MIXED: &str = "name = \"app\"\r\nversion = \"1.0.0\"\nport = 8080\r\n";
+
+#[test]
+fn test_usual_newline_is_the_majority() {
+    assert_eq!(usual_newline(MIXED), Newline::Crlf);
+    assert_eq!(usual_newline("a\nb\r\n"), Newline::Lf);
+    assert_eq!(usual_newline("no breaks"), Newline::Lf);
+}
+
+#[test]
+fn test_one_diagnostic_counts_every_wrong_ending() {
+    let rules = NewlineRules { line_endings: Some(LineEndings::Lf), final_newline: false };
+    let found = rules.check(Path::new("app.toml"), MIXED);
+
+    assert_eq!(found.len(), 1);
+    match &found[0] {
+        AppError::InconsistentLineEndings { expected, count, .. } => {
+            assert_eq!(expected, "LF");
+            assert_eq!(*count, 2);
+        }
+        other => panic!("unexpected {:?}", other),
+    }
+    assert!(found[0].is_warning());
+}
+
+#[test]
+fn test_fixes_convert_endings_and_add_final_newline() {
+    let content = "a: 1\r\nb: 2\nc: 3";
+    let rules = NewlineRules { line_endings: Some(LineEndings::Consistent), final_newline: true };
+    let found = rules.check(Path::new("app.yaml"), content);
+
+    assert_eq!(found.len(), 2, "{:?}", found);
+    let edits: Vec<_> = found.iter().flat_map(fixes_for).collect();
+    // A tie keeps the first line's ending
+    assert_eq!(apply_edits(content, &edits), "a: 1\r\nb: 2\r\nc: 3\r\n");
+}
+
+#[test]
+fn test_clean_files_have_no_diagnostics() {
+    let rules = NewlineRules { line_endings: Some(LineEndings::Crlf), final_newline: true };
+    assert!(rules.check(Path::new("app.toml"), "a = 1\r\nb = 2\r\n").is_empty());
+    assert!(NewlineRules::default().check(Path::new("app.toml"), MIXED).is_empty());
+}
+
+#[test]
+fn test_fix_rewrites_line_endings() {
+    let dir = std::env::temp_dir().join(format!("toml-and-jerry-newlines-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let input = dir.join("config.json");
+    std::fs::write(&input, "{\r\n  \"name\": \"app\",\n  \"version\": \"1.0.0\",\r\n  \"port\": 8080\n}").unwrap();
+
+    let output = Command::new("cargo")
+        .args(["run", "--", "check", "--schema", "test-examples/schema.json", "--line-endings", "lf", "--final-newline", "--fix"])
+        .arg(&input)
+        .output()
+        .expect("Failed to execute command");
+    let fixed = std::fs::read_to_string(&input).unwrap();
+    std::fs::remove_dir_all(&dir).ok();
+
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(fixed, "{\n  \"name\": \"app\",\n  \"version\": \"1.0.0\",\n  \"port\": 8080\n}\n");
+}
@@ -0,0 +1,41 @@
+use std::process::Command;
+
+fn run(args: &[&str]) -> std::process::Output {
+    Command::new("cargo").args(["run", "--"]).args(args).output().expect("Failed to execute command")
+}
+
+#[test]
+fn test_no_write_rejects_writing_flags_up_front() {
+    let status_file = std::env::temp_dir().join(format!("toml-and-jerry-nowrite-{}.json", std::process::id()));
+    let status_arg = status_file.to_string_lossy().into_owned();
+    let output = run(&[
+        "--no-write", "check", "test-examples/valid-config.json", "--schema", "test-examples/schema.json",
+        "--status-file", &status_arg,
+    ]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--no-write cannot be combined with --status-file"));
+    assert!(!status_file.exists(), "Nothing may be written");
+}
+
+#[test]
+fn test_no_write_check_still_validates() {
+    let output = run(&["check", "--no-write", "test-examples/valid-config.json", "--schema", "test-examples/schema.json"]);
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("All processed files are valid!"));
+}
+
+#[test]
+fn test_no_write_refuses_to_clear_cache() {
+    let cache = std::env::temp_dir().join(format!("toml-and-jerry-nowrite-cache-{}", std::process::id()));
+    std::fs::create_dir_all(cache.join("schemas")).unwrap();
+    std::fs::write(cache.join("schemas/entry.json"), "{}").unwrap();
+    let output = Command::new("cargo")
+        .args(["run", "--", "--no-write", "cache", "clear"])
+        .env("TOML_AND_JERRY_CACHE_DIR", &cache)
+        .output()
+        .expect("Failed to execute command");
+    let kept = cache.join("schemas/entry.json").exists();
+    std::fs::remove_dir_all(&cache).ok();
+    assert!(!output.status.success());
+    assert!(kept, "The cache must survive --no-write");
+}
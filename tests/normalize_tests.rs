@@ -0,0 +1,49 @@
+use std::process::Command;
+use serde_json::json;
+use toml_and_jerry::normalize::{normalize, parse};
+
+fn canonical(file: &str, format: &str) -> String {
+    let content = std::fs::read_to_string(file).unwrap();
+    let input_format = file.rsplit('.').next().unwrap();
+    normalize(&parse(&content, input_format).unwrap(), format).unwrap()
+}
+
+#[test]
+fn test_equal_data_gives_equal_bytes() {
+    let json = canonical("test-examples/normalize/app.json", "json");
+    assert_eq!(canonical("test-examples/normalize/app.yaml", "json"), json);
+    assert_eq!(canonical("test-examples/normalize/app.toml", "json"), json);
+    assert!(json.starts_with("{\n  \"database\": {\n    \"host\": \"db.internal\",\n"), "{}", json);
+
+    let yaml = canonical("test-examples/normalize/app.yaml", "yaml");
+    assert_eq!(canonical("test-examples/normalize/app.toml", "yaml"), yaml);
+    assert_eq!(yaml, "database:\n  host: db.internal\n  port: 5432\nname: billing\nport: 8080\ntags:\n- blue\n- green\n");
+}
+
+#[test]
+fn test_toml_needs_a_table_without_nulls() {
+    assert_eq!(normalize(&[json!({ "a": { "b": null } })], "toml").unwrap_err(), "null at /a/b cannot be written as TOML");
+    assert!(normalize(&[json!([1, 2])], "toml").is_err());
+    let dated = parse("[owner]\ndob = 1979-05-27T07:32:00Z\n", "toml").unwrap();
+    assert_eq!(dated, [json!({ "owner": { "dob": "1979-05-27T07:32:00Z" } })], "Dates are kept as written");
+}
+
+#[test]
+fn test_yaml_streams_keep_their_documents() {
+    let documents = parse("b: 1\na: 2\n---\nc: 3\n", "yaml").unwrap();
+    assert_eq!(normalize(&documents, "yaml").unwrap(), "a: 2\nb: 1\n---\nc: 3\n");
+    assert!(normalize(&documents, "json").is_err());
+}
+
+#[test]
+fn test_cli_normalize() {
+    let output = Command::new("cargo")
+        .args(["run", "--", "normalize", "test-examples/normalize/app.yaml", "--format", "toml"])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "name = \"billing\"\nport = 8080\ntags = [\n    \"blue\",\n    \"green\",\n]\n\n[database]\nhost = \"db.internal\"\nport = 5432\n"
+    );
+}
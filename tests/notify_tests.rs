@@ -0,0 +1,79 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::process::Command;
+use jsonschema::Validator;
+use toml_and_jerry::notify::{summary_text, top_diagnostics, webhook_payload, NotifyWhen};
+use toml_and_jerry::status::RunStatus;
+use toml_and_jerry::validation::validate_inputs;
+
+fn failing_diagnostics() -> Vec<toml_and_jerry::error::AppError> {
+    let schema: serde_json::Value = serde_json::from_str(&std::fs::read_to_string("test-examples/schema.json").unwrap()).unwrap();
+    let validator = Validator::new(&schema).unwrap();
+    let inputs = vec![PathBuf::from("test-examples/missing-required-fields.json"), PathBuf::from("test-examples/invalid-types.toml")];
+    validate_inputs(inputs, &validator).unwrap()
+}
+
+#[test]
+fn test_notify_when_gates_on_outcome() {
+    let passed = RunStatus::from_diagnostics(&[]);
+    let failed = RunStatus::from_diagnostics(&failing_diagnostics());
+    assert!(!NotifyWhen::Failure.should_notify(&passed));
+    assert!(NotifyWhen::Failure.should_notify(&failed));
+    assert!(NotifyWhen::Failure.should_notify(&RunStatus::aborted(1, "schema not found")));
+    assert!(NotifyWhen::Always.should_notify(&passed));
+    assert_eq!(NotifyWhen::parse("sometimes"), None);
+}
+
+#[test]
+fn test_summary_lists_top_diagnostics() {
+    let diagnostics = failing_diagnostics();
+    let status = RunStatus::from_diagnostics(&diagnostics);
+    let top = top_diagnostics(&diagnostics, 1);
+    assert_eq!(top.len(), 1);
+    let text = summary_text(&status, &top);
+    assert!(text.starts_with(&format!("toml-and-jerry check failed: {} error(s), 0 warning(s) in 2 file(s)", diagnostics.len())), "{}", text);
+    assert!(text.contains(&top[0]));
+    assert!(text.ends_with(&format!("…and {} more", diagnostics.len() - 1)), "{}", text);
+    assert_eq!(webhook_payload(&status, &top)["text"], serde_json::json!(text));
+}
+
+// Accepts one request and returns its body
+fn receive_one_post(listener: TcpListener) -> String {
+    let (stream, _) = listener.accept().unwrap();
+    let mut reader = BufReader::new(stream);
+    let mut content_length = 0;
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        if line.trim().is_empty() {
+            break;
+        }
+        if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+            content_length = value.trim().parse().unwrap();
+        }
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).unwrap();
+    reader.get_mut().write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok").unwrap();
+    String::from_utf8(body).unwrap()
+}
+
+#[test]
+fn test_cli_posts_summary_on_failure() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    let server = std::thread::spawn(move || receive_one_post(listener));
+
+    let output = Command::new("cargo")
+        .args(["run", "--", "check", "test-examples/missing-required-fields.json", "--schema", "test-examples/schema.json"])
+        .args(["--notify-webhook", &url, "--notify-top", "3"])
+        .output()
+        .expect("Failed to execute command");
+    assert!(!output.status.success());
+
+    let body: serde_json::Value = serde_json::from_str(&server.join().unwrap()).unwrap();
+    let text = body["text"].as_str().unwrap();
+    assert!(text.starts_with("toml-and-jerry check failed: 1 error(s)"), "{}", text);
+    assert!(text.contains("test-examples/missing-required-fields.json"), "{}", text);
+}
@@ -0,0 +1,42 @@
+use std::path::{Path, PathBuf};
+use toml_and_jerry::error::AppError;
+use toml_and_jerry::openapi::{compile_operation_validator, PayloadPart};
+use toml_and_jerry::validation::validate_inputs;
+
+const SPEC: &str = "test-examples/openapi/users-api.yaml";
+
+#[test]
+fn test_payload_part_parsing() {
+    assert_eq!(PayloadPart::parse("requestBody"), Some(PayloadPart::RequestBody));
+    assert_eq!(PayloadPart::parse("response"), Some(PayloadPart::Response("200".to_string())));
+    assert_eq!(PayloadPart::parse("response:404"), Some(PayloadPart::Response("404".to_string())));
+    assert_eq!(PayloadPart::parse("headers"), None);
+}
+
+#[test]
+fn test_request_body_fixture_via_component_ref() {
+    let validator = compile_operation_validator(Path::new(SPEC), "POST /users", &PayloadPart::RequestBody, None)
+        .expect("Operation schema should compile");
+
+    let errors = validate_inputs(vec![PathBuf::from("test-examples/openapi/create-user.json")], &validator).unwrap();
+    assert!(errors.is_empty(), "Valid request body fixture should pass");
+
+    let errors = validate_inputs(vec![PathBuf::from("test-examples/openapi/create-user-invalid.yaml")], &validator).unwrap();
+    assert!(errors.iter().any(|e| matches!(e, AppError::SchemaValidationError { .. })));
+}
+
+#[test]
+fn test_response_fixture_with_integer_status_key() {
+    let part = PayloadPart::Response("201".to_string());
+    let validator = compile_operation_validator(Path::new(SPEC), "post /users", &part, None)
+        .expect("Response schema should compile");
+
+    let errors = validate_inputs(vec![PathBuf::from("test-examples/openapi/user-created.json")], &validator).unwrap();
+    assert!(errors.is_empty(), "Valid response fixture should pass");
+}
+
+#[test]
+fn test_unknown_operation_is_reported() {
+    let result = compile_operation_validator(Path::new(SPEC), "DELETE /users", &PayloadPart::RequestBody, None);
+    assert!(matches!(result, Err(AppError::OpenApiError { .. })));
+}
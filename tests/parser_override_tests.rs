@@ -0,0 +1,45 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use jsonschema::Validator;
+use toml_and_jerry::error::AppError;
+use toml_and_jerry::parsers::{mask_templates, parser_for, Parser};
+use toml_and_jerry::project_config::load_project_config;
+use toml_and_jerry::validation::{validate_inputs_with_options, ValidationOptions};
+
+fn overrides() -> BTreeMap<String, Parser> {
+    load_project_config(Some(Path::new("test-examples/parsers/project.toml"))).unwrap().parsers
+}
+
+#[test]
+fn test_project_config_maps_extensions() {
+    let overrides = overrides();
+    assert_eq!(parser_for(Path::new("app.conf"), &overrides), Some("toml"));
+    assert_eq!(parser_for(Path::new("values.tpl"), &overrides), Some("yaml-template"));
+    assert_eq!(parser_for(Path::new("Cargo.lock"), &overrides), Some("json"));
+    assert_eq!(parser_for(Path::new("app.yaml"), &overrides), Some("yaml"), "Unmapped extensions keep their own parser");
+}
+
+#[test]
+fn test_unknown_parser_is_rejected() {
+    let error = toml::from_str::<toml_and_jerry::project_config::ProjectConfig>("[parsers]\nconf = \"xml\"\n").unwrap_err();
+    assert!(error.to_string().contains("unknown variant"), "{}", error);
+}
+
+#[test]
+fn test_masking_keeps_offsets() {
+    let content = "name: {{ .Values.name }}\n{% if x %}\nport: 1\n";
+    let masked = mask_templates(content);
+    assert_eq!(masked.len(), content.len());
+    assert_eq!(masked, "name: __________________\n          \nport: 1\n");
+}
+
+#[test]
+fn test_overridden_extensions_are_validated() {
+    let schema: serde_json::Value = serde_json::from_str(&std::fs::read_to_string("test-examples/schema.json").unwrap()).unwrap();
+    let validator = Validator::new(&schema).unwrap();
+    let options = ValidationOptions { parsers: overrides(), ..Default::default() };
+    let inputs = vec![PathBuf::from("test-examples/parsers/app.conf"), PathBuf::from("test-examples/parsers/values.tpl")];
+    let errors = validate_inputs_with_options(inputs, &validator, &options).unwrap();
+    assert_eq!(errors.len(), 1, "Only the .conf file's port is out of range: {:?}", errors);
+    assert!(matches!(&errors[0], AppError::SchemaValidationError(error) if error.path.ends_with("app.conf")));
+}
@@ -0,0 +1,42 @@
+use std::path::PathBuf;
+use serde_json::json;
+use toml_and_jerry::patch::{patch_for_instance, patches_for_inputs};
+use toml_and_jerry::schema::load_schema_json;
+use toml_and_jerry::yaml::TagPolicy;
+
+fn schema_json() -> serde_json::Value {
+    load_schema_json(&PathBuf::from("test-examples/patch/schema.json")).expect("Schema should load")
+}
+
+#[test]
+fn test_patch_moves_removes_and_adds_defaults() {
+    let patches = patches_for_inputs(&schema_json(), &[PathBuf::from("test-examples/patch/config.json")], &TagPolicy::default());
+    assert_eq!(
+        patches["test-examples/patch/config.json"],
+        vec![
+            json!({ "op": "remove", "path": "/debug" }),
+            json!({ "op": "move", "from": "/server/port", "path": "/server/listen_port" }),
+            json!({ "op": "add", "path": "/server/tls", "value": false }),
+        ]
+    );
+}
+
+#[test]
+fn test_no_patch_for_valid_or_unfixable_instances() {
+    let schema = schema_json();
+    assert!(patch_for_instance(&schema, &json!({ "name": "api", "server": { "listen_port": 1, "tls": true } })).is_empty());
+    // `name` is required but has no default, so there is nothing safe to add
+    assert!(patch_for_instance(&schema, &json!({ "server": { "listen_port": 1, "tls": true } })).is_empty());
+}
+
+#[test]
+fn test_open_objects_keep_unknown_properties() {
+    let schema = json!({ "type": "object", "properties": { "a": { "type": "string" } } });
+    assert!(patch_for_instance(&schema, &json!({ "a": "x", "extra": 1 })).is_empty());
+}
+
+#[test]
+fn test_pointer_segments_are_escaped() {
+    let schema = json!({ "type": "object", "additionalProperties": false });
+    assert_eq!(patch_for_instance(&schema, &json!({ "a/b~c": 1 })), vec![json!({ "op": "remove", "path": "/a~1b~0c" })]);
+}
@@ -0,0 +1,49 @@
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use toml_and_jerry::error::AppError;
+use toml_and_jerry::progress::{clear_handler, set_handler, ProgressHandler};
+use toml_and_jerry::schema::load_and_compile_schema;
+use toml_and_jerry::validation::validate_inputs;
+
+#[derive(Clone, Default)]
+struct Recorder(Arc<Mutex<Vec<String>>>);
+
+impl ProgressHandler for Recorder {
+    fn file_started(&mut self, path: &Path) {
+        self.0.lock().unwrap().push(format!("start {}", path.display()));
+    }
+
+    fn file_finished(&mut self, path: &Path, diagnostics: &[AppError]) {
+        self.0.lock().unwrap().push(format!("finish {} ({})", path.display(), diagnostics.len()));
+    }
+
+    fn run_finished(&mut self, diagnostics: &[AppError]) {
+        self.0.lock().unwrap().push(format!("done ({})", diagnostics.len()));
+    }
+}
+
+#[test]
+fn test_handler_sees_each_file_and_the_end_of_the_run() {
+    let validator = load_and_compile_schema(&PathBuf::from("test-examples/schema.json")).unwrap();
+    let recorder = Recorder::default();
+    set_handler(recorder.clone());
+    let inputs = vec![PathBuf::from("test-examples/valid-config.json"), PathBuf::from("test-examples/invalid-config.json")];
+    let errors = validate_inputs(inputs, &validator).unwrap();
+    clear_handler();
+
+    let events = recorder.0.lock().unwrap().clone();
+    assert_eq!(
+        events,
+        vec![
+            "start test-examples/valid-config.json".to_string(),
+            "finish test-examples/valid-config.json (0)".to_string(),
+            "start test-examples/invalid-config.json".to_string(),
+            format!("finish test-examples/invalid-config.json ({})", errors.len()),
+            format!("done ({})", errors.len()),
+        ]
+    );
+    assert!(!errors.is_empty());
+
+    validate_inputs(vec![PathBuf::from("test-examples/valid-config.json")], &validator).unwrap();
+    assert_eq!(recorder.0.lock().unwrap().len(), 5, "Nothing is reported once the handler is cleared");
+}
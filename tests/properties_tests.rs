@@ -0,0 +1,79 @@
+use std::path::PathBuf;
+use std::process::Command;
+use serde_json::json;
+use toml_and_jerry::error::{AppError, SchemaValidationError};
+use toml_and_jerry::ini::IniCoercion;
+use toml_and_jerry::properties::{parse_properties, PropertiesOptions};
+use toml_and_jerry::schema::load_and_compile_schema;
+use toml_and_jerry::validation::{validate_inputs_with_options, ValidationOptions};
+
+const SCHEMA: &str = "test-examples/properties/schema.json";
+
+const NESTED: PropertiesOptions = PropertiesOptions { nest: true, coercion: IniCoercion { booleans: true, numbers: true } };
+
+fn validate(file: &str, properties: PropertiesOptions) -> Vec<AppError> {
+    let validator = load_and_compile_schema(&PathBuf::from(SCHEMA)).unwrap();
+    let options = ValidationOptions { properties, ..Default::default() };
+    validate_inputs_with_options(vec![PathBuf::from(file)], &validator, &options).unwrap()
+}
+
+#[test]
+fn test_keys_are_flat_strings_by_default() {
+    let content = "# comment\n! also a comment\nserver.port = 8080\nname billing\ngreeting: caf\\u00e9 \\\n    au lait\nkey\\=with\\:separators = 1\n";
+    let document = parse_properties(content, PropertiesOptions::default()).unwrap();
+    assert_eq!(
+        document.value,
+        json!({ "server.port": "8080", "name": "billing", "greeting": "café au lait", "key=with:separators": "1" })
+    );
+    assert_eq!(document.span_for_pointer("/server.port"), Some(27..38));
+}
+
+#[test]
+fn test_nesting_expands_dotted_keys() {
+    let document = parse_properties("server.host = a\nserver.port = 8080\nflag = on\n", NESTED).unwrap();
+    assert_eq!(document.value, json!({ "server": { "host": "a", "port": 8080 }, "flag": true }));
+    assert_eq!(document.span_for_pointer("/server/port"), Some(16..27));
+    assert_eq!(document.span_for_pointer("/server"), Some(0..11), "Objects point at the key that created them");
+}
+
+#[test]
+fn test_malformed_entries_are_errors() {
+    assert!(parse_properties("a = 1\na = 2\n", PropertiesOptions::default()).unwrap_err().message.contains("set twice"));
+    let error = parse_properties("a = 1\na.b = 2\n", NESTED).unwrap_err();
+    assert_eq!((error.message.as_str(), error.span), ("key `a.b` nests under `a`, which is set to a value", 6..13));
+    assert!(parse_properties("a.b = 1\na = 2\n", NESTED).is_err());
+    assert!(parse_properties("a..b = 1\n", NESTED).is_err());
+    assert!(parse_properties("a..b = 1\n", PropertiesOptions::default()).is_ok(), "Without nesting dots are just characters");
+    assert!(parse_properties("a = \\u00zz\n", PropertiesOptions::default()).is_err());
+}
+
+#[test]
+fn test_properties_share_the_yaml_schema() {
+    let validator = load_and_compile_schema(&PathBuf::from(SCHEMA)).unwrap();
+    let yaml = validate_inputs_with_options(vec![PathBuf::from("test-examples/properties/application.yaml")], &validator, &ValidationOptions::default()).unwrap();
+    assert!(yaml.is_empty(), "{:?}", yaml);
+    assert!(validate("test-examples/properties/application.properties", NESTED).is_empty());
+    assert!(!validate("test-examples/properties/application.properties", PropertiesOptions::default()).is_empty(), "Flat keys do not match the nested schema");
+
+    let errors = validate("test-examples/properties/low-port.properties", NESTED);
+    let AppError::SchemaValidationError(error) = &errors[0] else { panic!("{:?}", errors) };
+    let SchemaValidationError { error_span, source_code, .. } = error.as_ref();
+    assert_eq!(&source_code[error_span.offset()..error_span.offset() + error_span.len()], "server.port");
+
+    let errors = validate("test-examples/properties/broken.properties", NESTED);
+    assert!(matches!(&errors[..], [AppError::PropertiesParseError { .. }]), "{:?}", errors);
+}
+
+#[test]
+fn test_cli_properties_flags() {
+    let check = |extra: &[&str]| {
+        Command::new("cargo")
+            .args(["run", "--", "check", "--schema", SCHEMA, "test-examples/properties/application.properties"])
+            .args(extra)
+            .output()
+            .expect("Failed to execute command")
+    };
+    assert_eq!(check(&[]).status.code(), Some(1));
+    let output = check(&["--properties-nest", "--properties-coerce", "booleans,numbers"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+}
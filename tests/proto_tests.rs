@@ -0,0 +1,86 @@
+use prost_types::field_descriptor_proto::{Label, Type};
+use prost_types::{
+    DescriptorProto, EnumDescriptorProto, EnumValueDescriptorProto, FieldDescriptorProto,
+    FileDescriptorProto, FileDescriptorSet,
+};
+use jsonschema::Validator;
+use serde_json::json;
+use toml_and_jerry::proto::message_json_schema;
+
+fn field(name: &str, json_name: &str, number: i32, ty: Type, type_name: Option<&str>) -> FieldDescriptorProto {
+    FieldDescriptorProto {
+        name: Some(name.to_string()),
+        json_name: Some(json_name.to_string()),
+        number: Some(number),
+        label: Some(Label::Optional as i32),
+        r#type: Some(ty as i32),
+        type_name: type_name.map(|t| t.to_string()),
+        ..Default::default()
+    }
+}
+
+// package demo; enum Level { LEVEL_UNSPECIFIED = 0; LEVEL_HIGH = 1; }
+// message Config { string service_name = 1; int64 max_bytes = 2; Level level = 3; }
+fn demo_descriptor_set() -> FileDescriptorSet {
+    let level = EnumDescriptorProto {
+        name: Some("Level".to_string()),
+        value: vec![
+            EnumValueDescriptorProto { name: Some("LEVEL_UNSPECIFIED".to_string()), number: Some(0), ..Default::default() },
+            EnumValueDescriptorProto { name: Some("LEVEL_HIGH".to_string()), number: Some(1), ..Default::default() },
+        ],
+        ..Default::default()
+    };
+    let config = DescriptorProto {
+        name: Some("Config".to_string()),
+        field: vec![
+            field("service_name", "serviceName", 1, Type::String, None),
+            field("max_bytes", "maxBytes", 2, Type::Int64, None),
+            field("level", "level", 3, Type::Enum, Some(".demo.Level")),
+        ],
+        ..Default::default()
+    };
+    FileDescriptorSet {
+        file: vec![FileDescriptorProto {
+            name: Some("demo.proto".to_string()),
+            package: Some("demo".to_string()),
+            message_type: vec![config],
+            enum_type: vec![level],
+            syntax: Some("proto3".to_string()),
+            ..Default::default()
+        }],
+    }
+}
+
+fn demo_validator() -> Validator {
+    let schema = message_json_schema(&demo_descriptor_set(), "demo.Config").expect("Schema should be generated");
+    Validator::new(&schema).expect("Generated schema should compile")
+}
+
+#[test]
+fn test_json_names_and_original_names_are_accepted() {
+    let validator = demo_validator();
+    assert!(validator.is_valid(&json!({ "serviceName": "api" })));
+    assert!(validator.is_valid(&json!({ "service_name": "api" })));
+    assert!(!validator.is_valid(&json!({ "serviceNom": "api" })), "Unknown fields should be rejected");
+}
+
+#[test]
+fn test_int64_accepts_strings_and_numbers() {
+    let validator = demo_validator();
+    assert!(validator.is_valid(&json!({ "maxBytes": "9007199254740993" })));
+    assert!(validator.is_valid(&json!({ "maxBytes": 1024 })));
+    assert!(!validator.is_valid(&json!({ "maxBytes": "lots" })));
+}
+
+#[test]
+fn test_enum_names_are_checked() {
+    let validator = demo_validator();
+    assert!(validator.is_valid(&json!({ "level": "LEVEL_HIGH" })));
+    assert!(validator.is_valid(&json!({ "level": 1 })));
+    assert!(!validator.is_valid(&json!({ "level": "HIGH" })));
+}
+
+#[test]
+fn test_unknown_message_is_an_error() {
+    assert!(message_json_schema(&demo_descriptor_set(), "demo.Missing").is_err());
+}
@@ -0,0 +1,48 @@
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::{Duration, UNIX_EPOCH};
+use serde_json::json;
+use toml_and_jerry::provenance::{file_artifact, input_artifacts, rfc3339, schema_artifact};
+
+#[test]
+fn test_rfc3339_timestamps() {
+    assert_eq!(rfc3339(UNIX_EPOCH), "1970-01-01T00:00:00Z");
+    assert_eq!(rfc3339(UNIX_EPOCH + Duration::from_secs(951_782_400 + 3_661)), "2000-02-29T01:01:01Z");
+}
+
+#[test]
+fn test_digests_track_content() {
+    // The "empty" fixture holds a single space
+    let empty = file_artifact(&PathBuf::from("test-examples/empty-file.json"));
+    assert_eq!(empty.sha256.as_deref(), Some("36a9e7f1c95b82ffb99743e0c5c4ce95d83c9a430aac59f84ef3cbfab6145068"));
+    assert_eq!(file_artifact(&PathBuf::from("test-examples/missing.json")).sha256, None);
+
+    let a = schema_artifact("a", &json!({ "type": "object" }));
+    let b = schema_artifact("b", &json!({ "type": "string" }));
+    assert_ne!(a.sha256, b.sha256);
+}
+
+#[test]
+fn test_directories_expand_to_file_digests() {
+    let artifacts = input_artifacts(&[PathBuf::from("test-examples/renames")]);
+    let uris: Vec<_> = artifacts.iter().map(|a| a.uri.replace('\\', "/")).collect();
+    assert_eq!(uris, vec!["test-examples/renames/legacy.toml", "test-examples/renames/schema.json"]);
+}
+
+#[test]
+fn test_json_report_includes_provenance() {
+    let output = Command::new("cargo")
+        .args(["run", "--", "check", "test-examples/valid-config.json", "--schema", "test-examples/schema.json"])
+        .args(["--format", "json", "--provenance"])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let report: serde_json::Value = serde_json::from_str(&stdout[stdout.find('{').unwrap()..]).expect("Report should be JSON");
+    assert_eq!(report["errors"], json!([]));
+    assert_eq!(report["provenance"]["tool"]["name"], "toml-and-jerry");
+    assert_eq!(report["provenance"]["schemas"][0]["uri"], "test-examples/schema.json");
+    assert_eq!(report["provenance"]["inputs"][0]["uri"], "test-examples/valid-config.json");
+    assert!(report["provenance"].get("hostname").is_none(), "Hostname is opt-in");
+}
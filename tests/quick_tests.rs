@@ -0,0 +1,60 @@
+use std::path::PathBuf;
+use std::process::Command;
+use serde_json::json;
+use toml_and_jerry::quick::{compile, shallow_schema};
+use toml_and_jerry::schema::load_schema_json;
+use toml_and_jerry::validation::validate_inputs;
+
+const SCHEMA: &str = "test-examples/schema.json";
+
+#[test]
+fn test_shallow_schema_keeps_root_types_and_required() {
+    let schema = json!({
+        "$ref": "#/$defs/App",
+        "$defs": {
+            "App": {
+                "type": "object",
+                "required": ["port"],
+                "properties": { "port": { "$ref": "#/$defs/Port" }, "name": { "minLength": 3 } },
+                "allOf": [{ "required": ["mode"], "properties": { "mode": { "type": "string", "enum": ["a"] } } }]
+            },
+            "Port": { "type": "integer", "minimum": 1024 }
+        }
+    });
+    assert_eq!(
+        shallow_schema(&schema),
+        json!({
+            "type": "object",
+            "required": ["port", "mode"],
+            "properties": { "port": { "type": "integer" }, "name": {}, "mode": { "type": "string" } }
+        })
+    );
+    assert_eq!(shallow_schema(&json!(true)), json!(true));
+}
+
+#[test]
+fn test_quick_skips_nested_and_value_keywords() {
+    let schema = load_schema_json(&PathBuf::from(SCHEMA)).unwrap();
+    let check = |file: &str, quick: bool| {
+        let validator = compile(&schema, SCHEMA, None, quick).unwrap();
+        validate_inputs(vec![PathBuf::from(file)], &validator).unwrap().len()
+    };
+    assert_eq!(check("test-examples/quick/deep-errors.json", true), 0, "Pattern, bounds and nested keys are not checked");
+    assert!(check("test-examples/quick/deep-errors.json", false) > 0);
+    assert_eq!(check("test-examples/quick/wrong-type.json", true), 1);
+    assert_eq!(check("test-examples/quick/missing-port.json", true), 1);
+}
+
+#[test]
+fn test_cli_quick_flag() {
+    let check = |extra: &[&str]| {
+        Command::new("cargo")
+            .args(["run", "--", "check", "--schema", SCHEMA, "test-examples/quick/deep-errors.json"])
+            .args(extra)
+            .output()
+            .expect("Failed to execute command")
+    };
+    assert_eq!(check(&[]).status.code(), Some(1));
+    let output = check(&["--quick"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+}
@@ -0,0 +1,68 @@
+use std::path::PathBuf;
+use std::process::Command;
+use jsonschema::Validator;
+use toml_and_jerry::error::AppError;
+use toml_and_jerry::ratchet::{apply_ratchet, load_state, RatchetState};
+use toml_and_jerry::validation::validate_inputs;
+
+fn diagnostics(inputs: &[&str]) -> Vec<AppError> {
+    let schema: serde_json::Value = serde_json::from_str(&std::fs::read_to_string("test-examples/schema.json").unwrap()).unwrap();
+    let validator = Validator::new(&schema).unwrap();
+    validate_inputs(inputs.iter().map(PathBuf::from).collect(), &validator).unwrap()
+}
+
+fn state_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("toml-and-jerry-ratchet-{}-{}.json", name, std::process::id()));
+    std::fs::remove_file(&path).ok();
+    path
+}
+
+#[test]
+fn test_counts_by_directory_and_code() {
+    let state = RatchetState::from_diagnostics(&diagnostics(&[
+        "test-examples/budgets/team-a/api.json",
+        "test-examples/budgets/team-a/worker.json",
+        "test-examples/budgets/team-b/api.json",
+    ]));
+    assert_eq!(state.counts["test-examples/budgets/team-a"]["app::schema::validation_error"], 2);
+    assert_eq!(state.counts["test-examples/budgets/team-b"]["app::schema::validation_error"], 1);
+    assert_eq!(state.total(), 3);
+}
+
+#[test]
+fn test_ratchet_records_fails_regressions_and_tightens() {
+    let path = state_path("lib");
+    let two = ["test-examples/budgets/team-a/api.json", "test-examples/budgets/team-a/worker.json"];
+
+    let (regressions, lines) = apply_ratchet(&path, &diagnostics(&two)).unwrap();
+    assert!(regressions.is_empty());
+    assert!(lines[0].starts_with("Ratchet started at 2 error(s)"), "{:?}", lines);
+
+    // A new error in another directory is a regression, and the state is left alone
+    let (regressions, _) = apply_ratchet(&path, &diagnostics(&[two[0], two[1], "test-examples/budgets/team-b/api.json"])).unwrap();
+    assert_eq!(regressions.len(), 1);
+    assert_eq!((regressions[0].area.as_str(), regressions[0].recorded, regressions[0].current), ("test-examples/budgets/team-b", 0, 1));
+    assert_eq!(load_state(&path).unwrap().unwrap().total(), 2);
+
+    let (regressions, lines) = apply_ratchet(&path, &diagnostics(&two[..1])).unwrap();
+    assert!(regressions.is_empty());
+    assert!(lines[0].starts_with("Ratchet tightened: 2 -> 1 error(s)"), "{:?}", lines);
+    assert_eq!(load_state(&path).unwrap().unwrap().total(), 1);
+    std::fs::remove_file(&path).ok();
+}
+
+#[test]
+fn test_cli_ratchet_exit_codes() {
+    let path = state_path("cli");
+    let run = |inputs: &[&str]| {
+        Command::new("cargo")
+            .args(["run", "--", "check", "--schema", "test-examples/schema.json", "--ratchet"])
+            .arg(&path)
+            .args(inputs)
+            .output()
+            .expect("Failed to execute command")
+    };
+    assert!(run(&["test-examples/budgets/team-a/api.json"]).status.success());
+    assert!(!run(&["test-examples/budgets/team-a/api.json", "test-examples/budgets/team-a/worker.json"]).status.success());
+    std::fs::remove_file(&path).ok();
+}
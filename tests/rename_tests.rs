@@ -0,0 +1,52 @@
+use std::fs;
+use std::path::PathBuf;
+use serde_json::json;
+use toml_and_jerry::error::AppError;
+use toml_and_jerry::fix::{apply_edits, fix_for};
+use toml_and_jerry::renames::{collect_rename_rules, find_key_span, find_renamed_properties};
+use toml_and_jerry::schema::{compile_schema, load_schema_json};
+use toml_and_jerry::validation::{validate_inputs_with_options, ValidationOptions};
+
+fn schema_json() -> serde_json::Value {
+    load_schema_json(&PathBuf::from("test-examples/renames/schema.json")).expect("Schema should load")
+}
+
+#[test]
+fn test_collects_rules_through_items_and_refs() {
+    let rules = collect_rename_rules(&schema_json());
+    let pairs: Vec<_> = rules.iter().map(|r| (r.from.as_str(), r.to.as_str())).collect();
+    assert_eq!(pairs, vec![("port", "listen_port"), ("threads", "max_threads"), ("thread_count", "max_threads")]);
+}
+
+#[test]
+fn test_finds_old_names_only_at_the_right_level() {
+    let rules = collect_rename_rules(&schema_json());
+    let hits = find_renamed_properties(&json!({ "port": 1, "server": { "port": 2 }, "workers": [{ "threads": 1 }] }), &rules);
+    let pointers: Vec<_> = hits.iter().map(|h| h.parent_pointer.as_str()).collect();
+    assert_eq!(pointers, vec!["/server", "/workers/0"]);
+}
+
+#[test]
+fn test_key_span_skips_values_and_longer_keys() {
+    let content = "report = \"port\"\nexport_port = 1\n\"port\": 2\n";
+    let range = find_key_span(content, "port").expect("Quoted key should be found");
+    assert_eq!(&content[range.start - 1..range.end + 1], "\"port\"");
+}
+
+#[test]
+fn test_renamed_properties_are_fixable_warnings() {
+    let schema = schema_json();
+    let validator = compile_schema(&schema, "test-examples/renames/schema.json").unwrap();
+    let options = ValidationOptions { rename_rules: collect_rename_rules(&schema), ..Default::default() };
+    let errors = validate_inputs_with_options(vec![PathBuf::from("test-examples/renames/legacy.toml")], &validator, &options).unwrap();
+
+    let renamed: Vec<_> = errors.iter().filter(|e| matches!(e, AppError::RenamedProperty { .. })).collect();
+    assert_eq!(renamed.len(), 2, "Expected both old names to be reported: {:?}", errors);
+    assert!(renamed.iter().all(|e| e.is_warning()));
+
+    let fixes: Vec<_> = renamed.iter().filter_map(|e| fix_for(e)).collect();
+    let original = fs::read_to_string("test-examples/renames/legacy.toml").unwrap();
+    let fixed = apply_edits(&original, &fixes);
+    assert!(fixed.contains("listen_port = 8080") && fixed.contains("max_threads = 4"));
+    assert!(fixed.starts_with("# Uses pre-rename property names\n"), "Comments and layout should survive");
+}
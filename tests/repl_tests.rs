@@ -0,0 +1,79 @@
+use std::io::{Cursor, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use serde_json::json;
+use toml_and_jerry::repl::{run, set_path, Session};
+use toml_and_jerry::schema::{compile_schema, load_schema_json};
+
+fn session(format: &str) -> Session {
+    let schema = load_schema_json(&PathBuf::from("test-examples/schema.json")).unwrap();
+    let validator = compile_schema(&schema, "schema.json").unwrap();
+    Session::new(schema, validator, format).unwrap()
+}
+
+fn transcript(format: &str, input: &str) -> String {
+    let mut output = Vec::new();
+    run(&mut session(format), Cursor::new(input), &mut output, false).unwrap();
+    String::from_utf8(output).unwrap()
+}
+
+#[test]
+fn test_pasted_snippet_is_validated_on_a_lone_dot() {
+    let output = transcript("yaml", "name: app\nversion: 1.2.3\nport: 8080\n.\n");
+    assert_eq!(output, "valid\n");
+
+    let output = transcript("yaml", "name: app\nversion: 1.2.3\nport: 80\n.\n");
+    assert!(output.contains("80 is less than the minimum of 1024"), "{}", output);
+    assert!(output.contains("snippet.yaml") && !output.contains("toml-and-jerry-repl-"), "Reports name the snippet: {}", output);
+    assert!(output.ends_with("1 diagnostic(s)\n"));
+}
+
+#[test]
+fn test_format_switch_and_set_tweaks() {
+    let output = transcript(
+        "yaml",
+        ":format toml\nname = \"app\"\nversion = \"1.2.3\"\nport = 80\n:check\n:set port=8080\n:show\n:quit\n:check\n",
+    );
+    let mut replies = output.split("format: toml\n").nth(1).unwrap().split("diagnostic(s)\n");
+    assert!(replies.next().unwrap().contains("less than the minimum"));
+    let after_set = replies.next().unwrap();
+    assert!(after_set.starts_with("valid\n"), "{}", after_set);
+    assert!(after_set.contains("port = 8080"), ":show prints the tweaked snippet: {}", after_set);
+    assert_eq!(output.matches("valid\n").count(), 1, "Nothing runs after :quit");
+}
+
+#[test]
+fn test_keys_and_unknown_commands() {
+    let output = transcript("json", ":keys database.\n:keys nope\n:format xml\n:frobnicate\n");
+    assert!(output.contains("database.port"));
+    assert!(!output.contains("debug"), "Only paths under the prefix: {}", output);
+    assert!(output.contains("no schema paths start with \"nope\""));
+    assert!(output.contains("unknown format \"xml\""));
+    assert!(output.contains("unknown command :frobnicate"));
+}
+
+#[test]
+fn test_set_path_creates_objects_and_appends() {
+    let mut document = json!({ "tags": ["a"] });
+    set_path(&mut document, "server.port", json!(8080)).unwrap();
+    set_path(&mut document, "tags.1", json!("b")).unwrap();
+    assert_eq!(document, json!({ "server": { "port": 8080 }, "tags": ["a", "b"] }));
+    assert!(set_path(&mut document, "tags.5", json!("c")).unwrap_err().contains("past the end"));
+    assert!(set_path(&mut document, "server.port.x", json!(1)).is_err());
+}
+
+#[test]
+fn test_cli_reads_commands_from_stdin() {
+    let mut child = Command::new("cargo")
+        .args(["run", "--", "repl", "--schema", "test-examples/schema.json", "--format", "json"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to execute command");
+    child.stdin.take().unwrap().write_all(b"{\"name\": \"app\", \"version\": \"1.2.3\", \"port\": 8080}\n.\n:quit\n").unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "valid\n", "No prompt or progress lines when piped");
+}
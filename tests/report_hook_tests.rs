@@ -0,0 +1,104 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use toml_and_jerry::report_hooks::{hook_payload, run_hook, run_hooks, FailurePolicy, ReportHook};
+use toml_and_jerry::status::RunStatus;
+use toml_and_jerry::project_config::ProjectConfig;
+
+fn hook(script: &str, timeout_secs: u64, on_failure: FailurePolicy) -> ReportHook {
+    ReportHook { command: vec!["sh".into(), "-c".into(), script.into()], timeout_secs, on_failure }
+}
+
+#[test]
+fn test_hooks_are_read_from_project_config() {
+    let config: ProjectConfig = toml::from_str(
+        "[report.hooks.tickets]\ncommand = [\"./open-tickets\", \"--project\", \"CFG\"]\ntimeout_secs = 5\non_failure = \"fail\"\n",
+    )
+    .unwrap();
+    let tickets = &config.report.hooks["tickets"];
+    assert_eq!(tickets.command, vec!["./open-tickets", "--project", "CFG"]);
+    assert_eq!((tickets.timeout_secs, tickets.on_failure), (5, FailurePolicy::Fail));
+
+    let defaults: ProjectConfig = toml::from_str("[report.hooks.db]\ncommand = [\"./store\"]\n").unwrap();
+    assert_eq!((defaults.report.hooks["db"].timeout_secs, defaults.report.hooks["db"].on_failure), (30, FailurePolicy::Warn));
+}
+
+#[test]
+fn test_hook_receives_report_on_stdin() {
+    let out = std::env::temp_dir().join(format!("toml-and-jerry-hook-{}.json", std::process::id()));
+    let payload = hook_payload(&RunStatus::from_diagnostics(&[]), &[]);
+    let script = format!("cat > '{}'", out.display());
+    run_hook("store", &hook(&script, 5, FailurePolicy::Fail), &payload).expect("Hook should succeed");
+    let received: serde_json::Value = serde_json::from_str(&fs::read_to_string(&out).unwrap()).unwrap();
+    fs::remove_file(&out).ok();
+    assert_eq!(received["status"]["outcome"], "passed");
+    assert!(received["diagnostics"].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn test_slow_hook_is_killed_at_timeout() {
+    let error = run_hook("slow", &hook("sleep 10", 1, FailurePolicy::Warn), &serde_json::json!({})).unwrap_err();
+    assert!(error.contains("timed out after 1s"), "{}", error);
+}
+
+#[test]
+fn test_failure_policy_decides_whether_the_run_fails() {
+    let payload = serde_json::json!({});
+    let mut hooks = BTreeMap::new();
+    hooks.insert("lenient".to_string(), hook("exit 3", 5, FailurePolicy::Warn));
+    hooks.insert("quiet".to_string(), hook("exit 3", 5, FailurePolicy::Ignore));
+    assert!(!run_hooks(&hooks, &payload));
+    hooks.insert("strict".to_string(), hook("exit 3", 5, FailurePolicy::Fail));
+    assert!(run_hooks(&hooks, &payload));
+}
+
+// A project config whose one hook copies the report to `marker`
+fn config_with_marker_hook(name: &str) -> (std::path::PathBuf, std::path::PathBuf) {
+    let dir = std::env::temp_dir().join(format!("toml-and-jerry-hooks-{}-{}", name, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let marker = dir.join("report.json");
+    let config = dir.join("project.toml");
+    let script = format!("cat > '{}'", marker.display());
+    fs::write(&config, format!("[report.hooks.copy]\ncommand = [\"sh\", \"-c\", {:?}]\n", script)).unwrap();
+    (config, marker)
+}
+
+#[test]
+fn test_no_hooks_and_no_write_skip_hooks() {
+    for (name, extra) in [("default", None), ("no-hooks", Some("--no-hooks")), ("no-write", Some("--no-write"))] {
+        let (config, marker) = config_with_marker_hook(name);
+        let output = Command::new("cargo")
+            .args(["run", "--", "check", "test-examples/valid-config.json", "--schema", "test-examples/schema.json", "--config"])
+            .arg(&config)
+            .args(extra)
+            .output()
+            .expect("Failed to execute command");
+        let ran = marker.exists();
+        fs::remove_dir_all(config.parent().unwrap()).ok();
+        assert!(output.status.success(), "{}: {}", name, String::from_utf8_lossy(&output.stderr));
+        assert_eq!(ran, extra.is_none(), "{}", name);
+    }
+}
+
+#[test]
+fn test_worker_runs_hooks_unless_told_not_to() {
+    for (name, worker_flags) in [("worker", &[][..]), ("worker-no-hooks", &["--no-hooks"][..])] {
+        let (config, marker) = config_with_marker_hook(name);
+        let mut child = Command::new("cargo")
+            .args(["run", "--", "--persistent_worker"])
+            .args(worker_flags)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("Failed to start worker");
+        let arguments = ["check", "test-examples/valid-config.json", "--schema", "test-examples/schema.json", "--config", &config.to_string_lossy()];
+        writeln!(child.stdin.as_mut().unwrap(), "{}", serde_json::json!({ "arguments": arguments })).unwrap();
+        let output = child.wait_with_output().unwrap();
+        let ran = marker.exists();
+        fs::remove_dir_all(config.parent().unwrap()).ok();
+        assert!(output.status.success(), "{}: {}", name, String::from_utf8_lossy(&output.stderr));
+        assert_eq!(ran, worker_flags.is_empty(), "{}", name);
+    }
+}
@@ -0,0 +1,56 @@
+use std::process::Command;
+use serde_json::json;
+use toml_and_jerry::error::{Category, Severity};
+use toml_and_jerry::report_schema::{report_schema, JsonReport, REPORT_VERSION};
+use toml_and_jerry::schema::compile_schema;
+use toml_and_jerry::validation::{PrintableError, Snippet};
+use toml_and_jerry::violation::Violation;
+
+fn every_field() -> PrintableError {
+    PrintableError {
+        file_path: "config.yaml".to_string(),
+        error_type: "Schema validation error".to_string(),
+        message: "3 is less than the minimum of 80".to_string(),
+        line: Some(4),
+        column: Some(9),
+        json_path: Some("/server/port".to_string()),
+        rule_id: "app::schema::validation_error".to_string(),
+        severity: Some(Severity::Error),
+        category: Some(Category::Schema),
+        snippet: Some(Snippet { start_line: 4, text: "  port: 3".to_string(), caret_start: 9, caret_end: 10 }),
+        violation: Some(Violation { keyword: "minimum".to_string(), expected: Some(json!(80)), actual: Some(json!(3)) }),
+    }
+}
+
+#[test]
+fn test_report_with_every_diagnostic_field_matches_its_schema() {
+    // Fails when PrintableError gains a field the versioned schema does not describe
+    let validator = compile_schema(&report_schema(), "report schema").expect("Report schema should compile");
+    let report = serde_json::to_value(JsonReport::from_printable(Some("schema.json"), vec![every_field()])).unwrap();
+    assert_eq!(report["reportVersion"], REPORT_VERSION);
+    assert!(validator.is_valid(&report), "{:?}", validator.iter_errors(&report).map(|e| e.to_string()).collect::<Vec<_>>());
+
+    let mut extended = report.clone();
+    extended["errors"][0]["fixable"] = serde_json::json!(true);
+    assert!(!validator.is_valid(&extended), "New fields need a new report version");
+}
+
+#[test]
+fn test_check_json_report_matches_printed_schema() {
+    let schema_output = Command::new("cargo").args(["run", "--", "schema-of-report"]).output().expect("Failed to execute command");
+    assert!(schema_output.status.success());
+    let printed: serde_json::Value = serde_json::from_slice(&schema_output.stdout).unwrap();
+    assert_eq!(printed, report_schema());
+
+    let output = Command::new("cargo")
+        .args(["run", "--", "check", "test-examples/invalid-config.json", "--schema", "test-examples/schema.json"])
+        .args(["--format", "json", "--provenance", "--summary"])
+        .output()
+        .expect("Failed to execute command");
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).expect("stdout should be the JSON report");
+    assert_eq!(report["schema"], "test-examples/schema.json");
+    assert_eq!(report["tool"]["version"], env!("CARGO_PKG_VERSION"));
+    assert!(report["generatedAt"].as_str().unwrap().ends_with('Z'));
+    let validator = compile_schema(&printed, "report schema").unwrap();
+    assert!(validator.is_valid(&report), "{:?}", validator.iter_errors(&report).map(|e| e.to_string()).collect::<Vec<_>>());
+}
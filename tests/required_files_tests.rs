@@ -0,0 +1,46 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use toml_and_jerry::error::AppError;
+use toml_and_jerry::required_files::{check_required_files, matching_dirs, RequiredFile};
+use toml_and_jerry::validation::ValidationOptions;
+
+const SERVICES: &str = "test-examples/required-files/services/*";
+
+fn rule(schema: Option<&str>) -> RequiredFile {
+    RequiredFile { dirs: SERVICES.to_string(), file: "service.yaml".to_string(), schema: schema.map(PathBuf::from) }
+}
+
+#[test]
+fn test_dirs_glob_matches_one_level() {
+    let dirs = matching_dirs(SERVICES).unwrap();
+    let names: Vec<_> = dirs.iter().map(|d| d.file_name().unwrap().to_string_lossy().into_owned()).collect();
+    assert_eq!(names, ["api", "web", "worker"]);
+    assert!(matching_dirs("test-examples/required-files/[").is_err());
+}
+
+#[test]
+fn test_missing_files_are_reported() {
+    let errors = check_required_files(&[rule(None)], &ValidationOptions::default()).unwrap();
+    assert_eq!(errors.len(), 1, "{:?}", errors);
+    assert!(matches!(&errors[0], AppError::MissingRequiredFile { path, .. } if path == Path::new("test-examples/required-files/services/worker/service.yaml")));
+}
+
+#[test]
+fn test_present_files_are_validated_against_the_schema() {
+    let errors = check_required_files(&[rule(Some("test-examples/required-files/schemas/service.schema.json"))], &ValidationOptions::default()).unwrap();
+    assert_eq!(errors.len(), 2, "{:?}", errors);
+    assert!(errors.iter().any(|e| matches!(e, AppError::SchemaValidationError(error) if error.path.ends_with("web/service.yaml"))));
+}
+
+#[test]
+fn test_cli_reports_required_files_from_project_config() {
+    let output = Command::new("cargo")
+        .args(["run", "--", "check", "--schema", "test-examples/schema.json", "test-examples/valid-config.json"])
+        .args(["--config", "test-examples/required-files/project.toml"])
+        .output()
+        .expect("Failed to execute command");
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("app::require::missing_file"), "{}", stderr);
+    assert!(stderr.contains("worker"), "{}", stderr);
+}
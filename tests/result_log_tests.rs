@@ -0,0 +1,82 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use toml_and_jerry::result_log::{already_passed, read_log, ResultLog};
+use toml_and_jerry::schema::load_and_compile_schema;
+use toml_and_jerry::validation::validate_inputs;
+
+const VALID: &str = "test-examples/valid-config.json";
+const INVALID: &str = "test-examples/invalid-config.json";
+
+fn log_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("toml-and-jerry-result-log-{}-{}.ndjson", name, std::process::id()));
+    fs::remove_file(&path).ok();
+    path
+}
+
+fn inputs() -> Vec<PathBuf> {
+    vec![PathBuf::from(VALID), PathBuf::from(INVALID)]
+}
+
+#[test]
+fn test_outcomes_are_appended_and_read_back() {
+    let path = log_path("append");
+    let validator = load_and_compile_schema(&PathBuf::from("test-examples/schema.json")).unwrap();
+    let mut log = ResultLog::open(&path, "schema.json@1".to_string()).unwrap();
+    log.append(&PathBuf::from(VALID), &[]).unwrap();
+    log.append(&PathBuf::from(INVALID), &validate_inputs(vec![PathBuf::from(INVALID)], &validator).unwrap()).unwrap();
+    drop(log);
+    // An interrupted write leaves a partial last line
+    fs::write(&path, fs::read_to_string(&path).unwrap() + "{\"path\": \"test-exa").unwrap();
+
+    let entries = read_log(&path);
+    assert_eq!(entries.len(), 2);
+    assert_eq!((entries[0].path.as_str(), entries[0].errors), (VALID, 0));
+    assert!(entries[1].errors > 0);
+    assert!(entries[0].sha256.is_some() && entries[0].finished_at.ends_with('Z'));
+
+    assert_eq!(already_passed(&entries, "schema.json@1", &inputs()), vec![PathBuf::from(VALID)]);
+    assert!(already_passed(&entries, "schema.json@2", &inputs()).is_empty(), "Another schema validates everything again");
+    fs::remove_file(&path).ok();
+}
+
+#[test]
+fn test_latest_entry_and_digest_decide() {
+    let path = log_path("latest");
+    let mut log = ResultLog::open(&path, "s".to_string()).unwrap();
+    log.append(&PathBuf::from(VALID), &[]).unwrap();
+    drop(log);
+    let mut entries = read_log(&path);
+    assert_eq!(already_passed(&entries, "s", &inputs()).len(), 1);
+
+    let mut changed = entries[0].clone();
+    changed.sha256 = Some("0".repeat(64));
+    entries.push(changed);
+    assert!(already_passed(&entries, "s", &inputs()).is_empty(), "A file changed since it passed is validated again");
+    assert!(already_passed(&[], "s", &inputs()).is_empty());
+    fs::remove_file(&path).ok();
+}
+
+#[test]
+fn test_cli_resume_skips_files_that_passed() {
+    let path = log_path("cli");
+    let check = |extra: &[&str]| {
+        Command::new("cargo")
+            .args(["run", "--", "check", "--schema", "test-examples/schema.json", VALID, INVALID, "--result-log"])
+            .arg(&path)
+            .args(extra)
+            .output()
+            .expect("Failed to execute command")
+    };
+    let first = check(&[]);
+    assert_eq!(first.status.code(), Some(1));
+    assert_eq!(read_log(&path).len(), 2);
+
+    let resumed = check(&["--resume"]);
+    let stdout = String::from_utf8_lossy(&resumed.stdout);
+    assert!(stdout.contains("Resuming: skipping 1 file(s)"), "{}", stdout);
+    assert!(!stdout.contains("Processing file: \"test-examples/valid-config.json\""), "{}", stdout);
+    assert_eq!(resumed.status.code(), Some(1), "The failing file is validated and reported again");
+    assert_eq!(read_log(&path).len(), 3);
+    fs::remove_file(&path).ok();
+}
@@ -0,0 +1,72 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use toml_and_jerry::error::AppError;
+use toml_and_jerry::rules_pack::{load_pack, run_rules_pack, RulesPack, Severity};
+use toml_and_jerry::validation::ValidationOptions;
+
+const PACK_DIR: &str = "test-examples/rules-pack/org-standards";
+
+fn pack() -> RulesPack {
+    RulesPack::open(PACK_DIR, None).expect("Pack should load")
+}
+
+#[test]
+fn test_manifest_is_loaded() {
+    let pack = pack();
+    assert_eq!(pack.manifest.name, "org-standards");
+    assert_eq!(pack.manifest.version, "1.2.0");
+    assert_eq!(pack.manifest.rules[0].docs.as_deref(), Some("docs/service-config.md"));
+    assert_eq!(pack.manifest.severity.get("app::schema::renamed_property"), Some(&Severity::Off));
+}
+
+#[test]
+fn test_pinned_version_must_match_manifest() {
+    let err = load_pack("org-standards@2.0.0", Path::new(PACK_DIR)).unwrap_err();
+    assert!(matches!(err, AppError::RulesPackError { .. }));
+    assert!(err.to_string().contains("org-standards@1.2.0"));
+    assert!(load_pack("org-standards@1.2.0", Path::new(PACK_DIR)).is_ok());
+}
+
+#[test]
+fn test_pack_rules_validate_matching_files() {
+    let errors = run_rules_pack(&pack(), &[PathBuf::from("test-examples/rules-pack/repo")], &ValidationOptions::default()).unwrap();
+    assert_eq!(errors.len(), 1, "Only search.yaml should fail: {:?}", errors);
+    assert!(matches!(&errors[0], AppError::SchemaValidationError(error) if error.path.ends_with("search.yaml")));
+}
+
+#[test]
+fn test_severity_mapping_downgrades_and_drops() {
+    let mut pack = pack();
+    pack.manifest.severity.insert("app::schema::validation_error".to_string(), Severity::Warning);
+    let errors = run_rules_pack(&pack, &[PathBuf::from("test-examples/rules-pack/repo")], &ValidationOptions::default()).unwrap();
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].is_warning(), "Mapped diagnostics should no longer fail the run");
+
+    pack.manifest.severity.insert("app::schema::validation_error".to_string(), Severity::Off);
+    assert!(pack.apply_severity(errors).is_empty());
+}
+
+#[test]
+fn test_pack_from_tarball() {
+    let dir = std::env::temp_dir().join(format!("toml-and-jerry-pack-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let archive = dir.join("org-standards-1.2.0.tar.gz");
+    {
+        let encoder = flate2::write::GzEncoder::new(std::fs::File::create(&archive).unwrap(), flate2::Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+        builder.append_dir_all("org-standards", PACK_DIR).unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+    }
+
+    let output = Command::new("cargo")
+        .args(["run", "--", "check", "--rules-pack"])
+        .arg(&archive)
+        .arg("test-examples/rules-pack/repo/services/payments.yaml")
+        .env("TOML_AND_JERRY_CACHE_DIR", dir.join("cache"))
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("org-standards@1.2.0"));
+
+    std::fs::remove_dir_all(&dir).ok();
+}
@@ -0,0 +1,51 @@
+use std::process::Command;
+use toml_and_jerry::provenance::DigestedArtifact;
+use toml_and_jerry::run_record::{args_without_record_flag, read_record, replay_drift};
+
+fn artifact(uri: &str, sha256: &str) -> DigestedArtifact {
+    DigestedArtifact { uri: uri.to_string(), sha256: Some(sha256.to_string()) }
+}
+
+#[test]
+fn test_record_flag_is_not_recorded() {
+    let args: Vec<String> = ["check", "a.json", "--record-run", "run.json", "--record-run=other.json", "-s", "s.json"]
+        .iter()
+        .map(|a| a.to_string())
+        .collect();
+    assert_eq!(args_without_record_flag(&args), vec!["check", "a.json", "-s", "s.json"]);
+}
+
+#[test]
+fn test_replay_drift_reports_changed_inputs() {
+    let dir = std::env::temp_dir().join(format!("toml-and-jerry-record-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let record_path = dir.join("run.json");
+
+    let output = Command::new("cargo")
+        .args(["run", "--", "check", "test-examples/valid-config.json", "--schema", "test-examples/schema.json", "--record-run"])
+        .arg(&record_path)
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let record = read_record(&record_path).expect("Record should be readable");
+    assert_eq!(record.args, vec!["check", "test-examples/valid-config.json", "--schema", "test-examples/schema.json"]);
+    assert_eq!(record.inputs[0].uri, "test-examples/valid-config.json");
+    assert!(replay_drift(&record, &record.tool_version, &record.schemas, &record.inputs).is_empty());
+
+    let edited = vec![artifact("test-examples/valid-config.json", "0000")];
+    let drift = replay_drift(&record, "0.0.0", &record.schemas, &edited);
+    assert_eq!(drift.len(), 2, "{:?}", drift);
+    assert!(drift[1].contains("has changed"));
+
+    let replay = Command::new("cargo")
+        .args(["run", "--", "--replay"])
+        .arg(&record_path)
+        .output()
+        .expect("Failed to execute command");
+    assert!(replay.status.success());
+    let stderr = String::from_utf8_lossy(&replay.stderr);
+    assert!(!stderr.contains("Replay differs"), "Nothing changed, so the replay should match: {}", stderr);
+
+    std::fs::remove_dir_all(&dir).ok();
+}
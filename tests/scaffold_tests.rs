@@ -0,0 +1,83 @@
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use serde_json::json;
+use toml_and_jerry::scaffold::{render_drift, scaffold_schema, schema_drift};
+
+const APP: &str = "test-examples/scaffold/app";
+
+#[test]
+fn test_schema_follows_serde_attributes() {
+    let schema = scaffold_schema(Path::new(APP), "Config").unwrap();
+    assert_eq!(schema["title"], "Config");
+    assert_eq!(schema["additionalProperties"], false, "deny_unknown_fields closes the object");
+    assert_eq!(schema["required"], json!(["name", "server"]), "Option, default and skipped fields are not required");
+    assert!(schema["properties"].get("loaded_from").is_none(), "#[serde(skip)] fields are left out");
+    assert_eq!(schema["properties"]["name"]["description"], "Name shown in logs");
+    assert_eq!(schema["properties"]["upstreams"]["additionalProperties"], json!({ "$ref": "#/$defs/Upstream" }));
+
+    let server = &schema["$defs"]["Server"];
+    assert_eq!(server["required"], json!(["host", "port", "tls"]), "rename_all and rename apply to the keys");
+    assert_eq!(server["properties"]["maxConnections"]["type"], "integer");
+    assert_eq!(server["properties"]["port"], json!({ "type": "integer", "minimum": 0, "maximum": 65535 }));
+    assert_eq!(schema["$defs"]["LogLevel"], json!({ "type": "string", "enum": ["debug", "info", "warn"] }));
+}
+
+#[test]
+fn test_unknown_root_is_an_error() {
+    let message = scaffold_schema(Path::new(APP), "Settings").unwrap_err();
+    assert!(message.contains("No struct or enum named Settings"), "{}", message);
+}
+
+#[test]
+fn test_drift_ignores_annotations_and_order() {
+    let generated = scaffold_schema(Path::new(APP), "Config").unwrap();
+    let committed: serde_json::Value = serde_json::from_str(&fs::read_to_string("test-examples/scaffold/schema.json").unwrap()).unwrap();
+    assert_eq!(schema_drift(&generated, &committed), Vec::new(), "descriptions and the order of required names differ");
+}
+
+#[test]
+fn test_drift_lists_changed_added_and_removed_keywords() {
+    let generated = scaffold_schema(Path::new(APP), "Config").unwrap();
+    let committed: serde_json::Value = serde_json::from_str(&fs::read_to_string("test-examples/scaffold/drifted.json").unwrap()).unwrap();
+    let drifts = schema_drift(&generated, &committed);
+    let pointers: Vec<&str> = drifts.iter().map(|d| d.pointer.as_str()).collect();
+    assert_eq!(
+        pointers,
+        vec!["/$defs/Server/properties/port/maximum", "/$defs/Server/properties/tls", "/$defs/Server/required", "/properties/timeout"]
+    );
+    let rendered = render_drift(&drifts, "drifted.json");
+    assert!(rendered.contains("/$defs/Server/properties/port/maximum: 65535 in the generated schema, 1023 in drifted.json\n"), "{}", rendered);
+    assert!(rendered.contains("/properties/timeout: missing in the generated schema, {\"type\":\"integer\"} in drifted.json\n"), "{}", rendered);
+}
+
+#[test]
+fn test_cli_check_fails_on_drift() {
+    let output = Command::new("cargo")
+        .args(["run", "--", "scaffold", APP, "--out", "test-examples/scaffold/schema.json", "--check"])
+        .output()
+        .expect("Failed to execute command");
+    assert_eq!(output.status.code(), Some(0), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let output = Command::new("cargo")
+        .args(["run", "--", "scaffold", APP, "--out", "test-examples/scaffold/drifted.json", "--check"])
+        .output()
+        .expect("Failed to execute command");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("/properties/timeout"));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("out of date: 4 difference(s)"));
+}
+
+#[test]
+fn test_cli_writes_the_schema() {
+    let out = std::env::temp_dir().join(format!("toml-and-jerry-scaffold-{}.json", std::process::id()));
+    let output = Command::new("cargo")
+        .args(["run", "--", "scaffold", APP, "--out"])
+        .arg(&out)
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let written: serde_json::Value = serde_json::from_str(&fs::read_to_string(&out).unwrap()).unwrap();
+    assert_eq!(written, scaffold_schema(Path::new(APP), "Config").unwrap());
+    fs::remove_file(&out).ok();
+}
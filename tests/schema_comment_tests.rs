@@ -0,0 +1,60 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use jsonschema::Validator;
+use serde_json::json;
+use toml_and_jerry::error::AppError;
+use toml_and_jerry::schema_comment::{annotated_schema_path, without_annotation};
+use toml_and_jerry::validation::{validate_inputs_with_options, ValidationOptions};
+
+const SERVICES: &str = "test-examples/schema-comment/services";
+
+#[test]
+fn test_annotation_resolves_relative_to_the_file() {
+    let input = Path::new(SERVICES).join("api.yaml");
+    assert_eq!(
+        annotated_schema_path(&input, "# tj-schema: ../schemas/service.schema.json\nservice: api\n"),
+        Some(Path::new(SERVICES).join("../schemas/service.schema.json"))
+    );
+    assert_eq!(
+        annotated_schema_path(&input, "// tj-schema: https://example.com/s.json\n"),
+        Some(PathBuf::from("https://example.com/s.json"))
+    );
+    assert_eq!(annotated_schema_path(&input, "service: api\n# tj-schema: other.json\n"), None);
+}
+
+#[test]
+fn test_blanking_keeps_offsets() {
+    let content = "// tj-schema: s.json\n{\"a\": 1}\n";
+    let blanked = without_annotation(content);
+    assert_eq!(blanked.len(), content.len());
+    assert_eq!(serde_json::from_str::<serde_json::Value>(&blanked).unwrap(), json!({ "a": 1 }));
+}
+
+#[test]
+fn test_annotated_schema_overrides_the_default() {
+    // The default schema rejects everything, so only annotated files can pass
+    let reject_all = Validator::new(&json!(false)).unwrap();
+    let inputs = vec![Path::new(SERVICES).join("api.yaml"), Path::new(SERVICES).join("worker.json")];
+    let errors = validate_inputs_with_options(inputs, &reject_all, &ValidationOptions::default()).unwrap();
+    assert_eq!(errors.len(), 1, "Only worker.json violates its schema: {:?}", errors);
+    assert!(matches!(&errors[0], AppError::SchemaValidationError(error)
+        if error.path.ends_with("worker.json") && error.instance_path == "/replicas"));
+}
+
+#[test]
+fn test_unannotated_files_fail_without_a_fallback_schema() {
+    let accept_all = Validator::new(&json!(true)).unwrap();
+    let options = ValidationOptions { require_schema_comment: true, ..Default::default() };
+    let errors = validate_inputs_with_options(vec![Path::new(SERVICES).join("unbound.toml")], &accept_all, &options).unwrap();
+    assert!(matches!(&errors[..], [AppError::InvalidSchemaPath { path_display }] if path_display.contains("tj-schema")));
+}
+
+#[test]
+fn test_schema_comments_flag_needs_no_schema() {
+    let output = Command::new("cargo")
+        .args(["run", "--", "check", "--schema-comments"])
+        .arg(Path::new(SERVICES).join("api.yaml"))
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+}
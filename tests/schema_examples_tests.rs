@@ -0,0 +1,73 @@
+use std::path::PathBuf;
+use std::process::Command;
+use toml_and_jerry::error::AppError;
+use toml_and_jerry::schema::load_schema_json;
+use toml_and_jerry::schema_examples::invalid_example_diagnostics;
+
+const SCHEMA: &str = "test-examples/schema-lint/schema.json";
+
+fn invalid_examples() -> Vec<(String, usize)> {
+    let schema = load_schema_json(&PathBuf::from(SCHEMA)).unwrap();
+    let content = std::fs::read_to_string(SCHEMA).unwrap();
+    invalid_example_diagnostics(&schema, SCHEMA, &content)
+        .into_iter()
+        .map(|error| match error {
+            AppError::InvalidSchemaExample(error) => (error.pointer, error.index),
+            other => panic!("Unexpected diagnostic {:?}", other),
+        })
+        .collect()
+}
+
+#[test]
+fn test_examples_are_checked_against_their_subschema() {
+    let mut found = invalid_examples();
+    found.sort();
+    assert_eq!(
+        found,
+        [
+            ("/$defs/Service/examples".to_string(), 1),
+            ("/$defs/Service/properties/port/examples".to_string(), 1),
+            ("/properties/mode/examples".to_string(), 1),
+        ]
+    );
+}
+
+#[test]
+fn test_references_resolve_inside_the_subschema() {
+    // `port: 443` only validates if `#/$defs/Port` was carried along
+    assert!(!invalid_examples().contains(&("/$defs/Service/properties/port/examples".to_string(), 0)));
+}
+
+#[test]
+fn test_diagnostic_is_labelled_at_the_examples_key() {
+    let schema = load_schema_json(&PathBuf::from(SCHEMA)).unwrap();
+    let content = std::fs::read_to_string(SCHEMA).unwrap();
+    let errors = invalid_example_diagnostics(&schema, SCHEMA, &content);
+    let mode = errors
+        .iter()
+        .find_map(|e| match e {
+            AppError::InvalidSchemaExample(error) if error.pointer == "/properties/mode/examples" => Some((&error.span, &error.message)),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(&content[mode.0.offset()..mode.0.offset() + mode.0.len()], "examples");
+    assert!(content[..mode.0.offset()].contains("\"mode\""));
+    assert!(mode.1.contains("turbo"), "{}", mode.1);
+}
+
+#[test]
+fn test_cli_lint_fails_on_invalid_examples() {
+    let output = Command::new("cargo")
+        .args(["run", "--", "schema", "lint", SCHEMA])
+        .output()
+        .expect("Failed to execute command");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains(&format!("3 problem(s) in {}", SCHEMA)));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("app::schema::invalid_example"));
+
+    let output = Command::new("cargo")
+        .args(["run", "--", "schema", "lint", "test-examples/schema.json"])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+}
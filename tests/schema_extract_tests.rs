@@ -0,0 +1,74 @@
+use std::path::PathBuf;
+use std::process::Command;
+use serde_json::json;
+use toml_and_jerry::schema::{compile_schema, load_schema_json};
+use toml_and_jerry::schema_extract::extract_fragment;
+use toml_and_jerry::validation::validate_inputs;
+
+fn big_schema() -> serde_json::Value {
+    load_schema_json(&PathBuf::from("test-examples/schema-extract/big-schema.json")).unwrap()
+}
+
+#[test]
+fn test_extracted_fragment_carries_its_references() {
+    let fragment = extract_fragment(&big_schema(), "#/definitions/Database").unwrap();
+
+    assert_eq!(fragment["$schema"], "http://json-schema.org/draft-07/schema#");
+    assert_eq!(fragment["properties"]["port"]["$ref"], "#/definitions/Port");
+    assert_eq!(fragment["properties"]["replica"]["$ref"], "#", "Self references point at the new root");
+    assert_eq!(fragment["properties"]["backup_port"]["$ref"], "#/properties/port");
+    assert_eq!(fragment["definitions"]["Credentials"]["properties"]["password"]["$ref"], "#/definitions/Credentials/definitions/Secret");
+    let copied: Vec<_> = fragment["definitions"].as_object().unwrap().keys().cloned().collect();
+    assert_eq!(copied, vec!["Credentials", "Port"], "Server is not referenced by Database");
+}
+
+#[test]
+fn test_extracted_fragment_validates_partial_documents() {
+    let fragment = extract_fragment(&big_schema(), "/definitions/Database").unwrap();
+    let validator = compile_schema(&fragment, "Database").expect("Extracted fragment should compile");
+    assert!(validate_inputs(vec![PathBuf::from("test-examples/schema-extract/database.yaml")], &validator).unwrap().is_empty());
+    assert!(!validator.is_valid(&json!({ "host": "db", "port": 70000 })));
+    assert!(!validator.is_valid(&json!({ "host": "db", "port": 1, "credentials": { "password": "short" } })));
+}
+
+#[test]
+fn test_name_clashes_and_bad_pointers() {
+    let schema = json!({
+        "$defs": {
+            "Port": { "type": "integer" },
+            "Service": {
+                "$defs": { "Port": { "type": "string" } },
+                "properties": {
+                    "name": { "$ref": "#/$defs/Service/$defs/Port" },
+                    "port": { "$ref": "#/$defs/Port" }
+                }
+            },
+            "Loop": { "$ref": "#" }
+        }
+    });
+    let fragment = extract_fragment(&schema, "#/$defs/Service").unwrap();
+    assert_eq!(fragment["properties"]["name"]["$ref"], "#/$defs/Port");
+    assert_eq!(fragment["properties"]["port"]["$ref"], "#/$defs/Port_2");
+    assert_eq!(fragment["$defs"]["Port_2"], json!({ "type": "integer" }));
+
+    assert!(extract_fragment(&schema, "#/$defs/Missing").unwrap_err().contains("nothing at"));
+    assert!(extract_fragment(&schema, "definitions/Service").is_err());
+    assert!(extract_fragment(&schema, "#/$defs/Loop").unwrap_err().contains("schema root"));
+}
+
+#[test]
+fn test_cli_writes_fragment_to_out_file() {
+    let dir = std::env::temp_dir().join(format!("tj-schema-extract-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let out = dir.join("db.json");
+    let output = Command::new("cargo")
+        .args(["run", "--", "schema", "extract", "test-examples/schema-extract/big-schema.json", "#/definitions/Database", "--out"])
+        .arg(&out)
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let written: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&out).unwrap()).unwrap();
+    assert_eq!(written["required"], json!(["host", "port"]));
+    std::fs::remove_dir_all(&dir).ok();
+}
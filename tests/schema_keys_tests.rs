@@ -0,0 +1,56 @@
+use std::path::PathBuf;
+use std::process::Command;
+use serde_json::json;
+use toml_and_jerry::schema::load_schema_json;
+use toml_and_jerry::schema_keys::schema_keys;
+
+#[test]
+fn test_flattens_nested_properties_with_required_flags() {
+    let schema = load_schema_json(&PathBuf::from("test-examples/schema.json")).unwrap();
+    let keys = schema_keys(&schema, "test-examples/schema.json").keys;
+    let paths: Vec<_> = keys.iter().map(|k| k.path.as_str()).collect();
+    assert!(paths.windows(2).all(|w| w[0] < w[1]), "Paths should be sorted: {:?}", paths);
+
+    let port = keys.iter().find(|k| k.path == "database.port").expect("Nested path should be listed");
+    assert_eq!(port.types, ["integer"]);
+    assert_eq!(port.description.as_deref(), Some("Database port"));
+    assert!(port.required);
+    let debug = keys.iter().find(|k| k.path == "debug").unwrap();
+    assert!(!debug.required);
+    assert_eq!(debug.default, Some(json!(false)));
+}
+
+#[test]
+fn test_follows_refs_items_maps_and_branches() {
+    let schema = json!({
+        "$defs": { "level": { "type": "string", "enum": ["debug", "info"], "description": "Log level" } },
+        "properties": {
+            "log": { "$ref": "#/$defs/level" },
+            "workers": { "type": "array", "items": { "properties": { "threads": { "type": "integer" } } } },
+            "labels": { "type": "object", "additionalProperties": { "type": "string" } },
+            "mode": { "oneOf": [{ "const": "fast" }, { "type": "integer" }] }
+        }
+    });
+    let keys = schema_keys(&schema, "inline").keys;
+    let find = |path: &str| keys.iter().find(|k| k.path == path).unwrap_or_else(|| panic!("{} should be listed", path));
+
+    assert_eq!(find("log").enum_values, [json!("debug"), json!("info")]);
+    assert_eq!(find("log").description.as_deref(), Some("Log level"));
+    assert_eq!(find("workers[]").types, ["object"]);
+    assert_eq!(find("workers[].threads").types, ["integer"]);
+    assert_eq!(find("labels.*").types, ["string"]);
+    assert_eq!(find("mode").enum_values, [json!("fast")]);
+    assert_eq!(find("mode").types, ["integer"]);
+}
+
+#[test]
+fn test_schema_keys_json_output() {
+    let output = Command::new("cargo")
+        .args(["run", "--", "schema", "keys", "test-examples/schema.json", "--format", "json"])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+    let export: serde_json::Value = serde_json::from_slice(&output.stdout).expect("Output should be JSON");
+    assert_eq!(export["schema"], "test-examples/schema.json");
+    assert!(export["keys"].as_array().unwrap().iter().any(|k| k["path"] == "database.host" && k["types"] == json!(["string"])));
+}
@@ -0,0 +1,76 @@
+use std::path::PathBuf;
+use std::process::Command;
+use toml_and_jerry::error::AppError;
+use toml_and_jerry::schema::load_schema_json;
+use toml_and_jerry::schema_patterns::{catastrophic_reason, unsafe_pattern_diagnostics};
+
+const SCHEMA: &str = "test-examples/schema-lint/patterns.json";
+
+#[test]
+fn test_nested_unbounded_quantifiers_are_flagged() {
+    for pattern in ["(a+)+", "^(\\w*,?)*$", "((ab)*c)+", "(?:x+y?){2,}"] {
+        let reason = catastrophic_reason(pattern).unwrap_or_else(|| panic!("{} should be flagged", pattern));
+        assert!(reason.contains("around a quantifier"), "{}: {}", pattern, reason);
+    }
+}
+
+#[test]
+fn test_overlapping_alternatives_under_repetition_are_flagged() {
+    for pattern in ["^(a|ab)*$", "(foo|foo)+", "(.|\\n)*"] {
+        let reason = catastrophic_reason(pattern).unwrap_or_else(|| panic!("{} should be flagged", pattern));
+        assert!(reason.contains("alternatives"), "{}: {}", pattern, reason);
+    }
+}
+
+#[test]
+fn test_ordinary_patterns_pass() {
+    for pattern in [
+        "^\\d+\\.\\d+\\.\\d+$",
+        "^[a-z]{2}-(east|west)-[0-9]+$",
+        "(a+)?",
+        "(\\d{1,3}\\.){3}\\d{1,3}",
+        "^[(+*)]+$",
+        "\\(a+\\)+",
+        "(a|b)*",
+        "x{2}",
+    ] {
+        assert_eq!(catastrophic_reason(pattern), None, "{}", pattern);
+    }
+}
+
+#[test]
+fn test_schema_patterns_and_pattern_properties_are_linted() {
+    let schema = load_schema_json(&PathBuf::from(SCHEMA)).unwrap();
+    let content = std::fs::read_to_string(SCHEMA).unwrap();
+    let mut pointers: Vec<String> = unsafe_pattern_diagnostics(&schema, SCHEMA, &content)
+        .into_iter()
+        .map(|warning| {
+            assert!(warning.is_warning());
+            match warning {
+                AppError::UnsafePattern(warning) => warning.pointer,
+                other => panic!("Unexpected diagnostic {:?}", other),
+            }
+        })
+        .collect();
+    pointers.sort();
+    assert_eq!(pointers, ["/patternProperties/^x-(.*)+$", "/properties/host/pattern", "/properties/tag/pattern"]);
+}
+
+#[test]
+fn test_cli_lint_warns_without_failing() {
+    let output = Command::new("cargo").args(["run", "--", "schema", "lint", SCHEMA]).output().expect("Failed to execute command");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("app::schema::unsafe_pattern"));
+}
+
+#[test]
+fn test_backtrack_limit_cuts_pattern_evaluation_off() {
+    let check = |limit: &str| {
+        Command::new("cargo")
+            .args(["run", "--", "check", "--schema", SCHEMA, "test-examples/schema-lint/pair.json", "--regex-backtrack-limit", limit])
+            .output()
+            .expect("Failed to execute command")
+    };
+    assert!(check("1000000").status.success(), "The backreference matches within the default budget");
+    assert!(!check("1").status.success(), "A value that needs more steps than allowed fails");
+}
@@ -0,0 +1,48 @@
+use std::path::PathBuf;
+use std::process::Command;
+use serde_json::json;
+use toml_and_jerry::error::AppError;
+use toml_and_jerry::schema::{check_shape, load_schema_json};
+
+#[test]
+fn test_array_schema_is_rejected_with_span_on_opening_bracket() {
+    let error = load_schema_json(&PathBuf::from("test-examples/schema-shape/exported-list.schema.json"))
+        .expect_err("An array is not a schema");
+    match &error {
+        AppError::InvalidSchemaShape { kind, span, .. } => {
+            assert_eq!(*kind, "an array");
+            assert_eq!((span.offset(), span.len()), (0, 1));
+        }
+        other => panic!("Expected InvalidSchemaShape, got {:?}", other),
+    }
+    assert!(!error.is_warning());
+}
+
+#[test]
+fn test_scalar_schemas_are_rejected_and_objects_and_booleans_accepted() {
+    let content = "  \"config.schema.json\"\n";
+    let error = check_shape(&json!("config.schema.json"), "quoted", content).unwrap_err();
+    match error {
+        AppError::InvalidSchemaShape { kind, span, .. } => {
+            assert_eq!(kind, "a string");
+            assert_eq!(&content[span.offset()..span.offset() + span.len()], "\"config.schema.json\"");
+        }
+        other => panic!("Expected InvalidSchemaShape, got {:?}", other),
+    }
+    assert!(matches!(check_shape(&json!(null), "null", "null"), Err(AppError::InvalidSchemaShape { kind: "null", .. })));
+    assert!(check_shape(&json!({ "type": "object" }), "object", "{\"type\": \"object\"}").is_ok());
+    assert!(check_shape(&json!(false), "boolean", "false").is_ok());
+}
+
+#[test]
+fn test_cli_reports_invalid_shape_with_hint() {
+    let output = Command::new("cargo")
+        .args(["run", "--", "check", "test-examples/valid-config.json", "--schema", "test-examples/schema-shape/exported-list.schema.json"])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("app::schema::invalid_shape"), "{}", stderr);
+    assert!(stderr.contains("OpenAPI"), "{}", stderr);
+}
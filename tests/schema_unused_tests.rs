@@ -0,0 +1,61 @@
+use std::path::PathBuf;
+use std::process::Command;
+use serde_json::json;
+use toml_and_jerry::error::{Category, Severity};
+use toml_and_jerry::schema::load_schema_json;
+use toml_and_jerry::schema_unused::{unused_definition_diagnostics, unused_definitions};
+use toml_and_jerry::validation::PrintableError;
+
+const SCHEMA: &str = "test-examples/schema-unused/schema.json";
+
+#[test]
+fn test_definitions_only_reached_from_unused_ones_are_unused() {
+    let schema = load_schema_json(&PathBuf::from(SCHEMA)).unwrap();
+    // Port is reached through Server, Logging by its $anchor; Timeout only from LegacyProxy
+    assert_eq!(unused_definitions(&schema), vec!["/$defs/LegacyProxy", "/$defs/Timeout"]);
+}
+
+#[test]
+fn test_both_definition_keywords_and_data_keywords() {
+    let schema = json!({
+        "properties": { "a": { "$ref": "#/definitions/A/properties/x" } },
+        "default": { "$ref": "#/definitions/B" },
+        "definitions": {
+            "A": { "properties": { "x": { "type": "string" } } },
+            "B": { "type": "string" },
+            "Self": { "$ref": "#/definitions/Self" }
+        },
+        "$defs": { "C": { "$ref": "#" } }
+    });
+    assert_eq!(unused_definitions(&schema), vec!["/definitions/B", "/definitions/Self", "/$defs/C"]);
+    assert!(unused_definitions(&json!(true)).is_empty());
+}
+
+#[test]
+fn test_diagnostics_are_schema_warnings_at_the_definition_key() {
+    let content = std::fs::read_to_string(SCHEMA).unwrap();
+    let schema: serde_json::Value = serde_json::from_str(&content).unwrap();
+    let diagnostics = unused_definition_diagnostics(&schema, SCHEMA, &content);
+
+    assert_eq!(diagnostics.len(), 2);
+    let printable = PrintableError::from(&diagnostics[0]);
+    assert_eq!(printable.rule_id, "app::schema::unused_definition");
+    assert_eq!(printable.file_path, SCHEMA);
+    assert_eq!(printable.severity, Some(Severity::Warning));
+    assert_eq!(printable.category, Some(Category::Schema));
+    assert!(printable.message.contains("/$defs/LegacyProxy"));
+}
+
+#[test]
+fn test_cli_reports_unused_definitions_without_failing() {
+    let output = Command::new("cargo")
+        .args(["run", "--", "schema", "unused", SCHEMA, "--format", "json"])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success(), "Unused definitions are warnings: {}", String::from_utf8_lossy(&output.stderr));
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["schema"], SCHEMA);
+    let codes: Vec<_> = report["errors"].as_array().unwrap().iter().map(|e| e["ruleId"].as_str().unwrap().to_string()).collect();
+    assert_eq!(codes, vec!["app::schema::unused_definition"; 2]);
+}
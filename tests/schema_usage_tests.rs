@@ -0,0 +1,53 @@
+use std::path::PathBuf;
+use std::process::Command;
+use serde_json::json;
+use toml_and_jerry::schema_usage::{schema_usage, values_at, PropertyUsage};
+use toml_and_jerry::yaml::TagPolicy;
+
+fn usage() -> Vec<PropertyUsage> {
+    let schema: serde_json::Value = serde_json::from_str(&std::fs::read_to_string("test-examples/usage/service.schema.json").unwrap()).unwrap();
+    let files: Vec<PathBuf> = ["api.yaml", "worker.json", "cron.toml"].iter().map(|f| PathBuf::from("test-examples/usage/corpus").join(f)).collect();
+    let usage = schema_usage(&schema, "service.schema.json", &files, &TagPolicy::default());
+    assert_eq!(usage.documents, 3);
+    usage.properties
+}
+
+fn property<'a>(properties: &'a [PropertyUsage], path: &str) -> &'a PropertyUsage {
+    properties.iter().find(|p| p.path == path).unwrap()
+}
+
+#[test]
+fn test_values_at_follows_items_and_maps() {
+    let document = json!({ "workers": [{ "labels": { "a": 1, "b": 2 } }, { "labels": { "c": 3 } }] });
+    assert_eq!(values_at(&document, "workers[].labels.*"), [&json!(1), &json!(2), &json!(3)]);
+    assert!(values_at(&document, "missing").is_empty());
+}
+
+#[test]
+fn test_counts_documents_and_values() {
+    let properties = usage();
+    let ports = property(&properties, "ports[]");
+    assert_eq!((ports.set_in, ports.distinct_values), (3, 3));
+    assert_eq!((ports.values[0].value.clone(), ports.values[0].count), (json!(80), 2));
+    assert_eq!(property(&properties, "legacy_mode").set_in, 0);
+}
+
+#[test]
+fn test_defaults_always_overridden() {
+    let properties = usage();
+    assert!(property(&properties, "replicas").default_always_overridden);
+    // Set to its default once, and missing from one document
+    assert!(!property(&properties, "log_level").default_always_overridden);
+}
+
+#[test]
+fn test_cli_schema_usage() {
+    let output = Command::new("cargo")
+        .args(["run", "--", "schema", "usage", "--schema", "test-examples/usage/service.schema.json", "test-examples/usage/corpus"])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("3 document(s) checked"), "{}", stdout);
+    assert!(stdout.contains("[default always overridden]"), "{}", stdout);
+}
@@ -0,0 +1,97 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::process::Command;
+use toml_and_jerry::error::{AppError, Severity};
+use toml_and_jerry::schema::load_and_compile_schema;
+use toml_and_jerry::severity::{apply, parse_flag, rule_names, RuleSeverity};
+use toml_and_jerry::validation::{validate_inputs, PrintableError};
+
+const PORT_TOO_LOW: &str = "test-examples/severity/config.json";
+const MISSING_NAME: &str = "test-examples/severity/missing-name.json";
+
+fn diagnostics(files: &[&str]) -> Vec<AppError> {
+    let validator = load_and_compile_schema(&PathBuf::from("test-examples/schema.json")).unwrap();
+    validate_inputs(files.iter().map(PathBuf::from).collect(), &validator).unwrap()
+}
+
+fn mapping(entries: &[(&str, RuleSeverity)]) -> BTreeMap<String, RuleSeverity> {
+    entries.iter().map(|(rule, level)| (rule.to_string(), *level)).collect()
+}
+
+fn check(args: &[&str]) -> std::process::Output {
+    Command::new("cargo")
+        .args(["run", "--", "check", "--schema", "test-examples/schema.json"])
+        .args(args)
+        .output()
+        .expect("Failed to execute command")
+}
+
+#[test]
+fn test_flags_parse_rule_and_level() {
+    assert_eq!(parse_flag("required=warning"), Ok(("required".to_string(), RuleSeverity::Warning)));
+    assert_eq!(parse_flag("app::style::final_newline=info"), Ok(("app::style::final_newline".to_string(), RuleSeverity::Info)));
+    assert!(parse_flag("required").is_err());
+    assert!(parse_flag("required=fatal").is_err());
+    assert!(parse_flag("=off").is_err());
+}
+
+#[test]
+fn test_schema_failures_are_named_by_keyword_then_code() {
+    let errors = diagnostics(&[PORT_TOO_LOW, MISSING_NAME]);
+    assert_eq!(rule_names(&errors[0]), ["minimum", "app::schema::validation_error"]);
+    assert_eq!(rule_names(&errors[1]), ["required", "app::schema::validation_error"]);
+}
+
+#[test]
+fn test_keyword_mapping_takes_precedence_over_code() {
+    let errors = diagnostics(&[PORT_TOO_LOW, MISSING_NAME]);
+    let mapped = apply(
+        &mapping(&[("app::schema::validation_error", RuleSeverity::Warning), ("required", RuleSeverity::Error)]),
+        errors,
+    );
+    assert_eq!(mapped[0].level(), Severity::Warning);
+    assert_eq!(mapped[1].level(), Severity::Error);
+    assert!(matches!(mapped[1], AppError::SchemaValidationError { .. }), "Unchanged levels are not wrapped");
+}
+
+#[test]
+fn test_levels_drop_downgrade_and_escalate() {
+    let errors = diagnostics(&[PORT_TOO_LOW, MISSING_NAME]);
+    let mapped = apply(&mapping(&[("minimum", RuleSeverity::Info), ("required", RuleSeverity::Off)]), errors);
+    assert_eq!(mapped.len(), 1);
+    assert_eq!(mapped[0].level(), Severity::Info);
+    assert!(mapped[0].is_warning(), "Info diagnostics do not fail the run");
+
+    let printable = PrintableError::from(&mapped[0]);
+    assert_eq!(printable.rule_id, "app::schema::validation_error");
+    assert_eq!(printable.severity, Some(Severity::Info));
+    assert_eq!(printable.json_path.as_deref(), Some("/port"));
+
+    let escalated = apply(&mapping(&[("minimum", RuleSeverity::Error)]), mapped);
+    assert_eq!(escalated[0].level(), Severity::Error);
+    assert!(matches!(escalated[0], AppError::SchemaValidationError { .. }), "Mapping back unwraps instead of wrapping twice");
+}
+
+#[test]
+fn test_cli_only_errors_fail_the_run() {
+    let output = check(&["--severity", "minimum=warning", PORT_TOO_LOW]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let output = check(&["--severity", "minimum=warning", PORT_TOO_LOW, MISSING_NAME]);
+    assert_eq!(output.status.code(), Some(1), "required is still an error");
+
+    let output = check(&["--severity", "minimum", PORT_TOO_LOW]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn test_cli_flag_overrides_project_config() {
+    let config = "test-examples/severity/project.toml";
+    let output = check(&["--config", config, "--format", "json", PORT_TOO_LOW]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["errors"][0]["severity"], "info");
+
+    let output = check(&["--config", config, "--severity", "minimum=error", PORT_TOO_LOW]);
+    assert_eq!(output.status.code(), Some(1));
+}
@@ -0,0 +1,45 @@
+use std::path::PathBuf;
+use std::process::Command;
+use toml_and_jerry::shard::Shard;
+
+fn inputs(names: &[&str]) -> Vec<PathBuf> {
+    names.iter().map(PathBuf::from).collect()
+}
+
+#[test]
+fn test_parse_shard() {
+    assert_eq!(Shard::parse("2/5"), Ok(Shard { index: 2, count: 5 }));
+    assert!(Shard::parse("0/5").is_err());
+    assert!(Shard::parse("6/5").is_err());
+    assert!(Shard::parse("2").is_err());
+}
+
+#[test]
+fn test_shards_are_disjoint_and_complete() {
+    let files = inputs(&["e.yaml", "a.yaml", "d.yaml", "b.yaml", "c.yaml", "a.yaml"]);
+    let shards: Vec<Vec<PathBuf>> = (1..=3).map(|index| Shard { index, count: 3 }.select(&files)).collect();
+    assert_eq!(shards[0], inputs(&["a.yaml", "d.yaml"]));
+    assert_eq!(shards[1], inputs(&["b.yaml", "e.yaml"]));
+    assert_eq!(shards[2], inputs(&["c.yaml"]));
+}
+
+#[test]
+fn test_assignment_ignores_argument_order() {
+    let forward = inputs(&["a.yaml", "b.yaml", "c.yaml", "d.yaml"]);
+    let backward: Vec<PathBuf> = forward.iter().rev().cloned().collect();
+    let shard = Shard { index: 2, count: 2 };
+    assert_eq!(shard.select(&forward), shard.select(&backward));
+}
+
+#[test]
+fn test_cli_validates_only_its_shard() {
+    let output = Command::new("cargo")
+        .args(["run", "--", "check", "test-examples/valid-config.json", "test-examples/missing-required-fields.json"])
+        .args(["--schema", "test-examples/schema.json", "--shard", "2/2"])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Shard 2/2: validating 1 of 2 input(s)"), "{}", stdout);
+    assert!(!stdout.contains("missing-required-fields.json"), "{}", stdout);
+}
@@ -0,0 +1,52 @@
+use std::path::Path;
+use std::process::Command;
+use toml_and_jerry::signing::{sign_report, signature_path, verify_report};
+
+const PRIVATE_KEY: &str = "test-examples/signing/report-key.pem";
+const PUBLIC_KEY: &str = "test-examples/signing/report-key.pub.pem";
+const OTHER_PUBLIC_KEY: &str = "test-examples/signing/other-key.pub.pem";
+
+#[test]
+fn test_signature_round_trip() {
+    let report = br#"[{"filePath":"a.json"}]"#;
+    let signature = sign_report(report, Path::new(PRIVATE_KEY)).expect("Signing should succeed");
+    assert_eq!(signature.algorithm, "ed25519");
+    assert!(verify_report(report, &signature, Path::new(PUBLIC_KEY)).unwrap());
+}
+
+#[test]
+fn test_tampered_report_or_wrong_key_fails() {
+    let signature = sign_report(b"[]", Path::new(PRIVATE_KEY)).unwrap();
+    assert!(!verify_report(b"[ ]", &signature, Path::new(PUBLIC_KEY)).unwrap(), "Modified report must not verify");
+    assert!(!verify_report(b"[]", &signature, Path::new(OTHER_PUBLIC_KEY)).unwrap(), "Other key must not verify");
+}
+
+#[test]
+fn test_sign_and_verify_report_cli() {
+    let dir = std::env::temp_dir().join(format!("toml-and-jerry-signing-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let report = dir.join("report.json");
+
+    let output = Command::new("cargo")
+        .args(["run", "--", "check", "--schema", "test-examples/schema.json", "--format", "json"])
+        .arg("--output").arg(&report)
+        .args(["--sign-report", PRIVATE_KEY, "test-examples/valid-config.json"])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(signature_path(&report).exists());
+
+    let verify = |key: &str| {
+        Command::new("cargo")
+            .args(["run", "--", "verify-report", "--key", key])
+            .arg(&report)
+            .output()
+            .expect("Failed to execute command")
+    };
+    assert!(verify(PUBLIC_KEY).status.success());
+
+    std::fs::write(&report, "[]\n\n").unwrap();
+    assert!(!verify(PUBLIC_KEY).status.success(), "Edited report should be rejected");
+
+    std::fs::remove_dir_all(&dir).ok();
+}
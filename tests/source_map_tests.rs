@@ -0,0 +1,44 @@
+use std::path::{Path, PathBuf};
+use toml_and_jerry::error::{AppError, MappedToTemplate};
+use toml_and_jerry::schema::{compile_schema, load_schema_json};
+use toml_and_jerry::source_map::{load_source_map, map_to_templates, sidecar_path};
+use toml_and_jerry::template::line_column;
+use toml_and_jerry::validation::{validate_inputs_with_options, PrintableError, ValidationOptions};
+
+fn check(input: &str) -> Vec<AppError> {
+    let schema_path = "test-examples/source_map/schema.json";
+    let schema = load_schema_json(&PathBuf::from(schema_path)).unwrap();
+    let validator = compile_schema(&schema, schema_path).unwrap();
+    validate_inputs_with_options(vec![PathBuf::from(input)], &validator, &ValidationOptions::default()).unwrap()
+}
+
+#[test]
+fn test_maps_generated_lines_to_template_lines() {
+    let generated = Path::new("test-examples/source_map/app.yaml");
+    assert_eq!(sidecar_path(generated), PathBuf::from("test-examples/source_map/app.yaml.map"));
+    let map = load_source_map(generated).unwrap().expect("Sidecar map should be found");
+    assert_eq!(map.original(3), Some((Path::new("templates/app.yaml.j2"), 3)));
+    assert_eq!(map.original(1), None, "The generated header has no template line");
+}
+
+#[test]
+fn test_diagnostic_is_reported_at_template() {
+    let errors = map_to_templates(check("test-examples/source_map/app.yaml"));
+    assert_eq!(errors.len(), 1, "{:?}", errors);
+    let AppError::MappedToTemplate(error) = &errors[0] else { panic!("{:?}", errors) };
+    let MappedToTemplate { template, generated_at, generated, .. } = error.as_ref();
+    assert_eq!(template, &PathBuf::from("test-examples/source_map/templates/app.yaml.j2"));
+    assert_eq!(generated_at, "test-examples/source_map/app.yaml:3:11");
+    assert!(matches!(generated[..], [AppError::SchemaValidationError { .. }]));
+    assert_eq!(line_column(&errors[0]), Some((3, 1)));
+
+    let printable = PrintableError::from(&errors[0]);
+    assert_eq!(printable.file_path, "test-examples/source_map/templates/app.yaml.j2");
+    assert_eq!(printable.rule_id, "app::schema::validation_error", "The original rule is kept");
+}
+
+#[test]
+fn test_files_without_map_are_unchanged() {
+    let errors = map_to_templates(check("test-examples/valid-config.yaml"));
+    assert!(errors.iter().all(|e| !matches!(e, AppError::MappedToTemplate { .. })));
+}
@@ -0,0 +1,78 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use toml_and_jerry::error::AppError;
+use toml_and_jerry::stamp::{check_stamp, comment_prefix, find_stamp, stale_reason, stamp_file, with_stamp, Stamp};
+
+fn stamp(date: &str, passed: bool) -> Stamp {
+    Stamp { schema: "schema.json".into(), digest: "abc123def456".into(), date: date.into(), passed }
+}
+
+#[test]
+fn test_stamp_round_trips() {
+    let line = stamp("2024-05-01", true).render("#");
+    assert_eq!(line, "# tj: validated against schema.json@abc123def456 on 2024-05-01 — PASS");
+    let (range, parsed) = find_stamp(&format!("{}\nport: 1\n", line)).expect("Stamp should be found");
+    assert_eq!((range, parsed), (0..line.len(), stamp("2024-05-01", true)));
+}
+
+#[test]
+fn test_stamp_is_inserted_then_refreshed_in_place() {
+    let original = "# service config\nport: 1 # keep\n";
+    let first = with_stamp(original, &stamp("2024-05-01", false).render("#"));
+    assert_eq!(first, format!("{}\n{}", stamp("2024-05-01", false).render("#"), original));
+    let refreshed = with_stamp(&first, &stamp("2024-06-01", true).render("#"));
+    assert_eq!(refreshed, format!("{}\n{}", stamp("2024-06-01", true).render("#"), original));
+}
+
+#[test]
+fn test_schema_comment_stays_first() {
+    let original = "# tj-schema: service.json\nport: 1\n";
+    let stamped = with_stamp(original, &stamp("2024-05-01", true).render("#"));
+    assert!(stamped.starts_with("# tj-schema: service.json\n# tj: validated against"), "{}", stamped);
+    assert!(stamped.ends_with("\nport: 1\n"));
+}
+
+#[test]
+fn test_formats_without_comments_are_skipped() {
+    let overrides = BTreeMap::new();
+    assert_eq!(comment_prefix(Path::new("a.yaml"), &overrides), Some("#"));
+    assert_eq!(comment_prefix(Path::new("a.cue"), &overrides), Some("//"));
+    assert_eq!(comment_prefix(Path::new("a.json"), &overrides), None);
+    assert!(!stamp_file(Path::new("test-examples/valid-config.json"), &stamp("2024-05-01", true), &overrides).unwrap());
+}
+
+#[test]
+fn test_stamp_file_writes_comment() {
+    let path = std::env::temp_dir().join(format!("toml-and-jerry-stamp-{}.toml", std::process::id()));
+    fs::write(&path, "port = 1\n").unwrap();
+    assert!(stamp_file(&path, &stamp("2024-05-01", true), &BTreeMap::new()).unwrap());
+    let content = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).ok();
+    assert_eq!(content, "# tj: validated against schema.json@abc123def456 on 2024-05-01 — PASS\nport = 1\n");
+}
+
+#[test]
+fn test_stale_when_modified_later_or_schema_changed() {
+    let current = stamp("2024-05-01", true);
+    assert_eq!(stale_reason(&current, "2024-05-01", Some("abc123def4567890")), None);
+    let modified = stale_reason(&current, "2024-05-02", None).expect("Later change is stale");
+    assert!(modified.contains("modified on 2024-05-02"), "{}", modified);
+    let schema = stale_reason(&current, "2024-05-01", Some("ffff00001111")).expect("Other schema is stale");
+    assert!(schema.contains("abc123def456") && schema.contains("ffff00001111"), "{}", schema);
+}
+
+#[test]
+fn test_check_stamp_warns_on_old_stamp() {
+    let path = std::env::temp_dir().join(format!("toml-and-jerry-stale-{}.yaml", std::process::id()));
+    fs::write(&path, format!("{}\nport: 1\n", stamp("2000-01-01", true).render("#"))).unwrap();
+    let warning = check_stamp(&path, None);
+    let unstamped = {
+        fs::write(&path, "port: 1\n").unwrap();
+        check_stamp(&path, None)
+    };
+    fs::remove_file(&path).ok();
+    let warning = warning.expect("A stamp from 2000 is older than the file");
+    assert!(matches!(warning, AppError::StaleStamp { .. }) && warning.is_warning());
+    assert!(unstamped.is_none());
+}
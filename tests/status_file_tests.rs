@@ -0,0 +1,45 @@
+use std::process::Command;
+
+fn run_with_status(name: &str, args: &[&str]) -> (bool, serde_json::Value) {
+    let dir = std::env::temp_dir().join(format!("toml-and-jerry-status-{}-{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let status_path = dir.join("status.json");
+    let output = Command::new("cargo")
+        .args(["run", "--", "check"])
+        .args(args)
+        .arg("--status-file")
+        .arg(&status_path)
+        .output()
+        .expect("Failed to execute command");
+    let status = serde_json::from_str(&std::fs::read_to_string(&status_path).expect("Status file should exist")).unwrap();
+    std::fs::remove_dir_all(&dir).ok();
+    (output.status.success(), status)
+}
+
+#[test]
+fn test_status_file_written_on_success() {
+    let (success, status) = run_with_status("ok", &["test-examples/valid-config.json", "--schema", "test-examples/schema.json"]);
+    assert!(success);
+    assert_eq!(status["outcome"], "passed");
+    assert_eq!(status["exitCode"], 0);
+    assert_eq!(status["errors"], 0);
+}
+
+#[test]
+fn test_status_file_counts_failures() {
+    let (success, status) = run_with_status("fail", &["test-examples/invalid-config.json", "--schema", "test-examples/schema.json"]);
+    assert!(!success);
+    assert_eq!(status["outcome"], "failed");
+    assert_eq!(status["exitCode"], 1);
+    assert_eq!(status["byCode"]["app::schema::validation_error"], 1);
+    assert_eq!(status["byCategory"]["schema"], 1);
+    assert_eq!(status["filesWithDiagnostics"], 1);
+}
+
+#[test]
+fn test_status_file_written_when_schema_is_unusable() {
+    let (success, status) = run_with_status("error", &["test-examples/valid-config.json", "--schema", "test-examples/missing.schema.json"]);
+    assert!(!success);
+    assert_eq!(status["outcome"], "error");
+    assert!(status["message"].as_str().is_some());
+}
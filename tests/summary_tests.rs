@@ -0,0 +1,101 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::Duration;
+use toml_and_jerry::schema::load_and_compile_schema;
+use toml_and_jerry::summary::{gate_breakdown, render_gates, RunSummary};
+use toml_and_jerry::validation::{skip_reason, validate_inputs, ValidationOptions};
+
+fn inputs() -> Vec<PathBuf> {
+    ["test-examples/valid-config.json", "test-examples/invalid-config.json", "test-examples/invalid-types.toml", "test-examples/summary/notes.txt"]
+        .iter()
+        .map(PathBuf::from)
+        .collect()
+}
+
+#[test]
+fn test_summary_counts_files_and_errors_per_rule() {
+    let validator = load_and_compile_schema(&PathBuf::from("test-examples/schema.json")).unwrap();
+    let errors = validate_inputs(inputs(), &validator).unwrap();
+    let summary = RunSummary::new(&inputs(), &ValidationOptions::default(), &errors, Duration::from_millis(1250));
+
+    assert_eq!((summary.files_scanned, summary.files_skipped), (3, 1));
+    assert_eq!((summary.files_passed, summary.files_failed), (1, 2));
+    assert_eq!(summary.errors, errors.len());
+    assert_eq!(summary.errors_by_rule.get("app::schema::validation_error"), Some(&2));
+    assert_eq!(summary.elapsed_ms, 1250);
+    let human = summary.render_human();
+    assert!(human.contains("Files: 3 scanned, 1 skipped, 1 passed, 2 failed"), "{}", human);
+    assert!(human.contains("Elapsed: 1.25s"), "{}", human);
+}
+
+#[test]
+fn test_skip_reason_matches_validated_types() {
+    let options = ValidationOptions::default();
+    assert_eq!(skip_reason(&PathBuf::from("notes.txt"), &options).as_deref(), Some("unsupported file type (txt)"));
+    assert_eq!(skip_reason(&PathBuf::from("Makefile"), &options).as_deref(), Some("file without extension"));
+    assert_eq!(skip_reason(&PathBuf::from("app.yaml"), &options), None);
+}
+
+#[test]
+fn test_json_report_carries_summary_object() {
+    let output = Command::new("cargo")
+        .args(["run", "--", "check", "--schema", "test-examples/schema.json", "--format", "json", "--summary"])
+        .args(inputs())
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).expect("stdout should be the JSON report");
+    assert!(report["errors"].is_array());
+    assert_eq!(report["summary"]["filesScanned"], 3);
+    assert_eq!(report["summary"]["filesSkipped"], 1);
+    assert_eq!(report["summary"]["filesFailed"], 2);
+    assert!(report["summary"]["elapsedMs"].is_u64());
+}
+
+#[test]
+fn test_gate_breakdown_counts_per_schema() {
+    let validator = load_and_compile_schema(&PathBuf::from("test-examples/schema.json")).unwrap();
+    let errors = validate_inputs(inputs(), &validator).unwrap();
+    let gates = BTreeMap::from([
+        (PathBuf::from("test-examples/valid-config.json"), None),
+        (PathBuf::from("test-examples/invalid-config.json"), Some("preset k8s: deployment".to_string())),
+        (PathBuf::from("test-examples/invalid-types.toml"), None),
+    ]);
+    let breakdown = gate_breakdown(&gates, "schema.json", &errors);
+
+    assert_eq!(breakdown.len(), 2);
+    assert_eq!((breakdown[0].gate.as_str(), breakdown[0].files_checked, breakdown[0].files_failed), ("preset k8s: deployment", 1, 1));
+    assert_eq!((breakdown[1].gate.as_str(), breakdown[1].files_checked, breakdown[1].files_failed), ("schema.json", 2, 1));
+    assert_eq!(breakdown.iter().map(|g| g.errors).sum::<usize>(), errors.len());
+    assert!(render_gates(&breakdown).contains("--- Results by Schema ---"));
+
+    let one_gate: BTreeMap<PathBuf, Option<String>> = gates.into_keys().map(|file| (file, None)).collect();
+    assert!(gate_breakdown(&one_gate, "schema.json", &errors).is_empty(), "a single schema needs no table");
+}
+
+#[test]
+fn test_bound_schemas_get_their_own_rows() {
+    let services = ["api.yaml", "worker.json", "unbound.toml"].map(|f| format!("test-examples/schema-comment/services/{}", f));
+    let output = Command::new("cargo")
+        .args(["run", "--", "check", "--schema", "test-examples/schema.json", "--format", "json", "--summary"])
+        .args(&services)
+        .output()
+        .expect("Failed to execute command");
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).expect("stdout should be the JSON report");
+    let gates = report["summary"]["gates"].as_array().expect("two schemas ran");
+    assert_eq!(gates.len(), 2, "{:?}", gates);
+    let bound = gates.iter().find(|g| g["gate"].as_str().unwrap().ends_with("service.schema.json")).unwrap();
+    assert_eq!((bound["filesChecked"].as_u64(), bound["filesFailed"].as_u64()), (Some(2), Some(1)));
+    let default = gates.iter().find(|g| g["gate"] == "test-examples/schema.json").unwrap();
+    assert_eq!((default["filesChecked"].as_u64(), default["filesFailed"].as_u64()), (Some(1), Some(1)));
+
+    let human = Command::new("cargo")
+        .args(["run", "--", "check", "--schema", "test-examples/schema.json"])
+        .args(&services)
+        .output()
+        .expect("Failed to execute command");
+    assert!(String::from_utf8_lossy(&human.stdout).contains("--- Results by Schema ---"), "the table is printed without --summary");
+}
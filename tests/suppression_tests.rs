@@ -0,0 +1,90 @@
+use std::fs;
+use std::path::PathBuf;
+use toml_and_jerry::error::AppError;
+use toml_and_jerry::schema::{compile_schema, load_schema_json};
+use toml_and_jerry::suppressions::{apply_suppressions, load_suppressions, parse_inline, Suppression};
+use toml_and_jerry::validation::{validate_inputs_with_options, ValidationOptions};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("toml-and-jerry-suppress-{}-{}", name, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+// Validates a TOML config whose `port` has the wrong type, with `comment` on the line above it
+fn diagnostics_with_comment(name: &str, comment: &str) -> (PathBuf, Vec<AppError>) {
+    let dir = temp_dir(name);
+    let config = dir.join("config.toml");
+    fs::write(&config, format!("name = \"app\"\nversion = \"1.0.0\"\n{}\nport = \"eighty\"\n", comment)).unwrap();
+    let schema = load_schema_json(&PathBuf::from("test-examples/schema.json")).unwrap();
+    let validator = compile_schema(&schema, "test-examples/schema.json").unwrap();
+    let errors = validate_inputs_with_options(vec![config], &validator, &ValidationOptions::default()).unwrap();
+    assert_eq!(errors.len(), 1, "Fixture should have one violation: {:?}", errors);
+    (dir, errors)
+}
+
+#[test]
+fn test_parses_codes_reason_and_expiry() {
+    let suppression = parse_inline("# toml-and-jerry: ignore[app::schema::validation_error, github-needs] reason=\"legacy port\" expires=2025-06-01")
+        .expect("Marker should parse");
+    assert_eq!(suppression.codes, vec!["app::schema::validation_error", "github-needs"]);
+    assert_eq!(suppression.reason.as_deref(), Some("legacy port"));
+    assert_eq!(suppression.expires.as_deref(), Some("2025-06-01"));
+    assert!(parse_inline("# toml-and-jerry: ignore-file").is_none());
+    assert!(parse_inline("port = 80").is_none());
+}
+
+#[test]
+fn test_inline_suppression_applies_until_it_expires() {
+    let comment = "# toml-and-jerry: ignore reason=\"migrating\" expires=2025-06-01";
+    let (_, errors) = diagnostics_with_comment("inline", comment);
+    let before = apply_suppressions(errors, &[], None, "2025-05-31");
+    assert!(before.is_empty(), "Unexpired suppression should hide the diagnostic: {:?}", before);
+
+    let (dir, errors) = diagnostics_with_comment("inline", comment);
+    let after = apply_suppressions(errors, &[], None, "2025-06-01");
+    assert_eq!(after.len(), 2);
+    assert!(matches!(after[0], AppError::SchemaValidationError { .. }));
+    assert!(matches!(&after[1], AppError::ExpiredSuppression { location, .. } if location.starts_with("line 3")));
+    assert!(after[1].is_warning());
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn test_inline_suppression_only_covers_listed_codes() {
+    let (dir, errors) = diagnostics_with_comment("codes", "# toml-and-jerry: ignore[app::toml::parse_error]");
+    assert_eq!(apply_suppressions(errors, &[], None, "2025-01-01").len(), 1);
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn test_suppressions_file_entries_match_path_and_instance_path() {
+    let (dir, errors) = diagnostics_with_comment("file", "");
+    let file = dir.join("suppressions.toml");
+    fs::write(
+        &file,
+        "[[suppress]]\npath = \"**/config.toml\"\ncode = [\"app::schema::validation_error\"]\ninstance_path = \"/port\"\nreason = \"ticket 42\"\n\n\
+         [[suppress]]\npath = \"**/other.toml\"\nreason = \"gone\"\nexpires = \"2024-01-01\"\n",
+    )
+    .unwrap();
+    let configured = load_suppressions(&file).expect("Suppressions file should load");
+    assert_eq!(configured[0].reason.as_deref(), Some("ticket 42"));
+
+    let kept = apply_suppressions(errors, &configured, Some(&file), "2025-01-01");
+    assert_eq!(kept.len(), 1, "Only the expired entry should nag: {:?}", kept);
+    assert!(matches!(&kept[0], AppError::ExpiredSuppression { location, .. } if location.starts_with("entry 2")));
+
+    let wrong_pointer = Suppression { instance_path: Some("/name".to_string()), ..configured[0].clone() };
+    let (_, errors) = diagnostics_with_comment("file", "");
+    assert_eq!(apply_suppressions(errors, &[wrong_pointer], Some(&file), "2025-01-01").len(), 1);
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn test_rejects_malformed_expiry_dates() {
+    let dir = temp_dir("dates");
+    let file = dir.join("suppressions.toml");
+    fs::write(&file, "[[suppress]]\nexpires = \"June 2025\"\n").unwrap();
+    assert!(load_suppressions(&file).unwrap_err().contains("YYYY-MM-DD"));
+    fs::remove_dir_all(&dir).ok();
+}
@@ -0,0 +1,71 @@
+use std::path::PathBuf;
+use std::process::Command;
+use toml_and_jerry::error::{AppError, LimitExceeded};
+use toml_and_jerry::template::{snippet, Template};
+use toml_and_jerry::validation::Snippet;
+
+fn limit_warning() -> AppError {
+    let source_code = "name: app\nservers:\n  - a\n".to_string();
+    AppError::LimitExceeded(Box::new(LimitExceeded {
+        path: PathBuf::from("configs/app.yaml"),
+        rule: "max-array-len".to_string(),
+        message: "array has 1 items (limit 0)".to_string(),
+        instance_path: "/servers".to_string(),
+        span: (21, 3).into(),
+        source_code,
+    }))
+}
+
+#[test]
+fn test_template_renders_fields() {
+    let template = Template::parse("{{file}}:{{line}}:{{column}} {{severity}} {{code}} {{path}}").unwrap();
+    assert_eq!(
+        template.render(&limit_warning()),
+        "configs/app.yaml:3:3 warning app::lint::limit_exceeded /servers"
+    );
+}
+
+#[test]
+fn test_template_filters_quote_values() {
+    let csv = Template::parse("{{file|csv}},{{message|csv}}").unwrap();
+    assert_eq!(csv.render(&limit_warning()), "configs/app.yaml,\"max-array-len exceeded in file \"\"configs/app.yaml\"\": array has 1 items (limit 0)\"");
+
+    let json = Template::parse("{\"text\": {{path|json}}}\\n").unwrap();
+    assert_eq!(json.render(&limit_warning()), "{\"text\": \"/servers\"}\n");
+}
+
+#[test]
+fn test_template_rejects_unknown_fields_and_filters() {
+    assert!(Template::parse("{{file}} {{lineno}}").unwrap_err().contains("lineno"));
+    assert!(Template::parse("{{file|upper}}").unwrap_err().contains("upper"));
+    assert!(Template::parse("{{file").is_err());
+}
+
+#[test]
+fn test_cli_template_format() {
+    let output = Command::new("cargo")
+        .args(["run", "--", "check", "test-examples/missing-required-fields.json", "--schema", "test-examples/schema.json"])
+        .args(["--format", "template", "--template", "{{file}} {{code}}"])
+        .output()
+        .expect("Failed to execute command");
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("test-examples/missing-required-fields.json app::schema::validation_error"), "{}", stdout);
+}
+
+#[test]
+fn test_snippet_holds_the_labelled_lines_and_columns() {
+    assert_eq!(
+        snippet(&limit_warning()),
+        Some(Snippet { start_line: 3, text: "  - a".to_string(), caret_start: 3, caret_end: 6 })
+    );
+
+    let whole_file = match limit_warning() {
+        AppError::LimitExceeded(mut limit) => {
+            limit.span = (0, limit.source_code.len()).into();
+            AppError::LimitExceeded(limit)
+        }
+        _ => unreachable!(),
+    };
+    assert_eq!(snippet(&whole_file), None, "A label over the whole file marks nothing in particular");
+}
@@ -0,0 +1,47 @@
+use std::path::Path;
+use std::process::Command;
+use toml_and_jerry::triage::{triage, write_suggestions, BASELINE_FILE};
+
+#[test]
+fn test_triage_counts_file_types_and_presets() {
+    let report = triage(Path::new("test-examples/triage"));
+    let types: Vec<(&str, usize)> = report.file_types.iter().map(|t| (t.format, t.files)).collect();
+    assert_eq!(types, [("json", 2), ("toml", 1), ("yaml", 1)]);
+
+    assert_eq!(report.schemas.len(), 1);
+    let azure = &report.schemas[0];
+    assert_eq!((azure.preset, azure.files), ("ci", 1));
+    // The catalog schema may not be reachable from the test machine
+    assert!(azure.error.is_some() || azure.passed + azure.failed == 1);
+    assert!(report.suggested_config.contains("toml-and-jerry check --preset ci test-examples/triage"));
+}
+
+#[test]
+fn test_triage_baseline_covers_syntax_failures() {
+    let report = triage(Path::new("test-examples/triage"));
+    assert_eq!((report.unmatched_files, report.syntax_failures), (3, 1));
+    assert_eq!(report.baseline.counts["test-examples/triage/configs"]["app::json::parse_error"], 1);
+    assert!(report.suggested_config.contains("[budget.\"test-examples/triage/configs\"]\nmax_errors = 1\n"), "{}", report.suggested_config);
+}
+
+#[test]
+fn test_write_suggestions_does_not_overwrite() {
+    let dir = std::env::temp_dir().join(format!("toml-and-jerry-triage-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let report = triage(Path::new("test-examples/triage"));
+    assert_eq!(write_suggestions(&report, &dir).unwrap().len(), 2);
+    assert!(dir.join(BASELINE_FILE).exists());
+    assert!(write_suggestions(&report, &dir).unwrap_err().contains("already exists"));
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn test_cli_triage_json() {
+    let output = Command::new("cargo")
+        .args(["run", "--", "triage", "test-examples/triage", "--format", "json"])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("\"suggestedConfig\""), "{}", stdout);
+}
@@ -1,6 +1,7 @@
 use std::path::PathBuf;
 use toml_and_jerry::validation::{validate_inputs, PrintableError};
-use toml_and_jerry::error::AppError;
+use toml_and_jerry::error::{AppError, SchemaValidationError};
+use toml_and_jerry::violation::Violation;
 use jsonschema::Validator;
 use serde_json::Value as JsonValue;
 
@@ -104,15 +105,17 @@ mod validation_unit_tests {
     #[test] 
     fn test_printable_error_conversion() {
         // Test conversion from AppError to PrintableError
-        let app_error = AppError::SchemaValidationError {
+        let app_error = AppError::SchemaValidationError(Box::new(SchemaValidationError {
             path: PathBuf::from("test.json"),
             message: "Schema validation failed".to_string(),
             source_code: "{}".to_string(),
             error_span: miette::SourceSpan::new(0.into(), 1usize.into()),
             label_message: "Invalid field".to_string(),
             instance_path: "/name".to_string(),
+            schema_path: "/required".to_string(),
             kind: "Required".to_string(),
-        };
+            violation: Violation { keyword: "required".to_string(), ..Violation::default() },
+        }));
 
         let printable_error = PrintableError::from(&app_error);
         
@@ -0,0 +1,48 @@
+use std::path::{Path, PathBuf};
+use toml_and_jerry::error::AppError;
+use toml_and_jerry::project_config::load_project_config;
+use toml_and_jerry::schema::compile_schema;
+use toml_and_jerry::validation::{validate_inputs_with_options, ValidationOptions};
+use toml_and_jerry::versions::declared_version;
+
+fn options() -> ValidationOptions {
+    let project = load_project_config(Some(Path::new("test-examples/versions/project.toml"))).unwrap();
+    ValidationOptions { schema_versions: project.schema_versions, ..Default::default() }
+}
+
+// Every document must be bound by its version: the fallback schema rejects everything
+fn check(input: &str) -> Vec<AppError> {
+    let reject_all = compile_schema(&serde_json::Value::Bool(false), "reject-all").unwrap();
+    validate_inputs_with_options(vec![PathBuf::from(input)], &reject_all, &options()).unwrap()
+}
+
+#[test]
+fn test_reads_declared_version() {
+    assert_eq!(declared_version(Some("yaml"), "version: 1\nport: 1\n"), Some(("version", "1".to_string())));
+    assert_eq!(
+        declared_version(Some("json"), "{\"version\": 9, \"x-config-version\": \"2\"}"),
+        Some(("x-config-version", "2".to_string())),
+        "x-config-version takes precedence"
+    );
+    assert_eq!(declared_version(Some("toml"), "port = 1\n"), None);
+}
+
+#[test]
+fn test_each_generation_validates_against_its_schema() {
+    assert!(check("test-examples/versions/legacy.yaml").is_empty());
+    assert!(check("test-examples/versions/current.json").is_empty());
+}
+
+#[test]
+fn test_unknown_version_is_an_error() {
+    let errors = check("test-examples/versions/future.toml");
+    assert_eq!(errors.len(), 1, "{:?}", errors);
+    let AppError::UnknownConfigVersion { version, known, .. } = &errors[0] else { panic!("{:?}", errors) };
+    assert_eq!((version.as_str(), known.as_str()), ("3", "`1`, `2`"));
+}
+
+#[test]
+fn test_documents_without_version_use_the_given_schema() {
+    let errors = check("test-examples/versions/unversioned.json");
+    assert!(matches!(errors[..], [AppError::SchemaValidationError { .. }]), "{:?}", errors);
+}
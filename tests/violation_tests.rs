@@ -0,0 +1,64 @@
+use std::path::PathBuf;
+use std::process::Command;
+use serde_json::json;
+use toml_and_jerry::error::AppError;
+use toml_and_jerry::schema::{compile_schema, load_and_compile_schema};
+use toml_and_jerry::validation::{validate_inputs, PrintableError};
+use toml_and_jerry::violation::Violation;
+
+fn violation(schema: serde_json::Value, instance: serde_json::Value) -> Violation {
+    let validator = compile_schema(&schema, "inline").unwrap();
+    let error = validator.validate(&instance).expect_err("instance should be invalid");
+    Violation::from_error(&error)
+}
+
+#[test]
+fn test_keyword_expected_and_actual_per_keyword() {
+    assert_eq!(
+        violation(json!({ "minimum": 1024 }), json!(80)),
+        Violation { keyword: "minimum".to_string(), expected: Some(json!(1024)), actual: Some(json!(80)) }
+    );
+    assert_eq!(
+        violation(json!({ "type": ["string", "null"] }), json!(3)),
+        Violation { keyword: "type".to_string(), expected: Some(json!(["null", "string"])), actual: Some(json!(3)) }
+    );
+    assert_eq!(
+        violation(json!({ "enum": ["debug", "info"] }), json!("trace")),
+        Violation { keyword: "enum".to_string(), expected: Some(json!(["debug", "info"])), actual: Some(json!("trace")) }
+    );
+    assert_eq!(violation(json!({ "const": 1 }), json!(2)).keyword, "const", "Keywords are spelled as in the schema");
+    assert_eq!(violation(json!({ "required": ["name"] }), json!({})).expected, Some(json!("name")));
+    assert_eq!(violation(json!({ "not": {} }), json!(1)).expected, None);
+}
+
+#[test]
+fn test_long_values_are_left_out() {
+    let long = "x".repeat(300);
+    let found = violation(json!({ "properties": { "tag": { "maxLength": 8 } } }), json!({ "tag": long }));
+    assert_eq!((found.keyword.as_str(), found.expected), ("maxLength", Some(json!(8))));
+    assert_eq!(found.actual, None);
+}
+
+#[test]
+fn test_printable_errors_carry_the_violation() {
+    let validator = load_and_compile_schema(&PathBuf::from("test-examples/schema.json")).unwrap();
+    let errors = validate_inputs(vec![PathBuf::from("test-examples/severity/config.json")], &validator).unwrap();
+    assert!(matches!(errors[0], AppError::SchemaValidationError { .. }));
+    let printable = PrintableError::from(&errors[0]);
+    let found = printable.violation.expect("schema errors carry a violation");
+    assert_eq!((found.keyword.as_str(), found.expected, found.actual), ("minimum", Some(json!(1024)), Some(json!(80))));
+}
+
+#[test]
+fn test_sarif_properties_carry_the_violation() {
+    let output = Command::new("cargo")
+        .args(["run", "--", "check", "--schema", "test-examples/schema.json", "--format", "sarif"])
+        .arg("test-examples/severity/config.json")
+        .output()
+        .expect("Failed to execute command");
+    let sarif: serde_json::Value = serde_json::from_slice(&output.stdout).expect("stdout should be the SARIF log");
+    let properties = &sarif["runs"][0]["results"][0]["properties"];
+    assert_eq!(properties["keyword"], "minimum", "{}", properties);
+    assert_eq!(properties["expected"], 1024);
+    assert_eq!(properties["actual"], 80);
+}
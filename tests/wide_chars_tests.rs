@@ -0,0 +1,43 @@
+use std::process::Command;
+use toml_and_jerry::template::display_width;
+
+fn check(args: &[&str]) -> std::process::Output {
+    Command::new("cargo")
+        .args(["run", "--", "check", "--schema", "test-examples/schema.json"])
+        .args(args)
+        .output()
+        .expect("Failed to execute command")
+}
+
+#[test]
+fn test_display_width_counts_cells() {
+    assert_eq!(display_width("port"), 4);
+    assert_eq!(display_width("日本語"), 6);
+    assert_eq!(display_width("🎉"), 2);
+    assert_eq!(display_width("\tx"), 9);
+}
+
+#[test]
+fn test_compact_columns_count_display_cells() {
+    let output = check(&["test-examples/wide-chars/wide.json", "--format", "compact"]);
+
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let column: usize = stdout.split(':').nth(2).and_then(|c| c.parse().ok()).expect("compact line with a column");
+    // The label is on the value, after the wide name
+    let line = std::fs::read_to_string("test-examples/wide-chars/wide.json").unwrap();
+    let byte_column = line.find("22").unwrap() + 1;
+    let cell_column = display_width(&line[..line.find("22").unwrap()]) + 1;
+    assert_ne!(byte_column, cell_column, "The fixture should have wide characters before the value");
+    assert_eq!(column, cell_column, "{}", stdout);
+}
+
+#[test]
+fn test_parse_errors_at_multibyte_characters_render() {
+    for file in ["test-examples/wide-chars/broken.json", "test-examples/wide-chars/broken.yaml"] {
+        let output = check(&[file]);
+
+        // A failing run, not a panic on a label inside a character
+        assert_eq!(output.status.code(), Some(1), "{}: {}", file, String::from_utf8_lossy(&output.stderr));
+    }
+}
@@ -0,0 +1,100 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+use prost::Message;
+use toml_and_jerry::worker::{serve, WorkRequest, WorkResponse};
+
+fn request(arguments: &[&str], request_id: i32) -> WorkRequest {
+    WorkRequest {
+        arguments: arguments.iter().map(|a| a.to_string()).collect(),
+        request_id,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_proto_requests_are_answered_in_order() {
+    let mut input = Vec::new();
+    request(&["first"], 0).encode_length_delimited(&mut input).unwrap();
+    request(&["second"], 7).encode_length_delimited(&mut input).unwrap();
+
+    let mut output = Vec::new();
+    serve(input.as_slice(), &mut output, |r| (r.arguments.len() as i32, r.arguments.join(" "))).unwrap();
+
+    let mut buffer = output.as_slice();
+    let first = WorkResponse::decode_length_delimited(&mut buffer).unwrap();
+    let second = WorkResponse::decode_length_delimited(&mut buffer).unwrap();
+    assert_eq!((first.exit_code, first.output.as_str(), first.request_id), (1, "first", 0));
+    assert_eq!((second.output.as_str(), second.request_id), ("second", 7));
+    assert!(buffer.is_empty());
+}
+
+#[test]
+fn test_json_requests_are_answered_as_json_lines() {
+    let input = b"{\"arguments\": [\"a\", \"b\"], \"requestId\": 3}\n{\"arguments\": []}\n";
+    let mut output = Vec::new();
+    serve(&input[..], &mut output, |r| (0, r.arguments.join(","))).unwrap();
+
+    let lines: Vec<serde_json::Value> = String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    assert_eq!(lines[0]["output"], "a,b");
+    assert_eq!(lines[0]["requestId"], 3);
+    assert_eq!(lines[1]["exitCode"], 0);
+}
+
+#[test]
+fn test_worker_runs_check_requests() {
+    let mut child = Command::new("cargo")
+        .args(["run", "--", "--persistent_worker"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to start worker");
+    {
+        let stdin = child.stdin.as_mut().unwrap();
+        for file in ["test-examples/valid-config.json", "test-examples/invalid-config.json"] {
+            let request = serde_json::json!({ "arguments": ["check", file, "--schema", "test-examples/schema.json"] });
+            writeln!(stdin, "{}", request).unwrap();
+        }
+    }
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+
+    let responses: Vec<serde_json::Value> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|l| serde_json::from_str(l).expect("Worker stdout should only carry responses"))
+        .collect();
+    assert_eq!(responses.len(), 2);
+    assert_eq!(responses[0]["exitCode"], 0);
+    assert_eq!(responses[1]["exitCode"], 1);
+}
+
+#[test]
+fn test_worker_applies_the_project_config() {
+    let mut child = Command::new("cargo")
+        .args(["run", "--", "--persistent_worker"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to start worker");
+    {
+        let stdin = child.stdin.as_mut().unwrap();
+        let inputs = ["test-examples/budgets/team-a/api.json", "test-examples/budgets/team-a/worker.json", "test-examples/budgets/team-b/api.json"];
+        for config in ["test-examples/budgets/within.toml", "test-examples/budgets/over.toml", "test-examples/budgets/within.toml"] {
+            let arguments = [&["check", "--schema", "test-examples/schema.json", "--config", config][..], &inputs[..]].concat();
+            writeln!(stdin, "{}", serde_json::json!({ "arguments": arguments })).unwrap();
+        }
+    }
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+
+    let exit_codes: Vec<serde_json::Value> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|l| serde_json::from_str::<serde_json::Value>(l).unwrap()["exitCode"].clone())
+        .collect();
+    assert_eq!(exit_codes, [0, 1, 0], "Errors within the config's budgets pass, as they do for `check`");
+}
@@ -0,0 +1,112 @@
+use std::path::PathBuf;
+use serde_json::json;
+use toml_and_jerry::error::{AppError, UnrepresentableValue};
+use toml_and_jerry::validation::{validate_inputs_with_options, ValidationOptions};
+use toml_and_jerry::yaml::{document_ranges, parse_documents, to_json_with_policy, TagPolicy, UnknownTagMode};
+use jsonschema::Validator;
+
+fn tagged_document() -> serde_yaml::Value {
+    let content = std::fs::read_to_string("test-examples/tagged.yaml").unwrap();
+    parse_documents(&content).unwrap().remove(0)
+}
+
+#[test]
+fn test_document_ranges_follow_parsed_documents() {
+    for content in ["# header\n---\na: 1\n---\nb: 2\n", "a: 1\n--- \nb: 2\n"] {
+        let ranges = document_ranges(content);
+        assert_eq!(ranges.len(), parse_documents(content).unwrap().len(), "{:?}", content);
+        assert!(content[ranges[0].clone()].contains("a: 1") && content[ranges[1].clone()].contains("b: 2"), "{:?}", ranges);
+    }
+    assert_eq!(document_ranges("a: 1\n"), vec![0..5]);
+}
+
+#[test]
+fn test_unknown_tags_as_strings() {
+    let policy = TagPolicy { unknown: UnknownTagMode::String, ..Default::default() };
+    let json = to_json_with_policy(tagged_document(), &policy).unwrap();
+    assert_eq!(json["database"]["port"], json!("DbPort"));
+}
+
+#[test]
+fn test_unknown_tags_can_be_rejected() {
+    let policy = TagPolicy { unknown: UnknownTagMode::Error, ..Default::default() };
+    assert_eq!(to_json_with_policy(tagged_document(), &policy), Err("!Sub".to_string()));
+}
+
+#[test]
+fn test_user_mappings_expand_tags() {
+    let policy = TagPolicy::default().with_mapping("!Ref=Ref").unwrap();
+    let json = to_json_with_policy(tagged_document(), &policy).unwrap();
+    assert_eq!(json["database"]["port"], json!({ "Ref": "DbPort" }));
+    assert!(TagPolicy::default().with_mapping("missing-key").is_none());
+}
+
+#[test]
+fn test_cloudformation_profile_expands_intrinsics() {
+    let policy = TagPolicy::default().with_profile("cloudformation").unwrap();
+    let json = to_json_with_policy(tagged_document(), &policy).unwrap();
+    assert_eq!(json["name"], json!({ "Fn::Sub": "${AWS::StackName}-app" }));
+    assert_eq!(json["database"]["host"], json!({ "Fn::GetAtt": ["Database", "Endpoint.Address"] }));
+    assert_eq!(json["database"]["port"], json!({ "Ref": "DbPort" }));
+}
+
+#[test]
+fn test_rejected_tag_is_a_parse_error() {
+    let validator = Validator::new(&json!({})).unwrap();
+    let options = ValidationOptions {
+        yaml_tags: TagPolicy { unknown: UnknownTagMode::Error, ..Default::default() },
+        ..Default::default()
+    };
+    let errors = validate_inputs_with_options(vec![PathBuf::from("test-examples/tagged.yaml")], &validator, &options).unwrap();
+    match errors.first() {
+        Some(AppError::YamlParseError { span, source_code, .. }) => {
+            assert!(source_code[span.offset()..].starts_with("!Sub"));
+        }
+        other => panic!("Expected a YAML parse error, got {:?}", other),
+    }
+}
+
+// (construct, instance path, labelled text) of the conversion diagnostics for a fixture
+fn unrepresentable(file: &str) -> Vec<(String, String, String)> {
+    let accept_all = Validator::new(&json!(true)).unwrap();
+    let path = PathBuf::from(format!("test-examples/unrepresentable/{}", file));
+    let errors = validate_inputs_with_options(vec![path], &accept_all, &ValidationOptions::default()).unwrap();
+    errors
+        .iter()
+        .map(|error| match error {
+            AppError::UnrepresentableValue(unrepresentable) => {
+                let UnrepresentableValue { construct, instance_path, span, source_code, .. } = unrepresentable.as_ref();
+                let text = source_code[span.offset()..span.offset() + span.len()].to_string();
+                (construct.clone(), instance_path.clone(), text)
+            }
+            other => panic!("Unexpected diagnostic {:?}", other),
+        })
+        .collect()
+}
+
+#[test]
+fn test_complex_keys_are_unrepresentable() {
+    assert_eq!(unrepresentable("complex-key.yaml"), vec![("complex key".to_string(), "/routes".to_string(), "eu-west".to_string())]);
+}
+
+#[test]
+fn test_sets_and_binary_are_unrepresentable() {
+    assert_eq!(unrepresentable("set.yaml"), vec![("!!set".to_string(), "/admins".to_string(), "!!set".to_string())]);
+    assert_eq!(unrepresentable("binary.yaml"), vec![("!!binary".to_string(), "/bundle".to_string(), "!!binary".to_string())]);
+    assert_eq!(
+        unrepresentable("nested-binary.yaml"),
+        vec![("!!binary".to_string(), "/services/1/certs/1".to_string(), "!!binary".to_string())]
+    );
+}
+
+#[test]
+fn test_keys_colliding_as_strings_are_unrepresentable() {
+    let found = unrepresentable("colliding.yaml");
+    assert_eq!(found.len(), 1);
+    assert_eq!((found[0].0.as_str(), found[0].1.as_str()), ("colliding keys", "/mapping"));
+}
+
+#[test]
+fn test_scalar_keys_still_convert() {
+    assert!(unrepresentable("status-codes.yaml").is_empty());
+}